@@ -0,0 +1,1081 @@
+//! Tests affected by a change, in confidence tiers, with the commands that
+//! run them.
+//!
+//! Changed symbols are the ones overlapping the diff hunks. Tests are found
+//! by the test impact layer (calls, imports, naming, directory proximity),
+//! by test files importing a changed file, and by following callers of the
+//! changed symbols through non-test code. Changed tests are selected
+//! themselves.
+//!
+//! Direct calls, imports of changed code and changed tests are high
+//! confidence; naming matches, indirect callers and imports of other symbols
+//! of a changed file are medium; directory proximity is low. A test file is
+//! selected whole when a runner cannot name the test, or when a file-level
+//! import ties it to the change; it drops a tier when individual tests in it
+//! are already selected at its tier or above, so CI can run those first.
+//!
+//! Commands are built per runner from the nearest project root: pytest node
+//! ids, `cargo test` filters (`--test <name>` for integration tests),
+//! `dotnet test --filter FullyQualifiedName~...`, `go test -run` per
+//! package, and vitest or jest (whichever `package.json` mentions) per file.
+//! `describe`/`it`/`test` blocks are not symbols: a test file importing
+//! changed code also selects its top-level blocks that mention a changed
+//! symbol, run with `-t <title pattern>` above the whole file.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::db::Db;
+use crate::impact::layers::test::TestImpactLayer;
+use crate::impact::types::ImpactSource;
+use crate::indexer::differ::ChangedFile;
+use crate::indexer::test_detection::{is_test_file, is_test_symbol};
+use crate::model::Symbol;
+use crate::util;
+
+pub struct AffectedTestsConfig {
+    pub graph_version: i64,
+    /// Working tree the commands run in; project roots are found from it.
+    pub repo_root: PathBuf,
+    /// Files with their changed line ranges; a file without ranges is
+    /// changed as a whole.
+    pub changed_files: Vec<ChangedFile>,
+    /// Call hops followed from a changed symbol to a test.
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Runner {
+    Pytest,
+    Cargo,
+    Dotnet,
+    Go,
+    Vitest,
+    Jest,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AffectedTests {
+    pub graph_version: i64,
+    pub changed_symbols: Vec<String>,
+    pub summary: TierSummary,
+    /// Non-empty tiers, highest confidence first.
+    pub tiers: Vec<TestTier>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct TierSummary {
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestTier {
+    pub confidence: Confidence,
+    pub tests: Vec<AffectedTest>,
+    pub commands: Vec<TestCommand>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AffectedTest {
+    pub path: String,
+    /// The test symbol; absent when the whole file is selected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qualname: Option<String>,
+    /// Absent for languages without a supported runner.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runner: Option<Runner>,
+    /// How the test was linked to the change.
+    pub strategies: Vec<&'static str>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TestCommand {
+    pub runner: Runner,
+    /// Directory to run in, relative to the repository root.
+    pub cwd: String,
+    pub argv: Vec<String>,
+    /// `argv` quoted for a POSIX shell.
+    pub command: String,
+}
+
+pub fn affected_tests(db: &Db, config: &AffectedTestsConfig) -> Result<AffectedTests> {
+    let graph_version = config.graph_version;
+    let mut files = FileSymbols::new(db, graph_version);
+    let mut hits = Hits::default();
+    let mut warnings = Vec::new();
+    let mut changed_qualnames = BTreeSet::new();
+    let mut changed_paths = HashSet::new();
+    let mut seeds: Vec<Symbol> = Vec::new();
+    let mut js_importers = BTreeSet::new();
+
+    for file in &config.changed_files {
+        let symbols = files.symbols(&file.path)?.to_vec();
+        if symbols.is_empty() {
+            warnings.push(format!("Path not found in index: {}", file.path));
+            continue;
+        }
+        changed_paths.insert(file.path.clone());
+        let changed = changed_symbols(file, symbols);
+        changed_qualnames.extend(changed.iter().map(|symbol| symbol.qualname.clone()));
+        let (tests, others): (Vec<Symbol>, Vec<Symbol>) =
+            changed.into_iter().partition(is_test_symbol);
+        if is_test_file(&file.path) && (file.changed_ranges.is_empty() || !others.is_empty()) {
+            // A change outside the tests themselves (imports, fixtures,
+            // helpers) can affect any test in the file.
+            hits.add_file(&file.path, Confidence::High, "changed");
+        } else {
+            for test in &tests {
+                hits.add_symbol(test, Confidence::High, "changed");
+            }
+        }
+        seeds.extend(others);
+    }
+
+    let seed_ids: Vec<i64> = seeds.iter().map(|symbol| symbol.id).collect();
+    let layer = TestImpactLayer::new(db).analyze(&seed_ids, graph_version)?;
+    let mut linked: Vec<i64> = layer.evidence.keys().copied().collect();
+    linked.sort_unstable();
+    let tests: HashMap<i64, Symbol> = db
+        .symbols_by_ids(&linked, None, graph_version)?
+        .into_iter()
+        .map(|symbol| (symbol.id, symbol))
+        .collect();
+    for id in &linked {
+        let Some(test) = tests.get(id) else {
+            continue;
+        };
+        for source in &layer.evidence[id] {
+            if let ImpactSource::TestLink { strategy, .. } = source
+                && let Some((strategy, confidence)) = layer_strategy(strategy)
+            {
+                hits.add_symbol(test, confidence, strategy);
+            }
+        }
+    }
+
+    for test in indirect_test_callers(db, &seed_ids, config.depth, graph_version)? {
+        hits.add_symbol(&test, Confidence::Medium, "indirect_call");
+    }
+
+    for edge in db.version_imports(graph_version)? {
+        let Some(target_path) = edge.target_path.as_deref() else {
+            continue;
+        };
+        if edge.file_path == target_path
+            || !changed_paths.contains(target_path)
+            || !is_test_file(&edge.file_path)
+        {
+            continue;
+        }
+        let imports_change = edge.kind == "IMPORTS_FILE"
+            || edge.target_qualname.as_deref().is_some_and(|imported| {
+                changed_qualnames
+                    .iter()
+                    .any(|changed| contains_qualname(imported, changed))
+            });
+        if imports_change {
+            hits.add_file(&edge.file_path, Confidence::High, "import");
+            if is_javascript(&edge.file_path) {
+                js_importers.insert(edge.file_path.clone());
+            }
+        } else {
+            hits.add_file(&edge.file_path, Confidence::Medium, "imports_changed_file");
+        }
+    }
+
+    let changed_names: BTreeSet<&str> = seeds.iter().map(|symbol| symbol.name.as_str()).collect();
+    for path in &js_importers {
+        let Some(module) = files.module(path)? else {
+            continue;
+        };
+        for title in test_blocks(db, path, &changed_names, &mut files)? {
+            hits.add_block(
+                path,
+                format!("{module}.{title}"),
+                &title,
+                Confidence::High,
+                "import",
+            );
+        }
+    }
+
+    let tests = hits.resolve(&config.repo_root);
+    let mut summary = TierSummary::default();
+    let mut tiers = Vec::new();
+    for confidence in [Confidence::High, Confidence::Medium, Confidence::Low] {
+        let tier_tests: Vec<AffectedTest> = tests
+            .iter()
+            .filter(|(_, tier)| *tier == confidence)
+            .map(|(test, _)| test.clone())
+            .collect();
+        match confidence {
+            Confidence::High => summary.high = tier_tests.len(),
+            Confidence::Medium => summary.medium = tier_tests.len(),
+            Confidence::Low => summary.low = tier_tests.len(),
+        }
+        if tier_tests.is_empty() {
+            continue;
+        }
+        let commands = build_commands(&config.repo_root, &tier_tests, &mut files)?;
+        tiers.push(TestTier {
+            confidence,
+            tests: tier_tests,
+            commands,
+        });
+    }
+
+    Ok(AffectedTests {
+        graph_version,
+        changed_symbols: changed_qualnames.into_iter().collect(),
+        summary,
+        tiers,
+        warnings,
+    })
+}
+
+/// Symbols overlapping the changed ranges, keeping the innermost when a hunk
+/// covers both a symbol and its container.
+fn changed_symbols(file: &ChangedFile, symbols: Vec<Symbol>) -> Vec<Symbol> {
+    let mut changed: Vec<Symbol> = symbols
+        .into_iter()
+        .filter(|symbol| {
+            file.changed_ranges.is_empty()
+                || file.changed_ranges.iter().any(|hunk| {
+                    // A pure deletion (`+N,0`) touches the line it follows.
+                    let hunk_end = hunk.start_line + hunk.line_count.max(1) - 1;
+                    symbol.start_line <= hunk_end && symbol.end_line >= hunk.start_line
+                })
+        })
+        .collect();
+    if file.changed_ranges.is_empty() {
+        return changed;
+    }
+    let ranges: Vec<(i64, i64, i64)> = changed
+        .iter()
+        .map(|symbol| (symbol.id, symbol.start_line, symbol.end_line))
+        .collect();
+    changed.retain(|symbol| {
+        !ranges.iter().any(|(id, start, end)| {
+            *id != symbol.id
+                && *start >= symbol.start_line
+                && *end <= symbol.end_line
+                && (*start > symbol.start_line || *end < symbol.end_line)
+        })
+    });
+    changed
+}
+
+fn layer_strategy(strategy: &str) -> Option<(&'static str, Confidence)> {
+    match strategy {
+        "call" => Some(("call", Confidence::High)),
+        "import" => Some(("import", Confidence::High)),
+        "naming" => Some(("naming", Confidence::Medium)),
+        "proximity" => Some(("proximity", Confidence::Low)),
+        _ => None,
+    }
+}
+
+/// Whether `qualname` is `imported` or a member of it.
+fn contains_qualname(imported: &str, qualname: &str) -> bool {
+    qualname
+        .strip_prefix(imported)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.') || rest.starts_with("::"))
+}
+
+/// Titles of the top-level `describe`/`it`/`test` blocks of a JavaScript
+/// test file whose source mentions one of `names`. Calls inside the block
+/// callbacks are not indexed, so the indexed text is searched instead; the
+/// working tree may have moved past the analyzed graph version.
+fn test_blocks(
+    db: &Db,
+    path: &str,
+    names: &BTreeSet<&str>,
+    files: &mut FileSymbols,
+) -> Result<Vec<String>> {
+    let Some(module_id) = files.module_symbol(path)?.map(|symbol| symbol.id) else {
+        return Ok(Vec::new());
+    };
+    let Some(source) = db.file_content(path, files.graph_version)? else {
+        return Ok(Vec::new());
+    };
+    let edges = db.edges_for_symbols(&[module_id], None, files.graph_version)?;
+    let mut titles = Vec::new();
+    for edge in edges.get(&module_id).into_iter().flatten() {
+        let callee = edge.detail.as_deref().or(edge.target_qualname.as_deref());
+        if edge.kind != "CALLS"
+            || edge.source_symbol_id != Some(module_id)
+            || !callee
+                .and_then(|callee| callee.rsplit('.').next())
+                .is_some_and(|name| matches!(name, "describe" | "it" | "test"))
+        {
+            continue;
+        }
+        let (Some(start), Some(end)) = (edge.evidence_start_line, edge.evidence_end_line) else {
+            continue;
+        };
+        let block = util::slice_lines(&source, start, end);
+        if let Some(title) = block_title(&block)
+            && names.iter().any(|name| mentions(&block, name))
+            && !titles.contains(&title)
+        {
+            titles.push(title);
+        }
+    }
+    Ok(titles)
+}
+
+/// The string literal opening a block call's arguments (`it('adds', ...)`);
+/// `None` for computed or interpolated titles.
+fn block_title(call: &str) -> Option<String> {
+    let args = call[call.find('(')? + 1..].trim_start();
+    let quote = args
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '\'' | '"' | '`'))?;
+    let mut chars = args[1..].chars().peekable();
+    let mut title = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => title.push(chars.next()?),
+            '$' if quote == '`' && chars.peek() == Some(&'{') => return None,
+            c if c == quote => return Some(title).filter(|title| !title.is_empty()),
+            c => title.push(c),
+        }
+    }
+    None
+}
+
+/// Whether `text` uses the identifier `name`.
+fn mentions(text: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    text.match_indices(name).any(|(at, _)| {
+        !text[..at].chars().next_back().is_some_and(is_ident)
+            && !text[at + name.len()..].chars().next().is_some_and(is_ident)
+    })
+}
+
+/// Tests calling the seeds through at least one non-test symbol, within
+/// `depth` hops. Direct callers are left to the test impact layer.
+fn indirect_test_callers(
+    db: &Db,
+    seed_ids: &[i64],
+    depth: usize,
+    graph_version: i64,
+) -> Result<Vec<Symbol>> {
+    let mut visited: HashSet<i64> = seed_ids.iter().copied().collect();
+    let mut frontier = seed_ids.to_vec();
+    let mut tests = Vec::new();
+    for distance in 1..=depth {
+        if frontier.is_empty() {
+            break;
+        }
+        let edges = db.edges_for_symbols(&frontier, None, graph_version)?;
+        let mut callers = Vec::new();
+        for id in &frontier {
+            for edge in edges.get(id).into_iter().flatten() {
+                if edge.kind == "CALLS"
+                    && edge.target_symbol_id == Some(*id)
+                    && let Some(source) = edge.source_symbol_id
+                    && visited.insert(source)
+                {
+                    callers.push(source);
+                }
+            }
+        }
+        frontier.clear();
+        for caller in db.symbols_by_ids(&callers, None, graph_version)? {
+            if !is_test_symbol(&caller) {
+                frontier.push(caller.id);
+            } else if distance > 1 {
+                tests.push(caller);
+            }
+        }
+    }
+    Ok(tests)
+}
+
+/// Symbols of each file, loaded once.
+struct FileSymbols<'a> {
+    db: &'a Db,
+    graph_version: i64,
+    symbols: HashMap<String, Vec<Symbol>>,
+}
+
+impl<'a> FileSymbols<'a> {
+    fn new(db: &'a Db, graph_version: i64) -> Self {
+        Self {
+            db,
+            graph_version,
+            symbols: HashMap::new(),
+        }
+    }
+
+    fn symbols(&mut self, path: &str) -> Result<&[Symbol]> {
+        if !self.symbols.contains_key(path) {
+            let symbols = self.db.get_symbols_for_file(path, self.graph_version)?;
+            self.symbols.insert(path.to_string(), symbols);
+        }
+        Ok(&self.symbols[path])
+    }
+
+    /// The module symbol spanning the file.
+    fn module_symbol(&mut self, path: &str) -> Result<Option<&Symbol>> {
+        Ok(self
+            .symbols(path)?
+            .iter()
+            .filter(|symbol| symbol.kind == "module")
+            .min_by_key(|symbol| (symbol.start_line, -symbol.end_line)))
+    }
+
+    /// Qualname of the module symbol spanning the file.
+    fn module(&mut self, path: &str) -> Result<Option<String>> {
+        Ok(self
+            .module_symbol(path)?
+            .map(|symbol| symbol.qualname.clone()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct TestKey {
+    path: String,
+    /// `None` selects the whole file.
+    qualname: Option<String>,
+}
+
+#[derive(Debug)]
+struct Hit {
+    confidence: Confidence,
+    name: String,
+    /// A `describe`/`it`/`test` block rather than a symbol; vitest and jest
+    /// select it by its title.
+    block: bool,
+    strategies: BTreeSet<&'static str>,
+}
+
+/// Selected tests with their best confidence and every strategy that found
+/// them.
+#[derive(Default)]
+struct Hits {
+    hits: BTreeMap<TestKey, Hit>,
+}
+
+impl Hits {
+    fn add_symbol(&mut self, test: &Symbol, confidence: Confidence, strategy: &'static str) {
+        if test.kind == "module" {
+            // A test module (`tests.test_users`) stands for its file.
+            self.add_file(&test.file_path, confidence, strategy);
+            return;
+        }
+        let key = TestKey {
+            path: test.file_path.clone(),
+            qualname: Some(test.qualname.clone()),
+        };
+        self.add(key, &test.name, confidence, [strategy]);
+    }
+
+    fn add_file(&mut self, path: &str, confidence: Confidence, strategy: &'static str) {
+        let key = TestKey {
+            path: path.to_string(),
+            qualname: None,
+        };
+        self.add(key, "", confidence, [strategy]);
+    }
+
+    fn add_block(
+        &mut self,
+        path: &str,
+        qualname: String,
+        title: &str,
+        confidence: Confidence,
+        strategy: &'static str,
+    ) {
+        let key = TestKey {
+            path: path.to_string(),
+            qualname: Some(qualname),
+        };
+        self.add(key.clone(), title, confidence, [strategy]);
+        if let Some(hit) = self.hits.get_mut(&key) {
+            hit.block = true;
+        }
+    }
+
+    fn add(
+        &mut self,
+        key: TestKey,
+        name: &str,
+        confidence: Confidence,
+        strategies: impl IntoIterator<Item = &'static str>,
+    ) {
+        let hit = self.hits.entry(key).or_insert_with(|| Hit {
+            confidence,
+            name: name.to_string(),
+            block: false,
+            strategies: BTreeSet::new(),
+        });
+        hit.confidence = hit.confidence.max(confidence);
+        hit.strategies.extend(strategies);
+    }
+
+    /// Final selection: tests a runner cannot name become their file, a
+    /// file drops a tier below individually selected tests, and tests at or
+    /// below their file's tier are folded into it.
+    fn resolve(self, repo_root: &Path) -> Vec<(AffectedTest, Confidence)> {
+        let mut runners: HashMap<String, Option<Runner>> = HashMap::new();
+        let mut runner_for = |path: &str| {
+            *runners
+                .entry(path.to_string())
+                .or_insert_with(|| detect_runner(repo_root, path))
+        };
+
+        let mut merged = Hits::default();
+        for (key, hit) in self.hits {
+            let runner = runner_for(&key.path);
+            if key.qualname.is_some()
+                && !hit.block
+                && runner.is_some_and(|runner| !names_test(runner, &hit.name))
+            {
+                merged.add(
+                    TestKey {
+                        path: key.path,
+                        qualname: None,
+                    },
+                    "",
+                    hit.confidence,
+                    hit.strategies,
+                );
+            } else {
+                merged.add(key, &hit.name, hit.confidence, hit.strategies);
+            }
+        }
+
+        let mut best_test: HashMap<String, Confidence> = HashMap::new();
+        for (key, hit) in &merged.hits {
+            if key.qualname.is_some() {
+                let best = best_test.entry(key.path.clone()).or_insert(hit.confidence);
+                *best = (*best).max(hit.confidence);
+            }
+        }
+        let mut file_tiers: HashMap<String, Confidence> = HashMap::new();
+        for (key, hit) in merged.hits.iter_mut() {
+            if key.qualname.is_none() {
+                if best_test
+                    .get(&key.path)
+                    .is_some_and(|best| *best >= hit.confidence)
+                {
+                    hit.confidence = lower(hit.confidence);
+                }
+                file_tiers.insert(key.path.clone(), hit.confidence);
+            }
+        }
+
+        let mut folded: Vec<(String, BTreeSet<&'static str>)> = Vec::new();
+        merged.hits.retain(|key, hit| {
+            let covered = key.qualname.is_some()
+                && file_tiers
+                    .get(&key.path)
+                    .is_some_and(|file| *file >= hit.confidence);
+            if covered {
+                folded.push((key.path.clone(), std::mem::take(&mut hit.strategies)));
+            }
+            !covered
+        });
+        for (path, strategies) in folded {
+            let key = TestKey {
+                path,
+                qualname: None,
+            };
+            if let Some(hit) = merged.hits.get_mut(&key) {
+                hit.strategies.extend(strategies);
+            }
+        }
+
+        merged
+            .hits
+            .into_iter()
+            .map(|(key, hit)| {
+                let runner = runner_for(&key.path);
+                let test = AffectedTest {
+                    path: key.path,
+                    qualname: key.qualname,
+                    runner,
+                    strategies: hit.strategies.into_iter().collect(),
+                };
+                (test, hit.confidence)
+            })
+            .collect()
+    }
+}
+
+fn lower(confidence: Confidence) -> Confidence {
+    match confidence {
+        Confidence::High => Confidence::Medium,
+        Confidence::Medium | Confidence::Low => Confidence::Low,
+    }
+}
+
+/// Whether the runner can select a test symbol called `name` on its own.
+/// Helpers that only look like tests, and JavaScript functions (tests are
+/// `it`/`test` callbacks), select their file instead.
+fn names_test(runner: Runner, name: &str) -> bool {
+    match runner {
+        Runner::Pytest => name.starts_with("test") || name.starts_with("Test"),
+        Runner::Go => name.starts_with("Test"),
+        Runner::Cargo | Runner::Dotnet => true,
+        Runner::Vitest | Runner::Jest => false,
+    }
+}
+
+fn is_javascript(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| JS_EXTENSIONS.contains(&extension))
+}
+
+const JS_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs", "mts", "cts"];
+
+fn detect_runner(repo_root: &Path, path: &str) -> Option<Runner> {
+    let extension = Path::new(path).extension()?.to_str()?;
+    match extension {
+        "py" => Some(Runner::Pytest),
+        "rs" => Some(Runner::Cargo),
+        "cs" => Some(Runner::Dotnet),
+        "go" => Some(Runner::Go),
+        extension if JS_EXTENSIONS.contains(&extension) => {
+            let dir = project_dir(repo_root, path, Runner::Jest);
+            let manifest = std::fs::read_to_string(repo_root.join(dir).join("package.json"))
+                .unwrap_or_default();
+            if manifest.contains("vitest") {
+                Some(Runner::Vitest)
+            } else {
+                Some(Runner::Jest)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Nearest directory above `path` holding the runner's project file,
+/// relative to the repository root (`.` when none is found).
+fn project_dir(repo_root: &Path, path: &str, runner: Runner) -> String {
+    let mut dir = Path::new(path).parent();
+    while let Some(current) = dir {
+        if is_project_dir(&repo_root.join(current), runner) {
+            let current = current.to_string_lossy();
+            return if current.is_empty() {
+                ".".to_string()
+            } else {
+                current.into_owned()
+            };
+        }
+        dir = current.parent();
+    }
+    ".".to_string()
+}
+
+fn is_project_dir(dir: &Path, runner: Runner) -> bool {
+    let markers: &[&str] = match runner {
+        Runner::Pytest => &["pyproject.toml", "pytest.ini", "setup.cfg", "tox.ini"],
+        Runner::Cargo => &["Cargo.toml"],
+        Runner::Go => &["go.mod"],
+        Runner::Vitest | Runner::Jest => &["package.json"],
+        Runner::Dotnet => {
+            return std::fs::read_dir(dir).is_ok_and(|entries| {
+                entries
+                    .flatten()
+                    .any(|entry| entry.file_name().to_string_lossy().ends_with(".csproj"))
+            });
+        }
+    };
+    markers.iter().any(|marker| dir.join(marker).is_file())
+}
+
+/// `path` relative to `dir` (a repository-relative directory or `.`).
+fn relative_to(path: &str, dir: &str) -> String {
+    if dir == "." {
+        return path.to_string();
+    }
+    path.strip_prefix(dir)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// One runner invocation being assembled: tests sharing a runner, a
+/// project directory and a scope (cargo test target, go package).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Invocation {
+    runner: Runner,
+    cwd: String,
+    scope: Option<String>,
+}
+
+#[derive(Default)]
+struct Filters {
+    filters: BTreeSet<String>,
+    /// Some selection runs everything in scope, so filters are dropped.
+    unfiltered: bool,
+}
+
+fn build_commands(
+    repo_root: &Path,
+    tests: &[AffectedTest],
+    files: &mut FileSymbols,
+) -> Result<Vec<TestCommand>> {
+    let mut invocations: BTreeMap<Invocation, Filters> = BTreeMap::new();
+    for test in tests {
+        let Some(runner) = test.runner else {
+            continue;
+        };
+        let cwd = project_dir(repo_root, &test.path, runner);
+        let rel = relative_to(&test.path, &cwd);
+        let (scope, selection) = select(runner, &rel, test, files)?;
+        let filters = invocations
+            .entry(Invocation { runner, cwd, scope })
+            .or_default();
+        match selection {
+            Some(selection) => filters.filters.extend(selection),
+            None => filters.unfiltered = true,
+        }
+    }
+
+    Ok(invocations
+        .into_iter()
+        .map(|(invocation, filters)| {
+            let filters: Vec<String> = if filters.unfiltered {
+                Vec::new()
+            } else {
+                filters.filters.into_iter().collect()
+            };
+            let argv = command_argv(invocation.runner, invocation.scope.as_deref(), &filters);
+            TestCommand {
+                runner: invocation.runner,
+                cwd: invocation.cwd,
+                command: shell_join(&argv),
+                argv,
+            }
+        })
+        .collect())
+}
+
+/// Scope and filters selecting `test`; `None` filters run the whole scope.
+fn select(
+    runner: Runner,
+    rel: &str,
+    test: &AffectedTest,
+    files: &mut FileSymbols,
+) -> Result<(Option<String>, Option<Vec<String>>)> {
+    let module = files.module(&test.path)?;
+    Ok(match runner {
+        Runner::Pytest => {
+            let suffix = test
+                .qualname
+                .as_deref()
+                .and_then(|qualname| qualname.strip_prefix(&format!("{}.", module.as_deref()?)));
+            let id = match suffix {
+                Some(suffix) => format!("{rel}::{}", suffix.replace('.', "::")),
+                None => rel.to_string(),
+            };
+            (None, Some(vec![id]))
+        }
+        Runner::Cargo => {
+            // Files under tests/ are integration test targets, whose test
+            // paths start below `crate::tests::<target>`.
+            let target = rel.strip_prefix("tests/").and_then(|rest| {
+                let stem = rest.split('/').next()?;
+                Some(stem.strip_suffix(".rs").unwrap_or(stem).to_string())
+            });
+            let prefix = match &target {
+                Some(target) => format!("crate::tests::{target}::"),
+                None => "crate::".to_string(),
+            };
+            let filter = match (&test.qualname, &module) {
+                (Some(qualname), _) => qualname.strip_prefix(&prefix).map(str::to_string),
+                (None, Some(module)) => format!("{module}::")
+                    .strip_prefix(&prefix)
+                    .filter(|rest| !rest.is_empty())
+                    .map(str::to_string),
+                (None, None) => None,
+            };
+            (target, filter.map(|filter| vec![filter]))
+        }
+        Runner::Dotnet => {
+            let names = match &test.qualname {
+                Some(qualname) => vec![qualname.clone()],
+                None => files
+                    .symbols(&test.path)?
+                    .iter()
+                    .filter(|symbol| symbol.kind == "class")
+                    .map(|symbol| symbol.qualname.clone())
+                    .collect(),
+            };
+            (None, Some(names).filter(|names| !names.is_empty()))
+        }
+        Runner::Go => {
+            let package = match Path::new(rel).parent().map(|dir| dir.to_string_lossy()) {
+                Some(dir) if !dir.is_empty() => format!("./{dir}"),
+                _ => ".".to_string(),
+            };
+            let names: Vec<String> = match &test.qualname {
+                Some(qualname) => qualname
+                    .rsplit('.')
+                    .next()
+                    .map(str::to_string)
+                    .into_iter()
+                    .collect(),
+                None => files
+                    .symbols(&test.path)?
+                    .iter()
+                    .filter(|symbol| symbol.kind == "function" && symbol.name.starts_with("Test"))
+                    .map(|symbol| symbol.name.clone())
+                    .collect(),
+            };
+            (Some(package), Some(names).filter(|names| !names.is_empty()))
+        }
+        Runner::Vitest | Runner::Jest => {
+            // Blocks are keyed `<module>.<title>`; the file is their scope.
+            let title = test
+                .qualname
+                .as_deref()
+                .and_then(|qualname| qualname.strip_prefix(&format!("{}.", module.as_deref()?)));
+            match title {
+                Some(title) => (Some(rel.to_string()), Some(vec![title.to_string()])),
+                None => (None, Some(vec![rel.to_string()])),
+            }
+        }
+    })
+}
+
+fn command_argv(runner: Runner, scope: Option<&str>, filters: &[String]) -> Vec<String> {
+    let mut argv: Vec<String> = Vec::new();
+    match runner {
+        Runner::Pytest => {
+            argv.push("pytest".into());
+            argv.extend(filters.iter().cloned());
+        }
+        Runner::Cargo => {
+            argv.extend(["cargo".into(), "test".into()]);
+            if let Some(target) = scope {
+                argv.extend(["--test".into(), target.to_string()]);
+            }
+            if !filters.is_empty() {
+                argv.push("--".into());
+                argv.extend(filters.iter().cloned());
+            }
+        }
+        Runner::Dotnet => {
+            argv.extend(["dotnet".into(), "test".into()]);
+            if !filters.is_empty() {
+                let filter: Vec<String> = filters
+                    .iter()
+                    .map(|name| format!("FullyQualifiedName~{name}"))
+                    .collect();
+                argv.extend(["--filter".into(), filter.join("|")]);
+            }
+        }
+        Runner::Go => {
+            argv.extend(["go".into(), "test".into()]);
+            match filters {
+                [] => {}
+                [name] => argv.extend(["-run".into(), format!("^{name}$")]),
+                names => argv.extend(["-run".into(), format!("^({})$", names.join("|"))]),
+            }
+            argv.push(scope.unwrap_or(".").to_string());
+        }
+        Runner::Vitest | Runner::Jest => {
+            argv.push("npx".into());
+            if runner == Runner::Vitest {
+                argv.extend(["vitest".into(), "run".into()]);
+            } else {
+                argv.push("jest".into());
+            }
+            match scope {
+                // One file, its blocks named by title.
+                Some(file) => {
+                    argv.push(file.to_string());
+                    if !filters.is_empty() {
+                        argv.extend(["-t".into(), title_pattern(filters)]);
+                    }
+                }
+                None => argv.extend(filters.iter().cloned()),
+            }
+        }
+    }
+    argv
+}
+
+/// `-t` pattern for tests under top-level blocks titled `titles`. Runners
+/// match it against the full test name, the block titles joined by spaces.
+fn title_pattern(titles: &[String]) -> String {
+    let escaped: Vec<String> = titles
+        .iter()
+        .map(|title| {
+            title.chars().fold(String::new(), |mut out, c| {
+                if "\\^$.*+?()[]{}|/".contains(c) {
+                    out.push('\\');
+                }
+                out.push(c);
+                out
+            })
+        })
+        .collect();
+    match escaped.as_slice() {
+        [title] => format!("^{title}( |$)"),
+        titles => format!("^({})( |$)", titles.join("|")),
+    }
+}
+
+fn shell_join(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `arg` as a single POSIX shell word.
+pub fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(runner: Runner, scope: Option<&str>, filters: &[&str]) -> String {
+        let filters: Vec<String> = filters.iter().map(|filter| filter.to_string()).collect();
+        shell_join(&command_argv(runner, scope, &filters))
+    }
+
+    #[test]
+    fn builds_runner_commands() {
+        assert_eq!(
+            argv(
+                Runner::Pytest,
+                None,
+                &["tests/test_users.py::TestUser::test_again"]
+            ),
+            "pytest tests/test_users.py::TestUser::test_again"
+        );
+        assert_eq!(
+            argv(Runner::Cargo, Some("it"), &["integration"]),
+            "cargo test --test it -- integration"
+        );
+        assert_eq!(argv(Runner::Cargo, None, &[]), "cargo test");
+        assert_eq!(
+            argv(Runner::Dotnet, None, &["Acme.Tests.A", "Acme.Tests.B"]),
+            "dotnet test --filter 'FullyQualifiedName~Acme.Tests.A|FullyQualifiedName~Acme.Tests.B'"
+        );
+        assert_eq!(
+            argv(Runner::Go, Some("./calc"), &["TestAdd"]),
+            "go test -run '^TestAdd$' ./calc"
+        );
+        assert_eq!(
+            argv(Runner::Go, Some("."), &["TestA", "TestB"]),
+            "go test -run '^(TestA|TestB)$' ."
+        );
+        assert_eq!(
+            argv(Runner::Vitest, None, &["web/sum.test.ts"]),
+            "npx vitest run web/sum.test.ts"
+        );
+        assert_eq!(
+            argv(
+                Runner::Jest,
+                Some("web/sum.test.ts"),
+                &["adds", "sum (a+b)"]
+            ),
+            r"npx jest web/sum.test.ts -t '^(adds|sum \(a\+b\))( |$)'"
+        );
+    }
+
+    #[test]
+    fn reads_block_titles() {
+        assert_eq!(block_title("it('adds', () => {})"), Some("adds".into()));
+        assert_eq!(
+            block_title("describe(\n  \"it's \\\"sum\\\"\", () => {})"),
+            Some("it's \"sum\"".into())
+        );
+        assert_eq!(block_title("test(`n=${n}`, () => {})"), None);
+        assert_eq!(block_title("test(name, () => {})"), None);
+        assert!(mentions("expect(sum(1, 2))", "sum"));
+        assert!(!mentions("expect(checksum(1))", "sum"));
+    }
+
+    #[test]
+    fn quotes_shell_arguments() {
+        assert_eq!(shell_quote("a/b.py::test_x"), "a/b.py::test_x");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn matches_imported_containers() {
+        assert!(contains_qualname("store.users", "store.users.load_user"));
+        assert!(contains_qualname("crate::math::add", "crate::math::add"));
+        assert!(!contains_qualname("store.user", "store.users.load_user"));
+    }
+
+    #[test]
+    fn files_drop_below_individual_tests() {
+        let mut hits = Hits::default();
+        hits.add(
+            TestKey {
+                path: "calc/Calc.cs".into(),
+                qualname: Some("Acme.CalcTests.Adds".into()),
+            },
+            "Adds",
+            Confidence::High,
+            ["call"],
+        );
+        hits.add(
+            TestKey {
+                path: "calc/Calc.cs".into(),
+                qualname: Some("Acme.CalcTests.Subtracts".into()),
+            },
+            "Subtracts",
+            Confidence::Low,
+            ["proximity"],
+        );
+        hits.add_file("calc/Calc.cs", Confidence::High, "import");
+        let resolved = hits.resolve(Path::new("/nonexistent"));
+        let summary: Vec<(Option<&str>, Confidence, &[&str])> = resolved
+            .iter()
+            .map(|(test, confidence)| {
+                (
+                    test.qualname.as_deref(),
+                    *confidence,
+                    test.strategies.as_slice(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (None, Confidence::Medium, &["import", "proximity"][..]),
+                (Some("Acme.CalcTests.Adds"), Confidence::High, &["call"][..]),
+            ]
+        );
+    }
+}
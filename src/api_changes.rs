@@ -0,0 +1,885 @@
+//! Public API changes between two graph versions, classified by the semver
+//! bump they require.
+//!
+//! Visibility is read from the indexed declaration text: Rust `pub`, C#
+//! `public`, TypeScript/JavaScript `export`, capitalized Go names, and
+//! Python `__all__` (or names without a leading underscore when a module has
+//! none). A member is public only when its container is. Symbols in other
+//! languages are not part of the API surface.
+//!
+//! Removing a public symbol, changing its signature or narrowing its
+//! visibility is major; adding one, or appending optional parameters, is
+//! minor; moves and changes to non-public symbols are patch. Each breaking
+//! change lists the callers outside its module, from either version.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use crate::compare::match_symbols;
+use crate::db::{Db, module_prefix};
+use crate::indexer::scan::language_for_path;
+use crate::indexer::type_resolve::language_family;
+use crate::model::{Symbol, VersionEdge};
+
+pub struct ApiChangesConfig {
+    pub from_version: i64,
+    pub to_version: i64,
+    /// Directory depth that groups files into modules; callers in another
+    /// module are external.
+    pub depth: usize,
+    /// Maximum changes listed; the summary counts everything.
+    pub limit: usize,
+    /// Maximum external callers listed per breaking change.
+    pub max_callers: usize,
+    pub languages: Option<Vec<String>>,
+    pub paths: Option<Vec<String>>,
+    /// Qualnames whose breaking changes are accepted; a trailing `*`
+    /// matches a prefix.
+    pub approved: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverLevel {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiChangeKind {
+    Removed,
+    SignatureChanged,
+    VisibilityReduced,
+    Added,
+    Exposed,
+    SignatureExtended,
+    Moved,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiChanges {
+    pub from_version: i64,
+    pub to_version: i64,
+    /// The largest level of any change.
+    pub bump: SemverLevel,
+    pub summary: ApiSummary,
+    /// Breaking changes first.
+    pub changes: Vec<ApiChange>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ApiSummary {
+    pub major: usize,
+    pub minor: usize,
+    pub patch: usize,
+    /// Patch-level changes to non-public symbols, counted in `patch` but
+    /// not listed.
+    pub internal: usize,
+    pub breaking_approved: usize,
+    pub breaking_unapproved: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ApiChange {
+    pub qualname: String,
+    pub kind: String,
+    pub path: String,
+    pub change: ApiChangeKind,
+    pub level: SemverLevel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_signature: Option<String>,
+    /// Set on breaking changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved: Option<bool>,
+    /// Number of distinct external callers; only counted for breaking
+    /// changes.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub external_callers: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub callers: Vec<ExternalCaller>,
+}
+
+/// The symbol (or file, for file-level edges) referring to a changed symbol
+/// from another module.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ExternalCaller {
+    pub source: String,
+    pub path: String,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+impl ApiChanges {
+    /// Breaking changes not covered by an approval.
+    pub fn has_unapproved_breaks(&self) -> bool {
+        self.summary.breaking_unapproved > 0
+    }
+}
+
+pub fn api_changes(db: &Db, config: &ApiChangesConfig) -> Result<ApiChanges> {
+    let languages = config.languages.as_deref();
+    let paths = config.paths.as_deref();
+    let old = VersionApi::load(db, config.from_version, languages, paths)?;
+    let new = VersionApi::load(db, config.to_version, languages, paths)?;
+    // Callers come from the whole version, not just the filtered paths.
+    let old_edges = db.version_edges(config.from_version, None, None)?;
+    let new_edges = db.version_edges(config.to_version, None, None)?;
+    Ok(classify(config, old, new, &old_edges, &new_edges))
+}
+
+/// One version's symbols and what decides their visibility.
+struct VersionApi {
+    symbols: Vec<Symbol>,
+    /// Declaration text by symbol id.
+    heads: HashMap<i64, String>,
+    /// Names listed in `__all__`, by Python file.
+    exports: HashMap<String, HashSet<String>>,
+}
+
+impl VersionApi {
+    fn load(
+        db: &Db,
+        graph_version: i64,
+        languages: Option<&[String]>,
+        paths: Option<&[String]>,
+    ) -> Result<Self> {
+        let exports = db
+            .python_export_lines(graph_version)?
+            .into_iter()
+            .filter_map(|(path, lines)| python_exports(&lines).map(|names| (path, names)))
+            .collect();
+        Ok(Self {
+            symbols: db.version_symbols(graph_version, languages, paths)?,
+            heads: db.declaration_heads(graph_version, languages, paths)?,
+            exports,
+        })
+    }
+
+    /// Visibility of every symbol in a supported language, by id.
+    fn visibility(&self) -> HashMap<i64, bool> {
+        let mut by_qualname: HashMap<&str, &Symbol> = HashMap::new();
+        for symbol in &self.symbols {
+            by_qualname
+                .entry(symbol.qualname.as_str())
+                .or_insert(symbol);
+        }
+        self.symbols
+            .iter()
+            .filter_map(|symbol| {
+                self.is_public(symbol, &by_qualname)
+                    .map(|public| (symbol.id, public))
+            })
+            .collect()
+    }
+
+    fn is_public(&self, symbol: &Symbol, by_qualname: &HashMap<&str, &Symbol>) -> Option<bool> {
+        if matches!(symbol.kind.as_str(), "module" | "namespace") {
+            return None;
+        }
+        let family = language_family(language_for_path(Path::new(&symbol.file_path))?);
+        let separator = if family == "rust" { "::" } else { "." };
+        let container = symbol
+            .qualname
+            .rsplit_once(separator)
+            .and_then(|(parent, _)| by_qualname.get(parent))
+            .filter(|parent| !matches!(parent.kind.as_str(), "module" | "namespace"));
+        if let Some(container) = container
+            && (matches!(container.kind.as_str(), "function" | "method")
+                || !self.is_public(container, by_qualname)?)
+        {
+            return Some(false);
+        }
+        let container_kind = container.map(|container| container.kind.as_str());
+        let head = self
+            .heads
+            .get(&symbol.id)
+            .map(|head| declaration_modifiers(head, &symbol.name))
+            .unwrap_or_default();
+        let has = |modifier: &str| head.split_whitespace().any(|token| token == modifier);
+        let public = match family {
+            "rust" => container_kind == Some("trait") || has("pub"),
+            "csharp" => has("public") || (container_kind == Some("interface") && !has("private")),
+            "js" => match container_kind {
+                Some(_) => {
+                    !has("private")
+                        && !has("protected")
+                        && !head.trim_end().ends_with('#')
+                        && !symbol.name.starts_with('#')
+                }
+                None => has("export"),
+            },
+            "go" => symbol.name.starts_with(|c: char| c.is_uppercase()),
+            "python" => match (container_kind, self.exports.get(&symbol.file_path)) {
+                (None, Some(exports)) => exports.contains(&symbol.name),
+                _ => !is_private_python_name(&symbol.name),
+            },
+            _ => return None,
+        };
+        Some(public)
+    }
+}
+
+/// Declaration text before the symbol's name, from the last `{`, `}` or `;`
+/// so that an enclosing block on the same line does not count.
+fn declaration_modifiers<'a>(head: &'a str, name: &str) -> &'a str {
+    let end = find_word(head, name).unwrap_or(head.len());
+    let prefix = &head[..end];
+    match prefix.rfind(['{', '}', ';']) {
+        Some(idx) => &prefix[idx + 1..],
+        None => prefix,
+    }
+}
+
+/// Byte offset of `word` where it is not part of a longer identifier or a
+/// string.
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    if word.is_empty() {
+        return None;
+    }
+    // Quotes exclude the name inside an attribute argument: `[Obsolete("Run")]`.
+    let is_ident = |c: char| c.is_alphanumeric() || matches!(c, '_' | '"' | '\'');
+    text.match_indices(word).map(|(idx, _)| idx).find(|&idx| {
+        let before = text[..idx].chars().next_back();
+        let after = text[idx + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+/// `_private` and `__mangled` are private; dunder methods are not.
+fn is_private_python_name(name: &str) -> bool {
+    name.starts_with('_') && !(name.starts_with("__") && name.ends_with("__"))
+}
+
+/// Names assigned or appended to `__all__` in a module's lines, or `None`
+/// when the module does not define it.
+fn python_exports(lines: &[String]) -> Option<HashSet<String>> {
+    let mut names = HashSet::new();
+    let mut found = false;
+    let mut depth = 0i32;
+    let mut collecting = false;
+    for line in lines {
+        let mut text = line.as_str();
+        if !collecting {
+            let Some(rest) = line.trim_start().strip_prefix("__all__") else {
+                continue;
+            };
+            // `__all__ = [...]`, `__all__ += [...]`, `__all__: list[str] = [...]`
+            let Some(idx) = rest.find('=') else {
+                continue;
+            };
+            found = true;
+            collecting = true;
+            text = &rest[idx + 1..];
+        }
+        let mut quote: Option<char> = None;
+        let mut current = String::new();
+        for c in text.chars() {
+            match quote {
+                Some(q) if c == q => {
+                    names.insert(std::mem::take(&mut current));
+                    quote = None;
+                }
+                Some(_) => current.push(c),
+                None => match c {
+                    '"' | '\'' => quote = Some(c),
+                    '[' | '(' | '{' => depth += 1,
+                    ']' | ')' | '}' => depth -= 1,
+                    '#' => break,
+                    _ => {}
+                },
+            }
+        }
+        if depth <= 0 {
+            collecting = false;
+            depth = 0;
+        }
+    }
+    found.then_some(names)
+}
+
+/// Whether `new` only appends optional parameters to `old`: defaults,
+/// TypeScript `?` parameters, rest parameters and C# `params`. The return
+/// part after the parameter list must be unchanged.
+fn extends_signature(old: &str, new: &str) -> bool {
+    let (Some((old_head, old_params, old_rest)), Some((new_head, new_params, new_rest))) =
+        (split_parameters(old), split_parameters(new))
+    else {
+        return false;
+    };
+    if old_head != new_head || old_rest != new_rest || new_params.len() <= old_params.len() {
+        return false;
+    }
+    new_params[..old_params.len()] == old_params[..]
+        && new_params[old_params.len()..].iter().all(|param| {
+            param.replace("=>", "").contains('=')
+                || param
+                    .split(':')
+                    .next()
+                    .is_some_and(|name| name.trim_end().ends_with('?'))
+                || param.starts_with('*')
+                || param.starts_with("...")
+                || param.starts_with("params ")
+        })
+}
+
+/// Text before the parameter list, the top-level parameters, and the text
+/// after the list.
+fn split_parameters(signature: &str) -> Option<(&str, Vec<&str>, &str)> {
+    let open = signature.find('(')?;
+    let mut depth = 0;
+    let mut params = Vec::new();
+    let mut start = open + 1;
+    for (idx, c) in signature.char_indices().skip_while(|(idx, _)| *idx <= open) {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' if depth == 0 => {
+                let last = signature[start..idx].trim();
+                if !last.is_empty() {
+                    params.push(last);
+                }
+                return Some((&signature[..open], params, signature[idx + 1..].trim()));
+            }
+            ')' | ']' | '}' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(signature[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn classify(
+    config: &ApiChangesConfig,
+    old: VersionApi,
+    new: VersionApi,
+    old_edges: &[VersionEdge],
+    new_edges: &[VersionEdge],
+) -> ApiChanges {
+    let old_public = old.visibility();
+    let new_public = new.visibility();
+    let matches = match_symbols(old.symbols, new.symbols);
+
+    let mut changes = Vec::new();
+    let mut internal = 0;
+    let mut record = |kind: ApiChangeKind, old: Option<&Symbol>, new: Option<&Symbol>| {
+        let symbol = new.or(old).expect("a change has a symbol");
+        let mut change = ApiChange {
+            qualname: symbol.qualname.clone(),
+            kind: symbol.kind.clone(),
+            path: symbol.file_path.clone(),
+            change: kind,
+            level: level_of(kind),
+            old_signature: old.and_then(|old| old.signature.clone()),
+            new_signature: new.and_then(|new| new.signature.clone()),
+            approved: None,
+            external_callers: 0,
+            callers: Vec::new(),
+        };
+        if change.level == SemverLevel::Major {
+            // Callers in either version: the old one still references what
+            // was removed, the new one what became incompatible.
+            let mut callers = BTreeSet::new();
+            if let Some(old) = old {
+                callers.extend(external_callers(old, old_edges, config.depth));
+            }
+            if let Some(new) = new {
+                callers.extend(external_callers(new, new_edges, config.depth));
+            }
+            change.external_callers = callers.len();
+            change.callers = callers.into_iter().take(config.max_callers).collect();
+            change.approved = Some(is_approved(&config.approved, &change.qualname));
+        }
+        changes.push(change);
+    };
+    let visibility = |public: &HashMap<i64, bool>, symbol: &Symbol| public.get(&symbol.id).copied();
+
+    for (old, new) in &matches.kept {
+        let (Some(was), Some(is)) = (visibility(&old_public, old), visibility(&new_public, new))
+        else {
+            continue;
+        };
+        let moved = old.file_path != new.file_path;
+        match (was, is) {
+            (true, true) if moved => record(ApiChangeKind::Moved, Some(old), Some(new)),
+            (true, false) => record(ApiChangeKind::VisibilityReduced, Some(old), Some(new)),
+            (false, true) => record(ApiChangeKind::Exposed, Some(old), Some(new)),
+            (false, false) if moved => internal += 1,
+            _ => {}
+        }
+    }
+    for (old, new) in &matches.changed {
+        let (Some(was), Some(is)) = (visibility(&old_public, old), visibility(&new_public, new))
+        else {
+            continue;
+        };
+        let extends = || matches!((&old.signature, &new.signature), (Some(old), Some(new)) if extends_signature(old, new));
+        match (was, is) {
+            (true, true) if extends() => {
+                record(ApiChangeKind::SignatureExtended, Some(old), Some(new))
+            }
+            (true, true) => record(ApiChangeKind::SignatureChanged, Some(old), Some(new)),
+            (true, false) => record(ApiChangeKind::VisibilityReduced, Some(old), Some(new)),
+            (false, true) => record(ApiChangeKind::Exposed, Some(old), Some(new)),
+            (false, false) => internal += 1,
+        }
+    }
+    for symbol in &matches.added {
+        match visibility(&new_public, symbol) {
+            Some(true) => record(ApiChangeKind::Added, None, Some(symbol)),
+            Some(false) => internal += 1,
+            None => {}
+        }
+    }
+    for symbol in &matches.removed {
+        match visibility(&old_public, symbol) {
+            Some(true) => record(ApiChangeKind::Removed, Some(symbol), None),
+            Some(false) => internal += 1,
+            None => {}
+        }
+    }
+
+    let mut summary = ApiSummary {
+        internal,
+        patch: internal,
+        ..ApiSummary::default()
+    };
+    for change in &changes {
+        match change.level {
+            SemverLevel::Major => summary.major += 1,
+            SemverLevel::Minor => summary.minor += 1,
+            SemverLevel::Patch => summary.patch += 1,
+            SemverLevel::None => {}
+        }
+        match change.approved {
+            Some(true) => summary.breaking_approved += 1,
+            Some(false) => summary.breaking_unapproved += 1,
+            None => {}
+        }
+    }
+    let bump = if summary.major > 0 {
+        SemverLevel::Major
+    } else if summary.minor > 0 {
+        SemverLevel::Minor
+    } else if summary.patch > 0 {
+        SemverLevel::Patch
+    } else {
+        SemverLevel::None
+    };
+
+    changes.sort_by(|a, b| {
+        b.level
+            .cmp(&a.level)
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.qualname.cmp(&b.qualname))
+    });
+    changes.truncate(config.limit);
+    ApiChanges {
+        from_version: config.from_version,
+        to_version: config.to_version,
+        bump,
+        summary,
+        changes,
+    }
+}
+
+fn level_of(change: ApiChangeKind) -> SemverLevel {
+    match change {
+        ApiChangeKind::Removed
+        | ApiChangeKind::SignatureChanged
+        | ApiChangeKind::VisibilityReduced => SemverLevel::Major,
+        ApiChangeKind::Added | ApiChangeKind::Exposed | ApiChangeKind::SignatureExtended => {
+            SemverLevel::Minor
+        }
+        ApiChangeKind::Moved => SemverLevel::Patch,
+    }
+}
+
+/// Sources of edges to `symbol` from files in another module. As for
+/// find-references, these are the edges resolved to it plus the unresolved
+/// calls whose target is its name or ends in `.name`.
+fn external_callers(symbol: &Symbol, edges: &[VersionEdge], depth: usize) -> Vec<ExternalCaller> {
+    let module = module_prefix(&symbol.file_path, depth);
+    let suffix = format!(".{}", symbol.name);
+    edges
+        .iter()
+        .filter(|edge| {
+            let Some(target) = edge.target_qualname.as_deref() else {
+                return false;
+            };
+            let refers = match edge.target_path.as_deref() {
+                Some(path) => target == symbol.qualname && path == symbol.file_path,
+                None => {
+                    edge.kind == "CALLS" && (target == symbol.name || target.ends_with(&suffix))
+                }
+            };
+            refers && module_prefix(&edge.file_path, depth) != module
+        })
+        .map(|edge| ExternalCaller {
+            source: edge
+                .source_qualname
+                .clone()
+                .unwrap_or_else(|| edge.file_path.clone()),
+            path: edge.file_path.clone(),
+        })
+        .collect()
+}
+
+fn is_approved(approved: &[String], qualname: &str) -> bool {
+    approved
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => qualname.starts_with(prefix),
+            None => pattern == qualname,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(id: i64, kind: &str, qualname: &str, path: &str) -> Symbol {
+        let name = qualname.rsplit(['.', ':']).next().unwrap().to_string();
+        Symbol {
+            id,
+            file_path: path.to_string(),
+            kind: kind.to_string(),
+            name,
+            qualname: qualname.to_string(),
+            start_line: 1,
+            start_col: 0,
+            end_line: 1,
+            end_col: 0,
+            start_byte: 0,
+            end_byte: 0,
+            signature: None,
+            docstring: None,
+            graph_version: 1,
+            commit_sha: None,
+            stable_id: Some(qualname.to_string()),
+        }
+    }
+
+    fn version(symbols: &[(&str, &str, &str, &str)]) -> VersionApi {
+        let mut api = VersionApi {
+            symbols: Vec::new(),
+            heads: HashMap::new(),
+            exports: HashMap::new(),
+        };
+        for (idx, (kind, qualname, path, head)) in symbols.iter().enumerate() {
+            let id = idx as i64 + 1;
+            api.symbols.push(symbol(id, kind, qualname, path));
+            api.heads.insert(id, head.to_string());
+        }
+        api
+    }
+
+    fn public_names(api: &VersionApi) -> Vec<&str> {
+        let visibility = api.visibility();
+        api.symbols
+            .iter()
+            .filter(|symbol| visibility.get(&symbol.id) == Some(&true))
+            .map(|symbol| symbol.qualname.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn reads_visibility_per_language() {
+        let mut api = version(&[
+            ("module", "crate", "src/lib.rs", ""),
+            ("function", "crate::open", "src/lib.rs", "pub fn open() {}"),
+            (
+                "function",
+                "crate::local",
+                "src/lib.rs",
+                "pub(crate) fn local() {}",
+            ),
+            ("struct", "crate::Thing", "src/lib.rs", "pub struct Thing;"),
+            (
+                "method",
+                "crate::Thing::new",
+                "src/lib.rs",
+                "impl Thing { pub fn new() {}",
+            ),
+            (
+                "method",
+                "crate::Thing::secret",
+                "src/lib.rs",
+                "impl Thing { fn secret() {}",
+            ),
+            ("trait", "crate::Api", "src/lib.rs", "pub trait Api {"),
+            (
+                "method",
+                "crate::Api::call",
+                "src/lib.rs",
+                "pub trait Api { fn call(&self); }",
+            ),
+            ("namespace", "Acme", "Lib/W.cs", ""),
+            ("class", "Acme.W", "Lib/W.cs", "public class W"),
+            (
+                "method",
+                "Acme.W.Run",
+                "Lib/W.cs",
+                "[Obsolete(\"Run\")]\npublic int Run(int x)",
+            ),
+            ("method", "Acme.W.Hide", "Lib/W.cs", "private void Hide()"),
+            ("class", "Acme.Helper", "Lib/W.cs", "internal class Helper"),
+            ("method", "Acme.Helper.Go", "Lib/W.cs", "public void Go()"),
+            ("interface", "Acme.IW", "Lib/W.cs", "public interface IW"),
+            ("method", "Acme.IW.Run", "Lib/W.cs", "void Run(int x);"),
+            (
+                "function",
+                "web/api.load",
+                "web/api.ts",
+                "export function load(id: string) {",
+            ),
+            (
+                "function",
+                "web/api.helper",
+                "web/api.ts",
+                "function helper() {}",
+            ),
+            (
+                "class",
+                "web/api.Client",
+                "web/api.ts",
+                "export class Client {",
+            ),
+            (
+                "method",
+                "web/api.Client.get",
+                "web/api.ts",
+                "get(path: string) {",
+            ),
+            (
+                "method",
+                "web/api.Client.secret",
+                "web/api.ts",
+                "private secret() {}",
+            ),
+            ("class", "svc.Server", "svc/svc.go", "type Server struct {"),
+            (
+                "method",
+                "svc.Server.Start",
+                "svc/svc.go",
+                "func (s *Server) Start() {",
+            ),
+            (
+                "method",
+                "svc.Server.stop",
+                "svc/svc.go",
+                "func (s *Server) stop() {",
+            ),
+            ("function", "svc.helper", "svc/svc.go", "func helper() {}"),
+            ("module", "pkg", "pkg/__init__.py", ""),
+            ("function", "pkg.listed", "pkg/__init__.py", "def listed():"),
+            (
+                "function",
+                "pkg.unlisted",
+                "pkg/__init__.py",
+                "def unlisted():",
+            ),
+            ("class", "pkg.plain.Widget", "pkg/plain.py", "class Widget:"),
+            (
+                "method",
+                "pkg.plain.Widget.__init__",
+                "pkg/plain.py",
+                "def __init__(self):",
+            ),
+            (
+                "method",
+                "pkg.plain.Widget._hidden",
+                "pkg/plain.py",
+                "def _hidden(self):",
+            ),
+            (
+                "function",
+                "pkg.plain._private",
+                "pkg/plain.py",
+                "def _private():",
+            ),
+            ("function", "Main.run", "src/Main.java", "public void run()"),
+        ]);
+        api.exports.insert(
+            "pkg/__init__.py".to_string(),
+            HashSet::from(["listed".to_string()]),
+        );
+        assert_eq!(
+            public_names(&api),
+            vec![
+                "crate::open",
+                "crate::Thing",
+                "crate::Thing::new",
+                "crate::Api",
+                "crate::Api::call",
+                "Acme.W",
+                "Acme.W.Run",
+                "Acme.IW",
+                "Acme.IW.Run",
+                "web/api.load",
+                "web/api.Client",
+                "web/api.Client.get",
+                "svc.Server",
+                "svc.Server.Start",
+                "pkg.listed",
+                "pkg.plain.Widget",
+                "pkg.plain.Widget.__init__",
+            ]
+        );
+        let visibility = api.visibility();
+        assert!(!visibility.contains_key(&1), "modules are not API");
+        assert!(!visibility.contains_key(&33), "Java is not covered");
+    }
+
+    #[test]
+    fn parses_python_all() {
+        let lines: Vec<String> = [
+            "import os",
+            "__all__ = [",
+            "    \"load\",  # main entry",
+            "    'Store',",
+            "]",
+            "__all__ += ('extra',)",
+            "names = ['not_exported']",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        let names = python_exports(&lines).unwrap();
+        assert_eq!(
+            names,
+            HashSet::from(["load", "Store", "extra"].map(str::to_string))
+        );
+        assert!(python_exports(&lines[..1]).is_none());
+    }
+
+    #[test]
+    fn recognizes_compatible_signature_extensions() {
+        assert!(extends_signature("(a, b=1)", "(a, b=1, c=None)"));
+        assert!(extends_signature(
+            "(id: string)",
+            "(id: string, opts?: Opts)"
+        ));
+        assert!(extends_signature(
+            "(int x) -> int",
+            "(int x, int y = 0) -> int"
+        ));
+        assert!(extends_signature("(self)", "(self, *args, **kwargs)"));
+        assert!(!extends_signature("(a, b=1)", "(a, c, b=1)"));
+        assert!(!extends_signature("(a)", "(a, b)"));
+        assert!(!extends_signature(
+            "(a: i32) -> i32",
+            "(a: i32, b: Option<i32>) -> i32"
+        ));
+        assert!(!extends_signature(
+            "(int x) -> int",
+            "(int x, int y = 0) -> long"
+        ));
+        assert!(!extends_signature(
+            "(cb: () => void)",
+            "(cb: () => void, f: () => void)"
+        ));
+    }
+
+    #[test]
+    fn classifies_changes_and_approvals() {
+        let mut old = version(&[
+            ("function", "store.load", "store/db.py", "def load(id):"),
+            ("function", "store.drop", "store/db.py", "def drop():"),
+            ("function", "store.hide", "store/db.py", "def hide():"),
+            ("function", "store.grow", "store/db.py", "def grow(a):"),
+            ("function", "store._tmp", "store/db.py", "def _tmp():"),
+        ]);
+        let mut new = version(&[
+            (
+                "function",
+                "store.load",
+                "store/db.py",
+                "def load(id, fields):",
+            ),
+            ("function", "store._hide", "store/db.py", "def _hide():"),
+            ("function", "store.grow", "store/db.py", "def grow(a, b=2):"),
+            ("function", "store.fresh", "store/db.py", "def fresh():"),
+        ]);
+        // `hide` is renamed, which is a removal; `load` and `grow` change
+        // signature under the same qualname.
+        old.symbols[0].signature = Some("(id)".to_string());
+        old.symbols[3].signature = Some("(a)".to_string());
+        new.symbols[0].signature = Some("(id, fields)".to_string());
+        new.symbols[0].stable_id = Some("store.load(id, fields)".to_string());
+        new.symbols[2].signature = Some("(a, b=2)".to_string());
+        new.symbols[2].stable_id = Some("store.grow(a, b=2)".to_string());
+        let caller = |source: &str, path: &str| VersionEdge {
+            kind: "CALLS".to_string(),
+            file_path: path.to_string(),
+            source_qualname: Some(source.to_string()),
+            target_qualname: Some("store.load".to_string()),
+            target_path: Some("store/db.py".to_string()),
+            detail: None,
+        };
+        let old_edges = vec![
+            caller("api.get", "api/routes.py"),
+            caller("api.get", "api/routes.py"),
+            caller("store.cache", "store/cache.py"),
+        ];
+        let config = ApiChangesConfig {
+            from_version: 1,
+            to_version: 2,
+            depth: 1,
+            limit: 100,
+            max_callers: 10,
+            languages: None,
+            paths: None,
+            approved: vec!["store.dr*".to_string()],
+        };
+        let result = classify(&config, old, new, &old_edges, &[]);
+
+        assert_eq!(result.bump, SemverLevel::Major);
+        let changes: Vec<(&str, ApiChangeKind, Option<bool>)> = result
+            .changes
+            .iter()
+            .map(|change| (change.qualname.as_str(), change.change, change.approved))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("store.drop", ApiChangeKind::Removed, Some(true)),
+                ("store.hide", ApiChangeKind::Removed, Some(false)),
+                ("store.load", ApiChangeKind::SignatureChanged, Some(false)),
+                ("store.fresh", ApiChangeKind::Added, None),
+                ("store.grow", ApiChangeKind::SignatureExtended, None),
+            ]
+        );
+        let load = &result.changes[2];
+        assert_eq!(load.external_callers, 1);
+        assert_eq!(
+            load.callers,
+            vec![ExternalCaller {
+                source: "api.get".to_string(),
+                path: "api/routes.py".to_string(),
+            }]
+        );
+        assert_eq!(
+            result.summary,
+            ApiSummary {
+                major: 3,
+                minor: 2,
+                patch: 2,
+                internal: 2,
+                breaking_approved: 1,
+                breaking_unapproved: 2,
+            }
+        );
+        assert!(result.has_unapproved_breaks());
+    }
+}
@@ -9,6 +9,11 @@ use std::path::PathBuf;
     about = "Code indexer v1",
     after_help = r#"Examples:
   lidx reindex --repo .
+  lidx reindex --workspace lidx-workspace.yaml
+  lidx reindex --repo . --rev main
+  lidx api-check --repo . --base v1.4 --approve 'crate::legacy::*'
+  lidx affected-tests --repo . --base origin/main --shell
+  git diff HEAD~1 | lidx affected-tests --repo . --diff -
   lidx request --method repo_overview --params '{"summary":true}'
   lidx request --method list_languages --params '{}'
   lidx request --method search --params '{"query":"Indexer","limit":10}'
@@ -16,6 +21,9 @@ use std::path::PathBuf;
   lidx request --method search_rg --params '{"query":"def\\s+greet","context_lines":8}'
   lidx serve --repo . --watch auto
   lidx mcp-serve --repo .
+  lidx mcp-serve --repo . --http 127.0.0.1:7333
+  lidx daemon --repo .
+  lidx lsp --repo .
 "#
 )]
 pub struct Args {
@@ -56,6 +64,13 @@ pub enum Command {
         /// Include files ignored by .gitignore.
         #[arg(long)]
         no_ignore: bool,
+        /// Reindex every repo listed in this workspace manifest instead of --repo.
+        #[arg(long, conflicts_with = "db")]
+        workspace: Option<PathBuf>,
+        /// Index this git revision (branch, tag or sha) from the object
+        /// database as its own graph version, without checking it out.
+        #[arg(long, conflicts_with = "workspace")]
+        rev: Option<String>,
     },
     /// Show changed files compared to DB state.
     ChangedFiles {
@@ -67,6 +82,51 @@ pub enum Command {
         #[arg(long)]
         no_ignore: bool,
     },
+    /// Report public API changes since a git revision and exit non-zero on
+    /// unapproved breaking changes.
+    ApiCheck {
+        #[arg(long, default_value = ".")]
+        repo: PathBuf,
+        #[arg(long)]
+        db: Option<PathBuf>,
+        /// Include files ignored by .gitignore.
+        #[arg(long)]
+        no_ignore: bool,
+        /// Revision (branch, tag or sha) holding the released API.
+        #[arg(long)]
+        base: String,
+        /// Revision to check; defaults to the working tree, which is reindexed first.
+        #[arg(long)]
+        head: Option<String>,
+        /// Qualname (or `prefix*`) whose breaking changes are accepted, on top of
+        /// `[api] approved` in .lidx.toml. Repeatable.
+        #[arg(long, value_name = "QUALNAME")]
+        approve: Vec<String>,
+        /// Only check files under this path prefix. Repeatable.
+        #[arg(long)]
+        path: Vec<String>,
+    },
+    /// List the tests affected by a change, in confidence tiers, with the
+    /// commands that run them.
+    AffectedTests {
+        #[arg(long, default_value = ".")]
+        repo: PathBuf,
+        #[arg(long)]
+        db: Option<PathBuf>,
+        /// Include files ignored by .gitignore.
+        #[arg(long)]
+        no_ignore: bool,
+        /// Unified diff file to read changes from; `-` reads stdin.
+        #[arg(long, conflicts_with = "base", required_unless_present = "base")]
+        diff: Option<PathBuf>,
+        /// Revision (branch, tag or sha) to diff the working tree against.
+        #[arg(long)]
+        base: Option<String>,
+        /// Print one shell line per command, highest confidence first,
+        /// instead of JSON.
+        #[arg(long)]
+        shell: bool,
+    },
     /// Print a repository overview.
     Overview {
         #[arg(long, default_value = ".")]
@@ -88,8 +148,14 @@ pub enum Command {
         params_file: Option<PathBuf>,
         #[arg(long, default_value = "1")]
         id: String,
+        /// Include files ignored by .gitignore.
+        #[arg(long)]
+        no_ignore: bool,
+        /// Run in-process instead of through the repo daemon.
+        #[arg(long)]
+        no_daemon: bool,
     },
-    /// Run MCP server over stdio.
+    /// Run MCP server over stdio, or over Streamable HTTP/SSE with --http.
     McpServe {
         #[arg(long, default_value = ".")]
         repo: PathBuf,
@@ -110,6 +176,59 @@ pub enum Command {
         /// Trigger a full reindex when a batch exceeds this many paths.
         #[arg(long, default_value_t = 1000)]
         watch_batch_max: usize,
+        /// Index and watch in-process instead of through the repo daemon.
+        #[arg(long)]
+        no_daemon: bool,
+        /// Serve MCP over HTTP on this address (POST /mcp, legacy GET /sse) instead of stdio.
+        #[arg(long, value_name = "ADDR")]
+        http: Option<String>,
+    },
+    /// Run the repo daemon: owns indexing and watching, serves JSONL RPC over a Unix socket.
+    Daemon {
+        #[arg(long, default_value = ".")]
+        repo: PathBuf,
+        #[arg(long)]
+        db: Option<PathBuf>,
+        /// Include files ignored by .gitignore.
+        #[arg(long)]
+        no_ignore: bool,
+        /// File watch mode: auto|on|off.
+        #[arg(long, default_value = "auto")]
+        watch: WatchMode,
+        /// Debounce window for filesystem events in milliseconds.
+        #[arg(long, default_value_t = 300)]
+        watch_debounce_ms: u64,
+        /// Fallback full-scan interval in seconds when watch is unavailable.
+        #[arg(long, default_value_t = 300)]
+        watch_fallback_secs: u64,
+        /// Trigger a full reindex when a batch exceeds this many paths.
+        #[arg(long, default_value_t = 1000)]
+        watch_batch_max: usize,
+        /// Exit after this many seconds without connected clients (0 = never).
+        #[arg(long, default_value_t = 0)]
+        idle_timeout_secs: u64,
+    },
+    /// Run a Language Server Protocol server over stdio backed by the index.
+    Lsp {
+        #[arg(long, default_value = ".")]
+        repo: PathBuf,
+        #[arg(long)]
+        db: Option<PathBuf>,
+        /// Include files ignored by .gitignore.
+        #[arg(long)]
+        no_ignore: bool,
+        /// File watch mode: auto|on|off.
+        #[arg(long, default_value = "auto")]
+        watch: WatchMode,
+        /// Debounce window for filesystem events in milliseconds.
+        #[arg(long, default_value_t = 300)]
+        watch_debounce_ms: u64,
+        /// Fallback full-scan interval in seconds when watch is unavailable.
+        #[arg(long, default_value_t = 300)]
+        watch_fallback_secs: u64,
+        /// Trigger a full reindex when a batch exceeds this many paths.
+        #[arg(long, default_value_t = 1000)]
+        watch_batch_max: usize,
     },
     /// Show compact cross-file context for a file.
     Context {
@@ -0,0 +1,671 @@
+//! Structural diff between two graph versions.
+//!
+//! Symbols are matched on `stable_id`, which hashes qualname, signature and
+//! kind: a match in another file is a move, and an unmatched pair with the
+//! same qualname and kind is a signature change. Edges have no stable id, so
+//! they are matched on kind and endpoint qualnames.
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::db::{Db, module_prefix};
+use crate::model::{Symbol, VersionEdge};
+
+/// Example edges listed under each kind and module.
+const EDGE_SAMPLES: usize = 10;
+
+pub struct CompareConfig {
+    pub from_version: i64,
+    pub to_version: i64,
+    /// Directory depth that groups files into modules.
+    pub depth: usize,
+    /// Maximum entries per list; the summary counts everything.
+    pub limit: usize,
+    pub languages: Option<Vec<String>>,
+    pub paths: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VersionComparison {
+    pub from_version: i64,
+    pub to_version: i64,
+    pub summary: ComparisonSummary,
+    pub symbols: SymbolChanges,
+    pub edges: EdgeChanges,
+    pub module_dependencies: Changes<ModuleDependency>,
+    pub http_routes: Changes<RouteEntry>,
+    pub rpc_impls: Changes<RouteEntry>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ComparisonSummary {
+    pub symbols_added: usize,
+    pub symbols_removed: usize,
+    pub symbols_moved: usize,
+    pub signatures_changed: usize,
+    pub symbols_unchanged: usize,
+    pub edges_added: usize,
+    pub edges_removed: usize,
+    pub module_dependencies_added: usize,
+    pub module_dependencies_removed: usize,
+    pub http_routes_added: usize,
+    pub http_routes_removed: usize,
+    pub rpc_impls_added: usize,
+    pub rpc_impls_removed: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SymbolChanges {
+    pub added: Vec<SymbolEntry>,
+    pub removed: Vec<SymbolEntry>,
+    pub moved: Vec<MovedSymbol>,
+    pub signature_changed: Vec<SignatureChange>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SymbolEntry {
+    pub qualname: String,
+    pub kind: String,
+    pub path: String,
+    pub start_line: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct MovedSymbol {
+    pub qualname: String,
+    pub kind: String,
+    pub from_path: String,
+    pub to_path: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SignatureChange {
+    pub qualname: String,
+    pub kind: String,
+    pub path: String,
+    pub old_signature: Option<String>,
+    pub new_signature: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct EdgeChanges {
+    pub added: Vec<EdgeGroup>,
+    pub removed: Vec<EdgeGroup>,
+}
+
+/// Added or removed edges of one kind whose file is in `module`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct EdgeGroup {
+    pub kind: String,
+    pub module: String,
+    pub count: usize,
+    pub edges: Vec<EdgeEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct EdgeEntry {
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Changes<T> {
+    pub added: Vec<T>,
+    pub removed: Vec<T>,
+}
+
+/// Edges from files in one module to symbols in another.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ModuleDependency {
+    pub source_module: String,
+    pub target_module: String,
+    pub count: usize,
+    pub kinds: Vec<String>,
+}
+
+/// An HTTP route (`GET /users/{}`) or RPC path and the first symbol serving it.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RouteEntry {
+    pub route: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handler: Option<String>,
+    pub path: String,
+}
+
+pub fn compare_versions(db: &Db, config: &CompareConfig) -> Result<VersionComparison> {
+    let languages = config.languages.as_deref();
+    let paths = config.paths.as_deref();
+    let old_symbols = db.version_symbols(config.from_version, languages, paths)?;
+    let new_symbols = db.version_symbols(config.to_version, languages, paths)?;
+    let old_edges = db.version_edges(config.from_version, languages, paths)?;
+    let new_edges = db.version_edges(config.to_version, languages, paths)?;
+    Ok(compare(
+        config,
+        old_symbols,
+        new_symbols,
+        &old_edges,
+        &new_edges,
+    ))
+}
+
+fn compare(
+    config: &CompareConfig,
+    old_symbols: Vec<Symbol>,
+    new_symbols: Vec<Symbol>,
+    old_edges: &[VersionEdge],
+    new_edges: &[VersionEdge],
+) -> VersionComparison {
+    let (mut symbols, symbols_unchanged) = diff_symbols(old_symbols, new_symbols);
+
+    let added_edges = edges_missing_from(new_edges, old_edges);
+    let removed_edges = edges_missing_from(old_edges, new_edges);
+
+    let old_deps = module_dependencies(old_edges, config.depth);
+    let new_deps = module_dependencies(new_edges, config.depth);
+    let mut deps = Changes {
+        added: dependencies_missing_from(&new_deps, &old_deps),
+        removed: dependencies_missing_from(&old_deps, &new_deps),
+    };
+
+    let mut http_routes = route_changes(old_edges, new_edges, "HTTP_ROUTE");
+    let mut rpc_impls = route_changes(old_edges, new_edges, "RPC_IMPL");
+
+    let summary = ComparisonSummary {
+        symbols_added: symbols.added.len(),
+        symbols_removed: symbols.removed.len(),
+        symbols_moved: symbols.moved.len(),
+        signatures_changed: symbols.signature_changed.len(),
+        symbols_unchanged,
+        edges_added: added_edges.len(),
+        edges_removed: removed_edges.len(),
+        module_dependencies_added: deps.added.len(),
+        module_dependencies_removed: deps.removed.len(),
+        http_routes_added: http_routes.added.len(),
+        http_routes_removed: http_routes.removed.len(),
+        rpc_impls_added: rpc_impls.added.len(),
+        rpc_impls_removed: rpc_impls.removed.len(),
+    };
+
+    let limit = config.limit;
+    symbols.added.truncate(limit);
+    symbols.removed.truncate(limit);
+    symbols.moved.truncate(limit);
+    symbols.signature_changed.truncate(limit);
+    let mut edges = EdgeChanges {
+        added: group_edges(&added_edges, config.depth),
+        removed: group_edges(&removed_edges, config.depth),
+    };
+    edges.added.truncate(limit);
+    edges.removed.truncate(limit);
+    for changes in [&mut http_routes, &mut rpc_impls] {
+        changes.added.truncate(limit);
+        changes.removed.truncate(limit);
+    }
+    deps.added.truncate(limit);
+    deps.removed.truncate(limit);
+
+    VersionComparison {
+        from_version: config.from_version,
+        to_version: config.to_version,
+        summary,
+        symbols,
+        edges,
+        module_dependencies: deps,
+        http_routes,
+        rpc_impls,
+    }
+}
+
+/// Symbol changes in path order, and the number of symbols left as they were.
+fn diff_symbols(old: Vec<Symbol>, new: Vec<Symbol>) -> (SymbolChanges, usize) {
+    let matches = match_symbols(old, new);
+    let mut changes = SymbolChanges::default();
+    let mut unchanged = 0;
+    for (old, symbol) in matches.kept {
+        if old.file_path == symbol.file_path {
+            unchanged += 1;
+        } else {
+            changes.moved.push(MovedSymbol {
+                qualname: symbol.qualname,
+                kind: symbol.kind,
+                from_path: old.file_path,
+                to_path: symbol.file_path,
+            });
+        }
+    }
+    changes.signature_changed = matches
+        .changed
+        .into_iter()
+        .map(|(old, symbol)| SignatureChange {
+            qualname: symbol.qualname,
+            kind: symbol.kind,
+            path: symbol.file_path,
+            old_signature: old.signature,
+            new_signature: symbol.signature,
+        })
+        .collect();
+    changes.added = matches.added.into_iter().map(symbol_entry).collect();
+    changes.removed = matches.removed.into_iter().map(symbol_entry).collect();
+    (changes, unchanged)
+}
+
+/// Symbols of two versions paired up, in the order of the version they
+/// come from.
+pub(crate) struct SymbolMatches {
+    /// Same stable id; the file may differ.
+    pub kept: Vec<(Symbol, Symbol)>,
+    /// Same qualname and kind under another signature.
+    pub changed: Vec<(Symbol, Symbol)>,
+    pub added: Vec<Symbol>,
+    pub removed: Vec<Symbol>,
+}
+
+pub(crate) fn match_symbols(old: Vec<Symbol>, new: Vec<Symbol>) -> SymbolMatches {
+    let mut old_order = Vec::new();
+    let mut old_by_id: HashMap<String, Symbol> = HashMap::new();
+    for symbol in old {
+        if let Entry::Vacant(entry) = old_by_id.entry(symbol_key(&symbol)) {
+            old_order.push(entry.key().clone());
+            entry.insert(symbol);
+        }
+    }
+
+    let mut kept = Vec::new();
+    let mut added = Vec::new();
+    let mut seen = HashSet::new();
+    for symbol in new {
+        let key = symbol_key(&symbol);
+        if !seen.insert(key.clone()) {
+            continue;
+        }
+        match old_by_id.remove(&key) {
+            Some(old) => kept.push((old, symbol)),
+            None => added.push(symbol),
+        }
+    }
+    let removed: Vec<Symbol> = old_order
+        .into_iter()
+        .filter_map(|key| old_by_id.remove(&key))
+        .collect();
+
+    // What is left on both sides under the same name changed its signature;
+    // overloads pair up in order.
+    let mut removed_by_name: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    for (idx, symbol) in removed.iter().enumerate().rev() {
+        removed_by_name
+            .entry((symbol.qualname.clone(), symbol.kind.clone()))
+            .or_default()
+            .push(idx);
+    }
+    let mut pairs: Vec<(usize, Symbol)> = Vec::new();
+    let mut unpaired = Vec::new();
+    for symbol in added {
+        let old_idx = removed_by_name
+            .get_mut(&(symbol.qualname.clone(), symbol.kind.clone()))
+            .and_then(Vec::pop);
+        match old_idx {
+            Some(idx) => pairs.push((idx, symbol)),
+            None => unpaired.push(symbol),
+        }
+    }
+    let mut removed: Vec<Option<Symbol>> = removed.into_iter().map(Some).collect();
+    let changed = pairs
+        .into_iter()
+        .filter_map(|(idx, symbol)| removed[idx].take().map(|old| (old, symbol)))
+        .collect();
+    SymbolMatches {
+        kept,
+        changed,
+        added: unpaired,
+        removed: removed.into_iter().flatten().collect(),
+    }
+}
+
+fn symbol_key(symbol: &Symbol) -> String {
+    symbol.stable_id.clone().unwrap_or_else(|| {
+        format!(
+            "{}\0{}\0{}",
+            symbol.qualname,
+            symbol.signature.as_deref().unwrap_or_default(),
+            symbol.kind
+        )
+    })
+}
+
+fn symbol_entry(symbol: Symbol) -> SymbolEntry {
+    SymbolEntry {
+        qualname: symbol.qualname,
+        kind: symbol.kind,
+        path: symbol.file_path,
+        start_line: symbol.start_line,
+        signature: symbol.signature,
+    }
+}
+
+/// Kind, source (the file for file-level edges) and target.
+fn edge_key(edge: &VersionEdge) -> (&str, &str, &str) {
+    (
+        edge.kind.as_str(),
+        edge.source_qualname.as_deref().unwrap_or(&edge.file_path),
+        edge.target_qualname.as_deref().unwrap_or_default(),
+    )
+}
+
+/// Distinct edges of `edges` that `other` lacks, in order.
+fn edges_missing_from<'a>(edges: &'a [VersionEdge], other: &[VersionEdge]) -> Vec<&'a VersionEdge> {
+    let other: HashSet<_> = other.iter().map(edge_key).collect();
+    let mut seen = HashSet::new();
+    edges
+        .iter()
+        .filter(|edge| {
+            let key = edge_key(edge);
+            !other.contains(&key) && seen.insert(key)
+        })
+        .collect()
+}
+
+/// Largest groups first.
+fn group_edges(edges: &[&VersionEdge], depth: usize) -> Vec<EdgeGroup> {
+    let mut groups: BTreeMap<(String, String), EdgeGroup> = BTreeMap::new();
+    for edge in edges {
+        let module = module_prefix(&edge.file_path, depth);
+        let group = groups
+            .entry((edge.kind.clone(), module.clone()))
+            .or_insert_with(|| EdgeGroup {
+                kind: edge.kind.clone(),
+                module,
+                count: 0,
+                edges: Vec::new(),
+            });
+        group.count += 1;
+        if group.edges.len() < EDGE_SAMPLES {
+            let (_, source, _) = edge_key(edge);
+            group.edges.push(EdgeEntry {
+                source: source.to_string(),
+                target: edge.target_qualname.clone(),
+                path: edge.file_path.clone(),
+            });
+        }
+    }
+    let mut groups: Vec<EdgeGroup> = groups.into_values().collect();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.count));
+    groups
+}
+
+/// Edge counts and kinds per (source module, target module) pair, for edges
+/// whose target resolved to a symbol in another module.
+fn module_dependencies(
+    edges: &[VersionEdge],
+    depth: usize,
+) -> BTreeMap<(String, String), (usize, BTreeSet<String>)> {
+    let mut deps: BTreeMap<(String, String), (usize, BTreeSet<String>)> = BTreeMap::new();
+    for edge in edges {
+        let Some(target_path) = &edge.target_path else {
+            continue;
+        };
+        let source = module_prefix(&edge.file_path, depth);
+        let target = module_prefix(target_path, depth);
+        if source == target {
+            continue;
+        }
+        let entry = deps.entry((source, target)).or_default();
+        entry.0 += 1;
+        entry.1.insert(edge.kind.clone());
+    }
+    deps
+}
+
+/// Module pairs of `deps` that `other` has no edge between, largest first.
+fn dependencies_missing_from(
+    deps: &BTreeMap<(String, String), (usize, BTreeSet<String>)>,
+    other: &BTreeMap<(String, String), (usize, BTreeSet<String>)>,
+) -> Vec<ModuleDependency> {
+    let mut missing: Vec<ModuleDependency> = deps
+        .iter()
+        .filter(|(pair, _)| !other.contains_key(*pair))
+        .map(|((source, target), (count, kinds))| ModuleDependency {
+            source_module: source.clone(),
+            target_module: target.clone(),
+            count: *count,
+            kinds: kinds.iter().cloned().collect(),
+        })
+        .collect();
+    missing.sort_by_key(|dep| std::cmp::Reverse(dep.count));
+    missing
+}
+
+/// Routes served by `kind` edges in one version but not the other. A route
+/// that changes handler is neither added nor removed.
+fn route_changes(
+    old_edges: &[VersionEdge],
+    new_edges: &[VersionEdge],
+    kind: &str,
+) -> Changes<RouteEntry> {
+    let old = routes(old_edges, kind);
+    let mut new = routes(new_edges, kind);
+    let mut removed = Vec::new();
+    for (route, entry) in old {
+        if new.remove(&route).is_none() {
+            removed.push(entry);
+        }
+    }
+    Changes {
+        added: new.into_values().collect(),
+        removed,
+    }
+}
+
+fn routes(edges: &[VersionEdge], kind: &str) -> BTreeMap<String, RouteEntry> {
+    let mut routes = BTreeMap::new();
+    for edge in edges.iter().filter(|edge| edge.kind == kind) {
+        let Some(target) = &edge.target_qualname else {
+            continue;
+        };
+        let method = edge
+            .detail
+            .as_deref()
+            .and_then(|detail| serde_json::from_str::<Value>(detail).ok())
+            .and_then(|detail| {
+                detail
+                    .get("method")
+                    .and_then(Value::as_str)
+                    .map(str::to_uppercase)
+            });
+        let route = match method {
+            Some(method) => format!("{method} {target}"),
+            None => target.clone(),
+        };
+        routes.entry(route.clone()).or_insert_with(|| RouteEntry {
+            route,
+            handler: edge.source_qualname.clone(),
+            path: edge.file_path.clone(),
+        });
+    }
+    routes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(qualname: &str, path: &str, signature: Option<&str>) -> Symbol {
+        Symbol {
+            id: 0,
+            file_path: path.to_string(),
+            kind: "function".to_string(),
+            name: qualname.rsplit('.').next().unwrap().to_string(),
+            qualname: qualname.to_string(),
+            start_line: 1,
+            start_col: 0,
+            end_line: 2,
+            end_col: 0,
+            start_byte: 0,
+            end_byte: 0,
+            signature: signature.map(str::to_string),
+            docstring: None,
+            graph_version: 1,
+            commit_sha: None,
+            stable_id: Some(format!("{qualname}|{}", signature.unwrap_or_default())),
+        }
+    }
+
+    fn edge(
+        kind: &str,
+        path: &str,
+        source: &str,
+        target: &str,
+        target_path: Option<&str>,
+    ) -> VersionEdge {
+        VersionEdge {
+            kind: kind.to_string(),
+            file_path: path.to_string(),
+            source_qualname: Some(source.to_string()),
+            target_qualname: Some(target.to_string()),
+            target_path: target_path.map(str::to_string),
+            detail: None,
+        }
+    }
+
+    fn config() -> CompareConfig {
+        CompareConfig {
+            from_version: 1,
+            to_version: 2,
+            depth: 1,
+            limit: 100,
+            languages: None,
+            paths: None,
+        }
+    }
+
+    #[test]
+    fn classifies_symbol_changes() {
+        let old = vec![
+            symbol("a.keep", "a.py", Some("()")),
+            symbol("a.go", "a.py", Some("(x)")),
+            symbol("a.gone", "a.py", None),
+            symbol("Core.Run", "src/old/Core.cs", Some("()")),
+        ];
+        let new = vec![
+            symbol("a.keep", "a.py", Some("()")),
+            symbol("a.go", "a.py", Some("(x, y)")),
+            symbol("a.fresh", "a.py", None),
+            symbol("Core.Run", "src/new/Core.cs", Some("()")),
+        ];
+        let (changes, unchanged) = diff_symbols(old, new);
+        assert_eq!(unchanged, 1);
+        assert_eq!(
+            changes.moved,
+            vec![MovedSymbol {
+                qualname: "Core.Run".to_string(),
+                kind: "function".to_string(),
+                from_path: "src/old/Core.cs".to_string(),
+                to_path: "src/new/Core.cs".to_string(),
+            }]
+        );
+        assert_eq!(
+            changes.signature_changed,
+            vec![SignatureChange {
+                qualname: "a.go".to_string(),
+                kind: "function".to_string(),
+                path: "a.py".to_string(),
+                old_signature: Some("(x)".to_string()),
+                new_signature: Some("(x, y)".to_string()),
+            }]
+        );
+        let names = |entries: &[SymbolEntry]| -> Vec<String> {
+            entries.iter().map(|entry| entry.qualname.clone()).collect()
+        };
+        assert_eq!(names(&changes.added), vec!["a.fresh"]);
+        assert_eq!(names(&changes.removed), vec!["a.gone"]);
+    }
+
+    #[test]
+    fn reports_edges_dependencies_and_routes() {
+        let mut route = edge(
+            "HTTP_ROUTE",
+            "api/users.py",
+            "api.users.list",
+            "/users",
+            None,
+        );
+        route.detail = Some(r#"{"method":"get","path":"/users"}"#.to_string());
+        let old = vec![
+            edge(
+                "CALLS",
+                "api/users.py",
+                "api.users.list",
+                "db.query",
+                Some("db/core.py"),
+            ),
+            edge(
+                "RPC_IMPL",
+                "rpc/server.py",
+                "rpc.server.Get",
+                "/pkg.Users/Get",
+                None,
+            ),
+        ];
+        let new = vec![
+            edge(
+                "CALLS",
+                "api/users.py",
+                "api.users.list",
+                "db.query",
+                Some("db/core.py"),
+            ),
+            edge(
+                "CALLS",
+                "api/users.py",
+                "api.users.list",
+                "cache.get",
+                Some("cache/lru.py"),
+            ),
+            edge(
+                "CALLS",
+                "api/users.py",
+                "api.users.list",
+                "cache.get",
+                Some("cache/lru.py"),
+            ),
+            route,
+        ];
+        let result = compare(&config(), Vec::new(), Vec::new(), &old, &new);
+
+        assert_eq!(result.summary.edges_added, 2);
+        assert_eq!(result.summary.edges_removed, 1);
+        assert_eq!(result.edges.added.len(), 2);
+        let calls = &result.edges.added[0];
+        assert_eq!(
+            (calls.kind.as_str(), calls.module.as_str()),
+            ("CALLS", "api/")
+        );
+        assert_eq!(calls.count, 1);
+        assert_eq!(calls.edges[0].target.as_deref(), Some("cache.get"));
+
+        assert_eq!(
+            result.module_dependencies.added,
+            vec![ModuleDependency {
+                source_module: "api/".to_string(),
+                target_module: "cache/".to_string(),
+                count: 2,
+                kinds: vec!["CALLS".to_string()],
+            }]
+        );
+        assert!(result.module_dependencies.removed.is_empty());
+
+        assert_eq!(result.http_routes.added[0].route, "GET /users");
+        assert_eq!(
+            result.http_routes.added[0].handler.as_deref(),
+            Some("api.users.list")
+        );
+        assert_eq!(result.rpc_impls.removed[0].route, "/pkg.Users/Get");
+        assert_eq!(result.summary.rpc_impls_added, 0);
+    }
+}
@@ -0,0 +1,416 @@
+//! Per-repo daemon: a single process owns the `Indexer` and the file watcher
+//! for a repo/db pair and serves the JSONL RPC protocol of `lidx serve` to any
+//! number of clients over a Unix domain socket next to the database.
+//!
+//! `mcp-serve` and `request` connect to it (spawning it when missing) instead of
+//! opening their own indexer and watcher, so concurrent editor and agent
+//! sessions no longer race each other writing to the same `.lidx` database.
+//!
+//! A request line carrying `"progress": true` gets `{"progress": {...}}` lines
+//! for each reindex progress step before its response.
+
+use crate::indexer::{IndexProgress, Indexer, PROGRESS_PHASES};
+use crate::rpc;
+use crate::util;
+use crate::watch::{self, WatchConfig, WatchMode};
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Longest socket path we bind directly; `sun_path` is 104–108 bytes.
+const MAX_SOCKET_PATH_BYTES: usize = 100;
+/// How long a client waits for a freshly spawned daemon to start listening.
+const SPAWN_TIMEOUT: Duration = Duration::from_secs(10);
+const SPAWN_POLL: Duration = Duration::from_millis(50);
+/// Idle timeout given to daemons spawned on demand by a client.
+pub const AUTO_IDLE_TIMEOUT_SECS: u64 = 1800;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DaemonConfig {
+    pub watch: WatchConfig,
+    /// Exit once no client has been connected for this long. `None` runs forever.
+    pub idle_timeout: Option<Duration>,
+}
+
+impl DaemonConfig {
+    pub fn new(watch: WatchConfig, idle_timeout_secs: u64) -> Self {
+        Self {
+            watch,
+            idle_timeout: (idle_timeout_secs > 0).then(|| Duration::from_secs(idle_timeout_secs)),
+        }
+    }
+}
+
+/// How a client launches a daemon when none is listening.
+#[derive(Clone, Debug)]
+pub struct SpawnOptions {
+    pub repo_root: PathBuf,
+    pub db_path: PathBuf,
+    pub no_ignore: bool,
+    pub watch: WatchMode,
+}
+
+/// Socket path for the daemon serving `db_path`: `<db>.sock` beside the
+/// database, or a hashed name in the temp dir when that path is too long.
+pub fn socket_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_else(|| ".lidx.sqlite".into());
+    name.push(".sock");
+    let candidate = db_path.with_file_name(name);
+    if candidate.as_os_str().len() <= MAX_SOCKET_PATH_BYTES {
+        return candidate;
+    }
+    let absolute = std::fs::canonicalize(db_path.parent().unwrap_or(Path::new(".")))
+        .map(|dir| dir.join(db_path.file_name().unwrap_or_default()))
+        .unwrap_or_else(|_| db_path.to_path_buf());
+    let digest = blake3::hash(absolute.as_os_str().as_encoded_bytes()).to_hex();
+    std::env::temp_dir().join(format!("lidx-{}.sock", &digest[..16]))
+}
+
+struct Job {
+    line: String,
+    reply: Sender<Reply>,
+}
+
+enum Reply {
+    Progress(IndexProgress),
+    Response(String),
+}
+
+/// Removes the socket file when the daemon exits.
+struct SocketGuard(PathBuf);
+
+impl Drop for SocketGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Keeps the live-connection count accurate however a connection ends.
+struct ClientGuard(Arc<AtomicUsize>);
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Run the daemon in the foreground until the idle timeout expires.
+///
+/// Requests from all connections are funnelled to this thread and answered in
+/// arrival order by one `Indexer`, while one watcher keeps the index fresh.
+pub fn serve(repo_root: PathBuf, db_path: PathBuf, config: DaemonConfig) -> Result<()> {
+    let socket = socket_path(&db_path);
+    let listener = bind(&socket)?;
+    let _socket_guard = SocketGuard(socket.clone());
+    let mut indexer = Indexer::new_with_options(
+        repo_root.clone(),
+        db_path.clone(),
+        config.watch.scan_options,
+    )?;
+    let _watcher = watch::start(repo_root, db_path, config.watch)?;
+
+    let (jobs_tx, jobs_rx) = mpsc::channel::<Job>();
+    let clients = Arc::new(AtomicUsize::new(0));
+    {
+        let clients = Arc::clone(&clients);
+        thread::spawn(move || accept_loop(listener, jobs_tx, clients));
+    }
+    eprintln!("lidx: daemon listening on {}", socket.display());
+
+    let poll = config
+        .idle_timeout
+        .map(|timeout| timeout.min(Duration::from_secs(1)));
+    let mut idle_since = Instant::now();
+    loop {
+        let job = match poll {
+            Some(poll) => match jobs_rx.recv_timeout(poll) {
+                Ok(job) => job,
+                Err(RecvTimeoutError::Timeout) => {
+                    if clients.load(Ordering::SeqCst) > 0 {
+                        idle_since = Instant::now();
+                    } else if config
+                        .idle_timeout
+                        .is_some_and(|timeout| idle_since.elapsed() >= timeout)
+                    {
+                        eprintln!("lidx: daemon idle, shutting down");
+                        break;
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            },
+            None => match jobs_rx.recv() {
+                Ok(job) => job,
+                Err(_) => break,
+            },
+        };
+        let wants_progress = serde_json::from_str::<Value>(&job.line)
+            .is_ok_and(|request| request.get("progress") == Some(&Value::Bool(true)));
+        if wants_progress {
+            let reply = job.reply.clone();
+            indexer.set_progress(Some(Box::new(move |progress| {
+                let _ = reply.send(Reply::Progress(progress));
+            })));
+        }
+        let response = rpc::handle_request_line(&mut indexer, &job.line);
+        if wants_progress {
+            indexer.set_progress(None);
+        }
+        let _ = job.reply.send(Reply::Response(response?));
+        idle_since = Instant::now();
+    }
+    Ok(())
+}
+
+fn bind(socket: &Path) -> Result<UnixListener> {
+    if socket.exists() {
+        if UnixStream::connect(socket).is_ok() {
+            bail!("lidx daemon already running at {}", socket.display());
+        }
+        // Left behind by a daemon that did not shut down cleanly.
+        std::fs::remove_file(socket)
+            .with_context(|| format!("remove stale socket {}", socket.display()))?;
+    }
+    util::ensure_parent_dir(socket)?;
+    UnixListener::bind(socket).with_context(|| format!("bind {}", socket.display()))
+}
+
+fn accept_loop(listener: UnixListener, jobs: Sender<Job>, clients: Arc<AtomicUsize>) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                clients.fetch_add(1, Ordering::SeqCst);
+                let guard = ClientGuard(Arc::clone(&clients));
+                let jobs = jobs.clone();
+                thread::spawn(move || {
+                    let _guard = guard;
+                    if let Err(err) = handle_connection(stream, jobs) {
+                        eprintln!("lidx: daemon connection error: {err}");
+                    }
+                });
+            }
+            Err(err) => eprintln!("lidx: daemon accept error: {err}"),
+        }
+    }
+}
+
+fn handle_connection(stream: UnixStream, jobs: Sender<Job>) -> Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (reply_tx, reply_rx) = mpsc::channel();
+        if jobs
+            .send(Job {
+                line,
+                reply: reply_tx,
+            })
+            .is_err()
+        {
+            break;
+        }
+        while let Ok(reply) = reply_rx.recv() {
+            match reply {
+                Reply::Progress(progress) => {
+                    let frame = json!({
+                        "progress": {
+                            "phase": progress.phase,
+                            "done": progress.done,
+                            "total": progress.total,
+                        }
+                    });
+                    writeln!(writer, "{frame}")?;
+                    writer.flush()?;
+                }
+                Reply::Response(response) => {
+                    writeln!(writer, "{response}")?;
+                    writer.flush()?;
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A `{"progress": {...}}` line sent ahead of a response.
+fn progress_frame(line: &str) -> Option<IndexProgress> {
+    let frame: Value = serde_json::from_str(line).ok()?;
+    let progress = frame.get("progress")?;
+    let phase = progress.get("phase")?.as_str()?;
+    Some(IndexProgress {
+        phase: PROGRESS_PHASES.iter().find(|known| **known == phase)?,
+        done: progress.get("done")?.as_u64()? as usize,
+        total: progress.get("total")?.as_u64()? as usize,
+    })
+}
+
+/// A connection to a running daemon.
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl Client {
+    /// Connect to the daemon serving `db_path`, or `None` when none is listening.
+    pub fn connect(db_path: &Path) -> Result<Option<Self>> {
+        let socket = socket_path(db_path);
+        match UnixStream::connect(&socket) {
+            Ok(stream) => Ok(Some(Self {
+                reader: BufReader::new(stream.try_clone()?),
+                writer: stream,
+            })),
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    ErrorKind::NotFound | ErrorKind::ConnectionRefused
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err).with_context(|| format!("connect {}", socket.display())),
+        }
+    }
+
+    /// Connect to the daemon, spawning `lidx daemon` in the background first
+    /// when none is listening.
+    pub fn connect_or_spawn(options: &SpawnOptions) -> Result<Self> {
+        if let Some(client) = Self::connect(&options.db_path)? {
+            return Ok(client);
+        }
+        spawn(options)?;
+        let deadline = Instant::now() + SPAWN_TIMEOUT;
+        loop {
+            if let Some(client) = Self::connect(&options.db_path)? {
+                return Ok(client);
+            }
+            if Instant::now() >= deadline {
+                bail!(
+                    "lidx daemon did not start within {}s (see {})",
+                    SPAWN_TIMEOUT.as_secs(),
+                    log_path(&options.db_path).display()
+                );
+            }
+            thread::sleep(SPAWN_POLL);
+        }
+    }
+
+    /// Send one JSONL request line and return the response line.
+    pub fn request_line(&mut self, line: &str) -> Result<String> {
+        writeln!(self.writer, "{}", line.trim_end())?;
+        self.writer.flush()?;
+        let mut response = String::new();
+        if self.reader.read_line(&mut response)? == 0 {
+            bail!("lidx daemon closed the connection");
+        }
+        Ok(response.trim_end().to_string())
+    }
+
+    /// Like `request_line`, asking the daemon to report reindex progress to
+    /// `progress` while the request runs.
+    pub fn request_line_with_progress(
+        &mut self,
+        line: &str,
+        progress: &mut dyn FnMut(IndexProgress),
+    ) -> Result<String> {
+        let mut request: Value = serde_json::from_str(line).with_context(|| "parse request")?;
+        if let Some(fields) = request.as_object_mut() {
+            fields.insert("progress".to_string(), Value::Bool(true));
+        }
+        writeln!(self.writer, "{request}")?;
+        self.writer.flush()?;
+        loop {
+            let mut response = String::new();
+            if self.reader.read_line(&mut response)? == 0 {
+                bail!("lidx daemon closed the connection");
+            }
+            match progress_frame(&response) {
+                Some(step) => progress(step),
+                None => return Ok(response.trim_end().to_string()),
+            }
+        }
+    }
+
+    /// Call `method` and return its result, mapping RPC errors to `Err`.
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        let line = serde_json::to_string(&serde_json::json!({
+            "id": 1,
+            "method": method,
+            "params": params,
+        }))?;
+        let response = self.request_line(&line)?;
+        rpc::response_result(&response)
+    }
+}
+
+fn log_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("daemon.log")
+}
+
+fn spawn(options: &SpawnOptions) -> Result<()> {
+    let exe = std::env::current_exe().with_context(|| "locate lidx executable")?;
+    let log_path = log_path(&options.db_path);
+    util::ensure_parent_dir(&log_path)?;
+    let log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .with_context(|| format!("open daemon log {}", log_path.display()))?;
+    let watch = options
+        .watch
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_else(|| "auto".to_string());
+    let mut cmd = Command::new(exe);
+    cmd.arg("daemon")
+        .arg("--repo")
+        .arg(&options.repo_root)
+        .arg("--db")
+        .arg(&options.db_path)
+        .arg("--watch")
+        .arg(watch)
+        .arg("--idle-timeout-secs")
+        .arg(AUTO_IDLE_TIMEOUT_SECS.to_string());
+    if options.no_ignore {
+        cmd.arg("--no-ignore");
+    }
+    // Own process group so the daemon outlives the client's terminal session.
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::from(log))
+        .process_group(0);
+    let mut child = cmd.spawn().with_context(|| "spawn lidx daemon")?;
+    // Reap the child if it exits while this client is still running.
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// Run a single `lidx request` through the daemon, spawning it if needed.
+pub fn call(
+    options: &SpawnOptions,
+    method: &str,
+    params_raw: &str,
+    id_raw: &str,
+) -> Result<String> {
+    let line = rpc::request_line(method, params_raw, id_raw)?;
+    let mut client = Client::connect_or_spawn(options)?;
+    client.request_line(&line)
+}
@@ -496,7 +496,7 @@ impl Db {
                      AND NOT EXISTS (
                        SELECT 1 FROM edges e
                        WHERE e.source_symbol_id = s.id
-                         AND e.kind IN ('HTTP_ROUTE', 'RPC_IMPL', 'CHANNEL_SUBSCRIBE')
+                         AND e.kind IN ('HTTP_ROUTE', 'RPC_IMPL', 'GRAPHQL_RESOLVE', 'CHANNEL_SUBSCRIBE')
                          AND e.graph_version = ?
                      )";
 
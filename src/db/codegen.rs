@@ -0,0 +1,149 @@
+use super::{ApiOperation, CodegenSymbol, Db};
+use crate::indexer::http::HTTP_ROUTE_KIND;
+use crate::indexer::openapi::OPENAPI_FRAMEWORK;
+use anyhow::Result;
+use rusqlite::params;
+use serde_json::Value;
+
+impl Db {
+    /// Symbols of `.proto` files, of files whose path mentions `pb` (the
+    /// usual protoc output names), and of files declaring a type that
+    /// implements a protobuf `IMessage` interface.
+    pub fn codegen_symbols(&self, graph_version: i64) -> Result<Vec<CodegenSymbol>> {
+        let conn = self.read_conn()?;
+        let mut stmt = conn.prepare(
+            "WITH message_bases AS (
+                 SELECT e.file_id FROM edges e
+                 WHERE e.kind IN ('EXTENDS', 'IMPLEMENTS', 'INHERITS')
+                   AND e.graph_version = ?
+                   AND e.target_qualname LIKE '%IMessage%')
+             SELECT s.id, s.kind, s.name, s.qualname, s.file_id, f.path,
+                    COALESCE(f.language, 'unknown'), s.start_line,
+                    s.file_id IN (SELECT m.file_id FROM message_bases m)
+             FROM symbols s
+             JOIN files f ON s.file_id = f.id
+             WHERE s.kind NOT IN ('module', 'namespace', 'package')
+               AND s.graph_version = ?
+               AND (f.deleted_version IS NULL OR f.deleted_version > ?)
+               AND (f.language = 'proto'
+                    OR f.path LIKE '%pb%'
+                    OR s.file_id IN (SELECT m.file_id FROM message_bases m))
+             ORDER BY s.id",
+        )?;
+        let rows = stmt.query_map(
+            params![graph_version, graph_version, graph_version],
+            |row| {
+                Ok(CodegenSymbol {
+                    id: row.get(0)?,
+                    kind: row.get(1)?,
+                    name: row.get(2)?,
+                    qualname: row.get(3)?,
+                    file_id: row.get(4)?,
+                    path: row.get(5)?,
+                    language: row.get(6)?,
+                    start_line: row.get(7)?,
+                    in_message_file: row.get(8)?,
+                })
+            },
+        )?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+impl Db {
+    /// Operations declared in OpenAPI / Swagger documents.
+    pub fn api_operations(&self, graph_version: i64) -> Result<Vec<ApiOperation>> {
+        let conn = self.read_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT e.source_symbol_id, s.qualname, e.detail
+             FROM edges e
+             JOIN symbols s ON s.id = e.source_symbol_id
+             JOIN files f ON e.file_id = f.id
+             WHERE e.kind = ?
+               AND e.graph_version = ?
+               AND e.detail LIKE ?
+               AND (f.deleted_version IS NULL OR f.deleted_version > ?)
+             ORDER BY e.id",
+        )?;
+        let framework = format!("%\"framework\":\"{OPENAPI_FRAMEWORK}\"%");
+        let rows = stmt.query_map(
+            params![HTTP_ROUTE_KIND, graph_version, framework, graph_version],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )?;
+        let mut operations = Vec::new();
+        for row in rows {
+            let (symbol_id, qualname, detail) = row?;
+            let Ok(detail) = serde_json::from_str::<Value>(&detail) else {
+                continue;
+            };
+            let field = |key: &str| detail.get(key).and_then(Value::as_str).map(str::to_string);
+            let (Some(method), Some(path), Some(raw_path)) =
+                (field("method"), field("path"), field("raw"))
+            else {
+                continue;
+            };
+            operations.push(ApiOperation {
+                symbol_id,
+                qualname,
+                operation_id: field("operation_id"),
+                method,
+                path,
+                raw_path,
+            });
+        }
+        Ok(operations)
+    }
+
+    /// Methods and functions whose name, lowercased and without
+    /// underscores, is one of `names`.
+    pub fn callables_named(
+        &self,
+        names: &[String],
+        graph_version: i64,
+    ) -> Result<Vec<CodegenSymbol>> {
+        let conn = self.read_conn()?;
+        let mut symbols = Vec::new();
+        for chunk in names.chunks(500) {
+            let placeholders = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let sql = format!(
+                "SELECT s.id, s.kind, s.name, s.qualname, s.file_id, f.path,
+                        COALESCE(f.language, 'unknown'), s.start_line
+                 FROM symbols s
+                 JOIN files f ON s.file_id = f.id
+                 WHERE s.kind IN ('method', 'function')
+                   AND s.graph_version = ?
+                   AND (f.deleted_version IS NULL OR f.deleted_version > ?)
+                   AND replace(lower(s.name), '_', '') IN ({placeholders})
+                 ORDER BY s.id"
+            );
+            let mut params: Vec<&dyn rusqlite::ToSql> = vec![&graph_version, &graph_version];
+            for name in chunk {
+                params.push(name as &dyn rusqlite::ToSql);
+            }
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(&*params, |row| {
+                Ok(CodegenSymbol {
+                    id: row.get(0)?,
+                    kind: row.get(1)?,
+                    name: row.get(2)?,
+                    qualname: row.get(3)?,
+                    file_id: row.get(4)?,
+                    path: row.get(5)?,
+                    language: row.get(6)?,
+                    start_line: row.get(7)?,
+                    in_message_file: false,
+                })
+            })?;
+            for row in rows {
+                symbols.push(row?);
+            }
+        }
+        Ok(symbols)
+    }
+}
@@ -0,0 +1,185 @@
+use super::{Db, append_path_filters, symbol_from_row};
+use crate::model::{Symbol, VersionEdge};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Lines from a symbol's start line that can hold its modifiers: attributes
+/// and decorators come before the name.
+const DECLARATION_LINES: i64 = 5;
+
+/// `AND f.language IN (...)` for a non-empty language filter.
+fn append_language_filter<'a>(
+    sql: &mut String,
+    params: &mut Vec<&'a dyn rusqlite::ToSql>,
+    languages: Option<&'a [String]>,
+) {
+    let Some(languages) = languages.filter(|languages| !languages.is_empty()) else {
+        return;
+    };
+    sql.push_str(" AND f.language IN (");
+    sql.push_str(&vec!["?"; languages.len()].join(","));
+    sql.push(')');
+    for language in languages {
+        params.push(language as &dyn rusqlite::ToSql);
+    }
+}
+
+impl Db {
+    // Whole-version snapshots for `compare_versions` and `api_changes`.
+
+    /// Every symbol of a graph version, ordered by path and position.
+    pub fn version_symbols(
+        &self,
+        graph_version: i64,
+        languages: Option<&[String]>,
+        paths: Option<&[String]>,
+    ) -> Result<Vec<Symbol>> {
+        let conn = self.read_conn()?;
+        let mut sql = String::from(
+            "SELECT s.id, f.path, s.kind, s.name, s.qualname, s.start_line, s.start_col,
+                    s.end_line, s.end_col, s.start_byte, s.end_byte, s.signature, s.docstring,
+                    s.graph_version, s.commit_sha, s.stable_id
+             FROM symbols s
+             JOIN files f ON f.id = s.file_id
+             WHERE s.graph_version = ?",
+        );
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&graph_version];
+        append_language_filter(&mut sql, &mut params, languages);
+        let mut path_params = Vec::new();
+        append_path_filters(&mut sql, &mut params, &mut path_params, paths, "f");
+        sql.push_str(" ORDER BY f.path, s.start_line, s.id");
+
+        let mut stmt = conn.prepare(&sql)?;
+        let symbols = stmt
+            .query_map(&*params, symbol_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(symbols)
+    }
+
+    /// Every edge of a graph version except CONTAINS, which only mirrors the
+    /// symbol tree. The language and path filters apply to the edge's file.
+    pub fn version_edges(
+        &self,
+        graph_version: i64,
+        languages: Option<&[String]>,
+        paths: Option<&[String]>,
+    ) -> Result<Vec<VersionEdge>> {
+        self.version_edges_where("e.kind != 'CONTAINS'", graph_version, languages, paths)
+    }
+
+    /// IMPORTS and IMPORTS_FILE edges of a graph version, for
+    /// `affected_tests`.
+    pub fn version_imports(&self, graph_version: i64) -> Result<Vec<VersionEdge>> {
+        self.version_edges_where(
+            "e.kind IN ('IMPORTS', 'IMPORTS_FILE')",
+            graph_version,
+            None,
+            None,
+        )
+    }
+
+    fn version_edges_where(
+        &self,
+        condition: &str,
+        graph_version: i64,
+        languages: Option<&[String]>,
+        paths: Option<&[String]>,
+    ) -> Result<Vec<VersionEdge>> {
+        let conn = self.read_conn()?;
+        let mut sql = format!(
+            "SELECT e.kind, f.path, src.qualname, COALESCE(tgt.qualname, e.target_qualname),
+                    tf.path, e.detail
+             FROM edges e
+             JOIN files f ON f.id = e.file_id
+             LEFT JOIN symbols src ON src.id = e.source_symbol_id
+             LEFT JOIN symbols tgt ON tgt.id = e.target_symbol_id
+             LEFT JOIN files tf ON tf.id = tgt.file_id
+             WHERE e.graph_version = ? AND {condition}"
+        );
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&graph_version];
+        append_language_filter(&mut sql, &mut params, languages);
+        let mut path_params = Vec::new();
+        append_path_filters(&mut sql, &mut params, &mut path_params, paths, "f");
+        sql.push_str(" ORDER BY e.id");
+
+        let mut stmt = conn.prepare(&sql)?;
+        let edges = stmt
+            .query_map(&*params, |row| {
+                Ok(VersionEdge {
+                    kind: row.get(0)?,
+                    file_path: row.get(1)?,
+                    source_qualname: row.get(2)?,
+                    target_qualname: row.get(3)?,
+                    target_path: row.get(4)?,
+                    detail: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(edges)
+    }
+
+    // Declaration text for `api_changes`, which reads visibility modifiers
+    // from the indexed lines.
+
+    /// The first lines of every symbol of a graph version except modules
+    /// and namespaces, joined by newlines and keyed by symbol id.
+    pub fn declaration_heads(
+        &self,
+        graph_version: i64,
+        languages: Option<&[String]>,
+        paths: Option<&[String]>,
+    ) -> Result<HashMap<i64, String>> {
+        let conn = self.read_conn()?;
+        let mut sql = String::from(
+            "SELECT s.id, c.text
+             FROM symbols s
+             JOIN files f ON f.id = s.file_id
+             JOIN content_lines c ON c.file_id = s.file_id
+                AND c.graph_version = s.graph_version
+                AND c.line BETWEEN s.start_line AND s.start_line + ?
+             WHERE s.graph_version = ? AND s.kind NOT IN ('module', 'namespace')",
+        );
+        let extra_lines = DECLARATION_LINES - 1;
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&extra_lines, &graph_version];
+        append_language_filter(&mut sql, &mut params, languages);
+        let mut path_params = Vec::new();
+        append_path_filters(&mut sql, &mut params, &mut path_params, paths, "f");
+        sql.push_str(" ORDER BY s.id, c.line");
+
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows = stmt.query(&*params)?;
+        let mut heads: HashMap<i64, String> = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let head = heads.entry(row.get(0)?).or_default();
+            if !head.is_empty() {
+                head.push('\n');
+            }
+            head.push_str(row.get_ref(1)?.as_str()?);
+        }
+        Ok(heads)
+    }
+
+    /// Every line of the Python files of a graph version that mention
+    /// `__all__`, by path.
+    pub fn python_export_lines(&self, graph_version: i64) -> Result<HashMap<String, Vec<String>>> {
+        let conn = self.read_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT f.path, c.text
+             FROM content_lines c
+             JOIN files f ON f.id = c.file_id
+             WHERE c.graph_version = ?1
+               AND f.language = 'python'
+               AND c.file_id IN (
+                   SELECT file_id FROM content_lines
+                   WHERE graph_version = ?1 AND instr(text, '__all__') > 0
+               )
+             ORDER BY f.path, c.line",
+        )?;
+        let mut rows = stmt.query([graph_version])?;
+        let mut lines: HashMap<String, Vec<String>> = HashMap::new();
+        while let Some(row) = rows.next()? {
+            lines.entry(row.get(0)?).or_default().push(row.get(1)?);
+        }
+        Ok(lines)
+    }
+}
@@ -0,0 +1,169 @@
+use super::{ContentMatch, Db, SymbolTextMatch};
+use anyhow::Result;
+use rusqlite::params;
+
+impl Db {
+    // Full-text index methods (content_lines/content_fts and symbol_fts)
+
+    /// Replace the indexed content lines of a file for `graph_version`.
+    pub fn replace_file_content(
+        &self,
+        file_id: i64,
+        source: &str,
+        graph_version: i64,
+    ) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM content_lines WHERE file_id = ? AND graph_version = ?",
+            params![file_id, graph_version],
+        )?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO content_lines (file_id, graph_version, line, text, tokens)
+                 VALUES (?, ?, ?, ?, ?)",
+            )?;
+            for (idx, line) in source.lines().enumerate() {
+                let text = line.trim_end();
+                if text.trim_start().is_empty() {
+                    continue;
+                }
+                let tokens = crate::search::index_tokens(text);
+                stmt.execute(params![
+                    file_id,
+                    graph_version,
+                    (idx + 1) as i64,
+                    text,
+                    tokens
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn delete_file_content(&self, file_id: i64, graph_version: i64) -> Result<()> {
+        self.conn().execute(
+            "DELETE FROM content_lines WHERE file_id = ? AND graph_version = ?",
+            params![file_id, graph_version],
+        )?;
+        Ok(())
+    }
+
+    /// The indexed text of `path` at `graph_version`, with blank lines
+    /// restored and trailing whitespace trimmed; `None` if not indexed.
+    pub fn file_content(&self, path: &str, graph_version: i64) -> Result<Option<String>> {
+        let conn = self.read_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT c.line, c.text
+             FROM content_lines c
+             JOIN files f ON f.id = c.file_id
+             WHERE f.path = ? AND c.graph_version = ?
+             ORDER BY c.line",
+        )?;
+        let rows = stmt.query_map(params![path, graph_version], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut content = None::<String>;
+        let mut next_line = 1;
+        for row in rows {
+            let (line, text) = row?;
+            let content = content.get_or_insert_with(String::new);
+            while next_line < line {
+                content.push('\n');
+                next_line += 1;
+            }
+            content.push_str(&text);
+            content.push('\n');
+            next_line += 1;
+        }
+        Ok(content)
+    }
+
+    /// Whether any file content has been indexed for `graph_version`.
+    pub fn has_content_index(&self, graph_version: i64) -> Result<bool> {
+        let exists: bool = self.read_conn()?.query_row(
+            "SELECT EXISTS(SELECT 1 FROM content_lines WHERE graph_version = ?)",
+            params![graph_version],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// Run an FTS5 MATCH expression against indexed lines, best BM25 rank first.
+    pub fn search_content(
+        &self,
+        match_expr: &str,
+        graph_version: i64,
+        limit: usize,
+    ) -> Result<Vec<ContentMatch>> {
+        let conn = self.read_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT f.path, f.language, c.line, c.text, -bm25(content_fts, 1.0, 0.6) AS score
+             FROM content_fts
+             JOIN content_lines c ON c.id = content_fts.rowid
+             JOIN files f ON f.id = c.file_id
+             WHERE content_fts MATCH ?1
+               AND c.graph_version = ?2
+               AND (f.deleted_version IS NULL OR f.deleted_version > ?2)
+             ORDER BY score DESC, f.path, c.line
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![match_expr, graph_version, limit as i64], |row| {
+            Ok(ContentMatch {
+                path: row.get(0)?,
+                language: row.get(1)?,
+                line: row.get(2)?,
+                text: row.get(3)?,
+                score: row.get(4)?,
+            })
+        })?;
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row?);
+        }
+        Ok(out)
+    }
+
+    /// Run an FTS5 MATCH expression against symbol text, best BM25 rank first.
+    pub fn search_symbol_text(
+        &self,
+        match_expr: &str,
+        graph_version: i64,
+        limit: usize,
+    ) -> Result<Vec<SymbolTextMatch>> {
+        let conn = self.read_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT f.path, f.language, s.start_line, s.start_col, s.qualname, c.text,
+                    -bm25(symbol_fts, 4.0, 2.0, 1.0, 0.5) AS score
+             FROM symbol_fts
+             JOIN symbols s ON s.id = symbol_fts.rowid
+             JOIN files f ON f.id = s.file_id
+             LEFT JOIN content_lines c
+               ON c.file_id = s.file_id AND c.graph_version = s.graph_version
+              AND c.line = s.start_line
+             WHERE symbol_fts MATCH ?1
+               AND s.graph_version = ?2
+               AND s.kind != 'module'
+               AND (f.deleted_version IS NULL OR f.deleted_version > ?2)
+             ORDER BY score DESC, f.path, s.start_line
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![match_expr, graph_version, limit as i64], |row| {
+            Ok(SymbolTextMatch {
+                path: row.get(0)?,
+                language: row.get(1)?,
+                line: row.get(2)?,
+                column: row.get(3)?,
+                qualname: row.get(4)?,
+                text: row.get(5)?,
+                score: row.get(6)?,
+            })
+        })?;
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row?);
+        }
+        Ok(out)
+    }
+}
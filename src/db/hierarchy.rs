@@ -0,0 +1,73 @@
+use super::{Db, DispatchEdge, HierarchySymbol, InheritanceEdge};
+use anyhow::Result;
+use rusqlite::params;
+
+impl Db {
+    /// Live type and member symbols: everything that can declare or override
+    /// a method.
+    pub fn hierarchy_symbols(&self, graph_version: i64) -> Result<Vec<HierarchySymbol>> {
+        let conn = self.read_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT s.id, s.kind, s.name, s.qualname, s.file_id, s.start_line,
+                    COALESCE(f.language, 'unknown')
+             FROM symbols s
+             JOIN files f ON s.file_id = f.id
+             WHERE s.kind IN ('class', 'interface', 'struct', 'trait', 'record', 'enum', 'type',
+                              'method', 'property')
+               AND s.graph_version = ?
+               AND (f.deleted_version IS NULL OR f.deleted_version > ?)",
+        )?;
+        let rows = stmt.query_map(params![graph_version, graph_version], |row| {
+            Ok(HierarchySymbol {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                name: row.get(2)?,
+                qualname: row.get(3)?,
+                file_id: row.get(4)?,
+                start_line: row.get(5)?,
+                language: row.get(6)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Type-level inheritance edges of live files.
+    pub fn inheritance_edges(&self, graph_version: i64) -> Result<Vec<InheritanceEdge>> {
+        let conn = self.read_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT e.source_symbol_id, e.target_symbol_id, e.target_qualname
+             FROM edges e
+             JOIN files f ON e.file_id = f.id
+             WHERE e.kind IN ('EXTENDS', 'IMPLEMENTS', 'INHERITS')
+               AND e.source_symbol_id IS NOT NULL
+               AND e.graph_version = ?
+               AND (f.deleted_version IS NULL OR f.deleted_version > ?)",
+        )?;
+        let rows = stmt.query_map(params![graph_version, graph_version], |row| {
+            Ok(InheritanceEdge {
+                source_id: row.get(0)?,
+                target_id: row.get(1)?,
+                target_qualname: row.get(2)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// OVERRIDES / IMPLEMENTS_MEMBER edges.
+    pub fn dispatch_edges(&self, graph_version: i64) -> Result<Vec<DispatchEdge>> {
+        let conn = self.read_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, source_symbol_id, target_symbol_id
+             FROM edges
+             WHERE kind IN ('OVERRIDES', 'IMPLEMENTS_MEMBER') AND graph_version = ?",
+        )?;
+        let rows = stmt.query_map(params![graph_version], |row| {
+            Ok(DispatchEdge {
+                id: row.get(0)?,
+                member_id: row.get(1)?,
+                base_id: row.get(2)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use rusqlite::{Connection, OptionalExtension, params};
 
-pub const SCHEMA_VERSION: i64 = 12;
+pub const SCHEMA_VERSION: i64 = 14;
 
 pub fn migrate(conn: &Connection) -> Result<()> {
     conn.execute_batch(
@@ -322,6 +322,89 @@ pub fn migrate(conn: &Connection) -> Result<()> {
         conn.execute("DROP TABLE IF EXISTS diagnostics", [])?;
     }
 
+    if existing < 13 {
+        // Full-text search: tokenized file lines plus an external-content index
+        // over symbol names/qualnames/docstrings kept in sync by triggers.
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS content_lines (
+                id INTEGER PRIMARY KEY,
+                file_id INTEGER NOT NULL,
+                graph_version INTEGER NOT NULL,
+                line INTEGER NOT NULL,
+                text TEXT NOT NULL,
+                tokens TEXT NOT NULL,
+                FOREIGN KEY(file_id) REFERENCES files(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_content_lines_file
+                ON content_lines(file_id, graph_version, line);
+            CREATE INDEX IF NOT EXISTS idx_content_lines_version
+                ON content_lines(graph_version);
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS content_fts USING fts5(
+                text, tokens,
+                content='content_lines', content_rowid='id'
+            );
+            CREATE TRIGGER IF NOT EXISTS content_lines_ai AFTER INSERT ON content_lines BEGIN
+                INSERT INTO content_fts(rowid, text, tokens)
+                VALUES (new.id, new.text, new.tokens);
+            END;
+            CREATE TRIGGER IF NOT EXISTS content_lines_ad AFTER DELETE ON content_lines BEGIN
+                INSERT INTO content_fts(content_fts, rowid, text, tokens)
+                VALUES ('delete', old.id, old.text, old.tokens);
+            END;
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS symbol_fts USING fts5(
+                name, qualname, signature, docstring,
+                content='symbols', content_rowid='id'
+            );
+            CREATE TRIGGER IF NOT EXISTS symbols_fts_ai AFTER INSERT ON symbols BEGIN
+                INSERT INTO symbol_fts(rowid, name, qualname, signature, docstring)
+                VALUES (new.id, new.name, new.qualname, new.signature, new.docstring);
+            END;
+            CREATE TRIGGER IF NOT EXISTS symbols_fts_ad AFTER DELETE ON symbols BEGIN
+                INSERT INTO symbol_fts(symbol_fts, rowid, name, qualname, signature, docstring)
+                VALUES ('delete', old.id, old.name, old.qualname, old.signature, old.docstring);
+            END;
+            CREATE TRIGGER IF NOT EXISTS symbols_fts_au
+            AFTER UPDATE OF name, qualname, signature, docstring ON symbols BEGIN
+                INSERT INTO symbol_fts(symbol_fts, rowid, name, qualname, signature, docstring)
+                VALUES ('delete', old.id, old.name, old.qualname, old.signature, old.docstring);
+                INSERT INTO symbol_fts(rowid, name, qualname, signature, docstring)
+                VALUES (new.id, new.name, new.qualname, new.signature, new.docstring);
+            END;
+            INSERT INTO symbol_fts(symbol_fts) VALUES ('rebuild');
+            ",
+        )?;
+    }
+
+    if existing < 14 {
+        // Git revisions indexed from the object database: the ref each
+        // version was requested as, and the blob of every file in its tree.
+        if !has_column(conn, "graph_versions", "revision")? {
+            conn.execute("ALTER TABLE graph_versions ADD COLUMN revision TEXT", [])?;
+        }
+        if !has_column(conn, "files", "revision_only")? {
+            conn.execute(
+                "ALTER TABLE files ADD COLUMN revision_only INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS revision_files (
+                graph_version INTEGER NOT NULL,
+                file_id INTEGER NOT NULL,
+                blob TEXT NOT NULL,
+                PRIMARY KEY(graph_version, file_id),
+                FOREIGN KEY(file_id) REFERENCES files(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_revision_files_blob
+                ON revision_files(file_id, blob);
+            ",
+        )?;
+    }
+
     if existing < SCHEMA_VERSION {
         conn.execute(
             "INSERT INTO meta (key, value) VALUES ('schema_version', ?)
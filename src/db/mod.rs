@@ -15,9 +15,20 @@ use std::time::Duration;
 
 mod analytics;
 mod co_change;
+mod codegen;
+mod compare;
+mod fts;
 mod graph_query;
+mod hierarchy;
 mod migrations;
 mod overview;
+mod revisions;
+mod sql_schema;
+mod table_refs;
+mod typed_calls;
+
+pub(crate) use overview::module_prefix;
+pub(crate) use typed_calls::common_prefix_segments;
 
 #[derive(Debug, Clone)]
 pub struct ModuleSummaryEntry {
@@ -65,6 +76,88 @@ pub struct SymbolRefRecord {
     pub language: String,
 }
 
+/// A type or member symbol, as loaded for inheritance linking.
+#[derive(Debug, Clone)]
+pub struct HierarchySymbol {
+    pub id: i64,
+    pub kind: String,
+    pub name: String,
+    pub qualname: String,
+    pub file_id: i64,
+    pub start_line: i64,
+    pub language: String,
+}
+
+/// A symbol of a `.proto` file or of code that may be generated from one or
+/// from an API spec, as loaded for generated-code linking.
+#[derive(Debug, Clone)]
+pub struct CodegenSymbol {
+    pub id: i64,
+    pub kind: String,
+    pub name: String,
+    pub qualname: String,
+    pub file_id: i64,
+    pub path: String,
+    pub language: String,
+    pub start_line: i64,
+    /// Declared in a file with a type that has a protobuf `IMessage` base
+    /// (C# generated code).
+    pub in_message_file: bool,
+}
+
+/// An operation of an OpenAPI / Swagger document, from its HTTP_ROUTE edge.
+#[derive(Debug, Clone)]
+pub struct ApiOperation {
+    pub symbol_id: i64,
+    pub qualname: String,
+    pub operation_id: Option<String>,
+    pub method: String,
+    /// Normalized route key, as in HTTP_ROUTE / HTTP_CALL targets.
+    pub path: String,
+    pub raw_path: String,
+}
+
+/// A type-level EXTENDS / IMPLEMENTS / INHERITS edge.
+#[derive(Debug, Clone)]
+pub struct InheritanceEdge {
+    pub source_id: i64,
+    pub target_id: Option<i64>,
+    pub target_qualname: Option<String>,
+}
+
+/// A member-level OVERRIDES / IMPLEMENTS_MEMBER edge.
+#[derive(Debug, Clone)]
+pub struct DispatchEdge {
+    pub id: i64,
+    pub member_id: Option<i64>,
+    pub base_id: Option<i64>,
+}
+
+/// A line of indexed file content matched by an FTS query.
+#[derive(Debug, Clone)]
+pub struct ContentMatch {
+    pub path: String,
+    pub language: String,
+    pub line: i64,
+    pub text: String,
+    /// Negated BM25 rank: higher is better.
+    pub score: f64,
+}
+
+/// A symbol whose name, qualname, signature or docstring matched an FTS query.
+#[derive(Debug, Clone)]
+pub struct SymbolTextMatch {
+    pub path: String,
+    pub language: String,
+    pub line: i64,
+    pub column: i64,
+    pub qualname: String,
+    /// Source line at the symbol start when the file content is indexed.
+    pub text: Option<String>,
+    /// Negated BM25 rank: higher is better.
+    pub score: f64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TableDigest {
     pub rows: usize,
@@ -153,7 +246,11 @@ impl Db {
         let mut stmt = conn.prepare(
             "SELECT id, path, hash, language, deleted_version
              FROM files
-             WHERE deleted_version IS NULL OR deleted_version > ?
+             WHERE CASE
+                WHEN EXISTS (SELECT 1 FROM revision_files WHERE graph_version = ?1)
+                THEN id IN (SELECT file_id FROM revision_files WHERE graph_version = ?1)
+                ELSE (deleted_version IS NULL OR deleted_version > ?1) AND revision_only = 0
+             END
              ORDER BY path",
         )?;
         let rows = stmt.query_map(params![graph_version], |row| {
@@ -209,7 +306,8 @@ impl Db {
                 language = excluded.language,
                 size = excluded.size,
                 modified = excluded.modified,
-                deleted_version = NULL",
+                deleted_version = NULL,
+                revision_only = 0",
             params![path, hash, language, size, modified],
         )?;
         let id: i64 = conn.query_row(
@@ -227,6 +325,21 @@ impl Db {
     }
 
     pub fn mark_file_deleted(&self, path: &str, graph_version: i64) -> Result<()> {
+        // A file that an indexed revision newer than `graph_version` still
+        // contains is only hidden from the working tree; a deleted_version
+        // would hide it from that revision too.
+        let in_later_revision = self.conn().execute(
+            "UPDATE files SET revision_only = 1
+             WHERE path = ?
+               AND EXISTS (
+                 SELECT 1 FROM revision_files r
+                 WHERE r.file_id = files.id AND r.graph_version > ?
+               )",
+            params![path, graph_version],
+        )?;
+        if in_later_revision > 0 {
+            return Ok(());
+        }
         self.conn().execute(
             "UPDATE files
              SET deleted_version = CASE
@@ -247,6 +360,44 @@ impl Db {
         Ok(())
     }
 
+    pub fn delete_edges_by_ids(&self, ids: &[i64]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        {
+            let mut delete = tx.prepare("DELETE FROM edges WHERE id = ?")?;
+            for id in ids {
+                delete.execute(params![id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Delete the `kind` edges whose JSON detail has `key` set to `value`,
+    /// for derived edges sharing a kind with extracted ones.
+    pub fn delete_edges_by_detail(
+        &self,
+        kind: &str,
+        key: &str,
+        value: &str,
+        graph_version: i64,
+    ) -> Result<()> {
+        let pattern = format!(
+            "%{}%",
+            serde_json::json!({ key: value })
+                .to_string()
+                .trim_matches(['{', '}'])
+        );
+        self.conn().execute(
+            "DELETE FROM edges WHERE kind = ? AND graph_version = ? AND detail LIKE ?",
+            params![kind, graph_version, pattern],
+        )?;
+        Ok(())
+    }
+
     /// Delete edges for a file (helper for incremental updates)
     pub fn delete_edges_for_file(&self, file_id: i64, graph_version: i64) -> Result<()> {
         self.conn().execute(
@@ -256,7 +407,7 @@ impl Db {
         Ok(())
     }
 
-    /// Delete all symbols, edges, and metrics for a file (legacy method)
+    /// Delete all symbols, edges, metrics, and indexed content for a file (legacy method)
     ///
     /// Note: This is the old approach. For incremental updates, prefer:
     /// - `update_file_symbols()` for symbols (Phase 3)
@@ -290,6 +441,7 @@ impl Db {
             "DELETE FROM file_metrics WHERE file_id = ?",
             params![file_id],
         )?;
+        self.delete_file_content(file_id, graph_version)?;
         Ok(())
     }
 
@@ -864,8 +1016,9 @@ impl Db {
                   evidence_start_line, evidence_end_line, confidence, graph_version, commit_sha, trace_id, span_id, event_ts)
                  VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             )?;
-            let mut exact_lookup_stmt =
-                tx.prepare("SELECT id FROM symbols WHERE qualname = ? LIMIT 1")?;
+            let mut exact_lookup_stmt = tx.prepare(
+                "SELECT id FROM symbols WHERE qualname = ? AND graph_version = ? LIMIT 1",
+            )?;
             // Same-language fuzzy lookup: prefer symbols from files matching source language
             let mut fuzzy_same_lang_stmt = tx.prepare(
                 "SELECT s.id
@@ -901,57 +1054,65 @@ impl Db {
                 .unwrap_or_else(|_| "unknown".to_string());
 
             for edge in edges {
-                let source_id =
-                    resolve_symbol_id(&edge.source_qualname, symbol_map, &mut exact_lookup_stmt)?;
-                let target_id =
-                    resolve_symbol_id(&edge.target_qualname, symbol_map, &mut exact_lookup_stmt)?
-                        .or_else(|| {
-                            // Fuzzy fallback: try same-language first, then cross-language for bridge edges only
-                            edge.target_qualname.as_ref().and_then(|qn| {
-                                let (method_name, dot_pattern, colons_pattern) =
-                                    fuzzy_qualname_patterns(qn);
-                                // Try same-language first
-                                let same_lang = fuzzy_same_lang_stmt
-                                    .query_row(
-                                        params![
-                                            method_name,
-                                            &dot_pattern,
-                                            &colons_pattern,
-                                            graph_version,
-                                            graph_version,
-                                            &source_lang,
-                                            method_name
-                                        ],
-                                        |row| row.get(0),
-                                    )
-                                    .optional()
-                                    .ok()
-                                    .flatten();
-                                if same_lang.is_some() {
-                                    return same_lang;
-                                }
-                                // Cross-language fallback only for bridge edge kinds
-                                if is_bridge_edge_kind(&edge.kind) {
-                                    fuzzy_any_lang_stmt
-                                        .query_row(
-                                            params![
-                                                method_name,
-                                                &dot_pattern,
-                                                &colons_pattern,
-                                                graph_version,
-                                                graph_version,
-                                                method_name
-                                            ],
-                                            |row| row.get(0),
-                                        )
-                                        .optional()
-                                        .ok()
-                                        .flatten()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
+                let source_id = resolve_symbol_id(
+                    &edge.source_qualname,
+                    symbol_map,
+                    &mut exact_lookup_stmt,
+                    graph_version,
+                )?;
+                let target_id = resolve_symbol_id(
+                    &edge.target_qualname,
+                    symbol_map,
+                    &mut exact_lookup_stmt,
+                    graph_version,
+                )?
+                .or_else(|| {
+                    // Fuzzy fallback: try same-language first, then cross-language for bridge edges only
+                    edge.target_qualname.as_ref().and_then(|qn| {
+                        let (method_name, dot_pattern, colons_pattern) =
+                            fuzzy_qualname_patterns(qn);
+                        // Try same-language first
+                        let same_lang = fuzzy_same_lang_stmt
+                            .query_row(
+                                params![
+                                    method_name,
+                                    &dot_pattern,
+                                    &colons_pattern,
+                                    graph_version,
+                                    graph_version,
+                                    &source_lang,
+                                    method_name
+                                ],
+                                |row| row.get(0),
+                            )
+                            .optional()
+                            .ok()
+                            .flatten();
+                        if same_lang.is_some() {
+                            return same_lang;
+                        }
+                        // Cross-language fallback only for bridge edge kinds
+                        if is_bridge_edge_kind(&edge.kind) {
+                            fuzzy_any_lang_stmt
+                                .query_row(
+                                    params![
+                                        method_name,
+                                        &dot_pattern,
+                                        &colons_pattern,
+                                        graph_version,
+                                        graph_version,
+                                        method_name
+                                    ],
+                                    |row| row.get(0),
+                                )
+                                .optional()
+                                .ok()
+                                .flatten()
+                        } else {
+                            None
+                        }
+                    })
+                });
 
                 insert_stmt.execute(params![
                     file_id,
@@ -1479,6 +1640,18 @@ impl Db {
         Ok(value.flatten())
     }
 
+    /// Working-tree version indexed before `graph_version`, skipping the
+    /// versions of indexed git revisions.
+    pub fn previous_graph_version(&self, graph_version: i64) -> Result<Option<i64>> {
+        self.read_conn()?
+            .query_row(
+                "SELECT MAX(id) FROM graph_versions WHERE id < ? AND revision IS NULL",
+                params![graph_version],
+                |row| row.get(0),
+            )
+            .map_err(Into::into)
+    }
+
     pub fn create_graph_version(&self, commit_sha: Option<&str>) -> Result<i64> {
         let created = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1496,7 +1669,7 @@ impl Db {
     pub fn list_graph_versions(&self, limit: usize, offset: usize) -> Result<Vec<GraphVersion>> {
         let conn = self.read_conn()?;
         let mut stmt = conn.prepare(
-            "SELECT id, created, commit_sha
+            "SELECT id, created, commit_sha, revision
              FROM graph_versions
              ORDER BY id DESC
              LIMIT ? OFFSET ?",
@@ -1508,6 +1681,7 @@ impl Db {
                 id: row.get(0)?,
                 created: row.get(1)?,
                 commit_sha: row.get(2)?,
+                revision: row.get(3)?,
             })
         })?;
         let mut versions = Vec::new();
@@ -1732,6 +1906,7 @@ fn resolve_symbol_id(
     qualname: &Option<String>,
     symbol_map: &HashMap<String, i64>,
     stmt: &mut rusqlite::Statement<'_>,
+    graph_version: i64,
 ) -> Result<Option<i64>> {
     let name = match qualname.as_ref() {
         Some(name) => name,
@@ -1740,7 +1915,9 @@ fn resolve_symbol_id(
     if let Some(id) = symbol_map.get(name) {
         return Ok(Some(*id));
     }
-    let id = stmt.query_row(params![name], |row| row.get(0)).optional()?;
+    let id = stmt
+        .query_row(params![name, graph_version], |row| row.get(0))
+        .optional()?;
     Ok(id)
 }
 
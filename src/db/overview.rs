@@ -403,7 +403,7 @@ impl Db {
 /// Paths deeper than `depth` are truncated (e.g. `"a/b/c.rs"` at depth 1
 /// becomes `"a/"`). Paths at or below `depth` use their parent directory,
 /// and root-level files map to `"."`.
-fn module_prefix(path: &str, depth: usize) -> String {
+pub(crate) fn module_prefix(path: &str, depth: usize) -> String {
     let parts: Vec<&str> = path.split('/').collect();
     if parts.len() > depth {
         parts[..depth].join("/") + "/"
@@ -0,0 +1,247 @@
+use super::Db;
+use anyhow::Result;
+use rusqlite::{OptionalExtension, params};
+
+/// Id, in graph version `?1`, of the symbol `column` refers to in an older
+/// version: same file and stable id.
+fn mapped_symbol(column: &str) -> String {
+    format!(
+        "(SELECT n.id FROM symbols o
+          JOIN symbols n ON n.file_id = o.file_id AND n.stable_id = o.stable_id
+          WHERE o.id = {column} AND n.graph_version = ?1
+          LIMIT 1)"
+    )
+}
+
+impl Db {
+    // Git revisions indexed from the object database. A revision gets its own
+    // graph version, which is never made current, and `revision_files`
+    // records the blob of each of its files.
+
+    /// Graph version for a revision; unlike `create_graph_version` it leaves
+    /// the current (working tree) version alone.
+    pub fn create_revision_version(&self, commit_sha: &str, revision: &str) -> Result<i64> {
+        let created = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO graph_versions (created, commit_sha, revision) VALUES (?, ?, ?)",
+            params![created, commit_sha, revision],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Drop revision version `graph_version` and everything written for it,
+    /// so that a revision whose indexing failed is not taken for an indexed
+    /// one. File rows that only this version used go too.
+    pub fn delete_revision_version(&self, graph_version: i64) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        for table in ["edges", "symbols", "content_lines", "revision_files"] {
+            tx.execute(
+                &format!("DELETE FROM {table} WHERE graph_version = ?"),
+                params![graph_version],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM files
+             WHERE revision_only = 1
+               AND NOT EXISTS (SELECT 1 FROM revision_files r WHERE r.file_id = files.id)
+               AND NOT EXISTS (SELECT 1 FROM symbols s WHERE s.file_id = files.id)",
+            [],
+        )?;
+        tx.execute(
+            "DELETE FROM graph_versions WHERE id = ? AND revision IS NOT NULL",
+            params![graph_version],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Number of edges stored for `graph_version`.
+    pub fn count_version_edges(&self, graph_version: i64) -> Result<usize> {
+        let count: i64 = self.read_conn()?.query_row(
+            "SELECT COUNT(*) FROM edges WHERE graph_version = ?",
+            params![graph_version],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Latest revision version indexed at `commit_sha`.
+    pub fn revision_version_for_commit(&self, commit_sha: &str) -> Result<Option<i64>> {
+        self.read_conn()?
+            .query_row(
+                "SELECT id FROM graph_versions
+                 WHERE revision IS NOT NULL AND commit_sha = ?
+                 ORDER BY id DESC LIMIT 1",
+                params![commit_sha],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Latest revision version requested as `name`, or whose commit sha
+    /// starts with it. Used when `name` no longer resolves in git.
+    pub fn revision_version_by_name(&self, name: &str) -> Result<Option<i64>> {
+        self.read_conn()?
+            .query_row(
+                "SELECT id FROM graph_versions
+                 WHERE revision IS NOT NULL
+                   AND (revision = ?1 OR (length(?1) >= 4 AND commit_sha LIKE ?1 || '%'))
+                 ORDER BY id DESC LIMIT 1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// File row for a path of revision `graph_version`. Paths the working
+    /// tree does not have are flagged `revision_only` so that working-tree
+    /// listings skip them, and rows deleted at or before the revision are
+    /// revived so the revision's symbols stay visible.
+    pub fn ensure_revision_file(
+        &self,
+        path: &str,
+        language: &str,
+        size: i64,
+        graph_version: i64,
+    ) -> Result<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO files (path, hash, language, size, modified, deleted_version, revision_only)
+             VALUES (?, '', ?, ?, 0, NULL, 1)
+             ON CONFLICT(path) DO UPDATE SET
+                revision_only = CASE
+                    WHEN deleted_version IS NOT NULL AND deleted_version <= ? THEN 1
+                    ELSE revision_only
+                END,
+                deleted_version = CASE
+                    WHEN deleted_version IS NOT NULL AND deleted_version <= ? THEN NULL
+                    ELSE deleted_version
+                END",
+            params![path, language, size, graph_version, graph_version],
+        )?;
+        let id = conn.query_row(
+            "SELECT id FROM files WHERE path = ?",
+            params![path],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    /// Content hash of a file that is live in the working tree, which is
+    /// what the current graph version holds for it.
+    pub fn worktree_file_hash(&self, path: &str) -> Result<Option<String>> {
+        self.read_conn()?
+            .query_row(
+                "SELECT hash FROM files
+                 WHERE path = ? AND deleted_version IS NULL AND revision_only = 0",
+                params![path],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    pub fn record_revision_file(&self, graph_version: i64, file_id: i64, blob: &str) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO revision_files (graph_version, file_id, blob) VALUES (?, ?, ?)
+             ON CONFLICT(graph_version, file_id) DO UPDATE SET blob = excluded.blob",
+            params![graph_version, file_id, blob],
+        )?;
+        Ok(())
+    }
+
+    /// Latest revision version, before `graph_version`, whose copy of the
+    /// file has this blob.
+    pub fn revision_version_with_blob(
+        &self,
+        file_id: i64,
+        blob: &str,
+        graph_version: i64,
+    ) -> Result<Option<i64>> {
+        self.read_conn()?
+            .query_row(
+                "SELECT MAX(graph_version) FROM revision_files
+                 WHERE file_id = ? AND blob = ? AND graph_version < ?",
+                params![file_id, blob, graph_version],
+                |row| row.get(0),
+            )
+            .map_err(Into::into)
+    }
+
+    /// Copy a file's symbols, symbol metrics and content lines from one graph
+    /// version to another. Edges are copied separately, once every file of
+    /// the target version has its symbols.
+    pub fn copy_file_symbols(
+        &self,
+        file_id: i64,
+        from_version: i64,
+        to_version: i64,
+        commit_sha: Option<&str>,
+    ) -> Result<usize> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let symbols = tx.execute(
+            "INSERT INTO symbols
+             (file_id, kind, name, qualname, start_line, start_col, end_line, end_col, start_byte, end_byte, signature, docstring, graph_version, commit_sha, stable_id)
+             SELECT file_id, kind, name, qualname, start_line, start_col, end_line, end_col, start_byte, end_byte, signature, docstring, ?3, ?4, stable_id
+             FROM symbols
+             WHERE file_id = ?1 AND graph_version = ?2
+             ORDER BY id",
+            params![file_id, from_version, to_version, commit_sha],
+        )?;
+        tx.execute(
+            "INSERT OR IGNORE INTO symbol_metrics (symbol_id, file_id, loc, complexity, duplication_hash)
+             SELECT n.id, m.file_id, m.loc, m.complexity, m.duplication_hash
+             FROM symbol_metrics m
+             JOIN symbols o ON o.id = m.symbol_id
+             JOIN symbols n ON n.file_id = o.file_id AND n.stable_id = o.stable_id
+             WHERE o.file_id = ?1 AND o.graph_version = ?2 AND n.graph_version = ?3",
+            params![file_id, from_version, to_version],
+        )?;
+        tx.execute(
+            "INSERT INTO content_lines (file_id, graph_version, line, text, tokens)
+             SELECT file_id, ?3, line, text, tokens
+             FROM content_lines
+             WHERE file_id = ?1 AND graph_version = ?2
+             ORDER BY line",
+            params![file_id, from_version, to_version],
+        )?;
+        tx.commit()?;
+        Ok(symbols)
+    }
+
+    /// Copy a file's edges between graph versions, re-pointing source and
+    /// target at the matching symbols of the new version. Targets with no
+    /// match are left NULL for `resolve_null_target_edges`.
+    pub fn copy_file_edges(
+        &self,
+        file_id: i64,
+        from_version: i64,
+        to_version: i64,
+        commit_sha: Option<&str>,
+    ) -> Result<usize> {
+        let sql = format!(
+            "INSERT INTO edges
+             (file_id, source_symbol_id, target_symbol_id, kind, target_qualname, detail, evidence_snippet,
+              evidence_start_line, evidence_end_line, confidence, graph_version, commit_sha, trace_id, span_id, event_ts)
+             SELECT e.file_id, {}, {}, e.kind, e.target_qualname, e.detail, e.evidence_snippet,
+                    e.evidence_start_line, e.evidence_end_line, e.confidence, ?1, ?2, e.trace_id, e.span_id, e.event_ts
+             FROM edges e
+             WHERE e.file_id = ?3 AND e.graph_version = ?4
+             ORDER BY e.id",
+            mapped_symbol("e.source_symbol_id"),
+            mapped_symbol("e.target_symbol_id"),
+        );
+        let count = self
+            .conn()
+            .execute(&sql, params![to_version, commit_sha, file_id, from_version])?;
+        Ok(count)
+    }
+}
@@ -0,0 +1,296 @@
+use super::Db;
+use super::table_refs::{normalize_table_name, same_table};
+use crate::indexer::sql_extractor::SCHEMA_CHANGE_KIND;
+use crate::model::{ColumnChange, SchemaChangeSite, TableColumn};
+use anyhow::Result;
+use rusqlite::params;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+struct ChangeDetail {
+    op: String,
+    table: String,
+    column: Option<String>,
+    #[serde(rename = "type")]
+    data_type: Option<String>,
+    to: Option<String>,
+}
+
+struct Change {
+    detail: ChangeDetail,
+    site: SchemaChangeSite,
+    target_symbol_id: Option<i64>,
+}
+
+/// Compare paths with digit runs compared numerically, so `V10__x.sql`
+/// sorts after `V9__x.sql`.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.as_bytes(), b.as_bytes());
+    loop {
+        match (a.first(), b.first()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let a_len = a.iter().take_while(|ch| ch.is_ascii_digit()).count();
+                let b_len = b.iter().take_while(|ch| ch.is_ascii_digit()).count();
+                let a_num = std::str::from_utf8(&a[..a_len]).unwrap_or_default();
+                let b_num = std::str::from_utf8(&b[..b_len]).unwrap_or_default();
+                let a_trim = a_num.trim_start_matches('0');
+                let b_trim = b_num.trim_start_matches('0');
+                let ordering = a_trim
+                    .len()
+                    .cmp(&b_trim.len())
+                    .then_with(|| a_trim.cmp(b_trim));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a = &a[a_len..];
+                b = &b[b_len..];
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(y);
+                }
+                a = &a[1..];
+                b = &b[1..];
+            }
+        }
+    }
+}
+
+/// Fold schema changes, oldest first, into the columns of the table known
+/// by any of `names`.
+fn fold_columns(changes: &[Change], names: &[String]) -> Vec<TableColumn> {
+    let is_table = |table: &str| names.iter().any(|name| same_table(name, table));
+    let mut columns: Vec<TableColumn> = Vec::new();
+    // Columns of the table before it was last re-created; a column that
+    // survives `CREATE TABLE` keeps the migration that introduced it.
+    let mut previous: HashMap<String, TableColumn> = HashMap::new();
+    let position = |columns: &[TableColumn], name: &str| {
+        let key = normalize_table_name(name);
+        columns
+            .iter()
+            .position(|column| normalize_table_name(&column.name) == key)
+    };
+    for change in changes {
+        let detail = &change.detail;
+        if !is_table(&detail.table) {
+            continue;
+        }
+        let column = detail.column.as_deref().unwrap_or_default();
+        match detail.op.as_str() {
+            "create" => {
+                previous = columns
+                    .drain(..)
+                    .map(|column| (normalize_table_name(&column.name), column))
+                    .collect();
+            }
+            "drop" => {
+                columns.clear();
+                previous.clear();
+            }
+            "rename" if detail.to.as_deref().is_some_and(|to| !is_table(to)) => {
+                columns.clear();
+                previous.clear();
+            }
+            "add_column" => {
+                let entry = match previous.remove(&normalize_table_name(column)) {
+                    Some(mut kept) => {
+                        kept.data_type = detail.data_type.clone().or(kept.data_type);
+                        kept.symbol_id = change.target_symbol_id.or(kept.symbol_id);
+                        kept
+                    }
+                    None => TableColumn {
+                        name: column.to_string(),
+                        data_type: detail.data_type.clone(),
+                        symbol_id: change.target_symbol_id,
+                        introduced_in: change.site.clone(),
+                        changes: Vec::new(),
+                    },
+                };
+                match position(&columns, column) {
+                    Some(idx) => columns[idx] = entry,
+                    None => columns.push(entry),
+                }
+            }
+            "drop_column" => {
+                if let Some(idx) = position(&columns, column) {
+                    columns.remove(idx);
+                }
+            }
+            "rename_column" => {
+                let (Some(idx), Some(to)) = (position(&columns, column), detail.to.as_deref())
+                else {
+                    continue;
+                };
+                let renamed = &mut columns[idx];
+                renamed.changes.push(ColumnChange {
+                    op: detail.op.clone(),
+                    path: change.site.path.clone(),
+                    line: change.site.line,
+                    data_type: None,
+                    previous_name: Some(renamed.name.clone()),
+                });
+                renamed.name = to.to_string();
+                renamed.symbol_id = change.target_symbol_id.or(renamed.symbol_id);
+            }
+            "alter_column" => {
+                let Some(idx) = position(&columns, column) else {
+                    continue;
+                };
+                let altered = &mut columns[idx];
+                if detail.data_type.is_some() {
+                    altered.data_type = detail.data_type.clone();
+                }
+                altered.changes.push(ColumnChange {
+                    op: detail.op.clone(),
+                    path: change.site.path.clone(),
+                    line: change.site.line,
+                    data_type: detail.data_type.clone(),
+                    previous_name: None,
+                });
+            }
+            _ => {}
+        }
+    }
+    columns
+}
+
+impl Db {
+    /// Current columns of a table or view: every SCHEMA_CHANGE edge (see
+    /// `indexer::sql_extractor`) for it, or for a table later renamed to it,
+    /// replayed in migration order (file paths compared with numbers in
+    /// numeric order, then line).
+    pub fn table_columns(&self, table: &str, graph_version: i64) -> Result<Vec<TableColumn>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT e.detail, f.path, COALESCE(e.evidence_start_line, 0), e.target_symbol_id
+             FROM edges e
+             JOIN files f ON e.file_id = f.id
+             WHERE e.kind = ?
+               AND e.graph_version = ?
+               AND e.detail IS NOT NULL
+               AND (f.deleted_version IS NULL OR f.deleted_version > ?)
+             ORDER BY e.id",
+        )?;
+        let rows = stmt.query_map(
+            params![SCHEMA_CHANGE_KIND, graph_version, graph_version],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                ))
+            },
+        )?;
+        let mut changes = Vec::new();
+        for row in rows {
+            let (detail, path, line, target_symbol_id) = row?;
+            let Ok(detail) = serde_json::from_str::<ChangeDetail>(&detail) else {
+                continue;
+            };
+            changes.push(Change {
+                detail,
+                site: SchemaChangeSite { path, line },
+                target_symbol_id,
+            });
+        }
+        // Stable sort: statements on one line keep their insertion order.
+        changes.sort_by(|a, b| {
+            natural_cmp(&a.site.path, &b.site.path).then(a.site.line.cmp(&b.site.line))
+        });
+
+        let mut names = vec![table.to_string()];
+        loop {
+            let before = names.len();
+            for change in &changes {
+                if change.detail.op == "rename"
+                    && let Some(to) = change.detail.to.as_deref()
+                    && names.iter().any(|name| same_table(name, to))
+                    && !names
+                        .iter()
+                        .any(|name| same_table(name, &change.detail.table))
+                {
+                    names.push(change.detail.table.clone());
+                }
+            }
+            if names.len() == before {
+                break;
+            }
+        }
+        Ok(fold_columns(&changes, &names))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(path: &str, line: i64, detail: &str) -> Change {
+        Change {
+            detail: serde_json::from_str(detail).unwrap(),
+            site: SchemaChangeSite {
+                path: path.to_string(),
+                line,
+            },
+            target_symbol_id: None,
+        }
+    }
+
+    #[test]
+    fn natural_order_compares_numbers_numerically() {
+        assert_eq!(natural_cmp("V9__a.sql", "V10__a.sql"), Ordering::Less);
+        assert_eq!(natural_cmp("002_b.sql", "010_a.sql"), Ordering::Less);
+        assert_eq!(natural_cmp("a.sql", "a.sql"), Ordering::Equal);
+    }
+
+    #[test]
+    fn fold_applies_renames_type_changes_and_drops() {
+        let changes = vec![
+            change("m/1.sql", 1, r#"{"op":"create","table":"orders"}"#),
+            change(
+                "m/1.sql",
+                2,
+                r#"{"op":"add_column","table":"orders","column":"id","type":"INT"}"#,
+            ),
+            change(
+                "m/1.sql",
+                3,
+                r#"{"op":"add_column","table":"orders","column":"note","type":"TEXT"}"#,
+            ),
+            change(
+                "m/2.sql",
+                1,
+                r#"{"op":"rename_column","table":"orders","column":"note","to":"notes"}"#,
+            ),
+            change(
+                "m/2.sql",
+                2,
+                r#"{"op":"alter_column","table":"public.orders","column":"notes","type":"VARCHAR(200)"}"#,
+            ),
+            change(
+                "m/3.sql",
+                1,
+                r#"{"op":"drop_column","table":"orders","column":"id"}"#,
+            ),
+            change(
+                "m/4.sql",
+                1,
+                r#"{"op":"rename","table":"orders","to":"purchases"}"#,
+            ),
+        ];
+        let columns = fold_columns(&changes, &["purchases".to_string(), "orders".to_string()]);
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns[0].name, "notes");
+        assert_eq!(columns[0].data_type.as_deref(), Some("VARCHAR(200)"));
+        assert_eq!(columns[0].introduced_in.path, "m/1.sql");
+        assert_eq!(columns[0].changes.len(), 2);
+
+        // Under the old name the table no longer exists.
+        assert!(fold_columns(&changes, &["orders".to_string()]).is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+use super::Db;
+use crate::indexer::embedded_sql::{READS_TABLE_KIND, WRITES_TABLE_KIND};
+use crate::indexer::sql_extractor::{DERIVES_FROM_KIND, SCHEMA_CHANGE_KIND};
+use anyhow::Result;
+use rusqlite::params;
+use std::collections::{HashMap, HashSet};
+
+const TABLE_KINDS: &str = "'table', 'view', 'materialized_view'";
+/// Schemas a bare table name falls back to.
+const DEFAULT_SCHEMAS: &[&str] = &["dbo", "public"];
+
+/// Lowercased dotted name with `[..]`, `".."` and backtick quoting removed.
+pub(super) fn normalize_table_name(name: &str) -> String {
+    name.split('.')
+        .map(|segment| {
+            segment
+                .trim_matches(['[', ']', '"', '`'])
+                .to_ascii_lowercase()
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn last_segment(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+fn in_default_schema(name: &str) -> bool {
+    match name.rsplit_once('.') {
+        Some((schema, _)) => DEFAULT_SCHEMAS.contains(&schema),
+        None => true,
+    }
+}
+
+/// Whether two table references name the same table: equal once quoting and
+/// case are ignored, or equal names where one side has no schema or a
+/// default one.
+pub(super) fn same_table(a: &str, b: &str) -> bool {
+    let (a, b) = (normalize_table_name(a), normalize_table_name(b));
+    if a == b {
+        return true;
+    }
+    last_segment(&a) == last_segment(&b) && (in_default_schema(&a) || in_default_schema(&b))
+}
+
+/// Index of table and view symbols by full and by last-segment name.
+#[derive(Default)]
+struct TableIndex {
+    by_qualname: HashMap<String, i64>,
+    by_name: HashMap<String, Vec<(String, i64)>>,
+    columns: HashMap<(i64, String), i64>,
+}
+
+impl TableIndex {
+    /// Register column `table.column` under the table its prefix resolves
+    /// to; columns of unknown tables are dropped.
+    fn insert_column(&mut self, qualname: &str, id: i64) {
+        let Some((table, column)) = qualname.rsplit_once('.') else {
+            return;
+        };
+        let Some(table_id) = self.lookup(table) else {
+            return;
+        };
+        self.columns
+            .entry((table_id, normalize_table_name(column)))
+            .or_insert(id);
+    }
+
+    /// The column `table.column` names, with the table part resolved like
+    /// a table reference.
+    fn lookup_column(&self, reference: &str) -> Option<i64> {
+        let (table, column) = reference.rsplit_once('.')?;
+        let table_id = self.lookup(table)?;
+        self.columns
+            .get(&(table_id, normalize_table_name(column)))
+            .copied()
+    }
+
+    fn insert(&mut self, qualname: &str, id: i64) {
+        let key = normalize_table_name(qualname);
+        self.by_qualname.entry(key.clone()).or_insert(id);
+        self.by_name
+            .entry(last_segment(&key).to_string())
+            .or_default()
+            .push((key, id));
+    }
+
+    /// The table `reference` names: an exact (case-insensitive) match, else
+    /// the only table with that name, else the one in a default schema.
+    fn lookup(&self, reference: &str) -> Option<i64> {
+        let key = normalize_table_name(reference);
+        if let Some(id) = self.by_qualname.get(&key) {
+            return Some(*id);
+        }
+        let name = last_segment(&key);
+        let candidates = self.by_name.get(name)?;
+        let qualified = key.len() != name.len();
+        if qualified {
+            // `sales.orders` never binds to `billing.orders`, but does bind to
+            // an unqualified `orders` declaration.
+            return candidates
+                .iter()
+                .find(|(qualname, _)| qualname == name)
+                .map(|(_, id)| *id);
+        }
+        if let [(_, id)] = candidates.as_slice() {
+            return Some(*id);
+        }
+        candidates
+            .iter()
+            .find(|(qualname, _)| {
+                qualname == name
+                    || DEFAULT_SCHEMAS
+                        .iter()
+                        .any(|schema| *qualname == format!("{schema}.{name}"))
+            })
+            .map(|(_, id)| *id)
+    }
+}
+
+impl Db {
+    /// Bind edges that name tables or columns to table, view and column
+    /// symbols by case-insensitive, schema-aware name: READS_TABLE /
+    /// WRITES_TABLE (see `indexer::embedded_sql`), SCHEMA_CHANGE and
+    /// DERIVES_FROM, and REFERENCES from SQL files. Edges naming a table that
+    /// is not indexed are left unresolved rather than bound to a same-named
+    /// class. Returns the number of edges bound.
+    pub fn resolve_table_refs(&self, graph_version: i64) -> Result<usize> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        let pending: Vec<(i64, String, Option<i64>)> = {
+            let mut stmt = tx.prepare(
+                "SELECT e.id, e.target_qualname, e.target_symbol_id
+                 FROM edges e
+                 JOIN files f ON e.file_id = f.id
+                 WHERE (e.kind IN (?, ?, ?, ?)
+                        OR (e.kind = 'REFERENCES' AND f.language IN ('sql', 'postgres', 'tsql')))
+                   AND e.target_qualname IS NOT NULL
+                   AND e.graph_version = ?",
+            )?;
+            let rows = stmt.query_map(
+                params![
+                    READS_TABLE_KIND,
+                    WRITES_TABLE_KIND,
+                    SCHEMA_CHANGE_KIND,
+                    DERIVES_FROM_KIND,
+                    graph_version
+                ],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let mut tables = TableIndex::default();
+        {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT s.id, s.qualname
+                 FROM symbols s
+                 JOIN files f ON s.file_id = f.id
+                 WHERE s.kind IN ({TABLE_KINDS})
+                   AND s.graph_version = ?
+                   AND (f.deleted_version IS NULL OR f.deleted_version > ?)
+                 ORDER BY s.id"
+            ))?;
+            let rows = stmt.query_map(params![graph_version, graph_version], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (id, qualname) = row?;
+                tables.insert(&qualname, id);
+            }
+            let mut stmt = tx.prepare(
+                "SELECT s.id, s.qualname
+                 FROM symbols s
+                 JOIN files f ON s.file_id = f.id
+                 WHERE s.kind = 'column'
+                   AND s.graph_version = ?
+                   AND (f.deleted_version IS NULL OR f.deleted_version > ?)
+                 ORDER BY s.id",
+            )?;
+            let rows = stmt.query_map(params![graph_version, graph_version], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (id, qualname) = row?;
+                tables.insert_column(&qualname, id);
+            }
+        }
+        let known: HashSet<i64> = tables
+            .by_name
+            .values()
+            .flatten()
+            .map(|(_, id)| *id)
+            .chain(tables.columns.values().copied())
+            .collect();
+
+        let mut bound = 0;
+        {
+            let mut update = tx.prepare("UPDATE edges SET target_symbol_id = ? WHERE id = ?")?;
+            for (edge_id, target, current) in pending {
+                if current.is_some_and(|id| known.contains(&id)) {
+                    bound += 1;
+                    continue;
+                }
+                let resolved = tables
+                    .lookup_column(&target)
+                    .or_else(|| tables.lookup(&target));
+                if resolved != current {
+                    update.execute(params![resolved, edge_id])?;
+                }
+                if resolved.is_some() {
+                    bound += 1;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(bound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_is_case_insensitive_and_schema_aware() {
+        let mut tables = TableIndex::default();
+        tables.insert("dbo.[Orders]", 1);
+        tables.insert("billing.orders", 2);
+        tables.insert("customers", 3);
+
+        assert_eq!(tables.lookup("orders"), Some(1));
+        assert_eq!(tables.lookup("DBO.ORDERS"), Some(1));
+        assert_eq!(tables.lookup("billing.Orders"), Some(2));
+        assert_eq!(tables.lookup("sales.orders"), None);
+        assert_eq!(tables.lookup("public.customers"), Some(3));
+        assert_eq!(tables.lookup("invoices"), None);
+
+        tables.insert_column("dbo.[Orders].[Status]", 10);
+        assert_eq!(tables.lookup_column("orders.status"), Some(10));
+        assert_eq!(tables.lookup_column("billing.orders.status"), None);
+        assert!(same_table("public.orders", "\"orders\""));
+        assert!(!same_table("billing.orders", "sales.orders"));
+    }
+}
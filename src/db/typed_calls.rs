@@ -0,0 +1,291 @@
+use super::Db;
+use crate::indexer::type_resolve::{RECEIVER_TYPE_KEY, callee_method, language_family};
+use anyhow::Result;
+use rusqlite::{Transaction, params};
+use std::collections::HashMap;
+
+/// Confidence of a call bound to a method declared on the receiver's type.
+const DECLARED_CONFIDENCE: f64 = 0.95;
+/// Confidence of a call bound to a method inherited from a base type.
+const INHERITED_CONFIDENCE: f64 = 0.85;
+/// How far up EXTENDS / IMPLEMENTS chains a method is looked for.
+const MAX_BASE_DEPTH: usize = 4;
+
+const TYPE_KINDS: &str = "'class', 'interface', 'struct', 'trait', 'record', 'enum', 'type'";
+
+#[derive(Debug, Clone)]
+struct TypeSymbol {
+    id: i64,
+    qualname: String,
+    file_id: i64,
+    family: &'static str,
+}
+
+fn dotted(qualname: &str) -> String {
+    qualname.replace("::", ".")
+}
+
+fn last_segment(name: &str) -> &str {
+    let name = name.split(['<', '[']).next().unwrap_or(name);
+    name.rsplit(['.', ':']).next().unwrap_or(name)
+}
+
+/// Leading qualname segments `a` and `b` share, with `::` read as `.`.
+pub(crate) fn common_prefix_segments(a: &str, b: &str) -> usize {
+    dotted(a)
+        .split('.')
+        .zip(dotted(b).split('.'))
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+impl Db {
+    /// Bind CALLS edges tagged with a receiver type (see
+    /// `indexer::type_resolve`) to the method declared on that type, or on
+    /// one of its bases. Edges whose type or method is not indexed keep their
+    /// name-based target. Returns the number of edges bound.
+    pub fn resolve_typed_calls(&self, graph_version: i64) -> Result<usize> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        let pending: Vec<(i64, i64, String, String, String, Option<String>)> = {
+            let mut stmt = tx.prepare(
+                "SELECT e.id, e.file_id, e.detail, COALESCE(e.target_qualname, ''),
+                        COALESCE(f.language, 'unknown'), src.qualname
+                 FROM edges e
+                 JOIN files f ON e.file_id = f.id
+                 LEFT JOIN symbols src ON src.id = e.source_symbol_id
+                 WHERE e.kind = 'CALLS'
+                   AND e.graph_version = ?
+                   AND e.detail LIKE '%\"receiver_type\"%'",
+            )?;
+            let rows = stmt.query_map(params![graph_version], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let types = load_types(&tx, graph_version)?;
+        let mut methods: HashMap<(i64, String), Option<(i64, f64)>> = HashMap::new();
+        let mut bound = 0;
+        {
+            let mut update =
+                tx.prepare("UPDATE edges SET target_symbol_id = ?, confidence = ? WHERE id = ?")?;
+            for (edge_id, file_id, detail, callee, language, source) in pending {
+                let Some(receiver_type) = serde_json::from_str::<serde_json::Value>(&detail)
+                    .ok()
+                    .and_then(|detail| {
+                        detail
+                            .get(RECEIVER_TYPE_KEY)
+                            .and_then(|ty| ty.as_str())
+                            .map(str::to_string)
+                    })
+                else {
+                    continue;
+                };
+                let method = callee_method(&callee);
+                if method.is_empty() {
+                    continue;
+                }
+                let family = language_family(&language);
+                let candidates =
+                    rank_types(&types, &receiver_type, family, file_id, source.as_deref());
+                for ty in candidates {
+                    let key = (ty.id, method.to_string());
+                    if !methods.contains_key(&key) {
+                        let found = find_method(&tx, &types, ty, method, graph_version)?;
+                        methods.insert(key.clone(), found);
+                    }
+                    if let Some((method_id, confidence)) = methods[&key] {
+                        update.execute(params![method_id, confidence, edge_id])?;
+                        bound += 1;
+                        break;
+                    }
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(bound)
+    }
+}
+
+/// Live type symbols keyed by simple name.
+fn load_types(
+    tx: &Transaction<'_>,
+    graph_version: i64,
+) -> Result<HashMap<String, Vec<TypeSymbol>>> {
+    let mut stmt = tx.prepare(&format!(
+        "SELECT s.id, s.name, s.qualname, s.file_id, COALESCE(f.language, 'unknown')
+         FROM symbols s
+         JOIN files f ON s.file_id = f.id
+         WHERE s.kind IN ({TYPE_KINDS})
+           AND s.graph_version = ?
+           AND (f.deleted_version IS NULL OR f.deleted_version > ?)"
+    ))?;
+    let rows = stmt.query_map(params![graph_version, graph_version], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, i64>(3)?,
+            row.get::<_, String>(4)?,
+        ))
+    })?;
+    let mut types: HashMap<String, Vec<TypeSymbol>> = HashMap::new();
+    for row in rows {
+        let (id, name, qualname, file_id, language) = row?;
+        types.entry(name).or_default().push(TypeSymbol {
+            id,
+            qualname,
+            file_id,
+            family: language_family(&language),
+        });
+    }
+    Ok(types)
+}
+
+/// Type symbols a receiver type name may refer to, most likely first: same
+/// file, then the longest qualname prefix shared with the calling symbol,
+/// then the shortest qualname.
+fn rank_types<'a>(
+    types: &'a HashMap<String, Vec<TypeSymbol>>,
+    receiver_type: &str,
+    family: &str,
+    file_id: i64,
+    source: Option<&str>,
+) -> Vec<&'a TypeSymbol> {
+    let Some(candidates) = types.get(last_segment(receiver_type)) else {
+        return Vec::new();
+    };
+    let same_family = candidates.iter().filter(|ty| ty.family == family);
+    let mut ranked: Vec<&TypeSymbol> = if receiver_type.contains(['.', ':']) {
+        // `pkg.Repo` / `models::Repo`: prefer qualnames ending in the written
+        // path, falling back to the simple name for package aliases.
+        let suffix = format!(".{}", dotted(receiver_type));
+        let qualified: Vec<&TypeSymbol> = same_family
+            .clone()
+            .filter(|ty| {
+                let qualname = dotted(&ty.qualname);
+                qualname == suffix[1..] || qualname.ends_with(&suffix)
+            })
+            .collect();
+        if qualified.is_empty() {
+            same_family.collect()
+        } else {
+            qualified
+        }
+    } else {
+        same_family.collect()
+    };
+    ranked.sort_by_key(|ty| {
+        (
+            ty.file_id != file_id,
+            std::cmp::Reverse(
+                source.map_or(0, |source| common_prefix_segments(source, &ty.qualname)),
+            ),
+            ty.qualname.len(),
+            ty.id,
+        )
+    });
+    ranked
+}
+
+/// The method `name` on `ty`, or on the nearest base type that declares it.
+fn find_method(
+    tx: &Transaction<'_>,
+    types: &HashMap<String, Vec<TypeSymbol>>,
+    ty: &TypeSymbol,
+    name: &str,
+    graph_version: i64,
+) -> Result<Option<(i64, f64)>> {
+    let mut method_stmt = tx.prepare_cached(
+        "SELECT s.id FROM symbols s
+         JOIN files f ON s.file_id = f.id
+         WHERE s.qualname IN (?, ?)
+           AND s.kind IN ('method', 'function', 'property')
+           AND s.graph_version = ?
+           AND (f.deleted_version IS NULL OR f.deleted_version > ?)
+         ORDER BY s.id
+         LIMIT 1",
+    )?;
+    let mut base_stmt = tx.prepare_cached(
+        "SELECT target_symbol_id, target_qualname FROM edges
+         WHERE source_symbol_id = ?
+           AND kind IN ('EXTENDS', 'IMPLEMENTS', 'INHERITS')
+           AND graph_version = ?",
+    )?;
+
+    let mut level = vec![ty.clone()];
+    let mut seen = vec![ty.id];
+    for depth in 0..=MAX_BASE_DEPTH {
+        let mut next = Vec::new();
+        for current in &level {
+            let found: Option<i64> = method_stmt
+                .query_row(
+                    params![
+                        format!("{}.{name}", current.qualname),
+                        format!("{}::{name}", current.qualname),
+                        graph_version,
+                        graph_version
+                    ],
+                    |row| row.get(0),
+                )
+                .ok();
+            if let Some(id) = found {
+                let confidence = if depth == 0 {
+                    DECLARED_CONFIDENCE
+                } else {
+                    INHERITED_CONFIDENCE
+                };
+                return Ok(Some((id, confidence)));
+            }
+            let bases = base_stmt.query_map(params![current.id, graph_version], |row| {
+                Ok((
+                    row.get::<_, Option<i64>>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                ))
+            })?;
+            for base in bases {
+                let (target_id, target_qualname) = base?;
+                let base = target_qualname.as_deref().and_then(|qualname| {
+                    let candidates = types.get(last_segment(qualname))?;
+                    candidates
+                        .iter()
+                        .find(|candidate| Some(candidate.id) == target_id)
+                        .or_else(|| {
+                            rank_types(
+                                types,
+                                qualname,
+                                current.family,
+                                current.file_id,
+                                Some(&current.qualname),
+                            )
+                            .into_iter()
+                            .next()
+                        })
+                });
+                if let Some(base) = base
+                    && !seen.contains(&base.id)
+                {
+                    seen.push(base.id);
+                    next.push(base.clone());
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        level = next;
+    }
+    Ok(None)
+}
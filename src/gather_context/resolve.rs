@@ -257,7 +257,7 @@ pub(super) fn line_range_to_bytes(
 /// NOTE: This function performs N+1 database queries (one per search hit)
 /// to resolve enclosing symbols. This is a known trade-off documented
 /// in the Staff Engineer review (Critical Issue #1). The search operation
-/// itself (ripgrep) dominates latency (~38ms), so the additional database
+/// itself (FTS index, or ripgrep when the index is empty) dominates latency, so the additional database
 /// queries (~15ms each) are acceptable for the MVP. Future optimization
 /// could batch these lookups if search seeds become performance-critical.
 #[allow(clippy::type_complexity)]
@@ -268,7 +268,7 @@ pub(super) fn resolve_search_seed(
     limit: Option<usize>,
     config: &GatherConfig,
 ) -> Result<(Vec<(i64, f32)>, HashMap<i64, MatchLocation>)> {
-    use crate::search::{SearchOptions, search_text};
+    use crate::search::{SearchOptions, search_indexed, search_text};
 
     let limit = limit.unwrap_or(10);
     let options = SearchOptions {
@@ -280,7 +280,10 @@ pub(super) fn resolve_search_seed(
         paths: config.paths.as_deref(),
     };
 
-    let hits = search_text(repo_root, query, limit, options)?;
+    let hits = match search_indexed(db, config.graph_version, query, limit, options)? {
+        Some(hits) => hits,
+        None => search_text(repo_root, query, limit, options)?,
+    };
 
     // Map hits to symbols via enclosing symbol lookup
     let mut results = Vec::new();
@@ -7,6 +7,7 @@
 use crate::db::Db;
 use crate::impact::confidence::apply_distance_decay;
 use crate::impact::types::{ConfidenceScore, ImpactSource, LayerResult};
+use crate::indexer::overrides::is_dispatch_edge_kind;
 use crate::model::{Edge, Symbol};
 use anyhow::Result;
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -35,7 +36,22 @@ impl From<&str> for TraversalDirection {
 }
 
 /// Determine the next symbol to visit based on edge direction
+///
+/// OVERRIDES / IMPLEMENTS_MEMBER edges point from an implementation to the
+/// member it is dispatched through, so they are walked against the requested
+/// direction: callers of `IRepository.Save` are upstream of
+/// `SqlRepository.Save`, and a call to `IRepository.Save` reaches every
+/// implementation downstream.
 fn next_symbol(edge: &Edge, current_id: i64, direction: TraversalDirection) -> Option<i64> {
+    let direction = if is_dispatch_edge_kind(&edge.kind) {
+        match direction {
+            TraversalDirection::Upstream => TraversalDirection::Downstream,
+            TraversalDirection::Downstream => TraversalDirection::Upstream,
+            TraversalDirection::Both => TraversalDirection::Both,
+        }
+    } else {
+        direction
+    };
     match direction {
         TraversalDirection::Upstream => {
             if edge.target_symbol_id == Some(current_id) {
@@ -137,6 +153,10 @@ fn resolve_next_id(
     direction: TraversalDirection,
     resolved_qualnames: &HashMap<String, i64>,
 ) -> Option<i64> {
+    if is_dispatch_edge_kind(&edge.kind) {
+        // Always linked by id; the fallbacks below would undo the reversal.
+        return next_symbol(edge, current_id, direction);
+    }
     next_symbol(edge, current_id, direction)
         .or_else(|| {
             if edge.source_symbol_id == Some(current_id) {
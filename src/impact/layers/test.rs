@@ -379,6 +379,9 @@ impl<'a> TestImpactLayer<'a> {
             "ts" | "tsx" => Some("typescript"),
             "js" | "jsx" => Some("javascript"),
             "rs" => Some("rust"),
+            "java" => Some("java"),
+            "kt" | "kts" => Some("kotlin"),
+            "lua" => Some("lua"),
             "proto" => Some("proto"),
             "sql" => Some("sql"),
             "md" => Some("markdown"),
@@ -0,0 +1,470 @@
+//! AsyncAPI 2 and 3 documents.
+//!
+//! Each channel becomes a `channel` symbol whose qualname is its `channel://`
+//! key, so the CHANNEL_PUBLISH / CHANNEL_SUBSCRIBE edges found in code bind
+//! to the documented channel. Each operation becomes an `operation` symbol
+//! with a channel edge of its own, so a service described only by its spec
+//! still shows up as a publisher or subscriber in trace_flow. Messages and
+//! schemas under `components` are symbols too, with TYPE_REF edges for their
+//! payloads. Messages carrying a CloudEvents type (`x-cloudevents-type`, or a
+//! constant `ce_type` header) are also published under that type.
+
+use crate::indexer::channel::{self, CHANNEL_PUBLISH_KIND, CHANNEL_SUBSCRIBE_KIND};
+use crate::indexer::extract::{EdgeInput, ExtractedFile};
+use crate::indexer::openapi::{
+    self, SpecLines, SpecSource, contains_edge, description, push_type_refs, spec_symbol,
+};
+use serde_json::{Value, json};
+
+pub const ASYNCAPI_FRAMEWORK: &str = "asyncapi";
+
+/// CloudEvents headers naming the event type, in binary content mode.
+const CLOUDEVENTS_TYPE_HEADERS: &[&str] = &["ce_type", "ce-type"];
+
+/// Whether `value` is an AsyncAPI 2 or 3 document (`asyncapi: 2.6.0`).
+pub fn is_asyncapi(value: &Value) -> bool {
+    value
+        .get("asyncapi")
+        .and_then(openapi::version_text)
+        .is_some_and(|v| v.starts_with('2') || v.starts_with('3'))
+}
+
+#[derive(Clone, Copy)]
+enum Action {
+    Send,
+    Receive,
+}
+
+impl Action {
+    fn verb(self) -> &'static str {
+        match self {
+            Action::Send => "send",
+            Action::Receive => "receive",
+        }
+    }
+
+    fn edge_kind(self) -> &'static str {
+        match self {
+            Action::Send => CHANNEL_PUBLISH_KIND,
+            Action::Receive => CHANNEL_SUBSCRIBE_KIND,
+        }
+    }
+
+    fn role(self) -> &'static str {
+        match self {
+            Action::Send => "publisher",
+            Action::Receive => "subscriber",
+        }
+    }
+}
+
+struct Spec<'a> {
+    document: &'a Value,
+    lines: SpecLines<'a>,
+    module_name: &'a str,
+    service: Option<&'a str>,
+}
+
+/// Add the channels, operations, messages and schemas of an AsyncAPI
+/// document.
+pub fn extract_spec(
+    value: &Value,
+    source: &SpecSource<'_>,
+    module_name: &str,
+    output: &mut ExtractedFile,
+) {
+    let spec = Spec {
+        document: value,
+        lines: SpecLines::new(source),
+        module_name,
+        service: value.pointer("/info/title").and_then(Value::as_str),
+    };
+    let channels = value.get("channels").and_then(Value::as_object);
+    for (key, channel) in channels.into_iter().flatten() {
+        let address = channel_address(key, channel);
+        let Some(channel_key) = channel::normalize_channel_name(address) else {
+            continue;
+        };
+        let span = spec.lines.span(spec.lines.find_path(&["channels", key]));
+        let mut symbol = spec_symbol("channel", address, &channel_key, span);
+        symbol.docstring = description(channel);
+        output.symbols.push(symbol);
+        output.edges.push(contains_edge(module_name, &channel_key));
+        push_type_refs(channel, &channel_key, "message", span, module_name, output);
+
+        // AsyncAPI 2 describes operations from the client's side: `subscribe`
+        // is what the application sends, `publish` what it receives.
+        for (operation_key, action) in [("subscribe", Action::Send), ("publish", Action::Receive)] {
+            let Some(operation) = channel.get(operation_key).filter(|op| op.is_object()) else {
+                continue;
+            };
+            let name = operation
+                .get("operationId")
+                .and_then(Value::as_str)
+                .filter(|id| !id.trim().is_empty())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{} {address}", action.verb()));
+            let line = spec.lines.find_path(&["channels", key, operation_key]);
+            let messages: Vec<&Value> = operation.get("message").into_iter().collect();
+            extract_operation(
+                &spec, &name, action, address, operation, &messages, line, output,
+            );
+        }
+    }
+
+    // AsyncAPI 3 operations reference their channel.
+    let operations = value.get("operations").and_then(Value::as_object);
+    for (name, operation) in operations.into_iter().flatten() {
+        let action = match operation.get("action").and_then(Value::as_str) {
+            Some("send") => Action::Send,
+            Some("receive") => Action::Receive,
+            _ => continue,
+        };
+        let Some((key, channel)) = operation
+            .get("channel")
+            .and_then(|channel| channel.get("$ref"))
+            .and_then(Value::as_str)
+            .and_then(|reference| channel_by_ref(value, reference))
+        else {
+            continue;
+        };
+        let address = channel_address(&key, channel);
+        let messages: Vec<&Value> = match operation.get("messages").and_then(Value::as_array) {
+            Some(messages) => messages.iter().collect(),
+            None => channel
+                .get("messages")
+                .and_then(Value::as_object)
+                .map(|messages| messages.values().collect())
+                .unwrap_or_default(),
+        };
+        let line = spec.lines.find_path(&["operations", name]);
+        extract_operation(
+            &spec, name, action, address, operation, &messages, line, output,
+        );
+    }
+
+    if let Some(messages) = value
+        .pointer("/components/messages")
+        .and_then(Value::as_object)
+    {
+        for (name, message) in messages {
+            let qualname = format!("{module_name}#/components/messages/{name}");
+            let span = spec
+                .lines
+                .span(spec.lines.find_path(&["components", "messages", name]));
+            let mut symbol = spec_symbol("message", name, &qualname, span);
+            symbol.signature = message.get("payload").and_then(openapi::schema_signature);
+            symbol.docstring = message
+                .get("summary")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .or_else(|| description(message));
+            output.symbols.push(symbol);
+            output.edges.push(contains_edge(module_name, &qualname));
+            push_type_refs(message, &qualname, "payload", span, module_name, output);
+        }
+    }
+    if let Some(schemas) = value.pointer("/components/schemas") {
+        openapi::extract_schemas(
+            schemas,
+            &["components", "schemas"],
+            &spec.lines,
+            module_name,
+            output,
+        );
+    }
+}
+
+/// The address a channel is published on: the AsyncAPI 3 `address`, or the
+/// channel key itself in AsyncAPI 2.
+fn channel_address<'a>(key: &'a str, channel: &'a Value) -> &'a str {
+    channel
+        .get("address")
+        .and_then(Value::as_str)
+        .filter(|address| !address.trim().is_empty())
+        .unwrap_or(key)
+}
+
+/// Key and value of the channel a `#/channels/<key>` reference points at.
+fn channel_by_ref<'a>(document: &'a Value, reference: &str) -> Option<(String, &'a Value)> {
+    let pointer = reference.strip_prefix('#')?;
+    let key = pointer.strip_prefix("/channels/")?;
+    let key = key.replace("~1", "/").replace("~0", "~");
+    Some((key, document.pointer(pointer)?))
+}
+
+/// `value`, or what its local `$ref` points at.
+fn resolve<'a>(document: &'a Value, value: &'a Value) -> &'a Value {
+    value
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix('#'))
+        .and_then(|pointer| document.pointer(pointer))
+        .unwrap_or(value)
+}
+
+/// CloudEvents type of a message: its `x-cloudevents-type`, or the constant
+/// value of its `ce_type` header.
+fn cloudevents_type<'a>(document: &'a Value, message: &'a Value) -> Option<&'a str> {
+    let message = resolve(document, message);
+    if let Some(kind) = message.get("x-cloudevents-type").and_then(Value::as_str) {
+        return Some(kind);
+    }
+    let headers = resolve(document, message.get("headers")?);
+    let properties = headers.get("properties")?;
+    CLOUDEVENTS_TYPE_HEADERS.iter().find_map(|name| {
+        let header = resolve(document, properties.get(*name)?);
+        header.get("const").and_then(Value::as_str).or_else(|| {
+            match header.get("enum").and_then(Value::as_array)?.as_slice() {
+                [only] => only.as_str(),
+                _ => None,
+            }
+        })
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn extract_operation(
+    spec: &Spec<'_>,
+    name: &str,
+    action: Action,
+    address: &str,
+    operation: &Value,
+    messages: &[&Value],
+    line: Option<usize>,
+    output: &mut ExtractedFile,
+) {
+    let Some(channel_key) = channel::normalize_channel_name(address) else {
+        return;
+    };
+    let module_name = spec.module_name;
+    let qualname = format!("{module_name}#{name}");
+    let span = spec.lines.span(line);
+    let mut symbol = spec_symbol("operation", name, &qualname, span);
+    symbol.signature = Some(format!("{} {address}", action.verb()));
+    symbol.docstring = operation
+        .get("summary")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| description(operation));
+    output.symbols.push(symbol);
+    output.edges.push(contains_edge(module_name, &qualname));
+
+    // `message` may list alternatives under `oneOf`.
+    let messages: Vec<&Value> = messages
+        .iter()
+        .flat_map(
+            |message| match message.get("oneOf").and_then(Value::as_array) {
+                Some(options) => options.iter().collect(),
+                None => vec![*message],
+            },
+        )
+        .collect();
+    let cloudevents_type = messages
+        .iter()
+        .find_map(|message| cloudevents_type(spec.document, message))
+        .or_else(|| operation.get("x-cloudevents-type").and_then(Value::as_str));
+
+    let mut targets = vec![(channel_key.clone(), address)];
+    if let Some(kind) = cloudevents_type
+        && let Some(key) = channel::normalize_channel_name(kind)
+        && key != channel_key
+    {
+        targets.push((key, kind));
+    }
+    for (target, raw) in targets {
+        let detail = json!({
+            "channel": target,
+            "raw": raw,
+            "framework": ASYNCAPI_FRAMEWORK,
+            "role": action.role(),
+            "service": spec.service,
+            "cloudevents_type": cloudevents_type,
+        });
+        output.edges.push(EdgeInput {
+            kind: action.edge_kind().to_string(),
+            source_qualname: Some(qualname.clone()),
+            target_qualname: Some(target),
+            detail: Some(detail.to_string()),
+            evidence_start_line: Some(span.0),
+            evidence_end_line: Some(span.0),
+            ..Default::default()
+        });
+    }
+    push_type_refs(operation, &qualname, "message", span, module_name, output);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(text: &str) -> ExtractedFile {
+        let value: serde_yaml_ng::Value = serde_yaml_ng::from_str(text).unwrap();
+        let value = serde_json::to_value(value).unwrap();
+        assert!(is_asyncapi(&value));
+        let source = SpecSource {
+            text,
+            line_offset: 1,
+            byte_offset: 0,
+        };
+        let mut output = ExtractedFile::default();
+        extract_spec(&value, &source, "specs/orders", &mut output);
+        output
+    }
+
+    fn channel_edges(output: &ExtractedFile) -> Vec<(&str, &str, &str)> {
+        output
+            .edges
+            .iter()
+            .filter(|edge| edge.kind.starts_with("CHANNEL_"))
+            .map(|edge| {
+                (
+                    edge.kind.as_str(),
+                    edge.source_qualname.as_deref().unwrap(),
+                    edge.target_qualname.as_deref().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    const V2: &str = r##"asyncapi: 2.6.0
+info:
+  title: Orders
+channels:
+  order_created:
+    subscribe:
+      operationId: publishOrderCreated
+      message:
+        $ref: "#/components/messages/OrderCreated"
+    publish:
+      message:
+        payload:
+          type: string
+components:
+  messages:
+    OrderCreated:
+      x-cloudevents-type: com.acme.order.created
+      payload:
+        $ref: "#/components/schemas/Order"
+  schemas:
+    Order:
+      type: object
+      properties:
+        id:
+          type: string
+"##;
+
+    #[test]
+    fn extracts_v2_channels_operations_and_messages() {
+        let output = extract(V2);
+        let symbol = |qualname: &str| {
+            output
+                .symbols
+                .iter()
+                .find(|symbol| symbol.qualname == qualname)
+                .unwrap_or_else(|| panic!("missing {qualname}"))
+        };
+        assert_eq!(symbol("channel://ordercreated").kind, "channel");
+        assert_eq!(symbol("channel://ordercreated").name, "order_created");
+        let operation = symbol("specs/orders#publishOrderCreated");
+        assert_eq!(operation.kind, "operation");
+        assert_eq!(operation.signature.as_deref(), Some("send order_created"));
+        assert_eq!(operation.start_line, 6);
+        assert_eq!(
+            symbol("specs/orders#receive order_created")
+                .signature
+                .as_deref(),
+            Some("receive order_created")
+        );
+        assert_eq!(
+            symbol("specs/orders#/components/messages/OrderCreated")
+                .signature
+                .as_deref(),
+            Some("Order")
+        );
+        symbol("specs/orders#/components/schemas/Order.id");
+
+        assert_eq!(
+            channel_edges(&output),
+            vec![
+                (
+                    CHANNEL_PUBLISH_KIND,
+                    "specs/orders#publishOrderCreated",
+                    "channel://ordercreated"
+                ),
+                (
+                    CHANNEL_PUBLISH_KIND,
+                    "specs/orders#publishOrderCreated",
+                    "channel://com.acme.order.created"
+                ),
+                (
+                    CHANNEL_SUBSCRIBE_KIND,
+                    "specs/orders#receive order_created",
+                    "channel://ordercreated"
+                ),
+            ]
+        );
+        assert!(output.edges.iter().any(|edge| edge.kind == "TYPE_REF"
+            && edge.source_qualname.as_deref()
+                == Some("specs/orders#/components/messages/OrderCreated")
+            && edge.target_qualname.as_deref() == Some("specs/orders#/components/schemas/Order")));
+    }
+
+    const V3: &str = r##"asyncapi: 3.0.0
+info:
+  title: Billing
+channels:
+  invoices:
+    address: billing/invoices
+    messages:
+      InvoiceIssued:
+        $ref: "#/components/messages/InvoiceIssued"
+operations:
+  onInvoiceIssued:
+    action: receive
+    channel:
+      $ref: "#/channels/invoices"
+components:
+  messages:
+    InvoiceIssued:
+      headers:
+        type: object
+        properties:
+          ce_type:
+            const: com.acme.invoice.issued
+"##;
+
+    #[test]
+    fn extracts_v3_operations_by_channel_reference() {
+        let output = extract(V3);
+        assert_eq!(
+            channel_edges(&output),
+            vec![
+                (
+                    CHANNEL_SUBSCRIBE_KIND,
+                    "specs/orders#onInvoiceIssued",
+                    "channel://billing/invoices"
+                ),
+                (
+                    CHANNEL_SUBSCRIBE_KIND,
+                    "specs/orders#onInvoiceIssued",
+                    "channel://com.acme.invoice.issued"
+                ),
+            ]
+        );
+        let detail: Value = serde_json::from_str(
+            output
+                .edges
+                .iter()
+                .find(|edge| edge.kind == CHANNEL_SUBSCRIBE_KIND)
+                .unwrap()
+                .detail
+                .as_deref()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(detail["service"], "Billing");
+        assert_eq!(detail["framework"], ASYNCAPI_FRAMEWORK);
+        assert_eq!(detail["cloudevents_type"], "com.acme.invoice.issued");
+    }
+}
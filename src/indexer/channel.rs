@@ -1,9 +1,11 @@
+use crate::indexer::rules;
 use serde_json::json;
 
 pub const CHANNEL_PUBLISH_KIND: &str = "CHANNEL_PUBLISH";
 pub const CHANNEL_SUBSCRIBE_KIND: &str = "CHANNEL_SUBSCRIBE";
 
 /// Known topic container prefixes (C# class names, Python enum names, etc.)
+/// Extended per project by `[[channels]]` rules in `.lidx.toml`.
 const TOPIC_CONTAINERS: &[&str] = &[
     "Topics",
     "TopicName",
@@ -71,23 +73,28 @@ const SUBSCRIBE_METHODS: &[&str] = &[
 
 /// Normalize a channel/topic name to a canonical form.
 ///
-/// Strips the container prefix (Topics., TopicName., etc.), removes underscores,
-/// and lowercases everything so that C# PascalCase and Python SCREAMING_SNAKE
-/// produce identical keys.
+/// Strips string quotes and the container prefix (Topics., TopicName., etc.),
+/// removes underscores, and lowercases everything so that C# PascalCase and
+/// Python SCREAMING_SNAKE produce identical keys.
 ///
 /// # Examples
 /// - `Topics.OrchestratorTriggers` → `channel://orchestratortriggers`
 /// - `TopicName.ORCHESTRATOR_TRIGGERS` → `channel://orchestratortriggers`
 /// - `Topics.DataProxyCommands` → `channel://dataproxycommands`
 /// - `DATAPROXY_COMMANDS` → `channel://dataproxycommands`
+///
+/// Topic rules from `.lidx.toml` (prefix/suffix stripping, aliases) are
+/// applied when active.
 pub fn normalize_channel_name(raw: &str) -> Option<String> {
-    let trimmed = raw.trim();
+    let trimmed = raw.trim().trim_matches(['"', '\'', '`']);
     if trimmed.is_empty() {
         return None;
     }
 
     // Strip known container prefix (Topics.X → X)
     let topic_part = strip_topic_container(trimmed);
+    let topic_part =
+        rules::with_active(|rules, _| rules.strip_topic(topic_part)).unwrap_or(topic_part);
     if topic_part.is_empty() {
         return None;
     }
@@ -102,6 +109,9 @@ pub fn normalize_channel_name(raw: &str) -> Option<String> {
     if normalized.is_empty() {
         return None;
     }
+    let normalized = rules::with_active(|rules, _| rules.topic_alias(&normalized))
+        .flatten()
+        .unwrap_or(normalized);
 
     Some(format!("channel://{normalized}"))
 }
@@ -111,7 +121,10 @@ pub fn normalize_channel_name(raw: &str) -> Option<String> {
 fn strip_topic_container(raw: &str) -> &str {
     if let Some((prefix, suffix)) = raw.split_once('.') {
         let prefix = prefix.rsplit('.').next().unwrap_or(prefix);
-        if TOPIC_CONTAINERS.contains(&prefix) {
+        if TOPIC_CONTAINERS.contains(&prefix)
+            || rules::with_active(|rules, language| rules.is_topic_container(language, prefix))
+                .unwrap_or(false)
+        {
             return suffix;
         }
     }
@@ -125,16 +138,22 @@ pub fn is_bus_receiver(receiver: &str) -> bool {
         return false;
     }
     BUS_RECEIVER_PATTERNS.contains(&last)
+        || rules::with_active(|rules, language| rules.is_bus_receiver(language, last))
+            .unwrap_or(false)
 }
 
 /// Check if a method name is a publish method.
 pub fn is_publish_method(name: &str) -> bool {
     PUBLISH_METHODS.contains(&name)
+        || rules::with_active(|rules, language| rules.is_publish_method(language, name))
+            .unwrap_or(false)
 }
 
 /// Check if a method name is a subscribe method.
 pub fn is_subscribe_method(name: &str) -> bool {
     SUBSCRIBE_METHODS.contains(&name)
+        || rules::with_active(|rules, language| rules.is_subscribe_method(language, name))
+            .unwrap_or(false)
 }
 
 /// Check if a raw topic value looks like a topic container member access.
@@ -172,6 +191,8 @@ pub fn bridge_complement(kind: &str) -> Option<&'static [&'static str]> {
         "RPC_IMPL" => Some(&["RPC_CALL"]),
         "HTTP_CALL" => Some(&["HTTP_ROUTE"]),
         "HTTP_ROUTE" => Some(&["HTTP_CALL"]),
+        "GRAPHQL_CALL" => Some(&["GRAPHQL_RESOLVE"]),
+        "GRAPHQL_RESOLVE" => Some(&["GRAPHQL_CALL"]),
         "CONFIG_SOURCE" => Some(&["CONFIG_READ"]),
         "CONFIG_READ" => Some(&["CONFIG_SOURCE"]),
         _ => None,
@@ -179,7 +200,7 @@ pub fn bridge_complement(kind: &str) -> Option<&'static [&'static str]> {
 }
 
 /// Returns true if the edge kind represents a cross-service/cross-language bridge
-/// (RPC, HTTP, message bus, config, XREF) where cross-language resolution is expected.
+/// (RPC, HTTP, GraphQL, message bus, config, XREF) where cross-language resolution is expected.
 pub fn is_bridge_edge_kind(kind: &str) -> bool {
     matches!(
         kind,
@@ -188,6 +209,8 @@ pub fn is_bridge_edge_kind(kind: &str) -> bool {
             | "RPC_ROUTE"
             | "HTTP_ROUTE"
             | "HTTP_CALL"
+            | "GRAPHQL_CALL"
+            | "GRAPHQL_RESOLVE"
             | "CHANNEL_PUBLISH"
             | "CHANNEL_SUBSCRIBE"
             | "CONFIG_SOURCE"
@@ -203,6 +226,7 @@ pub fn boundary_type_for_kind(kind: &str) -> &'static str {
         "CHANNEL_PUBLISH" | "CHANNEL_SUBSCRIBE" => "message_bus",
         "RPC_CALL" | "RPC_IMPL" | "RPC_ROUTE" => "grpc",
         "HTTP_CALL" | "HTTP_ROUTE" => "http",
+        "GRAPHQL_CALL" | "GRAPHQL_RESOLVE" => "graphql",
         "CONFIG_SOURCE" | "CONFIG_READ" => "config",
         _ => "other",
     }
@@ -252,6 +276,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn normalize_string_literal() {
+        assert_eq!(
+            normalize_channel_name("\"ORDER_CREATED\""),
+            Some("channel://ordercreated".to_string())
+        );
+    }
+
     #[test]
     fn normalize_empty() {
         assert_eq!(normalize_channel_name(""), None);
@@ -313,6 +345,14 @@ mod tests {
             bridge_complement("CONFIG_READ"),
             Some(&["CONFIG_SOURCE"] as &[&str])
         );
+        assert_eq!(
+            bridge_complement("GRAPHQL_CALL"),
+            Some(&["GRAPHQL_RESOLVE"] as &[&str])
+        );
+        assert_eq!(
+            bridge_complement("GRAPHQL_RESOLVE"),
+            Some(&["GRAPHQL_CALL"] as &[&str])
+        );
         assert_eq!(bridge_complement("CALLS"), None);
     }
 }
@@ -0,0 +1,196 @@
+//! docker-compose files.
+//!
+//! Each service becomes a `service` symbol (`compose://deploy/web`, scoped by
+//! the compose file's directory, which is the compose project). `environment`
+//! entries emit CONFIG_SOURCE to `env://NAME`, `depends_on` emits DEPENDS_ON
+//! between services and `build` emits IMPORTS_FILE to the Dockerfile, which
+//! the YAML extractor resolves once the file is known to exist.
+
+use crate::indexer::config::{self, CONFIG_SOURCE_KIND};
+use crate::indexer::extract::{EdgeInput, ExtractedFile};
+use crate::indexer::openapi::{SpecLines, SpecSource, contains_edge, spec_symbol};
+use crate::indexer::yaml;
+use serde_json::{Value, json};
+
+/// A top-level `services` mapping of service mappings, and no Kubernetes
+/// `apiVersion`.
+pub fn is_compose(value: &Value) -> bool {
+    if value.get("apiVersion").is_some() {
+        return false;
+    }
+    let Some(services) = value.get("services").and_then(Value::as_object) else {
+        return false;
+    };
+    !services.is_empty()
+        && services.values().all(|service| {
+            service.as_object().is_some_and(|service| {
+                ["image", "build", "environment", "command", "ports"]
+                    .iter()
+                    .any(|key| service.contains_key(*key))
+            })
+        })
+}
+
+/// `compose://{dir}/{service}`; the directory comes from the module name
+/// (`deploy/docker-compose` → `deploy`).
+pub fn service_qualname(module_name: &str, service: &str) -> String {
+    match module_name.rsplit_once('/') {
+        Some((dir, _)) => format!("compose://{dir}/{service}"),
+        None => format!("compose://{service}"),
+    }
+}
+
+pub fn extract_compose(
+    value: &Value,
+    source: &SpecSource<'_>,
+    module_name: &str,
+    output: &mut ExtractedFile,
+) {
+    let Some(services) = value.get("services").and_then(Value::as_object) else {
+        return;
+    };
+    let lines = SpecLines::new(source);
+    for (name, service) in services {
+        let qualname = service_qualname(module_name, name);
+        let span = lines.span(lines.find_path(&["services", name]));
+        let (start_line, end_line, _, _) = span;
+        let mut symbol = spec_symbol("service", name, &qualname, span);
+        symbol.signature = service_signature(service);
+        let ports = ports(service);
+        if !ports.is_empty() {
+            symbol.docstring = Some(format!("ports: {}", ports.join(", ")));
+        }
+        output.symbols.push(symbol);
+        output.edges.push(contains_edge(module_name, &qualname));
+
+        for env_name in environment_names(service.get("environment")) {
+            let Some(env_uri) = config::normalize_env_var_name(&env_name) else {
+                continue;
+            };
+            let detail = config::build_config_source_detail(
+                "env",
+                &env_uri,
+                &env_name,
+                Some(&json!({ "provider": "docker-compose", "service": name })),
+            );
+            output.edges.push(EdgeInput {
+                kind: CONFIG_SOURCE_KIND.to_string(),
+                source_qualname: Some(qualname.clone()),
+                target_qualname: Some(env_uri),
+                detail: Some(detail),
+                evidence_start_line: Some(start_line),
+                evidence_end_line: Some(end_line),
+                ..Default::default()
+            });
+            yaml::emit_section_prefix_edges(&qualname, &env_name, start_line, end_line, output);
+        }
+
+        for dependency in depends_on(service.get("depends_on")) {
+            output.edges.push(EdgeInput {
+                kind: "DEPENDS_ON".to_string(),
+                source_qualname: Some(qualname.clone()),
+                target_qualname: Some(service_qualname(module_name, &dependency)),
+                evidence_start_line: Some(start_line),
+                evidence_end_line: Some(end_line),
+                ..Default::default()
+            });
+        }
+
+        if let Some(dockerfile) = build_dockerfile(service.get("build")) {
+            output.edges.push(EdgeInput {
+                kind: "IMPORTS_FILE".to_string(),
+                source_qualname: Some(qualname.clone()),
+                target_qualname: Some(dockerfile),
+                evidence_start_line: Some(start_line),
+                evidence_end_line: Some(end_line),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+fn service_signature(service: &Value) -> Option<String> {
+    if let Some(image) = service.get("image").and_then(Value::as_str) {
+        return Some(image.to_string());
+    }
+    match service.get("build")? {
+        Value::String(context) => Some(format!("build {context}")),
+        build => build
+            .get("context")
+            .and_then(Value::as_str)
+            .map(|context| format!("build {context}")),
+    }
+}
+
+/// `context/dockerfile` of a `build` entry, relative to the compose file.
+fn build_dockerfile(build: Option<&Value>) -> Option<String> {
+    let (context, dockerfile) = match build? {
+        Value::String(context) => (context.as_str(), "Dockerfile"),
+        build => (
+            build.get("context").and_then(Value::as_str).unwrap_or("."),
+            build
+                .get("dockerfile")
+                .and_then(Value::as_str)
+                .unwrap_or("Dockerfile"),
+        ),
+    };
+    if context.contains("://") {
+        return None;
+    }
+    Some(format!("{}/{dockerfile}", context.trim_end_matches('/')))
+}
+
+/// Names set by `environment`, in list (`NAME=value`, `NAME`) or map form.
+fn environment_names(environment: Option<&Value>) -> Vec<String> {
+    match environment {
+        Some(Value::Object(map)) => map.keys().cloned().collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|item| item.split_once('=').map_or(item, |(name, _)| name))
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Services named by `depends_on`, in list or map (`condition:`) form.
+fn depends_on(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Object(map)) => map.keys().cloned().collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Published ports, short (`"8080:80"`) or long (`target`/`published`) form.
+fn ports(service: &Value) -> Vec<String> {
+    let Some(items) = service.get("ports").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Value::String(port) => Some(port.clone()),
+            Value::Number(port) => Some(port.to_string()),
+            Value::Object(_) => {
+                let target = item.get("target")?;
+                Some(match item.get("published") {
+                    Some(published) => format!(
+                        "{}:{target}",
+                        published
+                            .as_str()
+                            .map_or(published.to_string(), str::to_string)
+                    ),
+                    None => target.to_string(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
@@ -1,8 +1,10 @@
 use crate::indexer::channel;
 use crate::indexer::config;
 use crate::indexer::extract::{EdgeInput, ExtractedFile, SymbolInput};
+use crate::indexer::graphql;
 use crate::indexer::http;
 use crate::indexer::proto;
+use crate::indexer::rules;
 use crate::indexer::tree_helpers::{
     module_symbol_fallback, module_symbol_with_span, node_text, span,
 };
@@ -24,6 +26,8 @@ struct Context {
     route_groups: HashMap<String, String>,
     grpc_service: Option<String>,
     grpc_clients: HashMap<String, String>,
+    /// GraphQL type the current class resolves fields of (Hot Chocolate).
+    graphql_type: Option<String>,
 }
 
 pub struct CSharpExtractor {
@@ -70,12 +74,14 @@ impl crate::indexer::extract::LanguageExtractor for CSharpExtractor {
             route_groups: HashMap::new(),
             grpc_service: None,
             grpc_clients: HashMap::new(),
+            graphql_type: None,
         };
         if root.kind() == "compilation_unit" {
             walk_compilation_unit(root, &ctx, source, &mut output);
         } else {
             walk_node(root, &ctx, source, &mut output);
         }
+        output.tree = Some(tree);
         Ok(output)
     }
 }
@@ -336,6 +342,7 @@ fn handle_type(
     next_ctx.current_scope = qualname;
     next_ctx.route_prefix = combined_prefix;
     next_ctx.grpc_service = grpc_service;
+    next_ctx.graphql_type = graphql_type_from_attributes(node, &next_ctx.type_stack, source);
     if let Some(body) = node.child_by_field_name("body") {
         walk_declaration_list(body, &next_ctx, source, output);
     }
@@ -379,6 +386,9 @@ fn handle_method(node: Node<'_>, ctx: &Context, source: &str, output: &mut Extra
     for edge in route_edges_from_method_attributes(node, ctx, source, &qualname) {
         output.edges.push(edge);
     }
+    if let Some(edge) = graphql_resolve_edge(node, ctx, source, &name, &qualname) {
+        output.edges.push(edge);
+    }
     if let Some(body) = node.child_by_field_name("body") {
         let mut next_ctx = ctx.clone();
         next_ctx.fn_depth += 1;
@@ -776,6 +786,111 @@ fn route_prefix_from_attributes(node: Node<'_>, source: &str) -> Option<String>
     None
 }
 
+/// GraphQL type a Hot Chocolate class contributes fields to:
+/// `[QueryType]`, `[ExtendObjectType("Query")]`,
+/// `[ExtendObjectType(OperationTypeNames.Mutation)]`,
+/// `[ExtendObjectType(typeof(User))]`, `[ObjectType<User>]`, or a class
+/// named `Query`, `Mutation` or `Subscription`.
+fn graphql_type_from_attributes(
+    node: Node<'_>,
+    type_stack: &[String],
+    source: &str,
+) -> Option<String> {
+    for attr in attributes_for_node(node, source) {
+        if let Some(generic) = extract_generic_type_arg(&attr.name) {
+            let base = attr.name.split('<').next().unwrap_or(&attr.name);
+            if matches!(
+                normalize_attribute_name(base).as_str(),
+                "ExtendObjectType" | "ObjectType"
+            ) {
+                return Some(last_type_segment(&generic));
+            }
+            continue;
+        }
+        match normalize_attribute_name(&attr.name).as_str() {
+            "QueryType" => return Some("Query".to_string()),
+            "MutationType" => return Some("Mutation".to_string()),
+            "SubscriptionType" => return Some("Subscription".to_string()),
+            "ExtendObjectType" | "ObjectType" => {
+                if let Some(value) = attribute_first_string_arg(&attr, source) {
+                    return Some(value);
+                }
+                let arg = attr.args.first()?;
+                let text = node_text(*arg, source);
+                let text = text
+                    .strip_prefix("typeof(")
+                    .and_then(|rest| rest.strip_suffix(')'))
+                    .unwrap_or(&text);
+                return Some(last_type_segment(text));
+            }
+            _ => {}
+        }
+    }
+    let name = type_stack.last()?;
+    graphql::ROOT_TYPES
+        .contains(&name.as_str())
+        .then(|| name.clone())
+}
+
+fn last_type_segment(text: &str) -> String {
+    text.rsplit('.').next().unwrap_or(text).trim().to_string()
+}
+
+/// GRAPHQL_RESOLVE from a public method of a Hot Chocolate type. Like Hot
+/// Chocolate, the field name drops a `Get` prefix and an `Async` suffix
+/// unless `[GraphQLName]` names it.
+fn graphql_resolve_edge(
+    node: Node<'_>,
+    ctx: &Context,
+    source: &str,
+    name: &str,
+    qualname: &str,
+) -> Option<EdgeInput> {
+    let type_name = ctx.graphql_type.as_deref()?;
+    let mut cursor = node.walk();
+    let is_public = node
+        .children(&mut cursor)
+        .any(|child| child.kind() == "modifier" && node_text(child, source) == "public");
+    if !is_public {
+        return None;
+    }
+    let attrs = attributes_for_node(node, source);
+    if attrs
+        .iter()
+        .any(|attr| normalize_attribute_name(&attr.name) == "GraphQLIgnore")
+    {
+        return None;
+    }
+    let field = attrs
+        .iter()
+        .filter(|attr| normalize_attribute_name(&attr.name) == "GraphQLName")
+        .find_map(|attr| attribute_first_string_arg(attr, source))
+        .unwrap_or_else(|| {
+            let field = name.strip_suffix("Async").unwrap_or(name);
+            match field.strip_prefix("Get") {
+                Some(rest) if rest.starts_with(|ch: char| ch.is_ascii_uppercase()) => {
+                    rest.to_string()
+                }
+                _ => field.to_string(),
+            }
+        });
+    let key = graphql::field_key(type_name, &field)?;
+    let (start_line, _, end_line, _, _, _) = span(node);
+    Some(EdgeInput {
+        kind: graphql::GRAPHQL_RESOLVE_KIND.to_string(),
+        source_qualname: Some(qualname.to_string()),
+        target_qualname: Some(key),
+        detail: Some(graphql::build_resolve_detail(
+            type_name,
+            &field,
+            "hotchocolate",
+        )),
+        evidence_start_line: Some(start_line),
+        evidence_end_line: Some(end_line),
+        ..Default::default()
+    })
+}
+
 fn combine_route_prefix(prefix: Option<&str>, next: Option<&str>) -> Option<String> {
     match (prefix, next) {
         (Some(prefix), Some(next)) => Some(http::join_paths(prefix, next)),
@@ -1800,6 +1915,9 @@ fn http_client_label(receiver: Option<&str>, full: &str) -> Option<&'static str>
     if receiver_lower.ends_with("client") || receiver_lower.contains("client") {
         return Some("http_client");
     }
+    if receiver.is_some_and(rules::active_http_client) {
+        return Some("custom");
+    }
     None
 }
 
@@ -1,7 +1,7 @@
 use crate::indexer::extract::SymbolInput;
 use crate::indexer::stable_id::compute_stable_symbol_id;
 use crate::model::Symbol;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Result of comparing old symbols (database) vs new symbols (just extracted)
 #[derive(Debug, Default, Clone)]
@@ -43,6 +43,13 @@ pub struct SymbolDiff {
 /// 3. For each old symbol:
 ///    - If stable_id not in new → deleted
 pub fn compute_symbol_diff(old_symbols: Vec<Symbol>, new_symbols: Vec<SymbolInput>) -> SymbolDiff {
+    // Keep stable_ids in extraction order so symbols are written in the same
+    // order on every run, and row ids are reproducible.
+    let old_order: Vec<String> = old_symbols
+        .iter()
+        .filter_map(|s| s.stable_id.clone())
+        .collect();
+
     // Build map of old symbols keyed by stable_id
     let old_map: HashMap<String, Symbol> = old_symbols
         .into_iter()
@@ -53,10 +60,12 @@ pub fn compute_symbol_diff(old_symbols: Vec<Symbol>, new_symbols: Vec<SymbolInpu
         .collect();
 
     // Build map of new symbols keyed by computed stable_id
+    let mut new_order = Vec::with_capacity(new_symbols.len());
     let new_map: HashMap<String, SymbolInput> = new_symbols
         .into_iter()
         .map(|s| {
             let stable_id = compute_stable_symbol_id(&s);
+            new_order.push(stable_id.clone());
             (stable_id, s)
         })
         .collect();
@@ -64,7 +73,12 @@ pub fn compute_symbol_diff(old_symbols: Vec<Symbol>, new_symbols: Vec<SymbolInpu
     let mut diff = SymbolDiff::default();
 
     // Find added, modified, and unchanged symbols
-    for (stable_id, new_sym) in &new_map {
+    let mut seen = HashSet::new();
+    for stable_id in &new_order {
+        if !seen.insert(stable_id) {
+            continue;
+        }
+        let new_sym = &new_map[stable_id];
         match old_map.get(stable_id) {
             None => {
                 // Symbol is new
@@ -82,9 +96,10 @@ pub fn compute_symbol_diff(old_symbols: Vec<Symbol>, new_symbols: Vec<SymbolInpu
     }
 
     // Find deleted symbols
-    for stable_id in old_map.keys() {
-        if !new_map.contains_key(stable_id) {
-            diff.deleted.push(stable_id.clone());
+    let mut seen = HashSet::new();
+    for stable_id in old_order {
+        if !new_map.contains_key(&stable_id) && seen.insert(stable_id.clone()) {
+            diff.deleted.push(stable_id);
         }
     }
 
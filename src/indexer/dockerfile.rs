@@ -0,0 +1,566 @@
+//! Dockerfiles.
+//!
+//! Each `FROM` starts a `stage` symbol (`Dockerfile#build`, or the stage
+//! index when it has no `AS` name). `ENV` emits CONFIG_SOURCE to
+//! `env://NAME`, `COPY`/`ADD` sources emit IMPORTS_FILE to the copied code
+//! files, and `ENTRYPOINT`/`CMD` emit an ENTRYPOINT edge to the module the
+//! container runs (`python -m app.main`, `node server.js`,
+//! `uvicorn app.api:app`). The build context is taken to be the Dockerfile's
+//! directory, which is what `docker build .` next to it uses.
+
+use crate::indexer::config::{self, CONFIG_SOURCE_KIND};
+use crate::indexer::extract::{EdgeInput, ExtractedFile, LanguageExtractor, SymbolInput};
+use crate::indexer::tree_helpers::module_symbol_fallback;
+use crate::indexer::yaml;
+use crate::util;
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::path::Path;
+
+pub const ENTRYPOINT_KIND: &str = "ENTRYPOINT";
+
+/// Interpreters whose first path argument is the program they run.
+const INTERPRETERS: &[&str] = &[
+    "python", "python3", "node", "deno", "bun", "ruby", "php", "java", "dotnet", "sh", "bash",
+];
+/// ASGI/WSGI servers taking a `module:app` argument.
+const APP_SERVERS: &[&str] = &["uvicorn", "gunicorn", "hypercorn", "daphne"];
+
+pub struct DockerfileExtractor;
+
+impl DockerfileExtractor {
+    pub fn new() -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+impl LanguageExtractor for DockerfileExtractor {
+    fn module_name_from_rel_path(&self, rel_path: &str) -> String {
+        module_name_from_rel_path(rel_path)
+    }
+
+    fn extract(&mut self, source: &str, module_name: &str) -> Result<ExtractedFile> {
+        let mut output = ExtractedFile::default();
+        output
+            .symbols
+            .push(module_symbol_fallback(module_name, source, "/", None));
+
+        let instructions = parse_instructions(source);
+        let mut stages: Vec<Stage> = Vec::new();
+        for instruction in &instructions {
+            if instruction.keyword == "FROM" {
+                if let Some(stage) = stages.last_mut() {
+                    stage.end_line = instruction.start_line - 1;
+                }
+                let args = without_flags(&instruction.args);
+                let image = args.first().cloned().unwrap_or_default();
+                let alias = match args.as_slice() {
+                    [_, as_kw, alias, ..] if as_kw.eq_ignore_ascii_case("as") => {
+                        Some(alias.clone())
+                    }
+                    _ => None,
+                };
+                let name = alias.clone().unwrap_or_else(|| stages.len().to_string());
+                stages.push(Stage {
+                    qualname: format!("{module_name}#{name}"),
+                    name,
+                    image,
+                    start_line: instruction.start_line,
+                    end_line: instruction.end_line,
+                    start_byte: instruction.start_byte,
+                    end_byte: instruction.end_byte,
+                    workdir: "/".to_string(),
+                    copies: Vec::new(),
+                    entrypoint: None,
+                    cmd: None,
+                    ports: Vec::new(),
+                    edges: Vec::new(),
+                });
+                continue;
+            }
+            let Some(stage) = stages.last_mut() else {
+                continue;
+            };
+            stage.end_line = instruction.end_line;
+            stage.end_byte = instruction.end_byte;
+            match instruction.keyword.as_str() {
+                "WORKDIR" => {
+                    if let Some(dir) = instruction.args.first() {
+                        stage.workdir = join_container_path(&stage.workdir, dir);
+                    }
+                }
+                "ENV" => {
+                    for name in env_names(&instruction.args) {
+                        stage.env_edge(&name, instruction.start_line, &mut output);
+                    }
+                }
+                "EXPOSE" => stage.ports.extend(instruction.args.iter().cloned()),
+                "ENTRYPOINT" => stage.entrypoint = Some(command_args(instruction)),
+                "CMD" => stage.cmd = Some(command_args(instruction)),
+                "COPY" | "ADD" => {
+                    let from = instruction
+                        .args
+                        .iter()
+                        .find_map(|arg| arg.strip_prefix("--from="))
+                        .map(str::to_string);
+                    let args = without_flags(&command_args(instruction));
+                    let Some((dest, sources)) = args.split_last() else {
+                        continue;
+                    };
+                    let dest = join_container_path(&stage.workdir, dest);
+                    if let Some(from) = from {
+                        stage.edges.push(EdgeInput {
+                            kind: "REFERENCES".to_string(),
+                            source_qualname: Some(stage.qualname.clone()),
+                            target_qualname: Some(format!("{module_name}#{from}")),
+                            evidence_start_line: Some(instruction.start_line),
+                            evidence_end_line: Some(instruction.end_line),
+                            ..Default::default()
+                        });
+                        continue;
+                    }
+                    for src in sources {
+                        stage.copies.push((src.clone(), dest.clone()));
+                        if src.contains(['*', '?', '$']) || src.contains("://") {
+                            continue;
+                        }
+                        stage.edges.push(EdgeInput {
+                            kind: "IMPORTS_FILE".to_string(),
+                            source_qualname: Some(stage.qualname.clone()),
+                            target_qualname: Some(src.clone()),
+                            detail: Some(json!({ "instruction": instruction.keyword }).to_string()),
+                            evidence_start_line: Some(instruction.start_line),
+                            evidence_end_line: Some(instruction.end_line),
+                            ..Default::default()
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (idx, stage) in stages.iter().enumerate() {
+            let mut docs = Vec::new();
+            let command = stage.command();
+            if !command.is_empty() {
+                docs.push(format!("entrypoint: {}", command.join(" ")));
+            }
+            if !stage.ports.is_empty() {
+                docs.push(format!("ports: {}", stage.ports.join(", ")));
+            }
+            output.symbols.push(SymbolInput {
+                kind: "stage".to_string(),
+                name: stage.name.clone(),
+                qualname: stage.qualname.clone(),
+                start_line: stage.start_line,
+                start_col: 1,
+                end_line: stage.end_line,
+                end_col: 1,
+                start_byte: stage.start_byte,
+                end_byte: stage.end_byte,
+                signature: Some(format!("FROM {}", stage.image)),
+                docstring: (!docs.is_empty()).then(|| docs.join("\n")),
+            });
+            output.edges.push(EdgeInput {
+                kind: "CONTAINS".to_string(),
+                source_qualname: Some(module_name.to_string()),
+                target_qualname: Some(stage.qualname.clone()),
+                ..Default::default()
+            });
+            if let Some(base) = stages[..idx].iter().find(|prev| prev.name == stage.image) {
+                output.edges.push(EdgeInput {
+                    kind: "EXTENDS".to_string(),
+                    source_qualname: Some(stage.qualname.clone()),
+                    target_qualname: Some(base.qualname.clone()),
+                    evidence_start_line: Some(stage.start_line),
+                    evidence_end_line: Some(stage.start_line),
+                    ..Default::default()
+                });
+            }
+            output.edges.extend(stage.edges.iter().cloned());
+            if let Some(edge) = stage.entrypoint_edge() {
+                output.edges.push(edge);
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn resolve_imports(
+        &self,
+        repo_root: &Path,
+        file_rel_path: &str,
+        _module_name: &str,
+        edges: &mut Vec<EdgeInput>,
+    ) {
+        let context = Path::new(file_rel_path)
+            .parent()
+            .and_then(|dir| dir.to_str())
+            .unwrap_or("");
+        edges.retain_mut(|edge| match edge.kind.as_str() {
+            "IMPORTS_FILE" => {
+                let Some(raw) = edge.target_qualname.clone() else {
+                    return false;
+                };
+                let Some((rel, module)) = resolve_context_file(repo_root, context, &raw) else {
+                    return false;
+                };
+                edge.target_qualname = Some(module);
+                edge.detail = Some(
+                    json!({
+                        "src_path": file_rel_path,
+                        "dst_path": rel,
+                        "raw": raw,
+                        "confidence": 1.0,
+                    })
+                    .to_string(),
+                );
+                true
+            }
+            ENTRYPOINT_KIND => {
+                let mut detail: Value = edge
+                    .detail
+                    .as_deref()
+                    .and_then(|d| serde_json::from_str(d).ok())
+                    .unwrap_or_default();
+                if edge.target_qualname.is_some() {
+                    return true;
+                }
+                let candidates: Vec<String> = detail["candidates"]
+                    .as_array()
+                    .map(|items| {
+                        items
+                            .iter()
+                            .filter_map(|item| item.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let Some((rel, module)) = candidates
+                    .iter()
+                    .find_map(|raw| resolve_context_file(repo_root, context, raw))
+                else {
+                    return false;
+                };
+                detail["dst_path"] = json!(rel);
+                edge.target_qualname = Some(module);
+                edge.detail = Some(detail.to_string());
+                true
+            }
+            _ => true,
+        });
+    }
+}
+
+/// Dockerfiles are named by path (`Dockerfile`, `Dockerfile.prod`,
+/// `api.dockerfile`), so the module name is the path itself.
+pub fn module_name_from_rel_path(rel_path: &str) -> String {
+    rel_path.to_string()
+}
+
+/// `Dockerfile`, `Dockerfile.*`, `*.dockerfile` and `Containerfile`.
+pub fn is_dockerfile_name(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    lower == "dockerfile"
+        || lower == "containerfile"
+        || lower.starts_with("dockerfile.")
+        || lower.ends_with(".dockerfile")
+}
+
+/// `(rel_path, module)` of an indexed file at `raw` inside the build context.
+pub(crate) fn resolve_context_file(
+    repo_root: &Path,
+    context: &str,
+    raw: &str,
+) -> Option<(String, String)> {
+    let raw = raw.trim_start_matches("./");
+    let joined = if context.is_empty() {
+        raw.to_string()
+    } else {
+        format!("{context}/{raw}")
+    };
+    let rel = util::collapse_parent_dirs(&joined)?;
+    if !repo_root.join(&rel).is_file() {
+        return None;
+    }
+    let module = crate::indexer::module_name_for_path(&rel)?;
+    Some((rel, module))
+}
+
+/// Absolute container path of `path` relative to `workdir`.
+fn join_container_path(workdir: &str, path: &str) -> String {
+    let joined = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("{}/{path}", workdir.trim_end_matches('/'))
+    };
+    format!(
+        "/{}",
+        util::collapse_parent_dirs(&joined).unwrap_or_default()
+    )
+}
+
+struct Stage {
+    name: String,
+    qualname: String,
+    image: String,
+    start_line: i64,
+    end_line: i64,
+    start_byte: i64,
+    end_byte: i64,
+    workdir: String,
+    /// `(source, absolute destination)` of each `COPY`.
+    copies: Vec<(String, String)>,
+    entrypoint: Option<Vec<String>>,
+    cmd: Option<Vec<String>>,
+    ports: Vec<String>,
+    edges: Vec<EdgeInput>,
+}
+
+impl Stage {
+    fn env_edge(&self, name: &str, line: i64, output: &mut ExtractedFile) {
+        let Some(env_uri) = config::normalize_env_var_name(name) else {
+            return;
+        };
+        let detail = config::build_config_source_detail(
+            "env",
+            &env_uri,
+            name,
+            Some(&json!({ "provider": "dockerfile" })),
+        );
+        output.edges.push(EdgeInput {
+            kind: CONFIG_SOURCE_KIND.to_string(),
+            source_qualname: Some(self.qualname.clone()),
+            target_qualname: Some(env_uri),
+            detail: Some(detail),
+            evidence_start_line: Some(line),
+            evidence_end_line: Some(line),
+            ..Default::default()
+        });
+        yaml::emit_section_prefix_edges(&self.qualname, name, line, line, output);
+    }
+
+    /// Effective command: `ENTRYPOINT` followed by `CMD` as its arguments.
+    fn command(&self) -> Vec<String> {
+        let mut command = self.entrypoint.clone().unwrap_or_default();
+        command.extend(self.cmd.clone().unwrap_or_default());
+        command
+    }
+
+    /// ENTRYPOINT edge to the module the command runs. Python modules are
+    /// targeted directly; script paths are mapped back through `COPY` and
+    /// resolved against the build context in `resolve_imports`.
+    fn entrypoint_edge(&self) -> Option<EdgeInput> {
+        let command = self.command();
+        let program = command.first()?;
+        let program_name = program.rsplit('/').next().unwrap_or(program);
+        let mut module = None;
+        let mut script = None;
+        let mut args = command.iter().skip(1).peekable();
+        while let Some(arg) = args.next() {
+            if arg == "-m" && program_name.starts_with("python") {
+                module = args.next().cloned();
+                break;
+            }
+            if APP_SERVERS.contains(&program_name)
+                && let Some((target, _)) = arg.split_once(':')
+                && !arg.starts_with('-')
+                && target
+                    .chars()
+                    .all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '.')
+            {
+                module = Some(target.to_string());
+                break;
+            }
+            if INTERPRETERS
+                .iter()
+                .any(|name| program_name.starts_with(name))
+                && !arg.starts_with('-')
+            {
+                script = Some(arg.clone());
+                break;
+            }
+        }
+        if module.is_none() && script.is_none() && program.contains('.') {
+            script = Some(program.clone());
+        }
+
+        let mut detail = json!({ "command": command, "stage": self.name });
+        if let Some(script) = &script {
+            detail["candidates"] = json!(self.script_candidates(script));
+        }
+        if module.is_none() && script.is_none() {
+            return None;
+        }
+        Some(EdgeInput {
+            kind: ENTRYPOINT_KIND.to_string(),
+            source_qualname: Some(self.qualname.clone()),
+            target_qualname: module,
+            detail: Some(detail.to_string()),
+            evidence_start_line: Some(self.start_line),
+            evidence_end_line: Some(self.end_line),
+            ..Default::default()
+        })
+    }
+
+    /// Build-context paths a container path may have been copied from.
+    fn script_candidates(&self, script: &str) -> Vec<String> {
+        let absolute = join_container_path(&self.workdir, script);
+        let mut candidates = Vec::new();
+        for (src, dest) in self.copies.iter().rev() {
+            let src = src.trim_end_matches('/');
+            if absolute == *dest {
+                candidates.push(src.to_string());
+            } else if let Some(rest) =
+                absolute.strip_prefix(&format!("{}/", dest.trim_end_matches('/')))
+            {
+                if src == "." {
+                    candidates.push(rest.to_string());
+                } else {
+                    candidates.push(format!("{src}/{rest}"));
+                }
+            }
+        }
+        candidates.push(script.trim_start_matches('/').to_string());
+        candidates.dedup();
+        candidates
+    }
+}
+
+struct Instruction {
+    keyword: String,
+    /// Whitespace-separated arguments, or the JSON array in exec form.
+    args: Vec<String>,
+    /// Raw argument text.
+    raw: String,
+    start_line: i64,
+    end_line: i64,
+    start_byte: i64,
+    end_byte: i64,
+}
+
+/// Instructions with `\` continuations joined and comments dropped.
+fn parse_instructions(source: &str) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut current: Option<(String, i64, i64)> = None;
+    let mut offset = 0i64;
+    for (idx, line) in source.split('\n').enumerate() {
+        let line_no = idx as i64 + 1;
+        let line_start = offset;
+        offset += line.len() as i64 + 1;
+        let trimmed = line.trim();
+        if current.is_none() && (trimmed.is_empty() || trimmed.starts_with('#')) {
+            continue;
+        }
+        if current.is_some() && trimmed.starts_with('#') {
+            continue;
+        }
+        let (text, start_line, start_byte) =
+            current.get_or_insert_with(|| (String::new(), line_no, line_start));
+        let (body, continued) = match trimmed.strip_suffix('\\') {
+            Some(body) => (body, true),
+            None => (trimmed, false),
+        };
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(body.trim());
+        if continued {
+            continue;
+        }
+        let (start_line, start_byte) = (*start_line, *start_byte);
+        let text = std::mem::take(text);
+        current = None;
+        let (keyword, raw) = text.split_once(char::is_whitespace).unwrap_or((&text, ""));
+        let raw = raw.trim().to_string();
+        let args = match serde_json::from_str::<Vec<String>>(&raw) {
+            Ok(items) => items,
+            Err(_) => raw.split_whitespace().map(str::to_string).collect(),
+        };
+        instructions.push(Instruction {
+            keyword: keyword.to_ascii_uppercase(),
+            args,
+            raw,
+            start_line,
+            end_line: line_no,
+            start_byte,
+            end_byte: (line_start + line.len() as i64).min(source.len() as i64),
+        });
+    }
+    instructions
+}
+
+/// Arguments of a shell- or exec-form command.
+fn command_args(instruction: &Instruction) -> Vec<String> {
+    if instruction.raw.starts_with('[') {
+        return instruction.args.clone();
+    }
+    instruction
+        .args
+        .iter()
+        .map(|arg| arg.trim_matches(['"', '\'']).to_string())
+        .collect()
+}
+
+fn without_flags(args: &[String]) -> Vec<String> {
+    args.iter()
+        .filter(|arg| !arg.starts_with("--"))
+        .cloned()
+        .collect()
+}
+
+/// Names set by `ENV A=1 B="two words"` or the legacy `ENV A value`.
+fn env_names(args: &[String]) -> Vec<String> {
+    match args.first() {
+        Some(first) if !first.contains('=') => vec![first.clone()],
+        _ => args
+            .iter()
+            .filter_map(|arg| arg.split_once('='))
+            .map(|(name, _)| name.to_string())
+            .filter(|name| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continuation_lines_join() {
+        let instructions = parse_instructions(
+            "RUN apt-get update \\\n  # comment\n  && apt-get install -y curl\nCMD [\"node\", \"server.js\"]\n",
+        );
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[0].end_line, 3);
+        assert_eq!(instructions[1].args, vec!["node", "server.js"]);
+    }
+
+    #[test]
+    fn env_names_both_forms() {
+        let args = |raw: &str| {
+            raw.split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            env_names(&args("PORT=8080 LOG_LEVEL=info")),
+            vec!["PORT", "LOG_LEVEL"]
+        );
+        assert_eq!(env_names(&args("PATH /usr/bin")), vec!["PATH"]);
+    }
+
+    #[test]
+    fn container_paths_follow_workdir() {
+        assert_eq!(
+            join_container_path("/app", "src/main.py"),
+            "/app/src/main.py"
+        );
+        assert_eq!(join_container_path("/app", "/srv/x"), "/srv/x");
+        assert_eq!(join_container_path("/", "."), "/");
+    }
+}
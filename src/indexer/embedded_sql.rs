@@ -0,0 +1,800 @@
+//! Table access from application code.
+//!
+//! Two sources are recognised:
+//! - SQL in string literals (`QueryAsync("SELECT ... FROM orders")`,
+//!   `cursor.execute("UPDATE orders SET ...")`, `db.Query(...)`): tables in
+//!   FROM/JOIN/USING are read, tables after INSERT INTO, UPDATE, DELETE FROM,
+//!   MERGE INTO and TRUNCATE are written.
+//! - ORM mappings: EF Core `DbSet<Order> Orders`, `[Table("orders")]` and
+//!   `.ToTable("orders")`, SQLAlchemy `__tablename__`, GORM `TableName()`.
+//!   A mapping both reads and writes its table.
+//!
+//! Edges go from the enclosing symbol (the mapped type for ORM models) to the
+//! table name as written. `Db::resolve_table_refs` later binds them to the
+//! table and view symbols of the SQL extractor.
+
+use crate::indexer::extract::{EdgeInput, SymbolInput};
+use crate::indexer::{rules, xref};
+use crate::util;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+
+pub const READS_TABLE_KIND: &str = "READS_TABLE";
+pub const WRITES_TABLE_KIND: &str = "WRITES_TABLE";
+
+const SQL_CONFIDENCE: f64 = 0.85;
+const ORM_CONFIDENCE: f64 = 0.8;
+const SQL_MAX_LEN: usize = 20_000;
+
+const TYPE_KINDS: &[&str] = &["class", "struct", "record"];
+
+/// Words that end a table reference or can never name a table.
+const RESERVED: &[&str] = &[
+    "AND",
+    "APPLY",
+    "AS",
+    "BY",
+    "CASE",
+    "CROSS",
+    "DEFAULT",
+    "DELETE",
+    "ELSE",
+    "END",
+    "EXCEPT",
+    "FETCH",
+    "FOR",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "INNER",
+    "INSERT",
+    "INTERSECT",
+    "INTO",
+    "JOIN",
+    "LATERAL",
+    "LEFT",
+    "LIMIT",
+    "NATURAL",
+    "NOT",
+    "NULL",
+    "OFFSET",
+    "ON",
+    "ONLY",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "OUTPUT",
+    "RETURNING",
+    "RIGHT",
+    "SELECT",
+    "SET",
+    "TABLE",
+    "THEN",
+    "UNION",
+    "UPDATE",
+    "USING",
+    "VALUES",
+    "WHEN",
+    "WHERE",
+    "WINDOW",
+    "WITH",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+impl Access {
+    pub fn edge_kind(self) -> &'static str {
+        match self {
+            Access::Read => READS_TABLE_KIND,
+            Access::Write => WRITES_TABLE_KIND,
+        }
+    }
+}
+
+pub fn is_table_edge_kind(kind: &str) -> bool {
+    kind == READS_TABLE_KIND || kind == WRITES_TABLE_KIND
+}
+
+fn scans_language(language: &str) -> bool {
+    matches!(
+        language,
+        "csharp"
+            | "python"
+            | "go"
+            | "javascript"
+            | "typescript"
+            | "tsx"
+            | "java"
+            | "kotlin"
+            | "rust"
+    )
+}
+
+/// Where in the file an access was found.
+#[derive(Clone, Copy)]
+struct Site {
+    start_byte: usize,
+    end_byte: usize,
+    start_line: i64,
+    end_line: i64,
+}
+
+impl Site {
+    fn at(source: &str, start_byte: usize, end_byte: usize) -> Self {
+        Self {
+            start_byte,
+            end_byte,
+            start_line: rules::line_at(source, start_byte),
+            end_line: rules::line_at(source, end_byte),
+        }
+    }
+}
+
+/// READS_TABLE / WRITES_TABLE edges for one file.
+pub fn table_edges(language: &str, source: &str, symbols: &[SymbolInput]) -> Vec<EdgeInput> {
+    if !scans_language(language) {
+        return Vec::new();
+    }
+    let mut out = TableEdges {
+        source,
+        edges: Vec::new(),
+        seen: HashSet::new(),
+    };
+    for literal in xref::scan_string_literals(source) {
+        if literal.text.len() > SQL_MAX_LEN {
+            continue;
+        }
+        let sql = literal.text.replace("\\\"", "\"");
+        let Some(statement) = sql_statement(&sql) else {
+            continue;
+        };
+        let Some(owner) = rules::enclosing_symbol(symbols, literal.start_line) else {
+            continue;
+        };
+        let site = Site {
+            start_byte: literal.start_byte as usize,
+            end_byte: literal.end_byte as usize,
+            start_line: literal.start_line,
+            end_line: literal.end_line,
+        };
+        for (table, access) in table_accesses(&sql) {
+            out.push(
+                owner,
+                &table,
+                access,
+                json!({ "via": "sql", "statement": statement }),
+                SQL_CONFIDENCE,
+                site,
+            );
+        }
+    }
+    match language {
+        "csharp" => ef_core_mappings(&mut out, symbols),
+        "python" => sqlalchemy_mappings(&mut out, symbols),
+        "go" => gorm_mappings(&mut out, symbols),
+        _ => {}
+    }
+    out.edges
+}
+
+struct TableEdges<'a> {
+    source: &'a str,
+    edges: Vec<EdgeInput>,
+    seen: HashSet<(String, Access, String)>,
+}
+
+impl TableEdges<'_> {
+    fn push(
+        &mut self,
+        owner: &str,
+        table: &str,
+        access: Access,
+        detail: Value,
+        confidence: f64,
+        site: Site,
+    ) {
+        if !self
+            .seen
+            .insert((owner.to_string(), access, table.to_lowercase()))
+        {
+            return;
+        }
+        self.edges.push(EdgeInput {
+            kind: access.edge_kind().to_string(),
+            source_qualname: Some(owner.to_string()),
+            target_qualname: Some(table.to_string()),
+            detail: Some(detail.to_string()),
+            evidence_snippet: util::edge_evidence_snippet(
+                self.source,
+                site.start_byte as i64,
+                site.end_byte as i64,
+                site.start_line,
+                site.end_line,
+            ),
+            evidence_start_line: Some(site.start_line),
+            evidence_end_line: Some(site.end_line),
+            confidence: Some(confidence),
+            ..Default::default()
+        });
+    }
+
+    /// A mapping reads and writes its table.
+    fn push_mapping(&mut self, owner: &str, table: &str, detail: Value, site: Site) {
+        for access in [Access::Read, Access::Write] {
+            self.push(owner, table, access, detail.clone(), ORM_CONFIDENCE, site);
+        }
+    }
+}
+
+/// The lowercased leading keyword when `text` reads like a SQL statement
+/// rather than prose that happens to start with "Update" or "Select".
+fn sql_statement(text: &str) -> Option<String> {
+    let text = text.trim_start_matches(['"', '\'', '`']).trim_start();
+    let first: String = text
+        .chars()
+        .take_while(|ch| ch.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    let words: HashSet<String> = text
+        .split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_')
+        .map(|word| word.to_ascii_uppercase())
+        .collect();
+    let needs: &[&str] = match first.as_str() {
+        "SELECT" | "DELETE" => &["FROM"],
+        "INSERT" => &["INTO", "VALUES"],
+        "UPDATE" => &["SET"],
+        "WITH" => &["AS"],
+        "MERGE" => &["USING"],
+        "TRUNCATE" => &[],
+        _ => return None,
+    };
+    if !needs.is_empty() && !needs.iter().any(|word| words.contains(*word)) {
+        return None;
+    }
+    Some(first.to_ascii_lowercase())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Punct(char),
+    Literal,
+}
+
+impl Token {
+    fn keyword(&self) -> Option<String> {
+        match self {
+            Token::Word(word) => Some(word.to_ascii_uppercase()),
+            _ => None,
+        }
+    }
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '@' | '$' | '#' | ':')
+}
+
+fn tokenize(sql: &str) -> Vec<Token> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        let next = chars.get(i + 1).copied();
+        if ch.is_whitespace() {
+            i += 1;
+        } else if ch == '-' && next == Some('-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if ch == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if ch == '\'' {
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '\'' {
+                    if chars.get(i + 1) == Some(&'\'') {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Literal);
+        } else if is_word_char(ch) || matches!(ch, '[' | '"' | '`') {
+            let mut word = String::new();
+            loop {
+                match chars.get(i).copied() {
+                    Some(open @ ('[' | '"' | '`')) => {
+                        let close = if open == '[' { ']' } else { open };
+                        word.push(open);
+                        i += 1;
+                        while i < chars.len() && chars[i] != close {
+                            word.push(chars[i]);
+                            i += 1;
+                        }
+                        if i < chars.len() {
+                            word.push(close);
+                            i += 1;
+                        }
+                    }
+                    _ => {
+                        while i < chars.len() && is_word_char(chars[i]) {
+                            word.push(chars[i]);
+                            i += 1;
+                        }
+                    }
+                }
+                if chars.get(i) == Some(&'.')
+                    && chars
+                        .get(i + 1)
+                        .is_some_and(|ch| is_word_char(*ch) || matches!(ch, '[' | '"' | '`'))
+                {
+                    word.push('.');
+                    i += 1;
+                    continue;
+                }
+                break;
+            }
+            tokens.push(Token::Word(word));
+        } else {
+            tokens.push(Token::Punct(ch));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+/// `schema.table` with quoting removed, or `None` for keywords, parameters,
+/// placeholders and other words that cannot name a table.
+fn table_name(token: Option<&Token>) -> Option<String> {
+    let Token::Word(word) = token? else {
+        return None;
+    };
+    if RESERVED.contains(&word.to_ascii_uppercase().as_str()) {
+        return None;
+    }
+    let mut segments = Vec::new();
+    for segment in word.split('.') {
+        let segment = segment.trim_matches(['[', ']', '"', '`']);
+        let mut chars = segment.chars();
+        let first = chars.next()?;
+        if !(first.is_alphabetic() || first == '_') {
+            return None;
+        }
+        if !chars.all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$') {
+            return None;
+        }
+        segments.push(segment);
+    }
+    Some(segments.join("."))
+}
+
+/// Tables read and written by a SQL statement, in order of appearance.
+/// Names are returned as written, without quoting; CTE names are skipped.
+pub fn table_accesses(sql: &str) -> Vec<(String, Access)> {
+    let tokens = tokenize(sql);
+    let keyword = |idx: usize| tokens.get(idx).and_then(Token::keyword);
+
+    let mut ctes = HashSet::new();
+    for idx in 0..tokens.len() {
+        if keyword(idx + 1).as_deref() == Some("AS")
+            && tokens.get(idx + 2) == Some(&Token::Punct('('))
+            && let Some(name) = table_name(tokens.get(idx))
+        {
+            ctes.insert(name.to_ascii_lowercase());
+        }
+    }
+
+    let mut out: Vec<(String, Access)> = Vec::new();
+    let mut add = |name: Option<String>, access: Access| {
+        if let Some(name) = name
+            && !ctes.contains(&name.to_ascii_lowercase())
+            && !out.contains(&(name.clone(), access))
+        {
+            out.push((name, access));
+        }
+    };
+    // Whether each open parenthesis starts a subquery; FROM inside function
+    // calls such as EXTRACT(YEAR FROM ts) is not a table reference.
+    let mut parens: Vec<bool> = Vec::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        match &tokens[idx] {
+            Token::Punct('(') => {
+                let subquery = matches!(keyword(idx + 1).as_deref(), Some("SELECT" | "WITH"));
+                parens.push(subquery);
+                idx += 1;
+                continue;
+            }
+            Token::Punct(')') => {
+                parens.pop();
+                idx += 1;
+                continue;
+            }
+            _ => {}
+        }
+        let in_query = parens.last().copied().unwrap_or(true);
+        let Some(word) = keyword(idx) else {
+            idx += 1;
+            continue;
+        };
+        let prev = idx.checked_sub(1).and_then(keyword);
+        let skip_modifiers = |mut at: usize| {
+            while matches!(keyword(at).as_deref(), Some("ONLY" | "LATERAL" | "TABLE")) {
+                at += 1;
+            }
+            at
+        };
+        match word.as_str() {
+            "FROM" if in_query && prev.as_deref() != Some("DELETE") => {
+                let mut at = skip_modifiers(idx + 1);
+                loop {
+                    if tokens.get(at + 1) == Some(&Token::Punct('(')) {
+                        break;
+                    }
+                    add(table_name(tokens.get(at)), Access::Read);
+                    at += 1;
+                    if keyword(at).as_deref() == Some("AS") {
+                        at += 1;
+                    }
+                    if table_name(tokens.get(at)).is_some() {
+                        at += 1;
+                    }
+                    if tokens.get(at) != Some(&Token::Punct(',')) {
+                        break;
+                    }
+                    at += 1;
+                }
+            }
+            "JOIN" if in_query => {
+                let at = skip_modifiers(idx + 1);
+                if tokens.get(at + 1) != Some(&Token::Punct('(')) {
+                    add(table_name(tokens.get(at)), Access::Read);
+                }
+            }
+            "USING"
+                if tokens.get(idx + 1) != Some(&Token::Punct('('))
+                    && tokens.get(idx + 2) != Some(&Token::Punct('(')) =>
+            {
+                add(table_name(tokens.get(idx + 1)), Access::Read);
+            }
+            "INSERT" | "MERGE" => {
+                let mut at = idx + 1;
+                if keyword(at).as_deref() == Some("INTO") {
+                    at += 1;
+                }
+                add(table_name(tokens.get(at)), Access::Write);
+            }
+            "UPDATE" if !matches!(prev.as_deref(), Some("FOR" | "DO" | "ON" | "KEY")) => {
+                let at = skip_modifiers(idx + 1);
+                let set_follows =
+                    (1..=3).any(|offset| keyword(at + offset).as_deref() == Some("SET"));
+                if set_follows {
+                    add(table_name(tokens.get(at)), Access::Write);
+                }
+            }
+            "DELETE" if prev.as_deref() != Some("ON") => {
+                let mut at = idx + 1;
+                if keyword(at).as_deref() == Some("FROM") {
+                    at += 1;
+                }
+                let at = skip_modifiers(at);
+                add(table_name(tokens.get(at)), Access::Write);
+            }
+            "TRUNCATE" => {
+                let at = skip_modifiers(idx + 1);
+                add(table_name(tokens.get(at)), Access::Write);
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    out
+}
+
+/// The innermost class-like symbol containing `line`.
+fn enclosing_type(symbols: &[SymbolInput], line: i64) -> Option<&SymbolInput> {
+    symbols
+        .iter()
+        .filter(|symbol| TYPE_KINDS.contains(&symbol.kind.as_str()))
+        .filter(|symbol| symbol.start_line <= line && line <= symbol.end_line)
+        .min_by_key(|symbol| symbol.end_line - symbol.start_line)
+}
+
+/// The first class-like symbol declared at or after `line` (the target of
+/// an attribute or decorator on the preceding lines).
+fn next_type(symbols: &[SymbolInput], line: i64) -> Option<&SymbolInput> {
+    symbols
+        .iter()
+        .filter(|symbol| TYPE_KINDS.contains(&symbol.kind.as_str()))
+        .filter(|symbol| symbol.start_line >= line)
+        .min_by_key(|symbol| symbol.start_line)
+}
+
+fn identifier_at(text: &str) -> &str {
+    let end = text
+        .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+/// Occurrences of `needle` not preceded by an identifier character.
+fn word_matches<'a>(source: &'a str, needle: &'a str) -> impl Iterator<Item = usize> + 'a {
+    source.match_indices(needle).filter_map(move |(start, _)| {
+        let before = source[..start].chars().next_back();
+        (!before.is_some_and(|ch| ch.is_alphanumeric() || ch == '_')).then_some(start)
+    })
+}
+
+/// `(argument name, string value)` pairs; positional arguments have no name.
+type StringArgs = Vec<(Option<String>, String)>;
+
+/// String arguments of the call whose `(` is at `open`: positional ones in
+/// order, and `name = "..."` / `name: "..."` ones by name.
+fn string_arguments(source: &str, open: usize) -> Option<(StringArgs, usize)> {
+    let (args, close) = rules::split_arguments(source, open)?;
+    let values = args
+        .iter()
+        .filter_map(|arg| {
+            if let Some(value) = rules::string_literal(arg) {
+                return Some((None, value));
+            }
+            let (name, value) = arg.split_once(['=', ':'])?;
+            let value = rules::string_literal(value)?;
+            Some((Some(name.trim().to_ascii_lowercase()), value))
+        })
+        .collect();
+    Some((values, close))
+}
+
+fn qualify(schema: Option<&String>, table: &str) -> String {
+    match schema {
+        Some(schema) if !schema.is_empty() => format!("{schema}.{table}"),
+        _ => table.to_string(),
+    }
+}
+
+/// EF Core: `DbSet<Order> Orders` maps to a table named after the property,
+/// `[Table("orders", Schema = "sales")]` and `.ToTable("orders", "sales")`
+/// override it.
+fn ef_core_mappings(out: &mut TableEdges<'_>, symbols: &[SymbolInput]) {
+    let source = out.source;
+    for start in word_matches(source, "DbSet<") {
+        let after = &source[start + "DbSet<".len()..];
+        let Some(close) = after.find('>') else {
+            continue;
+        };
+        let entity = after[..close].trim();
+        let entity = entity.rsplit('.').next().unwrap_or(entity);
+        let rest = &after[close + 1..];
+        let trimmed = rest.trim_start();
+        let property = identifier_at(trimmed);
+        if property.is_empty() || property == entity {
+            continue;
+        }
+        let end =
+            start + "DbSet<".len() + close + 1 + (rest.len() - trimmed.len()) + property.len();
+        let site = Site::at(source, start, end);
+        let Some(owner) = rules::enclosing_symbol(symbols, site.start_line) else {
+            continue;
+        };
+        out.push_mapping(
+            owner,
+            property,
+            json!({ "via": "orm", "orm": "ef_core", "entity": entity }),
+            site,
+        );
+    }
+
+    for start in word_matches(source, "Table") {
+        let before = source[..start].trim_end();
+        if !before.ends_with(['[', ',']) {
+            continue;
+        }
+        let mut open = start + "Table".len();
+        if source[open..].starts_with("Attribute") {
+            open += "Attribute".len();
+        }
+        if !source[open..].starts_with('(') {
+            continue;
+        }
+        let Some((args, close)) = string_arguments(source, open) else {
+            continue;
+        };
+        let Some(table) = args
+            .iter()
+            .find(|(name, _)| name.is_none())
+            .map(|(_, value)| value)
+        else {
+            continue;
+        };
+        let schema = args
+            .iter()
+            .find(|(name, _)| name.as_deref() == Some("schema"))
+            .map(|(_, value)| value);
+        let site = Site::at(source, start, close + 1);
+        let Some(owner) = next_type(symbols, site.start_line) else {
+            continue;
+        };
+        out.push_mapping(
+            &owner.qualname,
+            &qualify(schema, table),
+            json!({ "via": "orm", "orm": "ef_core", "entity": owner.name }),
+            site,
+        );
+    }
+
+    for (start, _) in source.match_indices(".ToTable(") {
+        let open = start + ".ToTable".len();
+        let Some((args, close)) = string_arguments(source, open) else {
+            continue;
+        };
+        let mut positional = args.iter().filter(|(name, _)| name.is_none());
+        let Some((_, table)) = positional.next() else {
+            continue;
+        };
+        let schema = positional.next().map(|(_, value)| value).or_else(|| {
+            args.iter()
+                .find(|(name, _)| name.as_deref() == Some("schema"))
+                .map(|(_, value)| value)
+        });
+        let statement_start = source[..start]
+            .rfind([';', '{', '}'])
+            .map_or(0, |idx| idx + 1);
+        let entity = source[statement_start..start]
+            .rfind("Entity<")
+            .map(|idx| identifier_at(&source[statement_start + idx + "Entity<".len()..]));
+        let site = Site::at(source, start + 1, close + 1);
+        let Some(owner) = rules::enclosing_symbol(symbols, site.start_line) else {
+            continue;
+        };
+        let mut detail = json!({ "via": "orm", "orm": "ef_core" });
+        if let Some(entity) = entity.filter(|entity| !entity.is_empty()) {
+            detail["entity"] = json!(entity);
+        }
+        out.push_mapping(owner, &qualify(schema, table), detail, site);
+    }
+}
+
+/// SQLAlchemy declarative models: `__tablename__ = "orders"`.
+fn sqlalchemy_mappings(out: &mut TableEdges<'_>, symbols: &[SymbolInput]) {
+    let source = out.source;
+    for start in word_matches(source, "__tablename__") {
+        let rest = &source[start + "__tablename__".len()..];
+        let line_end = rest.find('\n').unwrap_or(rest.len());
+        let Some(value) = rest[..line_end].trim().strip_prefix('=') else {
+            continue;
+        };
+        let Some(table) = rules::string_literal(value.split('#').next().unwrap_or(value)) else {
+            continue;
+        };
+        let site = Site::at(source, start, start + "__tablename__".len() + line_end);
+        let Some(owner) = enclosing_type(symbols, site.start_line) else {
+            continue;
+        };
+        out.push_mapping(
+            &owner.qualname,
+            &table,
+            json!({ "via": "orm", "orm": "sqlalchemy" }),
+            site,
+        );
+    }
+}
+
+/// GORM: `func (Order) TableName() string { return "orders" }`.
+fn gorm_mappings(out: &mut TableEdges<'_>, symbols: &[SymbolInput]) {
+    let source = out.source;
+    for (start, _) in source.match_indices(") TableName() string") {
+        let line_start = source[..start].rfind('\n').map_or(0, |idx| idx + 1);
+        let Some(receiver) = source[line_start..start]
+            .trim_start()
+            .strip_prefix("func (")
+        else {
+            continue;
+        };
+        let receiver = receiver
+            .split_whitespace()
+            .last()
+            .unwrap_or_default()
+            .trim_start_matches('*');
+        let body = &source[start..];
+        let Some(ret) = body.find("return") else {
+            continue;
+        };
+        let value = &body[ret + "return".len()..];
+        let value_end = value.find(['\n', ';', '}']).unwrap_or(value.len());
+        let Some(table) = rules::string_literal(&value[..value_end]) else {
+            continue;
+        };
+        let site = Site::at(source, line_start, start + ret + "return".len() + value_end);
+        let owner = symbols
+            .iter()
+            .find(|symbol| symbol.name == receiver && TYPE_KINDS.contains(&symbol.kind.as_str()))
+            .map(|symbol| symbol.qualname.as_str())
+            .or_else(|| rules::enclosing_symbol(symbols, site.start_line));
+        let Some(owner) = owner else {
+            continue;
+        };
+        out.push_mapping(
+            owner,
+            &table,
+            json!({ "via": "orm", "orm": "gorm", "entity": receiver }),
+            site,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(name: &str) -> (String, Access) {
+        (name.to_string(), Access::Read)
+    }
+
+    fn write(name: &str) -> (String, Access) {
+        (name.to_string(), Access::Write)
+    }
+
+    #[test]
+    fn statements_yield_read_and_written_tables() {
+        assert_eq!(
+            table_accesses(
+                "SELECT o.id FROM dbo.[Orders] o, audit a JOIN customers c ON c.id = o.customer_id WHERE o.id = @id"
+            ),
+            vec![read("dbo.Orders"), read("audit"), read("customers")]
+        );
+        assert_eq!(
+            table_accesses("INSERT INTO order_lines (id, sku) SELECT id, sku FROM cart_items"),
+            vec![write("order_lines"), read("cart_items")]
+        );
+        assert_eq!(
+            table_accesses(
+                "UPDATE \"public\".\"orders\" SET status = 'DELETE FROM x' WHERE id = $1"
+            ),
+            vec![write("public.orders")]
+        );
+        assert_eq!(
+            table_accesses("delete from sessions where expires_at < now()"),
+            vec![write("sessions")]
+        );
+        assert_eq!(
+            table_accesses(
+                "WITH recent AS (SELECT * FROM orders WHERE EXTRACT(YEAR FROM created) = 2024) SELECT * FROM recent"
+            ),
+            vec![read("orders")]
+        );
+        assert_eq!(
+            table_accesses(
+                "MERGE INTO stock t USING incoming s ON t.sku = s.sku WHEN MATCHED THEN UPDATE SET qty = s.qty"
+            ),
+            vec![write("stock"), read("incoming")]
+        );
+        assert_eq!(
+            table_accesses("SELECT * FROM orders FOR UPDATE"),
+            vec![read("orders")]
+        );
+    }
+
+    #[test]
+    fn prose_is_not_sql() {
+        assert!(sql_statement("Update failed, please retry").is_none());
+        assert!(sql_statement("Select an option").is_none());
+        assert_eq!(
+            sql_statement("  SELECT id FROM orders").as_deref(),
+            Some("select")
+        );
+    }
+}
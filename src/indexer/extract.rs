@@ -34,6 +34,9 @@ pub struct ExtractedFile {
     pub edges: Vec<EdgeInput>,
     pub file_metrics: Option<FileMetricsInput>,
     pub symbol_metrics: Vec<SymbolMetricsInput>,
+    /// The tree-sitter parse of the source, kept for the passes that run
+    /// after extraction so the file is parsed once.
+    pub tree: Option<tree_sitter::Tree>,
 }
 use crate::metrics::{FileMetricsInput, SymbolMetricsInput};
 use anyhow::Result;
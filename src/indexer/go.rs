@@ -3,6 +3,7 @@ use crate::indexer::config;
 use crate::indexer::extract::{EdgeInput, ExtractedFile, SymbolInput};
 use crate::indexer::http;
 use crate::indexer::proto;
+use crate::indexer::rules;
 use crate::indexer::tree_helpers::{
     module_symbol_fallback, module_symbol_with_span, node_text, span,
 };
@@ -92,6 +93,7 @@ impl crate::indexer::extract::LanguageExtractor for GoExtractor {
             local_var_types: Vec::new(),
         };
         walk_node(root, &ctx, source, &mut output);
+        output.tree = Some(tree);
         Ok(output)
     }
 
@@ -389,11 +391,60 @@ fn handle_type_spec(node: Node<'_>, ctx: &Context, source: &str, output: &mut Ex
     output.edges.push(EdgeInput {
         kind: "CONTAINS".to_string(),
         source_qualname: Some(ctx.module.clone()),
-        target_qualname: Some(qualname),
+        target_qualname: Some(qualname.clone()),
         detail: None,
         evidence_snippet: None,
         ..Default::default()
     });
+    if type_node.kind() == "interface_type" {
+        handle_interface_methods(type_node, source, output, &qualname);
+    }
+}
+
+/// Emit the method set of an interface as `method` symbols so implementations
+/// can be linked to them.
+fn handle_interface_methods(
+    type_node: Node<'_>,
+    source: &str,
+    output: &mut ExtractedFile,
+    interface_qualname: &str,
+) {
+    let mut cursor = type_node.walk();
+    for child in type_node.named_children(&mut cursor) {
+        if !matches!(child.kind(), "method_elem" | "method_spec") {
+            continue;
+        }
+        let Some(name_node) = child.child_by_field_name("name") else {
+            continue;
+        };
+        let name = node_text(name_node, source);
+        if name.is_empty() {
+            continue;
+        }
+        let qualname = format!("{interface_qualname}.{name}");
+        let (start_line, start_col, end_line, end_col, start_byte, end_byte) = span(child);
+        output.symbols.push(SymbolInput {
+            kind: "method".to_string(),
+            name,
+            qualname: qualname.clone(),
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+            start_byte,
+            end_byte,
+            signature: Some(node_text(child, source)),
+            docstring: None,
+        });
+        output.edges.push(EdgeInput {
+            kind: "CONTAINS".to_string(),
+            source_qualname: Some(interface_qualname.to_string()),
+            target_qualname: Some(qualname),
+            detail: None,
+            evidence_snippet: None,
+            ..Default::default()
+        });
+    }
 }
 
 fn handle_const_declaration(
@@ -997,9 +1048,11 @@ fn http_call_edge(node: Node<'_>, ctx: &Context, source: &str) -> Option<EdgeInp
 
     // Only consider it an HTTP call if receiver looks like a client or is http package
     let receiver_lower = receiver.to_ascii_lowercase();
+    let custom = rules::active_http_client(&receiver);
     if !receiver_lower.contains("http")
         && !receiver_lower.contains("client")
         && !receiver_lower.contains("request")
+        && !custom
     {
         return None;
     }
@@ -1007,6 +1060,8 @@ fn http_call_edge(node: Node<'_>, ctx: &Context, source: &str) -> Option<EdgeInp
     let normalized = http::normalize_path(&raw_path)?;
     let client = if receiver_lower.contains("http") {
         "http"
+    } else if custom {
+        "custom"
     } else {
         "http_client"
     };
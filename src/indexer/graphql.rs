@@ -0,0 +1,763 @@
+//! GraphQL schemas and operation documents.
+//!
+//! Schema definitions (`type`, `interface`, `input`, `enum`, `union`,
+//! `scalar`, and their `extend` forms) become symbols with a `field` per
+//! field; fields of the root types are `query`, `mutation` and
+//! `subscription` symbols instead. Executable documents become `operation`
+//! and `fragment` symbols.
+//!
+//! Bridging works like HTTP routes: every root field has a
+//! `graphql://<operation>/<field>` key. Resolvers (schema root fields,
+//! Apollo resolver maps, NestJS `@Query()` methods, Hot Chocolate query
+//! types) emit GRAPHQL_RESOLVE to that key and client operations (`.graphql`
+//! documents, `gql` tagged templates) emit GRAPHQL_CALL, so `trace_flow`
+//! crosses from a query to the code answering it.
+
+use crate::indexer::extract::{EdgeInput, ExtractedFile};
+use crate::indexer::openapi::contains_edge;
+use crate::indexer::proto::TYPE_REF_KIND;
+use crate::indexer::tree_helpers::{
+    child_of_kind, children_of_kind, module_symbol_fallback, node_text, span, symbol_for_node,
+};
+use crate::indexer::yaml;
+use anyhow::Result;
+use serde_json::json;
+use std::collections::HashMap;
+use tree_sitter::{Node, Parser};
+use tree_sitter_graphql::LANGUAGE;
+
+pub const GRAPHQL_CALL_KIND: &str = "GRAPHQL_CALL";
+pub const GRAPHQL_RESOLVE_KIND: &str = "GRAPHQL_RESOLVE";
+
+/// Root operation types, which hold the fields clients call.
+pub const ROOT_TYPES: &[&str] = &["Query", "Mutation", "Subscription"];
+/// Built-in scalars, which never name a declared type.
+const BUILTIN_SCALARS: &[&str] = &["Int", "Float", "String", "Boolean", "ID"];
+
+/// Bridge key of a field: `graphql://query/user` for `Query.user`. Root
+/// types are keyed by operation, so a renamed root (`schema { query:
+/// RootQuery }`) still matches the clients. Lowercased so `GetUserById`
+/// style resolver names compare equal to `userById`.
+pub fn field_key(type_name: &str, field: &str) -> Option<String> {
+    let type_name = type_name.trim();
+    let field = field.trim();
+    if type_name.is_empty() || field.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "graphql://{}/{}",
+        type_name.to_lowercase(),
+        field.to_lowercase()
+    ))
+}
+
+pub fn build_call_detail(
+    operation: &str,
+    field: &str,
+    operation_name: Option<&str>,
+    framework: &str,
+) -> String {
+    json!({
+        "operation": operation,
+        "field": field,
+        "operation_name": operation_name,
+        "framework": framework,
+        "role": "client",
+    })
+    .to_string()
+}
+
+pub fn build_resolve_detail(type_name: &str, field: &str, framework: &str) -> String {
+    json!({
+        "type": type_name,
+        "field": field,
+        "framework": framework,
+        "role": "resolver",
+    })
+    .to_string()
+}
+
+/// A root field selected by a client operation.
+pub struct OperationCall {
+    /// `query`, `mutation` or `subscription`.
+    pub operation: String,
+    pub operation_name: Option<String>,
+    pub field: String,
+    /// Line of the field, 1-based within the parsed text.
+    pub line: i64,
+}
+
+/// Root fields selected by the operations of an executable document, such
+/// as the body of a `gql` template.
+pub fn operation_calls(text: &str) -> Vec<OperationCall> {
+    let mut parser = Parser::new();
+    if parser.set_language(&LANGUAGE.into()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(text, None) else {
+        return Vec::new();
+    };
+    let mut calls = Vec::new();
+    for definition in definitions(tree.root_node()) {
+        if definition.kind() != "operation_definition" {
+            continue;
+        }
+        let operation = operation_type(definition, text);
+        let operation_name = child_of_kind(definition, "name").map(|name| node_text(name, text));
+        for field in root_fields(definition, text) {
+            calls.push(OperationCall {
+                operation: operation.clone(),
+                operation_name: operation_name.clone(),
+                field: node_text(field, text),
+                line: span(field).0,
+            });
+        }
+    }
+    calls
+}
+
+pub struct GraphqlExtractor {
+    parser: Parser,
+}
+
+impl GraphqlExtractor {
+    pub fn new() -> Result<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(&LANGUAGE.into())?;
+        Ok(Self { parser })
+    }
+}
+
+impl crate::indexer::extract::LanguageExtractor for GraphqlExtractor {
+    fn module_name_from_rel_path(&self, rel_path: &str) -> String {
+        module_name_from_rel_path(rel_path)
+    }
+
+    fn extract(&mut self, source: &str, module_name: &str) -> Result<ExtractedFile> {
+        let mut output = ExtractedFile::default();
+        output
+            .symbols
+            .push(module_symbol_fallback(module_name, source, "/", None));
+        let Some(tree) = self.parser.parse(source, None) else {
+            return Ok(output);
+        };
+        let definitions = definitions(tree.root_node());
+
+        let mut roots = HashMap::new();
+        let mut declared = HashMap::new();
+        for &definition in &definitions {
+            if matches!(definition.kind(), "schema_definition" | "schema_extension") {
+                for root in children_of_kind(definition, "root_operation_type_definition") {
+                    if let (Some(operation), Some(named)) = (
+                        child_of_kind(root, "operation_type"),
+                        child_of_kind(root, "named_type"),
+                    ) {
+                        roots.insert(node_text(operation, source), node_text(named, source));
+                    }
+                }
+            } else if type_keyword(definition.kind()).is_some()
+                && let Some(name) = child_of_kind(definition, "name")
+            {
+                let name = node_text(name, source);
+                let qualname = format!("{module_name}.{name}");
+                declared.insert(name, qualname);
+            }
+        }
+        let type_target = |name: &str| {
+            declared
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| name.to_string())
+        };
+
+        for definition in definitions {
+            match definition.kind() {
+                "operation_definition" => {
+                    let operation = operation_type(definition, source);
+                    let operation_name =
+                        child_of_kind(definition, "name").map(|name| node_text(name, source));
+                    let name = operation_name.clone().unwrap_or_else(|| operation.clone());
+                    let qualname = format!("{module_name}.{name}");
+                    output.symbols.push(symbol_for_node(
+                        "operation",
+                        &name,
+                        &qualname,
+                        definition,
+                        Some(format!("{operation} {name}")),
+                        None,
+                    ));
+                    output.edges.push(contains_edge(module_name, &qualname));
+                    for field in root_fields(definition, source) {
+                        let field_name = node_text(field, source);
+                        let Some(key) = field_key(&operation, &field_name) else {
+                            continue;
+                        };
+                        let line = span(field).0;
+                        output.edges.push(EdgeInput {
+                            kind: GRAPHQL_CALL_KIND.to_string(),
+                            source_qualname: Some(qualname.clone()),
+                            target_qualname: Some(key),
+                            detail: Some(build_call_detail(
+                                &operation,
+                                &field_name,
+                                operation_name.as_deref(),
+                                "graphql-document",
+                            )),
+                            evidence_start_line: Some(line),
+                            evidence_end_line: Some(line),
+                            ..Default::default()
+                        });
+                    }
+                }
+                "fragment_definition" => {
+                    let Some(name) = child_of_kind(definition, "fragment_name")
+                        .and_then(|fragment| child_of_kind(fragment, "name"))
+                    else {
+                        continue;
+                    };
+                    let Some(on) = child_of_kind(definition, "type_condition")
+                        .and_then(|condition| child_of_kind(condition, "named_type"))
+                    else {
+                        continue;
+                    };
+                    let name = node_text(name, source);
+                    let on_name = node_text(on, source);
+                    let qualname = format!("{module_name}.{name}");
+                    output.symbols.push(symbol_for_node(
+                        "fragment",
+                        &name,
+                        &qualname,
+                        definition,
+                        Some(format!("fragment {name} on {on_name}")),
+                        None,
+                    ));
+                    output.edges.push(contains_edge(module_name, &qualname));
+                    output.edges.push(type_ref_edge(
+                        &qualname,
+                        type_target(&on_name),
+                        "fragment",
+                        on,
+                    ));
+                }
+                kind => {
+                    let Some(keyword) = type_keyword(kind) else {
+                        continue;
+                    };
+                    let Some(name) = child_of_kind(definition, "name") else {
+                        continue;
+                    };
+                    let name = node_text(name, source);
+                    let qualname = format!("{module_name}.{name}");
+                    let extend = if kind.ends_with("_extension") {
+                        "extend "
+                    } else {
+                        ""
+                    };
+                    let symbol_kind = match keyword {
+                        "interface" => "interface",
+                        "enum" => "enum",
+                        _ => "type",
+                    };
+                    output.symbols.push(symbol_for_node(
+                        symbol_kind,
+                        &name,
+                        &qualname,
+                        definition,
+                        Some(format!("{extend}{keyword} {name}")),
+                        description(definition, source),
+                    ));
+                    output.edges.push(contains_edge(module_name, &qualname));
+                    extract_type_body(
+                        definition,
+                        source,
+                        keyword,
+                        &qualname,
+                        root_operation(&roots, &name),
+                        &type_target,
+                        &mut output,
+                    );
+                }
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// Interfaces, union members and fields of a type definition.
+fn extract_type_body(
+    definition: Node<'_>,
+    source: &str,
+    keyword: &str,
+    qualname: &str,
+    operation: Option<&str>,
+    type_target: &dyn Fn(&str) -> String,
+    output: &mut ExtractedFile,
+) {
+    let type_name = qualname.rsplit('.').next().unwrap_or(qualname);
+    if let Some(interfaces) = child_of_kind(definition, "implements_interfaces") {
+        for interface in named_types(interfaces) {
+            let line = span(interface).0;
+            output.edges.push(EdgeInput {
+                kind: "IMPLEMENTS".to_string(),
+                source_qualname: Some(qualname.to_string()),
+                target_qualname: Some(type_target(&node_text(interface, source))),
+                evidence_start_line: Some(line),
+                evidence_end_line: Some(line),
+                ..Default::default()
+            });
+        }
+    }
+    if let Some(members) = child_of_kind(definition, "union_member_types") {
+        for member in named_types(members) {
+            output.edges.push(type_ref_edge(
+                qualname,
+                type_target(&node_text(member, source)),
+                "member",
+                member,
+            ));
+        }
+    }
+
+    let fields = [
+        "fields_definition",
+        "input_fields_definition",
+        "enum_values_definition",
+    ]
+    .into_iter()
+    .filter_map(|kind| child_of_kind(definition, kind))
+    .flat_map(named_children);
+    for field in fields {
+        let name = match field.kind() {
+            "enum_value_definition" => {
+                child_of_kind(field, "enum_value").and_then(|v| child_of_kind(v, "name"))
+            }
+            "field_definition" | "input_value_definition" => child_of_kind(field, "name"),
+            _ => None,
+        };
+        let Some(name) = name else {
+            continue;
+        };
+        let field_name = node_text(name, source);
+        let field_qualname = format!("{qualname}.{field_name}");
+        let kind = match (keyword, operation) {
+            (_, Some(operation)) => operation,
+            ("enum", _) => "enum_member",
+            _ => "field",
+        };
+
+        // Named types of the field and its arguments, with their role.
+        let mut type_refs = Vec::new();
+        let mut signature = None;
+        if let Some(ty) = child_of_kind(field, "type") {
+            type_refs.extend(named_type(ty).map(|named| (named, "field")));
+            let mut arguments = String::new();
+            if let Some(list) = child_of_kind(field, "arguments_definition") {
+                let mut parts = Vec::new();
+                for argument in children_of_kind(list, "input_value_definition") {
+                    let (Some(name), Some(arg_type)) = (
+                        child_of_kind(argument, "name"),
+                        child_of_kind(argument, "type"),
+                    ) else {
+                        continue;
+                    };
+                    parts.push(format!(
+                        "{}: {}",
+                        node_text(name, source),
+                        type_text(arg_type, source)
+                    ));
+                    type_refs.extend(named_type(arg_type).map(|named| (named, "argument")));
+                }
+                arguments = format!("({})", parts.join(", "));
+            }
+            signature = Some(format!(
+                "{field_name}{arguments}: {}",
+                type_text(ty, source)
+            ));
+        }
+
+        output.symbols.push(symbol_for_node(
+            kind,
+            &field_name,
+            &field_qualname,
+            field,
+            signature,
+            description(field, source),
+        ));
+        output.edges.push(contains_edge(qualname, &field_qualname));
+        for (named, role) in type_refs {
+            let target = node_text(named, source);
+            if BUILTIN_SCALARS.contains(&target.as_str()) {
+                continue;
+            }
+            output.edges.push(type_ref_edge(
+                &field_qualname,
+                type_target(&target),
+                role,
+                named,
+            ));
+        }
+        if let Some(operation) = operation
+            && let Some(key) = field_key(operation, &field_name)
+        {
+            let line = span(name).0;
+            output.edges.push(EdgeInput {
+                kind: GRAPHQL_RESOLVE_KIND.to_string(),
+                source_qualname: Some(field_qualname.clone()),
+                target_qualname: Some(key),
+                detail: Some(build_resolve_detail(
+                    type_name,
+                    &field_name,
+                    "graphql-schema",
+                )),
+                evidence_start_line: Some(line),
+                evidence_end_line: Some(line),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+pub fn module_name_from_rel_path(rel_path: &str) -> String {
+    yaml::module_name_from_rel_path(rel_path)
+}
+
+fn type_ref_edge(source: &str, target: String, role: &str, node: Node<'_>) -> EdgeInput {
+    let line = span(node).0;
+    EdgeInput {
+        kind: TYPE_REF_KIND.to_string(),
+        source_qualname: Some(source.to_string()),
+        target_qualname: Some(target),
+        detail: Some(json!({ "role": role }).to_string()),
+        evidence_start_line: Some(line),
+        evidence_end_line: Some(line),
+        ..Default::default()
+    }
+}
+
+/// The definitions of a document (`object_type_definition`,
+/// `operation_definition`, ...), unwrapped from the grammar's grouping
+/// nodes. Definitions inside a parse error are still found.
+fn definitions(root: Node<'_>) -> Vec<Node<'_>> {
+    let mut out = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            "source_file"
+            | "document"
+            | "definition"
+            | "executable_definition"
+            | "type_system_definition"
+            | "type_system_extension"
+            | "type_definition"
+            | "type_extension"
+            | "ERROR" => stack.extend(named_children(node).into_iter().rev()),
+            _ => out.push(node),
+        }
+    }
+    out
+}
+
+/// Schema keyword of a type definition or extension node.
+fn type_keyword(kind: &str) -> Option<&'static str> {
+    let base = kind
+        .strip_suffix("_definition")
+        .or_else(|| kind.strip_suffix("_extension"))?;
+    match base {
+        "object_type" => Some("type"),
+        "interface_type" => Some("interface"),
+        "input_object_type" => Some("input"),
+        "enum_type" => Some("enum"),
+        "union_type" => Some("union"),
+        "scalar_type" => Some("scalar"),
+        _ => None,
+    }
+}
+
+/// `query`, `mutation` or `subscription` when `type_name` is a root type,
+/// given the `schema { query: RootQuery }` renames in `roots`.
+fn root_operation(roots: &HashMap<String, String>, type_name: &str) -> Option<&'static str> {
+    const OPERATIONS: [&str; 3] = ["query", "mutation", "subscription"];
+    OPERATIONS
+        .into_iter()
+        .zip(ROOT_TYPES)
+        .find_map(|(operation, default)| {
+            let root = roots.get(operation).map(String::as_str).unwrap_or(default);
+            (root == type_name).then_some(operation)
+        })
+}
+
+/// Operation keyword; a bare selection set is a query.
+fn operation_type(definition: Node<'_>, source: &str) -> String {
+    child_of_kind(definition, "operation_type")
+        .map(|operation| node_text(operation, source))
+        .unwrap_or_else(|| "query".to_string())
+}
+
+/// Name nodes of the fields selected directly by an operation, aliases
+/// resolved to the field name. Fragments and `__` meta fields are skipped.
+fn root_fields<'a>(definition: Node<'a>, source: &str) -> Vec<Node<'a>> {
+    let Some(selections) = child_of_kind(definition, "selection_set") else {
+        return Vec::new();
+    };
+    children_of_kind(selections, "selection")
+        .into_iter()
+        .filter_map(|selection| child_of_kind(selection, "field"))
+        .filter_map(|field| child_of_kind(field, "name"))
+        .filter(|name| !node_text(*name, source).starts_with("__"))
+        .collect()
+}
+
+/// `named_type` nodes of an `implements` or union member list, which the
+/// grammar nests one level per member.
+fn named_types(list: Node<'_>) -> Vec<Node<'_>> {
+    let mut out = Vec::new();
+    for node in named_children(list) {
+        if node.kind() == "named_type" {
+            out.push(node);
+        } else if node.kind() == list.kind() {
+            out.extend(named_types(node));
+        }
+    }
+    out
+}
+
+/// The named type inside a type reference (`User` in `[User!]!`).
+fn named_type(ty: Node<'_>) -> Option<Node<'_>> {
+    if ty.kind() == "named_type" {
+        return Some(ty);
+    }
+    named_children(ty).into_iter().find_map(named_type)
+}
+
+/// A type reference as written, without whitespace: `[Order!]!`.
+fn type_text(ty: Node<'_>, source: &str) -> String {
+    node_text(ty, source).split_whitespace().collect()
+}
+
+fn description(node: Node<'_>, source: &str) -> Option<String> {
+    let value = child_of_kind(node, "description")
+        .and_then(|description| child_of_kind(description, "string_value"))?;
+    let raw = node_text(value, source);
+    Some(match raw.strip_prefix("\"\"\"") {
+        Some(_) => block_string_value(&raw),
+        None => raw.trim_matches('"').to_string(),
+    })
+}
+
+/// Contents of a `"""block string"""`, with common indentation removed.
+fn block_string_value(raw: &str) -> String {
+    let inner = raw
+        .strip_prefix("\"\"\"")
+        .unwrap_or(raw)
+        .strip_suffix("\"\"\"")
+        .unwrap_or(raw);
+    let lines: Vec<&str> = inner.lines().collect();
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx == 0 {
+                line.trim()
+            } else {
+                line.get(indent..).unwrap_or("").trim_end()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+fn named_children(node: Node<'_>) -> Vec<Node<'_>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::extract::LanguageExtractor;
+
+    const SCHEMA: &str = r#"
+"""
+A registered user.
+"""
+type User implements Node @key(fields: "id") {
+  id: ID!
+  "Orders, newest first."
+  orders(first: Int = 10, status: OrderStatus): [Order!]!
+}
+
+enum OrderStatus { OPEN CLOSED }
+
+union SearchResult = User | Order
+
+type Query {
+  user(id: ID!): User
+}
+
+extend type Mutation {
+  createUser(input: CreateUserInput!): User! @auth
+}
+
+query GetUser($id: ID!) {
+  me: user(id: $id) { id ...UserFields }
+  __typename
+}
+
+fragment UserFields on User { id }
+"#;
+
+    #[test]
+    fn extracts_schema_and_operations() {
+        let output = GraphqlExtractor::new()
+            .unwrap()
+            .extract(SCHEMA, "schema/users")
+            .unwrap();
+        let symbol = |qualname: &str| {
+            output
+                .symbols
+                .iter()
+                .find(|symbol| symbol.qualname == qualname)
+                .unwrap_or_else(|| panic!("missing {qualname}"))
+        };
+        let user = symbol("schema/users.User");
+        assert_eq!(user.kind, "type");
+        assert_eq!(user.docstring.as_deref(), Some("A registered user."));
+        let orders = symbol("schema/users.User.orders");
+        assert_eq!(orders.kind, "field");
+        assert_eq!(
+            orders.signature.as_deref(),
+            Some("orders(first: Int, status: OrderStatus): [Order!]!")
+        );
+        assert_eq!(orders.docstring.as_deref(), Some("Orders, newest first."));
+        assert_eq!(
+            symbol("schema/users.OrderStatus.CLOSED").kind,
+            "enum_member"
+        );
+        assert_eq!(symbol("schema/users.Query.user").kind, "query");
+        assert_eq!(symbol("schema/users.Mutation.createUser").kind, "mutation");
+        assert_eq!(symbol("schema/users.GetUser").kind, "operation");
+        assert_eq!(symbol("schema/users.UserFields").kind, "fragment");
+
+        let edges = |kind: &str| -> Vec<(String, String)> {
+            output
+                .edges
+                .iter()
+                .filter(|edge| edge.kind == kind)
+                .map(|edge| {
+                    (
+                        edge.source_qualname.clone().unwrap(),
+                        edge.target_qualname.clone().unwrap(),
+                    )
+                })
+                .collect()
+        };
+        let pair = |source: &str, target: &str| (source.to_string(), target.to_string());
+        assert_eq!(
+            edges(GRAPHQL_RESOLVE_KIND),
+            vec![
+                pair("schema/users.Query.user", "graphql://query/user"),
+                pair(
+                    "schema/users.Mutation.createUser",
+                    "graphql://mutation/createuser"
+                ),
+            ]
+        );
+        assert_eq!(
+            edges(GRAPHQL_CALL_KIND),
+            vec![pair("schema/users.GetUser", "graphql://query/user")]
+        );
+        let type_refs = edges(TYPE_REF_KIND);
+        for expected in [
+            pair("schema/users.User.orders", "Order"),
+            pair("schema/users.User.orders", "schema/users.OrderStatus"),
+            pair("schema/users.SearchResult", "schema/users.User"),
+            pair("schema/users.Query.user", "schema/users.User"),
+            pair("schema/users.Mutation.createUser", "CreateUserInput"),
+        ] {
+            assert!(type_refs.contains(&expected), "missing {expected:?}");
+        }
+        assert!(!type_refs.iter().any(|(_, target)| target == "ID"));
+        assert_eq!(edges("IMPLEMENTS"), vec![pair("schema/users.User", "Node")]);
+    }
+
+    #[test]
+    fn custom_root_types_key_by_operation() {
+        let output = GraphqlExtractor::new()
+            .unwrap()
+            .extract(
+                "schema { query: RootQuery }\ntype RootQuery { ping: String }",
+                "api",
+            )
+            .unwrap();
+        assert!(
+            output
+                .edges
+                .iter()
+                .any(|edge| edge.kind == GRAPHQL_RESOLVE_KIND
+                    && edge.target_qualname.as_deref() == Some("graphql://query/ping"))
+        );
+    }
+
+    #[test]
+    fn extensions_inputs_and_anonymous_queries() {
+        let source = "# orders\n\
+            type Order implements Node & Entity { id: ID! }\n\
+            extend type Order { total: Money }\n\
+            input NewOrder { items: [ItemInput!]!, note: String }\n\
+            union Thing = | Order | Item\n\
+            { viewer { id } }\n";
+        let output = GraphqlExtractor::new()
+            .unwrap()
+            .extract(source, "api")
+            .unwrap();
+        let signatures: Vec<(&str, &str)> = output
+            .symbols
+            .iter()
+            .filter_map(|symbol| Some((symbol.qualname.as_str(), symbol.signature.as_deref()?)))
+            .collect();
+        for expected in [
+            ("api.Order", "type Order"),
+            ("api.Order", "extend type Order"),
+            ("api.Order.total", "total: Money"),
+            ("api.NewOrder.items", "items: [ItemInput!]!"),
+            ("api.query", "query query"),
+        ] {
+            assert!(signatures.contains(&expected), "missing {expected:?}");
+        }
+        let targets = |kind: &str| -> Vec<&str> {
+            output
+                .edges
+                .iter()
+                .filter(|edge| edge.kind == kind)
+                .filter_map(|edge| edge.target_qualname.as_deref())
+                .collect()
+        };
+        assert_eq!(targets("IMPLEMENTS"), vec!["Node", "Entity"]);
+        assert!(targets(TYPE_REF_KIND).contains(&"Item"));
+        assert_eq!(targets(GRAPHQL_CALL_KIND), vec!["graphql://query/viewer"]);
+    }
+
+    #[test]
+    fn operation_calls_from_template_text() {
+        let calls = operation_calls("mutation Place { placeOrder(id: 1) { id } audit { ok } }");
+        let fields: Vec<(&str, &str)> = calls
+            .iter()
+            .map(|call| (call.operation.as_str(), call.field.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![("mutation", "placeOrder"), ("mutation", "audit")]
+        );
+        assert_eq!(calls[0].operation_name.as_deref(), Some("Place"));
+    }
+}
@@ -0,0 +1,895 @@
+//! Helm charts.
+//!
+//! `Chart.yaml` becomes a `chart` symbol (`helm://charts/api`) and every key
+//! of `values.yaml` a `value` symbol (`helm://charts/api/values/db.host`).
+//! Templates are rendered with a small subset of Go templates — enough for
+//! `{{ .Values.x }}`, `if`/`with`/`range`, pipes and the common Sprig helpers
+//! — against the chart's values, and the rendered manifests go through the
+//! regular Kubernetes extraction. Each `.Values` path a template reads emits
+//! REFERENCES to its value symbol.
+
+use crate::indexer::extract::{EdgeInput, ExtractedFile};
+use crate::indexer::openapi::{SpecLines, SpecSource, contains_edge, spec_symbol};
+use serde_json::{Map, Value, json};
+use std::path::Path;
+
+/// Release name used when rendering; Helm's own placeholder.
+const RELEASE_NAME: &str = "RELEASE-NAME";
+
+pub struct Chart {
+    /// Chart directory relative to the repo root (`""` at the root).
+    pub dir: String,
+    pub name: String,
+    pub values: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFile {
+    Chart,
+    Values,
+    Template,
+}
+
+pub fn chart_qualname(dir: &str) -> String {
+    if dir.is_empty() {
+        "helm://.".to_string()
+    } else {
+        format!("helm://{dir}")
+    }
+}
+
+pub fn value_qualname(dir: &str, path: &str) -> String {
+    format!("{}/values/{path}", chart_qualname(dir))
+}
+
+/// The chart a YAML module belongs to, and which part of it the file is.
+/// Only directories holding a `Chart.yaml` are charts.
+pub fn chart_file(repo_root: &Path, module_name: &str) -> Option<(Chart, ChartFile)> {
+    let (dir, file) = match module_name.rsplit_once('/') {
+        Some((dir, stem)) => (dir, stem),
+        None => ("", module_name),
+    };
+    let (chart_dir, role) = match file {
+        "Chart" => (dir.to_string(), ChartFile::Chart),
+        "values" => (dir.to_string(), ChartFile::Values),
+        _ => {
+            let (chart_dir, _) = if module_name.starts_with("templates/") {
+                ("", module_name)
+            } else {
+                module_name.rsplit_once("/templates/")?
+            };
+            (chart_dir.to_string(), ChartFile::Template)
+        }
+    };
+    let chart_path = ["Chart.yaml", "Chart.yml"]
+        .iter()
+        .map(|name| repo_root.join(&chart_dir).join(name))
+        .find(|path| path.is_file())?;
+    let name = read_yaml(&chart_path)
+        .and_then(|chart| {
+            chart
+                .get("name")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| {
+            chart_dir
+                .rsplit('/')
+                .next()
+                .filter(|name| !name.is_empty())
+                .unwrap_or("chart")
+                .to_string()
+        });
+    let values = ["values.yaml", "values.yml"]
+        .iter()
+        .find_map(|name| read_yaml(&repo_root.join(&chart_dir).join(name)))
+        .unwrap_or(Value::Null);
+    Some((
+        Chart {
+            dir: chart_dir,
+            name,
+            values,
+        },
+        role,
+    ))
+}
+
+fn read_yaml(path: &Path) -> Option<Value> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let value: serde_yaml_ng::Value = serde_yaml_ng::from_str(&text).ok()?;
+    serde_json::to_value(value).ok()
+}
+
+/// `chart` symbol for `Chart.yaml`.
+pub fn extract_chart(
+    chart: &Chart,
+    value: &Value,
+    source: &SpecSource<'_>,
+    module_name: &str,
+    output: &mut ExtractedFile,
+) {
+    let qualname = chart_qualname(&chart.dir);
+    let lines = SpecLines::new(source);
+    let (start_line, _, start_byte, _) = lines.span(None);
+    let (_, end_line, _, end_byte) = lines.span(Some(usize::MAX));
+    let mut symbol = spec_symbol(
+        "chart",
+        &chart.name,
+        &qualname,
+        (start_line, end_line, start_byte, end_byte),
+    );
+    symbol.signature = value
+        .get("version")
+        .map(|version| format!("version {}", scalar_text(version)));
+    symbol.docstring = value
+        .get("description")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    output.symbols.push(symbol);
+    output.edges.push(contains_edge(module_name, &qualname));
+}
+
+/// `value` symbols for every key of `values.yaml`; lists are leaves.
+pub fn extract_values(
+    chart: &Chart,
+    value: &Value,
+    source: &SpecSource<'_>,
+    module_name: &str,
+    output: &mut ExtractedFile,
+) {
+    let lines = SpecLines::new(source);
+    let mut stack: Vec<(Vec<&str>, &Value)> = vec![(Vec::new(), value)];
+    while let Some((path, value)) = stack.pop() {
+        let Some(map) = value.as_object() else {
+            continue;
+        };
+        for (key, child) in map.iter().rev() {
+            let mut child_path = path.clone();
+            child_path.push(key.as_str());
+            let dotted = child_path.join(".");
+            let qualname = value_qualname(&chart.dir, &dotted);
+            let span = lines.span(lines.find_path(&child_path));
+            let mut symbol = spec_symbol("value", key, &qualname, span);
+            if !child.is_object() {
+                symbol.signature = Some(scalar_text(child));
+            }
+            output.symbols.push(symbol);
+            let parent = if path.is_empty() {
+                module_name.to_string()
+            } else {
+                value_qualname(&chart.dir, &path.join("."))
+            };
+            output.edges.push(contains_edge(&parent, &qualname));
+            stack.push((child_path, child));
+        }
+    }
+}
+
+/// REFERENCES from `source_qualname` to the values a rendered template read.
+pub fn value_reference_edges(
+    chart: &Chart,
+    source_qualname: &str,
+    references: &[ValueRef],
+    line_offset: i64,
+    output: &mut ExtractedFile,
+) {
+    let mut seen = std::collections::HashSet::new();
+    for reference in references {
+        if !seen.insert(reference.path.as_str()) {
+            continue;
+        }
+        let line = line_offset + reference.line as i64 - 1;
+        output.edges.push(EdgeInput {
+            kind: "REFERENCES".to_string(),
+            source_qualname: Some(source_qualname.to_string()),
+            target_qualname: Some(value_qualname(&chart.dir, &reference.path)),
+            detail: Some(json!({ "values_path": reference.path }).to_string()),
+            evidence_start_line: Some(line),
+            evidence_end_line: Some(line),
+            ..Default::default()
+        });
+    }
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => "null".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// --- Template rendering ---
+
+/// A `.Values` path read while rendering, with its 1-based template line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueRef {
+    pub path: String,
+    pub line: usize,
+}
+
+/// Render `template` against the chart. Unknown functions and missing
+/// values render empty rather than failing, since the point is the shape of
+/// the manifest, not an installable release.
+pub fn render(template: &str, chart: &Chart) -> (String, Vec<ValueRef>) {
+    let tokens = lex(template);
+    let mut pos = 0;
+    let (nodes, _) = parse_nodes(&tokens, &mut pos);
+    let root = json!({
+        "Values": chart.values,
+        "Release": { "Name": RELEASE_NAME, "Namespace": "default", "Service": "Helm" },
+        "Chart": { "Name": chart.name },
+        "Capabilities": {},
+    });
+    let mut renderer = Renderer {
+        chart_name: &chart.name,
+        root: Val {
+            value: root,
+            path: None,
+        },
+        vars: Vec::new(),
+        refs: Vec::new(),
+        line: 0,
+    };
+    let dot = renderer.root.clone();
+    let mut out = String::new();
+    renderer.render_nodes(&nodes, &dot, &mut out);
+    (out, renderer.refs)
+}
+
+enum Token {
+    Text(String),
+    Action(String, usize),
+}
+
+fn lex(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut trim_next = false;
+    while pos < source.len() {
+        let rest = &source[pos..];
+        let Some(open) = rest.find("{{") else {
+            let text = if trim_next { rest.trim_start() } else { rest };
+            tokens.push(Token::Text(text.to_string()));
+            break;
+        };
+        let after = &rest[open + 2..];
+        let trim_before = after.starts_with('-') && after[1..].starts_with(char::is_whitespace);
+        let mut text = &rest[..open];
+        if trim_next {
+            text = text.trim_start();
+        }
+        if trim_before {
+            text = text.trim_end();
+        }
+        tokens.push(Token::Text(text.to_string()));
+        let Some(close) = after.find("}}") else {
+            tokens.push(Token::Text(rest[open..].to_string()));
+            break;
+        };
+        let mut body = &after[..close];
+        if trim_before {
+            body = &body[1..];
+        }
+        trim_next = body.ends_with('-') && body[..body.len() - 1].ends_with(char::is_whitespace);
+        if trim_next {
+            body = &body[..body.len() - 1];
+        }
+        let body = body.trim();
+        if !(body.starts_with("/*") && body.ends_with("*/")) {
+            let line = source[..pos + open].matches('\n').count() + 1;
+            tokens.push(Token::Action(body.to_string(), line));
+        }
+        pos += open + 2 + close + 2;
+    }
+    tokens
+}
+
+enum Node {
+    Text(String),
+    Output(Pipe, usize),
+    Assign(String, Pipe, usize),
+    If(Vec<(Pipe, Vec<Node>)>, Vec<Node>, usize),
+    With(Pipe, Vec<Node>, Vec<Node>, usize),
+    Range(Vec<String>, Pipe, Vec<Node>, Vec<Node>, usize),
+}
+
+/// What closed a node list.
+enum Close {
+    Eof,
+    End,
+    Else,
+    ElseIf(Pipe),
+}
+
+fn parse_nodes(tokens: &[Token], pos: &mut usize) -> (Vec<Node>, Close) {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.get(*pos) {
+        *pos += 1;
+        let (body, line) = match token {
+            Token::Text(text) => {
+                nodes.push(Node::Text(text.clone()));
+                continue;
+            }
+            Token::Action(body, line) => (body.as_str(), *line),
+        };
+        let (keyword, rest) = body
+            .split_once(char::is_whitespace)
+            .map_or((body, ""), |(keyword, rest)| (keyword, rest.trim()));
+        match keyword {
+            "end" => return (nodes, Close::End),
+            "else" => {
+                let rest = rest
+                    .strip_prefix("if ")
+                    .or_else(|| rest.strip_prefix("with "));
+                return match rest {
+                    Some(cond) => (nodes, Close::ElseIf(parse_pipe(cond))),
+                    None => (nodes, Close::Else),
+                };
+            }
+            "if" => {
+                let mut branches = Vec::new();
+                let mut cond = parse_pipe(rest);
+                let otherwise = loop {
+                    let (body, end) = parse_nodes(tokens, pos);
+                    branches.push((cond, body));
+                    match end {
+                        Close::ElseIf(next) => cond = next,
+                        Close::Else => break parse_nodes(tokens, pos).0,
+                        Close::End | Close::Eof => break Vec::new(),
+                    }
+                };
+                nodes.push(Node::If(branches, otherwise, line));
+            }
+            "with" | "range" => {
+                let (vars, expr) = split_declaration(rest);
+                let (body, end) = parse_nodes(tokens, pos);
+                let otherwise = match end {
+                    Close::Else | Close::ElseIf(_) => parse_nodes(tokens, pos).0,
+                    _ => Vec::new(),
+                };
+                let pipe = parse_pipe(expr);
+                if keyword == "with" {
+                    nodes.push(Node::With(pipe, body, otherwise, line));
+                } else {
+                    nodes.push(Node::Range(vars, pipe, body, otherwise, line));
+                }
+            }
+            "define" | "block" => {
+                // Named templates only matter through `include`.
+                parse_nodes(tokens, pos);
+            }
+            _ => {
+                let (vars, expr) = split_declaration(body);
+                match vars.into_iter().next() {
+                    Some(var) => nodes.push(Node::Assign(var, parse_pipe(expr), line)),
+                    None => nodes.push(Node::Output(parse_pipe(body), line)),
+                }
+            }
+        }
+    }
+    (nodes, Close::Eof)
+}
+
+/// `$k, $v := expr` → (`[$k, $v]`, `expr`).
+fn split_declaration(text: &str) -> (Vec<String>, &str) {
+    let Some((vars, expr)) = text.split_once(":=").or_else(|| {
+        text.split_once(" = ")
+            .filter(|(vars, _)| vars.trim_start().starts_with('$'))
+    }) else {
+        return (Vec::new(), text);
+    };
+    let vars: Vec<String> = vars.split(',').map(|var| var.trim().to_string()).collect();
+    if vars
+        .iter()
+        .all(|var| var.starts_with('$') && !var.contains(' '))
+    {
+        (vars, expr.trim())
+    } else {
+        (Vec::new(), text)
+    }
+}
+
+type Pipe = Vec<Vec<Term>>;
+
+#[derive(Debug, Clone)]
+enum Term {
+    /// `.a.b`, `$.a`, `$var.a` or `.`
+    Field(String),
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Nil,
+    Ident(String),
+    Sub(Pipe),
+}
+
+fn parse_pipe(text: &str) -> Pipe {
+    let chars: Vec<char> = text.chars().collect();
+    let mut pos = 0;
+    parse_pipe_chars(&chars, &mut pos)
+}
+
+fn parse_pipe_chars(chars: &[char], pos: &mut usize) -> Pipe {
+    let mut pipe = Vec::new();
+    let mut command = Vec::new();
+    while *pos < chars.len() {
+        let ch = chars[*pos];
+        match ch {
+            ' ' | '\t' | '\n' | '\r' => *pos += 1,
+            '|' => {
+                *pos += 1;
+                pipe.push(std::mem::take(&mut command));
+            }
+            '(' => {
+                *pos += 1;
+                command.push(Term::Sub(parse_pipe_chars(chars, pos)));
+            }
+            ')' => {
+                *pos += 1;
+                break;
+            }
+            '"' | '`' => {
+                *pos += 1;
+                let mut text = String::new();
+                while *pos < chars.len() && chars[*pos] != ch {
+                    if ch == '"' && chars[*pos] == '\\' && *pos + 1 < chars.len() {
+                        *pos += 1;
+                        text.push(match chars[*pos] {
+                            'n' => '\n',
+                            't' => '\t',
+                            other => other,
+                        });
+                    } else {
+                        text.push(chars[*pos]);
+                    }
+                    *pos += 1;
+                }
+                *pos += 1;
+                command.push(Term::Str(text));
+            }
+            _ => {
+                let start = *pos;
+                while *pos < chars.len() && !" \t\n\r|()".contains(chars[*pos]) {
+                    *pos += 1;
+                }
+                let word: String = chars[start..*pos].iter().collect();
+                command.push(match word.as_str() {
+                    "true" => Term::Bool(true),
+                    "false" => Term::Bool(false),
+                    "nil" => Term::Nil,
+                    _ if word.starts_with('.') || word.starts_with('$') => Term::Field(word),
+                    _ => match word.parse::<f64>() {
+                        Ok(number) => Term::Num(number),
+                        Err(_) => Term::Ident(word),
+                    },
+                });
+            }
+        }
+    }
+    if !command.is_empty() {
+        pipe.push(command);
+    }
+    pipe
+}
+
+/// A value and, when it came from `.Values`, its dotted path.
+#[derive(Debug, Clone)]
+struct Val {
+    value: Value,
+    path: Option<String>,
+}
+
+impl Val {
+    fn plain(value: Value) -> Self {
+        Self { value, path: None }
+    }
+
+    fn field(&self, key: &str) -> Val {
+        let value = self.value.get(key).cloned().unwrap_or(Value::Null);
+        let path = match &self.path {
+            Some(path) if path.is_empty() => Some(key.to_string()),
+            Some(path) => Some(format!("{path}.{key}")),
+            None => None,
+        };
+        Val { value, path }
+    }
+}
+
+struct Renderer<'a> {
+    chart_name: &'a str,
+    root: Val,
+    vars: Vec<(String, Val)>,
+    refs: Vec<ValueRef>,
+    line: usize,
+}
+
+impl Renderer<'_> {
+    fn render_nodes(&mut self, nodes: &[Node], dot: &Val, out: &mut String) {
+        let scope = self.vars.len();
+        for node in nodes {
+            self.render_node(node, dot, out);
+        }
+        self.vars.truncate(scope);
+    }
+
+    fn render_node(&mut self, node: &Node, dot: &Val, out: &mut String) {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Output(pipe, line) => {
+                self.line = *line;
+                let value = self.eval_pipe(pipe, dot);
+                out.push_str(&to_text(&value.value));
+            }
+            Node::Assign(var, pipe, line) => {
+                self.line = *line;
+                let value = self.eval_pipe(pipe, dot);
+                self.vars.push((var.clone(), value));
+            }
+            Node::If(branches, otherwise, line) => {
+                for (cond, body) in branches {
+                    self.line = *line;
+                    if truthy(&self.eval_pipe(cond, dot).value) {
+                        self.render_nodes(body, dot, out);
+                        return;
+                    }
+                }
+                self.render_nodes(otherwise, dot, out);
+            }
+            Node::With(pipe, body, otherwise, line) => {
+                self.line = *line;
+                let value = self.eval_pipe(pipe, dot);
+                if truthy(&value.value) {
+                    self.render_nodes(body, &value, out);
+                } else {
+                    self.render_nodes(otherwise, dot, out);
+                }
+            }
+            Node::Range(vars, pipe, body, otherwise, line) => {
+                self.line = *line;
+                let value = self.eval_pipe(pipe, dot);
+                let items: Vec<(Val, Val)> = match &value.value {
+                    // Go ranges over maps in key order.
+                    Value::Object(map) => {
+                        let mut keys: Vec<&String> = map.keys().collect();
+                        keys.sort();
+                        keys.into_iter()
+                            .map(|key| (Val::plain(json!(key)), value.field(key)))
+                            .collect()
+                    }
+                    Value::Array(items) => items
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, item)| (Val::plain(json!(idx)), Val::plain(item.clone())))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                if items.is_empty() {
+                    self.render_nodes(otherwise, dot, out);
+                }
+                for (key, item) in items {
+                    let scope = self.vars.len();
+                    match vars.as_slice() {
+                        [value_var] => self.vars.push((value_var.clone(), item.clone())),
+                        [key_var, value_var, ..] => {
+                            self.vars.push((key_var.clone(), key));
+                            self.vars.push((value_var.clone(), item.clone()));
+                        }
+                        [] => {}
+                    }
+                    self.render_nodes(body, &item, out);
+                    self.vars.truncate(scope);
+                }
+            }
+        }
+    }
+
+    fn eval_pipe(&mut self, pipe: &Pipe, dot: &Val) -> Val {
+        let mut result: Option<Val> = None;
+        let mut indented = false;
+        for (idx, command) in pipe.iter().enumerate() {
+            let Some(first) = command.first() else {
+                continue;
+            };
+            result = Some(match first {
+                Term::Ident(name) => {
+                    // An included helper is rendered as the chart name, which
+                    // is right for names but not for blocks of labels
+                    // spliced in with `nindent`; those render empty.
+                    if matches!(name.as_str(), "include" | "template")
+                        && pipe[idx + 1..].iter().any(|next| {
+                            matches!(next.first(), Some(Term::Ident(f)) if f == "nindent" || f == "indent" || f == "toYaml")
+                        })
+                    {
+                        indented = true;
+                    }
+                    let mut args: Vec<Val> = command[1..]
+                        .iter()
+                        .map(|term| self.eval_term(term, dot))
+                        .collect();
+                    if let Some(previous) = result.take() {
+                        args.push(previous);
+                    }
+                    if indented && matches!(name.as_str(), "include" | "template") {
+                        Val::plain(json!(""))
+                    } else {
+                        self.call(name, args)
+                    }
+                }
+                term => self.eval_term(term, dot),
+            });
+        }
+        result.unwrap_or(Val::plain(Value::Null))
+    }
+
+    fn eval_term(&mut self, term: &Term, dot: &Val) -> Val {
+        match term {
+            Term::Field(text) => self.eval_field(text, dot),
+            Term::Str(text) => Val::plain(json!(text)),
+            Term::Num(number) => Val::plain(json!(number)),
+            Term::Bool(value) => Val::plain(json!(value)),
+            Term::Nil => Val::plain(Value::Null),
+            Term::Ident(name) => self.call(name, Vec::new()),
+            Term::Sub(pipe) => self.eval_pipe(pipe, dot),
+        }
+    }
+
+    fn eval_field(&mut self, text: &str, dot: &Val) -> Val {
+        let (base, chain) = if let Some(rest) = text.strip_prefix('$') {
+            let (name, chain) = match rest.find('.') {
+                Some(idx) => (&rest[..idx], &rest[idx..]),
+                None => (rest, ""),
+            };
+            let base = if name.is_empty() {
+                self.root.clone()
+            } else {
+                let var = format!("${name}");
+                self.vars
+                    .iter()
+                    .rev()
+                    .find(|(candidate, _)| *candidate == var)
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or(Val::plain(Value::Null))
+            };
+            (base, chain)
+        } else {
+            (dot.clone(), text)
+        };
+        let mut current = base;
+        let mut is_root = current.path.is_none() && current.value == self.root.value;
+        for key in chain.split('.').filter(|key| !key.is_empty()) {
+            if is_root && key == "Values" {
+                current = Val {
+                    value: current.value.get("Values").cloned().unwrap_or(Value::Null),
+                    path: Some(String::new()),
+                };
+            } else {
+                current = current.field(key);
+            }
+            is_root = false;
+        }
+        if let Some(path) = &current.path
+            && !path.is_empty()
+        {
+            self.refs.push(ValueRef {
+                path: path.clone(),
+                line: self.line,
+            });
+        }
+        current
+    }
+
+    fn call(&mut self, name: &str, args: Vec<Val>) -> Val {
+        let text = |idx: usize| {
+            args.get(idx)
+                .map(|arg| to_text(&arg.value))
+                .unwrap_or_default()
+        };
+        let last = || args.last().cloned().unwrap_or(Val::plain(Value::Null));
+        let number = |idx: usize| {
+            args.get(idx)
+                .and_then(|arg| match &arg.value {
+                    Value::Number(number) => number.as_f64(),
+                    Value::String(text) => text.parse().ok(),
+                    _ => None,
+                })
+                .unwrap_or(0.0) as usize
+        };
+        match name {
+            "default" => match args.as_slice() {
+                [fallback, value, ..] if !truthy(&value.value) => fallback.clone(),
+                [_, value, ..] => value.clone(),
+                _ => last(),
+            },
+            "quote" => Val::plain(json!(format!("\"{}\"", text(args.len().saturating_sub(1))))),
+            "squote" => Val::plain(json!(format!("'{}'", text(args.len().saturating_sub(1))))),
+            "toYaml" => Val::plain(json!(to_yaml(&last().value))),
+            "toJson" | "toRawJson" | "toPrettyJson" => Val::plain(json!(last().value.to_string())),
+            "nindent" | "indent" => {
+                let pad = " ".repeat(number(0));
+                let indented = format!("{pad}{}", text(1).replace('\n', &format!("\n{pad}")));
+                let prefix = if name == "nindent" { "\n" } else { "" };
+                Val::plain(json!(format!("{prefix}{indented}")))
+            }
+            "upper" => Val::plain(json!(text(0).to_uppercase())),
+            "lower" => Val::plain(json!(text(0).to_lowercase())),
+            "trim" => Val::plain(json!(text(0).trim())),
+            "trunc" => Val::plain(json!(text(1).chars().take(number(0)).collect::<String>())),
+            "trimSuffix" => Val::plain(json!(
+                text(1)
+                    .strip_suffix(&text(0))
+                    .map_or(text(1), str::to_string)
+            )),
+            "trimPrefix" => Val::plain(json!(
+                text(1)
+                    .strip_prefix(&text(0))
+                    .map_or(text(1), str::to_string)
+            )),
+            "replace" => Val::plain(json!(text(2).replace(&text(0), &text(1)))),
+            "printf" => {
+                let mut rendered = text(0);
+                for arg in args.iter().skip(1) {
+                    let Some(idx) = rendered.find('%') else {
+                        break;
+                    };
+                    let end = (idx + 2).min(rendered.len());
+                    rendered.replace_range(idx..end, &to_text(&arg.value));
+                }
+                Val::plain(json!(rendered))
+            }
+            "toString" => Val::plain(json!(text(0))),
+            "required" => last(),
+            "tpl" | "b64enc" | "b64dec" | "int" | "int64" | "float64" => {
+                args.first().cloned().unwrap_or(Val::plain(Value::Null))
+            }
+            "include" | "template" => Val::plain(json!(self.chart_name)),
+            "not" => Val::plain(json!(!truthy(&last().value))),
+            "empty" => Val::plain(json!(!truthy(&last().value))),
+            "and" => args
+                .iter()
+                .find(|arg| !truthy(&arg.value))
+                .cloned()
+                .unwrap_or_else(last),
+            "or" => args
+                .iter()
+                .find(|arg| truthy(&arg.value))
+                .cloned()
+                .unwrap_or_else(last),
+            "eq" => Val::plain(json!(
+                args.len() > 1 && args[1..].iter().any(|arg| arg.value == args[0].value)
+            )),
+            "ne" => Val::plain(json!(args.len() > 1 && args[0].value != args[1].value)),
+            "hasKey" => Val::plain(json!(
+                args.first()
+                    .and_then(|map| map.value.as_object())
+                    .is_some_and(|map| map.contains_key(&text(1)))
+            )),
+            "list" => Val::plain(Value::Array(
+                args.into_iter().map(|arg| arg.value).collect(),
+            )),
+            "dict" => {
+                let mut map = Map::new();
+                for pair in args.chunks(2) {
+                    if let [key, value] = pair {
+                        map.insert(to_text(&key.value), value.value.clone());
+                    }
+                }
+                Val::plain(Value::Object(map))
+            }
+            "get" => match args.as_slice() {
+                [map, key, ..] => map.field(&to_text(&key.value)),
+                _ => Val::plain(Value::Null),
+            },
+            _ => Val::plain(Value::Null),
+        }
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64().is_some_and(|number| number != 0.0),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Number(number) => match number.as_f64() {
+            Some(float) if float.fract() == 0.0 && float.abs() < 1e15 => {
+                format!("{}", float as i64)
+            }
+            _ => number.to_string(),
+        },
+        Value::Bool(value) => value.to_string(),
+        other => to_yaml(other),
+    }
+}
+
+fn to_yaml(value: &Value) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    serde_yaml_ng::to_string(value)
+        .map(|text| text.trim_end().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(values: &str) -> Chart {
+        let values: serde_yaml_ng::Value = serde_yaml_ng::from_str(values).unwrap();
+        Chart {
+            dir: "charts/api".to_string(),
+            name: "api".to_string(),
+            values: serde_json::to_value(values).unwrap(),
+        }
+    }
+
+    #[test]
+    fn renders_values_and_pipes() {
+        let chart = chart("image:\n  repository: api\n  tag: 1.2\nreplicas: 2\n");
+        let (text, refs) = render(
+            "name: {{ .Release.Name }}-{{ .Chart.Name }}\nimage: \"{{ .Values.image.repository }}:{{ .Values.image.tag }}\"\nreplicas: {{ .Values.replicas | default 1 }}\npull: {{ .Values.pullPolicy | default \"Always\" | quote }}\n",
+            &chart,
+        );
+        assert_eq!(
+            text,
+            "name: RELEASE-NAME-api\nimage: \"api:1.2\"\nreplicas: 2\npull: \"Always\"\n"
+        );
+        let paths: Vec<(&str, usize)> = refs
+            .iter()
+            .map(|reference| (reference.path.as_str(), reference.line))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("image.repository", 2),
+                ("image.tag", 2),
+                ("replicas", 3),
+                ("pullPolicy", 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_control_flow_with_trimming() {
+        let chart = chart(
+            "env:\n  LOG_LEVEL: info\n  DATABASE_URL: postgres://db\ndebug: false\nservice:\n  port: 80\n",
+        );
+        let template = "env:\n{{- range $name, $value := .Values.env }}\n- name: {{ $name }}\n  value: {{ $value | quote }}\n{{- end }}\n{{- if .Values.debug }}\n- name: DEBUG\n{{- else }}\n- name: QUIET\n{{- end }}\n{{- with .Values.service }}\nport: {{ .port }}\n{{- end }}\n";
+        let (text, refs) = render(template, &chart);
+        assert_eq!(
+            text,
+            "env:\n- name: DATABASE_URL\n  value: \"postgres://db\"\n- name: LOG_LEVEL\n  value: \"info\"\n- name: QUIET\nport: 80\n"
+        );
+        assert!(
+            refs.iter()
+                .any(|reference| reference.path == "env.LOG_LEVEL")
+        );
+        assert!(
+            refs.iter()
+                .any(|reference| reference.path == "service.port")
+        );
+    }
+
+    #[test]
+    fn included_blocks_render_empty_under_nindent() {
+        let chart = chart("{}");
+        let (text, _) = render(
+            "metadata:\n  name: {{ include \"api.fullname\" . }}\n  labels:\n    {{- include \"api.labels\" . | nindent 4 }}\n    tier: web\n",
+            &chart,
+        );
+        assert_eq!(
+            text,
+            "metadata:\n  name: api\n  labels:\n    \n    tier: web\n"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::indexer::xref;
+use crate::indexer::{rules, xref};
 use serde_json::json;
 
 pub const HTTP_ROUTE_KIND: &str = "HTTP_ROUTE";
@@ -29,8 +29,13 @@ pub fn normalize_method(raw: &str) -> Option<String> {
     None
 }
 
+/// Normalize a route or URL path, removing any `.lidx.toml` base path.
 pub fn normalize_path(raw: &str) -> Option<String> {
-    xref::normalize_route_literal(raw)
+    let normalized = xref::normalize_route_literal(raw)?;
+    Some(
+        rules::with_active(|rules, _| rules.strip_base_path(normalized.clone()))
+            .unwrap_or(normalized),
+    )
 }
 
 pub fn build_route_detail(
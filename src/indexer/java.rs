@@ -0,0 +1,1047 @@
+use crate::indexer::extract::{EdgeInput, ExtractedFile};
+use crate::indexer::http;
+use crate::indexer::proto;
+use crate::indexer::tree_helpers::{
+    module_symbol_fallback, module_symbol_with_span, node_text, span, symbol_for_node,
+};
+use crate::util;
+use anyhow::Result;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+// The JVM conventions below (import resolution, Spring mappings, gRPC base
+// classes and stubs, Javadoc/KDoc) are shared with the Kotlin extractor; each
+// language walks its own grammar and feeds them annotations and call sites.
+
+pub(crate) type Span = (i64, i64, i64, i64, i64, i64);
+
+#[derive(Clone, Debug)]
+pub(crate) struct Annotation {
+    pub(crate) name: String,
+    pub(crate) args: Vec<AnnotationArg>,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AnnotationArg {
+    pub(crate) key: Option<String>,
+    pub(crate) strings: Vec<String>,
+    pub(crate) idents: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct CallSite {
+    pub(crate) raw: String,
+    pub(crate) name: String,
+    pub(crate) receiver: Option<String>,
+    pub(crate) simple: bool,
+    pub(crate) is_constructor: bool,
+    pub(crate) span: Span,
+}
+
+impl CallSite {
+    /// A call of `name`, through `chain` when the receiver is a plain dotted
+    /// name (`a.b`, `this`, `super`) and `None` when it is any other expression.
+    pub(crate) fn new(name: String, chain: Option<Option<String>>, span: Span) -> Self {
+        let (raw, simple) = match chain {
+            Some(Some(receiver)) => (format!("{receiver}.{name}"), true),
+            Some(None) => (name.clone(), true),
+            None => (name.clone(), false),
+        };
+        let receiver = raw
+            .rsplit_once('.')
+            .map(|(receiver, _)| receiver.to_string());
+        Self {
+            raw,
+            name,
+            receiver,
+            simple,
+            is_constructor: false,
+            span,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct TypeCtx {
+    type_stack: Vec<String>,
+    route_prefix: Option<String>,
+    grpc_service: Option<String>,
+}
+
+pub struct JavaExtractor {
+    parser: Parser,
+}
+
+impl JavaExtractor {
+    pub fn new() -> Result<Self> {
+        let mut parser = Parser::new();
+        let language = tree_sitter_java::LANGUAGE;
+        parser.set_language(&language.into())?;
+        Ok(Self { parser })
+    }
+}
+
+impl crate::indexer::extract::LanguageExtractor for JavaExtractor {
+    fn module_name_from_rel_path(&self, rel_path: &str) -> String {
+        module_name_from_rel_path(rel_path)
+    }
+
+    fn extract(&mut self, source: &str, module_name: &str) -> Result<ExtractedFile> {
+        let mut output = ExtractedFile::default();
+        let tree = match self.parser.parse(source, None) {
+            Some(tree) => tree,
+            None => {
+                output
+                    .symbols
+                    .push(module_symbol_fallback(module_name, source, "/", None));
+                return Ok(output);
+            }
+        };
+        let root = tree.root_node();
+        output
+            .symbols
+            .push(module_symbol_with_span(module_name, span(root), "/", None));
+        let mut grpc_stubs = HashMap::new();
+        collect_grpc_stubs(root, source, &mut grpc_stubs);
+        let mut walker = JavaWalker {
+            source,
+            module: module_name.to_string(),
+            package: None,
+            imports: HashMap::new(),
+            grpc_stubs,
+            output,
+        };
+        let mut cursor = root.walk();
+        for child in root.named_children(&mut cursor) {
+            match child.kind() {
+                "package_declaration" => {
+                    walker.package = child
+                        .named_children(&mut child.walk())
+                        .find(|node| matches!(node.kind(), "scoped_identifier" | "identifier"))
+                        .map(|node| node_text(node, source));
+                }
+                "import_declaration" => walker.handle_import(child),
+                _ => walker.walk_member(child, &TypeCtx::default()),
+            }
+        }
+        Ok(walker.output)
+    }
+}
+
+pub fn module_name_from_rel_path(rel_path: &str) -> String {
+    let path = Path::new(rel_path);
+    let mut parts: Vec<String> = path
+        .components()
+        .filter_map(|comp| comp.as_os_str().to_str().map(|s| s.to_string()))
+        .collect();
+    if parts.is_empty() {
+        return "module".to_string();
+    }
+    let file = parts.pop().unwrap_or_default();
+    let stem = Path::new(&file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&file)
+        .to_string();
+    if !stem.is_empty() {
+        parts.push(stem);
+    }
+    if parts.is_empty() {
+        "module".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+struct JavaWalker<'a> {
+    source: &'a str,
+    module: String,
+    package: Option<String>,
+    imports: HashMap<String, String>,
+    grpc_stubs: HashMap<String, String>,
+    output: ExtractedFile,
+}
+
+impl JavaWalker<'_> {
+    fn handle_import(&mut self, node: Node<'_>) {
+        let Some(name_node) = node
+            .named_children(&mut node.walk())
+            .find(|child| matches!(child.kind(), "scoped_identifier" | "identifier"))
+        else {
+            return;
+        };
+        let target = node_text(name_node, self.source);
+        let wildcard = node
+            .named_children(&mut node.walk())
+            .any(|child| child.kind() == "asterisk");
+        if !wildcard && let Some(simple) = target.rsplit('.').next() {
+            self.imports.insert(simple.to_string(), target.clone());
+        }
+        self.output
+            .edges
+            .push(import_edge(&self.module, target, node, self.source));
+    }
+
+    fn walk_member(&mut self, node: Node<'_>, ctx: &TypeCtx) {
+        match node.kind() {
+            "class_declaration"
+            | "interface_declaration"
+            | "enum_declaration"
+            | "record_declaration"
+            | "annotation_type_declaration" => self.handle_type(node, ctx),
+            "method_declaration" | "annotation_type_element_declaration" => {
+                self.handle_method(node, ctx, false)
+            }
+            "constructor_declaration" | "compact_constructor_declaration" => {
+                self.handle_method(node, ctx, true)
+            }
+            "field_declaration" | "constant_declaration" => self.handle_field(node, ctx),
+            "static_initializer" | "block" | "enum_constant" => {
+                let scope = self.container_qualname(ctx);
+                self.walk_calls(node, &scope, &scope);
+            }
+            "enum_body_declarations" => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.walk_member(child, ctx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_type(&mut self, node: Node<'_>, ctx: &TypeCtx) {
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return;
+        };
+        let name = node_text(name_node, self.source);
+        if name.is_empty() {
+            return;
+        }
+        let kind = match node.kind() {
+            "interface_declaration" | "annotation_type_declaration" => "interface",
+            "enum_declaration" => "enum",
+            "record_declaration" => "record",
+            _ => "class",
+        };
+        let qualname = self.qualname(ctx, &name);
+        let body = node.child_by_field_name("body");
+        let signature = declaration_header(node, body, self.source);
+        self.output.symbols.push(symbol_for_node(
+            kind,
+            &name,
+            &qualname,
+            node,
+            signature,
+            doc_comment_before(self.source, node.start_byte() as i64),
+        ));
+        self.push_contains(ctx, &qualname);
+
+        let mut extends = Vec::new();
+        let mut implements = Vec::new();
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            let (bucket, list) = match child.kind() {
+                "superclass" => (&mut extends, child),
+                "extends_interfaces" => (&mut extends, child),
+                "super_interfaces" => (&mut implements, child),
+                _ => continue,
+            };
+            let types = list
+                .named_children(&mut list.walk())
+                .find(|inner| inner.kind() == "type_list")
+                .unwrap_or(list);
+            let mut types_cursor = types.walk();
+            for type_node in types.named_children(&mut types_cursor) {
+                bucket.push(type_name(type_node, self.source));
+            }
+        }
+        for base in &extends {
+            self.push_type_edge("EXTENDS", &qualname, base);
+        }
+        for iface in &implements {
+            self.push_type_edge("IMPLEMENTS", &qualname, iface);
+        }
+
+        let annotations = annotations_of(node, self.source);
+        let mut next_ctx = ctx.clone();
+        next_ctx.type_stack.push(name);
+        next_ctx.route_prefix =
+            spring_route_prefix(&annotations).or_else(|| ctx.route_prefix.clone());
+        next_ctx.grpc_service = extends.iter().find_map(|base| grpc_service_from_base(base));
+
+        // Record components are the record's fields.
+        if let Some(params) = node.child_by_field_name("parameters") {
+            let mut cursor = params.walk();
+            for param in params.named_children(&mut cursor) {
+                let Some(param_name) = param.child_by_field_name("name") else {
+                    continue;
+                };
+                let name = node_text(param_name, self.source);
+                let field_qualname = self.qualname(&next_ctx, &name);
+                self.output.symbols.push(symbol_for_node(
+                    "field",
+                    &name,
+                    &field_qualname,
+                    param,
+                    Some(collapse_whitespace(&node_text(param, self.source))),
+                    None,
+                ));
+                self.push_contains(&next_ctx, &field_qualname);
+            }
+        }
+        if let Some(body) = body {
+            let mut cursor = body.walk();
+            for child in body.named_children(&mut cursor) {
+                self.walk_member(child, &next_ctx);
+            }
+        }
+    }
+
+    fn handle_method(&mut self, node: Node<'_>, ctx: &TypeCtx, is_constructor: bool) {
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return;
+        };
+        let name = node_text(name_node, self.source);
+        let params = node
+            .child_by_field_name("parameters")
+            .map(|params| collapse_whitespace(&node_text(params, self.source)))
+            .unwrap_or_else(|| "()".to_string());
+        let return_type = node
+            .child_by_field_name("type_parameters")
+            .or_else(|| node.child_by_field_name("type"))
+            .map(|start| {
+                collapse_whitespace(slice_source(
+                    self.source,
+                    start.start_byte() as i64,
+                    name_node.start_byte() as i64,
+                ))
+            })
+            .unwrap_or_default();
+        let signature = if return_type.is_empty() {
+            params
+        } else {
+            format!("{params} -> {return_type}")
+        };
+        let qualname = self.qualname(ctx, &name);
+        self.output.symbols.push(symbol_for_node(
+            "method",
+            &name,
+            &qualname,
+            node,
+            Some(signature),
+            doc_comment_before(self.source, node.start_byte() as i64),
+        ));
+        self.push_contains(ctx, &qualname);
+        if !is_constructor {
+            let annotations = annotations_of(node, self.source);
+            self.output.edges.extend(spring_route_edges(
+                ctx.route_prefix.as_deref(),
+                &annotations,
+                &qualname,
+                self.source,
+            ));
+            if let Some(edge) = grpc_impl_edge(
+                ctx.grpc_service.as_deref(),
+                &qualname,
+                &name,
+                node,
+                self.source,
+                "grpc-java",
+            ) {
+                self.output.edges.push(edge);
+            }
+        }
+        if let Some(body) = node.child_by_field_name("body") {
+            let container = self.container_qualname(ctx);
+            self.walk_calls(body, &qualname, &container);
+        }
+    }
+
+    fn handle_field(&mut self, node: Node<'_>, ctx: &TypeCtx) {
+        let type_start = node
+            .child_by_field_name("type")
+            .map(|type_node| type_node.start_byte())
+            .unwrap_or(node.start_byte());
+        let scope = self.container_qualname(ctx);
+        let mut cursor = node.walk();
+        for declarator in node.children_by_field_name("declarator", &mut cursor) {
+            let Some(name_node) = declarator.child_by_field_name("name") else {
+                continue;
+            };
+            let name = node_text(name_node, self.source);
+            let qualname = self.qualname(ctx, &name);
+            let signature = collapse_whitespace(slice_source(
+                self.source,
+                type_start as i64,
+                name_node.end_byte() as i64,
+            ));
+            self.output.symbols.push(symbol_for_node(
+                "field",
+                &name,
+                &qualname,
+                node,
+                Some(signature).filter(|value| !value.is_empty()),
+                None,
+            ));
+            self.push_contains(ctx, &qualname);
+            if let Some(value) = declarator.child_by_field_name("value") {
+                self.walk_calls(value, &scope, &scope);
+            }
+        }
+    }
+
+    /// CALLS edges for every invocation under `node`, attributed to `scope`;
+    /// bare names resolve against `container`. Local and anonymous class
+    /// bodies count as part of the enclosing scope.
+    fn walk_calls(&mut self, node: Node<'_>, scope: &str, container: &str) {
+        match node.kind() {
+            "method_invocation" => {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    let chain = match node.child_by_field_name("object") {
+                        Some(object) => receiver_chain(object, self.source).map(Some),
+                        None => Some(None),
+                    };
+                    let call = CallSite::new(node_text(name_node, self.source), chain, span(node));
+                    self.push_call(call, scope, container);
+                }
+            }
+            "object_creation_expression" => {
+                if let Some(type_node) = node.child_by_field_name("type") {
+                    let raw = type_name(type_node, self.source);
+                    let mut call = CallSite::new(raw, Some(None), span(node));
+                    call.is_constructor = true;
+                    self.push_call(call, scope, container);
+                }
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.walk_calls(child, scope, container);
+        }
+    }
+
+    fn push_call(&mut self, call: CallSite, scope: &str, container: &str) {
+        if let Some(edge) = grpc_call_edge(&call, &self.grpc_stubs, scope, "grpc-java") {
+            self.output.edges.push(edge);
+        }
+        let target = if call.is_constructor {
+            Some(resolve_type_name(&call.raw, &self.imports))
+        } else if call.simple {
+            resolve_call_target(&call.raw, container, &self.imports)
+        } else {
+            None
+        };
+        self.output
+            .edges
+            .push(call_edge(&call, scope, target, self.source));
+    }
+
+    fn push_contains(&mut self, ctx: &TypeCtx, qualname: &str) {
+        self.output.edges.push(EdgeInput {
+            kind: "CONTAINS".to_string(),
+            source_qualname: Some(self.container_qualname(ctx)),
+            target_qualname: Some(qualname.to_string()),
+            detail: None,
+            evidence_snippet: None,
+            ..Default::default()
+        });
+    }
+
+    fn push_type_edge(&mut self, kind: &str, source: &str, target: &str) {
+        self.output.edges.push(EdgeInput {
+            kind: kind.to_string(),
+            source_qualname: Some(source.to_string()),
+            target_qualname: Some(resolve_type_name(target, &self.imports)),
+            detail: None,
+            evidence_snippet: None,
+            ..Default::default()
+        });
+    }
+
+    fn qualname(&self, ctx: &TypeCtx, name: &str) -> String {
+        jvm_qualname(self.package.as_deref(), &self.module, &ctx.type_stack, name)
+    }
+
+    fn container_qualname(&self, ctx: &TypeCtx) -> String {
+        jvm_container_qualname(self.package.as_deref(), &self.module, &ctx.type_stack)
+    }
+}
+
+/// Dotted name of a call receiver made only of identifiers, `this` and
+/// `super`; `None` for any other expression.
+fn receiver_chain(node: Node<'_>, source: &str) -> Option<String> {
+    match node.kind() {
+        "identifier" | "this" | "super" => Some(node_text(node, source)),
+        "field_access" => {
+            let object = receiver_chain(node.child_by_field_name("object")?, source)?;
+            let field = node_text(node.child_by_field_name("field")?, source);
+            Some(format!("{object}.{field}"))
+        }
+        _ => None,
+    }
+}
+
+/// Type name without generic arguments: `List<String>` → `List`,
+/// `GreeterGrpc.GreeterImplBase` as written.
+fn type_name(node: Node<'_>, source: &str) -> String {
+    match node.kind() {
+        "generic_type" => node
+            .named_child(0)
+            .map(|inner| type_name(inner, source))
+            .unwrap_or_default(),
+        "scoped_type_identifier" => {
+            let mut parts = Vec::new();
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                parts.push(type_name(child, source));
+            }
+            parts.join(".")
+        }
+        _ => node_text(node, source),
+    }
+}
+
+/// Declaration text from its keyword (after modifiers and annotations) up
+/// to the body.
+fn declaration_header(node: Node<'_>, body: Option<Node<'_>>, source: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    let start = node
+        .children(&mut cursor)
+        .find(|child| child.kind() != "modifiers")?
+        .start_byte();
+    let end = body
+        .map(|body| body.start_byte())
+        .unwrap_or(node.end_byte());
+    Some(collapse_whitespace(slice_source(
+        source,
+        start as i64,
+        end as i64,
+    )))
+    .filter(|value| !value.is_empty())
+}
+
+fn annotations_of(node: Node<'_>, source: &str) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+    let mut cursor = node.walk();
+    for modifiers in node
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "modifiers")
+    {
+        let mut inner = modifiers.walk();
+        for child in modifiers.named_children(&mut inner) {
+            if !matches!(child.kind(), "annotation" | "marker_annotation") {
+                continue;
+            }
+            let Some(name_node) = child.child_by_field_name("name") else {
+                continue;
+            };
+            let mut args = Vec::new();
+            if let Some(list) = child.child_by_field_name("arguments") {
+                let mut list_cursor = list.walk();
+                for arg in list.named_children(&mut list_cursor) {
+                    let mut parsed = AnnotationArg::default();
+                    let value = if arg.kind() == "element_value_pair" {
+                        parsed.key = arg
+                            .child_by_field_name("key")
+                            .map(|key| node_text(key, source));
+                        arg.child_by_field_name("value")
+                    } else {
+                        Some(arg)
+                    };
+                    if let Some(value) = value {
+                        collect_literals(value, source, &mut parsed);
+                    }
+                    args.push(parsed);
+                }
+            }
+            annotations.push(Annotation {
+                name: node_text(name_node, source),
+                args,
+                span: span(child),
+            });
+        }
+    }
+    annotations
+}
+
+/// Gather the string literals and identifiers of an annotation argument.
+pub(crate) fn collect_literals(node: Node<'_>, source: &str, arg: &mut AnnotationArg) {
+    match node.kind() {
+        "string_literal" => {
+            let text = node_text(node, source);
+            arg.strings.push(text.trim_matches('"').to_string());
+            return;
+        }
+        "identifier" => {
+            arg.idents.push(node_text(node, source));
+            return;
+        }
+        _ => {}
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_literals(child, source, arg);
+    }
+}
+
+/// Record variables bound to generated gRPC stubs, e.g.
+/// `stub = GreeterGrpc.newBlockingStub(channel)`.
+fn collect_grpc_stubs(node: Node<'_>, source: &str, stubs: &mut HashMap<String, String>) {
+    let binding = match node.kind() {
+        "variable_declarator" => node
+            .child_by_field_name("name")
+            .zip(node.child_by_field_name("value")),
+        "assignment_expression" => node
+            .child_by_field_name("left")
+            .map(|left| left.child_by_field_name("field").unwrap_or(left))
+            .zip(node.child_by_field_name("right")),
+        _ => None,
+    };
+    if let Some((name, value)) = binding
+        && value.kind() == "method_invocation"
+        && let Some(holder) = value
+            .child_by_field_name("object")
+            .filter(|object| object.kind() == "identifier")
+        && let Some(factory) = value.child_by_field_name("name")
+        && let Some(service) =
+            grpc_service_from_stub_factory(&node_text(holder, source), &node_text(factory, source))
+    {
+        stubs.insert(node_text(name, source), service);
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_grpc_stubs(child, source, stubs);
+    }
+}
+
+pub(crate) fn import_edge(module: &str, target: String, node: Node<'_>, source: &str) -> EdgeInput {
+    let (start_line, _, end_line, _, start_byte, end_byte) = span(node);
+    EdgeInput {
+        kind: "IMPORTS".to_string(),
+        source_qualname: Some(module.to_string()),
+        target_qualname: Some(target),
+        detail: None,
+        evidence_snippet: util::edge_evidence_snippet(
+            source, start_byte, end_byte, start_line, end_line,
+        ),
+        evidence_start_line: Some(start_line),
+        evidence_end_line: Some(end_line),
+        ..Default::default()
+    }
+}
+
+/// CALLS edge for `call`; the raw callee goes to `detail` when unresolved.
+pub(crate) fn call_edge(
+    call: &CallSite,
+    scope: &str,
+    target: Option<String>,
+    source: &str,
+) -> EdgeInput {
+    let (start_line, _, end_line, _, start_byte, end_byte) = call.span;
+    let detail = if target.is_some() {
+        None
+    } else {
+        Some(call.raw.clone())
+    };
+    EdgeInput {
+        kind: "CALLS".to_string(),
+        source_qualname: Some(scope.to_string()),
+        target_qualname: target,
+        detail,
+        evidence_snippet: util::edge_evidence_snippet(
+            source, start_byte, end_byte, start_line, end_line,
+        ),
+        evidence_start_line: Some(start_line),
+        evidence_end_line: Some(end_line),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn jvm_qualname(
+    package: Option<&str>,
+    module: &str,
+    type_stack: &[String],
+    name: &str,
+) -> String {
+    let mut parts = vec![base_qualname(package, module)];
+    parts.extend(type_stack.iter().cloned());
+    parts.push(name.to_string());
+    parts.join(".")
+}
+
+pub(crate) fn jvm_container_qualname(
+    package: Option<&str>,
+    module: &str,
+    type_stack: &[String],
+) -> String {
+    if type_stack.is_empty() {
+        return module.to_string();
+    }
+    let mut parts = vec![base_qualname(package, module)];
+    parts.extend(type_stack.iter().cloned());
+    parts.join(".")
+}
+
+fn base_qualname(package: Option<&str>, module: &str) -> String {
+    match package {
+        Some(package) if !package.is_empty() => package.to_string(),
+        _ => module.to_string(),
+    }
+}
+
+pub(crate) fn resolve_call_target(
+    raw: &str,
+    container: &str,
+    imports: &HashMap<String, String>,
+) -> Option<String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let rest = raw
+        .strip_prefix("this.")
+        .or_else(|| raw.strip_prefix("super."))
+        .unwrap_or(raw);
+    if !rest.contains('.') {
+        if rest.chars().next().is_some_and(|ch| ch.is_uppercase()) {
+            return Some(resolve_type_name(rest, imports));
+        }
+        if container.is_empty() {
+            return Some(rest.to_string());
+        }
+        return Some(format!("{container}.{rest}"));
+    }
+    if let Some((head, tail)) = rest.split_once('.')
+        && let Some(full) = imports.get(head)
+    {
+        return Some(format!("{full}.{tail}"));
+    }
+    Some(rest.to_string())
+}
+
+pub(crate) fn resolve_type_name(name: &str, imports: &HashMap<String, String>) -> String {
+    let name = name.trim();
+    if let Some((head, tail)) = name.split_once('.') {
+        if let Some(full) = imports.get(head) {
+            return format!("{full}.{tail}");
+        }
+        return name.to_string();
+    }
+    imports
+        .get(name)
+        .cloned()
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// `GreeterGrpc.newBlockingStub` / `GreeterGrpcKt.GreeterCoroutineStub` → `Greeter`.
+pub(crate) fn grpc_service_from_stub_factory(holder: &str, factory: &str) -> Option<String> {
+    let service = holder
+        .strip_suffix("GrpcKt")
+        .or_else(|| holder.strip_suffix("Grpc"))?;
+    if service.is_empty() {
+        return None;
+    }
+    let is_factory = (factory.starts_with("new") && factory.ends_with("Stub"))
+        || factory == format!("{service}CoroutineStub");
+    if !is_factory {
+        return None;
+    }
+    Some(service.to_string())
+}
+
+/// `GreeterGrpc.GreeterImplBase` / `GreeterGrpcKt.GreeterCoroutineImplBase` → `Greeter`.
+pub(crate) fn grpc_service_from_base(base: &str) -> Option<String> {
+    let trimmed = base.trim();
+    let mut parts = trimmed.rsplit('.');
+    let last = parts.next()?.trim();
+    let service = last.strip_suffix("ImplBase")?;
+    let service = service.strip_suffix("Coroutine").unwrap_or(service);
+    if service.is_empty() {
+        return None;
+    }
+    if let Some(holder) = parts.next() {
+        let holder = holder
+            .strip_suffix("GrpcKt")
+            .or_else(|| holder.strip_suffix("Grpc"))?;
+        if holder != service {
+            return None;
+        }
+    }
+    Some(service.to_string())
+}
+
+/// `/Service/Method` path of an rpc implemented or called from JVM code.
+/// Methods are the lowerCamelCase form of the proto rpc (`sayHello` for
+/// `SayHello`), and the Java package comes from `java_package` rather than
+/// the proto package, so the path carries no package, as in Go.
+fn jvm_rpc_path(service: &str, method: &str) -> Option<(String, String, String)> {
+    let mut chars = method.chars();
+    let rpc: String = chars.next()?.to_uppercase().chain(chars).collect();
+    let (raw_path, normalized) = proto::normalize_rpc_path(None, service, &rpc)?;
+    Some((rpc, raw_path, normalized))
+}
+
+pub(crate) fn grpc_impl_edge(
+    service: Option<&str>,
+    qualname: &str,
+    method: &str,
+    node: Node<'_>,
+    source: &str,
+    framework: &str,
+) -> Option<EdgeInput> {
+    let service = service?;
+    let (rpc, raw_path, normalized) = jvm_rpc_path(service, method)?;
+    let (start_line, _, end_line, _, start_byte, end_byte) = span(node);
+    let snippet = util::edge_evidence_snippet(source, start_byte, end_byte, start_line, end_line);
+    let detail = json!({
+        "framework": framework,
+        "role": "server",
+        "service": service,
+        "rpc": rpc,
+        "raw": raw_path,
+    })
+    .to_string();
+    Some(EdgeInput {
+        kind: proto::RPC_IMPL_KIND.to_string(),
+        source_qualname: Some(qualname.to_string()),
+        target_qualname: Some(normalized),
+        detail: Some(detail),
+        evidence_snippet: snippet,
+        evidence_start_line: Some(start_line),
+        evidence_end_line: Some(end_line),
+        ..Default::default()
+    })
+}
+
+pub(crate) fn grpc_call_edge(
+    call: &CallSite,
+    stubs: &HashMap<String, String>,
+    scope: &str,
+    framework: &str,
+) -> Option<EdgeInput> {
+    let receiver = call.receiver.as_deref()?;
+    let last = receiver.rsplit('.').next().unwrap_or(receiver);
+    let service = stubs.get(last)?;
+    let (rpc, raw_path, normalized) = jvm_rpc_path(service, &call.name)?;
+    let detail = json!({
+        "framework": framework,
+        "role": "client",
+        "service": service,
+        "rpc": rpc,
+        "raw": raw_path,
+    })
+    .to_string();
+    let (start_line, _, end_line, _, _, _) = call.span;
+    Some(EdgeInput {
+        kind: proto::RPC_CALL_KIND.to_string(),
+        source_qualname: Some(scope.to_string()),
+        target_qualname: Some(normalized),
+        detail: Some(detail),
+        evidence_snippet: None,
+        evidence_start_line: Some(start_line),
+        evidence_end_line: Some(end_line),
+        ..Default::default()
+    })
+}
+
+const SPRING_MAPPINGS: &[(&str, Option<&str>)] = &[
+    ("GetMapping", Some("GET")),
+    ("PostMapping", Some("POST")),
+    ("PutMapping", Some("PUT")),
+    ("DeleteMapping", Some("DELETE")),
+    ("PatchMapping", Some("PATCH")),
+    ("RequestMapping", None),
+];
+
+fn annotation_simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+fn annotation_path(annotation: &Annotation) -> Option<String> {
+    annotation
+        .args
+        .iter()
+        .filter(|arg| matches!(arg.key.as_deref(), None | Some("value") | Some("path")))
+        .find_map(|arg| arg.strings.first().cloned())
+}
+
+pub(crate) fn spring_route_prefix(annotations: &[Annotation]) -> Option<String> {
+    annotations
+        .iter()
+        .filter(|annotation| annotation_simple_name(&annotation.name) == "RequestMapping")
+        .find_map(annotation_path)
+}
+
+/// Spring `@GetMapping("/x")`, `@RequestMapping(value = "/x", method = RequestMethod.GET)`
+/// and friends, combined with the class-level `@RequestMapping` prefix.
+pub(crate) fn spring_route_edges(
+    prefix: Option<&str>,
+    annotations: &[Annotation],
+    handler: &str,
+    source: &str,
+) -> Vec<EdgeInput> {
+    let mut edges = Vec::new();
+    for annotation in annotations {
+        let simple = annotation_simple_name(&annotation.name);
+        let Some((_, fixed_method)) = SPRING_MAPPINGS.iter().find(|(name, _)| *name == simple)
+        else {
+            continue;
+        };
+        let methods: Vec<String> = match fixed_method {
+            Some(method) => vec![method.to_string()],
+            None => {
+                let declared: Vec<String> = annotation
+                    .args
+                    .iter()
+                    .filter(|arg| arg.key.as_deref() == Some("method"))
+                    .flat_map(|arg| arg.idents.iter())
+                    .filter_map(|ident| http::normalize_method(ident))
+                    .collect();
+                if declared.is_empty() {
+                    vec![http::HTTP_ANY.to_string()]
+                } else {
+                    declared
+                }
+            }
+        };
+        let path = annotation_path(annotation);
+        let raw_path = match (prefix, path.as_deref()) {
+            (Some(prefix), Some(path)) => http::join_paths(prefix, path),
+            (Some(prefix), None) => http::join_paths(prefix, ""),
+            (None, Some(path)) => path.to_string(),
+            (None, None) => "/".to_string(),
+        };
+        let mut raw_path = raw_path.trim().to_string();
+        if !raw_path.starts_with('/') {
+            raw_path = format!("/{raw_path}");
+        }
+        let Some(normalized) = http::normalize_path(&raw_path) else {
+            continue;
+        };
+        let (start_line, _, end_line, _, start_byte, end_byte) = annotation.span;
+        let snippet =
+            util::edge_evidence_snippet(source, start_byte, end_byte, start_line, end_line);
+        for method in methods {
+            let detail = http::build_route_detail(&method, &normalized, &raw_path, "spring");
+            edges.push(EdgeInput {
+                kind: http::HTTP_ROUTE_KIND.to_string(),
+                source_qualname: Some(handler.to_string()),
+                target_qualname: Some(normalized.clone()),
+                detail: Some(detail),
+                evidence_snippet: snippet.clone(),
+                evidence_start_line: Some(start_line),
+                evidence_end_line: Some(end_line),
+                ..Default::default()
+            });
+        }
+    }
+    edges
+}
+
+pub(crate) fn slice_source(source: &str, start_byte: i64, end_byte: i64) -> &str {
+    if start_byte < 0 || end_byte <= start_byte {
+        return "";
+    }
+    source
+        .get(start_byte as usize..end_byte as usize)
+        .unwrap_or("")
+}
+
+pub(crate) fn collapse_whitespace(raw: &str) -> String {
+    raw.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Javadoc/KDoc block (`/** ... */`) immediately preceding `start_byte`, with
+/// the leading `*` decoration and block tags stripped.
+pub(crate) fn doc_comment_before(source: &str, start_byte: i64) -> Option<String> {
+    let prefix = source.get(..start_byte.max(0) as usize)?;
+    let close = prefix.rfind("*/")?;
+    if !only_annotations(&prefix[close + 2..]) {
+        return None;
+    }
+    let body_end = &prefix[..close];
+    // The comment closed here opens at the last `/*`; a plain block comment
+    // is not documentation, even when a Javadoc precedes it.
+    let open = body_end.rfind("/*")?;
+    let body = body_end[open..].strip_prefix("/**")?;
+    let mut lines = Vec::new();
+    for line in body.lines() {
+        let line = line.trim().trim_start_matches('*').trim();
+        if line.starts_with('@') {
+            break;
+        }
+        if !line.is_empty() {
+            lines.push(line.to_string());
+        }
+    }
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// Whether `text` holds nothing but whitespace and annotations
+/// (`@Override`, `@Deprecated(since = "2")`).
+fn only_annotations(text: &str) -> bool {
+    let mut rest = text.trim_start();
+    while let Some(annotation) = rest.strip_prefix('@') {
+        let name_end = annotation
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+            .unwrap_or(annotation.len());
+        if name_end == 0 {
+            return false;
+        }
+        rest = annotation[name_end..].trim_start();
+        if rest.starts_with('(') {
+            let mut depth = 0usize;
+            let Some(end) = rest.find(|c: char| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            }) else {
+                return false;
+            };
+            rest = rest[end + 1..].trim_start();
+        }
+    }
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grpc_service_from_generated_bases() {
+        assert_eq!(
+            grpc_service_from_base("GreeterGrpc.GreeterImplBase"),
+            Some("Greeter".to_string())
+        );
+        assert_eq!(
+            grpc_service_from_base("GreeterGrpcKt.GreeterCoroutineImplBase"),
+            Some("Greeter".to_string())
+        );
+        assert_eq!(grpc_service_from_base("OtherGrpc.GreeterImplBase"), None);
+        assert_eq!(grpc_service_from_base("BaseController"), None);
+    }
+
+    #[test]
+    fn doc_comment_strips_decoration_and_tags() {
+        let source =
+            "/**\n * Loads a user.\n * @param id the id\n */\npublic User load(long id) {}";
+        let start = source.find("public").unwrap() as i64;
+        assert_eq!(
+            doc_comment_before(source, start),
+            Some("Loads a user.".to_string())
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use crate::indexer::channel;
 use crate::indexer::config;
 use crate::indexer::extract::{EdgeInput, ExtractedFile, SymbolInput};
+use crate::indexer::graphql;
 use crate::indexer::http;
 use crate::indexer::proto;
+use crate::indexer::rules;
 use crate::indexer::tree_helpers::{
     module_symbol_fallback, module_symbol_with_span, node_text, span,
 };
@@ -49,6 +51,8 @@ struct Context {
     route_prefix: Option<String>,
     router_aliases: Vec<String>,
     grpc_clients: HashMap<String, GrpcService>,
+    /// Inside a NestJS `@Resolver()` class.
+    graphql_resolver: bool,
 }
 
 pub struct JavascriptExtractor {
@@ -299,8 +303,10 @@ fn extract_with_parser(
         route_prefix: None,
         router_aliases: Vec::new(),
         grpc_clients,
+        graphql_resolver: false,
     };
     walk_node(root, &ctx, source, &mut output);
+    output.tree = Some(tree);
     Ok(output)
 }
 
@@ -374,6 +380,11 @@ fn walk_node(node: Node<'_>, ctx: &Context, source: &str, output: &mut Extracted
     if node.kind() == "call_expression" || node.kind() == "new_expression" {
         handle_call(node, ctx, source, output);
     }
+    if node.kind() == "object" {
+        output
+            .edges
+            .extend(graphql_resolver_map_edges(node, ctx, source));
+    }
     // const { DB_URL } = process.env (destructuring)
     if node.kind() == "variable_declarator" {
         for edge in process_env_destructuring_edges(node, ctx, source) {
@@ -482,6 +493,9 @@ fn handle_class(node: Node<'_>, ctx: &Context, source: &str, output: &mut Extrac
     if let Some(prefix) = controller_prefix_from_class(node, source) {
         next_ctx.route_prefix = Some(prefix);
     }
+    next_ctx.graphql_resolver = class_decorator_nodes(node).into_iter().any(|decorator| {
+        decorator_name_and_args(decorator, source).is_some_and(|(name, _)| name == "Resolver")
+    });
     if let Some(body) = node.child_by_field_name("body") {
         walk_class_body(body, &next_ctx, source, output);
     }
@@ -639,6 +653,7 @@ fn handle_call(node: Node<'_>, ctx: &Context, source: &str, output: &mut Extract
     if let Some(edge) = channel_call_edge(node, ctx, source) {
         output.edges.push(edge);
     }
+    output.edges.extend(graphql_call_edges(node, ctx, source));
     let Some(target_node) = call_target_node(node) else {
         return;
     };
@@ -951,6 +966,199 @@ fn channel_call_edge(node: Node<'_>, ctx: &Context, source: &str) -> Option<Edge
     })
 }
 
+/// `gql`/`graphql` tagged templates (and `graphql(\`...\`)` calls): a
+/// GRAPHQL_CALL per root field the operation selects.
+fn graphql_call_edges(node: Node<'_>, ctx: &Context, source: &str) -> Vec<EdgeInput> {
+    let Some(target_node) = call_target_node(node) else {
+        return Vec::new();
+    };
+    let callee = node_text(target_node, source);
+    if !matches!(callee.as_str(), "gql" | "graphql") {
+        return Vec::new();
+    }
+    let Some(arguments) = node.child_by_field_name("arguments") else {
+        return Vec::new();
+    };
+    let template = if arguments.kind() == "template_string" {
+        arguments
+    } else {
+        match call_arguments(node).first() {
+            Some(arg) if arg.kind() == "template_string" => *arg,
+            _ => return Vec::new(),
+        }
+    };
+    let text = strip_template_substitutions(&node_text(template, source));
+    let scope = top_level_declarator_qualname(node, ctx, source)
+        .unwrap_or_else(|| ctx.current_scope.clone());
+    let start_line = span(template).0;
+    graphql::operation_calls(&text)
+        .into_iter()
+        .filter_map(|call| {
+            let key = graphql::field_key(&call.operation, &call.field)?;
+            let line = start_line + call.line - 1;
+            Some(EdgeInput {
+                kind: graphql::GRAPHQL_CALL_KIND.to_string(),
+                source_qualname: Some(scope.clone()),
+                target_qualname: Some(key),
+                detail: Some(graphql::build_call_detail(
+                    &call.operation,
+                    &call.field,
+                    call.operation_name.as_deref(),
+                    "gql",
+                )),
+                evidence_start_line: Some(line),
+                evidence_end_line: Some(line),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Template text without its backticks, `${...}` substitutions blanked so
+/// line numbers are kept.
+fn strip_template_substitutions(raw: &str) -> String {
+    let inner = raw.strip_prefix('`').unwrap_or(raw);
+    let inner = inner.strip_suffix('`').unwrap_or(inner);
+    let mut out = String::with_capacity(inner.len());
+    let mut depth = 0usize;
+    let mut chars = inner.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if depth == 0 && ch == '$' && chars.peek() == Some(&'{') {
+            chars.next();
+            depth = 1;
+            continue;
+        }
+        if depth > 0 {
+            match ch {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                '\n' => out.push('\n'),
+                _ => {}
+            }
+            continue;
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Qualname of the top-level `const X = ...` holding `node`, so module-level
+/// documents and resolver maps are attributed to their variable.
+fn top_level_declarator_qualname(node: Node<'_>, ctx: &Context, source: &str) -> Option<String> {
+    if ctx.fn_depth > 0 || !ctx.class_stack.is_empty() {
+        return None;
+    }
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        if parent.kind() == "variable_declarator" {
+            let name = node_text(parent.child_by_field_name("name")?, source);
+            return Some(build_qualname(&ctx.module, &ctx.class_stack, &name));
+        }
+        if is_nested_function_node(parent.kind()) {
+            return None;
+        }
+        current = parent.parent();
+    }
+    None
+}
+
+/// Apollo-style resolver maps: `{ Query: { user: ... }, User: { ... } }`.
+/// Only objects with a root type key are treated as resolver maps.
+fn graphql_resolver_map_edges(node: Node<'_>, ctx: &Context, source: &str) -> Vec<EdgeInput> {
+    let mut types = Vec::new();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() != "pair" {
+            continue;
+        }
+        let (Some(key), Some(value)) = (
+            child.child_by_field_name("key"),
+            child.child_by_field_name("value"),
+        ) else {
+            continue;
+        };
+        let Some(type_name) = grpc_property_name(key, source) else {
+            continue;
+        };
+        if value.kind() == "object" && type_name.starts_with(|ch: char| ch.is_ascii_uppercase()) {
+            types.push((type_name, value));
+        }
+    }
+    if !types
+        .iter()
+        .any(|(name, _)| graphql::ROOT_TYPES.contains(&name.as_str()))
+    {
+        return Vec::new();
+    }
+    let fallback = top_level_declarator_qualname(node, ctx, source)
+        .unwrap_or_else(|| ctx.current_scope.clone());
+    let mut edges = Vec::new();
+    for (type_name, fields) in types {
+        for (field, handler) in grpc_handlers_from_object(fields, ctx, source) {
+            let Some(key) = graphql::field_key(&type_name, &field) else {
+                continue;
+            };
+            let handler = if handler == ctx.current_scope {
+                fallback.clone()
+            } else {
+                handler
+            };
+            edges.push(EdgeInput {
+                kind: graphql::GRAPHQL_RESOLVE_KIND.to_string(),
+                source_qualname: Some(handler),
+                target_qualname: Some(key),
+                detail: Some(graphql::build_resolve_detail(&type_name, &field, "apollo")),
+                evidence_start_line: Some(span(fields).0),
+                evidence_end_line: Some(span(fields).2),
+                ..Default::default()
+            });
+        }
+    }
+    edges
+}
+
+/// NestJS `@Query()`, `@Mutation()` and `@Subscription()` resolver methods;
+/// the field is the decorator's `name` option or the method name.
+fn graphql_resolver_method_edges(
+    node: Node<'_>,
+    qualname: &str,
+    method_name: &str,
+    source: &str,
+) -> Vec<EdgeInput> {
+    let mut edges = Vec::new();
+    for decorator in method_decorator_nodes(node) {
+        let Some((name, args)) = decorator_name_and_args(decorator, source) else {
+            continue;
+        };
+        if !graphql::ROOT_TYPES.contains(&name.as_str()) {
+            continue;
+        }
+        let field = args
+            .iter()
+            .find_map(|arg| {
+                if arg.kind() == "object" {
+                    object_property_string(arg, "name", source)
+                } else {
+                    extract_string_literal(*arg, source)
+                }
+            })
+            .unwrap_or_else(|| method_name.to_string());
+        let Some(key) = graphql::field_key(&name, &field) else {
+            continue;
+        };
+        edges.push(EdgeInput {
+            kind: graphql::GRAPHQL_RESOLVE_KIND.to_string(),
+            source_qualname: Some(qualname.to_string()),
+            target_qualname: Some(key),
+            detail: Some(graphql::build_resolve_detail(&name, &field, "nestjs")),
+            evidence_start_line: Some(span(node).0),
+            evidence_end_line: Some(span(node).2),
+            ..Default::default()
+        });
+    }
+    edges
+}
+
 fn grpc_handlers_from_object(node: Node<'_>, ctx: &Context, source: &str) -> Vec<(String, String)> {
     let mut out = Vec::new();
     let mut cursor = node.walk();
@@ -1169,7 +1377,7 @@ fn method_route_edges(
     source: &str,
 ) -> Vec<EdgeInput> {
     let mut edges = Vec::new();
-    for decorator in decorator_nodes(node) {
+    for decorator in method_decorator_nodes(node) {
         let Some((name, args)) = decorator_name_and_args(decorator, source) else {
             continue;
         };
@@ -1203,7 +1411,7 @@ fn method_route_edges(
 }
 
 fn controller_prefix_from_class(node: Node<'_>, source: &str) -> Option<String> {
-    for decorator in decorator_nodes(node) {
+    for decorator in class_decorator_nodes(node) {
         let Some((name, args)) = decorator_name_and_args(decorator, source) else {
             continue;
         };
@@ -1218,6 +1426,32 @@ fn controller_prefix_from_class(node: Node<'_>, source: &str) -> Option<String>
     None
 }
 
+/// Decorators of a class, which sit on the `export` statement when the
+/// class is exported.
+fn class_decorator_nodes(node: Node<'_>) -> Vec<Node<'_>> {
+    let mut out = decorator_nodes(node);
+    if let Some(parent) = node.parent()
+        && parent.kind() == "export_statement"
+    {
+        out.extend(decorator_nodes(parent));
+    }
+    out
+}
+
+/// Decorators of a method. The TypeScript grammar places them in the class
+/// body just before the `method_definition` rather than inside it.
+fn method_decorator_nodes(node: Node<'_>) -> Vec<Node<'_>> {
+    let mut out = decorator_nodes(node);
+    let mut sibling = node.prev_named_sibling();
+    while let Some(prev) = sibling
+        && prev.kind() == "decorator"
+    {
+        out.push(prev);
+        sibling = prev.prev_named_sibling();
+    }
+    out
+}
+
 fn decorator_nodes(node: Node<'_>) -> Vec<Node<'_>> {
     let mut out = Vec::new();
     let mut cursor = node.walk();
@@ -1642,9 +1876,13 @@ fn axios_call_edge(node: Node<'_>, ctx: &Context, source: &str) -> Option<EdgeIn
         });
     }
     let (receiver, method_name) = member_receiver_and_method(target_node, source)?;
-    if receiver != "axios" {
+    let client = if receiver == "axios" {
+        "axios"
+    } else if rules::active_http_client(&receiver) {
+        "custom"
+    } else {
         return None;
-    }
+    };
     if !HTTP_METHOD_NAMES.contains(&method_name.as_str()) {
         return None;
     }
@@ -1653,7 +1891,7 @@ fn axios_call_edge(node: Node<'_>, ctx: &Context, source: &str) -> Option<EdgeIn
         .and_then(|arg| extract_string_literal(*arg, source))?;
     let normalized = http::normalize_path(&raw_path)?;
     let method = http::normalize_method(&method_name)?;
-    let detail = http::build_call_detail(&method, &normalized, &raw_path, "axios");
+    let detail = http::build_call_detail(&method, &normalized, &raw_path, client);
     Some(EdgeInput {
         kind: http::HTTP_CALL_KIND.to_string(),
         source_qualname: Some(ctx.current_scope.clone()),
@@ -2087,6 +2325,11 @@ fn handle_method(node: Node<'_>, ctx: &Context, source: &str, output: &mut Extra
     for edge in method_route_edges(node, &qualname, ctx, source) {
         output.edges.push(edge);
     }
+    if ctx.graphql_resolver {
+        output.edges.extend(graphql_resolver_method_edges(
+            node, &qualname, &name, source,
+        ));
+    }
     if let Some(body) = node.child_by_field_name("body") {
         let mut next_ctx = ctx.clone();
         next_ctx.fn_depth += 1;
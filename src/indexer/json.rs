@@ -0,0 +1,49 @@
+use crate::indexer::asyncapi;
+use crate::indexer::extract::{ExtractedFile, LanguageExtractor};
+use crate::indexer::openapi::{self, SpecSource};
+use crate::indexer::tree_helpers::module_symbol_fallback;
+use crate::indexer::yaml;
+use anyhow::Result;
+use serde_json::Value;
+
+/// API description documents in JSON. Only files named like one are
+/// scanned (see `scan::is_api_spec_json`); other JSON is not indexed.
+pub struct JsonExtractor;
+
+impl JsonExtractor {
+    pub fn new() -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+impl LanguageExtractor for JsonExtractor {
+    fn module_name_from_rel_path(&self, rel_path: &str) -> String {
+        module_name_from_rel_path(rel_path)
+    }
+
+    fn extract(&mut self, source: &str, module_name: &str) -> Result<ExtractedFile> {
+        let mut output = ExtractedFile::default();
+        output
+            .symbols
+            .push(module_symbol_fallback(module_name, source, "/", None));
+
+        let Ok(value) = serde_json::from_str::<Value>(source) else {
+            return Ok(output);
+        };
+        let source = SpecSource {
+            text: source,
+            line_offset: 1,
+            byte_offset: 0,
+        };
+        if openapi::is_api_spec(&value) {
+            openapi::extract_spec(&value, &source, module_name, &mut output);
+        } else if asyncapi::is_asyncapi(&value) {
+            asyncapi::extract_spec(&value, &source, module_name, &mut output);
+        }
+        Ok(output)
+    }
+}
+
+pub fn module_name_from_rel_path(rel_path: &str) -> String {
+    yaml::module_name_from_rel_path(rel_path)
+}
@@ -0,0 +1,651 @@
+use crate::indexer::extract::{EdgeInput, ExtractedFile};
+use crate::indexer::java::{
+    self, Annotation, AnnotationArg, CallSite, collapse_whitespace, doc_comment_before,
+    slice_source,
+};
+use crate::indexer::tree_helpers::{
+    module_symbol_fallback, module_symbol_with_span, node_text, span, symbol_for_node,
+};
+use anyhow::Result;
+use std::collections::HashMap;
+use tree_sitter::{Node, Parser};
+
+#[derive(Clone, Default)]
+struct TypeCtx {
+    type_stack: Vec<String>,
+    route_prefix: Option<String>,
+    grpc_service: Option<String>,
+}
+
+pub struct KotlinExtractor {
+    parser: Parser,
+}
+
+impl KotlinExtractor {
+    pub fn new() -> Result<Self> {
+        let mut parser = Parser::new();
+        let language = tree_sitter_kotlin_ng::LANGUAGE;
+        parser.set_language(&language.into())?;
+        Ok(Self { parser })
+    }
+}
+
+impl crate::indexer::extract::LanguageExtractor for KotlinExtractor {
+    fn module_name_from_rel_path(&self, rel_path: &str) -> String {
+        module_name_from_rel_path(rel_path)
+    }
+
+    fn extract(&mut self, source: &str, module_name: &str) -> Result<ExtractedFile> {
+        let mut output = ExtractedFile::default();
+        let tree = match self.parser.parse(source, None) {
+            Some(tree) => tree,
+            None => {
+                output
+                    .symbols
+                    .push(module_symbol_fallback(module_name, source, "/", None));
+                return Ok(output);
+            }
+        };
+        let root = tree.root_node();
+        output
+            .symbols
+            .push(module_symbol_with_span(module_name, span(root), "/", None));
+        let mut grpc_stubs = HashMap::new();
+        collect_grpc_stubs(root, source, &mut grpc_stubs);
+        let mut walker = KotlinWalker {
+            source,
+            module: module_name.to_string(),
+            package: None,
+            imports: HashMap::new(),
+            grpc_stubs,
+            output,
+        };
+        let mut cursor = root.walk();
+        for child in root.named_children(&mut cursor) {
+            match child.kind() {
+                "package_header" => {
+                    walker.package = child
+                        .named_children(&mut child.walk())
+                        .find(|node| matches!(node.kind(), "qualified_identifier" | "identifier"))
+                        .map(|node| node_text(node, source));
+                }
+                "import" => walker.handle_import(child),
+                _ => walker.walk_member(child, &TypeCtx::default()),
+            }
+        }
+        Ok(walker.output)
+    }
+}
+
+pub fn module_name_from_rel_path(rel_path: &str) -> String {
+    java::module_name_from_rel_path(rel_path)
+}
+
+struct KotlinWalker<'a> {
+    source: &'a str,
+    module: String,
+    package: Option<String>,
+    imports: HashMap<String, String>,
+    grpc_stubs: HashMap<String, String>,
+    output: ExtractedFile,
+}
+
+impl KotlinWalker<'_> {
+    /// `import a.b.C`, `import a.b.C as D` or `import a.b.*`.
+    fn handle_import(&mut self, node: Node<'_>) {
+        let mut cursor = node.walk();
+        let children: Vec<Node<'_>> = node.children(&mut cursor).collect();
+        let Some(name_node) = children
+            .iter()
+            .find(|child| matches!(child.kind(), "qualified_identifier" | "identifier"))
+        else {
+            return;
+        };
+        let target = node_text(*name_node, self.source);
+        let wildcard = children.iter().any(|child| child.kind() == "*");
+        let alias = children
+            .iter()
+            .skip_while(|child| child.kind() != "as")
+            .find(|child| child.kind() == "identifier")
+            .map(|child| node_text(*child, self.source))
+            .or_else(|| target.rsplit('.').next().map(|s| s.to_string()));
+        if !wildcard && let Some(alias) = alias {
+            self.imports.insert(alias, target.clone());
+        }
+        self.output
+            .edges
+            .push(java::import_edge(&self.module, target, node, self.source));
+    }
+
+    fn walk_member(&mut self, node: Node<'_>, ctx: &TypeCtx) {
+        match node.kind() {
+            "class_declaration" | "object_declaration" | "companion_object" => {
+                self.handle_type(node, ctx)
+            }
+            "function_declaration" => self.handle_function(node, ctx),
+            "property_declaration" => self.handle_property(node, ctx),
+            "secondary_constructor" => self.handle_secondary_constructor(node, ctx),
+            "anonymous_initializer" | "enum_entry" => {
+                let scope = self.container_qualname(ctx);
+                self.walk_calls(node, &scope, &scope);
+            }
+            // Recover members the grammar could not place, e.g. the body of a
+            // `fun interface`.
+            "ERROR" => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.walk_member(child, ctx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_type(&mut self, node: Node<'_>, ctx: &TypeCtx) {
+        let name = match node.child_by_field_name("name") {
+            Some(name_node) => node_text(name_node, self.source),
+            None if node.kind() == "companion_object" => "Companion".to_string(),
+            None => return,
+        };
+        let is_interface = has_token(node, "interface");
+        let kind = if is_interface {
+            "interface"
+        } else if has_modifier(node, "enum", self.source) {
+            "enum"
+        } else {
+            "class"
+        };
+        let qualname = self.qualname(ctx, &name);
+        let body = find_child(node, &["class_body", "enum_class_body"]);
+        let signature = declaration_header(node, body, self.source);
+        self.output.symbols.push(symbol_for_node(
+            kind,
+            &name,
+            &qualname,
+            node,
+            signature,
+            doc_comment_before(self.source, node.start_byte() as i64),
+        ));
+        self.push_contains(ctx, &qualname);
+
+        // A supertype with a constructor call is the superclass; the rest are
+        // interfaces (or, for an interface, the interfaces it extends).
+        let mut extends = Vec::new();
+        let mut implements = Vec::new();
+        if let Some(specifiers) = find_child(node, &["delegation_specifiers"]) {
+            let mut cursor = specifiers.walk();
+            for specifier in specifiers.named_children(&mut cursor) {
+                let Some(inner) = specifier.named_child(0) else {
+                    continue;
+                };
+                let is_constructor_call = inner.kind() == "constructor_invocation";
+                let Some(type_node) = (match inner.kind() {
+                    "constructor_invocation" | "explicit_delegation" => {
+                        find_child(inner, &["user_type"])
+                    }
+                    _ => Some(inner),
+                }) else {
+                    continue;
+                };
+                let base = type_name(type_node, self.source);
+                if is_interface || is_constructor_call {
+                    extends.push(base);
+                } else {
+                    implements.push(base);
+                }
+            }
+        }
+        for base in &extends {
+            self.push_type_edge("EXTENDS", &qualname, base);
+        }
+        for iface in &implements {
+            self.push_type_edge("IMPLEMENTS", &qualname, iface);
+        }
+
+        let annotations = annotations_of(node, self.source);
+        let mut next_ctx = ctx.clone();
+        next_ctx.type_stack.push(name);
+        next_ctx.route_prefix =
+            java::spring_route_prefix(&annotations).or_else(|| ctx.route_prefix.clone());
+        next_ctx.grpc_service = extends
+            .iter()
+            .find_map(|base| java::grpc_service_from_base(base));
+
+        if let Some(params) = find_child(node, &["primary_constructor"])
+            .and_then(|ctor| find_child(ctor, &["class_parameters"]))
+        {
+            self.handle_constructor_properties(params, &next_ctx);
+        }
+        if let Some(body) = body {
+            let mut cursor = body.walk();
+            for child in body.named_children(&mut cursor) {
+                self.walk_member(child, &next_ctx);
+            }
+        }
+    }
+
+    /// `val`/`var` parameters of a primary constructor are class properties.
+    fn handle_constructor_properties(&mut self, params: Node<'_>, ctx: &TypeCtx) {
+        let mut cursor = params.walk();
+        for param in params.named_children(&mut cursor) {
+            if param.kind() != "class_parameter"
+                || !(has_token(param, "val") || has_token(param, "var"))
+            {
+                continue;
+            }
+            let Some(name_node) = find_child(param, &["identifier"]) else {
+                continue;
+            };
+            let name = node_text(name_node, self.source);
+            let sig_end = typed_end(param, name_node);
+            let signature = collapse_whitespace(slice_source(
+                self.source,
+                name_node.start_byte() as i64,
+                sig_end as i64,
+            ));
+            let qualname = self.qualname(ctx, &name);
+            self.output.symbols.push(symbol_for_node(
+                "property",
+                &name,
+                &qualname,
+                param,
+                Some(signature),
+                None,
+            ));
+            self.push_contains(ctx, &qualname);
+        }
+    }
+
+    fn handle_function(&mut self, node: Node<'_>, ctx: &TypeCtx) {
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return;
+        };
+        let name = node_text(name_node, self.source);
+        let Some(params_node) = find_child(node, &["function_value_parameters"]) else {
+            return;
+        };
+        let params = collapse_whitespace(&node_text(params_node, self.source));
+        let return_type = return_type(node, params_node, self.source);
+        let signature = if return_type.is_empty() {
+            params
+        } else {
+            format!("{params} -> {return_type}")
+        };
+        let kind = if ctx.type_stack.is_empty() {
+            "function"
+        } else {
+            "method"
+        };
+        let qualname = self.qualname(ctx, &name);
+        self.output.symbols.push(symbol_for_node(
+            kind,
+            &name,
+            &qualname,
+            node,
+            Some(signature),
+            doc_comment_before(self.source, node.start_byte() as i64),
+        ));
+        self.push_contains(ctx, &qualname);
+        let annotations = annotations_of(node, self.source);
+        self.output.edges.extend(java::spring_route_edges(
+            ctx.route_prefix.as_deref(),
+            &annotations,
+            &qualname,
+            self.source,
+        ));
+        if let Some(edge) = java::grpc_impl_edge(
+            ctx.grpc_service.as_deref(),
+            &qualname,
+            &name,
+            node,
+            self.source,
+            "grpc-kotlin",
+        ) {
+            self.output.edges.push(edge);
+        }
+        if let Some(body) = find_child(node, &["function_body"]) {
+            let container = self.container_qualname(ctx);
+            self.walk_calls(body, &qualname, &container);
+        }
+    }
+
+    fn handle_secondary_constructor(&mut self, node: Node<'_>, ctx: &TypeCtx) {
+        let Some(class_name) = ctx.type_stack.last().cloned() else {
+            return;
+        };
+        let signature = find_child(node, &["function_value_parameters"])
+            .map(|params| collapse_whitespace(&node_text(params, self.source)));
+        let qualname = self.qualname(ctx, &class_name);
+        self.output.symbols.push(symbol_for_node(
+            "method",
+            &class_name,
+            &qualname,
+            node,
+            signature,
+            None,
+        ));
+        self.push_contains(ctx, &qualname);
+        if let Some(body) = find_child(node, &["block"]) {
+            let container = self.container_qualname(ctx);
+            self.walk_calls(body, &qualname, &container);
+        }
+    }
+
+    fn handle_property(&mut self, node: Node<'_>, ctx: &TypeCtx) {
+        let scope = self.container_qualname(ctx);
+        // Destructuring declarations bind no single property.
+        if let Some(declaration) = find_child(node, &["variable_declaration"])
+            && let Some(name_node) = find_child(declaration, &["identifier"])
+        {
+            let name = node_text(name_node, self.source);
+            let signature = collapse_whitespace(&node_text(declaration, self.source));
+            let kind = if ctx.type_stack.is_empty() {
+                "variable"
+            } else {
+                "property"
+            };
+            let qualname = self.qualname(ctx, &name);
+            self.output.symbols.push(symbol_for_node(
+                kind,
+                &name,
+                &qualname,
+                node,
+                Some(signature).filter(|value| !value.is_empty()),
+                doc_comment_before(self.source, node.start_byte() as i64),
+            ));
+            self.push_contains(ctx, &qualname);
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if !matches!(
+                child.kind(),
+                "modifiers" | "variable_declaration" | "multi_variable_declaration"
+            ) {
+                self.walk_calls(child, &scope, &scope);
+            }
+        }
+    }
+
+    /// CALLS edges for every call under `node`, attributed to `scope`; bare
+    /// names resolve against `container`. Lambdas and object expressions count
+    /// as part of the enclosing scope.
+    fn walk_calls(&mut self, node: Node<'_>, scope: &str, container: &str) {
+        match node.kind() {
+            "class_declaration" | "object_declaration" => return,
+            "call_expression" => {
+                if let Some(callee) = node.named_child(0) {
+                    let call = match callee.kind() {
+                        "identifier" => Some(CallSite::new(
+                            node_text(callee, self.source),
+                            Some(None),
+                            span(node),
+                        )),
+                        "navigation_expression" => {
+                            navigation_parts(callee).map(|(receiver, name)| {
+                                CallSite::new(
+                                    node_text(name, self.source),
+                                    receiver_chain(receiver, self.source).map(Some),
+                                    span(node),
+                                )
+                            })
+                        }
+                        _ => None,
+                    };
+                    if let Some(call) = call {
+                        self.push_call(call, scope, container);
+                    }
+                }
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.walk_calls(child, scope, container);
+        }
+    }
+
+    fn push_call(&mut self, call: CallSite, scope: &str, container: &str) {
+        if let Some(edge) = java::grpc_call_edge(&call, &self.grpc_stubs, scope, "grpc-kotlin") {
+            self.output.edges.push(edge);
+        }
+        let target = if call.simple {
+            java::resolve_call_target(&call.raw, container, &self.imports)
+        } else {
+            None
+        };
+        self.output
+            .edges
+            .push(java::call_edge(&call, scope, target, self.source));
+    }
+
+    fn push_contains(&mut self, ctx: &TypeCtx, qualname: &str) {
+        self.output.edges.push(EdgeInput {
+            kind: "CONTAINS".to_string(),
+            source_qualname: Some(self.container_qualname(ctx)),
+            target_qualname: Some(qualname.to_string()),
+            detail: None,
+            evidence_snippet: None,
+            ..Default::default()
+        });
+    }
+
+    fn push_type_edge(&mut self, kind: &str, source: &str, target: &str) {
+        self.output.edges.push(EdgeInput {
+            kind: kind.to_string(),
+            source_qualname: Some(source.to_string()),
+            target_qualname: Some(java::resolve_type_name(target, &self.imports)),
+            detail: None,
+            evidence_snippet: None,
+            ..Default::default()
+        });
+    }
+
+    fn qualname(&self, ctx: &TypeCtx, name: &str) -> String {
+        java::jvm_qualname(self.package.as_deref(), &self.module, &ctx.type_stack, name)
+    }
+
+    fn container_qualname(&self, ctx: &TypeCtx) -> String {
+        java::jvm_container_qualname(self.package.as_deref(), &self.module, &ctx.type_stack)
+    }
+}
+
+fn find_child<'a>(node: Node<'a>, kinds: &[&str]) -> Option<Node<'a>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|child| kinds.contains(&child.kind()))
+}
+
+/// Whether `node` has the keyword `token` as a direct child.
+fn has_token(node: Node<'_>, token: &str) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|child| !child.is_named() && child.kind() == token)
+}
+
+fn has_modifier(node: Node<'_>, modifier: &str, source: &str) -> bool {
+    let Some(modifiers) = find_child(node, &["modifiers"]) else {
+        return false;
+    };
+    let mut cursor = modifiers.walk();
+    modifiers
+        .named_children(&mut cursor)
+        .any(|child| node_text(child, source) == modifier)
+}
+
+/// Receiver and member name of `a.b`, `a?.b` or `a!!.b`.
+fn navigation_parts(node: Node<'_>) -> Option<(Node<'_>, Node<'_>)> {
+    let receiver = node.named_child(0)?;
+    let name = node.named_child(node.named_child_count().checked_sub(1)?)?;
+    (name.kind() == "identifier" && name.id() != receiver.id()).then_some((receiver, name))
+}
+
+/// Dotted name of a call receiver made only of identifiers, `this` and
+/// `super` (through `?.` and `!!`); `None` for any other expression.
+fn receiver_chain(node: Node<'_>, source: &str) -> Option<String> {
+    match node.kind() {
+        "identifier" => Some(node_text(node, source)),
+        "this_expression" => Some("this".to_string()),
+        "super_expression" => Some("super".to_string()),
+        "navigation_expression" => {
+            let (receiver, name) = navigation_parts(node)?;
+            let receiver = receiver_chain(receiver, source)?;
+            Some(format!("{receiver}.{}", node_text(name, source)))
+        }
+        "unary_expression" => receiver_chain(node.child_by_field_name("argument")?, source),
+        _ => None,
+    }
+}
+
+/// `a.b.Foo<Bar>?` → `a.b.Foo`.
+fn type_name(node: Node<'_>, source: &str) -> String {
+    match node.kind() {
+        "user_type" => {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .filter(|child| child.kind() == "identifier")
+                .map(|child| node_text(child, source))
+                .collect::<Vec<_>>()
+                .join(".")
+        }
+        "nullable_type" => node
+            .named_child(0)
+            .map(|inner| type_name(inner, source))
+            .unwrap_or_default(),
+        _ => node_text(node, source),
+    }
+}
+
+/// End byte of `name: Type` in a parameter: the type after the `:`, or the
+/// name itself when untyped.
+fn typed_end(node: Node<'_>, name_node: Node<'_>) -> usize {
+    let mut cursor = node.walk();
+    let children: Vec<Node<'_>> = node.children(&mut cursor).collect();
+    children
+        .iter()
+        .position(|child| child.kind() == ":")
+        .and_then(|colon| children.get(colon + 1))
+        .map(|type_node| type_node.end_byte())
+        .unwrap_or(name_node.end_byte())
+}
+
+/// Declared return type of a function: the type after the `:` following its
+/// parameters.
+fn return_type(node: Node<'_>, params: Node<'_>, source: &str) -> String {
+    let mut cursor = node.walk();
+    let children: Vec<Node<'_>> = node.children(&mut cursor).collect();
+    let Some(params_idx) = children.iter().position(|child| child.id() == params.id()) else {
+        return String::new();
+    };
+    match (children.get(params_idx + 1), children.get(params_idx + 2)) {
+        (Some(colon), Some(type_node)) if colon.kind() == ":" => {
+            collapse_whitespace(&node_text(*type_node, source))
+        }
+        _ => String::new(),
+    }
+}
+
+/// Declaration text from its keyword (after modifiers and annotations) up
+/// to the body.
+fn declaration_header(node: Node<'_>, body: Option<Node<'_>>, source: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    let start = node
+        .children(&mut cursor)
+        .find(|child| child.kind() != "modifiers")?
+        .start_byte();
+    let end = body
+        .map(|body| body.start_byte())
+        .unwrap_or(node.end_byte());
+    Some(collapse_whitespace(slice_source(
+        source,
+        start as i64,
+        end as i64,
+    )))
+    .filter(|value| !value.is_empty())
+}
+
+/// `@Name`, `@Name(args)` and use-site targeted `@field:Name(args)`.
+fn annotations_of(node: Node<'_>, source: &str) -> Vec<Annotation> {
+    let Some(modifiers) = find_child(node, &["modifiers"]) else {
+        return Vec::new();
+    };
+    let mut annotations = Vec::new();
+    let mut cursor = modifiers.walk();
+    for annotation in modifiers
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "annotation")
+    {
+        let Some(body) = find_child(annotation, &["user_type", "constructor_invocation"]) else {
+            continue;
+        };
+        let (type_node, arguments) = if body.kind() == "constructor_invocation" {
+            (
+                find_child(body, &["user_type"]),
+                find_child(body, &["value_arguments"]),
+            )
+        } else {
+            (Some(body), None)
+        };
+        let Some(type_node) = type_node else {
+            continue;
+        };
+        let mut args = Vec::new();
+        if let Some(arguments) = arguments {
+            let mut args_cursor = arguments.walk();
+            for argument in arguments.named_children(&mut args_cursor) {
+                let mut parsed = AnnotationArg::default();
+                let mut children_cursor = argument.walk();
+                let children: Vec<Node<'_>> = argument.children(&mut children_cursor).collect();
+                let value = match children.as_slice() {
+                    [key, eq, value, ..] if key.kind() == "identifier" && eq.kind() == "=" => {
+                        parsed.key = Some(node_text(*key, source));
+                        *value
+                    }
+                    _ => argument,
+                };
+                java::collect_literals(value, source, &mut parsed);
+                args.push(parsed);
+            }
+        }
+        annotations.push(Annotation {
+            name: type_name(type_node, source),
+            args,
+            span: span(annotation),
+        });
+    }
+    annotations
+}
+
+/// Record variables bound to generated gRPC stubs, e.g.
+/// `val stub = GreeterGrpcKt.GreeterCoroutineStub(channel)`.
+fn collect_grpc_stubs(node: Node<'_>, source: &str, stubs: &mut HashMap<String, String>) {
+    let binding = match node.kind() {
+        "property_declaration" => find_child(node, &["variable_declaration"])
+            .and_then(|declaration| find_child(declaration, &["identifier"]))
+            .zip(find_child(node, &["call_expression"])),
+        "assignment" => node
+            .child_by_field_name("left")
+            .map(|left| navigation_parts(left).map(|(_, name)| name).unwrap_or(left))
+            .zip(
+                node.child_by_field_name("right")
+                    .filter(|right| right.kind() == "call_expression"),
+            ),
+        _ => None,
+    };
+    if let Some((name, call)) = binding
+        && let Some((holder, factory)) = call
+            .named_child(0)
+            .filter(|callee| callee.kind() == "navigation_expression")
+            .and_then(navigation_parts)
+        && holder.kind() == "identifier"
+        && let Some(service) = java::grpc_service_from_stub_factory(
+            &node_text(holder, source),
+            &node_text(factory, source),
+        )
+    {
+        stubs.insert(node_text(name, source), service);
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_grpc_stubs(child, source, stubs);
+    }
+}
@@ -0,0 +1,795 @@
+use crate::indexer::extract::{EdgeInput, ExtractedFile, LanguageExtractor, SymbolInput};
+use crate::indexer::tree_helpers::module_symbol_fallback;
+use crate::util;
+use anyhow::Result;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+
+const LUA_KEYWORDS: &[&str] = &[
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "goto", "if", "in",
+    "local", "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+/// Roots searched (in order) when resolving `require("a.b")` to a file.
+/// Mirrors the common `package.path` layouts: repo root, Neovim-style `lua/`
+/// and rocks-style `src/`.
+const REQUIRE_ROOTS: &[&str] = &["", "lua", "src"];
+
+pub struct LuaExtractor;
+
+impl LuaExtractor {
+    pub fn new() -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+impl LanguageExtractor for LuaExtractor {
+    fn module_name_from_rel_path(&self, rel_path: &str) -> String {
+        module_name_from_rel_path(rel_path)
+    }
+
+    fn extract(&mut self, source: &str, module_name: &str) -> Result<ExtractedFile> {
+        let tokens = tokenize_lua(source);
+        let mut parser = LuaParser {
+            tokens: &tokens,
+            source,
+            module: module_name.to_string(),
+            output: ExtractedFile::default(),
+            calls: Vec::new(),
+            declared: HashMap::new(),
+        };
+        parser.output.symbols.push(module_symbol_fallback(
+            module_name,
+            source,
+            "/",
+            doc_comment_before(source, first_code_line(&tokens)),
+        ));
+        parser.parse();
+        parser.flush_calls();
+        Ok(parser.output)
+    }
+
+    fn resolve_imports(
+        &self,
+        repo_root: &Path,
+        file_rel_path: &str,
+        _module_name: &str,
+        edges: &mut Vec<EdgeInput>,
+    ) {
+        let mut resolved = Vec::new();
+        for edge in edges.iter() {
+            if edge.kind != "IMPORTS" {
+                continue;
+            }
+            let Some(target) = edge.target_qualname.as_deref() else {
+                continue;
+            };
+            let Some(dst_path) = resolve_require_path(repo_root, file_rel_path, target) else {
+                continue;
+            };
+            resolved.push(EdgeInput {
+                kind: "IMPORTS_FILE".to_string(),
+                source_qualname: edge.source_qualname.clone(),
+                target_qualname: Some(module_name_from_rel_path(&dst_path)),
+                detail: Some(
+                    json!({
+                        "src_path": file_rel_path,
+                        "dst_path": dst_path,
+                        "confidence": 1.0,
+                    })
+                    .to_string(),
+                ),
+                evidence_snippet: edge.evidence_snippet.clone(),
+                evidence_start_line: edge.evidence_start_line,
+                evidence_end_line: edge.evidence_end_line,
+                ..Default::default()
+            });
+        }
+        edges.extend(resolved);
+    }
+}
+
+/// `lua/plugin/util.lua` → `lua/plugin/util`; `lua/plugin/init.lua` →
+/// `lua/plugin` (the directory *is* the module, as with `require`).
+pub fn module_name_from_rel_path(rel_path: &str) -> String {
+    let path = Path::new(rel_path);
+    let mut parts: Vec<String> = path
+        .components()
+        .filter_map(|comp| comp.as_os_str().to_str().map(|s| s.to_string()))
+        .collect();
+    if parts.is_empty() {
+        return "init".to_string();
+    }
+    let file = parts.pop().unwrap_or_default();
+    let stem = Path::new(&file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&file)
+        .to_string();
+    if stem != "init" && !stem.is_empty() {
+        parts.push(stem);
+    }
+    if parts.is_empty() {
+        "init".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+fn resolve_require_path(repo_root: &Path, file_rel_path: &str, target: &str) -> Option<String> {
+    let target = target.trim();
+    if target.is_empty() || target.contains("..") {
+        return None;
+    }
+    let rel = target.replace('.', "/");
+    let file_dir = Path::new(file_rel_path)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    let mut bases: Vec<&Path> = REQUIRE_ROOTS.iter().map(Path::new).collect();
+    bases.push(file_dir);
+    for base in bases {
+        for candidate in [
+            base.join(format!("{rel}.lua")),
+            base.join(&rel).join("init.lua"),
+        ] {
+            if repo_root.join(&candidate).is_file() {
+                return Some(util::normalize_path(&candidate));
+            }
+        }
+    }
+    None
+}
+
+#[derive(Clone)]
+struct Token {
+    kind: TokenKind,
+    text: String,
+    start_line: i64,
+    start_col: i64,
+    end_line: i64,
+    end_col: i64,
+    start_byte: i64,
+    end_byte: i64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Ident,
+    Number,
+    Str,
+    Punct(char),
+}
+
+impl Token {
+    fn is_ident(&self, value: &str) -> bool {
+        self.kind == TokenKind::Ident && self.text == value
+    }
+
+    fn is_name(&self) -> bool {
+        self.kind == TokenKind::Ident && !LUA_KEYWORDS.contains(&self.text.as_str())
+    }
+
+    fn is_punct(&self, ch: char) -> bool {
+        self.kind == TokenKind::Punct(ch)
+    }
+}
+
+enum Block {
+    /// A function body; `symbol` indexes into `output.symbols` when the
+    /// function is named, `table` is the receiver table for `self` calls.
+    Function {
+        qualname: Option<String>,
+        symbol: Option<usize>,
+        table: Option<String>,
+    },
+    Other,
+}
+
+struct PendingCall {
+    scope: String,
+    table: Option<String>,
+    raw: String,
+    start: usize,
+    end: usize,
+}
+
+struct LuaParser<'a> {
+    tokens: &'a [Token],
+    source: &'a str,
+    module: String,
+    output: ExtractedFile,
+    calls: Vec<PendingCall>,
+    /// Module-level function chains (`M.helper`, `helper`) → qualname, used to
+    /// resolve calls regardless of declaration order.
+    declared: HashMap<String, String>,
+}
+
+impl LuaParser<'_> {
+    fn parse(&mut self) {
+        let tokens = self.tokens;
+        let mut blocks: Vec<Block> = Vec::new();
+        // Table constructors currently open; `Some(name)` when the constructor
+        // is assigned to a name (`local M = { ... }`).
+        let mut tables: Vec<Option<String>> = Vec::new();
+        let mut idx = 0;
+        while idx < tokens.len() {
+            let token = &tokens[idx];
+            if token.is_punct('{') {
+                tables.push(assigned_name_before(tokens, idx));
+                idx += 1;
+                continue;
+            }
+            if token.is_punct('}') {
+                tables.pop();
+                idx += 1;
+                continue;
+            }
+            if token.kind != TokenKind::Ident {
+                idx += 1;
+                continue;
+            }
+            match token.text.as_str() {
+                "function" => {
+                    let (block, next) = self.open_function(idx, &blocks, &tables);
+                    blocks.push(block);
+                    idx = next;
+                    continue;
+                }
+                "if" | "do" | "repeat" => blocks.push(Block::Other),
+                "end" | "until" => {
+                    if let Some(Block::Function {
+                        symbol: Some(symbol),
+                        ..
+                    }) = blocks.pop()
+                    {
+                        self.close_symbol(symbol, token);
+                    }
+                }
+                "require" => {
+                    if let Some(next) = self.push_require(idx, &blocks) {
+                        idx = next;
+                        continue;
+                    }
+                }
+                _ => {
+                    if token.is_name() && !preceded_by_accessor(tokens, idx) {
+                        let (chain, chain_end) = read_chain(tokens, idx);
+                        if is_call_start(tokens.get(chain_end)) {
+                            let (scope, table) = current_scope(&blocks, &self.module);
+                            self.calls.push(PendingCall {
+                                scope,
+                                table,
+                                raw: chain,
+                                start: idx,
+                                end: chain_end,
+                            });
+                        }
+                        idx = chain_end.max(idx + 1);
+                        continue;
+                    }
+                }
+            }
+            idx += 1;
+        }
+    }
+
+    /// Handle a `function` keyword at `idx`: named statements
+    /// (`function M.a:b()`, `local function f()`), assignments
+    /// (`M.f = function()`), table fields (`{ f = function() }`) and anonymous
+    /// callbacks. Returns the block to push and the index to resume at.
+    fn open_function(
+        &mut self,
+        idx: usize,
+        blocks: &[Block],
+        tables: &[Option<String>],
+    ) -> (Block, usize) {
+        let tokens = self.tokens;
+        let (enclosing, enclosing_table) = match current_scope(blocks, &self.module) {
+            (scope, table) if scope != self.module => (Some(scope), table),
+            _ => (None, None),
+        };
+
+        let mut name_chain = None;
+        let mut start_idx = idx;
+        let mut params_idx = idx + 1;
+        if tokens.get(idx + 1).is_some_and(|t| t.is_name()) {
+            let (chain, chain_end) = read_chain(tokens, idx + 1);
+            name_chain = Some(chain);
+            params_idx = chain_end;
+            if idx > 0 && tokens[idx - 1].is_ident("local") {
+                start_idx = idx - 1;
+            }
+        } else if idx >= 2 && tokens[idx - 1].is_punct('=') {
+            let chain_end = idx - 1;
+            let chain_start = chain_start_before(tokens, chain_end);
+            if chain_start < chain_end {
+                let (chain, _) = read_chain(tokens, chain_start);
+                let in_constructor = chain_start > 0
+                    && (tokens[chain_start - 1].is_punct('{')
+                        || tokens[chain_start - 1].is_punct(',')
+                        || tokens[chain_start - 1].is_punct(';'))
+                    && !chain.contains('.');
+                name_chain = if in_constructor {
+                    tables
+                        .last()
+                        .cloned()
+                        .flatten()
+                        .map(|table| format!("{table}.{chain}"))
+                } else {
+                    Some(chain)
+                };
+                start_idx = chain_start;
+                if chain_start > 0 && tokens[chain_start - 1].is_ident("local") {
+                    start_idx = chain_start - 1;
+                }
+            }
+        }
+        let is_local = tokens[start_idx].is_ident("local");
+
+        let Some(chain) = name_chain else {
+            // Anonymous function: calls inside it belong to the enclosing scope.
+            return (
+                Block::Function {
+                    qualname: enclosing,
+                    symbol: None,
+                    table: enclosing_table,
+                },
+                params_idx,
+            );
+        };
+
+        let dotted = chain.replace(':', ".");
+        let (table, name) = match dotted.rsplit_once('.') {
+            Some((table, name)) => (Some(table.to_string()), name.to_string()),
+            None => (None, dotted.clone()),
+        };
+        let parent = match enclosing {
+            Some(parent) if is_local => parent,
+            _ => self.module.clone(),
+        };
+        let qualname = format!("{parent}.{dotted}");
+        if parent == self.module {
+            self.declared.insert(dotted.clone(), qualname.clone());
+        }
+        let kind = if table.is_some() {
+            "method"
+        } else {
+            "function"
+        };
+        let start = &tokens[start_idx];
+        let signature = tokens
+            .get(params_idx)
+            .filter(|t| t.is_punct('('))
+            .and_then(|_| find_matching_paren(tokens, params_idx))
+            .map(|close| {
+                collapse_whitespace(&slice_source(
+                    self.source,
+                    tokens[params_idx].start_byte,
+                    tokens[close].end_byte,
+                ))
+            });
+        let symbol = SymbolInput {
+            kind: kind.to_string(),
+            name,
+            qualname: qualname.clone(),
+            start_line: start.start_line,
+            start_col: start.start_col,
+            end_line: start.end_line,
+            end_col: start.end_col,
+            start_byte: start.start_byte,
+            end_byte: start.end_byte,
+            signature,
+            docstring: doc_comment_before(self.source, start.start_line),
+        };
+        self.output.symbols.push(symbol);
+        self.output.edges.push(EdgeInput {
+            kind: "CONTAINS".to_string(),
+            source_qualname: Some(parent),
+            target_qualname: Some(qualname.clone()),
+            ..Default::default()
+        });
+        (
+            Block::Function {
+                qualname: Some(qualname),
+                symbol: Some(self.output.symbols.len() - 1),
+                table,
+            },
+            params_idx,
+        )
+    }
+
+    fn close_symbol(&mut self, symbol: usize, end: &Token) {
+        if let Some(symbol) = self.output.symbols.get_mut(symbol) {
+            symbol.end_line = end.end_line;
+            symbol.end_col = end.end_col;
+            symbol.end_byte = end.end_byte;
+        }
+    }
+
+    /// `require("a.b")`, `require "a.b"` and `require 'a.b'` → IMPORTS.
+    fn push_require(&mut self, idx: usize, blocks: &[Block]) -> Option<usize> {
+        let tokens = self.tokens;
+        if preceded_by_accessor(tokens, idx) {
+            return None;
+        }
+        let (arg_idx, end_idx) = match tokens.get(idx + 1) {
+            Some(t) if t.is_punct('(') => {
+                let close = find_matching_paren(tokens, idx + 1)?;
+                if close != idx + 3 {
+                    return None;
+                }
+                (idx + 2, close)
+            }
+            Some(t) if t.kind == TokenKind::Str => (idx + 1, idx + 1),
+            _ => return None,
+        };
+        let arg = tokens.get(arg_idx).filter(|t| t.kind == TokenKind::Str)?;
+        if arg.text.is_empty() {
+            return None;
+        }
+        let (scope, _) = current_scope(blocks, &self.module);
+        let start = &tokens[idx];
+        let end = &tokens[end_idx];
+        self.output.edges.push(EdgeInput {
+            kind: "IMPORTS".to_string(),
+            source_qualname: Some(scope),
+            target_qualname: Some(arg.text.clone()),
+            evidence_snippet: util::edge_evidence_snippet(
+                self.source,
+                start.start_byte,
+                end.end_byte,
+                start.start_line,
+                end.end_line,
+            ),
+            evidence_start_line: Some(start.start_line),
+            evidence_end_line: Some(end.end_line),
+            ..Default::default()
+        });
+        Some(end_idx + 1)
+    }
+
+    fn flush_calls(&mut self) {
+        for call in std::mem::take(&mut self.calls) {
+            let dotted = call.raw.replace(':', ".");
+            let target = self.resolve_call(&dotted, call.table.as_deref());
+            let detail = if target.is_some() {
+                None
+            } else {
+                Some(call.raw.clone())
+            };
+            let start = &self.tokens[call.start];
+            let end = &self.tokens[call.end.saturating_sub(1).max(call.start)];
+            self.output.edges.push(EdgeInput {
+                kind: "CALLS".to_string(),
+                source_qualname: Some(call.scope),
+                target_qualname: target,
+                detail,
+                evidence_snippet: util::edge_evidence_snippet(
+                    self.source,
+                    start.start_byte,
+                    end.end_byte,
+                    start.start_line,
+                    end.end_line,
+                ),
+                evidence_start_line: Some(start.start_line),
+                evidence_end_line: Some(end.end_line),
+                ..Default::default()
+            });
+        }
+    }
+
+    fn resolve_call(&self, dotted: &str, table: Option<&str>) -> Option<String> {
+        if let Some(qualname) = self.declared.get(dotted) {
+            return Some(qualname.clone());
+        }
+        if let Some(rest) = dotted.strip_prefix("self.")
+            && let Some(table) = table
+        {
+            let key = format!("{table}.{rest}");
+            return Some(
+                self.declared
+                    .get(&key)
+                    .cloned()
+                    .unwrap_or_else(|| format!("{}.{key}", self.module)),
+            );
+        }
+        if dotted.contains('.') {
+            return Some(dotted.to_string());
+        }
+        None
+    }
+}
+
+fn current_scope(blocks: &[Block], module: &str) -> (String, Option<String>) {
+    for block in blocks.iter().rev() {
+        if let Block::Function {
+            qualname: Some(qualname),
+            table,
+            ..
+        } = block
+        {
+            return (qualname.clone(), table.clone());
+        }
+    }
+    (module.to_string(), None)
+}
+
+fn preceded_by_accessor(tokens: &[Token], idx: usize) -> bool {
+    idx > 0
+        && (tokens[idx - 1].is_punct('.')
+            || tokens[idx - 1].is_punct(':')
+            || tokens[idx - 1].is_ident("function"))
+}
+
+/// Read `a.b.c:d` starting at `start`; returns the chain text and the index
+/// just past it.
+fn read_chain(tokens: &[Token], start: usize) -> (String, usize) {
+    let mut chain = tokens[start].text.clone();
+    let mut idx = start + 1;
+    while idx + 1 < tokens.len()
+        && (tokens[idx].is_punct('.') || tokens[idx].is_punct(':'))
+        && tokens[idx + 1].is_name()
+    {
+        chain.push_str(&tokens[idx].text);
+        chain.push_str(&tokens[idx + 1].text);
+        idx += 2;
+    }
+    (chain, idx)
+}
+
+/// Walk backwards from `end` (exclusive) over a `a.b.c` chain.
+fn chain_start_before(tokens: &[Token], end: usize) -> usize {
+    if end == 0 || !tokens[end - 1].is_name() {
+        return end;
+    }
+    let mut start = end - 1;
+    while start >= 2 && tokens[start - 1].is_punct('.') && tokens[start - 2].is_name() {
+        start -= 2;
+    }
+    start
+}
+
+fn assigned_name_before(tokens: &[Token], brace_idx: usize) -> Option<String> {
+    if brace_idx < 2 || !tokens[brace_idx - 1].is_punct('=') || tokens[brace_idx - 2].is_punct('=')
+    {
+        return None;
+    }
+    let chain_end = brace_idx - 1;
+    let chain_start = chain_start_before(tokens, chain_end);
+    if chain_start == chain_end {
+        return None;
+    }
+    Some(read_chain(tokens, chain_start).0)
+}
+
+fn is_call_start(token: Option<&Token>) -> bool {
+    token.is_some_and(|t| t.is_punct('(') || t.is_punct('{') || t.kind == TokenKind::Str)
+}
+
+fn find_matching_paren(tokens: &[Token], open_idx: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (idx, token) in tokens.iter().enumerate().skip(open_idx) {
+        if token.is_punct('(') {
+            depth += 1;
+        } else if token.is_punct(')') {
+            depth = depth.saturating_sub(1);
+            if depth == 0 {
+                return Some(idx);
+            }
+        }
+    }
+    None
+}
+
+fn first_code_line(tokens: &[Token]) -> i64 {
+    tokens.first().map(|t| t.start_line).unwrap_or(1)
+}
+
+fn slice_source(source: &str, start: i64, end: i64) -> String {
+    util::slice_bytes(source, start, end).unwrap_or_default()
+}
+
+fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Collect the `--`/`---` comment lines directly above `line` (1-based),
+/// dropping LuaDoc `@tag` lines.
+fn doc_comment_before(source: &str, line: i64) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut idx = (line - 1).max(0) as usize;
+    let mut collected = Vec::new();
+    while idx > 0 {
+        idx -= 1;
+        let trimmed = lines.get(idx).map(|l| l.trim()).unwrap_or("");
+        let Some(text) = trimmed.strip_prefix("--") else {
+            break;
+        };
+        if text.starts_with("[[") || text.starts_with("[=") {
+            break;
+        }
+        let text = text.trim_start_matches('-').trim();
+        if text.starts_with('@') {
+            continue;
+        }
+        collected.push(text.to_string());
+    }
+    collected.reverse();
+    while collected.first().is_some_and(|l| l.is_empty()) {
+        collected.remove(0);
+    }
+    while collected.last().is_some_and(|l| l.is_empty()) {
+        collected.pop();
+    }
+    if collected.is_empty() {
+        None
+    } else {
+        Some(collected.join("\n"))
+    }
+}
+
+fn tokenize_lua(source: &str) -> Vec<Token> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut idx = 0usize;
+    let mut line = 1i64;
+    let mut col = 1i64;
+
+    // Advance to byte `end`, tracking line/column.
+    fn advance(bytes: &[u8], idx: &mut usize, line: &mut i64, col: &mut i64, end: usize) {
+        while *idx < end.min(bytes.len()) {
+            if bytes[*idx] == b'\n' {
+                *line += 1;
+                *col = 1;
+            } else {
+                *col += 1;
+            }
+            *idx += 1;
+        }
+    }
+
+    while idx < bytes.len() {
+        let ch = bytes[idx];
+        if ch.is_ascii_whitespace() {
+            let end = idx + 1;
+            advance(bytes, &mut idx, &mut line, &mut col, end);
+            continue;
+        }
+        if ch == b'-' && bytes.get(idx + 1) == Some(&b'-') {
+            let body = idx + 2;
+            if let Some(level) = long_bracket_level(bytes, body) {
+                let end = long_bracket_end(bytes, body, level);
+                advance(bytes, &mut idx, &mut line, &mut col, end);
+            } else {
+                let end = bytes[idx..]
+                    .iter()
+                    .position(|b| *b == b'\n')
+                    .map(|p| idx + p)
+                    .unwrap_or(bytes.len());
+                advance(bytes, &mut idx, &mut line, &mut col, end);
+            }
+            continue;
+        }
+        let (start_byte, start_line, start_col) = (idx, line, col);
+        let (kind, text) = if ch == b'"' || ch == b'\'' {
+            let mut end = idx + 1;
+            while end < bytes.len() && bytes[end] != ch && bytes[end] != b'\n' {
+                if bytes[end] == b'\\' {
+                    end += 1;
+                }
+                end += 1;
+            }
+            let end = (end + 1).min(bytes.len());
+            let text = source
+                .get(start_byte + 1..end.saturating_sub(1).max(start_byte + 1))
+                .unwrap_or("")
+                .to_string();
+            advance(bytes, &mut idx, &mut line, &mut col, end);
+            (TokenKind::Str, text)
+        } else if ch == b'['
+            && let Some(level) = long_bracket_level(bytes, idx)
+        {
+            let end = long_bracket_end(bytes, idx, level);
+            let open_len = level + 2;
+            let close_len = if end >= idx + 2 * open_len && bytes[..end].ends_with(b"]") {
+                open_len
+            } else {
+                0
+            };
+            let text = source
+                .get(idx + open_len..end - close_len)
+                .unwrap_or("")
+                .trim_start_matches('\n')
+                .to_string();
+            advance(bytes, &mut idx, &mut line, &mut col, end);
+            (TokenKind::Str, text)
+        } else if ch.is_ascii_alphabetic() || ch == b'_' {
+            let mut end = idx + 1;
+            while end < bytes.len() && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_') {
+                end += 1;
+            }
+            let text = source[idx..end].to_string();
+            advance(bytes, &mut idx, &mut line, &mut col, end);
+            (TokenKind::Ident, text)
+        } else if ch.is_ascii_digit() {
+            let mut end = idx + 1;
+            while end < bytes.len() && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'.') {
+                end += 1;
+            }
+            let text = source[idx..end].to_string();
+            advance(bytes, &mut idx, &mut line, &mut col, end);
+            (TokenKind::Number, text)
+        } else if ch == b'.' && bytes.get(idx + 1) == Some(&b'.') {
+            // `..` concatenation and `...` varargs are never chain accessors.
+            let len = if bytes.get(idx + 2) == Some(&b'.') {
+                3
+            } else {
+                2
+            };
+            let end = idx + len;
+            advance(bytes, &mut idx, &mut line, &mut col, end);
+            (TokenKind::Punct('~'), "..".to_string())
+        } else if ch == b':' && bytes.get(idx + 1) == Some(&b':') {
+            // `::label::`
+            let end = idx + 2;
+            advance(bytes, &mut idx, &mut line, &mut col, end);
+            (TokenKind::Punct('~'), "::".to_string())
+        } else if ch == b'=' && bytes.get(idx + 1) == Some(&b'=') {
+            let end = idx + 2;
+            advance(bytes, &mut idx, &mut line, &mut col, end);
+            (TokenKind::Punct('~'), "==".to_string())
+        } else if ch.is_ascii() {
+            let end = idx + 1;
+            advance(bytes, &mut idx, &mut line, &mut col, end);
+            (TokenKind::Punct(ch as char), (ch as char).to_string())
+        } else {
+            let len = source[idx..]
+                .chars()
+                .next()
+                .map(char::len_utf8)
+                .unwrap_or(1);
+            let end = idx + len;
+            advance(bytes, &mut idx, &mut line, &mut col, end);
+            continue;
+        };
+        tokens.push(Token {
+            kind,
+            text,
+            start_line,
+            start_col,
+            end_line: line,
+            end_col: col,
+            start_byte: start_byte as i64,
+            end_byte: idx as i64,
+        });
+    }
+    tokens
+}
+
+/// If `bytes[idx..]` opens a long bracket (`[[`, `[==[`), return its level.
+fn long_bracket_level(bytes: &[u8], idx: usize) -> Option<usize> {
+    if bytes.get(idx) != Some(&b'[') {
+        return None;
+    }
+    let mut level = 0;
+    while bytes.get(idx + 1 + level) == Some(&b'=') {
+        level += 1;
+    }
+    (bytes.get(idx + 1 + level) == Some(&b'[')).then_some(level)
+}
+
+/// Byte index just past the long bracket opened at `idx`, or end of input.
+fn long_bracket_end(bytes: &[u8], idx: usize, level: usize) -> usize {
+    let mut pos = idx + level + 2;
+    while pos < bytes.len() {
+        if bytes[pos] == b']'
+            && bytes[pos + 1..].iter().take(level).all(|b| *b == b'=')
+            && bytes.get(pos + 1 + level) == Some(&b']')
+        {
+            return pos + level + 2;
+        }
+        pos += 1;
+    }
+    bytes.len()
+}
@@ -0,0 +1,749 @@
+use crate::indexer::extract::{EdgeInput, ExtractedFile, LanguageExtractor, SymbolInput};
+use crate::indexer::tree_helpers::{module_symbol_fallback, node_text};
+use crate::util;
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::{Node, Parser, Point, Range};
+
+/// A reference from a doc section to an indexed file (or a section within a
+/// Markdown file): relative links, fence titles and inline-code paths.
+pub const DOC_REF_KIND: &str = "DOC_REF";
+
+/// tree-sitter-md splits Markdown into a block grammar and an inline grammar;
+/// every `inline` node of the block tree is re-parsed with the second parser.
+pub struct MarkdownExtractor {
+    parser: Parser,
+    inline_parser: Parser,
+}
+
+impl MarkdownExtractor {
+    pub fn new() -> Result<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_md::LANGUAGE.into())?;
+        let mut inline_parser = Parser::new();
+        inline_parser.set_language(&tree_sitter_md::INLINE_LANGUAGE.into())?;
+        Ok(Self {
+            parser,
+            inline_parser,
+        })
+    }
+}
+
+impl LanguageExtractor for MarkdownExtractor {
+    fn module_name_from_rel_path(&self, rel_path: &str) -> String {
+        module_name_from_rel_path(rel_path)
+    }
+
+    fn extract(&mut self, source: &str, module_name: &str) -> Result<ExtractedFile> {
+        let mut output = ExtractedFile::default();
+        let tree = match self.parser.parse(source, None) {
+            Some(tree) => tree,
+            None => {
+                output
+                    .symbols
+                    .push(module_symbol_fallback(module_name, source, "/", None));
+                return Ok(output);
+            }
+        };
+        let mut walker = BlockWalker {
+            source,
+            inline_parser: &mut self.inline_parser,
+            headings: Vec::new(),
+            paragraphs: Vec::new(),
+            references: Vec::new(),
+        };
+        walker.walk(tree.root_node());
+        let BlockWalker {
+            headings,
+            paragraphs,
+            references,
+            ..
+        } = walker;
+        let lines = split_lines(source);
+
+        let title = headings
+            .iter()
+            .find(|h| h.level == 1)
+            .map(|h| h.text.clone());
+        output
+            .symbols
+            .push(module_symbol_fallback(module_name, source, "/", title));
+
+        let sections = build_sections(&lines, &headings, &paragraphs, module_name);
+        let slugs: HashMap<&str, &str> = sections
+            .iter()
+            .map(|s| (s.slug.as_str(), s.qualname.as_str()))
+            .collect();
+        for section in &sections {
+            output.symbols.push(section.symbol.clone());
+            output.edges.push(EdgeInput {
+                kind: "CONTAINS".to_string(),
+                source_qualname: Some(
+                    section
+                        .parent
+                        .clone()
+                        .unwrap_or_else(|| module_name.to_string()),
+                ),
+                target_qualname: Some(section.qualname.clone()),
+                ..Default::default()
+            });
+        }
+
+        for reference in references {
+            if !is_local_target(&reference.raw) {
+                continue;
+            }
+            let scope = sections
+                .iter()
+                .rev()
+                .find(|s| {
+                    s.symbol.start_line <= reference.line && reference.line <= s.symbol.end_line
+                })
+                .map(|s| s.qualname.clone())
+                .unwrap_or_else(|| module_name.to_string());
+            let snippet = lines
+                .get((reference.line - 1) as usize)
+                .map(|line| util::truncate_str_bytes(line.text.trim(), 200));
+            // Same-file anchors resolve immediately; everything else needs the
+            // repo root and is finished in `resolve_imports`.
+            let target = reference
+                .raw
+                .strip_prefix('#')
+                .and_then(|anchor| slugs.get(anchor.to_lowercase().as_str()))
+                .map(|qualname| qualname.to_string());
+            if target.is_none() && reference.raw.starts_with('#') {
+                continue;
+            }
+            output.edges.push(EdgeInput {
+                kind: DOC_REF_KIND.to_string(),
+                source_qualname: Some(scope),
+                target_qualname: target,
+                detail: Some(json!({"raw": reference.raw, "via": reference.via}).to_string()),
+                evidence_snippet: snippet,
+                evidence_start_line: Some(reference.line),
+                evidence_end_line: Some(reference.line),
+                ..Default::default()
+            });
+        }
+        Ok(output)
+    }
+
+    fn resolve_imports(
+        &self,
+        repo_root: &Path,
+        file_rel_path: &str,
+        _module_name: &str,
+        edges: &mut Vec<EdgeInput>,
+    ) {
+        edges.retain_mut(|edge| {
+            if edge.kind != DOC_REF_KIND || edge.target_qualname.is_some() {
+                return true;
+            }
+            let Some(detail) = edge
+                .detail
+                .as_deref()
+                .and_then(|d| serde_json::from_str::<Value>(d).ok())
+            else {
+                return false;
+            };
+            let raw = detail["raw"].as_str().unwrap_or_default();
+            let via = detail["via"].as_str().unwrap_or_default();
+            let Some((dst_path, target)) = resolve_reference(repo_root, file_rel_path, raw, via)
+            else {
+                return false;
+            };
+            edge.target_qualname = Some(target);
+            edge.detail = Some(
+                json!({
+                    "src_path": file_rel_path,
+                    "dst_path": dst_path,
+                    "raw": raw,
+                    "via": via,
+                    "confidence": 1.0,
+                })
+                .to_string(),
+            );
+            true
+        });
+    }
+}
+
+pub fn module_name_from_rel_path(rel_path: &str) -> String {
+    let path = Path::new(rel_path);
+    let mut parts: Vec<String> = path
+        .components()
+        .filter_map(|comp| comp.as_os_str().to_str().map(|s| s.to_string()))
+        .collect();
+    if parts.is_empty() {
+        return "README".to_string();
+    }
+    let file = parts.pop().unwrap_or_default();
+    let stem = Path::new(&file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&file)
+        .to_string();
+    if !stem.is_empty() {
+        parts.push(stem);
+    }
+    if parts.is_empty() {
+        "README".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+/// GitHub-style heading anchor: lowercase, spaces to `-`, punctuation other
+/// than `-`/`_` dropped.
+pub fn heading_slug(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|ch| {
+            if ch.is_alphanumeric() || ch == '-' || ch == '_' {
+                Some(ch)
+            } else if ch == ' ' {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Resolve a raw reference to `(dst_path, target_qualname)`. Only files the
+/// indexer understands produce a target; links into Markdown keep their
+/// `#anchor` so they land on the section symbol.
+fn resolve_reference(
+    repo_root: &Path,
+    file_rel_path: &str,
+    raw: &str,
+    via: &str,
+) -> Option<(String, String)> {
+    let (path_part, fragment) = match raw.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment)),
+        None => (raw, None),
+    };
+    let path_part = strip_line_suffix(&path_part.replace("%20", " "));
+    if path_part.is_empty() {
+        return None;
+    }
+    let file_dir = Path::new(file_rel_path)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    let mut candidates = Vec::new();
+    if let Some(rooted) = path_part.strip_prefix('/') {
+        candidates.push(rooted.to_string());
+    } else {
+        candidates.push(util::normalize_path(&file_dir.join(&path_part)));
+        // Inline code and fence titles are usually written repo-relative.
+        if via != "link" {
+            candidates.push(path_part.clone());
+        }
+    }
+    for candidate in candidates {
+        let Some(rel) = util::collapse_parent_dirs(&candidate) else {
+            continue;
+        };
+        if !repo_root.join(&rel).is_file() {
+            continue;
+        }
+        let module = crate::indexer::module_name_for_path(&rel)?;
+        let target = match fragment {
+            Some(anchor)
+                if !anchor.is_empty()
+                    && crate::indexer::scan::language_for_path(Path::new(&rel))
+                        == Some("markdown") =>
+            {
+                format!("{module}#{}", anchor.to_lowercase())
+            }
+            _ => module,
+        };
+        return Some((rel, target));
+    }
+    None
+}
+
+/// `src/main.rs:42` / `src/main.rs:42:7` → `src/main.rs`.
+fn strip_line_suffix(path: &str) -> String {
+    let mut path = path.trim();
+    while let Some((head, tail)) = path.rsplit_once(':') {
+        if tail.is_empty() || !tail.chars().all(|c| c.is_ascii_digit()) {
+            break;
+        }
+        path = head;
+    }
+    path.to_string()
+}
+
+struct Line<'a> {
+    text: &'a str,
+    start_byte: usize,
+    end_byte: usize,
+}
+
+fn split_lines(source: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for raw in source.split_inclusive('\n') {
+        let text = raw.trim_end_matches(['\n', '\r']);
+        lines.push(Line {
+            text,
+            start_byte: offset,
+            end_byte: offset + text.len(),
+        });
+        offset += raw.len();
+    }
+    lines
+}
+
+struct Heading {
+    level: usize,
+    text: String,
+    /// 0-based row of the first line of the heading.
+    line: usize,
+    /// 0-based row of the last line (differs for setext headings).
+    last_line: usize,
+}
+
+struct Paragraph {
+    /// 0-based row of the first line.
+    line: usize,
+    text: String,
+}
+
+struct Reference {
+    raw: String,
+    via: &'static str,
+    line: i64,
+}
+
+/// Walks the block tree collecting headings, paragraphs (for section
+/// docstrings) and references. Front matter and code block bodies are never
+/// read as prose.
+struct BlockWalker<'a> {
+    source: &'a str,
+    inline_parser: &'a mut Parser,
+    headings: Vec<Heading>,
+    paragraphs: Vec<Paragraph>,
+    references: Vec<Reference>,
+}
+
+impl BlockWalker<'_> {
+    fn walk(&mut self, node: Node<'_>) {
+        match node.kind() {
+            "minus_metadata" | "plus_metadata" | "indented_code_block" | "html_block" => {}
+            "atx_heading" => {
+                let level = node
+                    .child(0)
+                    .and_then(|marker| atx_level(marker.kind()))
+                    .unwrap_or(1);
+                if let Some(content) = node.child_by_field_name("heading_content") {
+                    let text = strip_closing_hashes(&self.inline_text(content));
+                    self.push_heading(level, text, node);
+                    self.inline_references(content);
+                }
+            }
+            "setext_heading" => {
+                let level = if has_child(node, "setext_h1_underline") {
+                    1
+                } else {
+                    2
+                };
+                if let Some(content) = node.child_by_field_name("heading_content") {
+                    let inline = find_child(content, "inline").unwrap_or(content);
+                    let text = self.inline_text(inline);
+                    self.push_heading(level, text, node);
+                    self.inline_references(inline);
+                }
+            }
+            "fenced_code_block" => {
+                if let Some(info) = find_child(node, "info_string")
+                    && let Some(path) = fence_path(&node_text(info, self.source))
+                {
+                    self.push_reference(path, "code_fence", node);
+                }
+            }
+            "link_reference_definition" => {
+                if let Some(dest) = find_child(node, "link_destination") {
+                    let raw = node_text(dest, self.source);
+                    self.push_reference(raw.trim_matches(['<', '>']).to_string(), "link", node);
+                }
+            }
+            "paragraph" => {
+                let text = node_text(node, self.source);
+                self.paragraphs.push(Paragraph {
+                    line: node.start_position().row,
+                    text: text
+                        .lines()
+                        .map(|line| line.trim().trim_start_matches('>').trim())
+                        .filter(|line| !line.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                });
+                for (offset, line) in text.lines().enumerate() {
+                    if let Some(raw) = reference_definition(line) {
+                        self.references.push(Reference {
+                            raw,
+                            via: "link",
+                            line: (node.start_position().row + offset) as i64 + 1,
+                        });
+                    }
+                }
+                self.walk_children(node);
+            }
+            "inline" | "pipe_table_cell" => self.inline_references(node),
+            _ => self.walk_children(node),
+        }
+    }
+
+    fn walk_children(&mut self, node: Node<'_>) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.walk(child);
+        }
+    }
+
+    fn push_heading(&mut self, level: usize, text: String, node: Node<'_>) {
+        if text.is_empty() {
+            return;
+        }
+        let last_line = node
+            .end_position()
+            .row
+            .saturating_sub(usize::from(node.end_position().column == 0))
+            .max(node.start_position().row);
+        self.headings.push(Heading {
+            level,
+            text,
+            line: node.start_position().row,
+            last_line,
+        });
+    }
+
+    fn push_reference(&mut self, raw: String, via: &'static str, node: Node<'_>) {
+        self.references.push(Reference {
+            raw,
+            via,
+            line: node.start_position().row as i64 + 1,
+        });
+    }
+
+    /// Parse the inline content of `node` with the inline grammar, skipping
+    /// block continuations (`> ` prefixes, list indentation) inside it.
+    fn parse_inline(&mut self, node: Node<'_>) -> Option<tree_sitter::Tree> {
+        let mut ranges = Vec::new();
+        let mut start = (node.start_byte(), node.start_position());
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if child.start_byte() > start.0 {
+                ranges.push(range(start, (child.start_byte(), child.start_position())));
+            }
+            start = (child.end_byte(), child.end_position());
+        }
+        if node.end_byte() > start.0 {
+            ranges.push(range(start, (node.end_byte(), node.end_position())));
+        }
+        if ranges.is_empty() {
+            return None;
+        }
+        self.inline_parser.set_included_ranges(&ranges).ok()?;
+        self.inline_parser.parse(self.source, None)
+    }
+
+    /// Heading text with inline markup dropped: `[text](url)` → `text`,
+    /// code and emphasis delimiters removed.
+    fn inline_text(&mut self, node: Node<'_>) -> String {
+        let Some(tree) = self.parse_inline(node) else {
+            return String::new();
+        };
+        let mut out = String::new();
+        plain_text(tree.root_node(), self.source, &mut out);
+        out.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Link and image destinations, then inline code spans that look like
+    /// repo paths.
+    fn inline_references(&mut self, node: Node<'_>) {
+        let Some(tree) = self.parse_inline(node) else {
+            return;
+        };
+        let mut links = Vec::new();
+        let mut code = Vec::new();
+        collect_inline(tree.root_node(), self.source, &mut links, &mut code);
+        for (raw, line) in links {
+            self.references.push(Reference {
+                raw,
+                via: "link",
+                line,
+            });
+        }
+        for (raw, line) in code {
+            if looks_like_path(&raw) {
+                self.references.push(Reference {
+                    raw,
+                    via: "inline_code",
+                    line,
+                });
+            }
+        }
+    }
+}
+
+fn range(start: (usize, Point), end: (usize, Point)) -> Range {
+    Range {
+        start_byte: start.0,
+        end_byte: end.0,
+        start_point: start.1,
+        end_point: end.1,
+    }
+}
+
+fn find_child<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .find(|child| child.kind() == kind)
+}
+
+fn has_child(node: Node<'_>, kind: &str) -> bool {
+    find_child(node, kind).is_some()
+}
+
+fn atx_level(marker: &str) -> Option<usize> {
+    marker
+        .strip_prefix("atx_h")?
+        .strip_suffix("_marker")?
+        .parse()
+        .ok()
+}
+
+/// `Title ##` → `Title`; a closing sequence must follow whitespace.
+fn strip_closing_hashes(text: &str) -> String {
+    let stripped = text.trim_end_matches('#');
+    if stripped.len() != text.len() && (stripped.is_empty() || stripped.ends_with(' ')) {
+        stripped.trim_end().to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+const LINK_KINDS: &[&str] = &[
+    "inline_link",
+    "image",
+    "full_reference_link",
+    "collapsed_reference_link",
+    "shortcut_link",
+];
+
+fn plain_text(node: Node<'_>, source: &str, out: &mut String) {
+    if LINK_KINDS.contains(&node.kind()) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if matches!(child.kind(), "link_text" | "image_description") {
+                plain_text(child, source, out);
+            }
+        }
+        return;
+    }
+    let mut pos = node.start_byte();
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        out.push_str(&source[pos..child.start_byte()]);
+        if !matches!(child.kind(), "code_span_delimiter" | "emphasis_delimiter") {
+            plain_text(child, source, out);
+        }
+        pos = child.end_byte();
+    }
+    if pos < node.end_byte() {
+        out.push_str(&source[pos..node.end_byte()]);
+    }
+}
+
+fn collect_inline(
+    node: Node<'_>,
+    source: &str,
+    links: &mut Vec<(String, i64)>,
+    code: &mut Vec<(String, i64)>,
+) {
+    let line = node.start_position().row as i64 + 1;
+    match node.kind() {
+        "inline_link" | "image" => {
+            if let Some(dest) = find_child(node, "link_destination") {
+                let raw = node_text(dest, source);
+                let raw = raw.trim_matches(['<', '>']);
+                if !raw.is_empty() {
+                    links.push((raw.to_string(), line));
+                }
+            }
+        }
+        "code_span" => {
+            let mut cursor = node.walk();
+            let delimiters: Vec<_> = node
+                .children(&mut cursor)
+                .filter(|c| c.kind() == "code_span_delimiter")
+                .collect();
+            if let [open, .., close] = delimiters.as_slice() {
+                let text = &source[open.end_byte()..close.start_byte()];
+                code.push((text.trim().to_string(), line));
+            }
+        }
+        _ => {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                collect_inline(child, source, links, code);
+            }
+        }
+    }
+}
+
+/// `[label]: target` — tree-sitter-md only recognizes some definitions as
+/// `link_reference_definition`; the rest come through as paragraph text.
+fn reference_definition(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    let rest = trimmed.strip_prefix('[')?;
+    let (label, target) = rest.split_once("]:")?;
+    if label.is_empty() || label.contains('[') {
+        return None;
+    }
+    let target = target.split_whitespace().next()?;
+    Some(target.trim_matches(['<', '>']).to_string())
+}
+
+struct Section {
+    slug: String,
+    qualname: String,
+    parent: Option<String>,
+    symbol: SymbolInput,
+}
+
+fn build_sections(
+    lines: &[Line<'_>],
+    headings: &[Heading],
+    paragraphs: &[Paragraph],
+    module_name: &str,
+) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    for (pos, heading) in headings.iter().enumerate() {
+        let base = heading_slug(&heading.text);
+        let count = seen.entry(base.clone()).or_insert(0);
+        let slug = if *count == 0 {
+            base.clone()
+        } else {
+            format!("{base}-{count}")
+        };
+        *count += 1;
+        let qualname = format!("{module_name}#{slug}");
+
+        // The section runs until the next heading at the same or a higher level.
+        let next_line = headings[pos + 1..]
+            .iter()
+            .find(|h| h.level <= heading.level)
+            .map(|h| h.line)
+            .unwrap_or(lines.len());
+        let mut last = next_line.saturating_sub(1).max(heading.last_line);
+        while last > heading.last_line && lines[last].text.trim().is_empty() {
+            last -= 1;
+        }
+
+        while stack
+            .last()
+            .is_some_and(|(level, _)| *level >= heading.level)
+        {
+            stack.pop();
+        }
+        let parent = stack.last().map(|(_, q)| q.clone());
+        stack.push((heading.level, qualname.clone()));
+
+        // The docstring is the first paragraph before any nested heading.
+        let body_end = headings.get(pos + 1).map(|h| h.line).unwrap_or(lines.len());
+        let docstring = paragraphs
+            .iter()
+            .find(|p| p.line > heading.last_line && p.line < body_end && !p.text.is_empty())
+            .map(|p| p.text.clone());
+
+        let start = &lines[heading.line];
+        let end = &lines[last];
+        sections.push(Section {
+            slug,
+            qualname: qualname.clone(),
+            parent,
+            symbol: SymbolInput {
+                kind: "section".to_string(),
+                name: heading.text.clone(),
+                qualname,
+                start_line: heading.line as i64 + 1,
+                start_col: 1,
+                end_line: last as i64 + 1,
+                end_col: end.text.len() as i64 + 1,
+                start_byte: start.start_byte as i64,
+                end_byte: end.end_byte as i64,
+                signature: Some(format!("{} {}", "#".repeat(heading.level), heading.text)),
+                docstring,
+            },
+        });
+    }
+    sections
+}
+
+fn is_local_target(raw: &str) -> bool {
+    let lower = raw.to_ascii_lowercase();
+    !raw.is_empty()
+        && !lower.contains("://")
+        && !lower.starts_with("mailto:")
+        && !lower.starts_with("tel:")
+        && !lower.starts_with("data:")
+        && !raw.contains("{{")
+}
+
+/// A repo path written as code: no whitespace, a directory separator and a
+/// file extension on the last segment (`src/indexer/mod.rs`).
+fn looks_like_path(text: &str) -> bool {
+    let text = strip_line_suffix(text);
+    !text.is_empty()
+        && !text.contains(char::is_whitespace)
+        && text.contains('/')
+        && text
+            .rsplit('/')
+            .next()
+            .and_then(|name| name.rsplit_once('.'))
+            .is_some_and(|(stem, ext)| {
+                !stem.is_empty() && !ext.is_empty() && ext.chars().all(|c| c.is_alphanumeric())
+            })
+}
+
+/// File path named by a fence info string: ```` ```rust title="src/lib.rs" ````,
+/// ```` ```toml file=Cargo.toml ````, ```` ```rust:src/lib.rs ```` or a bare
+/// path after the language.
+fn fence_path(info: &str) -> Option<String> {
+    let mut words = info.split_whitespace();
+    let first = words.next()?;
+    if let Some((_, path)) = first.split_once(':')
+        && looks_like_file(path)
+    {
+        return Some(path.to_string());
+    }
+    for word in words {
+        if let Some((key, value)) = word.split_once('=') {
+            if matches!(key, "title" | "file" | "filename" | "path" | "src") {
+                let value = value.trim_matches(['"', '\'']);
+                if looks_like_file(value) {
+                    return Some(value.to_string());
+                }
+            }
+        } else if looks_like_file(word) {
+            return Some(word.to_string());
+        }
+    }
+    None
+}
+
+fn looks_like_file(text: &str) -> bool {
+    looks_like_path(text)
+        || text
+            .rsplit_once('.')
+            .is_some_and(|(stem, ext)| !stem.is_empty() && ext.chars().all(|c| c.is_alphanumeric()))
+}
@@ -1,31 +1,53 @@
 use crate::db::Db;
 use crate::indexer::extract::ExtractedFile;
 use crate::metrics;
-use crate::model::{ChangedFilesResult, IndexStats};
+use crate::model::{ChangedFilesResult, IndexStats, RevisionStats};
 use anyhow::{Result, anyhow};
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
+pub mod asyncapi;
 pub mod batch;
 pub mod bicep;
 pub mod channel;
+pub mod compose;
 pub mod config;
 pub mod csharp;
 pub mod differ;
+pub mod dockerfile;
+pub mod embedded_sql;
 pub mod extract;
 pub mod go;
+pub mod graphql;
+pub mod helm;
 pub mod http;
+pub mod java;
 pub mod javascript;
+pub mod json;
+pub mod kotlin;
+pub mod lua;
+pub mod markdown;
+pub mod openapi;
+pub mod openapi_links;
+pub mod overrides;
+pub mod pipeline;
 pub mod postgres;
 pub mod proto;
+pub mod proto_links;
 pub mod python;
+pub mod query_rules;
+pub mod revision;
+pub mod rules;
 pub mod rust;
 pub mod scan;
 pub mod sql_extractor;
 pub mod stable_id;
+pub mod terraform;
 pub mod test_detection;
 pub mod tree_helpers;
+pub mod type_resolve;
 pub mod xref;
 pub mod yaml;
 
@@ -39,13 +61,36 @@ pub struct SyncStats {
     pub edges: usize,
 }
 
+/// Progress of a full reindex, reported to the callback installed with
+/// `Indexer::set_progress`. `done` counts up to `total` across both passes
+/// (per-file extraction, then edge linking).
+#[derive(Clone, Copy, Debug)]
+pub struct IndexProgress {
+    pub phase: &'static str,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Every `IndexProgress::phase`, in reporting order.
+pub const PROGRESS_PHASES: &[&str] = &["extract", "link", "done"];
+
+pub type ProgressCallback = Box<dyn FnMut(IndexProgress)>;
+
+type Extractors = HashMap<String, Box<dyn extract::LanguageExtractor>>;
+
+/// Upper bound on progress callbacks per reindex pass.
+const PROGRESS_STEPS: usize = 50;
+
 pub struct Indexer {
     repo_root: PathBuf,
     db: Db,
     scan_options: scan::ScanOptions,
     graph_version: i64,
     commit_sha: Option<String>,
-    extractors: HashMap<String, Box<dyn extract::LanguageExtractor>>,
+    extractors: Extractors,
+    rules: Arc<rules::ProjectRules>,
+    progress: Option<ProgressCallback>,
+    pipeline: pipeline::PipelineConfig,
 }
 
 impl Indexer {
@@ -62,30 +107,9 @@ impl Indexer {
         let db = Db::new(&db_path)?;
         let graph_version = db.current_graph_version()?;
         let commit_sha = db.graph_version_commit(graph_version)?;
+        let rules = Arc::new(rules::ProjectRules::load(&repo_root)?);
 
-        let mut extractors: HashMap<String, Box<dyn extract::LanguageExtractor>> = HashMap::new();
-        extractors.insert("python".into(), Box::new(python::PythonExtractor::new()?));
-        extractors.insert("rust".into(), Box::new(rust::RustExtractor::new()?));
-        extractors.insert(
-            "javascript".into(),
-            Box::new(javascript::JavascriptExtractor::new()?),
-        );
-        extractors.insert(
-            "typescript".into(),
-            Box::new(javascript::TypescriptExtractor::new()?),
-        );
-        extractors.insert("tsx".into(), Box::new(javascript::TsxExtractor::new()?));
-        extractors.insert("csharp".into(), Box::new(csharp::CSharpExtractor::new()?));
-        extractors.insert("go".into(), Box::new(go::GoExtractor::new()?));
-        extractors.insert("sql".into(), Box::new(sql_extractor::SqlExtractor::new()?));
-        extractors.insert(
-            "postgres".into(),
-            Box::new(sql_extractor::SqlExtractor::new()?),
-        );
-        extractors.insert("tsql".into(), Box::new(sql_extractor::SqlExtractor::new()?));
-        extractors.insert("proto".into(), Box::new(proto::ProtoExtractor::new()?));
-        extractors.insert("yaml".into(), Box::new(yaml::YamlExtractor::new()?));
-        extractors.insert("bicep".into(), Box::new(bicep::BicepExtractor::new()?));
+        let extractors = build_extractors(&repo_root)?;
 
         Ok(Self {
             repo_root,
@@ -94,6 +118,9 @@ impl Indexer {
             graph_version,
             commit_sha,
             extractors,
+            rules,
+            progress: None,
+            pipeline: pipeline::PipelineConfig::default(),
         })
     }
 
@@ -109,6 +136,19 @@ impl Indexer {
         &self.repo_root
     }
 
+    /// Rules loaded from the repo's `.lidx.toml`.
+    pub fn rules(&self) -> &rules::ProjectRules {
+        &self.rules
+    }
+
+    /// Re-read `.lidx.toml`, keeping the current rules if it no longer parses.
+    fn reload_rules(&mut self) {
+        match rules::ProjectRules::load(&self.repo_root) {
+            Ok(rules) => self.rules = Arc::new(rules),
+            Err(err) => eprintln!("lidx: keeping previous rules: {err:#}"),
+        }
+    }
+
     pub fn graph_version(&self) -> i64 {
         self.graph_version
     }
@@ -176,6 +216,7 @@ impl Indexer {
         let mut stats = SyncStats::default();
         let mut touched = false;
         let mut indexed_files = Vec::new();
+        let mut synced_paths = Vec::new();
         for path in paths {
             let rel_path = match crate::util::normalize_rel_path(&self.repo_root, path) {
                 Ok(value) => value,
@@ -189,6 +230,7 @@ impl Indexer {
                 }
                 stats.deleted += 1;
                 touched = true;
+                synced_paths.push(rel_path);
                 continue;
             }
             let Some(scanned) = scan::scan_path(&self.repo_root, path)? else {
@@ -200,6 +242,7 @@ impl Indexer {
                     }
                     stats.deleted += 1;
                     touched = true;
+                    synced_paths.push(rel_path);
                 }
                 continue;
             };
@@ -215,6 +258,7 @@ impl Indexer {
                     stats.symbols += symbols;
                     stats.edges += edges;
                     touched = true;
+                    synced_paths.push(scanned.rel_path.clone());
                     indexed_files.push(scanned.clone());
                 }
                 Err(err) => {
@@ -228,6 +272,7 @@ impl Indexer {
                 &mut self.db,
                 &indexed_files,
                 false,
+                &self.rules,
                 self.graph_version,
             )?;
             stats.edges += xref_edges;
@@ -247,6 +292,11 @@ impl Indexer {
             if resolved > 0 {
                 eprintln!("lidx: resolved {resolved} edge(s) after incremental sync");
             }
+            self.db.resolve_typed_calls(self.graph_version)?;
+            self.db.resolve_table_refs(self.graph_version)?;
+            overrides::relink_member_overrides(&mut self.db, self.graph_version, &synced_paths)?;
+            proto_links::link_generated_code(&mut self.db, self.graph_version)?;
+            openapi_links::link_generated_clients(&mut self.db, self.graph_version)?;
 
             let now = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -257,8 +307,29 @@ impl Indexer {
         Ok(stats)
     }
 
+    /// Install (or clear) the callback `reindex` reports progress to.
+    pub fn set_progress(&mut self, progress: Option<ProgressCallback>) {
+        self.progress = progress;
+    }
+
+    /// Override the thread count and memory budget of `reindex` extraction.
+    pub fn set_pipeline_config(&mut self, config: pipeline::PipelineConfig) {
+        self.pipeline = config;
+    }
+
+    fn report_progress(&mut self, phase: &'static str, done: usize, total: usize) {
+        let Some(progress) = self.progress.as_mut() else {
+            return;
+        };
+        let step = (total / (2 * PROGRESS_STEPS)).max(1);
+        if done.is_multiple_of(step) || done == total {
+            progress(IndexProgress { phase, done, total });
+        }
+    }
+
     pub fn reindex(&mut self) -> Result<IndexStats> {
         let started = Instant::now();
+        self.reload_rules();
         let previous_graph_version = self.graph_version;
         let commit_sha = crate::util::git_head_sha(&self.repo_root);
         self.graph_version = self.db.create_graph_version(commit_sha.as_deref())?;
@@ -287,65 +358,67 @@ impl Indexer {
         let mut file_data: Vec<(scan::ScannedFile, ExtractedFile, differ::SymbolDiff, i64)> =
             Vec::new();
 
+        let progress_total = scanned.len() * 2;
         for file in &scanned {
             seen.insert(file.rel_path.clone());
-
-            // Extract symbols
-            let source = match crate::util::read_to_string(&file.abs_path) {
-                Ok(s) => s,
-                Err(err) => {
-                    eprintln!("read error {}: {err}", file.rel_path);
-                    continue;
-                }
-            };
-
-            let mut extracted = match self.extract_file(file, &source) {
-                Ok(e) => e,
-                Err(err) => {
-                    eprintln!("extract error {}: {err}", file.rel_path);
-                    continue;
-                }
-            };
-
-            let file_metrics = metrics::compute_file_metrics(&source, &file.language);
-            let symbol_metrics =
-                metrics::compute_symbol_metrics(&source, &file.language, &extracted.symbols);
-            extracted.file_metrics = Some(file_metrics);
-            extracted.symbol_metrics = symbol_metrics;
-
-            // Compute diff
-            let existing_symbols = self
-                .db
-                .get_symbols_for_file(&file.rel_path, self.graph_version)?;
-            let diff = differ::compute_symbol_diff(existing_symbols, extracted.symbols.clone());
-
-            // Upsert file to get file_id
-            let file_id = self.db.upsert_file(
-                &file.rel_path,
-                &file.hash,
-                &file.language,
-                file.size,
-                file.modified,
-            )?;
-
-            // Add to batch
-            batch_writer.add(batch::FileDiff {
-                file_id,
-                file_path: file.rel_path.clone(),
-                diff: diff.clone(),
-                graph_version: self.graph_version,
-                commit_sha: self.commit_sha.clone(),
-            });
-
-            // Store for edge processing
-            file_data.push((file.clone(), extracted, diff, file_id));
-
-            // Flush if batch is ready
-            if batch_writer.should_flush() {
-                let batch = batch_writer.take();
-                self.db.update_files_symbols_batch(&batch)?;
-            }
         }
+        // Extraction runs on worker threads; this closure is the single writer
+        // and sees the files in scan order.
+        let repo_root = self.repo_root.clone();
+        let rules = Arc::clone(&self.rules);
+        let config = self.pipeline.clone();
+        pipeline::extract_files(
+            &repo_root,
+            &rules,
+            &scanned,
+            &config,
+            |idx, file, result| {
+                self.report_progress("extract", idx, progress_total);
+                let (source, extracted) = match result {
+                    Ok(result) => result,
+                    Err(err) => {
+                        eprintln!("{err}");
+                        return Ok(());
+                    }
+                };
+
+                // Compute diff
+                let existing_symbols = self
+                    .db
+                    .get_symbols_for_file(&file.rel_path, self.graph_version)?;
+                let diff = differ::compute_symbol_diff(existing_symbols, extracted.symbols.clone());
+
+                // Upsert file to get file_id
+                let file_id = self.db.upsert_file(
+                    &file.rel_path,
+                    &file.hash,
+                    &file.language,
+                    file.size,
+                    file.modified,
+                )?;
+                self.db
+                    .replace_file_content(file_id, &source, self.graph_version)?;
+
+                // Add to batch
+                batch_writer.add(batch::FileDiff {
+                    file_id,
+                    file_path: file.rel_path.clone(),
+                    diff: diff.clone(),
+                    graph_version: self.graph_version,
+                    commit_sha: self.commit_sha.clone(),
+                });
+
+                // Store for edge processing
+                file_data.push((file.clone(), extracted, diff, file_id));
+
+                // Flush if batch is ready
+                if batch_writer.should_flush() {
+                    let batch = batch_writer.take();
+                    self.db.update_files_symbols_batch(&batch)?;
+                }
+                Ok(())
+            },
+        )?;
 
         // Flush remaining batch
         if !batch_writer.is_empty() {
@@ -354,7 +427,9 @@ impl Indexer {
         }
 
         // Now process edges for all files
-        for (file, extracted, diff, file_id) in file_data {
+        let scanned_count = scanned.len();
+        for (idx, (file, extracted, diff, file_id)) in file_data.into_iter().enumerate() {
+            self.report_progress("link", scanned_count + idx, progress_total);
             // Delete existing edges
             self.db.delete_edges_for_file(file_id, self.graph_version)?;
 
@@ -395,9 +470,20 @@ impl Indexer {
             }
         }
 
-        let xref_edges =
-            xref::link_cross_language_refs(&mut self.db, &scanned, true, self.graph_version)?;
+        let xref_edges = xref::link_cross_language_refs(
+            &mut self.db,
+            &scanned,
+            true,
+            &self.rules,
+            self.graph_version,
+        )?;
         stats.edges += xref_edges;
+        self.db.resolve_typed_calls(self.graph_version)?;
+        self.db.resolve_table_refs(self.graph_version)?;
+        stats.edges += overrides::link_member_overrides(&mut self.db, self.graph_version)?;
+        stats.edges += proto_links::link_generated_code(&mut self.db, self.graph_version)?;
+        stats.edges += openapi_links::link_generated_clients(&mut self.db, self.graph_version)?;
+        self.report_progress("done", progress_total, progress_total);
 
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -408,11 +494,214 @@ impl Indexer {
         Ok(stats)
     }
 
+    /// Index git revision `rev` from the object database into its own graph
+    /// version, leaving the working tree and the current version alone.
+    /// Files whose blob is unchanged from an indexed revision, or from the
+    /// working tree, are copied from that version instead of re-extracted.
+    /// Import resolution that probes the filesystem still sees the working
+    /// tree.
+    pub fn index_revision(&mut self, rev: &str) -> Result<RevisionStats> {
+        let started = Instant::now();
+        let commit = revision::resolve_commit(&self.repo_root, rev)?;
+        let mut stats = RevisionStats {
+            revision: rev.to_string(),
+            commit_sha: commit.clone(),
+            graph_version: 0,
+            already_indexed: false,
+            scanned: 0,
+            indexed: 0,
+            reused: 0,
+            skipped: 0,
+            symbols: 0,
+            edges: 0,
+            duration_ms: 0,
+        };
+        if let Some(graph_version) = self.db.revision_version_for_commit(&commit)? {
+            stats.graph_version = graph_version;
+            stats.already_indexed = true;
+            stats.duration_ms = started.elapsed().as_millis() as u64;
+            return Ok(stats);
+        }
+
+        let rules = match revision::read_path(&self.repo_root, &commit, rules::RULES_FILE)? {
+            Some(text) => rules::ProjectRules::parse(&text).unwrap_or_else(|err| {
+                eprintln!("lidx: ignoring {} at {rev}: {err:#}", rules::RULES_FILE);
+                rules::ProjectRules::default()
+            }),
+            None => rules::ProjectRules::default(),
+        };
+        let rules = Arc::new(rules);
+        let mut reader = revision::BlobReader::new(&self.repo_root)?;
+        let scanned =
+            revision::scan_revision(&self.repo_root, &commit, self.scan_options, &mut reader)?;
+        stats.scanned = scanned.len();
+        let reader = Mutex::new(reader);
+        let graph_version = self.db.create_revision_version(&commit, rev)?;
+        stats.graph_version = graph_version;
+        if let Err(err) =
+            self.fill_revision_version(graph_version, commit, &rules, &scanned, &reader, &mut stats)
+        {
+            // A half-written version would be reported as already indexed
+            // on the next attempt.
+            if let Err(cleanup) = self.db.delete_revision_version(graph_version) {
+                eprintln!("lidx: failed to drop partial revision {rev}: {cleanup:#}");
+            }
+            return Err(err);
+        }
+        stats.duration_ms = started.elapsed().as_millis() as u64;
+        Ok(stats)
+    }
+
+    /// Symbols, content and edges of revision version `graph_version`.
+    fn fill_revision_version(
+        &mut self,
+        graph_version: i64,
+        commit: String,
+        rules: &Arc<rules::ProjectRules>,
+        scanned: &[scan::ScannedFile],
+        reader: &Mutex<revision::BlobReader>,
+        stats: &mut RevisionStats,
+    ) -> Result<()> {
+        let commit_sha = Some(commit);
+
+        // Copy files with a known blob; queue the rest for extraction.
+        let mut file_ids = HashMap::new();
+        let mut reused = Vec::new();
+        let mut pending = Vec::new();
+        for file in scanned {
+            let worktree_hash = self.db.worktree_file_hash(&file.rel_path)?;
+            let file_id = self.db.ensure_revision_file(
+                &file.rel_path,
+                &file.language,
+                file.size,
+                graph_version,
+            )?;
+            self.db
+                .record_revision_file(graph_version, file_id, &file.hash)?;
+            file_ids.insert(file.rel_path.clone(), file_id);
+            let mut source_version =
+                self.db
+                    .revision_version_with_blob(file_id, &file.hash, graph_version)?;
+            if source_version.is_none()
+                && let Some(hash) = worktree_hash
+                && scan::hash_bytes(&reader.lock().unwrap().read(&file.hash)?) == hash
+            {
+                source_version = Some(self.graph_version);
+            }
+            match source_version {
+                Some(from) => {
+                    stats.symbols += self.db.copy_file_symbols(
+                        file_id,
+                        from,
+                        graph_version,
+                        commit_sha.as_deref(),
+                    )?;
+                    stats.reused += 1;
+                    reused.push((file_id, from));
+                }
+                None => pending.push(file.clone()),
+            }
+        }
+
+        let mut batch_writer = batch::BatchWriter::with_defaults();
+        let mut file_data: Vec<(i64, ExtractedFile)> = Vec::new();
+        let read = |file: &scan::ScannedFile| reader.lock().unwrap().read_to_string(file);
+        let repo_root = self.repo_root.clone();
+        let config = self.pipeline.clone();
+        pipeline::extract_files_with(
+            &repo_root,
+            rules,
+            &pending,
+            &config,
+            &read,
+            |_, file, result| {
+                let (source, extracted) = match result {
+                    Ok(result) => result,
+                    Err(err) => {
+                        eprintln!("{err}");
+                        stats.skipped += 1;
+                        return Ok(());
+                    }
+                };
+                let file_id = file_ids[&file.rel_path];
+                self.db
+                    .replace_file_content(file_id, &source, graph_version)?;
+                let diff = differ::compute_symbol_diff(Vec::new(), extracted.symbols.clone());
+                stats.symbols += diff.added.len();
+                batch_writer.add(batch::FileDiff {
+                    file_id,
+                    file_path: file.rel_path.clone(),
+                    diff,
+                    graph_version,
+                    commit_sha: commit_sha.clone(),
+                });
+                file_data.push((file_id, extracted));
+                if batch_writer.should_flush() {
+                    let batch = batch_writer.take();
+                    self.db.update_files_symbols_batch(&batch)?;
+                }
+                Ok(())
+            },
+        )?;
+        if !batch_writer.is_empty() {
+            let batch = batch_writer.take();
+            self.db.update_files_symbols_batch(&batch)?;
+        }
+
+        // Edges once every file has its symbols. File metrics are not
+        // versioned, so only the working tree writes them.
+        for (file_id, extracted) in file_data {
+            let symbol_map = self.db.symbol_map_for_file(file_id, graph_version)?;
+            self.db.insert_edges(
+                file_id,
+                &extracted.edges,
+                &symbol_map,
+                graph_version,
+                commit_sha.as_deref(),
+            )?;
+            self.db
+                .insert_symbol_metrics(file_id, &extracted.symbol_metrics, &symbol_map)?;
+            stats.indexed += 1;
+        }
+        for (file_id, from) in reused {
+            self.db
+                .copy_file_edges(file_id, from, graph_version, commit_sha.as_deref())?;
+        }
+        self.db.resolve_null_target_edges(graph_version)?;
+
+        xref::link_cross_language_refs_with(
+            &mut self.db,
+            scanned,
+            true,
+            rules,
+            graph_version,
+            read,
+        )?;
+        self.db.resolve_typed_calls(graph_version)?;
+        self.db.resolve_table_refs(graph_version)?;
+        overrides::link_member_overrides(&mut self.db, graph_version)?;
+        proto_links::link_generated_code(&mut self.db, graph_version)?;
+        openapi_links::link_generated_clients(&mut self.db, graph_version)?;
+        // The link passes recount edges copied from other versions, so
+        // count what was stored instead of summing their results.
+        stats.edges = self.db.count_version_edges(graph_version)?;
+        Ok(())
+    }
+
+    /// Graph version of an indexed revision: the one for the commit `rev`
+    /// resolves to, or, when git no longer knows `rev`, the latest one
+    /// indexed under that name or a matching sha prefix.
+    pub fn revision_graph_version(&self, rev: &str) -> Result<Option<i64>> {
+        match revision::resolve_commit(&self.repo_root, rev) {
+            Ok(commit) => self.db.revision_version_for_commit(&commit),
+            Err(_) => self.db.revision_version_by_name(rev),
+        }
+    }
+
     fn index_scanned_file(&mut self, file: &scan::ScannedFile) -> Result<(usize, usize)> {
         // Phase 6: Check file size before reading (skip very large files)
-        const MAX_FILE_SIZE_MB: u64 = 10;
         let metadata = std::fs::metadata(&file.abs_path)?;
-        if metadata.len() > MAX_FILE_SIZE_MB * 1024 * 1024 {
+        if metadata.len() > scan::MAX_FILE_SIZE_MB * 1024 * 1024 {
             eprintln!(
                 "lidx: Skipping large file ({}MB): {}",
                 metadata.len() / (1024 * 1024),
@@ -452,34 +741,25 @@ impl Indexer {
         }
 
         // Phase 3: Use incremental updates for symbols
-        let (symbol_count, edge_count) = self.index_file(file, extracted, diff)?;
+        let (symbol_count, edge_count) = self.index_file(file, &source, extracted, diff)?;
 
         Ok((symbol_count, edge_count))
     }
 
     fn extract_file(&mut self, file: &scan::ScannedFile, source: &str) -> Result<ExtractedFile> {
-        let extractor = self
-            .extractors
-            .get_mut(file.language.as_str())
-            .ok_or_else(|| anyhow!("skip {}: unknown language {}", file.rel_path, file.language))?;
-        let module_name = extractor.module_name_from_rel_path(&file.rel_path);
-        let mut extracted = extractor
-            .extract(source, &module_name)
-            .map_err(|err| anyhow!("extract error {} ({module_name}): {err}", file.rel_path))?;
-        // Re-borrow immutably for resolve_imports (extract's &mut borrow is released)
-        let extractor = self.extractors.get(file.language.as_str()).unwrap();
-        extractor.resolve_imports(
+        extract_source(
+            &mut self.extractors,
             &self.repo_root,
-            &file.rel_path,
-            &module_name,
-            &mut extracted.edges,
-        );
-        Ok(extracted)
+            &self.rules,
+            file,
+            source,
+        )
     }
 
     fn index_file(
         &mut self,
         file: &scan::ScannedFile,
+        source: &str,
         extracted: ExtractedFile,
         diff: differ::SymbolDiff,
     ) -> Result<(usize, usize)> {
@@ -490,6 +770,8 @@ impl Indexer {
             file.size,
             file.modified,
         )?;
+        self.db
+            .replace_file_content(file_id, source, self.graph_version)?;
 
         // Phase 3: Use incremental symbol updates instead of delete-all-insert
         let symbols = self.db.update_file_symbols(
@@ -523,3 +805,119 @@ impl Indexer {
         Ok((symbols.len(), edges_count))
     }
 }
+
+/// One extractor per language. Extractors hold tree-sitter parsers, so each
+/// extraction thread builds its own set.
+pub(crate) fn build_extractors(repo_root: &Path) -> Result<Extractors> {
+    let mut extractors: Extractors = HashMap::new();
+    extractors.insert("python".into(), Box::new(python::PythonExtractor::new()?));
+    extractors.insert("rust".into(), Box::new(rust::RustExtractor::new()?));
+    extractors.insert(
+        "javascript".into(),
+        Box::new(javascript::JavascriptExtractor::new()?),
+    );
+    extractors.insert(
+        "typescript".into(),
+        Box::new(javascript::TypescriptExtractor::new()?),
+    );
+    extractors.insert("tsx".into(), Box::new(javascript::TsxExtractor::new()?));
+    extractors.insert("csharp".into(), Box::new(csharp::CSharpExtractor::new()?));
+    extractors.insert("go".into(), Box::new(go::GoExtractor::new()?));
+    extractors.insert("java".into(), Box::new(java::JavaExtractor::new()?));
+    extractors.insert("kotlin".into(), Box::new(kotlin::KotlinExtractor::new()?));
+    extractors.insert("lua".into(), Box::new(lua::LuaExtractor::new()?));
+    extractors.insert(
+        "markdown".into(),
+        Box::new(markdown::MarkdownExtractor::new()?),
+    );
+    extractors.insert("sql".into(), Box::new(sql_extractor::SqlExtractor::new()?));
+    extractors.insert(
+        "postgres".into(),
+        Box::new(sql_extractor::SqlExtractor::new()?),
+    );
+    extractors.insert("tsql".into(), Box::new(sql_extractor::SqlExtractor::new()?));
+    extractors.insert("proto".into(), Box::new(proto::ProtoExtractor::new()?));
+    extractors.insert(
+        "yaml".into(),
+        Box::new(yaml::YamlExtractor::with_repo_root(
+            repo_root.to_path_buf(),
+        )?),
+    );
+    extractors.insert("json".into(), Box::new(json::JsonExtractor::new()?));
+    extractors.insert("bicep".into(), Box::new(bicep::BicepExtractor::new()?));
+    extractors.insert(
+        "terraform".into(),
+        Box::new(terraform::TerraformExtractor::new()?),
+    );
+    extractors.insert(
+        "graphql".into(),
+        Box::new(graphql::GraphqlExtractor::new()?),
+    );
+    extractors.insert(
+        "dockerfile".into(),
+        Box::new(dockerfile::DockerfileExtractor::new()?),
+    );
+    Ok(extractors)
+}
+
+/// Run the file's extractor and the language-independent edge passes.
+pub(crate) fn extract_source(
+    extractors: &mut Extractors,
+    repo_root: &Path,
+    rules: &Arc<rules::ProjectRules>,
+    file: &scan::ScannedFile,
+    source: &str,
+) -> Result<ExtractedFile> {
+    let extractor = extractors
+        .get_mut(file.language.as_str())
+        .ok_or_else(|| anyhow!("skip {}: unknown language {}", file.rel_path, file.language))?;
+    let module_name = extractor.module_name_from_rel_path(&file.rel_path);
+    let _rules = rules::scoped(rules, &file.language);
+    let mut extracted = extractor
+        .extract(source, &module_name)
+        .map_err(|err| anyhow!("extract error {} ({module_name}): {err}", file.rel_path))?;
+    extractor.resolve_imports(
+        repo_root,
+        &file.rel_path,
+        &module_name,
+        &mut extracted.edges,
+    );
+    if let Some(tree) = extracted.tree.take() {
+        type_resolve::annotate_edges(&file.language, source, &tree, &mut extracted.edges);
+    }
+    let helper_edges = rules.helper_edges(&file.language, source, &extracted.symbols);
+    extracted.edges.extend(helper_edges);
+    let query_edges = rules.query_edges(&file.language, source, &extracted.symbols);
+    extracted.edges.extend(query_edges);
+    let table_edges = embedded_sql::table_edges(&file.language, source, &extracted.symbols);
+    extracted.edges.extend(table_edges);
+    Ok(extracted)
+}
+
+/// Module qualname the owning extractor would assign to `rel_path`, or `None`
+/// when the path is not an indexed language. Used by extractors that link to
+/// files in other languages (e.g. Markdown doc references).
+pub fn module_name_for_path(rel_path: &str) -> Option<String> {
+    let language = scan::language_for_path(Path::new(rel_path))?;
+    let name = match language {
+        "python" => python::module_name_from_rel_path(rel_path),
+        "rust" => rust::module_name_from_rel_path(rel_path),
+        "javascript" | "typescript" | "tsx" => javascript::module_name_from_rel_path(rel_path),
+        "csharp" => csharp::module_name_from_rel_path(rel_path),
+        "go" => go::module_name_from_rel_path(rel_path),
+        "java" => java::module_name_from_rel_path(rel_path),
+        "kotlin" => kotlin::module_name_from_rel_path(rel_path),
+        "lua" => lua::module_name_from_rel_path(rel_path),
+        "markdown" => markdown::module_name_from_rel_path(rel_path),
+        "sql" | "postgres" | "tsql" => sql_extractor::module_name_from_rel_path(rel_path),
+        "proto" => proto::module_name_from_rel_path(rel_path),
+        "yaml" => yaml::module_name_from_rel_path(rel_path),
+        "json" => json::module_name_from_rel_path(rel_path),
+        "bicep" => bicep::module_name_from_rel_path(rel_path),
+        "terraform" => terraform::module_name_from_rel_path(rel_path),
+        "graphql" => graphql::module_name_from_rel_path(rel_path),
+        "dockerfile" => dockerfile::module_name_from_rel_path(rel_path),
+        _ => return None,
+    };
+    Some(name)
+}
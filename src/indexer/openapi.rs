@@ -0,0 +1,581 @@
+//! OpenAPI 3 and Swagger 2 documents.
+//!
+//! Each operation becomes a `route` symbol (named by its operationId) with an
+//! HTTP_ROUTE edge keyed like the framework routes, so HTTP calls bridge to
+//! the spec even when the server framework is not recognised. Schemas under
+//! `components/schemas` (or `definitions`) and their properties are symbols
+//! too, with TYPE_REF edges for every `$ref` to them.
+
+use crate::indexer::extract::{EdgeInput, ExtractedFile, SymbolInput};
+use crate::indexer::http;
+use crate::indexer::proto::TYPE_REF_KIND;
+use serde_json::{Value, json};
+
+pub const OPENAPI_FRAMEWORK: &str = "openapi";
+
+const OPERATION_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Whether `value` is an OpenAPI 3 (`openapi: 3.x`) or Swagger 2
+/// (`swagger: "2.0"`) document.
+pub fn is_api_spec(value: &Value) -> bool {
+    let version = |key: &str| value.get(key).and_then(version_text);
+    version("openapi").is_some_and(|v| v.starts_with('3'))
+        || version("swagger").is_some_and(|v| v.starts_with('2'))
+}
+
+pub(crate) fn version_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// Where a document sits in its file, for symbol and edge positions.
+pub struct SpecSource<'a> {
+    pub text: &'a str,
+    pub line_offset: i64,
+    pub byte_offset: usize,
+}
+
+/// Lines of a document, located by key: YAML `key:` and JSON `"key":`
+/// both match when the key starts its line.
+pub(crate) struct SpecLines<'a> {
+    lines: Vec<(usize, &'a str)>,
+    line_offset: i64,
+    byte_offset: usize,
+}
+
+impl<'a> SpecLines<'a> {
+    pub(crate) fn new(source: &SpecSource<'a>) -> Self {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for line in source.text.split_inclusive('\n') {
+            lines.push((start, line.trim_end_matches(['\r', '\n'])));
+            start += line.len();
+        }
+        Self {
+            lines,
+            line_offset: source.line_offset,
+            byte_offset: source.byte_offset,
+        }
+    }
+
+    fn indent(&self, idx: usize) -> usize {
+        let line = self.lines[idx].1;
+        line.len() - line.trim_start().len()
+    }
+
+    /// First line in `within` (a `block`) whose key is `key`.
+    fn find_key(&self, within: Option<(usize, usize)>, key: &str) -> Option<usize> {
+        let (from, to) = within.unwrap_or((0, self.lines.len()));
+        (from..to.min(self.lines.len())).find(|&idx| {
+            let line = self.lines[idx].1.trim_start();
+            let line = line.strip_prefix("- ").unwrap_or(line);
+            [
+                line.strip_prefix(key),
+                line.strip_prefix('"')
+                    .and_then(|rest| rest.strip_prefix(key))
+                    .and_then(|rest| rest.strip_prefix('"')),
+                line.strip_prefix('\'')
+                    .and_then(|rest| rest.strip_prefix(key))
+                    .and_then(|rest| rest.strip_prefix('\'')),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|rest| rest.trim_start().starts_with(':'))
+        })
+    }
+
+    /// Lines nested under `idx`: up to the next line indented no deeper
+    /// than it.
+    fn block(&self, idx: usize) -> (usize, usize) {
+        let indent = self.indent(idx);
+        let end = (idx + 1..self.lines.len())
+            .find(|&next| !self.lines[next].1.trim().is_empty() && self.indent(next) <= indent)
+            .unwrap_or(self.lines.len());
+        (idx + 1, end)
+    }
+
+    /// Line of the key reached through `keys`, each looked up inside the
+    /// previous one's block.
+    pub(crate) fn find_path(&self, keys: &[&str]) -> Option<usize> {
+        let mut within = None;
+        let mut found = None;
+        for key in keys {
+            let idx = self.find_key(within, key)?;
+            within = Some(self.block(idx));
+            found = Some(idx);
+        }
+        found
+    }
+
+    /// `(start_line, end_line, start_byte, end_byte)` of the block at `idx`,
+    /// or of the first line when the key was not found.
+    pub(crate) fn span(&self, idx: Option<usize>) -> (i64, i64, i64, i64) {
+        let idx = idx.unwrap_or(0).min(self.lines.len().saturating_sub(1));
+        let Some((start, _)) = self.lines.get(idx) else {
+            return (self.line_offset, self.line_offset, 0, 0);
+        };
+        let (_, end) = self.block(idx);
+        let last = end.saturating_sub(1).max(idx);
+        let (last_start, last_text) = self.lines[last];
+        (
+            self.line_offset + idx as i64,
+            self.line_offset + last as i64,
+            (self.byte_offset + start) as i64,
+            (self.byte_offset + last_start + last_text.len()) as i64,
+        )
+    }
+}
+
+/// Add the operations and schemas of an OpenAPI / Swagger document.
+pub fn extract_spec(
+    value: &Value,
+    source: &SpecSource<'_>,
+    module_name: &str,
+    output: &mut ExtractedFile,
+) {
+    let lines = SpecLines::new(source);
+    let base_path = base_path(value);
+    let (schemas_key, schemas) = match value.pointer("/components/schemas") {
+        Some(schemas) => (["components", "schemas"].as_slice(), schemas),
+        None => match value.get("definitions") {
+            Some(definitions) => (["definitions"].as_slice(), definitions),
+            None => (&[] as &[&str], &Value::Null),
+        },
+    };
+
+    if let Some(paths) = value.get("paths").and_then(Value::as_object) {
+        for (path, item) in paths {
+            let Some(item) = item.as_object() else {
+                continue;
+            };
+            for (method, operation) in item {
+                if !OPERATION_METHODS.contains(&method.as_str()) {
+                    continue;
+                }
+                let line = lines.find_path(&["paths", path, method]);
+                extract_operation(
+                    &Operation {
+                        method: method.to_ascii_uppercase(),
+                        path,
+                        raw_path: http::join_paths(&base_path, path),
+                        value: operation,
+                        shared_parameters: item.get("parameters"),
+                    },
+                    lines.span(line),
+                    module_name,
+                    output,
+                );
+            }
+        }
+    }
+
+    extract_schemas(schemas, schemas_key, &lines, module_name, output);
+}
+
+/// Add a `schema` symbol per entry of `schemas` (found at `keys` in the
+/// document), with a `field` per property and TYPE_REF edges for `$ref`s.
+pub(crate) fn extract_schemas(
+    schemas: &Value,
+    keys: &[&str],
+    lines: &SpecLines<'_>,
+    module_name: &str,
+    output: &mut ExtractedFile,
+) {
+    let Some(schemas) = schemas.as_object() else {
+        return;
+    };
+    let pointer_prefix = format!("#/{}", keys.join("/"));
+    for (name, schema) in schemas {
+        let qualname = format!("{module_name}{pointer_prefix}/{name}");
+        let mut keys = keys.to_vec();
+        keys.push(name);
+        let schema_line = lines.find_path(&keys);
+        let mut symbol = spec_symbol("schema", name, &qualname, lines.span(schema_line));
+        symbol.signature = schema_signature(schema);
+        symbol.docstring = description(schema);
+        output.symbols.push(symbol);
+        output.edges.push(contains_edge(module_name, &qualname));
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (property, property_schema) in properties.into_iter().flatten() {
+            let property_qualname = format!("{qualname}.{property}");
+            let mut property_keys = keys.clone();
+            property_keys.extend(["properties", property.as_str()]);
+            let span = lines.span(lines.find_path(&property_keys).or(schema_line));
+            let mut symbol = spec_symbol("field", property, &property_qualname, span);
+            symbol.signature = schema_signature(property_schema)
+                .map(|signature| format!("{property}: {signature}"));
+            symbol.docstring = description(property_schema);
+            output.symbols.push(symbol);
+            output
+                .edges
+                .push(contains_edge(&qualname, &property_qualname));
+            push_type_refs(
+                property_schema,
+                &property_qualname,
+                "field",
+                span,
+                module_name,
+                output,
+            );
+        }
+        let mut outer = schema.clone();
+        if let Some(outer) = outer.as_object_mut() {
+            outer.remove("properties");
+        }
+        push_type_refs(
+            &outer,
+            &qualname,
+            "schema",
+            lines.span(schema_line),
+            module_name,
+            output,
+        );
+    }
+}
+
+struct Operation<'a> {
+    method: String,
+    path: &'a str,
+    raw_path: String,
+    value: &'a Value,
+    shared_parameters: Option<&'a Value>,
+}
+
+fn extract_operation(
+    operation: &Operation<'_>,
+    span: (i64, i64, i64, i64),
+    module_name: &str,
+    output: &mut ExtractedFile,
+) {
+    let operation_id = operation
+        .value
+        .get("operationId")
+        .and_then(Value::as_str)
+        .filter(|id| !id.trim().is_empty());
+    let name = match operation_id {
+        Some(id) => id.to_string(),
+        None => format!("{} {}", operation.method, operation.path),
+    };
+    let qualname = format!("{module_name}#{name}");
+    let mut symbol = spec_symbol("route", &name, &qualname, span);
+    symbol.signature = Some(format!("{} {}", operation.method, operation.raw_path));
+    symbol.docstring = operation
+        .value
+        .get("summary")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| description(operation.value));
+    output.symbols.push(symbol);
+    output.edges.push(contains_edge(module_name, &qualname));
+
+    if let Some(normalized) = http::normalize_path(&operation.raw_path) {
+        let detail = json!({
+            "method": operation.method,
+            "path": normalized,
+            "raw": operation.raw_path,
+            "framework": OPENAPI_FRAMEWORK,
+            "operation_id": operation_id,
+        });
+        output.edges.push(EdgeInput {
+            kind: http::HTTP_ROUTE_KIND.to_string(),
+            source_qualname: Some(qualname.clone()),
+            target_qualname: Some(normalized),
+            detail: Some(detail.to_string()),
+            evidence_start_line: Some(span.0),
+            evidence_end_line: Some(span.0),
+            ..Default::default()
+        });
+    }
+
+    let parameters = operation
+        .shared_parameters
+        .into_iter()
+        .chain(operation.value.get("parameters"))
+        .filter_map(Value::as_array)
+        .flatten();
+    for parameter in parameters {
+        // Swagger 2 request bodies are `in: body` parameters.
+        let role = if parameter.get("in").and_then(Value::as_str) == Some("body") {
+            "request"
+        } else {
+            "parameter"
+        };
+        push_type_refs(parameter, &qualname, role, span, module_name, output);
+    }
+    if let Some(body) = operation.value.get("requestBody") {
+        push_type_refs(body, &qualname, "request", span, module_name, output);
+    }
+    if let Some(responses) = operation.value.get("responses") {
+        push_type_refs(responses, &qualname, "response", span, module_name, output);
+    }
+}
+
+/// Path prefix every operation is served under: Swagger 2 `basePath`, or
+/// the path of the first OpenAPI 3 server URL.
+fn base_path(value: &Value) -> String {
+    if let Some(base) = value.get("basePath").and_then(Value::as_str) {
+        return base.to_string();
+    }
+    let Some(url) = value.pointer("/servers/0/url").and_then(Value::as_str) else {
+        return String::new();
+    };
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map(|idx| &rest[idx..]).unwrap_or(""),
+        None => url,
+    };
+    // Templated server paths (`/{version}`) cannot be matched literally.
+    if path.contains('{') || !path.starts_with('/') {
+        String::new()
+    } else {
+        path.to_string()
+    }
+}
+
+pub(crate) fn spec_symbol(
+    kind: &str,
+    name: &str,
+    qualname: &str,
+    (start_line, end_line, start_byte, end_byte): (i64, i64, i64, i64),
+) -> SymbolInput {
+    SymbolInput {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        qualname: qualname.to_string(),
+        start_line,
+        start_col: 1,
+        end_line,
+        end_col: 1,
+        start_byte,
+        end_byte,
+        signature: None,
+        docstring: None,
+    }
+}
+
+pub(crate) fn contains_edge(container: &str, qualname: &str) -> EdgeInput {
+    EdgeInput {
+        kind: "CONTAINS".to_string(),
+        source_qualname: Some(container.to_string()),
+        target_qualname: Some(qualname.to_string()),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn description(value: &Value) -> Option<String> {
+    value
+        .get("description")
+        .and_then(Value::as_str)
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+/// `string`, `array of User`, `User`, ... for a schema.
+pub(crate) fn schema_signature(schema: &Value) -> Option<String> {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference.rsplit('/').next().map(str::to_string);
+    }
+    let kind = schema.get("type").and_then(Value::as_str)?;
+    if kind == "array"
+        && let Some(items) = schema.get("items").and_then(schema_signature)
+    {
+        return Some(format!("array of {items}"));
+    }
+    match schema.get("format").and_then(Value::as_str) {
+        Some(format) => Some(format!("{kind} ({format})")),
+        None => Some(kind.to_string()),
+    }
+}
+
+/// Every schema `$ref` under `value`, in document order.
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                if key == "$ref" {
+                    if let Some(reference) = child.as_str() {
+                        refs.push(reference);
+                    }
+                } else {
+                    collect_refs(child, refs);
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_refs(item, refs)),
+        _ => {}
+    }
+}
+
+/// Qualname of the schema (or AsyncAPI message) a `$ref` points at:
+/// `#/components/schemas/X` in this document, or `other.yaml#/...` relative
+/// to it.
+fn ref_qualname(module_name: &str, reference: &str) -> Option<String> {
+    let (file, pointer) = reference.split_once('#')?;
+    let targets = [
+        "/components/schemas/",
+        "/components/messages/",
+        "/definitions/",
+    ];
+    if !targets.iter().any(|prefix| pointer.starts_with(prefix)) {
+        return None;
+    }
+    if file.is_empty() {
+        return Some(format!("{module_name}#{pointer}"));
+    }
+    let mut parts: Vec<&str> = module_name.split('/').collect();
+    parts.pop();
+    for segment in file.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            segment => parts.push(segment),
+        }
+    }
+    let file_name = parts.pop()?;
+    let stem = file_name
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .unwrap_or(file_name);
+    parts.push(stem);
+    Some(format!("{}#{pointer}", parts.join("/")))
+}
+
+pub(crate) fn push_type_refs(
+    value: &Value,
+    source_qualname: &str,
+    role: &str,
+    span: (i64, i64, i64, i64),
+    module_name: &str,
+    output: &mut ExtractedFile,
+) {
+    let mut refs = Vec::new();
+    collect_refs(value, &mut refs);
+    let mut seen = Vec::new();
+    for reference in refs {
+        let Some(target) = ref_qualname(module_name, reference) else {
+            continue;
+        };
+        if seen.contains(&target) {
+            continue;
+        }
+        seen.push(target.clone());
+        output.edges.push(EdgeInput {
+            kind: TYPE_REF_KIND.to_string(),
+            source_qualname: Some(source_qualname.to_string()),
+            target_qualname: Some(target),
+            detail: Some(json!({ "role": role }).to_string()),
+            evidence_start_line: Some(span.0),
+            evidence_end_line: Some(span.0),
+            ..Default::default()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"openapi: 3.0.1
+servers:
+  - url: https://api.example.com/v1
+paths:
+  /users/{id}:
+    parameters:
+      - name: id
+        in: path
+    get:
+      operationId: getUserById
+      summary: Fetch one user
+      responses:
+        "200":
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/User"
+    delete:
+      responses:
+        "204":
+          description: gone
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        id:
+          type: string
+        address:
+          $ref: "./common.yaml#/components/schemas/Address"
+"##;
+
+    #[test]
+    fn extracts_operations_schemas_and_refs() {
+        let value: serde_yaml_ng::Value = serde_yaml_ng::from_str(SPEC).unwrap();
+        let value = serde_json::to_value(value).unwrap();
+        assert!(is_api_spec(&value));
+        let mut output = ExtractedFile::default();
+        let source = SpecSource {
+            text: SPEC,
+            line_offset: 1,
+            byte_offset: 0,
+        };
+        extract_spec(&value, &source, "api/users", &mut output);
+
+        let symbol = |qualname: &str| {
+            output
+                .symbols
+                .iter()
+                .find(|symbol| symbol.qualname == qualname)
+                .unwrap_or_else(|| panic!("missing {qualname}"))
+        };
+        let get = symbol("api/users#getUserById");
+        assert_eq!(get.kind, "route");
+        assert_eq!(get.signature.as_deref(), Some("GET /v1/users/{id}"));
+        assert_eq!(get.start_line, 9);
+        assert_eq!(get.end_line, 17);
+        assert_eq!(symbol("api/users#DELETE /users/{id}").start_line, 18);
+        assert_eq!(
+            symbol("api/users#/components/schemas/User.id")
+                .signature
+                .as_deref(),
+            Some("id: string")
+        );
+
+        let route = output
+            .edges
+            .iter()
+            .find(|edge| edge.kind == http::HTTP_ROUTE_KIND)
+            .unwrap();
+        assert_eq!(route.target_qualname.as_deref(), Some("/v1/users/{}"));
+        let refs: Vec<(&str, &str)> = output
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == TYPE_REF_KIND)
+            .map(|edge| {
+                (
+                    edge.source_qualname.as_deref().unwrap(),
+                    edge.target_qualname.as_deref().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            refs,
+            vec![
+                (
+                    "api/users#getUserById",
+                    "api/users#/components/schemas/User"
+                ),
+                (
+                    "api/users#/components/schemas/User.address",
+                    "api/common#/components/schemas/Address"
+                ),
+            ]
+        );
+    }
+}
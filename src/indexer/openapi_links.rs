@@ -0,0 +1,214 @@
+//! Generated API clients linked to the OpenAPI operations they call.
+//!
+//! Client generators (openapi-generator, NSwag, Kiota, ...) name each client
+//! method after the operationId (`getUserById`, `GetUserByIdAsync`,
+//! `get_user_by_id`) and build the URL at run time, so the extractors rarely
+//! see the HTTP call. This pass adds an HTTP_CALL edge from each such method
+//! to the operation's route key, which bridges to the spec operation and to
+//! any server handler routed at the same path.
+
+use crate::db::{ApiOperation, CodegenSymbol, Db};
+use crate::indexer::extract::EdgeInput;
+use crate::indexer::http::{self, HTTP_CALL_KIND};
+use crate::indexer::openapi::OPENAPI_FRAMEWORK;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Confidence of a client method matched to an operation by name.
+const CLIENT_CONFIDENCE: f64 = 0.7;
+/// Suffix C# generators add to asynchronous client methods.
+const ASYNC_SUFFIX: &str = "async";
+/// Owner type names of generated clients (`UsersApi`, `UsersClient`,
+/// `UsersApiService`), lowercased.
+const CLIENT_OWNER_SUFFIXES: &[&str] = &["api", "client", "service"];
+/// Path fragments of files holding generated client functions.
+const CLIENT_PATH_HINTS: &[&str] = &["client", "api", "generated"];
+
+/// Rebuild the HTTP_CALL edges from generated client methods.
+pub fn link_generated_clients(db: &mut Db, graph_version: i64) -> Result<usize> {
+    db.delete_edges_by_detail(HTTP_CALL_KIND, "client", OPENAPI_FRAMEWORK, graph_version)?;
+    let operations = db.api_operations(graph_version)?;
+    let mut by_name: HashMap<String, Vec<&ApiOperation>> = HashMap::new();
+    for operation in &operations {
+        if let Some(id) = operation.operation_id.as_deref() {
+            by_name.entry(normalize(id)).or_default().push(operation);
+        }
+    }
+    if by_name.is_empty() {
+        return Ok(0);
+    }
+    let mut names: Vec<String> = by_name.keys().cloned().collect();
+    names.extend(by_name.keys().map(|name| format!("{name}{ASYNC_SUFFIX}")));
+    let candidates = db.callables_named(&names, graph_version)?;
+
+    let mut by_file: HashMap<i64, Vec<EdgeInput>> = HashMap::new();
+    let mut symbol_maps: HashMap<i64, HashMap<String, i64>> = HashMap::new();
+    for candidate in candidates.iter().filter(|c| is_client_callable(c)) {
+        let Some(operation) = operation_for(candidate, &by_name) else {
+            continue;
+        };
+        let detail = http::build_call_detail(
+            &operation.method,
+            &operation.path,
+            &operation.raw_path,
+            OPENAPI_FRAMEWORK,
+        );
+        by_file
+            .entry(candidate.file_id)
+            .or_default()
+            .push(EdgeInput {
+                kind: HTTP_CALL_KIND.to_string(),
+                source_qualname: Some(candidate.qualname.clone()),
+                target_qualname: Some(operation.path.clone()),
+                detail: Some(detail),
+                evidence_start_line: Some(candidate.start_line),
+                evidence_end_line: Some(candidate.start_line),
+                confidence: Some(CLIENT_CONFIDENCE),
+                ..Default::default()
+            });
+        symbol_maps
+            .entry(candidate.file_id)
+            .or_default()
+            .insert(candidate.qualname.clone(), candidate.id);
+    }
+
+    let commit_sha = db.graph_version_commit(graph_version)?;
+    let mut total = 0;
+    for (file_id, edges) in by_file {
+        let symbol_map = symbol_maps.remove(&file_id).unwrap_or_default();
+        total += db.insert_edges(
+            file_id,
+            &edges,
+            &symbol_map,
+            graph_version,
+            commit_sha.as_deref(),
+        )?;
+    }
+    Ok(total)
+}
+
+/// Lowercase with underscores removed: `getUserById`, `GetUserById` and
+/// `get_user_by_id` compare equal.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|ch| *ch != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Whether `symbol` looks like part of a generated client: a method of an
+/// `...Api` / `...Client` / `...Service` type, or a function in a file whose
+/// path mentions a client or API.
+fn is_client_callable(symbol: &CodegenSymbol) -> bool {
+    if matches!(symbol.language.as_str(), "yaml" | "json") {
+        return false;
+    }
+    if symbol.kind == "method" {
+        let owner = symbol
+            .qualname
+            .strip_suffix(symbol.name.as_str())
+            .map(|owner| owner.trim_end_matches(['.', ':']))
+            .and_then(|owner| owner.rsplit(['.', ':', '/']).next())
+            .map(normalize)
+            .unwrap_or_default();
+        return CLIENT_OWNER_SUFFIXES
+            .iter()
+            .any(|suffix| owner.ends_with(suffix));
+    }
+    let path = symbol.path.to_ascii_lowercase();
+    CLIENT_PATH_HINTS.iter().any(|hint| path.contains(hint))
+}
+
+/// The one operation `symbol` is named after; ambiguous operationIds (the
+/// same id in several specs, at different routes) link nothing.
+fn operation_for<'a>(
+    symbol: &CodegenSymbol,
+    by_name: &HashMap<String, Vec<&'a ApiOperation>>,
+) -> Option<&'a ApiOperation> {
+    let name = normalize(&symbol.name);
+    let operations = by_name.get(&name).or_else(|| {
+        name.strip_suffix(ASYNC_SUFFIX)
+            .and_then(|name| by_name.get(name))
+    })?;
+    let first = operations.first()?;
+    operations
+        .iter()
+        .all(|operation| operation.method == first.method && operation.path == first.path)
+        .then_some(*first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn callable(kind: &str, qualname: &str, path: &str) -> CodegenSymbol {
+        CodegenSymbol {
+            id: 1,
+            kind: kind.to_string(),
+            name: qualname.rsplit(['.', ':']).next().unwrap().to_string(),
+            qualname: qualname.to_string(),
+            file_id: 1,
+            path: path.to_string(),
+            language: "typescript".to_string(),
+            start_line: 1,
+            in_message_file: false,
+        }
+    }
+
+    fn operation(id: &str, path: &str) -> ApiOperation {
+        ApiOperation {
+            symbol_id: 1,
+            qualname: format!("api#{id}"),
+            operation_id: Some(id.to_string()),
+            method: "GET".to_string(),
+            path: path.to_string(),
+            raw_path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn matches_client_methods_by_operation_id() {
+        assert!(is_client_callable(&callable(
+            "method",
+            "src/gen/api.UsersApi.getUserById",
+            "src/gen/api.ts"
+        )));
+        assert!(is_client_callable(&callable(
+            "method",
+            "Acme.Clients.UsersClient.GetUserByIdAsync",
+            "src/Clients.cs"
+        )));
+        assert!(!is_client_callable(&callable(
+            "method",
+            "app.UserRepository.getUserById",
+            "src/repo.ts"
+        )));
+        assert!(is_client_callable(&callable(
+            "function",
+            "client.get_user_by_id",
+            "pkg/client.py"
+        )));
+
+        let get = operation("getUserById", "/users/{}");
+        let mut by_name: HashMap<String, Vec<&ApiOperation>> = HashMap::new();
+        by_name
+            .entry("getuserbyid".to_string())
+            .or_default()
+            .push(&get);
+        let found = operation_for(
+            &callable("method", "UsersClient.GetUserByIdAsync", "x.cs"),
+            &by_name,
+        );
+        assert_eq!(found.map(|op| op.path.as_str()), Some("/users/{}"));
+
+        let other = operation("getUserById", "/v2/users/{}");
+        by_name.get_mut("getuserbyid").unwrap().push(&other);
+        assert!(
+            operation_for(
+                &callable("method", "UsersApi.getUserById", "x.ts"),
+                &by_name
+            )
+            .is_none()
+        );
+    }
+}
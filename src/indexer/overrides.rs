@@ -0,0 +1,529 @@
+//! Method-level inheritance edges.
+//!
+//! Extractors record inheritance between types (EXTENDS / IMPLEMENTS); this
+//! pass links the members themselves so impact analysis can follow virtual
+//! dispatch. A method that redefines a base-class method gets an OVERRIDES
+//! edge to it; a method that fulfils an interface or trait member gets an
+//! IMPLEMENTS_MEMBER edge. Go has no declared implementations, so Go types
+//! are matched structurally against every interface whose method set they
+//! cover.
+
+use crate::db::{Db, HierarchySymbol, InheritanceEdge, common_prefix_segments};
+use crate::indexer::extract::EdgeInput;
+use crate::indexer::type_resolve::language_family;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+pub const OVERRIDES_KIND: &str = "OVERRIDES";
+pub const IMPLEMENTS_MEMBER_KIND: &str = "IMPLEMENTS_MEMBER";
+
+/// Confidence of a Go method linked to an interface by method set alone.
+const STRUCTURAL_CONFIDENCE: f64 = 0.9;
+/// How far up a type hierarchy a member is looked for.
+const MAX_BASE_DEPTH: usize = 4;
+/// Constructors are chained, not dispatched.
+const CONSTRUCTOR_NAMES: &[&str] = &[".ctor", "constructor", "__init__", "__new__"];
+
+/// Whether `kind` links a member to the base member it is dispatched through.
+/// These edges point from the implementation to the declaration, so impact
+/// traversal follows them against the requested direction.
+pub fn is_dispatch_edge_kind(kind: &str) -> bool {
+    kind == OVERRIDES_KIND || kind == IMPLEMENTS_MEMBER_KIND
+}
+
+/// Rebuild every OVERRIDES / IMPLEMENTS_MEMBER edge of `graph_version`.
+pub fn link_member_overrides(db: &mut Db, graph_version: i64) -> Result<usize> {
+    db.delete_edges_by_kind(OVERRIDES_KIND, graph_version)?;
+    db.delete_edges_by_kind(IMPLEMENTS_MEMBER_KIND, graph_version)?;
+    let symbols = db.hierarchy_symbols(graph_version)?;
+    let bases = db.inheritance_edges(graph_version)?;
+    let links = plan_member_links(&symbols, &bases);
+    insert_links(db, graph_version, &symbols, &links)
+}
+
+/// Relink after an incremental sync of `paths`. Only the edges that can
+/// change are replaced: those from or to members of the types declared in
+/// the synced files or deriving from them, and those whose base member is
+/// gone. Edges stored with the synced files were already dropped by the sync.
+pub fn relink_member_overrides(db: &mut Db, graph_version: i64, paths: &[String]) -> Result<usize> {
+    let mut file_ids = HashSet::new();
+    for path in paths {
+        if let Some(file) = db.get_file_by_path(path)? {
+            file_ids.insert(file.id);
+        }
+    }
+    let symbols = db.hierarchy_symbols(graph_version)?;
+    let bases = db.inheritance_edges(graph_version)?;
+    let hierarchy = Hierarchy::new(&symbols, &bases);
+    let existing = db.dispatch_edges(graph_version)?;
+
+    let mut affected: HashSet<i64> = hierarchy
+        .types
+        .values()
+        .filter(|ty| file_ids.contains(&ty.file_id))
+        .map(|ty| ty.id)
+        .collect();
+    for edge in &existing {
+        if edge.base_id.is_none()
+            && let Some(owner) = edge
+                .member_id
+                .and_then(|member| hierarchy.owners.get(&member))
+        {
+            affected.insert(*owner);
+        }
+    }
+    let mut derived: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (&child, parents) in &hierarchy.parents {
+        for &parent in parents {
+            derived.entry(parent).or_default().push(child);
+        }
+    }
+    let mut queue: Vec<i64> = affected.iter().copied().collect();
+    while let Some(ty) = queue.pop() {
+        for &child in derived.get(&ty).into_iter().flatten() {
+            if affected.insert(child) {
+                queue.push(child);
+            }
+        }
+    }
+
+    let touches = |member: Option<i64>| {
+        member
+            .and_then(|member| hierarchy.owners.get(&member))
+            .is_none_or(|owner| affected.contains(owner))
+    };
+    let stale: Vec<i64> = existing
+        .iter()
+        .filter(|edge| edge.base_id.is_none() || touches(edge.member_id) || touches(edge.base_id))
+        .map(|edge| edge.id)
+        .collect();
+    db.delete_edges_by_ids(&stale)?;
+    let links: Vec<MemberLink> = hierarchy
+        .links()
+        .into_iter()
+        .filter(|link| touches(Some(link.member)) || touches(Some(link.base)))
+        .collect();
+    insert_links(db, graph_version, &symbols, &links)
+}
+
+/// Store `links`, each with the file of its member.
+fn insert_links(
+    db: &mut Db,
+    graph_version: i64,
+    symbols: &[HierarchySymbol],
+    links: &[MemberLink],
+) -> Result<usize> {
+    if links.is_empty() {
+        return Ok(0);
+    }
+
+    let by_id: HashMap<i64, &HierarchySymbol> = symbols.iter().map(|s| (s.id, s)).collect();
+    let mut by_file: HashMap<i64, Vec<&MemberLink>> = HashMap::new();
+    for link in links {
+        by_file
+            .entry(by_id[&link.member].file_id)
+            .or_default()
+            .push(link);
+    }
+    let commit_sha = db.graph_version_commit(graph_version)?;
+    let mut total = 0;
+    for (file_id, links) in by_file {
+        let mut symbol_map = HashMap::new();
+        let edges: Vec<EdgeInput> = links
+            .iter()
+            .map(|link| {
+                let member = by_id[&link.member];
+                let base = by_id[&link.base];
+                symbol_map.insert(member.qualname.clone(), member.id);
+                symbol_map.insert(base.qualname.clone(), base.id);
+                EdgeInput {
+                    kind: link.kind.to_string(),
+                    source_qualname: Some(member.qualname.clone()),
+                    target_qualname: Some(base.qualname.clone()),
+                    evidence_start_line: Some(member.start_line),
+                    evidence_end_line: Some(member.start_line),
+                    confidence: link.structural.then_some(STRUCTURAL_CONFIDENCE),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        total += db.insert_edges(
+            file_id,
+            &edges,
+            &symbol_map,
+            graph_version,
+            commit_sha.as_deref(),
+        )?;
+    }
+    Ok(total)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MemberLink {
+    kind: &'static str,
+    member: i64,
+    base: i64,
+    structural: bool,
+}
+
+fn is_type_kind(kind: &str) -> bool {
+    matches!(
+        kind,
+        "class" | "interface" | "struct" | "trait" | "record" | "enum" | "type"
+    )
+}
+
+fn is_abstract_kind(kind: &str) -> bool {
+    matches!(kind, "interface" | "trait")
+}
+
+/// Qualname of the type declaring a member (`pkg.Repo.save` → `pkg.Repo`).
+fn owner_qualname(member: &HierarchySymbol) -> Option<&str> {
+    let owner = member.qualname.strip_suffix(member.name.as_str())?;
+    owner
+        .strip_suffix("::")
+        .or_else(|| owner.strip_suffix('.'))
+        .filter(|owner| !owner.is_empty())
+}
+
+fn simple_name(name: &str) -> &str {
+    let name = name.split(['<', '[', '(']).next().unwrap_or(name);
+    name.rsplit(['.', ':']).next().unwrap_or(name).trim()
+}
+
+/// Work out which member overrides or implements which.
+fn plan_member_links(symbols: &[HierarchySymbol], bases: &[InheritanceEdge]) -> Vec<MemberLink> {
+    Hierarchy::new(symbols, bases).links()
+}
+
+/// Types with their members and resolved bases.
+struct Hierarchy<'a> {
+    types: HashMap<i64, &'a HierarchySymbol>,
+    /// Members per declaring type, keyed by name.
+    members: HashMap<i64, HashMap<&'a str, i64>>,
+    /// Declaring type of every member.
+    owners: HashMap<i64, i64>,
+    /// Direct bases per type.
+    parents: HashMap<i64, Vec<i64>>,
+}
+
+impl<'a> Hierarchy<'a> {
+    fn new(symbols: &'a [HierarchySymbol], bases: &[InheritanceEdge]) -> Self {
+        let types: HashMap<i64, &HierarchySymbol> = symbols
+            .iter()
+            .filter(|s| is_type_kind(&s.kind))
+            .map(|s| (s.id, s))
+            .collect();
+        let mut type_by_qualname: HashMap<&str, i64> = HashMap::new();
+        let mut types_by_name: HashMap<&str, Vec<i64>> = HashMap::new();
+        for ty in types.values() {
+            type_by_qualname.entry(&ty.qualname).or_insert(ty.id);
+            types_by_name.entry(&ty.name).or_default().push(ty.id);
+        }
+        let mut members: HashMap<i64, HashMap<&str, i64>> = HashMap::new();
+        let mut owners = HashMap::new();
+        for member in symbols.iter().filter(|s| !is_type_kind(&s.kind)) {
+            let Some(owner) = owner_qualname(member).and_then(|q| type_by_qualname.get(q)) else {
+                continue;
+            };
+            owners.insert(member.id, *owner);
+            if !CONSTRUCTOR_NAMES.contains(&member.name.as_str()) {
+                members
+                    .entry(*owner)
+                    .or_default()
+                    .entry(&member.name)
+                    .or_insert(member.id);
+            }
+        }
+
+        // Resolve each declared base to a type symbol of the same language family.
+        let mut parents: HashMap<i64, Vec<i64>> = HashMap::new();
+        for edge in bases {
+            let Some(derived) = types.get(&edge.source_id) else {
+                continue;
+            };
+            let family = language_family(&derived.language);
+            let base = edge
+                .target_id
+                .filter(|id| types.contains_key(id))
+                .or_else(|| {
+                    let name = simple_name(edge.target_qualname.as_deref()?);
+                    types_by_name
+                        .get(name)?
+                        .iter()
+                        .map(|id| types[id])
+                        .filter(|ty| ty.id != derived.id && language_family(&ty.language) == family)
+                        .min_by_key(|ty| {
+                            (
+                                ty.file_id != derived.file_id,
+                                std::cmp::Reverse(common_prefix_segments(
+                                    &derived.qualname,
+                                    &ty.qualname,
+                                )),
+                                ty.qualname.len(),
+                                ty.id,
+                            )
+                        })
+                        .map(|ty| ty.id)
+                });
+            if let Some(base) = base
+                && base != derived.id
+            {
+                let entry = parents.entry(derived.id).or_default();
+                if !entry.contains(&base) {
+                    entry.push(base);
+                }
+            }
+        }
+
+        Self {
+            types,
+            members,
+            owners,
+            parents,
+        }
+    }
+
+    fn links(&self) -> Vec<MemberLink> {
+        let Self {
+            types,
+            members,
+            parents,
+            ..
+        } = self;
+        let mut links = Vec::new();
+        let mut seen: HashSet<(i64, i64)> = HashSet::new();
+        for (&derived, own) in members.iter() {
+            let Some(direct) = parents.get(&derived) else {
+                continue;
+            };
+            for (&name, &member) in own {
+                // Breadth-first up the hierarchy; a branch stops at the first
+                // base declaring the member, which links onward itself.
+                let mut queue: VecDeque<(i64, usize)> = direct.iter().map(|&b| (b, 1)).collect();
+                let mut visited: HashSet<i64> = HashSet::from([derived]);
+                while let Some((base, depth)) = queue.pop_front() {
+                    if !visited.insert(base) {
+                        continue;
+                    }
+                    if let Some(&target) = members.get(&base).and_then(|m| m.get(name)) {
+                        if seen.insert((member, target)) {
+                            let kind = if is_abstract_kind(&types[&base].kind) {
+                                IMPLEMENTS_MEMBER_KIND
+                            } else {
+                                OVERRIDES_KIND
+                            };
+                            links.push(MemberLink {
+                                kind,
+                                member,
+                                base: target,
+                                structural: false,
+                            });
+                        }
+                        continue;
+                    }
+                    if depth < MAX_BASE_DEPTH
+                        && let Some(next) = parents.get(&base)
+                    {
+                        queue.extend(next.iter().map(|&b| (b, depth + 1)));
+                    }
+                }
+            }
+        }
+
+        links.extend(structural_links(types, members, parents, &mut seen));
+        links.sort_by_key(|link| (link.member, link.base));
+        links
+    }
+}
+
+/// Go: a type implements every interface whose full method set (including
+/// embedded interfaces) it declares.
+fn structural_links(
+    types: &HashMap<i64, &HierarchySymbol>,
+    members: &HashMap<i64, HashMap<&str, i64>>,
+    parents: &HashMap<i64, Vec<i64>>,
+    seen: &mut HashSet<(i64, i64)>,
+) -> Vec<MemberLink> {
+    let is_go = |id: &i64| types[id].language == "go";
+    let mut implementers_by_method: HashMap<&str, Vec<i64>> = HashMap::new();
+    for (id, own) in members {
+        if is_go(id) && types[id].kind != "interface" {
+            for name in own.keys() {
+                implementers_by_method.entry(name).or_default().push(*id);
+            }
+        }
+    }
+
+    let mut links = Vec::new();
+    for (&interface, ty) in types {
+        if !is_go(&interface) || ty.kind != "interface" {
+            continue;
+        }
+        // Method set: name → declaring interface member.
+        let mut method_set: HashMap<&str, i64> = HashMap::new();
+        let mut queue = VecDeque::from([(interface, 0)]);
+        let mut visited = HashSet::new();
+        while let Some((current, depth)) = queue.pop_front() {
+            if !visited.insert(current) {
+                continue;
+            }
+            if let Some(own) = members.get(&current) {
+                for (&name, &member) in own {
+                    method_set.entry(name).or_insert(member);
+                }
+            }
+            if depth < MAX_BASE_DEPTH
+                && let Some(next) = parents.get(&current)
+            {
+                queue.extend(next.iter().map(|&b| (b, depth + 1)));
+            }
+        }
+        // Start from the rarest method name to keep the candidate set small.
+        let Some(candidates) = method_set
+            .keys()
+            .map(|name| implementers_by_method.get(name))
+            .min_by_key(|ids| ids.map_or(0, Vec::len))
+            .flatten()
+        else {
+            continue;
+        };
+        for &implementer in candidates {
+            let own = &members[&implementer];
+            if !method_set.keys().all(|name| own.contains_key(name)) {
+                continue;
+            }
+            for (name, &target) in &method_set {
+                let member = own[name];
+                if seen.insert((member, target)) {
+                    links.push(MemberLink {
+                        kind: IMPLEMENTS_MEMBER_KIND,
+                        member,
+                        base: target,
+                        structural: true,
+                    });
+                }
+            }
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(id: i64, kind: &str, qualname: &str, language: &str) -> HierarchySymbol {
+        let name = qualname.rsplit(['.', ':']).next().unwrap().to_string();
+        HierarchySymbol {
+            id,
+            kind: kind.to_string(),
+            name,
+            qualname: qualname.to_string(),
+            file_id: 1,
+            start_line: 1,
+            language: language.to_string(),
+        }
+    }
+
+    fn base(source_id: i64, target: &str) -> InheritanceEdge {
+        InheritanceEdge {
+            source_id,
+            target_id: None,
+            target_qualname: Some(target.to_string()),
+        }
+    }
+
+    fn link(kind: &'static str, member: i64, base: i64, structural: bool) -> MemberLink {
+        MemberLink {
+            kind,
+            member,
+            base,
+            structural,
+        }
+    }
+
+    #[test]
+    fn links_overrides_through_intermediate_bases() {
+        let symbols = vec![
+            symbol(1, "interface", "Acme.IRepository", "csharp"),
+            symbol(2, "method", "Acme.IRepository.Save", "csharp"),
+            symbol(3, "class", "Acme.BaseRepo", "csharp"),
+            symbol(4, "method", "Acme.BaseRepo.Save", "csharp"),
+            symbol(5, "class", "Acme.SqlRepository", "csharp"),
+            symbol(6, "method", "Acme.SqlRepository.Save", "csharp"),
+            symbol(7, "class", "Acme.CachedRepository", "csharp"),
+            symbol(8, "method", "Acme.CachedRepository.Save", "csharp"),
+            symbol(9, "class", "Acme.Passthrough", "csharp"),
+        ];
+        let bases = vec![
+            base(3, "IRepository"),
+            base(5, "BaseRepo"),
+            base(9, "IRepository"),
+            base(7, "Passthrough"),
+        ];
+        assert_eq!(
+            plan_member_links(&symbols, &bases),
+            vec![
+                link(IMPLEMENTS_MEMBER_KIND, 4, 2, false),
+                link(OVERRIDES_KIND, 6, 4, false),
+                // Passthrough declares no Save, so the search continues past it.
+                link(IMPLEMENTS_MEMBER_KIND, 8, 2, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_constructors_and_other_languages() {
+        let symbols = vec![
+            symbol(1, "class", "svc.Base", "python"),
+            symbol(2, "method", "svc.Base.__init__", "python"),
+            symbol(3, "class", "svc.Child", "python"),
+            symbol(4, "method", "svc.Child.__init__", "python"),
+            symbol(5, "class", "app.Base", "javascript"),
+            symbol(6, "method", "app.Base.save", "javascript"),
+            symbol(7, "method", "svc.Child.save", "python"),
+        ];
+        assert!(plan_member_links(&symbols, &[base(3, "Base")]).is_empty());
+    }
+
+    #[test]
+    fn go_types_implement_interfaces_structurally() {
+        let symbols = vec![
+            symbol(1, "interface", "main.Repository", "go"),
+            symbol(2, "method", "main.Repository.Save", "go"),
+            symbol(3, "method", "main.Repository.Load", "go"),
+            symbol(4, "class", "main.SqlRepo", "go"),
+            symbol(5, "method", "main.SqlRepo.Save", "go"),
+            symbol(6, "method", "main.SqlRepo.Load", "go"),
+            symbol(7, "class", "main.Partial", "go"),
+            symbol(8, "method", "main.Partial.Save", "go"),
+        ];
+        assert_eq!(
+            plan_member_links(&symbols, &[]),
+            vec![
+                link(IMPLEMENTS_MEMBER_KIND, 5, 2, true),
+                link(IMPLEMENTS_MEMBER_KIND, 6, 3, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn rust_trait_impls_use_path_separators() {
+        let symbols = vec![
+            symbol(1, "trait", "crate::Repository", "rust"),
+            symbol(2, "method", "crate::Repository::save", "rust"),
+            symbol(3, "struct", "crate::SqlRepo", "rust"),
+            symbol(4, "method", "crate::SqlRepo::save", "rust"),
+        ];
+        let bases = vec![InheritanceEdge {
+            source_id: 3,
+            target_id: Some(1),
+            target_qualname: Some("crate::Repository".to_string()),
+        }];
+        assert_eq!(
+            plan_member_links(&symbols, &bases),
+            vec![link(IMPLEMENTS_MEMBER_KIND, 4, 2, false)]
+        );
+    }
+}
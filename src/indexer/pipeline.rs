@@ -0,0 +1,253 @@
+//! Parallel extraction for full reindexes.
+//!
+//! Worker threads each own a set of extractors; they read, parse and extract
+//! files and send the results through a bounded channel to the calling
+//! thread, which stays the only SQLite writer. Files are admitted in scan
+//! order against a memory budget (`LIDX_MAX_MEMORY_MB`) and handed to the
+//! writer in that same order, so the database ends up exactly as the
+//! sequential loop left it, whatever order the workers finish in.
+
+use crate::indexer::batch::BatchConfig;
+use crate::indexer::extract::ExtractedFile;
+use crate::indexer::rules::ProjectRules;
+use crate::indexer::scan::ScannedFile;
+use crate::indexer::{build_extractors, extract_source};
+use crate::metrics;
+use anyhow::{Result, anyhow};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::mpsc;
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Rough bytes held per source byte while a file is in flight: the source,
+/// its syntax tree and the extracted symbols and edges.
+const BYTES_PER_SOURCE_BYTE: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct PipelineConfig {
+    /// Extraction threads (default: available cores, `LIDX_INDEX_THREADS`).
+    pub threads: usize,
+    /// Budget for files read but not yet written (default: the batch
+    /// writer's `LIDX_MAX_MEMORY_MB`).
+    pub max_memory_bytes: usize,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            threads: std::env::var("LIDX_INDEX_THREADS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|threads| *threads > 0)
+                .unwrap_or_else(|| {
+                    std::thread::available_parallelism()
+                        .map(|threads| threads.get())
+                        .unwrap_or(1)
+                }),
+            max_memory_bytes: BatchConfig::default().max_memory_bytes,
+        }
+    }
+}
+
+/// A file's source and extraction (with metrics), or why it was skipped.
+pub type FileResult = Result<(String, ExtractedFile)>;
+
+/// Extract `files` on `config.threads` workers and call `write` with each
+/// result in scan order. An error from `write` stops admitting files and is
+/// returned once the workers have drained.
+pub fn extract_files<F>(
+    repo_root: &Path,
+    rules: &Arc<ProjectRules>,
+    files: &[ScannedFile],
+    config: &PipelineConfig,
+    write: F,
+) -> Result<()>
+where
+    F: FnMut(usize, &ScannedFile, FileResult) -> Result<()>,
+{
+    let read = |file: &ScannedFile| {
+        crate::util::read_to_string(&file.abs_path)
+            .map_err(|err| anyhow!("read error {}: {err}", file.rel_path))
+    };
+    extract_files_with(repo_root, rules, files, config, &read, write)
+}
+
+/// `extract_files` with sources from `read` instead of the working tree.
+pub fn extract_files_with<F>(
+    repo_root: &Path,
+    rules: &Arc<ProjectRules>,
+    files: &[ScannedFile],
+    config: &PipelineConfig,
+    read: &(dyn Fn(&ScannedFile) -> Result<String> + Sync),
+    mut write: F,
+) -> Result<()>
+where
+    F: FnMut(usize, &ScannedFile, FileResult) -> Result<()>,
+{
+    if files.is_empty() {
+        return Ok(());
+    }
+    let threads = config.threads.clamp(1, files.len());
+    let admission = Admission::new(config.max_memory_bytes);
+    let setup_error: Mutex<Option<anyhow::Error>> = Mutex::new(None);
+
+    let outcome = std::thread::scope(|scope| {
+        let (tx, rx) = mpsc::sync_channel::<(usize, usize, FileResult)>(threads * 2);
+        for _ in 0..threads {
+            let tx = tx.clone();
+            let admission = &admission;
+            let setup_error = &setup_error;
+            scope.spawn(move || {
+                let mut extractors = match build_extractors(repo_root) {
+                    Ok(extractors) => extractors,
+                    Err(err) => {
+                        admission.stop();
+                        setup_error.lock().unwrap().get_or_insert(err);
+                        return;
+                    }
+                };
+                while let Some((idx, cost)) = admission.admit(files) {
+                    let file = &files[idx];
+                    let result = read(file).and_then(|source| {
+                        let mut extracted =
+                            extract_source(&mut extractors, repo_root, rules, file, &source)?;
+                        extracted.file_metrics =
+                            Some(metrics::compute_file_metrics(&source, &file.language));
+                        extracted.symbol_metrics = metrics::compute_symbol_metrics(
+                            &source,
+                            &file.language,
+                            &extracted.symbols,
+                        );
+                        Ok((source, extracted))
+                    });
+                    if tx.send((idx, cost, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        // Results arrive in completion order; hold them until every earlier
+        // file has been written. Admission is in scan order, so the next file
+        // to write is always in flight and the buffer stays within budget.
+        let mut pending: BTreeMap<usize, (usize, FileResult)> = BTreeMap::new();
+        let mut next = 0;
+        let mut outcome = Ok(());
+        for (idx, cost, result) in rx {
+            pending.insert(idx, (cost, result));
+            while let Some((cost, result)) = pending.remove(&next) {
+                if outcome.is_ok() {
+                    outcome = write(next, &files[next], result);
+                    if outcome.is_err() {
+                        admission.stop();
+                    }
+                }
+                admission.release(cost);
+                next += 1;
+            }
+        }
+        outcome
+    });
+
+    if let Some(err) = setup_error.into_inner().unwrap() {
+        return Err(err);
+    }
+    outcome
+}
+
+/// Hands out file indices in order while the estimated memory of admitted,
+/// unwritten files stays within budget. A file larger than the whole budget
+/// is still admitted once nothing else is in flight.
+struct Admission {
+    state: Mutex<AdmissionState>,
+    released: Condvar,
+    budget: usize,
+}
+
+struct AdmissionState {
+    next: usize,
+    in_flight: usize,
+    stopped: bool,
+}
+
+impl Admission {
+    fn new(budget: usize) -> Self {
+        Self {
+            state: Mutex::new(AdmissionState {
+                next: 0,
+                in_flight: 0,
+                stopped: false,
+            }),
+            released: Condvar::new(),
+            budget,
+        }
+    }
+
+    fn admit(&self, files: &[ScannedFile]) -> Option<(usize, usize)> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.stopped || state.next >= files.len() {
+                return None;
+            }
+            let cost =
+                (files[state.next].size.max(0) as usize).saturating_mul(BYTES_PER_SOURCE_BYTE);
+            if state.in_flight == 0 || state.in_flight.saturating_add(cost) <= self.budget {
+                let idx = state.next;
+                state.next += 1;
+                state.in_flight += cost;
+                return Some((idx, cost));
+            }
+            state = self.released.wait(state).unwrap();
+        }
+    }
+
+    fn release(&self, cost: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.in_flight = state.in_flight.saturating_sub(cost);
+        self.released.notify_all();
+    }
+
+    fn stop(&self) {
+        self.state.lock().unwrap().stopped = true;
+        self.released.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn scanned(size: i64) -> ScannedFile {
+        ScannedFile {
+            rel_path: "a.py".to_string(),
+            abs_path: PathBuf::from("a.py"),
+            hash: String::new(),
+            size,
+            modified: 0,
+            language: "python".to_string(),
+        }
+    }
+
+    #[test]
+    fn admission_respects_budget_in_order() {
+        let files = vec![scanned(10), scanned(10), scanned(100)];
+        let admission = Admission::new(25 * BYTES_PER_SOURCE_BYTE);
+        assert_eq!(admission.admit(&files), Some((0, 80)));
+        assert_eq!(admission.admit(&files), Some((1, 80)));
+        admission.release(80);
+        admission.release(80);
+        // Larger than the budget, but admitted alone.
+        assert_eq!(admission.admit(&files), Some((2, 800)));
+        assert_eq!(admission.admit(&files), None);
+    }
+
+    #[test]
+    fn stopped_admission_hands_out_nothing() {
+        let files = vec![scanned(1)];
+        let admission = Admission::new(usize::MAX);
+        admission.stop();
+        assert_eq!(admission.admit(&files), None);
+    }
+}
@@ -4,11 +4,19 @@ use crate::indexer::tree_helpers::module_symbol_fallback;
 use crate::util;
 use anyhow::Result;
 use serde_json::json;
+use std::collections::HashSet;
 use std::path::Path;
 
 pub const RPC_ROUTE_KIND: &str = "RPC_ROUTE";
 pub const RPC_CALL_KIND: &str = "RPC_CALL";
 pub const RPC_IMPL_KIND: &str = "RPC_IMPL";
+pub const TYPE_REF_KIND: &str = "TYPE_REF";
+
+/// Scalar field types, which never name a message or enum.
+const SCALAR_TYPES: &[&str] = &[
+    "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+    "fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
 
 #[derive(Clone)]
 struct Token {
@@ -23,6 +31,9 @@ struct Token {
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum TokenKind {
     Ident,
+    Number,
+    /// String literal; `text` holds the unquoted contents.
+    Str,
     Punct(char),
 }
 
@@ -60,6 +71,28 @@ struct ActiveService {
     depth: usize,
 }
 
+/// A `message` or `enum` declaration.
+struct TypeDef {
+    kind: &'static str,
+    qualname: String,
+    /// Index in the parsed list of the message this one is nested in.
+    parent: Option<usize>,
+    start_token: Token,
+    name_token: Token,
+    end_token: Token,
+    members: Vec<MemberDef>,
+}
+
+/// A message field or enum value.
+struct MemberDef {
+    name_token: Token,
+    start_token: Token,
+    end_token: Token,
+    /// Message or enum type the field refers to (map value type for maps).
+    type_ref: Option<String>,
+    signature: String,
+}
+
 pub struct ProtoExtractor;
 
 impl ProtoExtractor {
@@ -82,6 +115,62 @@ impl crate::indexer::extract::LanguageExtractor for ProtoExtractor {
         let tokens = tokenize_proto(source);
         let package = find_package(&tokens);
         let services = parse_services(&tokens);
+        let types = parse_types(&tokens, package.as_deref().unwrap_or(""));
+        let declared: HashSet<&str> = types.iter().map(|ty| ty.qualname.as_str()).collect();
+
+        for (path, token) in find_imports(&tokens) {
+            output.edges.push(EdgeInput {
+                kind: "IMPORTS".to_string(),
+                source_qualname: Some(module_name.to_string()),
+                target_qualname: Some(module_name_from_rel_path(&path)),
+                evidence_start_line: Some(token.start_line),
+                evidence_end_line: Some(token.start_line),
+                ..Default::default()
+            });
+        }
+
+        for ty in &types {
+            let mut symbol = symbol_from_span(
+                ty.kind,
+                &ty.name_token,
+                &ty.qualname,
+                &ty.start_token,
+                &ty.end_token,
+            );
+            symbol.signature = Some(format!("{} {}", ty.kind, ty.name_token.text));
+            output.symbols.push(symbol);
+            let container = match ty.parent {
+                Some(parent) => types[parent].qualname.clone(),
+                None => module_name.to_string(),
+            };
+            output.edges.push(contains_edge(&container, &ty.qualname));
+
+            let member_kind = if ty.kind == "enum" {
+                "enum_member"
+            } else {
+                "field"
+            };
+            for member in &ty.members {
+                let qualname = format!("{}.{}", ty.qualname, member.name_token.text);
+                let mut symbol = symbol_from_span(
+                    member_kind,
+                    &member.name_token,
+                    &qualname,
+                    &member.start_token,
+                    &member.end_token,
+                );
+                symbol.signature = Some(member.signature.clone());
+                output.symbols.push(symbol);
+                output.edges.push(contains_edge(&ty.qualname, &qualname));
+                if let Some(type_ref) = member.type_ref.as_deref() {
+                    let target =
+                        resolve_type_ref(type_ref, &ty.qualname, package.as_deref(), &declared);
+                    output
+                        .edges
+                        .push(type_ref_edge(source, &qualname, target, "field", member));
+                }
+            }
+        }
 
         for service in services {
             let service_name = service.name.clone();
@@ -115,6 +204,26 @@ impl crate::indexer::extract::LanguageExtractor for ProtoExtractor {
                     evidence_snippet: None,
                     ..Default::default()
                 });
+                for (role, message) in [("request", &rpc.request), ("response", &rpc.response)] {
+                    let Some(message) = message.as_deref().map(strip_stream) else {
+                        continue;
+                    };
+                    let target = resolve_type_ref(
+                        message,
+                        package.as_deref().unwrap_or(""),
+                        package.as_deref(),
+                        &declared,
+                    );
+                    output.edges.push(EdgeInput {
+                        kind: TYPE_REF_KIND.to_string(),
+                        source_qualname: Some(rpc_qualname.clone()),
+                        target_qualname: Some(target),
+                        detail: Some(json!({ "role": role }).to_string()),
+                        evidence_start_line: Some(rpc.start_token.start_line),
+                        evidence_end_line: Some(rpc.end_token.start_line),
+                        ..Default::default()
+                    });
+                }
 
                 let Some((raw_path, normalized)) =
                     normalize_rpc_path(package.as_deref(), &service_name, &rpc.name)
@@ -197,6 +306,106 @@ fn symbol_from_token(kind: &str, name: &str, qualname: &str, token: &Token) -> S
     }
 }
 
+fn symbol_from_span(
+    kind: &str,
+    name_token: &Token,
+    qualname: &str,
+    start: &Token,
+    end: &Token,
+) -> SymbolInput {
+    SymbolInput {
+        kind: kind.to_string(),
+        name: name_token.text.clone(),
+        qualname: qualname.to_string(),
+        start_line: start.start_line,
+        start_col: start.start_col,
+        end_line: end.start_line,
+        end_col: end.start_col + (end.end_byte - end.start_byte),
+        start_byte: start.start_byte,
+        end_byte: end.end_byte,
+        signature: None,
+        docstring: None,
+    }
+}
+
+fn contains_edge(container: &str, qualname: &str) -> EdgeInput {
+    EdgeInput {
+        kind: "CONTAINS".to_string(),
+        source_qualname: Some(container.to_string()),
+        target_qualname: Some(qualname.to_string()),
+        ..Default::default()
+    }
+}
+
+fn type_ref_edge(
+    source: &str,
+    qualname: &str,
+    target: String,
+    role: &str,
+    member: &MemberDef,
+) -> EdgeInput {
+    EdgeInput {
+        kind: TYPE_REF_KIND.to_string(),
+        source_qualname: Some(qualname.to_string()),
+        target_qualname: Some(target),
+        detail: Some(json!({ "role": role }).to_string()),
+        evidence_snippet: util::edge_evidence_snippet(
+            source,
+            member.start_token.start_byte,
+            member.end_token.end_byte,
+            member.start_token.start_line,
+            member.end_token.start_line,
+        ),
+        evidence_start_line: Some(member.start_token.start_line),
+        evidence_end_line: Some(member.end_token.start_line),
+        ..Default::default()
+    }
+}
+
+fn strip_stream(message: &str) -> &str {
+    message.strip_prefix("stream ").unwrap_or(message).trim()
+}
+
+fn join_qualname(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+/// Qualname of the message or enum `reference` names from inside `scope`,
+/// following protobuf scoping: a leading `.` is fully qualified; otherwise
+/// the innermost enclosing scope that declares it in this file wins. Names
+/// declared elsewhere are taken relative to the package when unqualified and
+/// as written when qualified.
+fn resolve_type_ref(
+    reference: &str,
+    scope: &str,
+    package: Option<&str>,
+    declared: &HashSet<&str>,
+) -> String {
+    if let Some(absolute) = reference.strip_prefix('.') {
+        return absolute.to_string();
+    }
+    let mut scope = scope;
+    loop {
+        let candidate = join_qualname(scope, reference);
+        if declared.contains(candidate.as_str()) {
+            return candidate;
+        }
+        if scope.is_empty() {
+            break;
+        }
+        scope = scope.rsplit_once('.').map(|(outer, _)| outer).unwrap_or("");
+    }
+    if reference.contains('.') {
+        reference.to_string()
+    } else {
+        join_qualname(package.unwrap_or(""), reference)
+    }
+}
+
 fn build_service_qualname(package: Option<&str>, service: &str) -> String {
     match package {
         Some(package) if !package.is_empty() => format!("{package}.{service}"),
@@ -231,6 +440,201 @@ fn find_package(tokens: &[Token]) -> Option<String> {
     None
 }
 
+/// `import "path/x.proto";` statements (including `public` and `weak`).
+fn find_imports(tokens: &[Token]) -> Vec<(String, &Token)> {
+    let mut imports = Vec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        if !token.is_ident("import") || (idx > 0 && !tokens[idx - 1].is_punct(';')) {
+            continue;
+        }
+        let mut next = idx + 1;
+        if tokens
+            .get(next)
+            .is_some_and(|t| t.is_ident("public") || t.is_ident("weak"))
+        {
+            next += 1;
+        }
+        if let Some(path) = tokens.get(next).filter(|t| t.kind == TokenKind::Str) {
+            imports.push((path.text.clone(), token));
+        }
+    }
+    imports
+}
+
+/// Every `message` and `enum` in the file, outer declarations before the
+/// ones nested in them.
+fn parse_types(tokens: &[Token], package: &str) -> Vec<TypeDef> {
+    let mut types = Vec::new();
+    parse_block(tokens, 0, package, None, &mut types);
+    types
+}
+
+/// Parse declarations from `idx` up to the `}` closing the current block,
+/// returning the index after it. Fields and enum values are added to
+/// `owner`; `scope` prefixes nested type qualnames.
+fn parse_block(
+    tokens: &[Token],
+    mut idx: usize,
+    scope: &str,
+    owner: Option<usize>,
+    types: &mut Vec<TypeDef>,
+) -> usize {
+    while idx < tokens.len() {
+        let token = &tokens[idx];
+        if token.is_punct('}') {
+            return idx + 1;
+        }
+        if token.is_punct(';') {
+            idx += 1;
+            continue;
+        }
+        let name = tokens
+            .get(idx + 1)
+            .filter(|t| t.is_ident_any())
+            .filter(|_| tokens.get(idx + 2).is_some_and(|t| t.is_punct('{')));
+        if let Some(name) = name
+            && (token.is_ident("message") || token.is_ident("enum"))
+        {
+            let qualname = join_qualname(scope, &name.text);
+            types.push(TypeDef {
+                kind: if token.is_ident("message") {
+                    "message"
+                } else {
+                    "enum"
+                },
+                qualname: qualname.clone(),
+                parent: owner,
+                start_token: token.clone(),
+                name_token: name.clone(),
+                end_token: name.clone(),
+                members: Vec::new(),
+            });
+            let def = types.len() - 1;
+            idx = parse_block(tokens, idx + 3, &qualname, Some(def), types);
+            if let Some(end) = tokens.get(idx - 1) {
+                types[def].end_token = end.clone();
+            }
+            continue;
+        }
+        if name.is_some()
+            && token.is_ident("oneof")
+            && owner.is_some_and(|owner| types[owner].kind == "message")
+        {
+            // Oneof members are fields of the enclosing message.
+            idx = parse_block(tokens, idx + 3, scope, owner, types);
+            continue;
+        }
+        let end = statement_end(tokens, idx);
+        if let Some(owner) = owner
+            && let Some(member) = parse_member(&tokens[idx..end], types[owner].kind)
+        {
+            types[owner].members.push(member);
+        }
+        idx = end.max(idx + 1);
+    }
+    idx
+}
+
+/// End (exclusive) of the statement starting at `idx`: after its `;` or its
+/// closing `}`, or at the `}` that closes the enclosing block.
+fn statement_end(tokens: &[Token], idx: usize) -> usize {
+    let mut depth = 0usize;
+    for (offset, token) in tokens[idx..].iter().enumerate() {
+        if token.is_punct('{') {
+            depth += 1;
+        } else if token.is_punct('}') {
+            if depth == 0 {
+                return idx + offset;
+            }
+            depth -= 1;
+            if depth == 0 {
+                return idx + offset + 1;
+            }
+        } else if token.is_punct(';') && depth == 0 {
+            return idx + offset + 1;
+        }
+    }
+    tokens.len()
+}
+
+/// A field (`[label] Type name = N`, `map<K, V> name = N`) of a message or
+/// a value (`NAME = N`) of an enum.
+fn parse_member(statement: &[Token], owner_kind: &str) -> Option<MemberDef> {
+    let first = statement.first()?;
+    if [
+        "option",
+        "reserved",
+        "extensions",
+        "extend",
+        "message",
+        "enum",
+        "group",
+    ]
+    .iter()
+    .any(|keyword| first.is_ident(keyword))
+    {
+        return None;
+    }
+    let eq = statement.iter().position(|t| t.is_punct('='))?;
+    let name_token = statement.get(eq.checked_sub(1)?)?.clone();
+    if !name_token.is_ident_any() {
+        return None;
+    }
+    // Field options (`[deprecated = true]`) are not part of the signature.
+    let end = statement
+        .iter()
+        .position(|t| t.is_punct('[') || t.is_punct(';'))
+        .unwrap_or(statement.len());
+    let end_token = statement.get(end.saturating_sub(1))?.clone();
+    let signature = signature_text(&statement[..end]);
+    if owner_kind == "enum" {
+        return (eq == 1).then_some(MemberDef {
+            name_token,
+            start_token: first.clone(),
+            end_token,
+            type_ref: None,
+            signature,
+        });
+    }
+    let type_tokens = &statement[..eq - 1];
+    let type_name = if first.is_ident("map") {
+        // map<K, V>: the value type is the one that can name a message.
+        let close = type_tokens.iter().position(|t| t.is_punct('>'))?;
+        type_tokens.get(close.checked_sub(1)?)?
+    } else {
+        type_tokens.last()?
+    };
+    if !type_name.is_ident_any() {
+        return None;
+    }
+    let type_ref =
+        (!SCALAR_TYPES.contains(&type_name.text.as_str())).then(|| type_name.text.clone());
+    Some(MemberDef {
+        name_token,
+        start_token: first.clone(),
+        end_token,
+        type_ref,
+        signature,
+    })
+}
+
+fn signature_text(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    for token in tokens {
+        let glue = token.is_punct('<')
+            || token.is_punct('>')
+            || token.is_punct(',')
+            || text.ends_with('<')
+            || text.ends_with('-')
+            || text.is_empty();
+        if !glue {
+            text.push(' ');
+        }
+        text.push_str(&token.text);
+    }
+    text
+}
+
 fn parse_services(tokens: &[Token]) -> Vec<ServiceDef> {
     let mut services = Vec::new();
     let mut idx = 0;
@@ -423,6 +827,54 @@ fn tokenize_proto(source: &str) -> Vec<Token> {
         }
 
         let ch = byte as char;
+        if byte == b'"' || byte == b'\'' {
+            let start = idx;
+            let start_line = line;
+            let start_col = col;
+            idx += 1;
+            col += 1;
+            while idx < bytes.len() && bytes[idx] != byte && bytes[idx] != b'\n' {
+                if bytes[idx] == b'\\' && idx + 1 < bytes.len() {
+                    idx += 1;
+                    col += 1;
+                }
+                idx += 1;
+                col += 1;
+            }
+            let text = source.get(start + 1..idx).unwrap_or("").to_string();
+            if idx < bytes.len() && bytes[idx] == byte {
+                idx += 1;
+                col += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Str,
+                text,
+                start_line,
+                start_col,
+                start_byte: start as i64,
+                end_byte: idx as i64,
+            });
+            continue;
+        }
+
+        if byte.is_ascii_digit() {
+            let start = idx;
+            let start_col = col;
+            while idx < bytes.len() && (bytes[idx].is_ascii_alphanumeric() || bytes[idx] == b'.') {
+                idx += 1;
+                col += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Number,
+                text: source.get(start..idx).unwrap_or("").to_string(),
+                start_line: line,
+                start_col,
+                start_byte: start as i64,
+                end_byte: idx as i64,
+            });
+            continue;
+        }
+
         if is_ident_start(byte, bytes.get(idx + 1).copied()) {
             let start = idx;
             let start_line = line;
@@ -445,7 +897,10 @@ fn tokenize_proto(source: &str) -> Vec<Token> {
             continue;
         }
 
-        if matches!(ch, '{' | '}' | '(' | ')' | ';') {
+        if matches!(
+            ch,
+            '{' | '}' | '(' | ')' | ';' | '=' | '<' | '>' | ',' | '[' | ']' | '-'
+        ) {
             tokens.push(Token {
                 kind: TokenKind::Punct(ch),
                 text: ch.to_string(),
@@ -479,7 +934,7 @@ fn is_ident_continue(current: u8) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{ProtoExtractor, RPC_ROUTE_KIND};
+    use super::{ProtoExtractor, RPC_ROUTE_KIND, TYPE_REF_KIND};
     use crate::indexer::extract::LanguageExtractor;
 
     #[test]
@@ -510,4 +965,89 @@ service UserService {
             edge.target_qualname.as_deref() == Some("/example.v1.userservice/streamusers")
         }));
     }
+
+    #[test]
+    fn extracts_messages_fields_and_type_refs() {
+        let source = r#"
+syntax = "proto3";
+package example.v1;
+
+import "common/money.proto";
+
+message GetUserRequest {
+  string user_id = 1;
+}
+
+message User {
+  enum Status {
+    STATUS_UNSPECIFIED = 0;
+    ACTIVE = 1;
+  }
+  message Address { string city = 1; }
+  string name = 1 [json_name = "fullName"];
+  Status status = 2;
+  repeated Address addresses = 3;
+  map<string, common.v1.Money> balances = 4;
+  oneof contact {
+    string email = 5;
+  }
+}
+
+service UserService {
+  rpc GetUser (GetUserRequest) returns (stream User);
+}
+"#;
+        let mut extractor = ProtoExtractor::new().unwrap();
+        let file = extractor.extract(source, "users").unwrap();
+        let symbol = |qualname: &str| {
+            file.symbols
+                .iter()
+                .find(|symbol| symbol.qualname == qualname)
+                .unwrap_or_else(|| panic!("missing {qualname}"))
+        };
+        assert_eq!(symbol("example.v1.User").kind, "message");
+        assert_eq!(symbol("example.v1.User.Status").kind, "enum");
+        assert_eq!(symbol("example.v1.User.Status.ACTIVE").kind, "enum_member");
+        assert_eq!(symbol("example.v1.User.Address.city").kind, "field");
+        assert_eq!(symbol("example.v1.User.email").kind, "field");
+        assert_eq!(
+            symbol("example.v1.User.balances").signature.as_deref(),
+            Some("map<string, common.v1.Money> balances = 4")
+        );
+        assert_eq!(
+            symbol("example.v1.User.name").signature.as_deref(),
+            Some("string name = 1")
+        );
+
+        let type_refs = file
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == TYPE_REF_KIND)
+            .map(|edge| {
+                (
+                    edge.source_qualname.as_deref().unwrap(),
+                    edge.target_qualname.as_deref().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        for expected in [
+            ("example.v1.User.status", "example.v1.User.Status"),
+            ("example.v1.User.addresses", "example.v1.User.Address"),
+            ("example.v1.User.balances", "common.v1.Money"),
+            (
+                "example.v1.UserService.GetUser",
+                "example.v1.GetUserRequest",
+            ),
+            ("example.v1.UserService.GetUser", "example.v1.User"),
+        ] {
+            assert!(type_refs.contains(&expected), "missing {expected:?}");
+        }
+        assert!(
+            !type_refs
+                .iter()
+                .any(|(source, _)| *source == "example.v1.User.name")
+        );
+        assert!(file.edges.iter().any(|edge| edge.kind == "IMPORTS"
+            && edge.target_qualname.as_deref() == Some("common/money")));
+    }
 }
@@ -0,0 +1,315 @@
+//! Generated protobuf code linked back to its `.proto` declarations.
+//!
+//! protoc emits a class (struct, interface) per message and enum, with a
+//! property, accessor or constant per field. This pass adds a GENERATED_FROM
+//! edge from each generated type to the message or enum it was generated
+//! from, and from each generated member to its field or enum value, so
+//! impact analysis on a proto field reaches the code reading it in every
+//! language. Generated files are recognised by the protoc output names
+//! (`.pb.go`, `_pb2.py`, `_pb.ts`, ...) or, for C#, by an `IMessage` base.
+
+use crate::db::{CodegenSymbol, Db};
+use crate::indexer::extract::EdgeInput;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+pub const GENERATED_FROM_KIND: &str = "GENERATED_FROM";
+
+/// Confidence of a link made by name.
+const GENERATED_CONFIDENCE: f64 = 0.9;
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go", "_pb2.py", "_pb2.pyi", "_pb.d.ts", "_pb.ts", "_pb.js", ".pb.ts", ".pb.cs",
+];
+/// Accessor prefixes of generated getters and setters (`GetUserId`,
+/// `getUserId`, `hasUserId`, ...).
+const ACCESSOR_PREFIXES: &[&str] = &["get", "set", "has", "clear"];
+/// Suffixes protoc adds to member names: field-number constants and the
+/// JavaScript `getTagsList` / `getLabelsMap` accessors.
+const MEMBER_SUFFIXES: &[&str] = &["fieldnumber", "list", "map"];
+
+/// Rebuild every GENERATED_FROM edge of `graph_version`.
+pub fn link_generated_code(db: &mut Db, graph_version: i64) -> Result<usize> {
+    db.delete_edges_by_kind(GENERATED_FROM_KIND, graph_version)?;
+    let symbols = db.codegen_symbols(graph_version)?;
+    let links = plan_links(&symbols);
+    if links.is_empty() {
+        return Ok(0);
+    }
+
+    let by_id: HashMap<i64, &CodegenSymbol> = symbols.iter().map(|s| (s.id, s)).collect();
+    let mut by_file: HashMap<i64, Vec<(i64, i64)>> = HashMap::new();
+    for (generated, declared) in links {
+        by_file
+            .entry(by_id[&generated].file_id)
+            .or_default()
+            .push((generated, declared));
+    }
+    let commit_sha = db.graph_version_commit(graph_version)?;
+    let mut total = 0;
+    for (file_id, links) in by_file {
+        let mut symbol_map = HashMap::new();
+        let edges: Vec<EdgeInput> = links
+            .iter()
+            .map(|(generated, declared)| {
+                let generated = by_id[generated];
+                let declared = by_id[declared];
+                symbol_map.insert(generated.qualname.clone(), generated.id);
+                symbol_map.insert(declared.qualname.clone(), declared.id);
+                EdgeInput {
+                    kind: GENERATED_FROM_KIND.to_string(),
+                    source_qualname: Some(generated.qualname.clone()),
+                    target_qualname: Some(declared.qualname.clone()),
+                    evidence_start_line: Some(generated.start_line),
+                    evidence_end_line: Some(generated.start_line),
+                    confidence: Some(GENERATED_CONFIDENCE),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        total += db.insert_edges(
+            file_id,
+            &edges,
+            &symbol_map,
+            graph_version,
+            commit_sha.as_deref(),
+        )?;
+    }
+    Ok(total)
+}
+
+fn is_type_kind(kind: &str) -> bool {
+    matches!(
+        kind,
+        "class" | "struct" | "interface" | "type" | "record" | "enum"
+    )
+}
+
+fn is_generated_type(symbol: &CodegenSymbol) -> bool {
+    symbol.language != "proto"
+        && is_type_kind(&symbol.kind)
+        && (symbol.in_message_file
+            || GENERATED_SUFFIXES
+                .iter()
+                .any(|suffix| symbol.path.ends_with(suffix)))
+}
+
+/// Lowercase with underscores removed: `user_id`, `UserId` and `userId`
+/// compare equal.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|ch| *ch != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// File name without directories and protoc suffixes, normalized:
+/// `users/v1/user_service.proto`, `user_service.pb.go` and `UserService.cs`
+/// all give `userservice`.
+fn file_stem(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let stem = GENERATED_SUFFIXES
+        .iter()
+        .chain(&[".proto"])
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or_else(|| name.split('.').next().unwrap_or(name));
+    normalize(stem)
+}
+
+/// Direct children of `parent` among `symbols`: members declared as
+/// `parent.name` (or `parent::name`) in the same file.
+fn children<'a>(
+    parent: &CodegenSymbol,
+    symbols: &'a [CodegenSymbol],
+) -> impl Iterator<Item = &'a CodegenSymbol> {
+    let prefix = parent.qualname.clone();
+    let file_id = parent.file_id;
+    symbols.iter().filter(move |symbol| {
+        symbol.file_id == file_id
+            && symbol
+                .qualname
+                .strip_prefix(prefix.as_str())
+                .and_then(|rest| rest.strip_prefix('.').or_else(|| rest.strip_prefix("::")))
+                .is_some_and(|name| !name.is_empty() && !name.contains(['.', ':']))
+    })
+}
+
+/// Names a generated member may be matched by.
+fn member_keys(member: &CodegenSymbol) -> Vec<String> {
+    let name = normalize(&member.name);
+    let mut keys = vec![name.clone()];
+    if matches!(member.kind.as_str(), "method" | "function") {
+        for prefix in ACCESSOR_PREFIXES {
+            if let Some(rest) = name.strip_prefix(prefix).filter(|rest| !rest.is_empty()) {
+                keys.push(rest.to_string());
+            }
+        }
+    }
+    for key in keys.clone() {
+        for suffix in MEMBER_SUFFIXES {
+            if let Some(rest) = key.strip_suffix(suffix).filter(|rest| !rest.is_empty()) {
+                keys.push(rest.to_string());
+            }
+        }
+    }
+    keys
+}
+
+/// Names a proto field or enum value is generated under. Enum values also
+/// match without the enum name prefix (`STATUS_ACTIVE` → C# `Active`).
+fn declared_keys(member: &CodegenSymbol, owner: &CodegenSymbol) -> Vec<String> {
+    let name = normalize(&member.name);
+    let mut keys = vec![name.clone()];
+    if member.kind == "enum_member"
+        && let Some(rest) = name
+            .strip_prefix(&normalize(&owner.name))
+            .filter(|rest| !rest.is_empty())
+    {
+        keys.push(rest.to_string());
+    }
+    keys
+}
+
+/// Pairs of (generated symbol, proto symbol) to link.
+fn plan_links(symbols: &[CodegenSymbol]) -> Vec<(i64, i64)> {
+    let declared: Vec<&CodegenSymbol> = symbols
+        .iter()
+        .filter(|s| s.language == "proto" && matches!(s.kind.as_str(), "message" | "enum"))
+        .collect();
+    let declared_qualnames: HashSet<&str> = declared.iter().map(|s| s.qualname.as_str()).collect();
+    // Generated names of nested messages join the outer names with `_`
+    // (Go, TypeScript); C# and Python nest the class and keep the name.
+    let mut by_name: HashMap<String, Vec<(&CodegenSymbol, bool)>> = HashMap::new();
+    for ty in &declared {
+        let mut flat = ty.name.clone();
+        let mut outer = ty.qualname.as_str();
+        while let Some((parent, _)) = outer.rsplit_once('.')
+            && declared_qualnames.contains(parent)
+        {
+            let parent_name = parent.rsplit('.').next().unwrap_or(parent);
+            flat = format!("{parent_name}_{flat}");
+            outer = parent;
+        }
+        if flat != ty.name {
+            by_name.entry(flat).or_default().push((ty, true));
+        }
+        by_name
+            .entry(ty.name.clone())
+            .or_default()
+            .push((ty, false));
+    }
+
+    let mut links = Vec::new();
+    for generated in symbols.iter().filter(|s| is_generated_type(s)) {
+        let Some(candidates) = by_name.get(&generated.name) else {
+            continue;
+        };
+        let mut candidates: Vec<(&CodegenSymbol, bool)> = candidates.clone();
+        if candidates.len() > 1 {
+            let stem = file_stem(&generated.path);
+            let same_file: Vec<_> = candidates
+                .iter()
+                .copied()
+                .filter(|(ty, _)| file_stem(&ty.path) == stem)
+                .collect();
+            if !same_file.is_empty() {
+                candidates = same_file;
+            }
+        }
+        if candidates.len() > 1 {
+            candidates.retain(|(_, flat)| *flat);
+        }
+        let [(declared, _)] = candidates.as_slice() else {
+            continue;
+        };
+        links.push((generated.id, declared.id));
+
+        let mut fields: HashMap<String, i64> = HashMap::new();
+        for member in children(declared, symbols) {
+            for key in declared_keys(member, declared) {
+                fields.entry(key).or_insert(member.id);
+            }
+        }
+        for member in children(generated, symbols) {
+            if let Some(field) = member_keys(member).iter().find_map(|key| fields.get(key)) {
+                links.push((member.id, *field));
+            }
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(id: i64, kind: &str, qualname: &str, path: &str) -> CodegenSymbol {
+        CodegenSymbol {
+            id,
+            kind: kind.to_string(),
+            name: qualname.rsplit('.').next().unwrap().to_string(),
+            qualname: qualname.to_string(),
+            file_id: id / 100,
+            path: path.to_string(),
+            language: if path.ends_with(".proto") {
+                "proto".to_string()
+            } else {
+                "other".to_string()
+            },
+            start_line: 1,
+            in_message_file: false,
+        }
+    }
+
+    #[test]
+    fn links_generated_types_and_members_to_proto() {
+        let mut symbols = vec![
+            symbol(100, "message", "acme.v1.User", "proto/user.proto"),
+            symbol(101, "field", "acme.v1.User.user_id", "proto/user.proto"),
+            symbol(102, "message", "acme.v1.User.Address", "proto/user.proto"),
+            symbol(103, "enum", "acme.v1.User.Status", "proto/user.proto"),
+            symbol(
+                104,
+                "enum_member",
+                "acme.v1.User.Status.STATUS_ACTIVE",
+                "proto/user.proto",
+            ),
+            symbol(200, "class", "pb/user.pb.User", "pb/user.pb.go"),
+            symbol(201, "method", "pb/user.pb.User.GetUserId", "pb/user.pb.go"),
+            symbol(202, "class", "pb/user.pb.User_Address", "pb/user.pb.go"),
+            symbol(300, "class", "Acme.V1.User", "src/User.cs"),
+            symbol(301, "property", "Acme.V1.User.UserId", "src/User.cs"),
+            symbol(
+                302,
+                "field",
+                "Acme.V1.User.UserIdFieldNumber",
+                "src/User.cs",
+            ),
+            symbol(303, "enum", "Acme.V1.User.Types.Status", "src/User.cs"),
+            symbol(
+                304,
+                "enum_member",
+                "Acme.V1.User.Types.Status.Active",
+                "src/User.cs",
+            ),
+            // Same name, not generated.
+            symbol(400, "class", "app.models.User", "app/models.py"),
+        ];
+        for symbol in symbols.iter_mut().filter(|s| s.path == "src/User.cs") {
+            symbol.in_message_file = true;
+        }
+        let links = plan_links(&symbols);
+        for expected in [
+            (200, 100),
+            (201, 101),
+            (202, 102),
+            (300, 100),
+            (301, 101),
+            (302, 101),
+            (303, 103),
+            (304, 104),
+        ] {
+            assert!(links.contains(&expected), "missing {expected:?}");
+        }
+        assert!(!links.iter().any(|(generated, _)| *generated == 400));
+    }
+}
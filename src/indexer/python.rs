@@ -3,6 +3,7 @@ use crate::indexer::config;
 use crate::indexer::extract::{EdgeInput, ExtractedFile, SymbolInput};
 use crate::indexer::http;
 use crate::indexer::proto;
+use crate::indexer::rules;
 use crate::indexer::tree_helpers::{
     module_symbol_fallback, module_symbol_with_span, node_text, span,
 };
@@ -71,6 +72,7 @@ impl crate::indexer::extract::LanguageExtractor for PythonExtractor {
             grpc_service: None,
         };
         walk_node(root, &ctx, source, &mut output);
+        output.tree = Some(tree);
         Ok(output)
     }
 
@@ -1159,6 +1161,9 @@ fn http_client_label(base: &str) -> Option<&'static str> {
     if base.ends_with("session") || base.ends_with("client") {
         return Some("http_client");
     }
+    if rules::active_http_client(base) {
+        return Some("custom");
+    }
     None
 }
 
@@ -0,0 +1,356 @@
+//! User-defined edges declared as tree-sitter queries in `.lidx.toml`.
+//!
+//! ```toml
+//! [[edges]]
+//! id = "feature-flag"
+//! languages = ["csharp"]
+//! kind = "FEATURE_FLAG_READ"
+//! target = "flag://{flag}"
+//! query = '''
+//! (invocation_expression
+//!   function: (member_access_expression
+//!     expression: (identifier) @_class (#eq? @_class "FeatureFlags")
+//!     name: (identifier) @_method (#eq? @_method "IsEnabled"))
+//!   arguments: (argument_list (argument (string_literal) @flag)))
+//! '''
+//! ```
+//!
+//! Every match becomes an edge of `kind` from the innermost symbol enclosing
+//! the match to `target`, with `{capture}` placeholders replaced by the
+//! captured text (string quotes removed). The rule id and the captures not
+//! prefixed with `_` are recorded in `detail`.
+
+use crate::indexer::extract::{EdgeInput, SymbolInput};
+use crate::indexer::rules;
+use crate::util;
+use anyhow::{Result, anyhow, bail};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::collections::{HashMap, HashSet};
+use tree_sitter::{Language, Parser, Query, QueryCursor, StreamingIterator};
+
+/// Key under which the id of the producing rule is stored in `detail`.
+pub const RULE_ID_KEY: &str = "rule";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EdgeRule {
+    pub id: String,
+    pub languages: Vec<String>,
+    pub kind: String,
+    pub query: String,
+    pub target: String,
+    pub confidence: Option<f64>,
+}
+
+struct CompiledRule {
+    id: String,
+    kind: String,
+    target: String,
+    confidence: Option<f64>,
+    query: Query,
+}
+
+/// Edge rules compiled per language.
+#[derive(Default)]
+pub struct QueryRules {
+    by_language: HashMap<String, Vec<CompiledRule>>,
+}
+
+impl std::fmt::Debug for QueryRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut ids: Vec<(&str, &str)> = self
+            .by_language
+            .iter()
+            .flat_map(|(language, rules)| {
+                rules
+                    .iter()
+                    .map(move |rule| (language.as_str(), rule.id.as_str()))
+            })
+            .collect();
+        ids.sort();
+        f.debug_struct("QueryRules").field("rules", &ids).finish()
+    }
+}
+
+fn grammar(language: &str) -> Option<Language> {
+    let grammar = match language {
+        "csharp" => tree_sitter_c_sharp::LANGUAGE,
+        "python" => tree_sitter_python::LANGUAGE,
+        "typescript" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX,
+        "javascript" => tree_sitter_javascript::LANGUAGE,
+        "go" => tree_sitter_go::LANGUAGE,
+        "java" => tree_sitter_java::LANGUAGE,
+        "kotlin" => tree_sitter_kotlin_ng::LANGUAGE,
+        "rust" => tree_sitter_rust::LANGUAGE,
+        _ => return None,
+    };
+    Some(grammar.into())
+}
+
+/// `{name}` placeholders of a target template.
+fn placeholders(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        names.push(&rest[open + 1..open + close]);
+        rest = &rest[open + close + 1..];
+    }
+    names
+}
+
+impl QueryRules {
+    pub fn compile(rules: &[EdgeRule]) -> Result<Self> {
+        let mut by_language: HashMap<String, Vec<CompiledRule>> = HashMap::new();
+        let mut ids = HashSet::new();
+        for rule in rules {
+            if rule.id.is_empty() {
+                bail!("edge rule without an `id`");
+            }
+            if !ids.insert(rule.id.as_str()) {
+                bail!("duplicate edge rule id `{}`", rule.id);
+            }
+            if rule.kind.is_empty() || rule.target.is_empty() || rule.query.is_empty() {
+                bail!("edge rule `{}` needs `kind`, `target` and `query`", rule.id);
+            }
+            if rule.languages.is_empty() {
+                bail!("edge rule `{}` needs `languages`", rule.id);
+            }
+            for language in &rule.languages {
+                let grammar = grammar(language).ok_or_else(|| {
+                    anyhow!(
+                        "edge rule `{}`: no query support for language `{language}`",
+                        rule.id
+                    )
+                })?;
+                let query = Query::new(&grammar, &rule.query)
+                    .map_err(|err| anyhow!("edge rule `{}` ({language}): {err}", rule.id))?;
+                for name in placeholders(&rule.target) {
+                    if query.capture_index_for_name(name).is_none() {
+                        bail!(
+                            "edge rule `{}`: target uses `{{{name}}}` but the query has no @{name} capture",
+                            rule.id
+                        );
+                    }
+                }
+                by_language
+                    .entry(language.clone())
+                    .or_default()
+                    .push(CompiledRule {
+                        id: rule.id.clone(),
+                        kind: rule.kind.clone(),
+                        target: rule.target.clone(),
+                        confidence: rule.confidence,
+                        query,
+                    });
+            }
+        }
+        Ok(Self { by_language })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_language.is_empty()
+    }
+
+    /// Edges produced by the rules for `language` on one file.
+    pub fn edges(&self, language: &str, source: &str, symbols: &[SymbolInput]) -> Vec<EdgeInput> {
+        let Some(compiled) = self.by_language.get(language) else {
+            return Vec::new();
+        };
+        let Some(grammar) = grammar(language) else {
+            return Vec::new();
+        };
+        let mut parser = Parser::new();
+        if parser.set_language(&grammar).is_err() {
+            return Vec::new();
+        }
+        let Some(tree) = parser.parse(source, None) else {
+            return Vec::new();
+        };
+        let mut edges = Vec::new();
+        let mut seen = HashSet::new();
+        for rule in compiled {
+            let names = rule.query.capture_names();
+            let mut cursor = QueryCursor::new();
+            let mut matches = cursor.matches(&rule.query, tree.root_node(), source.as_bytes());
+            while let Some(found) = matches.next() {
+                let mut captures: HashMap<&str, String> = HashMap::new();
+                let mut start = usize::MAX;
+                let mut end = 0;
+                let mut start_line = i64::MAX;
+                let mut end_line = 0;
+                for capture in found.captures {
+                    let node = capture.node;
+                    start = start.min(node.start_byte());
+                    end = end.max(node.end_byte());
+                    start_line = start_line.min(node.start_position().row as i64 + 1);
+                    end_line = end_line.max(node.end_position().row as i64 + 1);
+                    let text = &source[node.start_byte()..node.end_byte()];
+                    captures
+                        .entry(names[capture.index as usize])
+                        .or_insert_with(|| unquote(text));
+                }
+                if captures.is_empty() {
+                    continue;
+                }
+                let Some(target) = render_target(&rule.target, &captures) else {
+                    continue;
+                };
+                let Some(source_qualname) = rules::enclosing_symbol(symbols, start_line) else {
+                    continue;
+                };
+                if !seen.insert((rule.id.as_str(), start, target.clone())) {
+                    continue;
+                }
+                let mut detail = Map::new();
+                detail.insert(RULE_ID_KEY.to_string(), json!(rule.id));
+                let mut public: Vec<(&&str, &String)> = captures
+                    .iter()
+                    .filter(|(name, _)| !name.starts_with('_'))
+                    .collect();
+                public.sort();
+                if !public.is_empty() {
+                    let values: Map<String, Value> = public
+                        .into_iter()
+                        .map(|(name, text)| (name.to_string(), json!(text)))
+                        .collect();
+                    detail.insert("captures".to_string(), Value::Object(values));
+                }
+                edges.push(EdgeInput {
+                    kind: rule.kind.clone(),
+                    source_qualname: Some(source_qualname.to_string()),
+                    target_qualname: Some(target),
+                    detail: Some(Value::Object(detail).to_string()),
+                    evidence_snippet: util::edge_evidence_snippet(
+                        source,
+                        start as i64,
+                        end as i64,
+                        start_line,
+                        end_line,
+                    ),
+                    evidence_start_line: Some(start_line),
+                    evidence_end_line: Some(end_line),
+                    confidence: rule.confidence,
+                    ..Default::default()
+                });
+            }
+        }
+        edges
+    }
+}
+
+/// The contents of a captured string literal, or the text as is.
+fn unquote(text: &str) -> String {
+    rules::string_literal(text).unwrap_or_else(|| text.trim().to_string())
+}
+
+fn render_target(template: &str, captures: &HashMap<&str, String>) -> Option<String> {
+    let mut out = template.to_string();
+    for name in placeholders(template) {
+        let value = captures.get(name)?;
+        out = out.replace(&format!("{{{name}}}"), value);
+    }
+    if out.trim().is_empty() {
+        return None;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(qualname: &str, start_line: i64, end_line: i64) -> SymbolInput {
+        SymbolInput {
+            kind: "method".to_string(),
+            name: qualname.rsplit('.').next().unwrap().to_string(),
+            qualname: qualname.to_string(),
+            start_line,
+            start_col: 0,
+            end_line,
+            end_col: 0,
+            start_byte: 0,
+            end_byte: 0,
+            signature: None,
+            docstring: None,
+        }
+    }
+
+    fn rule(language: &str, query: &str, target: &str) -> EdgeRule {
+        EdgeRule {
+            id: "test".to_string(),
+            languages: vec![language.to_string()],
+            kind: "CUSTOM".to_string(),
+            query: query.to_string(),
+            target: target.to_string(),
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn matches_emit_edges_with_rendered_targets() {
+        let rules = QueryRules::compile(&[rule(
+            "python",
+            r#"(call function: (attribute attribute: (identifier) @_fn (#eq? @_fn "is_enabled"))
+                    arguments: (argument_list (string) @flag))"#,
+            "flag://{flag}",
+        )])
+        .unwrap();
+        let source = "def run():\n    if flags.is_enabled(\"new-checkout\"):\n        pass\n    flags.other(\"x\")\n";
+        let edges = rules.edges("python", source, &[symbol("app.run", 1, 4)]);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].source_qualname.as_deref(), Some("app.run"));
+        assert_eq!(
+            edges[0].target_qualname.as_deref(),
+            Some("flag://new-checkout")
+        );
+        let detail: Value = serde_json::from_str(edges[0].detail.as_deref().unwrap()).unwrap();
+        assert_eq!(detail["rule"], "test");
+        assert_eq!(detail["captures"]["flag"], "new-checkout");
+        assert!(detail["captures"].get("_fn").is_none());
+        assert!(rules.edges("go", source, &[]).is_empty());
+    }
+
+    #[test]
+    fn jvm_rules_match_java_and_kotlin() {
+        let java_rules = QueryRules::compile(&[rule(
+            "java",
+            r#"(method_invocation name: (identifier) @_fn (#eq? @_fn "isEnabled")
+                arguments: (argument_list (string_literal) @flag))"#,
+            "flag://{flag}",
+        )])
+        .unwrap();
+        let java = "class A {\n  void run() {\n    if (isEnabled(\"beta\")) {}\n  }\n}\n";
+        let edges = java_rules.edges("java", java, &[symbol("A.run", 2, 4)]);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].target_qualname.as_deref(), Some("flag://beta"));
+
+        let kotlin_rules = QueryRules::compile(&[rule(
+            "kotlin",
+            r#"(call_expression (identifier) @_fn (#eq? @_fn "isEnabled")
+                (value_arguments (value_argument (string_literal) @flag)))"#,
+            "flag://{flag}",
+        )])
+        .unwrap();
+        let kotlin = "fun run() {\n    if (isEnabled(\"beta\")) {}\n}\n";
+        let edges = kotlin_rules.edges("kotlin", kotlin, &[symbol("app.run", 1, 3)]);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].source_qualname.as_deref(), Some("app.run"));
+        assert_eq!(edges[0].target_qualname.as_deref(), Some("flag://beta"));
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let err = QueryRules::compile(&[rule("python", "(call", "x")]).unwrap_err();
+        assert!(err.to_string().contains("edge rule `test`"), "{err}");
+        let err =
+            QueryRules::compile(&[rule("python", "(call) @call", "flag://{name}")]).unwrap_err();
+        assert!(err.to_string().contains("@name"), "{err}");
+        let err = QueryRules::compile(&[rule("lua", "(call) @call", "x")]).unwrap_err();
+        assert!(err.to_string().contains("lua"), "{err}");
+    }
+}
@@ -0,0 +1,336 @@
+//! Reading a git revision straight from the object database.
+//!
+//! `index_revision` lists the revision's tree with `git ls-tree` and reads
+//! blobs through one long-lived `git cat-file --batch`, so the working tree
+//! and the checked-out branch are never touched.
+
+use crate::indexer::scan::{self, ScanOptions, ScannedFile};
+use anyhow::{Context, Result, anyhow, bail};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// Full sha of the commit `rev` (a branch, tag, sha or any rev-parse
+/// expression) points at.
+pub fn resolve_commit(repo_root: &Path, rev: &str) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_root)
+        .args(["rev-parse", "--verify", "--quiet", "--end-of-options"])
+        .arg(format!("{rev}^{{commit}}"))
+        .output()
+        .context("run git rev-parse")?;
+    let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || sha.is_empty() {
+        bail!("unknown revision: {rev}");
+    }
+    Ok(sha)
+}
+
+/// Contents of `path` at `commit`, or `None` when the revision lacks it.
+pub fn read_path(repo_root: &Path, commit: &str, path: &str) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_root)
+        .args(["cat-file", "blob"])
+        .arg(format!("{commit}:{path}"))
+        .stderr(Stdio::null())
+        .output()
+        .context("run git cat-file")?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8(output.stdout)?))
+}
+
+/// Unified diff (no context lines) from `rev` to the working tree.
+pub fn diff_since(repo_root: &Path, rev: &str) -> Result<String> {
+    let commit = resolve_commit(repo_root, rev)?;
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_root)
+        .args(["diff", "--no-color", "--no-ext-diff", "-U0", &commit, "--"])
+        .output()
+        .context("run git diff")?;
+    if !output.status.success() {
+        bail!(
+            "git diff {rev} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Indexable files of `commit`, sorted by path like a working-tree scan and
+/// filtered the same way: `.gitignore` and `.ignore` files of the revision
+/// (plus the repository's exclude file and the global gitignore) apply
+/// unless `options.no_ignore`, and files over the size limit are skipped.
+/// `hash` holds the git blob id, and `abs_path` is where the file would be
+/// in the working tree; it is never read.
+pub fn scan_revision(
+    repo_root: &Path,
+    commit: &str,
+    options: ScanOptions,
+    reader: &mut BlobReader,
+) -> Result<Vec<ScannedFile>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_root)
+        .args(["ls-tree", "-r", "-z", "--long", "--full-tree", commit])
+        .output()
+        .context("run git ls-tree")?;
+    if !output.status.success() {
+        bail!(
+            "git ls-tree {commit} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let entries: Vec<TreeEntry> = output
+        .stdout
+        .split(|byte| *byte == 0)
+        .filter_map(|entry| parse_tree_entry(&String::from_utf8_lossy(entry)))
+        .filter(|entry| {
+            !entry
+                .path
+                .split('/')
+                .any(|segment| segment == ".lidx" || segment == ".git")
+        })
+        .collect();
+    let ignores = if options.no_ignore {
+        IgnoreRules::default()
+    } else {
+        IgnoreRules::from_tree(repo_root, &entries, reader)?
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let Some(language) = scan::language_for_path(Path::new(&entry.path)) else {
+            continue;
+        };
+        if ignores.is_ignored(repo_root, &entry.path) {
+            continue;
+        }
+        if entry.size as u64 > scan::MAX_FILE_SIZE_MB * 1024 * 1024 {
+            eprintln!(
+                "lidx: Skipping large file ({}MB): {}",
+                entry.size / (1024 * 1024),
+                entry.path
+            );
+            continue;
+        }
+        files.push(ScannedFile {
+            abs_path: repo_root.join(&entry.path),
+            rel_path: entry.path,
+            hash: entry.blob,
+            size: entry.size,
+            modified: 0,
+            language: language.to_string(),
+        });
+    }
+    files.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    Ok(files)
+}
+
+/// Ignore files in effect for a revision, most specific first: deeper
+/// directories before their parents and, within a directory, `.ignore`
+/// before `.gitignore`, as in a working-tree walk.
+#[derive(Default)]
+struct IgnoreRules {
+    dirs: Vec<(String, Gitignore)>,
+    fallback: Vec<Gitignore>,
+}
+
+impl IgnoreRules {
+    fn from_tree(repo_root: &Path, entries: &[TreeEntry], reader: &mut BlobReader) -> Result<Self> {
+        let mut dirs = Vec::new();
+        for entry in entries {
+            let (dir, name) = match entry.path.rsplit_once('/') {
+                Some((dir, name)) => (dir, name),
+                None => ("", entry.path.as_str()),
+            };
+            if name != ".gitignore" && name != ".ignore" {
+                continue;
+            }
+            let text = String::from_utf8_lossy(&reader.read(&entry.blob)?).into_owned();
+            let mut builder = GitignoreBuilder::new(repo_root.join(dir));
+            for line in text.lines() {
+                if let Err(err) = builder.add_line(None, line) {
+                    eprintln!("lidx: {}: {err}", entry.path);
+                }
+            }
+            dirs.push((dir.to_string(), name == ".ignore", builder.build()?));
+        }
+        dirs.sort_by(|a, b| {
+            let depth = |dir: &str| {
+                if dir.is_empty() {
+                    0
+                } else {
+                    dir.split('/').count()
+                }
+            };
+            depth(&b.0).cmp(&depth(&a.0)).then(b.1.cmp(&a.1))
+        });
+
+        let mut fallback = Vec::new();
+        let exclude = repo_root.join(".git").join("info").join("exclude");
+        if exclude.is_file() {
+            let mut builder = GitignoreBuilder::new(repo_root);
+            if builder.add(&exclude).is_none() {
+                fallback.push(builder.build()?);
+            }
+        }
+        let (global, _) = GitignoreBuilder::new(repo_root).build_global();
+        fallback.push(global);
+        Ok(Self {
+            dirs: dirs
+                .into_iter()
+                .map(|(dir, _, matcher)| (dir, matcher))
+                .collect(),
+            fallback,
+        })
+    }
+
+    fn is_ignored(&self, repo_root: &Path, path: &str) -> bool {
+        let abs = repo_root.join(path);
+        let applicable = self
+            .dirs
+            .iter()
+            .filter(|(dir, _)| {
+                dir.is_empty()
+                    || path
+                        .strip_prefix(dir.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .map(|(_, matcher)| matcher)
+            .chain(&self.fallback);
+        for matcher in applicable {
+            let matched = matcher.matched_path_or_any_parents(&abs, false);
+            if matched.is_ignore() {
+                return true;
+            }
+            if matched.is_whitelist() {
+                return false;
+            }
+        }
+        false
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct TreeEntry {
+    path: String,
+    blob: String,
+    size: i64,
+}
+
+/// `<mode> blob <oid> <size>\t<path>`; symlinks and submodules are skipped.
+fn parse_tree_entry(entry: &str) -> Option<TreeEntry> {
+    let (meta, path) = entry.split_once('\t')?;
+    let mut fields = meta.split_whitespace();
+    let mode = fields.next()?;
+    let kind = fields.next()?;
+    let blob = fields.next()?;
+    let size = fields.next()?.parse().ok()?;
+    if kind != "blob" || !matches!(mode, "100644" | "100755") {
+        return None;
+    }
+    Some(TreeEntry {
+        path: path.to_string(),
+        blob: blob.to_string(),
+        size,
+    })
+}
+
+/// A `git cat-file --batch` process answering blob reads in order.
+pub struct BlobReader {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl BlobReader {
+    pub fn new(repo_root: &Path) -> Result<Self> {
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(repo_root)
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("run git cat-file")?;
+        let stdin = child.stdin.take();
+        let stdout = child
+            .stdout
+            .take()
+            .map(BufReader::new)
+            .ok_or_else(|| anyhow!("git cat-file has no stdout"))?;
+        Ok(Self {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    pub fn read(&mut self, blob: &str) -> Result<Vec<u8>> {
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| anyhow!("git cat-file is closed"))?;
+        writeln!(stdin, "{blob}")?;
+        stdin.flush()?;
+        let mut header = String::new();
+        self.stdout.read_line(&mut header)?;
+        // `<oid> <type> <size>`, or `<oid> missing`.
+        let size: usize = match header.split_whitespace().collect::<Vec<_>>()[..] {
+            [_, "blob", size] => size.parse()?,
+            _ => bail!("git cat-file {blob}: {}", header.trim()),
+        };
+        let mut data = vec![0; size + 1];
+        self.stdout.read_exact(&mut data)?;
+        data.pop();
+        Ok(data)
+    }
+
+    pub fn read_to_string(&mut self, file: &ScannedFile) -> Result<String> {
+        let data = self
+            .read(&file.hash)
+            .map_err(|err| anyhow!("read error {}: {err}", file.rel_path))?;
+        String::from_utf8(data).map_err(|err| anyhow!("read error {}: {err}", file.rel_path))
+    }
+}
+
+impl Drop for BlobReader {
+    fn drop(&mut self) {
+        // Closing stdin ends the batch.
+        self.stdin.take();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_blob_entries_only() {
+        assert_eq!(
+            parse_tree_entry(
+                "100644 blob 3b18e512dba79e4c8300dd08aeb37f8e728b8dad      12\tsrc/a b.py"
+            ),
+            Some(TreeEntry {
+                path: "src/a b.py".to_string(),
+                blob: "3b18e512dba79e4c8300dd08aeb37f8e728b8dad".to_string(),
+                size: 12,
+            })
+        );
+        assert_eq!(
+            parse_tree_entry("120000 blob 3b18e512dba79e4c8300dd08aeb37f8e728b8dad 7\tlink.py"),
+            None
+        );
+        assert_eq!(
+            parse_tree_entry("160000 commit 3b18e512dba79e4c8300dd08aeb37f8e728b8dad -\tvendor"),
+            None
+        );
+    }
+}
@@ -0,0 +1,744 @@
+//! Project-level extraction rules read from `.lidx.toml` at the repo root.
+//!
+//! The built-in bus, HTTP and RPC conventions cover common frameworks; the
+//! rules file extends them for in-house wrappers:
+//!
+//! ```toml
+//! [[channels]]
+//! languages = ["csharp"]          # optional, defaults to every language
+//! receivers = ["_outbox"]
+//! publish_methods = ["Enqueue"]
+//! subscribe_methods = ["Consume"]
+//! topic_containers = ["Events"]
+//!
+//! [topics]
+//! strip_prefixes = ["prod."]
+//! aliases = { "legacy-orders" = "orders" }
+//!
+//! [http]
+//! base_paths = ["/api/v1"]
+//!
+//! [[http.clients]]                # objects whose GetAsync/post/... are HTTP calls
+//! receivers = ["_gateway"]
+//!
+//! [[http.calls]]                  # helper functions: fetch_json("/orders")
+//! function = "fetch_json"
+//! method = "GET"
+//!
+//! [[http.routes]]                 # route helpers: register("/orders", handler)
+//! function = "register"
+//!
+//! [[rpc.calls]]                   # RPC helpers: _rpc.Invoke("orders.Orders/Get")
+//! receiver = "_rpc"
+//! function = "Invoke"
+//!
+//! [api]                           # breaking changes accepted by `api_changes`
+//! approved = ["crate::legacy::*", "crate::Client::connect"]
+//! ```
+//!
+//! `[[edges]]` entries declare further edges as tree-sitter queries; see
+//! `indexer::query_rules`.
+//!
+//! Extractors consult the rules through [`scoped`], which activates them for
+//! the file being extracted on the current thread.
+
+use crate::indexer::extract::{EdgeInput, SymbolInput};
+use crate::indexer::query_rules::{EdgeRule, QueryRules};
+use crate::indexer::type_resolve::language_family;
+use crate::indexer::{http, proto};
+use crate::util;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+pub const RULES_FILE: &str = ".lidx.toml";
+
+/// Confidence of edges produced by configured helper calls.
+const HELPER_CONFIDENCE: f64 = 0.9;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectRules {
+    pub channels: Vec<ChannelRule>,
+    pub topics: TopicRules,
+    pub http: HttpRules,
+    pub rpc: RpcRules,
+    pub api: ApiRules,
+    pub edges: Vec<EdgeRule>,
+    #[serde(skip)]
+    queries: Arc<QueryRules>,
+}
+
+/// Additional bus conventions, merged with the built-in lists in
+/// `indexer::channel`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChannelRule {
+    pub languages: Vec<String>,
+    pub receivers: Vec<String>,
+    pub publish_methods: Vec<String>,
+    pub subscribe_methods: Vec<String>,
+    pub topic_containers: Vec<String>,
+}
+
+/// Rewrites applied to topic names before they become `channel://` keys.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TopicRules {
+    pub strip_prefixes: Vec<String>,
+    pub strip_suffixes: Vec<String>,
+    pub aliases: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpRules {
+    /// Path prefixes removed from routes and calls so that `/api/v1/orders`
+    /// matches a route declared as `/orders`.
+    pub base_paths: Vec<String>,
+    pub clients: Vec<ClientRule>,
+    pub calls: Vec<CallRule>,
+    pub routes: Vec<CallRule>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RpcRules {
+    pub calls: Vec<CallRule>,
+}
+
+/// Public API changes that `api_changes` reports as approved.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiRules {
+    /// Qualnames whose breaking changes are accepted; a trailing `*`
+    /// matches every qualname with that prefix.
+    pub approved: Vec<String>,
+}
+
+/// Receivers whose verb-named methods (`GetAsync`, `post`, ...) are HTTP calls.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientRule {
+    pub languages: Vec<String>,
+    pub receivers: Vec<String>,
+}
+
+/// A helper function whose string argument is a route, URL or RPC path.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CallRule {
+    pub languages: Vec<String>,
+    pub function: String,
+    /// Last segment of the receiver expression; any receiver when unset.
+    pub receiver: Option<String>,
+    /// HTTP method of the call or route; `ANY` when unset.
+    pub method: Option<String>,
+    /// Zero-based index of the path argument.
+    pub arg: usize,
+}
+
+fn applies(languages: &[String], language: &str) -> bool {
+    languages.is_empty()
+        || languages
+            .iter()
+            .any(|entry| entry == language || entry == language_family(language))
+}
+
+impl ProjectRules {
+    /// Load `.lidx.toml` from `repo_root`; a missing file yields no rules.
+    pub fn load(repo_root: &Path) -> Result<Self> {
+        let path = repo_root.join(RULES_FILE);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let text =
+            std::fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("parse {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rules: Self = toml::from_str(text)?;
+        rules.queries = Arc::new(QueryRules::compile(&rules.edges)?);
+        Ok(rules)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+            && self.topics.strip_prefixes.is_empty()
+            && self.topics.strip_suffixes.is_empty()
+            && self.topics.aliases.is_empty()
+            && self.http.base_paths.is_empty()
+            && self.http.clients.is_empty()
+            && self.http.calls.is_empty()
+            && self.http.routes.is_empty()
+            && self.rpc.calls.is_empty()
+            && self.api.approved.is_empty()
+            && self.edges.is_empty()
+    }
+
+    /// Edge kinds declared by `[[edges]]` rules.
+    pub fn edge_kinds(&self) -> Vec<String> {
+        let mut kinds: Vec<String> = self.edges.iter().map(|rule| rule.kind.clone()).collect();
+        kinds.sort();
+        kinds.dedup();
+        kinds
+    }
+
+    /// Edges produced by the `[[edges]]` query rules for one file.
+    pub fn query_edges(
+        &self,
+        language: &str,
+        source: &str,
+        symbols: &[SymbolInput],
+    ) -> Vec<EdgeInput> {
+        self.queries.edges(language, source, symbols)
+    }
+
+    fn channel_rules<'a>(&'a self, language: &'a str) -> impl Iterator<Item = &'a ChannelRule> {
+        self.channels
+            .iter()
+            .filter(move |rule| applies(&rule.languages, language))
+    }
+
+    pub fn is_bus_receiver(&self, language: &str, receiver: &str) -> bool {
+        self.channel_rules(language)
+            .any(|rule| rule.receivers.iter().any(|value| value == receiver))
+    }
+
+    pub fn is_publish_method(&self, language: &str, name: &str) -> bool {
+        self.channel_rules(language)
+            .any(|rule| rule.publish_methods.iter().any(|value| value == name))
+    }
+
+    pub fn is_subscribe_method(&self, language: &str, name: &str) -> bool {
+        self.channel_rules(language)
+            .any(|rule| rule.subscribe_methods.iter().any(|value| value == name))
+    }
+
+    pub fn is_topic_container(&self, language: &str, name: &str) -> bool {
+        self.channel_rules(language)
+            .any(|rule| rule.topic_containers.iter().any(|value| value == name))
+    }
+
+    pub fn is_http_client(&self, language: &str, receiver: &str) -> bool {
+        self.http
+            .clients
+            .iter()
+            .filter(|rule| applies(&rule.languages, language))
+            .any(|rule| rule.receivers.iter().any(|value| value == receiver))
+    }
+
+    /// Apply the configured prefix/suffix stripping to a raw topic name.
+    /// Quotes around a stripped literal are dropped.
+    pub fn strip_topic<'a>(&self, topic: &'a str) -> &'a str {
+        let unquoted = topic.trim_matches(['"', '\'', '`']);
+        let mut stripped = unquoted;
+        if let Some(rest) = self
+            .topics
+            .strip_prefixes
+            .iter()
+            .find_map(|prefix| stripped.strip_prefix(prefix.as_str()))
+            .filter(|rest| !rest.is_empty())
+        {
+            stripped = rest;
+        }
+        if let Some(rest) = self
+            .topics
+            .strip_suffixes
+            .iter()
+            .find_map(|suffix| stripped.strip_suffix(suffix.as_str()))
+            .filter(|rest| !rest.is_empty())
+        {
+            stripped = rest;
+        }
+        if stripped.len() == unquoted.len() {
+            topic
+        } else {
+            stripped
+        }
+    }
+
+    /// The canonical topic key an already-normalized key is an alias of.
+    pub fn topic_alias(&self, normalized: &str) -> Option<String> {
+        self.topics.aliases.iter().find_map(|(alias, target)| {
+            (normalize_topic_key(alias) == normalized).then(|| normalize_topic_key(target))
+        })
+    }
+
+    /// Remove a configured base path from a normalized route.
+    pub fn strip_base_path(&self, route: String) -> String {
+        for base in &self.http.base_paths {
+            let Some(base) = normalize_base_path(base) else {
+                continue;
+            };
+            if let Some(rest) = route.strip_prefix(&base)
+                && rest.starts_with('/')
+            {
+                return rest.to_string();
+            }
+        }
+        route
+    }
+
+    /// HTTP_CALL / HTTP_ROUTE / RPC_CALL edges for calls to the configured
+    /// helper functions in `source`. Each edge is attributed to the innermost
+    /// symbol enclosing the call.
+    pub fn helper_edges(
+        &self,
+        language: &str,
+        source: &str,
+        symbols: &[SymbolInput],
+    ) -> Vec<EdgeInput> {
+        let mut edges = Vec::new();
+        let groups: [(&[CallRule], HelperKind); 3] = [
+            (&self.http.calls, HelperKind::HttpCall),
+            (&self.http.routes, HelperKind::HttpRoute),
+            (&self.rpc.calls, HelperKind::RpcCall),
+        ];
+        for (rules, kind) in groups {
+            for rule in rules {
+                if rule.function.is_empty() || !applies(&rule.languages, language) {
+                    continue;
+                }
+                for call in find_calls(source, rule) {
+                    let Some(source_qualname) = enclosing_symbol(symbols, call.line) else {
+                        continue;
+                    };
+                    if let Some(edge) = helper_edge(kind, rule, &call, source, source_qualname) {
+                        edges.push(edge);
+                    }
+                }
+            }
+        }
+        edges
+    }
+}
+
+fn normalize_topic_key(raw: &str) -> String {
+    raw.trim()
+        .chars()
+        .filter(|ch| *ch != '_')
+        .flat_map(|ch| ch.to_lowercase())
+        .collect()
+}
+
+fn normalize_base_path(raw: &str) -> Option<String> {
+    let trimmed = raw.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        return None;
+    }
+    let path = if trimmed.starts_with('/') {
+        trimmed.to_string()
+    } else {
+        format!("/{trimmed}")
+    };
+    Some(path.to_ascii_lowercase())
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<(Arc<ProjectRules>, String)>> = const { RefCell::new(None) };
+}
+
+/// Activates rules for one language on the current thread until dropped.
+pub struct ScopedRules {
+    previous: Option<(Arc<ProjectRules>, String)>,
+}
+
+impl Drop for ScopedRules {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        ACTIVE.with(|active| *active.borrow_mut() = previous);
+    }
+}
+
+pub fn scoped(rules: &Arc<ProjectRules>, language: &str) -> ScopedRules {
+    let previous = ACTIVE.with(|active| {
+        active
+            .borrow_mut()
+            .replace((Arc::clone(rules), language.to_string()))
+    });
+    ScopedRules { previous }
+}
+
+/// Evaluate `f` against the active rules, if any.
+pub fn with_active<R>(f: impl FnOnce(&ProjectRules, &str) -> R) -> Option<R> {
+    ACTIVE.with(|active| {
+        active
+            .borrow()
+            .as_ref()
+            .map(|(rules, language)| f(rules, language))
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+enum HelperKind {
+    HttpCall,
+    HttpRoute,
+    RpcCall,
+}
+
+struct HelperCall {
+    line: i64,
+    end_line: i64,
+    start_byte: usize,
+    end_byte: usize,
+    literal: String,
+}
+
+fn helper_edge(
+    kind: HelperKind,
+    rule: &CallRule,
+    call: &HelperCall,
+    source: &str,
+    source_qualname: &str,
+) -> Option<EdgeInput> {
+    let raw = call.literal.as_str();
+    let (edge_kind, target, detail) = match kind {
+        HelperKind::HttpCall | HelperKind::HttpRoute => {
+            let method = rule
+                .method
+                .as_deref()
+                .and_then(http::normalize_method)
+                .unwrap_or_else(|| http::HTTP_ANY.to_string());
+            let normalized = http::normalize_path(raw)?;
+            if matches!(kind, HelperKind::HttpCall) {
+                let detail = http::build_call_detail(&method, &normalized, raw, &rule.function);
+                (http::HTTP_CALL_KIND, normalized, detail)
+            } else {
+                let detail = http::build_route_detail(&method, &normalized, raw, &rule.function);
+                (http::HTTP_ROUTE_KIND, normalized, detail)
+            }
+        }
+        HelperKind::RpcCall => {
+            let path = raw.trim().trim_start_matches('/');
+            let (service, rpc) = path.rsplit_once(['/', '.'])?;
+            if service.is_empty() || rpc.is_empty() {
+                return None;
+            }
+            let (raw_path, normalized) = proto::normalize_rpc_path(None, service, rpc)?;
+            let detail = json!({
+                "framework": rule.function,
+                "role": "client",
+                "service": service,
+                "rpc": rpc,
+                "raw": raw_path,
+            })
+            .to_string();
+            (proto::RPC_CALL_KIND, normalized, detail)
+        }
+    };
+    let snippet = util::edge_evidence_snippet(
+        source,
+        call.start_byte as i64,
+        call.end_byte as i64,
+        call.line,
+        call.end_line,
+    );
+    Some(EdgeInput {
+        kind: edge_kind.to_string(),
+        source_qualname: Some(source_qualname.to_string()),
+        target_qualname: Some(target),
+        detail: Some(detail),
+        evidence_snippet: snippet,
+        evidence_start_line: Some(call.line),
+        evidence_end_line: Some(call.end_line),
+        confidence: Some(HELPER_CONFIDENCE),
+        ..Default::default()
+    })
+}
+
+pub(crate) fn enclosing_symbol(symbols: &[SymbolInput], line: i64) -> Option<&str> {
+    symbols
+        .iter()
+        .filter(|symbol| symbol.start_line <= line && line <= symbol.end_line)
+        .min_by_key(|symbol| symbol.end_line - symbol.start_line)
+        .map(|symbol| symbol.qualname.as_str())
+}
+
+fn is_ident_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '$'
+}
+
+/// Calls of `rule.function` whose argument `rule.arg` is a string literal.
+fn find_calls(source: &str, rule: &CallRule) -> Vec<HelperCall> {
+    let mut calls = Vec::new();
+    let name = rule.function.as_str();
+    for (start, _) in source.match_indices(name) {
+        let before = &source[..start];
+        if before.chars().next_back().is_some_and(is_ident_char) {
+            continue;
+        }
+        if let Some(receiver) = rule.receiver.as_deref()
+            && receiver_before(before) != Some(receiver)
+        {
+            continue;
+        }
+        let after = &source[start + name.len()..];
+        let Some(args_offset) = call_arguments_offset(after) else {
+            continue;
+        };
+        let args_start = start + name.len() + args_offset;
+        let Some((args, args_end)) = split_arguments(source, args_start) else {
+            continue;
+        };
+        let Some(literal) = args.get(rule.arg).and_then(|arg| string_literal(arg)) else {
+            continue;
+        };
+        calls.push(HelperCall {
+            line: line_at(source, start),
+            end_line: line_at(source, args_end),
+            start_byte: start,
+            end_byte: args_end + 1,
+            literal,
+        });
+    }
+    calls
+}
+
+/// The identifier before a `.`, `?.`, `->` or `::` that precedes a call.
+fn receiver_before(before: &str) -> Option<&str> {
+    let trimmed = before.trim_end();
+    let trimmed = trimmed
+        .strip_suffix("?.")
+        .or_else(|| trimmed.strip_suffix('.'))
+        .or_else(|| trimmed.strip_suffix("->"))
+        .or_else(|| trimmed.strip_suffix("::"))?
+        .trim_end();
+    let start = trimmed
+        .char_indices()
+        .rev()
+        .take_while(|(_, ch)| is_ident_char(*ch))
+        .last()
+        .map(|(idx, _)| idx)?;
+    Some(&trimmed[start..])
+}
+
+/// Offset of the `(` opening the argument list, skipping generic arguments.
+fn call_arguments_offset(after: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (idx, ch) in after.char_indices() {
+        match ch {
+            '<' => depth += 1,
+            '>' if depth > 0 => depth -= 1,
+            '(' if depth == 0 => return Some(idx),
+            ch if depth == 0 && !ch.is_whitespace() && ch != '!' => return None,
+            '\n' | ';' | '{' | '}' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Top-level arguments of the call whose `(` is at `open`, and the offset of
+/// the closing `)`.
+pub(crate) fn split_arguments(source: &str, open: usize) -> Option<(Vec<String>, usize)> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (offset, ch) in source[open + 1..].char_indices() {
+        if let Some(q) = quote {
+            current.push(ch);
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == q {
+                quote = None;
+            }
+            continue;
+        }
+        match ch {
+            '"' | '\'' | '`' => {
+                quote = Some(ch);
+                current.push(ch);
+            }
+            '(' | '[' | '{' => {
+                depth += 1;
+                current.push(ch);
+            }
+            ')' if depth == 0 => {
+                if !current.trim().is_empty() {
+                    args.push(current.trim().to_string());
+                }
+                return Some((args, open + 1 + offset));
+            }
+            ')' | ']' | '}' => {
+                depth = depth.saturating_sub(1);
+                current.push(ch);
+            }
+            ',' if depth == 0 => {
+                args.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(ch),
+        }
+    }
+    None
+}
+
+/// The contents of a string literal argument, accepting the common prefixes
+/// (`@"..."`, `f"..."`, `r"..."`, template literals).
+pub(crate) fn string_literal(arg: &str) -> Option<String> {
+    let arg = arg.trim();
+    let body = arg.trim_start_matches(['@', '$', 'f', 'r', 'b', 'u', 'F', 'R', 'B', 'U']);
+    let quote = body.chars().next()?;
+    if !matches!(quote, '"' | '\'' | '`') {
+        return None;
+    }
+    let inner = body.strip_prefix(quote)?.strip_suffix(quote)?;
+    if inner.is_empty() || inner.contains(quote) {
+        return None;
+    }
+    Some(inner.to_string())
+}
+
+pub(crate) fn line_at(source: &str, offset: usize) -> i64 {
+    source[..offset].matches('\n').count() as i64 + 1
+}
+
+/// Whether the last segment of `receiver` is a configured HTTP client for
+/// the language being extracted.
+pub fn active_http_client(receiver: &str) -> bool {
+    let last = receiver
+        .rsplit(['.', ':'])
+        .next()
+        .unwrap_or(receiver)
+        .trim();
+    !last.is_empty()
+        && with_active(|rules, language| rules.is_http_client(language, last)).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+# Messaging wrapper
+[[channels]]
+languages = ["csharp"]
+receivers = ["_outbox"]
+publish_methods = ["Enqueue"]   # trailing comment
+topic_containers = [
+    "Events",
+]
+
+[topics]
+strip_prefixes = ["prod."]
+aliases = { "legacy-orders" = "orders" }
+
+[http]
+base_paths = ['/api/v1']
+
+[[http.calls]]
+function = "fetch_json"
+method = "GET"
+arg = 1
+"#;
+
+    #[test]
+    fn parses_tables_arrays_and_inline_tables() {
+        let rules = ProjectRules::parse(SAMPLE).unwrap();
+        assert_eq!(rules.channels.len(), 1);
+        assert!(rules.is_bus_receiver("csharp", "_outbox"));
+        assert!(!rules.is_bus_receiver("python", "_outbox"));
+        assert!(rules.is_publish_method("csharp", "Enqueue"));
+        assert!(rules.is_topic_container("csharp", "Events"));
+        assert_eq!(rules.topics.aliases["legacy-orders"], "orders");
+        assert_eq!(rules.http.base_paths, vec!["/api/v1".to_string()]);
+        assert_eq!(rules.http.calls[0].function, "fetch_json");
+        assert_eq!(rules.http.calls[0].arg, 1);
+    }
+
+    #[test]
+    fn rejects_malformed_and_unknown_keys() {
+        let err = ProjectRules::parse("[channels\nreceivers = []").unwrap_err();
+        assert!(err.to_string().contains("line 1"), "{err}");
+        assert!(ProjectRules::parse("[[channels]]\nrecievers = [\"x\"]").is_err());
+        assert!(ProjectRules::parse("[http]\nbase_paths = [\"/a\"]\nbase_paths = []").is_err());
+    }
+
+    #[test]
+    fn parses_multiline_strings_and_inline_tables() {
+        let rules = ProjectRules::parse(
+            r#"
+channels = [
+    { receivers = ["_bus"], publish_methods = [
+        "Send",
+        "Publish",
+    ] },
+]
+
+[[edges]]
+id = "route"
+languages = ["python"]
+kind = "ROUTE"
+query = """
+(call
+  function: (identifier) @fn
+  (#eq? @fn "route")) @edge
+"""
+target = 'route://{fn}'
+"#,
+        )
+        .unwrap();
+        assert!(rules.is_publish_method("python", "Publish"));
+        assert!(rules.is_bus_receiver("go", "_bus"));
+        assert_eq!(
+            rules.edges[0].query,
+            "(call\n  function: (identifier) @fn\n  (#eq? @fn \"route\")) @edge\n"
+        );
+        assert_eq!(rules.edge_kinds(), vec!["ROUTE".to_string()]);
+    }
+
+    #[test]
+    fn languages_match_family_names() {
+        let rules = ProjectRules {
+            channels: vec![ChannelRule {
+                languages: vec!["js".to_string()],
+                receivers: vec!["hub".to_string()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(rules.is_bus_receiver("typescript", "hub"));
+        assert!(!rules.is_bus_receiver("go", "hub"));
+    }
+
+    #[test]
+    fn strips_base_paths_and_topics() {
+        let rules = ProjectRules::parse(SAMPLE).unwrap();
+        assert_eq!(rules.strip_base_path("/api/v1/orders".into()), "/orders");
+        assert_eq!(
+            rules.strip_base_path("/api/v10/orders".into()),
+            "/api/v10/orders"
+        );
+        assert_eq!(rules.strip_topic("\"prod.orders\""), "orders");
+        assert_eq!(rules.strip_topic("staging.orders"), "staging.orders");
+        assert_eq!(rules.topic_alias("legacyorders"), None);
+        assert_eq!(
+            rules.topic_alias("legacy-orders"),
+            Some("orders".to_string())
+        );
+    }
+
+    #[test]
+    fn finds_helper_calls_with_literal_arguments() {
+        let rule = CallRule {
+            function: "fetch_json".to_string(),
+            receiver: Some("api".to_string()),
+            ..Default::default()
+        };
+        let source = "def fetch_json(path):\n    pass\n\nx = self.api.fetch_json(\n    \"/orders/1\", timeout=(1, 2))\ny = other.fetch_json(\"/skip\")\n";
+        let calls = find_calls(source, &rule);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].literal, "/orders/1");
+        assert_eq!((calls[0].line, calls[0].end_line), (4, 5));
+    }
+}
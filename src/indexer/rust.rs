@@ -3,6 +3,7 @@ use crate::indexer::config;
 use crate::indexer::extract::{EdgeInput, ExtractedFile, SymbolInput};
 use crate::indexer::http;
 use crate::indexer::proto;
+use crate::indexer::rules;
 use crate::indexer::tree_helpers::{
     module_symbol_fallback, module_symbol_with_span, node_text, span,
 };
@@ -68,6 +69,7 @@ impl crate::indexer::extract::LanguageExtractor for RustExtractor {
             grpc_clients: HashMap::new(),
         };
         walk_node(root, &ctx, source, &mut output);
+        output.tree = Some(tree);
         Ok(output)
     }
 
@@ -1522,6 +1524,9 @@ fn http_client_label(receiver: Option<&str>, full: &str) -> Option<&'static str>
     if receiver_lower.ends_with("client") || receiver_lower.contains("client") {
         return Some("http_client");
     }
+    if receiver.is_some_and(rules::active_http_client) {
+        return Some("custom");
+    }
     None
 }
 
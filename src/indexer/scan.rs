@@ -1,3 +1,4 @@
+use crate::indexer::dockerfile;
 use anyhow::{Context, Result, bail};
 use blake3::Hasher;
 use ignore::WalkBuilder;
@@ -5,6 +6,9 @@ use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Files larger than this are not indexed.
+pub const MAX_FILE_SIZE_MB: u64 = 10;
+
 #[derive(Debug, Clone)]
 pub struct ScannedFile {
     pub rel_path: String,
@@ -79,10 +83,30 @@ static LANGUAGE_SPECS: &[LanguageSpec] = &[
         name: "proto",
         extensions: &["proto"],
     },
+    LanguageSpec {
+        name: "graphql",
+        extensions: &["graphql", "graphqls", "gql"],
+    },
     LanguageSpec {
         name: "go",
         extensions: &["go"],
     },
+    LanguageSpec {
+        name: "java",
+        extensions: &["java"],
+    },
+    LanguageSpec {
+        name: "kotlin",
+        extensions: &["kt", "kts"],
+    },
+    LanguageSpec {
+        name: "lua",
+        extensions: &["lua"],
+    },
+    LanguageSpec {
+        name: "markdown",
+        extensions: &["md", "markdown"],
+    },
     LanguageSpec {
         name: "yaml",
         extensions: &["yaml", "yml"],
@@ -91,6 +115,14 @@ static LANGUAGE_SPECS: &[LanguageSpec] = &[
         name: "bicep",
         extensions: &["bicep", "bicepparam"],
     },
+    LanguageSpec {
+        name: "terraform",
+        extensions: &["tf", "hcl"],
+    },
+    LanguageSpec {
+        name: "dockerfile",
+        extensions: &["dockerfile"],
+    },
 ];
 
 static LANGUAGE_FILTERS: &[LanguageFilter] = &[
@@ -202,6 +234,10 @@ static LANGUAGE_FILTERS: &[LanguageFilter] = &[
         name: "grpc",
         languages: &["proto"],
     },
+    LanguageFilter {
+        name: "graphql",
+        languages: &["graphql"],
+    },
     LanguageFilter {
         name: "go",
         languages: &["go"],
@@ -210,6 +246,34 @@ static LANGUAGE_FILTERS: &[LanguageFilter] = &[
         name: "golang",
         languages: &["go"],
     },
+    LanguageFilter {
+        name: "java",
+        languages: &["java"],
+    },
+    LanguageFilter {
+        name: "kotlin",
+        languages: &["kotlin"],
+    },
+    LanguageFilter {
+        name: "kt",
+        languages: &["kotlin"],
+    },
+    LanguageFilter {
+        name: "jvm",
+        languages: &["java", "kotlin"],
+    },
+    LanguageFilter {
+        name: "lua",
+        languages: &["lua"],
+    },
+    LanguageFilter {
+        name: "markdown",
+        languages: &["markdown"],
+    },
+    LanguageFilter {
+        name: "md",
+        languages: &["markdown"],
+    },
     LanguageFilter {
         name: "yaml",
         languages: &["yaml"],
@@ -226,6 +290,18 @@ static LANGUAGE_FILTERS: &[LanguageFilter] = &[
         name: "kubernetes",
         languages: &["yaml"],
     },
+    LanguageFilter {
+        name: "json",
+        languages: &["json"],
+    },
+    LanguageFilter {
+        name: "openapi",
+        languages: &["yaml", "json"],
+    },
+    LanguageFilter {
+        name: "asyncapi",
+        languages: &["yaml", "json"],
+    },
     LanguageFilter {
         name: "bicep",
         languages: &["bicep"],
@@ -238,6 +314,34 @@ static LANGUAGE_FILTERS: &[LanguageFilter] = &[
         name: "azure",
         languages: &["bicep"],
     },
+    LanguageFilter {
+        name: "terraform",
+        languages: &["terraform"],
+    },
+    LanguageFilter {
+        name: "tf",
+        languages: &["terraform"],
+    },
+    LanguageFilter {
+        name: "hcl",
+        languages: &["terraform"],
+    },
+    LanguageFilter {
+        name: "dockerfile",
+        languages: &["dockerfile"],
+    },
+    LanguageFilter {
+        name: "docker",
+        languages: &["dockerfile", "yaml"],
+    },
+    LanguageFilter {
+        name: "compose",
+        languages: &["yaml"],
+    },
+    LanguageFilter {
+        name: "helm",
+        languages: &["yaml"],
+    },
 ];
 
 pub fn language_specs() -> &'static [LanguageSpec] {
@@ -354,7 +458,30 @@ pub fn scan_path(repo_root: &Path, path: &Path) -> Result<Option<ScannedFile>> {
     }))
 }
 
+/// JSON is only indexed for API description documents, recognised by file
+/// name; other JSON (lockfiles, fixtures) would swamp the content index.
+fn is_api_spec_json(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let name = name.to_ascii_lowercase();
+    name.ends_with(".json")
+        && ["openapi", "swagger", "asyncapi"]
+            .iter()
+            .any(|marker| name.contains(marker))
+}
+
 fn detect_language(path: &Path) -> Option<&'static str> {
+    if is_api_spec_json(path) {
+        return Some("json");
+    }
+    if path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(dockerfile::is_dockerfile_name)
+    {
+        return Some("dockerfile");
+    }
     let ext = path.extension().and_then(|ext| ext.to_str())?;
     for spec in LANGUAGE_SPECS {
         if spec.extensions.contains(&ext) {
@@ -421,7 +548,12 @@ fn extensions_for_language(language: &str) -> &'static [&'static str] {
 
 fn hash_file(path: &Path) -> Result<String> {
     let data = fs::read(path)?;
+    Ok(hash_bytes(&data))
+}
+
+/// Content hash stored in `files.hash` for working-tree files.
+pub(crate) fn hash_bytes(data: &[u8]) -> String {
     let mut hasher = Hasher::new();
-    hasher.update(&data);
-    Ok(hasher.finalize().to_hex().to_string())
+    hasher.update(data);
+    hasher.finalize().to_hex().to_string()
 }
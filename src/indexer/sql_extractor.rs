@@ -1,11 +1,24 @@
+use crate::indexer::embedded_sql::READS_TABLE_KIND;
 use crate::indexer::extract::{EdgeInput, ExtractedFile, SymbolInput};
 use crate::indexer::tree_helpers::{
     module_symbol_fallback, module_symbol_with_span, node_text, span,
 };
+use crate::util;
 use anyhow::Result;
+use serde_json::{Value, json};
+use std::collections::HashMap;
 use std::path::Path;
 use tree_sitter::{Node, Parser};
 
+/// Edge from a SQL file to the table or column a DDL statement changes.
+/// `detail` holds `{"op", "table", "column"?, "type"?, "to"?}` where `op` is
+/// one of `create`, `drop`, `rename`, `add_column`, `drop_column`,
+/// `rename_column` or `alter_column`; `Db::table_columns` folds them, in
+/// migration order, into the current columns of a table.
+pub const SCHEMA_CHANGE_KIND: &str = "SCHEMA_CHANGE";
+/// Edge from a view column to the table column it is computed from.
+pub const DERIVES_FROM_KIND: &str = "DERIVES_FROM";
+
 #[derive(Clone)]
 struct Context {
     module: String,
@@ -88,6 +101,27 @@ pub fn module_name_from_rel_path(rel_path: &str) -> String {
 }
 
 fn walk_node(node: Node<'_>, ctx: &Context, source: &str, output: &mut ExtractedFile) {
+    match node.kind() {
+        "alter_table" => {
+            extract_alter_table(node, ctx, source, output);
+            return;
+        }
+        "drop_table" => {
+            let mut cursor = node.walk();
+            for object in node.named_children(&mut cursor) {
+                if object.kind() == "object_reference"
+                    && let Some(table) = object_reference_name(object, source)
+                {
+                    let detail = json!({ "op": "drop", "table": table });
+                    output
+                        .edges
+                        .push(schema_change(ctx, node, source, table, detail));
+                }
+            }
+            return;
+        }
+        _ => {}
+    }
     if let Some(kind) = create_kind(node.kind()) {
         if let Some((qualname, name)) = extract_object_name(node, source) {
             let (start_line, start_col, end_line, end_col, start_byte, end_byte) = span(node);
@@ -151,6 +185,10 @@ fn walk_node(node: Node<'_>, ctx: &Context, source: &str, output: &mut Extracted
                             ..Default::default()
                         });
                     }
+                    extract_table_columns(node, &qualname_owned, ctx, source, output);
+                }
+                "create_view" | "create_materialized_view" => {
+                    extract_view_columns(node, &qualname_owned, ctx, source, output);
                 }
                 _ => {}
             }
@@ -250,6 +288,378 @@ fn object_reference_name(node: Node<'_>, source: &str) -> Option<String> {
     Some(parts.join("."))
 }
 
+// Column-level schema
+
+fn schema_change(
+    ctx: &Context,
+    node: Node<'_>,
+    source: &str,
+    target: String,
+    detail: Value,
+) -> EdgeInput {
+    let (start_line, _, end_line, _, start_byte, end_byte) = span(node);
+    EdgeInput {
+        kind: SCHEMA_CHANGE_KIND.to_string(),
+        source_qualname: Some(ctx.module.clone()),
+        target_qualname: Some(target),
+        detail: Some(detail.to_string()),
+        evidence_snippet: util::edge_evidence_snippet(
+            source, start_byte, end_byte, start_line, end_line,
+        ),
+        evidence_start_line: Some(start_line),
+        evidence_end_line: Some(end_line),
+        ..Default::default()
+    }
+}
+
+/// Column symbol `table.name` contained by its table.
+fn push_column(
+    node: Node<'_>,
+    table: &str,
+    name: &str,
+    signature: Option<String>,
+    output: &mut ExtractedFile,
+) -> String {
+    let (start_line, start_col, end_line, end_col, start_byte, end_byte) = span(node);
+    let qualname = format!("{table}.{name}");
+    output.symbols.push(SymbolInput {
+        kind: "column".to_string(),
+        name: name.to_string(),
+        qualname: qualname.clone(),
+        start_line,
+        start_col,
+        end_line,
+        end_col,
+        start_byte,
+        end_byte,
+        signature,
+        docstring: None,
+    });
+    output.edges.push(EdgeInput {
+        kind: "CONTAINS".to_string(),
+        source_qualname: Some(table.to_string()),
+        target_qualname: Some(qualname.clone()),
+        ..Default::default()
+    });
+    qualname
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A `column_definition`: column symbol, `add_column` schema change and a
+/// column-level REFERENCES edge for inline foreign keys.
+fn add_column_definition(
+    node: Node<'_>,
+    table: &str,
+    ctx: &Context,
+    source: &str,
+    output: &mut ExtractedFile,
+) {
+    let Some(name_node) = node.child_by_field_name("name") else {
+        return;
+    };
+    let name = node_text(name_node, source);
+    if name.is_empty() {
+        return;
+    }
+    let data_type = node
+        .child_by_field_name("type")
+        .map(|type_node| collapse_whitespace(&node_text(type_node, source)));
+    let signature = collapse_whitespace(&node_text(node, source));
+    let qualname = push_column(node, table, &name, Some(signature), output);
+    let mut detail = json!({ "op": "add_column", "table": table, "column": name });
+    if let Some(data_type) = &data_type {
+        detail["type"] = json!(data_type);
+    }
+    output
+        .edges
+        .push(schema_change(ctx, node, source, qualname.clone(), detail));
+
+    let mut cursor = node.walk();
+    let children: Vec<Node<'_>> = node.children(&mut cursor).collect();
+    if let Some(at) = children
+        .iter()
+        .position(|child| child.kind() == "keyword_references")
+        && let Some(target) = children[at + 1..]
+            .iter()
+            .find(|child| child.kind() == "object_reference")
+            .and_then(|object| object_reference_name(*object, source))
+    {
+        let column = children[at + 1..]
+            .iter()
+            .find(|child| child.kind() == "identifier")
+            .map(|child| node_text(*child, source));
+        output.edges.push(EdgeInput {
+            kind: "REFERENCES".to_string(),
+            source_qualname: Some(qualname),
+            target_qualname: Some(match column {
+                Some(column) => format!("{target}.{column}"),
+                None => target,
+            }),
+            detail: Some("foreign key".to_string()),
+            evidence_snippet: None,
+            ..Default::default()
+        });
+    }
+}
+
+fn extract_table_columns(
+    node: Node<'_>,
+    table: &str,
+    ctx: &Context,
+    source: &str,
+    output: &mut ExtractedFile,
+) {
+    let detail = json!({ "op": "create", "table": table });
+    output
+        .edges
+        .push(schema_change(ctx, node, source, table.to_string(), detail));
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() != "column_definitions" {
+            continue;
+        }
+        let mut columns = child.walk();
+        for column in child.named_children(&mut columns) {
+            if column.kind() == "column_definition" {
+                add_column_definition(column, table, ctx, source, output);
+            }
+        }
+    }
+}
+
+fn extract_alter_table(node: Node<'_>, ctx: &Context, source: &str, output: &mut ExtractedFile) {
+    let mut cursor = node.walk();
+    let children: Vec<Node<'_>> = node.named_children(&mut cursor).collect();
+    let Some(table) = children
+        .iter()
+        .find(|child| child.kind() == "object_reference")
+        .and_then(|object| object_reference_name(*object, source))
+    else {
+        return;
+    };
+    let field = |action: Node<'_>, name: &str| {
+        action
+            .child_by_field_name(name)
+            .map(|child| node_text(child, source))
+            .filter(|text| !text.is_empty())
+    };
+    for action in children {
+        match action.kind() {
+            "add_column" => {
+                let mut columns = action.walk();
+                for column in action.named_children(&mut columns) {
+                    if column.kind() == "column_definition" {
+                        add_column_definition(column, &table, ctx, source, output);
+                    }
+                }
+            }
+            "drop_column" => {
+                let Some(column) = field(action, "name") else {
+                    continue;
+                };
+                let detail = json!({ "op": "drop_column", "table": table, "column": column });
+                let target = format!("{table}.{column}");
+                output
+                    .edges
+                    .push(schema_change(ctx, action, source, target, detail));
+            }
+            "rename_column" => {
+                let (Some(old), Some(new)) = (field(action, "old_name"), field(action, "new_name"))
+                else {
+                    continue;
+                };
+                let target = push_column(action, &table, &new, None, output);
+                let detail = json!({
+                    "op": "rename_column",
+                    "table": table,
+                    "column": old,
+                    "to": new,
+                });
+                output
+                    .edges
+                    .push(schema_change(ctx, action, source, target, detail));
+            }
+            "alter_column" => {
+                let Some(column) = field(action, "name") else {
+                    continue;
+                };
+                let mut detail = json!({ "op": "alter_column", "table": table, "column": column });
+                if let Some(data_type) = field(action, "type") {
+                    detail["type"] = json!(collapse_whitespace(&data_type));
+                }
+                let target = format!("{table}.{column}");
+                output
+                    .edges
+                    .push(schema_change(ctx, action, source, target, detail));
+            }
+            "rename_object" => {
+                let mut objects = action.walk();
+                let Some(to) = action
+                    .named_children(&mut objects)
+                    .find(|child| child.kind() == "object_reference")
+                    .and_then(|object| object_reference_name(object, source))
+                else {
+                    continue;
+                };
+                let detail = json!({ "op": "rename", "table": table, "to": to });
+                output
+                    .edges
+                    .push(schema_change(ctx, action, source, table.clone(), detail));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn named_child_of_kind<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|child| child.kind() == kind)
+}
+
+fn collect_fields<'a>(node: Node<'a>, out: &mut Vec<Node<'a>>) {
+    if node.kind() == "field" {
+        out.push(node);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_fields(child, out);
+    }
+}
+
+/// Output columns of `CREATE VIEW ... AS SELECT`, each with DERIVES_FROM
+/// edges to the table columns its expression reads, plus READS_TABLE edges
+/// from the view to the tables in its FROM clause.
+fn extract_view_columns(
+    node: Node<'_>,
+    view: &str,
+    ctx: &Context,
+    source: &str,
+    output: &mut ExtractedFile,
+) {
+    let Some(query) = named_child_of_kind(node, "create_query") else {
+        return;
+    };
+    let (Some(select), Some(from)) = (
+        named_child_of_kind(query, "select"),
+        named_child_of_kind(query, "from"),
+    ) else {
+        return;
+    };
+
+    // Relations in FROM / JOIN, keyed by alias and by (last segment of) name.
+    let mut tables: Vec<String> = Vec::new();
+    let mut aliases: HashMap<String, String> = HashMap::new();
+    let mut relations = Vec::new();
+    let mut cursor = from.walk();
+    for child in from.named_children(&mut cursor) {
+        match child.kind() {
+            "relation" => relations.push(child),
+            "join" => relations.extend(named_child_of_kind(child, "relation")),
+            _ => {}
+        }
+    }
+    for relation in relations {
+        let Some(table) = named_child_of_kind(relation, "object_reference")
+            .and_then(|object| object_reference_name(object, source))
+        else {
+            continue;
+        };
+        if let Some(alias) = relation.child_by_field_name("alias") {
+            aliases.insert(node_text(alias, source).to_lowercase(), table.clone());
+        }
+        let short = table.rsplit('.').next().unwrap_or(&table).to_lowercase();
+        aliases.entry(short).or_insert_with(|| table.clone());
+        aliases
+            .entry(table.to_lowercase())
+            .or_insert_with(|| table.clone());
+        if !tables.contains(&table) {
+            tables.push(table);
+        }
+    }
+    for table in &tables {
+        output.edges.push(EdgeInput {
+            kind: READS_TABLE_KIND.to_string(),
+            source_qualname: Some(view.to_string()),
+            target_qualname: Some(table.clone()),
+            detail: Some(json!({ "via": "view" }).to_string()),
+            ..Default::default()
+        });
+    }
+
+    let detail = json!({ "op": "create", "table": view });
+    output
+        .edges
+        .push(schema_change(ctx, node, source, view.to_string(), detail));
+    let Some(expressions) = named_child_of_kind(select, "select_expression") else {
+        return;
+    };
+    let mut terms = expressions.walk();
+    for term in expressions.named_children(&mut terms) {
+        if term.kind() != "term" {
+            continue;
+        }
+        let Some(value) = term.child_by_field_name("value") else {
+            continue;
+        };
+        let name = match term.child_by_field_name("alias") {
+            Some(alias) => node_text(alias, source),
+            None if value.kind() == "field" => value
+                .child_by_field_name("name")
+                .map(|name| node_text(name, source))
+                .unwrap_or_default(),
+            None => continue,
+        };
+        if name.is_empty() {
+            continue;
+        }
+        let signature = collapse_whitespace(&node_text(term, source));
+        let qualname = push_column(term, view, &name, Some(signature), output);
+        let detail = json!({ "op": "add_column", "table": view, "column": name });
+        output
+            .edges
+            .push(schema_change(ctx, term, source, qualname.clone(), detail));
+
+        let mut fields = Vec::new();
+        collect_fields(value, &mut fields);
+        let mut derived = Vec::new();
+        for field in fields {
+            let Some(column) = field
+                .child_by_field_name("name")
+                .map(|name| node_text(name, source))
+            else {
+                continue;
+            };
+            let table = match named_child_of_kind(field, "object_reference") {
+                Some(object) => object_reference_name(object, source)
+                    .and_then(|qualifier| aliases.get(&qualifier.to_lowercase()).cloned()),
+                None if tables.len() == 1 => tables.first().cloned(),
+                None => None,
+            };
+            let Some(table) = table else {
+                continue;
+            };
+            let target = format!("{table}.{column}");
+            if derived.contains(&target) {
+                continue;
+            }
+            derived.push(target.clone());
+            output.edges.push(EdgeInput {
+                kind: DERIVES_FROM_KIND.to_string(),
+                source_qualname: Some(qualname.clone()),
+                target_qualname: Some(target),
+                detail: Some("view column lineage".to_string()),
+                evidence_snippet: None,
+                ..Default::default()
+            });
+        }
+    }
+}
+
 // PL/pgSQL-specific helpers
 
 fn extract_dollar_quoted_body(text: &str) -> Option<&str> {
@@ -785,4 +1195,51 @@ $$ LANGUAGE plpgsql;
             "db/schema"
         );
     }
+
+    #[test]
+    fn extracts_columns_and_schema_changes() {
+        let source = r#"
+CREATE TABLE orders (id INT PRIMARY KEY, status VARCHAR(20) DEFAULT 'new');
+ALTER TABLE orders ADD COLUMN total NUMERIC(10,2), DROP COLUMN status;
+"#;
+        let mut extractor = SqlExtractor::new().unwrap();
+        let file = extractor.extract(source, "migrations/001").unwrap();
+        let columns: Vec<_> = file
+            .symbols
+            .iter()
+            .filter(|s| s.kind == "column")
+            .map(|s| (s.qualname.as_str(), s.signature.as_deref().unwrap_or("")))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("orders.id", "id INT PRIMARY KEY"),
+                ("orders.status", "status VARCHAR(20) DEFAULT 'new'"),
+                ("orders.total", "total NUMERIC(10,2)"),
+            ]
+        );
+        let ops: Vec<String> = file
+            .edges
+            .iter()
+            .filter(|e| e.kind == SCHEMA_CHANGE_KIND)
+            .map(|e| {
+                let detail: Value = serde_json::from_str(e.detail.as_deref().unwrap()).unwrap();
+                format!(
+                    "{} {}",
+                    detail["op"].as_str().unwrap(),
+                    e.target_qualname.as_deref().unwrap()
+                )
+            })
+            .collect();
+        assert_eq!(
+            ops,
+            vec![
+                "create orders",
+                "add_column orders.id",
+                "add_column orders.status",
+                "add_column orders.total",
+                "drop_column orders.status",
+            ]
+        );
+    }
 }
@@ -0,0 +1,698 @@
+//! Terraform / HCL configurations, parsed with tree-sitter-hcl.
+//!
+//! Top-level `resource`, `data`, `module`, `variable`, `output` and `locals`
+//! blocks become symbols. Terraform resolves names per directory, so symbols
+//! are qualified by the directory rather than the file: `var.region` in
+//! `infra/main.tf` is `infra.var.region` wherever `infra/variables.tf`
+//! declares it. References between blocks (`var.x`, `local.x`, `module.x`,
+//! `data.t.n`, `t.n`) become REFERENCES edges, and a `module` with a local
+//! `source` imports every `.tf` file of that directory.
+//!
+//! Environment variables and app settings set on resources (`app_settings`,
+//! `environment { variables = {...} }`, `env { name = ... }`) emit
+//! CONFIG_SOURCE edges to `env://NAME`, and Key Vault secrets to
+//! `secret://name`, the same targets the Bicep and Kubernetes extractors use.
+
+use crate::indexer::config::{self, CONFIG_SOURCE_KIND};
+use crate::indexer::extract::{EdgeInput, ExtractedFile, LanguageExtractor};
+use crate::indexer::openapi::contains_edge;
+use crate::indexer::tree_helpers::{
+    child_of_kind, children_of_kind, module_symbol_fallback, node_text, symbol_for_node,
+};
+use crate::indexer::yaml;
+use crate::util;
+use anyhow::Result;
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+pub const REFERENCES_KIND: &str = "REFERENCES";
+
+/// Scope of files at the repository root, which have no directory.
+const ROOT_SCOPE: &str = "root";
+/// Map attributes whose keys are environment variable names.
+const ENV_MAPS: &[&str] = &[
+    "app_settings",
+    "variables",
+    "environment",
+    "environment_variables",
+    "env_vars",
+    "env",
+];
+/// Blocks (and list items) naming one environment variable via `name`.
+const ENV_BLOCKS: &[&str] = &["env", "environment_variable", "env_var"];
+/// Traversal roots that never name a resource type.
+const BUILTIN_ROOTS: &[&str] = &["each", "count", "path", "self", "terraform"];
+
+pub struct TerraformExtractor {
+    parser: Parser,
+}
+
+impl TerraformExtractor {
+    pub fn new() -> Result<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_hcl::LANGUAGE.into())?;
+        Ok(Self { parser })
+    }
+}
+
+impl LanguageExtractor for TerraformExtractor {
+    fn module_name_from_rel_path(&self, rel_path: &str) -> String {
+        module_name_from_rel_path(rel_path)
+    }
+
+    fn extract(&mut self, source: &str, module_name: &str) -> Result<ExtractedFile> {
+        let mut output = ExtractedFile::default();
+        output
+            .symbols
+            .push(module_symbol_fallback(module_name, source, "/", None));
+        let Some(tree) = self.parser.parse(source, None) else {
+            return Ok(output);
+        };
+
+        let scope = module_scope(module_name);
+        let Some(body) = child_of_kind(tree.root_node(), "body") else {
+            return Ok(output);
+        };
+        for block in blocks(body) {
+            let block_type = child_of_kind(block, "identifier")
+                .map(|node| node_text(node, source))
+                .unwrap_or_default();
+            let labels = block_labels(block, source);
+            let attrs = child_of_kind(block, "body")
+                .map(|body| entries(body, source))
+                .unwrap_or_default();
+            let attr = |name: &str| attrs.iter().find(|attr| attr.name == name);
+            let string_attr =
+                |name: &str| attr(name).and_then(|attr| string_value(attr.value, source));
+            let expression = |name: &str| attr(name).map(|attr| collapsed_text(attr.value, source));
+
+            let declared = match (block_type.as_str(), labels.as_slice()) {
+                ("resource", [ty, name]) => {
+                    Some(("resource", format!("{ty}.{name}"), Some(ty.clone())))
+                }
+                ("data", [ty, name]) => {
+                    Some(("data_source", format!("data.{ty}.{name}"), Some(ty.clone())))
+                }
+                ("module", [name]) => Some((
+                    "module_ref",
+                    format!("module.{name}"),
+                    string_attr("source"),
+                )),
+                ("variable", [name]) => {
+                    let mut signature = expression("type").unwrap_or_default();
+                    if expression("sensitive").as_deref() == Some("true") {
+                        if !signature.is_empty() {
+                            signature.push(' ');
+                        }
+                        signature.push_str("(sensitive)");
+                    }
+                    let signature = (!signature.is_empty()).then_some(signature);
+                    Some(("variable", format!("var.{name}"), signature))
+                }
+                ("output", [name]) => Some(("output", format!("output.{name}"), None)),
+                _ => None,
+            };
+
+            if let Some((kind, name, signature)) = declared {
+                let qualname = format!("{scope}.{name}");
+                output.symbols.push(symbol_for_node(
+                    kind,
+                    &name,
+                    &qualname,
+                    block,
+                    signature,
+                    string_attr("description"),
+                ));
+                output.edges.push(contains_edge(module_name, &qualname));
+                output
+                    .edges
+                    .extend(reference_edges(block, source, &scope, &qualname));
+
+                if kind == "module_ref"
+                    && let Some(source_path) = string_attr("source")
+                    && is_local_source(&source_path)
+                {
+                    let line = attr("source").map(|attr| line_of(attr.key));
+                    output.edges.push(EdgeInput {
+                        kind: "IMPORTS_FILE".to_string(),
+                        source_qualname: Some(qualname.clone()),
+                        target_qualname: Some(source_path),
+                        evidence_start_line: line,
+                        evidence_end_line: line,
+                        ..Default::default()
+                    });
+                }
+                if kind == "resource" {
+                    let resource_type = labels.first().map(String::as_str).unwrap_or_default();
+                    config_source_edges(block, source, resource_type, &qualname, &mut output);
+                }
+                continue;
+            }
+
+            // Each attribute of a `locals` block is its own symbol.
+            if block_type == "locals" {
+                for local in &attrs {
+                    let name = format!("local.{}", local.name);
+                    let qualname = format!("{scope}.{name}");
+                    output.symbols.push(symbol_for_node(
+                        "local", &name, &qualname, local.node, None, None,
+                    ));
+                    output.edges.push(contains_edge(module_name, &qualname));
+                    output
+                        .edges
+                        .extend(reference_edges(local.value, source, &scope, &qualname));
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn resolve_imports(
+        &self,
+        repo_root: &Path,
+        file_rel_path: &str,
+        _module_name: &str,
+        edges: &mut Vec<EdgeInput>,
+    ) {
+        let file_dir = Path::new(file_rel_path)
+            .parent()
+            .and_then(|dir| dir.to_str())
+            .unwrap_or("");
+        let mut resolved = Vec::new();
+        edges.retain(|edge| {
+            if edge.kind != "IMPORTS_FILE" {
+                return true;
+            }
+            let Some(raw) = edge.target_qualname.as_deref() else {
+                return true;
+            };
+            if !is_local_source(raw) {
+                return true;
+            }
+            let Some(dir) = util::collapse_parent_dirs(&format!("{file_dir}/{raw}")) else {
+                return false;
+            };
+            for dst_path in module_files(repo_root, &dir) {
+                resolved.push(EdgeInput {
+                    target_qualname: Some(module_name_from_rel_path(&dst_path)),
+                    detail: Some(
+                        json!({
+                            "src_path": file_rel_path,
+                            "dst_path": dst_path,
+                            "raw": raw,
+                            "confidence": 1.0,
+                        })
+                        .to_string(),
+                    ),
+                    ..edge.clone()
+                });
+            }
+            false
+        });
+        edges.extend(resolved);
+    }
+}
+
+pub fn module_name_from_rel_path(rel_path: &str) -> String {
+    let path = Path::new(rel_path);
+    let mut parts: Vec<String> = path
+        .components()
+        .filter_map(|comp| comp.as_os_str().to_str().map(|s| s.to_string()))
+        .collect();
+    if parts.is_empty() {
+        return "main".to_string();
+    }
+    let file = parts.pop().unwrap_or_default();
+    let stem = Path::new(&file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&file)
+        .to_string();
+    if !stem.is_empty() {
+        parts.push(stem);
+    }
+    if parts.is_empty() {
+        "main".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+/// Directory a file's declarations are scoped to: `infra/net` for
+/// `infra/net/main`, [`ROOT_SCOPE`] at the repository root.
+pub fn module_scope(module_name: &str) -> String {
+    match module_name.rsplit_once('/') {
+        Some((dir, _)) if !dir.is_empty() => dir.to_string(),
+        _ => ROOT_SCOPE.to_string(),
+    }
+}
+
+fn is_local_source(source: &str) -> bool {
+    source.starts_with("./") || source.starts_with("../")
+}
+
+/// `.tf` files directly inside `dir`, sorted.
+fn module_files(repo_root: &Path, dir: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(repo_root.join(dir)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(|name| name.to_string()))
+        .filter(|name| name.ends_with(".tf"))
+        .map(|name| format!("{dir}/{name}"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn line_of(node: Node<'_>) -> i64 {
+    node.start_position().row as i64 + 1
+}
+
+// --- Syntax tree ---
+
+/// An attribute `name = value` of a body, or an entry of an object value
+/// (`key = value`, `"key" = value`, `key: value`).
+struct Entry<'t> {
+    name: String,
+    /// The whole attribute or object element.
+    node: Node<'t>,
+    key: Node<'t>,
+    /// The value expression.
+    value: Node<'t>,
+}
+
+fn blocks(body: Node<'_>) -> Vec<Node<'_>> {
+    children_of_kind(body, "block")
+}
+
+/// Labels of a block such as `resource "aws_s3_bucket" "logs" { ... }`.
+fn block_labels(block: Node<'_>, source: &str) -> Vec<String> {
+    let mut cursor = block.walk();
+    block
+        .named_children(&mut cursor)
+        .skip(1)
+        .take_while(|child| matches!(child.kind(), "string_lit" | "identifier"))
+        .map(|label| match label.kind() {
+            "string_lit" => string_lit_value(label, source).unwrap_or_default(),
+            _ => node_text(label, source),
+        })
+        .collect()
+}
+
+/// Attributes of a `body`, or elements of an `object`.
+fn entries<'t>(node: Node<'t>, source: &str) -> Vec<Entry<'t>> {
+    let mut out = Vec::new();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let entry = match child.kind() {
+            "attribute" => child_of_kind(child, "identifier")
+                .zip(child_of_kind(child, "expression"))
+                .map(|(key, value)| (node_text(key, source), key, value)),
+            "object_elem" => child
+                .child_by_field_name("key")
+                .zip(child.child_by_field_name("val"))
+                .and_then(|(key, value)| Some((object_key(key, source)?, key, value))),
+            _ => None,
+        };
+        if let Some((name, key, value)) = entry {
+            out.push(Entry {
+                name,
+                node: child,
+                key,
+                value,
+            });
+        }
+    }
+    out
+}
+
+/// Name of an object key: a bare identifier or a literal string.
+fn object_key(key: Node<'_>, source: &str) -> Option<String> {
+    let inner = key.named_child(0)?;
+    match inner.kind() {
+        "variable_expr" if key.named_child_count() == 1 => Some(node_text(inner, source)),
+        "literal_value" => string_value(key, source),
+        _ => None,
+    }
+}
+
+/// Literal string value of an expression, without interpolations.
+fn string_value(expression: Node<'_>, source: &str) -> Option<String> {
+    let literal = child_of_kind(expression, "literal_value")?;
+    string_lit_value(child_of_kind(literal, "string_lit")?, source)
+}
+
+fn string_lit_value(string_lit: Node<'_>, source: &str) -> Option<String> {
+    let start = child_of_kind(string_lit, "quoted_template_start")?;
+    let end = child_of_kind(string_lit, "quoted_template_end")?;
+    source
+        .get(start.end_byte()..end.start_byte())
+        .map(str::to_string)
+}
+
+/// Source text of a node, whitespace collapsed.
+fn collapsed_text(node: Node<'_>, source: &str) -> String {
+    node_text(node, source)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The `collection_value` of `kind` (`object` or `tuple`) an expression
+/// consists of.
+fn collection<'t>(expression: Node<'t>, kind: &str) -> Option<Node<'t>> {
+    child_of_kind(child_of_kind(expression, "collection_value")?, kind)
+}
+
+/// Outermost objects inside `node`, in source order.
+fn objects<'t>(node: Node<'t>, out: &mut Vec<Node<'t>>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == "object" {
+            out.push(child);
+        } else {
+            objects(child, out);
+        }
+    }
+}
+
+// --- References ---
+
+/// REFERENCES from `source_qualname` to every block named under `node`,
+/// including names inside `"${...}"` interpolations and heredocs.
+fn reference_edges(
+    node: Node<'_>,
+    source: &str,
+    scope: &str,
+    source_qualname: &str,
+) -> Vec<EdgeInput> {
+    let mut seen = HashSet::new();
+    let mut edges = Vec::new();
+    for (target, line) in references(node, source, scope) {
+        if target == source_qualname || !seen.insert(target.clone()) {
+            continue;
+        }
+        edges.push(EdgeInput {
+            kind: REFERENCES_KIND.to_string(),
+            source_qualname: Some(source_qualname.to_string()),
+            target_qualname: Some(target),
+            evidence_start_line: Some(line),
+            evidence_end_line: Some(line),
+            ..Default::default()
+        });
+    }
+    edges
+}
+
+/// `(target qualname, line)` for each traversal under `node`.
+fn references(node: Node<'_>, source: &str, scope: &str) -> Vec<(String, i64)> {
+    // Iterator names of `for` expressions and `dynamic` blocks look like
+    // resource traversals (`app_setting.value`), so they are skipped.
+    let mut bound = HashSet::new();
+    let mut variables = Vec::new();
+    let mut stack = vec![node];
+    while let Some(current) = stack.pop() {
+        match current.kind() {
+            "for_intro" => {
+                for name in children_of_kind(current, "identifier") {
+                    bound.insert(node_text(name, source));
+                }
+            }
+            "block"
+                if child_of_kind(current, "identifier")
+                    .is_some_and(|ty| node_text(ty, source) == "dynamic") =>
+            {
+                bound.extend(block_labels(current, source));
+            }
+            "variable_expr" => variables.push(current),
+            _ => {}
+        }
+        let mut cursor = current.walk();
+        let children: Vec<_> = current.named_children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+
+    let mut out = Vec::new();
+    for variable in variables {
+        let root = node_text(variable, source);
+        let segments: Vec<String> = traversal(variable)
+            .into_iter()
+            .map_while(|attr| child_of_kind(attr, "identifier"))
+            .map(|name| node_text(name, source))
+            .collect();
+        let target = match (root.as_str(), segments.as_slice()) {
+            ("var" | "local" | "module", [name, ..]) => Some(format!("{root}.{name}")),
+            ("data", [ty, name, ..]) => Some(format!("data.{ty}.{name}")),
+            (_, [name, ..])
+                if root.contains('_')
+                    && !BUILTIN_ROOTS.contains(&root.as_str())
+                    && !bound.contains(&root) =>
+            {
+                Some(format!("{root}.{name}"))
+            }
+            _ => None,
+        };
+        if let Some(target) = target {
+            out.push((format!("{scope}.{target}"), line_of(variable)));
+        }
+    }
+    out
+}
+
+/// The `.name` accesses following a variable. tree-sitter-hcl attaches the
+/// accesses of an operation's last operand (`a + var.x`) to the enclosing
+/// expression, so they are looked up past the operation.
+fn traversal(variable: Node<'_>) -> Vec<Node<'_>> {
+    let mut node = variable;
+    loop {
+        let mut attrs = Vec::new();
+        let mut next = node.next_named_sibling();
+        while let Some(sibling) = next.filter(|sibling| sibling.kind() == "get_attr") {
+            attrs.push(sibling);
+            next = sibling.next_named_sibling();
+        }
+        if !attrs.is_empty() || next.is_some() {
+            return attrs;
+        }
+        match node.parent() {
+            Some(parent)
+                if matches!(
+                    parent.kind(),
+                    "operation" | "binary_operation" | "unary_operation"
+                ) =>
+            {
+                node = parent;
+            }
+            _ => return attrs,
+        }
+    }
+}
+
+// --- Config sources ---
+
+/// CONFIG_SOURCE edges for env vars and app settings a resource injects,
+/// and for Key Vault secrets it declares.
+fn config_source_edges(
+    block: Node<'_>,
+    source: &str,
+    resource_type: &str,
+    qualname: &str,
+    output: &mut ExtractedFile,
+) {
+    let Some(body) = child_of_kind(block, "body") else {
+        return;
+    };
+    let mut names = Vec::new();
+    collect_env_names(body, source, &mut names);
+    let mut seen = HashSet::new();
+    for (name, line) in names {
+        let Some(env_uri) = config::normalize_env_var_name(&name) else {
+            continue;
+        };
+        if !seen.insert(env_uri.clone()) {
+            continue;
+        }
+        let detail = config::build_config_source_detail(
+            "env",
+            &env_uri,
+            &name,
+            Some(&json!({ "provider": "terraform", "resource": resource_type })),
+        );
+        output.edges.push(EdgeInput {
+            kind: CONFIG_SOURCE_KIND.to_string(),
+            source_qualname: Some(qualname.to_string()),
+            target_qualname: Some(env_uri),
+            detail: Some(detail),
+            evidence_start_line: Some(line),
+            evidence_end_line: Some(line),
+            ..Default::default()
+        });
+        yaml::emit_section_prefix_edges(qualname, &name, line, line, output);
+    }
+
+    if resource_type == "azurerm_key_vault_secret"
+        && let Some(name_attr) = entries(body, source)
+            .into_iter()
+            .find(|attr| attr.name == "name")
+        && let Some(secret_name) = string_value(name_attr.value, source)
+        && let Some(secret_uri) = config::normalize_secret_name(&secret_name)
+    {
+        let line = line_of(name_attr.key);
+        let detail = config::build_config_source_detail(
+            "secret",
+            &secret_uri,
+            &secret_name,
+            Some(&json!({ "provider": "azure-keyvault" })),
+        );
+        output.edges.push(EdgeInput {
+            kind: CONFIG_SOURCE_KIND.to_string(),
+            source_qualname: Some(qualname.to_string()),
+            target_qualname: Some(secret_uri),
+            detail: Some(detail),
+            evidence_start_line: Some(line),
+            evidence_end_line: Some(line),
+            ..Default::default()
+        });
+    }
+}
+
+/// Env var names set in a body or object, searched through nested blocks
+/// and object values (`jsonencode([{ environment = [...] }])`).
+fn collect_env_names(node: Node<'_>, source: &str, out: &mut Vec<(String, i64)>) {
+    for attr in entries(node, source) {
+        if ENV_MAPS.contains(&attr.name.as_str()) {
+            if let Some(map) = collection(attr.value, "object") {
+                for entry in entries(map, source) {
+                    if is_literal_name(&entry.name) {
+                        out.push((entry.name, line_of(entry.key)));
+                    }
+                }
+                continue;
+            }
+            if let Some(list) = collection(attr.value, "tuple") {
+                let mut items = Vec::new();
+                objects(list, &mut items);
+                for item in items {
+                    push_named_env(item, source, out);
+                }
+                continue;
+            }
+        }
+        let mut nested = Vec::new();
+        objects(attr.value, &mut nested);
+        for object in nested {
+            collect_env_names(object, source, out);
+        }
+    }
+    if node.kind() != "body" {
+        return;
+    }
+    for block in blocks(node) {
+        let Some(body) = child_of_kind(block, "body") else {
+            continue;
+        };
+        let block_type = child_of_kind(block, "identifier")
+            .map(|ty| node_text(ty, source))
+            .unwrap_or_default();
+        if ENV_BLOCKS.contains(&block_type.as_str()) {
+            push_named_env(body, source, out);
+        }
+        collect_env_names(body, source, out);
+    }
+}
+
+/// The `name = "X"` entry of an `env` block or list item.
+fn push_named_env(node: Node<'_>, source: &str, out: &mut Vec<(String, i64)>) {
+    if let Some(attr) = entries(node, source)
+        .into_iter()
+        .find(|attr| attr.name == "name")
+        && let Some(name) = string_value(attr.value, source)
+        && is_literal_name(&name)
+    {
+        out.push((name, line_of(attr.key)));
+    }
+}
+
+fn is_literal_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains("${")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> tree_sitter::Tree {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_hcl::LANGUAGE.into())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    #[test]
+    fn module_scope_is_directory() {
+        assert_eq!(module_scope("infra/net/main"), "infra/net");
+        assert_eq!(module_scope("main"), "root");
+    }
+
+    #[test]
+    fn references_skip_iterators_and_builtins() {
+        let source = r#"names = [for sub_net in aws_subnet.private : sub_net.id]
+count = length(var.zones) + count.index
+tag = "${local.prefix}-${data.aws_region.current.name}"
+flag = !var.enabled || local.force
+dynamic "app_setting" { content { value = app_setting.value } }"#;
+        let tree = parse(source);
+        let targets: Vec<String> = references(tree.root_node(), source, "infra")
+            .into_iter()
+            .map(|(target, _)| target)
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                "infra.aws_subnet.private",
+                "infra.var.zones",
+                "infra.local.prefix",
+                "infra.data.aws_region.current",
+                "infra.var.enabled",
+                "infra.local.force",
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_end_at_newline_unless_continued() {
+        let source = "a = var.x ?\n  1 : 2\nb = {\n  c = 1\n}\nd = \"x\"";
+        let tree = parse(source);
+        let body = child_of_kind(tree.root_node(), "body").unwrap();
+        let attrs = entries(body, source);
+        let names: Vec<&str> = attrs.iter().map(|attr| attr.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b", "d"]);
+        assert_eq!(string_value(attrs[2].value, source).as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn heredoc_is_one_string() {
+        let source = "policy = <<-EOT\n  {\"a\": 1}\n  EOT\nname = \"x\"\n";
+        let tree = parse(source);
+        let body = child_of_kind(tree.root_node(), "body").unwrap();
+        let attrs = entries(body, source);
+        assert_eq!(attrs.len(), 2);
+        assert_eq!(attrs[1].name, "name");
+    }
+
+    #[test]
+    fn collapse_parent_dirs_resolves_relative_sources() {
+        assert_eq!(
+            util::collapse_parent_dirs("infra/envs/prod/../../modules/net").as_deref(),
+            Some("infra/modules/net")
+        );
+        assert_eq!(util::collapse_parent_dirs("../x"), None);
+    }
+}
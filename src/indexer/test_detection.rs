@@ -18,6 +18,7 @@ use crate::model::Symbol;
 /// - Symbol kind is "test"
 /// - Has `#[test]` attribute (detected by tree-sitter)
 /// - Has `#[tokio::test]` or `#[actix_rt::test]` attribute
+/// - Function in a `tests` module (`mod tests`, or a file under `tests/`)
 ///
 /// ## JavaScript/TypeScript
 /// - Function name contains "test", "it", "describe", "spec"
@@ -28,6 +29,10 @@ use crate::model::Symbol;
 /// - Method name starts with `test`
 /// - File ends with `Test.java` or `TestCase.java`
 ///
+/// ## Kotlin
+/// - Method name starts with `test`
+/// - Method in a test file (JUnit, Kotest), e.g. `UserServiceTest.kt`
+///
 /// ## C#
 /// - Method/class in a test file (NUnit, xUnit, MSTest)
 /// - Qualname contains `.Tests.` or `.Test.` namespace
@@ -59,6 +64,12 @@ pub fn is_test_symbol(symbol: &Symbol) -> bool {
 
     // Rust tests
     if file_lower.ends_with(".rs") {
+        // Attributes are not part of the signature, so fall back on the
+        // `mod tests` convention; files under tests/ are `crate::tests::<file>`.
+        if symbol.kind == "function" && symbol.qualname.contains("::tests::") {
+            return true;
+        }
+
         // Check for test attributes in signature
         if let Some(sig) = &symbol.signature {
             let sig_lower = sig.to_lowercase();
@@ -125,6 +136,16 @@ pub fn is_test_symbol(symbol: &Symbol) -> bool {
         }
     }
 
+    // Kotlin tests
+    if file_lower.ends_with(".kt") {
+        if name_lower.starts_with("test") && symbol.kind == "method" {
+            return true;
+        }
+        if is_test_file(&symbol.file_path) && symbol.kind == "method" {
+            return true;
+        }
+    }
+
     // C# tests
     if file_lower.ends_with(".cs") {
         // Methods/classes in test files (NUnit, xUnit, MSTest)
@@ -157,7 +178,8 @@ pub fn is_test_symbol(symbol: &Symbol) -> bool {
 ///
 /// Reuses existing logic from `src/impact/layers/direct.rs`
 pub fn is_test_file(path: &str) -> bool {
-    let path_lower = path.to_lowercase();
+    // Leading slash so that a top-level `tests/` directory matches too.
+    let path_lower = format!("/{}", path.to_lowercase());
     path_lower.contains("/test/")
         || path_lower.contains("/tests/")
         || path_lower.contains("/_test/")
@@ -179,6 +201,7 @@ pub fn is_test_file(path: &str) -> bool {
         || path_lower.ends_with(".spec.jsx")
         || path_lower.ends_with("_spec.rb")
         || path_lower.ends_with("test.java")
+        || path_lower.ends_with("test.kt")
         || path_lower.ends_with("_test.go")
 }
 
@@ -308,6 +331,10 @@ mod tests {
         let sym = make_symbol("tests/integration.rs", "function", "test_integration", None);
         assert!(is_test_symbol(&sym), "Test function in tests/ directory");
 
+        let mut sym = make_symbol("src/math.rs", "function", "adds", None);
+        sym.qualname = "crate::math::tests::adds".to_string();
+        assert!(is_test_symbol(&sym), "Function in a tests module");
+
         let sym = make_symbol("src/lib.rs", "function", "regular_function", None);
         assert!(!is_test_symbol(&sym), "Not a test");
     }
@@ -342,12 +369,32 @@ mod tests {
         assert!(!is_test_symbol(&sym), "C# production method");
     }
 
+    #[test]
+    fn test_is_test_symbol_kotlin() {
+        let sym = make_symbol(
+            "src/test/kotlin/com/acme/UserServiceTest.kt",
+            "method",
+            "rejectsBlankNames",
+            None,
+        );
+        assert!(is_test_symbol(&sym), "Kotlin method in test file");
+
+        let sym = make_symbol(
+            "src/main/kotlin/com/acme/UserService.kt",
+            "method",
+            "rename",
+            None,
+        );
+        assert!(!is_test_symbol(&sym), "Not a test");
+    }
+
     #[test]
     fn test_is_test_file() {
         assert!(is_test_file("tests/test_core.py"));
         assert!(is_test_file("src/core.test.ts"));
         assert!(is_test_file("lib/__tests__/util.js"));
         assert!(is_test_file("src/main_test.go"));
+        assert!(is_test_file("tests/integration.rs"));
         assert!(!is_test_file("src/core.py"));
         assert!(!is_test_file("lib/utils.js"));
     }
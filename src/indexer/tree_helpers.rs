@@ -79,6 +79,46 @@ pub fn module_symbol_fallback(
     )
 }
 
+/// First named child of `node` with the given kind.
+pub fn child_of_kind<'t>(node: Node<'t>, kind: &str) -> Option<Node<'t>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|child| child.kind() == kind)
+}
+
+/// Named children of `node` with the given kind.
+pub fn children_of_kind<'t>(node: Node<'t>, kind: &str) -> Vec<Node<'t>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|child| child.kind() == kind)
+        .collect()
+}
+
+/// Builds a `SymbolInput` spanning `node`.
+pub fn symbol_for_node(
+    kind: &str,
+    name: &str,
+    qualname: &str,
+    node: Node<'_>,
+    signature: Option<String>,
+    docstring: Option<String>,
+) -> SymbolInput {
+    let (start_line, start_col, end_line, end_col, start_byte, end_byte) = span(node);
+    SymbolInput {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        qualname: qualname.to_string(),
+        start_line,
+        start_col,
+        end_line,
+        end_col,
+        start_byte,
+        end_byte,
+        signature,
+        docstring,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,1033 @@
+//! Receiver types for method calls.
+//!
+//! Extractors record a method call as written (`_svc.DeployAsync`,
+//! `self.repo.save`, `s.repo.Save`), which name-based lookup can only bind by
+//! method name. This pass walks the extractor's parse tree of a file, tracks the declared types of
+//! fields, parameters and locals (constructor injection, type hints and
+//! annotations, struct fields, `impl` blocks, `new X()` / `X{}` / `X::new()`
+//! initializers) and tags each `receiver.method(...)` CALLS edge with the
+//! receiver's type name in `detail` (`{"receiver_type": "IDeployService"}`).
+//! `Db::resolve_typed_calls` later binds the tagged edges to the method on
+//! that type once every file's symbols are stored.
+
+use crate::indexer::extract::EdgeInput;
+use crate::indexer::tree_helpers::node_text;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+use tree_sitter::{Language, Node, Parser, Tree};
+
+/// Key under which the receiver type is stored in a CALLS edge's `detail`.
+pub const RECEIVER_TYPE_KEY: &str = "receiver_type";
+
+/// A `receiver.method(...)` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiverCall {
+    /// 1-based line of the call expression.
+    pub line: i64,
+    pub method: String,
+    /// `None` when the receiver's type could not be inferred.
+    pub receiver_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lang {
+    CSharp,
+    Python,
+    TypeScript,
+    JavaScript,
+    Go,
+    Rust,
+}
+
+fn grammar(language: &str) -> Option<(Lang, Language)> {
+    let (lang, grammar) = match language {
+        "csharp" => (Lang::CSharp, tree_sitter_c_sharp::LANGUAGE),
+        "python" => (Lang::Python, tree_sitter_python::LANGUAGE),
+        "typescript" => (
+            Lang::TypeScript,
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        ),
+        "tsx" => (Lang::TypeScript, tree_sitter_typescript::LANGUAGE_TSX),
+        "javascript" => (Lang::JavaScript, tree_sitter_javascript::LANGUAGE),
+        "go" => (Lang::Go, tree_sitter_go::LANGUAGE),
+        "rust" => (Lang::Rust, tree_sitter_rust::LANGUAGE),
+        _ => return None,
+    };
+    Some((lang, grammar.into()))
+}
+
+/// Languages whose types can see each other's members.
+pub fn language_family(language: &str) -> &'static str {
+    match language {
+        "typescript" | "tsx" | "javascript" => "js",
+        "java" | "kotlin" => "jvm",
+        "csharp" => "csharp",
+        "python" => "python",
+        "go" => "go",
+        "rust" => "rust",
+        _ => "other",
+    }
+}
+
+/// Method name of a callee as recorded on an edge (`self.repo.save` → `save`).
+pub fn callee_method(callee: &str) -> &str {
+    let callee = callee.split('(').next().unwrap_or(callee);
+    callee.rsplit(['.', ':']).next().unwrap_or(callee).trim()
+}
+
+/// Tag the CALLS edges of one file with the receiver types found in `tree`,
+/// the extractor's parse of `source`. Rust leaves unresolved callees in
+/// `detail`; those are moved to `target_qualname` so the edge can be bound
+/// later.
+pub fn annotate_edges(language: &str, source: &str, tree: &Tree, edges: &mut [EdgeInput]) {
+    let calls = tree_receiver_calls(language, source, tree);
+    if calls.iter().all(|call| call.receiver_type.is_none()) {
+        return;
+    }
+    // Untyped calls stay queued so that calls sharing a line and method name
+    // are matched to edges in source order.
+    let mut pending: HashMap<(i64, String), VecDeque<Option<&str>>> = HashMap::new();
+    for call in &calls {
+        pending
+            .entry((call.line, call.method.clone()))
+            .or_default()
+            .push_back(call.receiver_type.as_deref());
+    }
+    for edge in edges.iter_mut().filter(|edge| edge.kind == "CALLS") {
+        let Some(line) = edge.evidence_start_line else {
+            continue;
+        };
+        let raw_callee = edge.target_qualname.is_none();
+        let Some(method) = edge
+            .target_qualname
+            .as_deref()
+            .or(edge.detail.as_deref())
+            .map(|callee| callee_method(callee).to_string())
+        else {
+            continue;
+        };
+        let mut detail = match edge.detail.as_deref() {
+            None => Map::new(),
+            Some(_) if raw_callee => Map::new(),
+            Some(text) => match serde_json::from_str::<Value>(text) {
+                Ok(Value::Object(map)) => map,
+                _ => continue,
+            },
+        };
+        let Some(receiver_type) = pending
+            .get_mut(&(line, method))
+            .and_then(VecDeque::pop_front)
+            .flatten()
+        else {
+            continue;
+        };
+        if raw_callee {
+            edge.target_qualname = edge.detail.take();
+        }
+        detail.insert(
+            RECEIVER_TYPE_KEY.to_string(),
+            Value::String(receiver_type.to_string()),
+        );
+        edge.detail = Some(Value::Object(detail).to_string());
+    }
+}
+
+/// Every method call in `source`, with its receiver type where it can be
+/// inferred.
+pub fn receiver_calls(language: &str, source: &str) -> Vec<ReceiverCall> {
+    let Some((_, grammar)) = grammar(language) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(&grammar).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+    tree_receiver_calls(language, source, &tree)
+}
+
+/// `receiver_calls` over an existing parse of `source`.
+fn tree_receiver_calls(language: &str, source: &str, tree: &Tree) -> Vec<ReceiverCall> {
+    let Some((lang, _)) = grammar(language) else {
+        return Vec::new();
+    };
+    let mut scanner = Scanner {
+        lang,
+        source,
+        members: HashMap::new(),
+        scopes: vec![HashMap::new()],
+        types: Vec::new(),
+        calls: Vec::new(),
+        record_calls: false,
+    };
+    // Members can be declared after the methods that use them (C# fields at
+    // the bottom of a class, Python attributes set in a later method), so the
+    // first walk only collects them.
+    scanner.visit(tree.root_node());
+    scanner.record_calls = true;
+    scanner.scopes = vec![HashMap::new()];
+    scanner.visit(tree.root_node());
+    scanner.calls
+}
+
+struct Scanner<'a> {
+    lang: Lang,
+    source: &'a str,
+    /// Field and property types per declaring type name.
+    members: HashMap<String, HashMap<String, String>>,
+    /// Variable types, innermost scope last.
+    scopes: Vec<HashMap<String, String>>,
+    /// Enclosing class / impl / receiver types, innermost last.
+    types: Vec<String>,
+    calls: Vec<ReceiverCall>,
+    record_calls: bool,
+}
+
+impl<'a> Scanner<'a> {
+    fn text(&self, node: Node<'_>) -> String {
+        node_text(node, self.source)
+    }
+
+    fn field(&self, node: Node<'_>, name: &str) -> Option<String> {
+        node.child_by_field_name(name).map(|child| self.text(child))
+    }
+
+    fn visit(&mut self, node: Node<'_>) {
+        let kind = node.kind();
+        let type_name = self.type_context(node);
+        let new_scope = type_name.is_some() || self.opens_scope(kind);
+        if let Some(name) = &type_name {
+            self.types.push(name.clone());
+        }
+        if new_scope {
+            self.scopes.push(HashMap::new());
+            if let Some(name) = &type_name {
+                for this in ["this", "self"] {
+                    self.bind(this, name.clone());
+                }
+            }
+        }
+
+        self.declare(node);
+        if self.record_calls {
+            self.record_call(node);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.visit(child);
+        }
+
+        if new_scope {
+            self.scopes.pop();
+        }
+        if type_name.is_some() {
+            self.types.pop();
+        }
+    }
+
+    /// The type whose members are in scope inside `node`, if it declares one.
+    fn type_context(&self, node: Node<'_>) -> Option<String> {
+        match (self.lang, node.kind()) {
+            (
+                Lang::CSharp,
+                "class_declaration"
+                | "struct_declaration"
+                | "record_declaration"
+                | "interface_declaration",
+            )
+            | (Lang::Python, "class_definition")
+            | (
+                Lang::TypeScript | Lang::JavaScript,
+                "class_declaration" | "abstract_class_declaration" | "class",
+            ) => self.field(node, "name"),
+            (Lang::Rust, "impl_item") => node
+                .child_by_field_name("type")
+                .and_then(|ty| self.type_name(ty)),
+            (Lang::Rust, "trait_item") => self.field(node, "name"),
+            (Lang::Go, "method_declaration") => {
+                let receiver = node.child_by_field_name("receiver")?;
+                let mut cursor = receiver.walk();
+                let param = receiver
+                    .named_children(&mut cursor)
+                    .find(|child| child.kind() == "parameter_declaration")?;
+                self.type_name(param.child_by_field_name("type")?)
+            }
+            _ => None,
+        }
+    }
+
+    fn opens_scope(&self, kind: &str) -> bool {
+        match self.lang {
+            Lang::CSharp => matches!(
+                kind,
+                "method_declaration"
+                    | "constructor_declaration"
+                    | "local_function_statement"
+                    | "lambda_expression"
+                    | "block"
+            ),
+            Lang::Python => matches!(kind, "function_definition" | "lambda"),
+            Lang::TypeScript | Lang::JavaScript => matches!(
+                kind,
+                "method_definition"
+                    | "function_declaration"
+                    | "function_expression"
+                    | "function"
+                    | "arrow_function"
+                    | "statement_block"
+            ),
+            Lang::Go => matches!(
+                kind,
+                "function_declaration" | "method_declaration" | "func_literal" | "block"
+            ),
+            Lang::Rust => matches!(kind, "function_item" | "closure_expression" | "block"),
+        }
+    }
+
+    fn bind(&mut self, name: &str, type_name: String) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), type_name);
+        }
+    }
+
+    fn bind_member(&mut self, owner: Option<String>, name: &str, type_name: String) {
+        if let Some(owner) = owner.or_else(|| self.types.last().cloned()) {
+            self.members
+                .entry(owner)
+                .or_default()
+                .insert(name.to_string(), type_name);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).cloned())
+    }
+
+    fn member(&self, owner: &str, name: &str) -> Option<String> {
+        self.members.get(owner)?.get(name).cloned()
+    }
+
+    /// Record the bindings introduced by a declaration node.
+    fn declare(&mut self, node: Node<'_>) {
+        match (self.lang, node.kind()) {
+            // C#
+            (Lang::CSharp, "parameter") => {
+                if let (Some(ty), Some(name)) = (
+                    node.child_by_field_name("type")
+                        .and_then(|t| self.type_name(t)),
+                    self.field(node, "name"),
+                ) {
+                    // Primary constructor parameters are captured as members.
+                    if node
+                        .parent()
+                        .and_then(|list| list.parent())
+                        .is_some_and(|owner| self.type_context(owner).is_some())
+                    {
+                        self.bind_member(None, &name, ty.clone());
+                    }
+                    self.bind(&name, ty);
+                }
+            }
+            (Lang::CSharp, "variable_declaration") => {
+                let is_field = node
+                    .parent()
+                    .is_some_and(|parent| parent.kind() == "field_declaration");
+                let declared = node
+                    .child_by_field_name("type")
+                    .filter(|ty| ty.kind() != "implicit_type")
+                    .and_then(|ty| self.type_name(ty));
+                let mut cursor = node.walk();
+                for declarator in node.named_children(&mut cursor) {
+                    if declarator.kind() != "variable_declarator" {
+                        continue;
+                    }
+                    let Some(name) = self.field(declarator, "name").or_else(|| {
+                        declarator
+                            .named_child(0)
+                            .filter(|child| child.kind() == "identifier")
+                            .map(|child| self.text(child))
+                    }) else {
+                        continue;
+                    };
+                    let ty = declared.clone().or_else(|| {
+                        let mut inner = declarator.walk();
+                        let value = declarator
+                            .named_children(&mut inner)
+                            .find(|child| child.kind() != "identifier");
+                        value.and_then(|value| self.expr_type(value))
+                    });
+                    if let Some(ty) = ty {
+                        if is_field {
+                            self.bind_member(None, &name, ty);
+                        } else {
+                            self.bind(&name, ty);
+                        }
+                    }
+                }
+            }
+            (Lang::CSharp, "property_declaration") => {
+                if let (Some(ty), Some(name)) = (
+                    node.child_by_field_name("type")
+                        .and_then(|t| self.type_name(t)),
+                    self.field(node, "name"),
+                ) {
+                    self.bind_member(None, &name, ty);
+                }
+            }
+
+            // Python
+            (Lang::Python, "typed_parameter") => {
+                let name = node
+                    .named_child(0)
+                    .filter(|child| child.kind() == "identifier")
+                    .map(|child| self.text(child));
+                if let (Some(name), Some(ty)) = (
+                    name,
+                    node.child_by_field_name("type")
+                        .and_then(|t| self.type_name(t)),
+                ) {
+                    self.bind(&name, ty);
+                }
+            }
+            (Lang::Python, "typed_default_parameter") => {
+                if let (Some(name), Some(ty)) = (
+                    self.field(node, "name"),
+                    node.child_by_field_name("type")
+                        .and_then(|t| self.type_name(t)),
+                ) {
+                    self.bind(&name, ty);
+                }
+            }
+            (Lang::Python, "assignment") => {
+                let Some(left) = node.child_by_field_name("left") else {
+                    return;
+                };
+                let ty = node
+                    .child_by_field_name("type")
+                    .and_then(|t| self.type_name(t))
+                    .or_else(|| {
+                        node.child_by_field_name("right")
+                            .and_then(|right| self.expr_type(right))
+                    });
+                let Some(ty) = ty else {
+                    return;
+                };
+                self.assign(left, ty, "attribute", "object", "attribute");
+            }
+
+            // TypeScript / JavaScript
+            (Lang::TypeScript, "required_parameter" | "optional_parameter") => {
+                let Some(pattern) = node.child_by_field_name("pattern") else {
+                    return;
+                };
+                let Some(ty) = node
+                    .child_by_field_name("type")
+                    .and_then(|t| self.type_name(t))
+                else {
+                    return;
+                };
+                let name = self.text(pattern);
+                let mut cursor = node.walk();
+                let is_property = node
+                    .children(&mut cursor)
+                    .any(|child| matches!(child.kind(), "accessibility_modifier" | "readonly"));
+                if is_property {
+                    self.bind_member(None, &name, ty.clone());
+                }
+                self.bind(&name, ty);
+            }
+            (
+                Lang::TypeScript | Lang::JavaScript,
+                "public_field_definition" | "field_definition",
+            ) => {
+                let name = self
+                    .field(node, "name")
+                    .or_else(|| self.field(node, "property"));
+                let ty = node
+                    .child_by_field_name("type")
+                    .and_then(|t| self.type_name(t))
+                    .or_else(|| {
+                        node.child_by_field_name("value")
+                            .and_then(|value| self.expr_type(value))
+                    });
+                if let (Some(name), Some(ty)) = (name, ty) {
+                    self.bind_member(None, &name, ty);
+                }
+            }
+            (Lang::TypeScript | Lang::JavaScript, "variable_declarator") => {
+                let Some(name) = node
+                    .child_by_field_name("name")
+                    .filter(|name| name.kind() == "identifier")
+                    .map(|name| self.text(name))
+                else {
+                    return;
+                };
+                let ty = node
+                    .child_by_field_name("type")
+                    .and_then(|t| self.type_name(t))
+                    .or_else(|| {
+                        node.child_by_field_name("value")
+                            .and_then(|value| self.expr_type(value))
+                    });
+                if let Some(ty) = ty {
+                    self.bind(&name, ty);
+                }
+            }
+            (Lang::TypeScript | Lang::JavaScript, "assignment_expression") => {
+                if let (Some(left), Some(ty)) = (
+                    node.child_by_field_name("left"),
+                    node.child_by_field_name("right")
+                        .and_then(|right| self.expr_type(right)),
+                ) {
+                    self.assign(left, ty, "member_expression", "object", "property");
+                }
+            }
+
+            // Go
+            (Lang::Go, "type_spec") => {
+                let (Some(owner), Some(body)) =
+                    (self.field(node, "name"), node.child_by_field_name("type"))
+                else {
+                    return;
+                };
+                if body.kind() != "struct_type" {
+                    return;
+                }
+                let mut cursor = body.walk();
+                for list in body.named_children(&mut cursor) {
+                    let mut inner = list.walk();
+                    for field in list.named_children(&mut inner) {
+                        if field.kind() != "field_declaration" {
+                            continue;
+                        }
+                        let Some(ty) = field
+                            .child_by_field_name("type")
+                            .and_then(|t| self.type_name(t))
+                        else {
+                            continue;
+                        };
+                        let mut names = field.walk();
+                        for name in field.children_by_field_name("name", &mut names) {
+                            let name = self.text(name);
+                            self.bind_member(Some(owner.clone()), &name, ty.clone());
+                        }
+                    }
+                }
+            }
+            (Lang::Go, "parameter_declaration" | "var_spec") => {
+                let ty = node
+                    .child_by_field_name("type")
+                    .and_then(|t| self.type_name(t))
+                    .or_else(|| {
+                        node.child_by_field_name("value")
+                            .and_then(|values| values.named_child(0))
+                            .and_then(|value| self.expr_type(value))
+                    });
+                let Some(ty) = ty else {
+                    return;
+                };
+                let mut cursor = node.walk();
+                let names: Vec<String> = node
+                    .children_by_field_name("name", &mut cursor)
+                    .map(|name| self.text(name))
+                    .collect();
+                for name in names {
+                    self.bind(&name, ty.clone());
+                }
+            }
+            (Lang::Go, "short_var_declaration") => {
+                let (Some(left), Some(right)) = (
+                    node.child_by_field_name("left"),
+                    node.child_by_field_name("right"),
+                ) else {
+                    return;
+                };
+                let mut left_cursor = left.walk();
+                let mut right_cursor = right.walk();
+                let names: Vec<Node<'_>> = left.named_children(&mut left_cursor).collect();
+                let values: Vec<Node<'_>> = right.named_children(&mut right_cursor).collect();
+                if names.len() != values.len() {
+                    return;
+                }
+                for (name, value) in names.into_iter().zip(values) {
+                    if let Some(ty) = self.expr_type(value) {
+                        let name = self.text(name);
+                        self.bind(&name, ty);
+                    }
+                }
+            }
+
+            // Rust
+            (Lang::Rust, "struct_item") => {
+                let (Some(owner), Some(body)) =
+                    (self.field(node, "name"), node.child_by_field_name("body"))
+                else {
+                    return;
+                };
+                let mut cursor = body.walk();
+                for field in body.named_children(&mut cursor) {
+                    if field.kind() != "field_declaration" {
+                        continue;
+                    }
+                    if let (Some(name), Some(ty)) = (
+                        self.field(field, "name"),
+                        field
+                            .child_by_field_name("type")
+                            .and_then(|t| self.type_name(t)),
+                    ) {
+                        self.bind_member(Some(owner.clone()), &name, ty);
+                    }
+                }
+            }
+            (Lang::Rust, "parameter" | "let_declaration") => {
+                let Some(pattern) = node
+                    .child_by_field_name("pattern")
+                    .map(|pattern| {
+                        if pattern.kind() == "mut_pattern" {
+                            pattern.named_child(0).unwrap_or(pattern)
+                        } else {
+                            pattern
+                        }
+                    })
+                    .filter(|pattern| pattern.kind() == "identifier")
+                else {
+                    return;
+                };
+                let ty = node
+                    .child_by_field_name("type")
+                    .and_then(|t| self.type_name(t))
+                    .or_else(|| {
+                        node.child_by_field_name("value")
+                            .and_then(|value| self.expr_type(value))
+                    });
+                if let Some(ty) = ty {
+                    let name = self.text(pattern);
+                    self.bind(&name, ty);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Bind `left = <value of type ty>` for a plain name or a `self.x` /
+    /// `this.x` member.
+    fn assign(&mut self, left: Node<'_>, ty: String, member_kind: &str, object: &str, name: &str) {
+        if left.kind() == "identifier" {
+            let left = self.text(left);
+            self.bind(&left, ty);
+        } else if left.kind() == member_kind
+            && let Some(object) = left.child_by_field_name(object)
+            && matches!(self.text(object).as_str(), "self" | "this")
+            && let Some(attribute) = self.field(left, name)
+        {
+            self.bind_member(None, &attribute, ty);
+        }
+    }
+
+    fn record_call(&mut self, node: Node<'_>) {
+        let (call_kind, access_kind, receiver_field, name_field) = match self.lang {
+            Lang::CSharp => (
+                "invocation_expression",
+                "member_access_expression",
+                "expression",
+                "name",
+            ),
+            Lang::Python => ("call", "attribute", "object", "attribute"),
+            Lang::TypeScript | Lang::JavaScript => {
+                ("call_expression", "member_expression", "object", "property")
+            }
+            Lang::Go => ("call_expression", "selector_expression", "operand", "field"),
+            Lang::Rust => ("call_expression", "field_expression", "value", "field"),
+        };
+        if node.kind() != call_kind {
+            return;
+        }
+        let Some(access) = node
+            .child_by_field_name("function")
+            .filter(|function| function.kind() == access_kind)
+        else {
+            return;
+        };
+        let (Some(receiver), Some(method)) = (
+            access.child_by_field_name(receiver_field),
+            access.child_by_field_name(name_field),
+        ) else {
+            return;
+        };
+        // C# generic calls (`x.Get<T>()`) name a generic_name node.
+        let method = method
+            .child_by_field_name("name")
+            .or_else(|| {
+                (method.kind() == "generic_name")
+                    .then(|| method.named_child(0))
+                    .flatten()
+            })
+            .unwrap_or(method);
+        self.calls.push(ReceiverCall {
+            line: node.start_position().row as i64 + 1,
+            method: self.text(method),
+            receiver_type: self.expr_type(receiver),
+        });
+    }
+
+    /// Static type of an expression, when the pass can tell.
+    fn expr_type(&self, node: Node<'_>) -> Option<String> {
+        match (self.lang, node.kind()) {
+            (_, "identifier") => {
+                let name = self.text(node);
+                self.lookup(&name).or_else(|| {
+                    // C# fields and properties are in scope without `this.`,
+                    // and Rust unit structs are values of their own type.
+                    match self.lang {
+                        Lang::CSharp => self.member(self.types.last()?, &name),
+                        Lang::Rust if name.starts_with(|c: char| c.is_ascii_uppercase()) => {
+                            Some(name)
+                        }
+                        _ => None,
+                    }
+                })
+            }
+            (_, "this" | "self" | "this_expression") => self.types.last().cloned(),
+            (Lang::Python | Lang::JavaScript | Lang::TypeScript, "parenthesized_expression")
+            | (Lang::CSharp | Lang::Go | Lang::Rust, "parenthesized_expression") => {
+                self.expr_type(node.named_child(0)?)
+            }
+            (Lang::CSharp, "member_access_expression") => {
+                self.member_type(node, "expression", "name")
+            }
+            (Lang::Python, "attribute") => self.member_type(node, "object", "attribute"),
+            (Lang::TypeScript | Lang::JavaScript, "member_expression") => {
+                self.member_type(node, "object", "property")
+            }
+            (Lang::Go, "selector_expression") => self.member_type(node, "operand", "field"),
+            (Lang::Rust, "field_expression") => self.member_type(node, "value", "field"),
+
+            // Constructors
+            (Lang::CSharp, "object_creation_expression") => {
+                self.type_name(node.child_by_field_name("type")?)
+            }
+            (Lang::TypeScript | Lang::JavaScript, "new_expression") => {
+                self.type_name(node.child_by_field_name("constructor")?)
+            }
+            (Lang::Python, "call") => {
+                let function = node.child_by_field_name("function")?;
+                if !matches!(function.kind(), "identifier" | "attribute") {
+                    return None;
+                }
+                let name = self.text(function);
+                let last = name.rsplit('.').next()?;
+                last.starts_with(|c: char| c.is_ascii_uppercase())
+                    .then(|| name.clone())
+            }
+            (Lang::Go, "composite_literal") => self.type_name(node.child_by_field_name("type")?),
+            (Lang::Go, "unary_expression") => self.expr_type(node.child_by_field_name("operand")?),
+            (Lang::Go, "call_expression") => {
+                // `NewRepo(...)` conventionally returns a `*Repo`.
+                let function = self.text(node.child_by_field_name("function")?);
+                let last = function.rsplit('.').next()?;
+                let ty = last.strip_prefix("New")?;
+                ty.starts_with(|c: char| c.is_ascii_uppercase())
+                    .then(|| ty.to_string())
+            }
+            (Lang::Rust, "struct_expression") => self.type_name(node.child_by_field_name("name")?),
+            (Lang::Rust, "call_expression") => {
+                // `Repo::new(..)`, `Repo::default()`
+                let function = node.child_by_field_name("function")?;
+                if function.kind() != "scoped_identifier" {
+                    return None;
+                }
+                let path = self.text(function.child_by_field_name("path")?);
+                let last = path.rsplit("::").next()?;
+                last.starts_with(|c: char| c.is_ascii_uppercase())
+                    .then(|| path.clone())
+            }
+            (Lang::Rust, "reference_expression" | "try_expression") => {
+                self.expr_type(node.named_child(0)?)
+            }
+            (Lang::CSharp | Lang::TypeScript | Lang::JavaScript, "await_expression") => {
+                let inner = node.named_child(node.named_child_count().checked_sub(1)?)?;
+                // Only `await new X()`-style expressions have a known type.
+                matches!(
+                    inner.kind(),
+                    "object_creation_expression" | "new_expression"
+                )
+                .then(|| self.expr_type(inner))
+                .flatten()
+            }
+            _ => None,
+        }
+    }
+
+    fn member_type(&self, node: Node<'_>, object: &str, name: &str) -> Option<String> {
+        let owner = self.expr_type(node.child_by_field_name(object)?)?;
+        let name = self.field(node, name)?;
+        let owner = owner.rsplit(['.', ':']).next().unwrap_or(&owner);
+        self.member(owner, &name)
+    }
+
+    /// Normalize a type node to the name methods are looked up on:
+    /// references, pointers, nullability, optional wrappers, smart pointers
+    /// and generic arguments are stripped (`&mut Arc<Repo>` → `Repo`,
+    /// `Optional[Repo]` → `Repo`, `List<Order>` → `List`).
+    fn type_name(&self, node: Node<'_>) -> Option<String> {
+        clean_type(&self.text(node))
+    }
+}
+
+fn clean_type(raw: &str) -> Option<String> {
+    let mut ty = raw.trim().trim_start_matches(':').trim();
+    loop {
+        let before = ty;
+        ty = ty
+            .trim_start_matches('&')
+            .trim_start_matches('*')
+            .trim_start();
+        if ty.starts_with('\'') {
+            ty = ty.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
+        }
+        for prefix in ["mut ", "dyn ", "impl ", "readonly ", "ref ", "const "] {
+            ty = ty.strip_prefix(prefix).unwrap_or(ty).trim_start();
+        }
+        ty = ty.trim_end_matches('?').trim();
+        ty = ty.trim_matches(['"', '\'']);
+        // `Repo | null`, `Repo | undefined`
+        if let Some((first, _)) = ty.split_once('|') {
+            ty = first.trim();
+        }
+        for wrapper in [
+            "Optional[",
+            "Box<",
+            "Arc<",
+            "Rc<",
+            "Ref<",
+            "RefMut<",
+            "Mutex<",
+            "RwLock<",
+        ] {
+            if let Some(inner) = ty.strip_prefix(wrapper) {
+                ty = inner.strip_suffix([']', '>']).unwrap_or(inner).trim();
+            }
+        }
+        if ty == before {
+            break;
+        }
+    }
+    let end = ty.find(['<', '[', '(', ' ']).unwrap_or(ty.len());
+    let ty = ty[..end].trim_end_matches(['.', ':']);
+    let valid = ty
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && ty
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | ':'));
+    valid.then(|| ty.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(language: &str, source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser.set_language(&grammar(language).unwrap().1).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn calls(language: &str, source: &str) -> Vec<(i64, String, String)> {
+        receiver_calls(language, source)
+            .into_iter()
+            .filter_map(|call| Some((call.line, call.method, call.receiver_type?)))
+            .collect()
+    }
+
+    fn call(line: i64, method: &str, ty: &str) -> (i64, String, String) {
+        (line, method.to_string(), ty.to_string())
+    }
+
+    #[test]
+    fn clean_type_strips_wrappers() {
+        assert_eq!(clean_type("&mut Arc<Repo>").as_deref(), Some("Repo"));
+        assert_eq!(clean_type("Optional[Repo]").as_deref(), Some("Repo"));
+        assert_eq!(clean_type(": Repo | null").as_deref(), Some("Repo"));
+        assert_eq!(clean_type("List<Order>").as_deref(), Some("List"));
+        assert_eq!(clean_type("*pkg.Repo").as_deref(), Some("pkg.Repo"));
+        assert_eq!(clean_type("IRepo?").as_deref(), Some("IRepo"));
+        assert_eq!(clean_type("&'a Cache").as_deref(), Some("Cache"));
+        assert_eq!(clean_type("[]int"), None);
+    }
+
+    #[test]
+    fn csharp_fields_parameters_and_locals() {
+        let source = "class Controller {
+    public Controller(IDeployService svc) { _svc = svc; }
+    public void Run(Other other) {
+        _svc.DeployAsync(\"x\");
+        other.DeployAsync(\"y\");
+        var local = new DeployService();
+        local.DeployAsync(\"z\");
+        this._svc.Get<int>();
+    }
+    private readonly IDeployService _svc;
+}";
+        assert_eq!(
+            calls("csharp", source),
+            vec![
+                call(4, "DeployAsync", "IDeployService"),
+                call(5, "DeployAsync", "Other"),
+                call(7, "DeployAsync", "DeployService"),
+                call(8, "Get", "IDeployService"),
+            ]
+        );
+    }
+
+    #[test]
+    fn python_hints_and_self_attributes() {
+        let source = "class Service:
+    def handle(self, c: Cache):
+        self.repo.save(1)
+        c.save(2)
+        r = Repo()
+        r.save(3)
+
+    def __init__(self, repo: Repo):
+        self.repo = repo
+";
+        assert_eq!(
+            calls("python", source),
+            vec![
+                call(3, "save", "Repo"),
+                call(4, "save", "Cache"),
+                call(6, "save", "Repo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn go_struct_fields_and_locals() {
+        let source = "package main
+type Service struct { repo *Repo }
+func (s *Service) Handle(c *Cache) {
+	s.repo.Save(1)
+	c.Save(2)
+	r := &Repo{}
+	r.Save(3)
+	var k Cache
+	k.Save(4)
+	n := NewStore()
+	n.Put()
+}
+";
+        assert_eq!(
+            calls("go", source),
+            vec![
+                call(4, "Save", "Repo"),
+                call(5, "Save", "Cache"),
+                call(7, "Save", "Repo"),
+                call(9, "Save", "Cache"),
+                call(11, "Put", "Store"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rust_impl_fields_and_lets() {
+        let source = "struct Service { repo: Arc<Repo> }
+impl Service {
+    fn handle(&self, c: &Cache) {
+        self.repo.save(1);
+        c.save(2);
+        let mut r = Repo::new();
+        r.save(3);
+        let k = Cache {};
+        k.save(4);
+        let u = Unit;
+        u.save(5);
+    }
+}
+";
+        assert_eq!(
+            calls("rust", source),
+            vec![
+                call(4, "save", "Repo"),
+                call(5, "save", "Cache"),
+                call(7, "save", "Repo"),
+                call(9, "save", "Cache"),
+                call(11, "save", "Unit"),
+            ]
+        );
+    }
+
+    #[test]
+    fn javascript_constructors_and_this_fields() {
+        let source = "class Service {
+  cache = new Cache();
+  constructor(repo) { this.repo = new Repo(); }
+  handle() { this.repo.save(1); this.cache.save(2); const r = new Repo(); r.save(3); }
+}
+";
+        assert_eq!(
+            calls("javascript", source),
+            vec![
+                call(4, "save", "Repo"),
+                call(4, "save", "Cache"),
+                call(4, "save", "Repo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn annotate_edges_tags_matching_calls() {
+        let source =
+            "struct S { repo: Repo }\nimpl S {\n    fn f(&self) { self.repo.save(1); }\n}\n";
+        let mut edges = vec![
+            EdgeInput {
+                kind: "CALLS".to_string(),
+                detail: Some("self.repo.save".to_string()),
+                evidence_start_line: Some(3),
+                ..Default::default()
+            },
+            EdgeInput {
+                kind: "CALLS".to_string(),
+                target_qualname: Some("crate::other".to_string()),
+                evidence_start_line: Some(3),
+                ..Default::default()
+            },
+        ];
+        annotate_edges("rust", source, &parse("rust", source), &mut edges);
+        assert_eq!(edges[0].target_qualname.as_deref(), Some("self.repo.save"));
+        assert_eq!(
+            edges[0].detail.as_deref(),
+            Some(r#"{"receiver_type":"Repo"}"#)
+        );
+        assert!(edges[1].detail.is_none());
+        assert_eq!(callee_method("svc.Service.repo.save"), "save");
+        assert_eq!(callee_method("crate::Repo::save"), "save");
+    }
+
+    #[test]
+    fn annotate_edges_keeps_untyped_calls_in_order() {
+        let source = "def f(x, r: Repo):\n    x.save(1); r.save(2)\n";
+        let mut edges: Vec<EdgeInput> = ["x.save", "r.save"]
+            .into_iter()
+            .map(|callee| EdgeInput {
+                kind: "CALLS".to_string(),
+                target_qualname: Some(callee.to_string()),
+                evidence_start_line: Some(2),
+                ..Default::default()
+            })
+            .collect();
+        annotate_edges("python", source, &parse("python", source), &mut edges);
+        assert!(edges[0].detail.is_none());
+        assert_eq!(
+            edges[1].detail.as_deref(),
+            Some(r#"{"receiver_type":"Repo"}"#)
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::db::{Db, SymbolRefRecord};
 use crate::indexer::extract::EdgeInput;
+use crate::indexer::rules::ProjectRules;
 use crate::indexer::scan::ScannedFile;
 use crate::util;
 use anyhow::Result;
@@ -26,7 +27,23 @@ pub fn link_cross_language_refs(
     db: &mut Db,
     files: &[ScannedFile],
     clear_existing: bool,
+    rules: &ProjectRules,
     graph_version: i64,
+) -> Result<usize> {
+    link_cross_language_refs_with(db, files, clear_existing, rules, graph_version, |file| {
+        util::read_to_string(&file.abs_path)
+    })
+}
+
+/// `link_cross_language_refs` with sources from `read` instead of the
+/// working tree.
+pub fn link_cross_language_refs_with(
+    db: &mut Db,
+    files: &[ScannedFile],
+    clear_existing: bool,
+    rules: &ProjectRules,
+    graph_version: i64,
+    mut read: impl FnMut(&ScannedFile) -> Result<String>,
 ) -> Result<usize> {
     if clear_existing {
         db.delete_edges_by_kind(XREF_KIND, graph_version)?;
@@ -42,9 +59,9 @@ pub fn link_cross_language_refs(
         let Some(record) = db.get_file_by_path(&file.rel_path)? else {
             continue;
         };
-        let source = util::read_to_string(&file.abs_path)?;
+        let source = read(file)?;
         let xref_edges = collect_xref_edges(db, &index, file, &source, graph_version)?;
-        let route_edges = collect_route_edges(db, file, &source, graph_version)?;
+        let route_edges = collect_route_edges(db, file, &source, rules, graph_version)?;
         if xref_edges.is_empty() && route_edges.is_empty() {
             continue;
         }
@@ -64,7 +81,10 @@ pub fn link_cross_language_refs(
 }
 
 fn should_scan_file(file: &ScannedFile) -> bool {
-    file.language != "yaml" && file.language != "bicep"
+    !matches!(
+        file.language.as_str(),
+        "yaml" | "json" | "bicep" | "terraform" | "graphql" | "dockerfile"
+    )
 }
 
 fn collect_xref_edges(
@@ -142,6 +162,7 @@ fn collect_route_edges(
     db: &Db,
     file: &ScannedFile,
     source: &str,
+    rules: &ProjectRules,
     graph_version: i64,
 ) -> Result<Vec<EdgeInput>> {
     let literals = scan_string_literals(source);
@@ -155,6 +176,7 @@ fn collect_route_edges(
         let Some(route) = normalize_route_literal(&literal.text) else {
             continue;
         };
+        let route = rules.strip_base_path(route);
         let Some(source_qualname) = lookup_source_qualname(
             db,
             &file.rel_path,
@@ -551,15 +573,15 @@ fn is_token_char(ch: char) -> bool {
 }
 
 #[derive(Clone)]
-struct StringLiteral {
-    text: String,
-    start_line: i64,
-    end_line: i64,
-    start_byte: i64,
-    end_byte: i64,
+pub(crate) struct StringLiteral {
+    pub(crate) text: String,
+    pub(crate) start_line: i64,
+    pub(crate) end_line: i64,
+    pub(crate) start_byte: i64,
+    pub(crate) end_byte: i64,
 }
 
-fn scan_string_literals(source: &str) -> Vec<StringLiteral> {
+pub(crate) fn scan_string_literals(source: &str) -> Vec<StringLiteral> {
     let bytes = source.as_bytes();
     let mut out = Vec::new();
     let mut i = 0;
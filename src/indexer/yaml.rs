@@ -1,15 +1,31 @@
+use crate::indexer::asyncapi;
+use crate::indexer::compose;
 use crate::indexer::config::{self, CONFIG_READ_KIND, CONFIG_SOURCE_KIND};
+use crate::indexer::dockerfile;
 use crate::indexer::extract::{EdgeInput, ExtractedFile, LanguageExtractor, SymbolInput};
+use crate::indexer::helm::{self, ChartFile};
+use crate::indexer::openapi::{self, SpecSource};
 use crate::indexer::tree_helpers::module_symbol_fallback;
 use anyhow::Result;
+use serde_json::json;
 use serde_yaml_ng::Value;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub struct YamlExtractor;
+pub struct YamlExtractor {
+    /// Needed to recognise Helm charts, whose templates render against the
+    /// chart's `values.yaml`.
+    repo_root: Option<PathBuf>,
+}
 
 impl YamlExtractor {
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        Ok(Self { repo_root: None })
+    }
+
+    pub fn with_repo_root(repo_root: PathBuf) -> Result<Self> {
+        Ok(Self {
+            repo_root: Some(repo_root),
+        })
     }
 }
 
@@ -24,16 +40,67 @@ impl LanguageExtractor for YamlExtractor {
             .symbols
             .push(module_symbol_fallback(module_name, source, "/", None));
 
+        let chart = self
+            .repo_root
+            .as_deref()
+            .and_then(|root| helm::chart_file(root, module_name));
         let documents = split_documents(source);
         for doc in &documents {
             if doc.text.trim().is_empty() {
                 continue;
             }
-            let value: Value = match serde_yaml_ng::from_str(&doc.text) {
-                Ok(v) => v,
-                Err(_) => continue,
+            // Helm templates are parsed as the manifest they render to, keeping
+            // the template's own line spans.
+            let (text, value_refs) = match &chart {
+                Some((chart, ChartFile::Template)) => helm::render(&doc.text, chart),
+                _ => (doc.text.clone(), Vec::new()),
             };
-            let Some(resource) = parse_k8s_resource(&value) else {
+            let value: Option<Value> = serde_yaml_ng::from_str(&text).ok();
+            let resource = value.as_ref().and_then(parse_k8s_resource);
+            if let Some((chart, _)) = &chart
+                && !value_refs.is_empty()
+            {
+                let source_qualname = resource
+                    .as_ref()
+                    .map(k8s_qualname)
+                    .unwrap_or_else(|| module_name.to_string());
+                helm::value_reference_edges(
+                    chart,
+                    &source_qualname,
+                    &value_refs,
+                    doc.line_offset,
+                    &mut output,
+                );
+            }
+            let Some(value) = value else {
+                continue;
+            };
+            let Some(resource) = resource else {
+                if let Ok(spec) = serde_json::to_value(&value) {
+                    let source = SpecSource {
+                        text: &doc.text,
+                        line_offset: doc.line_offset,
+                        byte_offset: doc.byte_offset,
+                    };
+                    match &chart {
+                        Some((chart, ChartFile::Chart)) => {
+                            helm::extract_chart(chart, &spec, &source, module_name, &mut output)
+                        }
+                        Some((chart, ChartFile::Values)) => {
+                            helm::extract_values(chart, &spec, &source, module_name, &mut output)
+                        }
+                        _ if compose::is_compose(&spec) => {
+                            compose::extract_compose(&spec, &source, module_name, &mut output)
+                        }
+                        _ if openapi::is_api_spec(&spec) => {
+                            openapi::extract_spec(&spec, &source, module_name, &mut output)
+                        }
+                        _ if asyncapi::is_asyncapi(&spec) => {
+                            asyncapi::extract_spec(&spec, &source, module_name, &mut output)
+                        }
+                        _ => {}
+                    }
+                }
                 continue;
             };
             resource_to_symbols(&resource, &value, module_name, doc, source, &mut output);
@@ -41,6 +108,43 @@ impl LanguageExtractor for YamlExtractor {
 
         Ok(output)
     }
+
+    /// Compose `build` entries point at a Dockerfile relative to the compose
+    /// file; keep them only when it exists.
+    fn resolve_imports(
+        &self,
+        repo_root: &Path,
+        file_rel_path: &str,
+        _module_name: &str,
+        edges: &mut Vec<EdgeInput>,
+    ) {
+        let dir = Path::new(file_rel_path)
+            .parent()
+            .and_then(|dir| dir.to_str())
+            .unwrap_or("");
+        edges.retain_mut(|edge| {
+            if edge.kind != "IMPORTS_FILE" {
+                return true;
+            }
+            let Some(raw) = edge.target_qualname.clone() else {
+                return false;
+            };
+            let Some((rel, module)) = dockerfile::resolve_context_file(repo_root, dir, &raw) else {
+                return false;
+            };
+            edge.target_qualname = Some(module);
+            edge.detail = Some(
+                json!({
+                    "src_path": file_rel_path,
+                    "dst_path": rel,
+                    "raw": raw,
+                    "confidence": 1.0,
+                })
+                .to_string(),
+            );
+            true
+        });
+    }
 }
 
 // --- K8s resource parsing ---
@@ -106,6 +210,15 @@ fn extract_string_map(value: Option<&Value>) -> Vec<(String, String)> {
 
 // --- Symbol generation ---
 
+fn k8s_qualname(resource: &K8sResource) -> String {
+    format!(
+        "k8s://{}/{}/{}",
+        resource.namespace,
+        resource.kind.to_ascii_lowercase(),
+        resource.name
+    )
+}
+
 fn resource_to_symbols(
     resource: &K8sResource,
     value: &Value,
@@ -115,10 +228,7 @@ fn resource_to_symbols(
     output: &mut ExtractedFile,
 ) {
     let kind_lower = resource.kind.to_ascii_lowercase();
-    let qualname = format!(
-        "k8s://{}/{}/{}",
-        resource.namespace, kind_lower, resource.name
-    );
+    let qualname = k8s_qualname(resource);
     let signature = format!(
         "{} {} {}/{}",
         resource.api_version, resource.kind, resource.namespace, resource.name
@@ -290,7 +400,7 @@ fn find_containers(kind: &str, value: &Value) -> Vec<ContainerInfo> {
 /// For env vars with `__` separators (e.g., .NET `Database__ConnectionString`),
 /// emit additional CONFIG_SOURCE edges for each section prefix so that
 /// `GetSection("Database")` → `env://DATABASE` matches the K8s env declaration.
-fn emit_section_prefix_edges(
+pub(crate) fn emit_section_prefix_edges(
     container_qualname: &str,
     env_name: &str,
     start_line: i64,
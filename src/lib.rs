@@ -1,20 +1,28 @@
+pub mod affected_tests;
+pub mod api_changes;
 pub mod cli;
+pub mod compare;
 pub mod config;
 pub mod context;
+#[cfg(unix)]
+pub mod daemon;
 pub mod db;
 pub mod gather_context;
 pub mod git_mining;
 pub mod impact;
 pub mod indexer;
 pub mod init;
+pub mod lsp;
 pub mod mcp;
 pub mod metrics;
 pub mod model;
 pub mod repo_map;
 pub mod resolve;
 pub mod rpc;
+pub mod runtime;
 pub mod search;
 pub mod subgraph;
 pub mod traversal;
 pub mod util;
 pub mod watch;
+pub mod workspace;
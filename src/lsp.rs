@@ -0,0 +1,986 @@
+//! Language Server Protocol front-end over the symbol graph (`lidx lsp`).
+//!
+//! Navigation requests are answered from the index rather than by a compiler:
+//! go-to-definition and find-references follow resolved edges (bridging
+//! HTTP/RPC/channel hops across languages), call hierarchy reuses
+//! `traversal::trace_flow`, and symbols come straight from the symbols table.
+//! The watcher keeps the index fresh, or `didSave` when watching is off;
+//! buffers are not tracked.
+
+use crate::db::Db;
+use crate::indexer::Indexer;
+use crate::indexer::channel::{bridge_complement, is_bridge_edge_kind};
+use crate::model::{Edge, Symbol};
+use crate::traversal::{self, TraceConfig, TraceDirection};
+use crate::util;
+use crate::watch;
+use anyhow::{Result, anyhow, bail};
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Edge kinds that do not denote a use of their target.
+const NON_REFERENCE_KINDS: &[&str] = &["CONTAINS", "MODULE_FILE"];
+const WORKSPACE_SYMBOL_LIMIT: usize = 200;
+const DEFINITION_FALLBACK_LIMIT: usize = 20;
+
+/// Run the language server over stdio until the client sends `exit`.
+pub fn serve(repo_root: PathBuf, db_path: PathBuf, watch_config: watch::WatchConfig) -> Result<()> {
+    let indexer = Indexer::new_with_options(
+        repo_root.clone(),
+        db_path.clone(),
+        watch_config.scan_options,
+    )?;
+    let watcher = watch::start(repo_root, db_path, watch_config)?;
+    let mut server = Server::new(indexer);
+    server.set_sync_on_save(watcher.is_none());
+
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let mut stdout = io::stdout();
+    while let Some(incoming) = read_message(&mut reader)? {
+        let response = match incoming {
+            Incoming::Message(message) => server.handle_message(&message),
+            Incoming::Malformed { id, error } => {
+                eprintln!("lidx: lsp malformed message: {error}");
+                id.map(|id| error_response(id, -32700, &error))
+            }
+        };
+        if let Some(response) = response {
+            write_message(&mut stdout, &response)?;
+        }
+        if server.exited() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// One framed message read from the client.
+#[derive(Debug)]
+pub enum Incoming {
+    Message(Value),
+    /// A frame whose header or body did not parse. `id` is recovered from the
+    /// body when possible so the request can still get an error response.
+    Malformed {
+        id: Option<Value>,
+        error: String,
+    },
+}
+
+/// Read one `Content-Length` framed JSON-RPC message, or `None` at EOF.
+/// Only I/O failures are errors; a bad frame is returned as `Malformed` so the
+/// caller can keep reading.
+pub fn read_message(reader: &mut impl BufRead) -> Result<Option<Incoming>> {
+    let mut length = None;
+    let mut bad_header = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            if length.is_some() || bad_header.is_some() {
+                break;
+            }
+            continue;
+        }
+        // Matched anywhere in the line so a frame that follows an unread body
+        // is still found.
+        if let Some(value) = content_length_value(header) {
+            match value.parse::<usize>() {
+                Ok(value) => {
+                    length = Some(value);
+                    bad_header = None;
+                }
+                Err(_) => bad_header = Some(format!("invalid Content-Length: {value}")),
+            }
+        }
+    }
+    if let Some(error) = bad_header {
+        return Ok(Some(Incoming::Malformed { id: None, error }));
+    }
+    let mut body = vec![0; length.unwrap_or(0)];
+    reader.read_exact(&mut body)?;
+    Ok(Some(match serde_json::from_slice(&body) {
+        Ok(message) => Incoming::Message(message),
+        Err(err) => Incoming::Malformed {
+            id: recover_id(&body),
+            error: format!("parse LSP message: {err}"),
+        },
+    }))
+}
+
+fn content_length_value(header: &str) -> Option<&str> {
+    const NAME: &str = "content-length";
+    let start = header.to_ascii_lowercase().rfind(NAME)? + NAME.len();
+    let value = header[start..].trim_start().strip_prefix(':')?;
+    Some(value.trim())
+}
+
+/// The numeric or string `"id"` of an unparseable JSON-RPC body.
+fn recover_id(body: &[u8]) -> Option<Value> {
+    let text = String::from_utf8_lossy(body);
+    let mut rest = text.as_ref();
+    while let Some(pos) = rest.find("\"id\"") {
+        rest = &rest[pos + 4..];
+        let Some(value) = rest.trim_start().strip_prefix(':') else {
+            continue;
+        };
+        let id = serde_json::Deserializer::from_str(value)
+            .into_iter::<Value>()
+            .next()
+            .and_then(Result::ok)
+            .filter(|id| id.is_number() || id.is_string());
+        if id.is_some() {
+            return id;
+        }
+    }
+    None
+}
+
+/// Write one `Content-Length` framed JSON-RPC message.
+pub fn write_message(writer: &mut impl Write, message: &Value) -> Result<()> {
+    let body = serde_json::to_vec(message)?;
+    write!(writer, "Content-Length: {}\r\n\r\n", body.len())?;
+    writer.write_all(&body)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// LSP request handling over one `Indexer`.
+pub struct Server {
+    indexer: Indexer,
+    sync_on_save: bool,
+    shutdown: bool,
+    exited: bool,
+}
+
+impl Server {
+    pub fn new(indexer: Indexer) -> Self {
+        Self {
+            indexer,
+            sync_on_save: true,
+            shutdown: false,
+            exited: false,
+        }
+    }
+
+    /// Whether `didSave` reindexes the saved file. Off when a watcher
+    /// already picks up the change.
+    pub fn set_sync_on_save(&mut self, enabled: bool) {
+        self.sync_on_save = enabled;
+    }
+
+    /// Whether the client has sent `exit`.
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Handle one message; returns the response for requests.
+    pub fn handle_message(&mut self, message: &Value) -> Option<Value> {
+        let method = message.get("method").and_then(Value::as_str)?;
+        let id = message.get("id").cloned();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let Some(id) = id else {
+            self.handle_notification(method, &params);
+            return None;
+        };
+        if self.shutdown {
+            return Some(error_response(id, -32600, "server is shutting down"));
+        }
+        let result = match method {
+            "initialize" => Ok(initialize_result()),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => self.definition(&params),
+            "textDocument/references" => self.references(&params),
+            "textDocument/documentSymbol" => self.document_symbols(&params),
+            "workspace/symbol" => self.workspace_symbols(&params),
+            "textDocument/prepareCallHierarchy" => self.prepare_call_hierarchy(&params),
+            "callHierarchy/incomingCalls" => self.calls(&params, TraceDirection::Upstream),
+            "callHierarchy/outgoingCalls" => self.calls(&params, TraceDirection::Downstream),
+            _ => return Some(error_response(id, -32601, "method not found")),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(err) => error_response(id, -32603, &err.to_string()),
+        })
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) {
+        match method {
+            "exit" => self.exited = true,
+            "textDocument/didSave" if self.sync_on_save => {
+                let Some(path) = text_document_uri(params).and_then(path_from_uri) else {
+                    return;
+                };
+                if let Err(err) = self.indexer.sync_abs_paths(&[path]) {
+                    eprintln!("lidx: lsp sync error: {err}");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn db(&self) -> &Db {
+        self.indexer.db()
+    }
+
+    fn graph_version(&self) -> Result<i64> {
+        self.db().current_graph_version()
+    }
+
+    fn definition(&self, params: &Value) -> Result<Value> {
+        let Some(cursor) = self.cursor(params)? else {
+            return Ok(Value::Null);
+        };
+        let mut files = SourceCache::new(self.indexer.repo_root());
+        let locations: Vec<Value> = self
+            .definitions_at(&cursor)?
+            .iter()
+            .map(|symbol| symbol_location(symbol, &mut files))
+            .collect();
+        Ok(Value::Array(locations))
+    }
+
+    fn references(&self, params: &Value) -> Result<Value> {
+        let Some(cursor) = self.cursor(params)? else {
+            return Ok(Value::Null);
+        };
+        let include_declaration = params
+            .pointer("/context/includeDeclaration")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let graph_version = self.graph_version()?;
+        let mut files = SourceCache::new(self.indexer.repo_root());
+        let mut seen = HashSet::new();
+        let mut locations = Vec::new();
+        for symbol in self.definitions_at(&cursor)? {
+            if include_declaration && seen.insert((symbol.file_path.clone(), symbol.start_line)) {
+                locations.push(symbol_location(&symbol, &mut files));
+            }
+            for edge in self.reference_edges(&symbol, graph_version)? {
+                let Some(line) = edge.evidence_start_line else {
+                    continue;
+                };
+                if seen.insert((edge.file_path.clone(), line)) {
+                    let range = files.word_range(&edge.file_path, line, &symbol.name);
+                    locations.push(json!({ "uri": files.uri(&edge.file_path), "range": range }));
+                }
+            }
+        }
+        Ok(Value::Array(locations))
+    }
+
+    fn document_symbols(&self, params: &Value) -> Result<Value> {
+        let Some(path) = text_document_uri(params).and_then(path_from_uri) else {
+            return Ok(Value::Null);
+        };
+        let Some(rel_path) = self.rel_path(&path) else {
+            return Ok(Value::Array(Vec::new()));
+        };
+        let mut symbols = self
+            .db()
+            .get_symbols_for_file(&rel_path, self.graph_version()?)?;
+        symbols.retain(|symbol| symbol.kind != "module");
+        let mut files = SourceCache::new(self.indexer.repo_root());
+        Ok(Value::Array(document_symbol_tree(&symbols, &mut files)))
+    }
+
+    fn workspace_symbols(&self, params: &Value) -> Result<Value> {
+        let query = params.get("query").and_then(Value::as_str).unwrap_or("");
+        if query.trim().is_empty() {
+            return Ok(Value::Array(Vec::new()));
+        }
+        let symbols =
+            self.db()
+                .find_symbols(query, WORKSPACE_SYMBOL_LIMIT, None, self.graph_version()?)?;
+        let mut files = SourceCache::new(self.indexer.repo_root());
+        let infos = symbols
+            .iter()
+            .filter(|symbol| symbol.kind != "module")
+            .map(|symbol| {
+                json!({
+                    "name": symbol.name,
+                    "kind": symbol_kind(&symbol.kind),
+                    "location": symbol_location(symbol, &mut files),
+                    "containerName": container_name(symbol),
+                })
+            })
+            .collect();
+        Ok(Value::Array(infos))
+    }
+
+    fn prepare_call_hierarchy(&self, params: &Value) -> Result<Value> {
+        let Some(cursor) = self.cursor(params)? else {
+            return Ok(Value::Null);
+        };
+        let mut files = SourceCache::new(self.indexer.repo_root());
+        let items: Vec<Value> = self
+            .definitions_at(&cursor)?
+            .iter()
+            .map(|symbol| call_hierarchy_item(symbol, &mut files))
+            .collect();
+        Ok(if items.is_empty() {
+            Value::Null
+        } else {
+            Value::Array(items)
+        })
+    }
+
+    /// `incomingCalls`/`outgoingCalls`: the direct hops of a one-level
+    /// `trace_flow`, so HTTP/RPC/channel bridges show up as calls.
+    fn calls(&self, params: &Value, direction: TraceDirection) -> Result<Value> {
+        let item = params
+            .get("item")
+            .ok_or_else(|| anyhow!("missing call hierarchy item"))?;
+        let graph_version = self.graph_version()?;
+        let Some(symbol) = self.item_symbol(item, graph_version)? else {
+            return Ok(Value::Array(Vec::new()));
+        };
+        let upstream = matches!(direction, TraceDirection::Upstream);
+        let config = TraceConfig {
+            // Hops at distance 1 only: the direct callers or callees.
+            max_hops: 0,
+            max_bytes: usize::MAX,
+            direction,
+            include_snippets: false,
+            compact: true,
+            ..TraceConfig::default()
+        };
+        let trace = traversal::trace_flow(
+            self.db(),
+            vec![symbol.id],
+            None,
+            None,
+            graph_version,
+            &config,
+        )?;
+
+        let mut files = SourceCache::new(self.indexer.repo_root());
+        let outgoing_edges = if upstream {
+            Vec::new()
+        } else {
+            self.db().edges_for_symbol(symbol.id, None, graph_version)?
+        };
+        let mut calls = Vec::new();
+        for hop in trace.hops.iter().filter(|hop| hop.distance == 1) {
+            let other = &hop.symbol;
+            // `fromRanges` are always in the caller's file.
+            let (caller, callee, edges) = if upstream {
+                let edges = self.db().edges_for_symbol(other.id, None, graph_version)?;
+                (other, &symbol, edges)
+            } else {
+                (&symbol, other, outgoing_edges.clone())
+            };
+            let lines = call_lines(&edges, caller.id, callee, &hop.edge_kind);
+            let ranges: Vec<Value> = if lines.is_empty() {
+                vec![selection_range(caller, &mut files)]
+            } else {
+                lines
+                    .into_iter()
+                    .map(|line| files.word_range(&caller.file_path, line, &callee.name))
+                    .collect()
+            };
+            let key = if upstream { "from" } else { "to" };
+            calls.push(json!({
+                key: call_hierarchy_item(other, &mut files),
+                "fromRanges": ranges,
+            }));
+        }
+        Ok(Value::Array(calls))
+    }
+
+    fn item_symbol(&self, item: &Value, graph_version: i64) -> Result<Option<Symbol>> {
+        if let Some(id) = item.pointer("/data/id").and_then(Value::as_i64)
+            && let Some(symbol) = self.db().get_symbol_by_id(id)?
+            && symbol.graph_version == graph_version
+        {
+            return Ok(Some(symbol));
+        }
+        // Ids change when the index is rebuilt; fall back to the qualname.
+        match item.pointer("/data/qualname").and_then(Value::as_str) {
+            Some(qualname) => self.db().get_symbol_by_qualname(qualname, graph_version),
+            None => Ok(None),
+        }
+    }
+
+    fn rel_path(&self, path: &Path) -> Option<String> {
+        let root = self.indexer.repo_root();
+        util::normalize_rel_path(root, path)
+            .or_else(|_| util::normalize_rel_path(root, &std::fs::canonicalize(path)?))
+            .ok()
+    }
+
+    /// Resolve `textDocument` + `position` to the identifier under the cursor.
+    fn cursor(&self, params: &Value) -> Result<Option<Cursor>> {
+        let Some(path) = text_document_uri(params).and_then(path_from_uri) else {
+            bail!("missing textDocument.uri");
+        };
+        let Some(rel_path) = self.rel_path(&path) else {
+            return Ok(None);
+        };
+        let line = params
+            .pointer("/position/line")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| anyhow!("missing position"))?;
+        let character = params
+            .pointer("/position/character")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        let Ok(source) = util::read_to_string(&path) else {
+            return Ok(None);
+        };
+        let Some(text) = source.lines().nth(line as usize) else {
+            return Ok(None);
+        };
+        let byte = byte_col(text, character as usize);
+        let Some(word) = word_at(text, byte) else {
+            return Ok(None);
+        };
+        Ok(Some(Cursor {
+            rel_path,
+            line: line as i64 + 1,
+            word: word.to_string(),
+        }))
+    }
+
+    /// Symbols the identifier under the cursor refers to: the declaration it
+    /// names, the targets of edges leaving this line, or same-named symbols.
+    fn definitions_at(&self, cursor: &Cursor) -> Result<Vec<Symbol>> {
+        let db = self.db();
+        let graph_version = self.graph_version()?;
+        let file_symbols = db.get_symbols_for_file(&cursor.rel_path, graph_version)?;
+        if let Some(declared) = file_symbols
+            .iter()
+            .find(|symbol| symbol.start_line == cursor.line && symbol.name == cursor.word)
+        {
+            return Ok(vec![declared.clone()]);
+        }
+
+        let mut found = Vec::new();
+        let mut seen = HashSet::new();
+        if let Some(enclosing) =
+            db.enclosing_symbol_for_line(&cursor.rel_path, cursor.line, graph_version)?
+        {
+            for edge in db.edges_for_symbol(enclosing.id, None, graph_version)? {
+                if edge.source_symbol_id != Some(enclosing.id)
+                    || !edge_on_line(&edge, cursor.line)
+                    || NON_REFERENCE_KINDS.contains(&edge.kind.as_str())
+                {
+                    continue;
+                }
+                for id in self.edge_targets(&edge, &cursor.word, graph_version)? {
+                    if seen.insert(id)
+                        && let Some(symbol) = db.get_symbol_by_id(id)?
+                    {
+                        found.push(symbol);
+                    }
+                }
+            }
+        }
+        if !found.is_empty() {
+            return Ok(found);
+        }
+
+        let mut candidates: Vec<Symbol> = db
+            .find_symbols(
+                &cursor.word,
+                DEFINITION_FALLBACK_LIMIT * 5,
+                None,
+                graph_version,
+            )?
+            .into_iter()
+            .filter(|symbol| symbol.name == cursor.word && symbol.kind != "module")
+            .collect();
+        // Prefer definitions in the same file.
+        candidates.sort_by_key(|symbol| symbol.file_path != cursor.rel_path);
+        candidates.truncate(DEFINITION_FALLBACK_LIMIT);
+        Ok(candidates)
+    }
+
+    /// Symbols an edge on the cursor line leads to. Bridge edges (HTTP, RPC,
+    /// channels) jump to the other side whatever the word; ordinary edges
+    /// must name the word under the cursor.
+    fn edge_targets(&self, edge: &Edge, word: &str, graph_version: i64) -> Result<Vec<i64>> {
+        let db = self.db();
+        if let Some(complement) = bridge_complement(&edge.kind)
+            && let Some(target) = edge.target_qualname.as_deref()
+        {
+            let ids = db
+                .edges_by_target_qualname_and_kinds(target, complement, None, graph_version)?
+                .into_iter()
+                .filter_map(|bridged| bridged.source_symbol_id)
+                .collect::<Vec<_>>();
+            if !ids.is_empty() {
+                return Ok(ids);
+            }
+        }
+        let names_word = edge
+            .target_qualname
+            .as_deref()
+            .is_some_and(|qualname| qualname_tail(qualname) == word);
+        if let Some(id) = edge.target_symbol_id {
+            if names_word || db.get_symbol_by_id(id)?.is_some_and(|s| s.name == word) {
+                return Ok(vec![id]);
+            }
+            return Ok(Vec::new());
+        }
+        match edge.target_qualname.as_deref() {
+            Some(qualname) if names_word => Ok(db
+                .lookup_symbol_id_fuzzy(qualname, None, graph_version)?
+                .into_iter()
+                .collect()),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Edges that use `symbol`: resolved incoming edges, unresolved calls by
+    /// name, and the far side of any route/handler/subscription it exposes.
+    fn reference_edges(&self, symbol: &Symbol, graph_version: i64) -> Result<Vec<Edge>> {
+        let db = self.db();
+        let mut edges = Vec::new();
+        for edge in db.edges_for_symbol(symbol.id, None, graph_version)? {
+            if edge.target_symbol_id == Some(symbol.id)
+                && edge.source_symbol_id != Some(symbol.id)
+                && !NON_REFERENCE_KINDS.contains(&edge.kind.as_str())
+            {
+                edges.push(edge);
+            } else if edge.source_symbol_id == Some(symbol.id)
+                && let Some(complement) = bridge_complement(&edge.kind)
+                && let Some(target) = edge.target_qualname.as_deref()
+            {
+                edges.extend(db.edges_by_target_qualname_and_kinds(
+                    target,
+                    complement,
+                    None,
+                    graph_version,
+                )?);
+            }
+        }
+        edges.extend(
+            db.incoming_edges_by_qualname_pattern(&symbol.name, "CALLS", None, graph_version)?
+                .into_iter()
+                .filter(|edge| edge.target_symbol_id.is_none()),
+        );
+        edges.sort_by(|a, b| {
+            (&a.file_path, a.evidence_start_line).cmp(&(&b.file_path, b.evidence_start_line))
+        });
+        Ok(edges)
+    }
+}
+
+struct Cursor {
+    rel_path: String,
+    /// 1-based, as stored in the index.
+    line: i64,
+    word: String,
+}
+
+fn initialize_result() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": { "openClose": false, "change": 0, "save": true },
+            "definitionProvider": true,
+            "referencesProvider": true,
+            "documentSymbolProvider": true,
+            "workspaceSymbolProvider": true,
+            "callHierarchyProvider": true,
+        },
+        "serverInfo": {
+            "name": "lidx",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn text_document_uri(params: &Value) -> Option<&str> {
+    params.pointer("/textDocument/uri").and_then(Value::as_str)
+}
+
+/// `file://` URI to a filesystem path (percent-decoded).
+pub fn path_from_uri(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = rest.strip_prefix("localhost").unwrap_or(rest);
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%'
+            && let Some(hex) = path.get(idx + 1..idx + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            out.push(byte);
+            idx += 3;
+            continue;
+        }
+        out.push(bytes[idx]);
+        idx += 1;
+    }
+    Some(PathBuf::from(String::from_utf8(out).ok()?))
+}
+
+/// Filesystem path to a `file://` URI, percent-encoding reserved bytes.
+pub fn uri_from_path(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+fn qualname_tail(qualname: &str) -> &str {
+    qualname
+        .rsplit(['.', ':', '/', '#', '\\'])
+        .next()
+        .unwrap_or(qualname)
+}
+
+fn container_name(symbol: &Symbol) -> Option<&str> {
+    symbol
+        .qualname
+        .strip_suffix(symbol.name.as_str())
+        .map(|prefix| prefix.trim_end_matches(['.', ':', '#', '/']))
+        .filter(|prefix| !prefix.is_empty())
+}
+
+fn edge_on_line(edge: &Edge, line: i64) -> bool {
+    match (edge.evidence_start_line, edge.evidence_end_line) {
+        (Some(start), Some(end)) => start <= line && line <= end,
+        (Some(start), None) => start == line,
+        _ => false,
+    }
+}
+
+/// Evidence lines of the edges from `caller_id` that account for a hop to
+/// `callee` of kind `hop_kind`.
+fn call_lines(edges: &[Edge], caller_id: i64, callee: &Symbol, hop_kind: &str) -> Vec<i64> {
+    let complement = bridge_complement(hop_kind).unwrap_or(&[]);
+    let mut lines: Vec<i64> = edges
+        .iter()
+        .filter(|edge| edge.source_symbol_id == Some(caller_id))
+        .filter(|edge| match edge.target_symbol_id {
+            Some(target) => target == callee.id,
+            None => {
+                (edge.kind == hop_kind || complement.contains(&edge.kind.as_str()))
+                    && (is_bridge_edge_kind(&edge.kind)
+                        || edge
+                            .target_qualname
+                            .as_deref()
+                            .is_some_and(|qualname| qualname_tail(qualname) == callee.name))
+            }
+        })
+        .filter_map(|edge| edge.evidence_start_line)
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+    lines
+}
+
+/// LSP `SymbolKind` for an index symbol kind.
+fn symbol_kind(kind: &str) -> u32 {
+    match kind {
+        "module" | "container" => 2,
+        "namespace" | "package" => 3,
+        "class" | "record" | "object" | "type" => 5,
+        "method" | "rpc" => 6,
+        "property" => 7,
+        "field" | "column" => 8,
+        "constructor" => 9,
+        "enum" => 10,
+        "interface" | "trait" | "service" => 11,
+        "function" => 12,
+        "const" | "constant" => 14,
+        "section" => 15,
+        "enum_member" | "enum_variant" => 22,
+        "struct" | "table" | "view" => 23,
+        _ => 13,
+    }
+}
+
+/// Nest symbols by range containment into `DocumentSymbol`s.
+fn document_symbol_tree(symbols: &[Symbol], files: &mut SourceCache) -> Vec<Value> {
+    let mut order: Vec<&Symbol> = symbols.iter().collect();
+    order.sort_by_key(|s| {
+        (
+            s.start_line,
+            s.start_col,
+            std::cmp::Reverse((s.end_line, s.end_col)),
+        )
+    });
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); order.len()];
+    let mut roots = Vec::new();
+    let mut stack: Vec<usize> = Vec::new();
+    for (idx, symbol) in order.iter().enumerate() {
+        while let Some(&top) = stack.last() {
+            let parent = order[top];
+            if (parent.end_line, parent.end_col) >= (symbol.end_line, symbol.end_col) {
+                break;
+            }
+            stack.pop();
+        }
+        match stack.last() {
+            Some(&parent) => children[parent].push(idx),
+            None => roots.push(idx),
+        }
+        stack.push(idx);
+    }
+
+    fn build(
+        idx: usize,
+        order: &[&Symbol],
+        children: &[Vec<usize>],
+        files: &mut SourceCache,
+    ) -> Value {
+        let symbol = order[idx];
+        let nested: Vec<Value> = children[idx]
+            .iter()
+            .map(|&child| build(child, order, children, files))
+            .collect();
+        json!({
+            "name": symbol.name,
+            "detail": symbol.signature.as_deref().unwrap_or(&symbol.qualname),
+            "kind": symbol_kind(&symbol.kind),
+            "range": symbol_range(symbol, files),
+            "selectionRange": selection_range(symbol, files),
+            "children": nested,
+        })
+    }
+    roots
+        .into_iter()
+        .map(|idx| build(idx, &order, &children, files))
+        .collect()
+}
+
+fn call_hierarchy_item(symbol: &Symbol, files: &mut SourceCache) -> Value {
+    json!({
+        "name": symbol.name,
+        "kind": symbol_kind(&symbol.kind),
+        "detail": symbol.qualname,
+        "uri": files.uri(&symbol.file_path),
+        "range": symbol_range(symbol, files),
+        "selectionRange": selection_range(symbol, files),
+        "data": { "id": symbol.id, "qualname": symbol.qualname },
+    })
+}
+
+fn symbol_location(symbol: &Symbol, files: &mut SourceCache) -> Value {
+    json!({
+        "uri": files.uri(&symbol.file_path),
+        "range": selection_range(symbol, files),
+    })
+}
+
+fn symbol_range(symbol: &Symbol, files: &mut SourceCache) -> Value {
+    range(
+        files.position(&symbol.file_path, symbol.start_line, symbol.start_col - 1),
+        files.position(&symbol.file_path, symbol.end_line, symbol.end_col - 1),
+    )
+}
+
+/// Range of the symbol's name on its first line, or its start when the name
+/// does not appear there.
+fn selection_range(symbol: &Symbol, files: &mut SourceCache) -> Value {
+    let from = (symbol.start_col - 1).max(0) as usize;
+    let found = files
+        .line(&symbol.file_path, symbol.start_line)
+        .and_then(|text| {
+            let start = text.get(from..)?.find(symbol.name.as_str())? + from;
+            Some((start, start + symbol.name.len()))
+        });
+    let (start, end) = found.unwrap_or((from, from));
+    range(
+        files.position(&symbol.file_path, symbol.start_line, start as i64),
+        files.position(&symbol.file_path, symbol.start_line, end as i64),
+    )
+}
+
+fn range(start: Value, end: Value) -> Value {
+    json!({ "start": start, "end": end })
+}
+
+/// The identifier (letters, digits, `_`, `$`) touching byte offset `byte`.
+fn word_at(text: &str, byte: usize) -> Option<&str> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let byte = byte.min(text.len());
+    let start = text[..byte]
+        .char_indices()
+        .rev()
+        .take_while(|&(_, c)| is_word(c))
+        .last()
+        .map_or(byte, |(idx, _)| idx);
+    let end = text[byte..]
+        .char_indices()
+        .find(|&(_, c)| !is_word(c))
+        .map_or(text.len(), |(idx, _)| byte + idx);
+    (start < end).then(|| &text[start..end])
+}
+
+/// Byte offset in `text` of UTF-16 column `character`.
+fn byte_col(text: &str, character: usize) -> usize {
+    let mut units = 0;
+    for (idx, c) in text.char_indices() {
+        if units >= character {
+            return idx;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+/// UTF-16 column of byte offset `byte` in `text`.
+fn utf16_col(text: &str, byte: usize) -> usize {
+    let mut byte = byte.min(text.len());
+    while !text.is_char_boundary(byte) {
+        byte -= 1;
+    }
+    text[..byte].encode_utf16().count()
+}
+
+/// Source lines of indexed files, read on demand to convert the index's byte
+/// columns into LSP positions.
+struct SourceCache<'a> {
+    repo_root: &'a Path,
+    files: HashMap<String, Option<Vec<String>>>,
+}
+
+impl<'a> SourceCache<'a> {
+    fn new(repo_root: &'a Path) -> Self {
+        Self {
+            repo_root,
+            files: HashMap::new(),
+        }
+    }
+
+    fn uri(&self, rel_path: &str) -> String {
+        uri_from_path(&self.repo_root.join(rel_path))
+    }
+
+    fn line(&mut self, rel_path: &str, line: i64) -> Option<&str> {
+        let repo_root = self.repo_root;
+        let lines = self
+            .files
+            .entry(rel_path.to_string())
+            .or_insert_with(|| {
+                util::read_to_string(&repo_root.join(rel_path))
+                    .ok()
+                    .map(|source| source.lines().map(str::to_string).collect())
+            })
+            .as_ref()?;
+        lines
+            .get(usize::try_from(line - 1).ok()?)
+            .map(String::as_str)
+    }
+
+    /// LSP position of 1-based `line` and 0-based byte column `byte`.
+    fn position(&mut self, rel_path: &str, line: i64, byte: i64) -> Value {
+        let byte = byte.max(0) as usize;
+        let character = self
+            .line(rel_path, line)
+            .map_or(byte, |text| utf16_col(text, byte));
+        json!({ "line": (line - 1).max(0), "character": character })
+    }
+
+    /// Range of `word` on `line`, or the line's indentation when absent.
+    fn word_range(&mut self, rel_path: &str, line: i64, word: &str) -> Value {
+        let text = self.line(rel_path, line).unwrap_or("");
+        let indent = text.len() - text.trim_start().len();
+        let (start, end) = text
+            .find(word)
+            .filter(|_| !word.is_empty())
+            .map_or((indent, indent), |start| (start, start + word.len()));
+        range(
+            self.position(rel_path, line, start as i64),
+            self.position(rel_path, line, end as i64),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor as IoCursor;
+
+    #[test]
+    fn messages_round_trip_through_framing() {
+        let mut buf = Vec::new();
+        write_message(
+            &mut buf,
+            &json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}),
+        )
+        .unwrap();
+        write_message(&mut buf, &json!({"jsonrpc": "2.0", "method": "exit"})).unwrap();
+        let mut reader = IoCursor::new(buf);
+        assert_eq!(message(&mut reader)["id"], 1);
+        assert_eq!(message(&mut reader)["method"], "exit");
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    fn message(reader: &mut impl BufRead) -> Value {
+        match read_message(reader).unwrap().unwrap() {
+            Incoming::Message(message) => message,
+            other => panic!("expected a message, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn malformed_frames_are_reported_and_skipped() {
+        let mut buf = Vec::new();
+        let bad = br#"{"jsonrpc": "2.0", "id": 7, "method": "ping""#;
+        write!(buf, "Content-Length: {}\r\n\r\n", bad.len()).unwrap();
+        buf.extend_from_slice(bad);
+        buf.extend_from_slice(b"Content-Length: nope\r\n\r\n{}");
+        write_message(&mut buf, &json!({"jsonrpc": "2.0", "method": "exit"})).unwrap();
+        let mut reader = IoCursor::new(buf);
+
+        match read_message(&mut reader).unwrap().unwrap() {
+            Incoming::Malformed { id, .. } => assert_eq!(id, Some(json!(7))),
+            other => panic!("expected a malformed frame, got {other:?}"),
+        }
+        match read_message(&mut reader).unwrap().unwrap() {
+            Incoming::Malformed { id, error } => {
+                assert_eq!(id, None);
+                assert!(error.contains("Content-Length"), "{error}");
+            }
+            other => panic!("expected a malformed frame, got {other:?}"),
+        }
+        assert_eq!(message(&mut reader)["method"], "exit");
+    }
+
+    #[test]
+    fn words_and_columns() {
+        let text = "let é = greet_user(x);";
+        assert_eq!(word_at(text, byte_col(text, 10)), Some("greet_user"));
+        assert_eq!(word_at(text, text.len()), None);
+        assert_eq!(utf16_col(text, text.find("greet").unwrap()), 8);
+        assert_eq!(byte_col(text, 8), text.find("greet").unwrap());
+    }
+
+    #[test]
+    fn uris_round_trip() {
+        let path = Path::new("/tmp/my repo/a#b.py");
+        let uri = uri_from_path(path);
+        assert_eq!(uri, "file:///tmp/my%20repo/a%23b.py");
+        assert_eq!(path_from_uri(&uri).unwrap(), path);
+        assert_eq!(qualname_tail("pkg.mod.Class.method"), "method");
+    }
+}
@@ -1,6 +1,8 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use lidx::{cli, context, db, indexer, init, mcp, rpc, watch};
+#[cfg(unix)]
+use lidx::daemon;
+use lidx::{affected_tests, cli, context, db, indexer, init, lsp, mcp, rpc, watch, workspace};
 use std::path::{Path, PathBuf};
 
 fn default_db_path(repo: &Path) -> PathBuf {
@@ -30,10 +32,31 @@ fn main() -> Result<()> {
             );
             rpc::serve(repo, db_path, watch_config)
         }
+        cli::Command::Reindex {
+            repo: _,
+            db: _,
+            no_ignore,
+            workspace: Some(manifest),
+            rev: _,
+        } => {
+            let mut stats = serde_json::Map::new();
+            for member in workspace::load_members(&manifest)? {
+                let mut indexer = indexer::Indexer::new_with_options(
+                    member.root,
+                    member.db_path,
+                    indexer::scan::ScanOptions::new(no_ignore),
+                )?;
+                stats.insert(member.name, serde_json::to_value(indexer.reindex()?)?);
+            }
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+            Ok(())
+        }
         cli::Command::Reindex {
             repo,
             db,
             no_ignore,
+            workspace: None,
+            rev,
         } => {
             let db_path = db.unwrap_or_else(|| default_db_path(&repo));
             let mut indexer = indexer::Indexer::new_with_options(
@@ -41,7 +64,10 @@ fn main() -> Result<()> {
                 db_path,
                 indexer::scan::ScanOptions::new(no_ignore),
             )?;
-            let stats = indexer.reindex()?;
+            let stats = match rev {
+                Some(rev) => serde_json::to_value(indexer.index_revision(&rev)?)?,
+                None => serde_json::to_value(indexer.reindex()?)?,
+            };
             println!("{}", serde_json::to_string_pretty(&stats)?);
             Ok(())
         }
@@ -60,6 +86,92 @@ fn main() -> Result<()> {
             println!("{}", serde_json::to_string_pretty(&changed)?);
             Ok(())
         }
+        cli::Command::ApiCheck {
+            repo,
+            db,
+            no_ignore,
+            base,
+            head,
+            approve,
+            path,
+        } => {
+            let db_path = db.unwrap_or_else(|| default_db_path(&repo));
+            let mut indexer = indexer::Indexer::new_with_options(
+                repo,
+                db_path,
+                indexer::scan::ScanOptions::new(no_ignore),
+            )?;
+            let to = match head {
+                Some(head) => serde_json::json!(head),
+                None => {
+                    indexer.reindex()?;
+                    serde_json::json!(indexer.db().current_graph_version()?)
+                }
+            };
+            let params = serde_json::json!({
+                "from": base,
+                "to": to,
+                "approve": approve,
+                "paths": path,
+                "limit": 500,
+                "max_response_bytes": usize::MAX,
+            });
+            let report = rpc::handle_method(&mut indexer, "api_changes", params)?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            let unapproved = report["summary"]["breaking_unapproved"]
+                .as_u64()
+                .unwrap_or(0);
+            if unapproved > 0 {
+                eprintln!("lidx: {unapproved} unapproved breaking API change(s)");
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        cli::Command::AffectedTests {
+            repo,
+            db,
+            no_ignore,
+            diff,
+            base,
+            shell,
+        } => {
+            let db_path = db.unwrap_or_else(|| default_db_path(&repo));
+            let mut indexer = indexer::Indexer::new_with_options(
+                repo,
+                db_path,
+                indexer::scan::ScanOptions::new(no_ignore),
+            )?;
+            indexer.reindex()?;
+            let mut params = serde_json::json!({"max_response_bytes": usize::MAX});
+            match diff {
+                Some(path) if path.as_os_str() == "-" => {
+                    let mut diff = String::new();
+                    std::io::Read::read_to_string(&mut std::io::stdin(), &mut diff)?;
+                    params["diff"] = serde_json::json!(diff);
+                }
+                Some(path) => params["diff"] = serde_json::json!(std::fs::read_to_string(path)?),
+                None => params["base"] = serde_json::json!(base),
+            }
+            let report = rpc::handle_method(&mut indexer, "affected_tests", params)?;
+            if !shell {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+            for tier in report["tiers"].as_array().into_iter().flatten() {
+                println!("# {}", tier["confidence"].as_str().unwrap_or_default());
+                for command in tier["commands"].as_array().into_iter().flatten() {
+                    let cwd = command["cwd"].as_str().unwrap_or(".");
+                    let command = command["command"].as_str().unwrap_or_default();
+                    if cwd == "." {
+                        println!("{command}");
+                    } else {
+                        let cwd = affected_tests::shell_quote(cwd);
+                        println!("(cd {cwd} && {command})");
+                    }
+                }
+            }
+            Ok(())
+        }
         cli::Command::Overview { repo, db } => {
             let db_path = db.unwrap_or_else(|| default_db_path(&repo));
             let db = db::Db::new(&db_path)?;
@@ -75,6 +187,8 @@ fn main() -> Result<()> {
             params,
             params_file,
             id,
+            no_ignore,
+            no_daemon,
         } => {
             let db_path = db.unwrap_or_else(|| default_db_path(&repo));
             let params_raw = if let Some(path) = params_file {
@@ -82,7 +196,32 @@ fn main() -> Result<()> {
             } else {
                 params
             };
-            let response = rpc::call(repo, db_path, method, &params_raw, &id)?;
+            #[cfg(unix)]
+            if !no_daemon {
+                let options = daemon::SpawnOptions {
+                    repo_root: repo.clone(),
+                    db_path: db_path.clone(),
+                    no_ignore,
+                    watch: watch::WatchMode::Auto,
+                };
+                match daemon::call(&options, &method, &params_raw, &id) {
+                    Ok(response) => {
+                        println!("{response}");
+                        return Ok(());
+                    }
+                    Err(err) => eprintln!("lidx: daemon unavailable ({err}), running in-process"),
+                }
+            }
+            #[cfg(not(unix))]
+            let _ = no_daemon;
+            let response = rpc::call_with_options(
+                repo,
+                db_path,
+                indexer::scan::ScanOptions::new(no_ignore),
+                method,
+                &params_raw,
+                &id,
+            )?;
             println!("{response}");
             Ok(())
         }
@@ -94,6 +233,63 @@ fn main() -> Result<()> {
             watch_debounce_ms,
             watch_fallback_secs,
             watch_batch_max,
+            no_daemon,
+            http,
+        } => {
+            let db_path = db.unwrap_or_else(|| default_db_path(&repo));
+            let watch_config = watch::WatchConfig::new(
+                watch_mode,
+                watch_debounce_ms,
+                watch_fallback_secs,
+                watch_batch_max,
+                no_ignore,
+            );
+            match http {
+                Some(addr) => {
+                    let listener = std::net::TcpListener::bind(&addr)
+                        .with_context(|| format!("bind MCP HTTP listener on {addr}"))?;
+                    mcp::serve_http(listener, repo, db_path, watch_config, !no_daemon)
+                }
+                None => mcp::serve(repo, db_path, watch_config, !no_daemon),
+            }
+        }
+        #[cfg(unix)]
+        cli::Command::Daemon {
+            repo,
+            db,
+            no_ignore,
+            watch: watch_mode,
+            watch_debounce_ms,
+            watch_fallback_secs,
+            watch_batch_max,
+            idle_timeout_secs,
+        } => {
+            let db_path = db.unwrap_or_else(|| default_db_path(&repo));
+            let watch_config = watch::WatchConfig::new(
+                watch_mode,
+                watch_debounce_ms,
+                watch_fallback_secs,
+                watch_batch_max,
+                no_ignore,
+            );
+            daemon::serve(
+                repo,
+                db_path,
+                daemon::DaemonConfig::new(watch_config, idle_timeout_secs),
+            )
+        }
+        #[cfg(not(unix))]
+        cli::Command::Daemon { .. } => {
+            anyhow::bail!("lidx daemon requires Unix domain sockets")
+        }
+        cli::Command::Lsp {
+            repo,
+            db,
+            no_ignore,
+            watch: watch_mode,
+            watch_debounce_ms,
+            watch_fallback_secs,
+            watch_batch_max,
         } => {
             let db_path = db.unwrap_or_else(|| default_db_path(&repo));
             let watch_config = watch::WatchConfig::new(
@@ -103,7 +299,7 @@ fn main() -> Result<()> {
                 watch_batch_max,
                 no_ignore,
             );
-            mcp::serve(repo, db_path, watch_config)
+            lsp::serve(repo, db_path, watch_config)
         }
         cli::Command::Context {
             repo,
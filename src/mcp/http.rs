@@ -0,0 +1,548 @@
+//! MCP over HTTP: the Streamable HTTP transport (`POST /mcp`) plus the legacy
+//! HTTP+SSE transport (`GET /sse` with `POST /messages`).
+//!
+//! Connections are handled on their own threads, but every message is
+//! funnelled to the thread that owns the shared `State`, so HTTP clients see
+//! the same indexer cache, defaults and watcher as one stdio session would.
+
+use super::{Defaults, State, handle_message, jsonrpc_error};
+use crate::watch;
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const MCP_PATH: &str = "/mcp";
+const SSE_PATH: &str = "/sse";
+const MESSAGES_PATH: &str = "/messages";
+const SESSION_HEADER: &str = "mcp-session-id";
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+/// Comment line sent on idle SSE streams so dead clients are noticed.
+const SSE_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// Output of one dispatched message: zero or more notifications, then the
+/// response (`None` for notifications and client responses).
+enum Event {
+    Notification(Value),
+    Done(Option<Value>),
+}
+
+struct Job {
+    message: Value,
+    events: Sender<Event>,
+}
+
+/// Live sessions by id. Legacy SSE sessions hold the sender for their stream.
+type Sessions = Arc<Mutex<HashMap<String, Option<Sender<Value>>>>>;
+
+/// Serve MCP over HTTP on `listener` until the process exits.
+pub fn serve_http(
+    listener: TcpListener,
+    repo_root: PathBuf,
+    db_path: PathBuf,
+    watch_config: watch::WatchConfig,
+    use_daemon: bool,
+) -> Result<()> {
+    let addr = listener.local_addr()?;
+    let defaults = Defaults { repo_root, db_path };
+    let mut state = State::new(defaults, watch_config, use_daemon);
+
+    let (jobs_tx, jobs_rx) = mpsc::channel::<Job>();
+    let sessions: Sessions = Arc::new(Mutex::new(HashMap::new()));
+    thread::spawn(move || accept_loop(listener, jobs_tx, sessions));
+    eprintln!("lidx: MCP listening on http://{addr}{MCP_PATH} (legacy SSE at {SSE_PATH})");
+
+    for job in jobs_rx {
+        let events = job.events.clone();
+        state.notifier = Some(Rc::new(move |notification: Value| {
+            let _ = events.send(Event::Notification(notification));
+        }));
+        let response = handle_message(job.message, &mut state);
+        state.notifier = None;
+        let _ = job.events.send(Event::Done(response));
+    }
+    Ok(())
+}
+
+fn accept_loop(listener: TcpListener, jobs: Sender<Job>, sessions: Sessions) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let jobs = jobs.clone();
+                let sessions = Arc::clone(&sessions);
+                thread::spawn(move || {
+                    if let Err(err) = handle_connection(stream, jobs, sessions) {
+                        eprintln!("lidx: MCP HTTP connection error: {err}");
+                    }
+                });
+            }
+            Err(err) => eprintln!("lidx: MCP HTTP accept error: {err}"),
+        }
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn query_param(&self, name: &str) -> Option<&str> {
+        self.query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key == name).then_some(value)
+        })
+    }
+
+    fn accepts_event_stream(&self) -> bool {
+        self.header("accept")
+            .is_some_and(|accept| accept.contains("text/event-stream"))
+    }
+
+    fn wants_close(&self) -> bool {
+        self.header("connection")
+            .is_some_and(|value| value.eq_ignore_ascii_case("close"))
+    }
+}
+
+/// What to do with the connection after answering a request.
+enum Next {
+    KeepAlive,
+    Close,
+}
+
+fn handle_connection(stream: TcpStream, jobs: Sender<Job>, sessions: Sessions) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    while let Some(request) = read_request(&mut reader)? {
+        let next = route(&request, &mut writer, &jobs, &sessions)?;
+        writer.flush()?;
+        if matches!(next, Next::Close) || request.wants_close() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn route(
+    request: &Request,
+    writer: &mut TcpStream,
+    jobs: &Sender<Job>,
+    sessions: &Sessions,
+) -> Result<Next> {
+    // Browsers may reach a localhost server from any page; only accept
+    // cross-origin requests from local origins (DNS rebinding protection).
+    if let Some(origin) = request.header("origin")
+        && !is_local_origin(origin)
+    {
+        write_response(writer, 403, &[], "text/plain", b"forbidden origin")?;
+        return Ok(Next::Close);
+    }
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", MCP_PATH) => handle_post_mcp(request, writer, jobs, sessions),
+        ("DELETE", MCP_PATH) => {
+            let removed = request
+                .header(SESSION_HEADER)
+                .is_some_and(|id| sessions.lock().unwrap().remove(id).is_some());
+            let status = if removed { 200 } else { 404 };
+            write_response(writer, status, &[], "text/plain", b"")?;
+            Ok(Next::KeepAlive)
+        }
+        // No server-initiated stream on the Streamable HTTP endpoint.
+        ("GET", MCP_PATH) => {
+            let allow = [("Allow", "POST, DELETE".to_string())];
+            write_response(writer, 405, &allow, "text/plain", b"method not allowed")?;
+            Ok(Next::KeepAlive)
+        }
+        ("GET", SSE_PATH) => {
+            handle_legacy_sse(writer, sessions)?;
+            Ok(Next::Close)
+        }
+        ("POST", MESSAGES_PATH) => handle_legacy_message(request, writer, jobs, sessions),
+        _ => {
+            write_response(writer, 404, &[], "text/plain", b"not found")?;
+            Ok(Next::KeepAlive)
+        }
+    }
+}
+
+/// Streamable HTTP: one JSON-RPC message or a batch per POST. Requests are
+/// answered with JSON, or with an SSE stream when the client accepts one and
+/// asked for progress, so `notifications/progress` arrive before the result.
+fn handle_post_mcp(
+    request: &Request,
+    writer: &mut TcpStream,
+    jobs: &Sender<Job>,
+    sessions: &Sessions,
+) -> Result<Next> {
+    if let Some(id) = request.header(SESSION_HEADER)
+        && !sessions.lock().unwrap().contains_key(id)
+    {
+        write_response(writer, 404, &[], "text/plain", b"unknown session")?;
+        return Ok(Next::KeepAlive);
+    }
+    let payload = match serde_json::from_slice::<Value>(&request.body) {
+        Ok(value) => value,
+        Err(err) => {
+            let error = jsonrpc_error(Value::Null, -32700, &format!("parse error: {err}"));
+            write_json(writer, 400, &[], &error)?;
+            return Ok(Next::KeepAlive);
+        }
+    };
+    let batch = payload.is_array();
+    let messages = match payload {
+        Value::Array(items) => items,
+        value => vec![value],
+    };
+
+    let mut headers = Vec::new();
+    if messages
+        .iter()
+        .any(|message| message["method"] == "initialize")
+    {
+        let id = new_session_id();
+        sessions.lock().unwrap().insert(id.clone(), None);
+        headers.push(("Mcp-Session-Id", id));
+    }
+
+    if !messages.iter().any(is_request) {
+        for message in messages {
+            dispatch(jobs, message)?.wait();
+        }
+        write_response(writer, 202, &headers, "text/plain", b"")?;
+        return Ok(Next::KeepAlive);
+    }
+
+    if !batch && request.accepts_event_stream() && has_progress_token(&messages[0]) {
+        let message = messages.into_iter().next().expect("single message");
+        let events = dispatch(jobs, message)?;
+        write_sse_head(writer, &headers)?;
+        for event in events.0 {
+            match event {
+                Event::Notification(value) => write_sse_event(writer, "message", &value)?,
+                Event::Done(response) => {
+                    if let Some(response) = response {
+                        write_sse_event(writer, "message", &response)?;
+                    }
+                    break;
+                }
+            }
+        }
+        return Ok(Next::Close);
+    }
+
+    let mut responses = Vec::new();
+    for message in messages {
+        if let Some(response) = dispatch(jobs, message)?.wait() {
+            responses.push(response);
+        }
+    }
+    let body = if batch {
+        Value::Array(responses)
+    } else {
+        responses.pop().unwrap_or(Value::Null)
+    };
+    write_json(writer, 200, &headers, &body)?;
+    Ok(Next::KeepAlive)
+}
+
+/// Legacy HTTP+SSE: the stream first names the endpoint to POST messages to,
+/// then carries every response and notification for the session.
+fn handle_legacy_sse(writer: &mut TcpStream, sessions: &Sessions) -> Result<()> {
+    let id = new_session_id();
+    let (tx, rx) = mpsc::channel::<Value>();
+    sessions.lock().unwrap().insert(id.clone(), Some(tx));
+
+    let result = (|| -> Result<()> {
+        write_sse_head(writer, &[])?;
+        let endpoint = format!("{MESSAGES_PATH}?sessionId={id}");
+        write!(writer, "event: endpoint\ndata: {endpoint}\n\n")?;
+        writer.flush()?;
+        loop {
+            match rx.recv_timeout(SSE_KEEPALIVE) {
+                Ok(value) => write_sse_event(writer, "message", &value)?,
+                Err(RecvTimeoutError::Timeout) => {
+                    write!(writer, ": keepalive\n\n")?;
+                    writer.flush()?;
+                }
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+    })();
+    sessions.lock().unwrap().remove(&id);
+    // A write error is how a legacy SSE session normally ends: the client hung up.
+    let _ = result;
+    Ok(())
+}
+
+fn handle_legacy_message(
+    request: &Request,
+    writer: &mut TcpStream,
+    jobs: &Sender<Job>,
+    sessions: &Sessions,
+) -> Result<Next> {
+    let stream = request
+        .query_param("sessionId")
+        .and_then(|id| sessions.lock().unwrap().get(id).cloned().flatten());
+    let Some(stream) = stream else {
+        write_response(writer, 404, &[], "text/plain", b"unknown session")?;
+        return Ok(Next::KeepAlive);
+    };
+    let messages = match serde_json::from_slice::<Value>(&request.body) {
+        Ok(Value::Array(items)) => items,
+        Ok(value) => vec![value],
+        Err(err) => {
+            let error = jsonrpc_error(Value::Null, -32700, &format!("parse error: {err}"));
+            let _ = stream.send(error);
+            write_response(writer, 400, &[], "text/plain", b"parse error")?;
+            return Ok(Next::KeepAlive);
+        }
+    };
+    write_response(writer, 202, &[], "text/plain", b"")?;
+    writer.flush()?;
+    for message in messages {
+        for event in dispatch(jobs, message)?.0 {
+            match event {
+                Event::Notification(value) => {
+                    let _ = stream.send(value);
+                }
+                Event::Done(response) => {
+                    if let Some(response) = response {
+                        let _ = stream.send(response);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    Ok(Next::KeepAlive)
+}
+
+struct Pending(Receiver<Event>);
+
+impl Pending {
+    /// Drop notifications and return the response.
+    fn wait(self) -> Option<Value> {
+        self.0.iter().find_map(|event| match event {
+            Event::Done(response) => Some(response),
+            Event::Notification(_) => None,
+        })?
+    }
+}
+
+fn dispatch(jobs: &Sender<Job>, message: Value) -> Result<Pending> {
+    let (events, rx) = mpsc::channel();
+    jobs.send(Job { message, events })
+        .map_err(|_| anyhow::anyhow!("MCP server stopped"))?;
+    Ok(Pending(rx))
+}
+
+fn is_request(message: &Value) -> bool {
+    message.get("method").is_some() && message.get("id").is_some_and(|id| !id.is_null())
+}
+
+fn has_progress_token(message: &Value) -> bool {
+    message
+        .get("params")
+        .and_then(|params| params.get("_meta"))
+        .and_then(|meta| meta.get("progressToken"))
+        .is_some()
+}
+
+fn new_session_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let seed = format!(
+        "{nanos}-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    );
+    blake3::hash(seed.as_bytes()).to_hex()[..32].to_string()
+}
+
+fn is_local_origin(origin: &str) -> bool {
+    let authority = origin
+        .split_once("://")
+        .map_or(origin, |(_, rest)| rest)
+        .split('/')
+        .next()
+        .unwrap_or("");
+    let host = if let Some(rest) = authority.strip_prefix('[') {
+        rest.split(']').next().unwrap_or("")
+    } else {
+        authority.split(':').next().unwrap_or("")
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+/// Read one HTTP/1.1 request, or `None` when the peer closed the connection.
+fn read_request(reader: &mut BufReader<TcpStream>) -> Result<Option<Request>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        bail!("malformed request line: {}", line.trim_end());
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        headers: Vec::new(),
+        body: Vec::new(),
+    };
+
+    let mut header_bytes = 0;
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        header_bytes += read;
+        if read == 0 || header_bytes > MAX_HEADER_BYTES {
+            bail!("truncated or oversized request headers");
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            request
+                .headers
+                .push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    if request
+        .header("transfer-encoding")
+        .is_some_and(|value| value.eq_ignore_ascii_case("chunked"))
+    {
+        bail!("chunked request bodies are not supported");
+    }
+    let length = match request.header("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .with_context(|| format!("invalid content-length {value}"))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        bail!("request body too large ({length} bytes)");
+    }
+    request.body.resize(length, 0);
+    reader.read_exact(&mut request.body)?;
+    Ok(Some(request))
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+fn write_response(
+    writer: &mut TcpStream,
+    status: u16,
+    headers: &[(&str, String)],
+    content_type: &str,
+    body: &[u8],
+) -> Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {status} {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n",
+        status_text(status),
+        body.len()
+    )?;
+    for (name, value) in headers {
+        write!(writer, "{name}: {value}\r\n")?;
+    }
+    writer.write_all(b"\r\n")?;
+    writer.write_all(body)?;
+    Ok(())
+}
+
+fn write_json(
+    writer: &mut TcpStream,
+    status: u16,
+    headers: &[(&str, String)],
+    body: &Value,
+) -> Result<()> {
+    let body = serde_json::to_vec(body)?;
+    write_response(writer, status, headers, "application/json", &body)
+}
+
+fn write_sse_head(writer: &mut TcpStream, headers: &[(&str, String)]) -> Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n"
+    )?;
+    for (name, value) in headers {
+        write!(writer, "{name}: {value}\r\n")?;
+    }
+    writer.write_all(b"\r\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_sse_event(writer: &mut TcpStream, event: &str, data: &Value) -> Result<()> {
+    write!(
+        writer,
+        "event: {event}\ndata: {}\n\n",
+        serde_json::to_string(data)?
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn local_origins_are_accepted() {
+        assert!(is_local_origin("http://localhost:3000"));
+        assert!(is_local_origin("http://127.0.0.1"));
+        assert!(is_local_origin("https://[::1]:8443/app"));
+        assert!(!is_local_origin("https://evil.example"));
+        assert!(!is_local_origin("http://localhost.evil.example"));
+        assert!(!is_local_origin("null"));
+    }
+
+    #[test]
+    fn requests_are_told_apart_from_notifications() {
+        assert!(is_request(&json!({"id": 1, "method": "ping"})));
+        assert!(!is_request(&json!({"method": "notifications/initialized"})));
+        assert!(!is_request(&json!({"id": 1, "result": {}})));
+        assert!(has_progress_token(
+            &json!({"id": 1, "method": "tools/call", "params": {"_meta": {"progressToken": 7}}})
+        ));
+    }
+}
@@ -0,0 +1,1183 @@
+#[cfg(unix)]
+use crate::daemon;
+use crate::indexer::{IndexProgress, Indexer, ProgressCallback};
+use crate::rpc;
+use crate::watch;
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+mod http;
+
+pub use http::serve_http;
+
+/// Name of the original single dispatch tool, still accepted by `tools/call`.
+const LEGACY_TOOL_NAME: &str = "lidx";
+
+struct Defaults {
+    repo_root: PathBuf,
+    db_path: PathBuf,
+}
+
+#[derive(Clone, Copy)]
+enum TextMode {
+    None,
+    Compact,
+    Pretty,
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+struct CacheKey {
+    repo_root: PathBuf,
+    db_path: PathBuf,
+}
+
+struct State {
+    defaults: Defaults,
+    indexers: HashMap<CacheKey, Indexer>,
+    watch_config: watch::WatchConfig,
+    watcher: Option<watch::WatchHandle>,
+    watch_target: Option<(PathBuf, PathBuf)>,
+    /// Forward calls to the repo daemon instead of indexing in-process.
+    use_daemon: bool,
+    #[cfg(unix)]
+    clients: HashMap<CacheKey, daemon::Client>,
+    /// Sends server-to-client notifications on the transport of the message
+    /// being handled (progress for long `reindex` calls).
+    notifier: Option<Notifier>,
+}
+
+type Notifier = Rc<dyn Fn(Value)>;
+
+impl State {
+    fn new(defaults: Defaults, watch_config: watch::WatchConfig, use_daemon: bool) -> Self {
+        Self {
+            defaults,
+            indexers: HashMap::new(),
+            watch_config,
+            watcher: None,
+            watch_target: None,
+            use_daemon: use_daemon && cfg!(unix),
+            #[cfg(unix)]
+            clients: HashMap::new(),
+            notifier: None,
+        }
+    }
+
+    fn call(
+        &mut self,
+        repo_root: PathBuf,
+        db_path: PathBuf,
+        method: &str,
+        params: Value,
+    ) -> Result<Value> {
+        self.call_with_progress(repo_root, db_path, method, params, None)
+    }
+
+    /// Like `call`, reporting reindex progress to `progress`, which the
+    /// daemon relays when calls are forwarded to it.
+    fn call_with_progress(
+        &mut self,
+        repo_root: PathBuf,
+        db_path: PathBuf,
+        method: &str,
+        params: Value,
+        mut progress: Option<ProgressCallback>,
+    ) -> Result<Value> {
+        #[cfg(unix)]
+        if self.use_daemon {
+            match self.call_daemon(&repo_root, &db_path, method, &params, progress.as_mut()) {
+                Ok(response) => return rpc::response_result(&response),
+                Err(err) => {
+                    eprintln!("lidx: daemon unavailable ({err}), indexing in-process");
+                    self.use_daemon = false;
+                    if let Err(err) = self.ensure_watch(&repo_root, &db_path) {
+                        eprintln!("watch error: {err}");
+                    }
+                }
+            }
+        }
+        let indexer = self.get_indexer(repo_root, db_path)?;
+        let reports_progress = progress.is_some();
+        if reports_progress {
+            indexer.set_progress(progress);
+        }
+        let result = rpc::handle_method(indexer, method, params);
+        if reports_progress {
+            indexer.set_progress(None);
+        }
+        result
+    }
+
+    /// Call `method` against the default repo/db (used by resources and prompts).
+    fn call_default(&mut self, method: &str, params: Value) -> Result<Value> {
+        let repo_root = self.defaults.repo_root.clone();
+        let db_path = self.defaults.db_path.clone();
+        if !self.use_daemon
+            && self.watcher.is_none()
+            && let Err(err) = self.ensure_watch(&repo_root, &db_path)
+        {
+            eprintln!("watch error: {err}");
+        }
+        self.call(repo_root, db_path, method, params)
+    }
+
+    /// Send one request to the daemon for `repo_root`/`db_path`, reconnecting
+    /// (and respawning) once if a cached connection has gone away.
+    #[cfg(unix)]
+    fn call_daemon(
+        &mut self,
+        repo_root: &Path,
+        db_path: &Path,
+        method: &str,
+        params: &Value,
+        mut progress: Option<&mut ProgressCallback>,
+    ) -> Result<String> {
+        let key = CacheKey {
+            repo_root: repo_root.to_path_buf(),
+            db_path: db_path.to_path_buf(),
+        };
+        let line = serde_json::to_string(&json!({
+            "id": 1,
+            "method": method,
+            "params": params,
+        }))?;
+        let mut request = |client: &mut daemon::Client| match progress.as_mut() {
+            Some(progress) => client.request_line_with_progress(&line, &mut **progress),
+            None => client.request_line(&line),
+        };
+        if let Some(client) = self.clients.get_mut(&key) {
+            match request(client) {
+                Ok(response) => return Ok(response),
+                Err(_) => {
+                    self.clients.remove(&key);
+                }
+            }
+        }
+        let options = daemon::SpawnOptions {
+            repo_root: key.repo_root.clone(),
+            db_path: key.db_path.clone(),
+            no_ignore: self.watch_config.scan_options.no_ignore,
+            watch: self.watch_config.mode,
+        };
+        let mut client = daemon::Client::connect_or_spawn(&options)?;
+        let response = request(&mut client)?;
+        self.clients.insert(key, client);
+        Ok(response)
+    }
+
+    fn set_defaults(&mut self, repo_root: PathBuf, db_path: PathBuf) {
+        self.defaults = Defaults { repo_root, db_path };
+    }
+
+    fn get_indexer(&mut self, repo_root: PathBuf, db_path: PathBuf) -> Result<&mut Indexer> {
+        let key = CacheKey {
+            repo_root: repo_root.clone(),
+            db_path: db_path.clone(),
+        };
+        if !self.indexers.contains_key(&key) {
+            let indexer =
+                Indexer::new_with_options(repo_root, db_path, self.watch_config.scan_options)?;
+            self.indexers.insert(key.clone(), indexer);
+        }
+        Ok(self.indexers.get_mut(&key).expect("indexer cache"))
+    }
+
+    fn ensure_watch(&mut self, repo_root: &Path, db_path: &Path) -> Result<()> {
+        if self.watch_config.mode == watch::WatchMode::Off {
+            return Ok(());
+        }
+        let target = (repo_root.to_path_buf(), db_path.to_path_buf());
+        if self.watch_target.as_ref() == Some(&target) {
+            return Ok(());
+        }
+        let next = watch::start(
+            repo_root.to_path_buf(),
+            db_path.to_path_buf(),
+            self.watch_config,
+        )?;
+        if let Some(handle) = self.watcher.take() {
+            handle.stop();
+        }
+        self.watcher = next;
+        self.watch_target = Some(target);
+        Ok(())
+    }
+}
+
+/// Serve MCP over stdio. With `use_daemon`, tool calls are forwarded to the
+/// repo daemon (spawned on demand) which owns indexing and watching.
+pub fn serve(
+    repo_root: PathBuf,
+    db_path: PathBuf,
+    watch_config: watch::WatchConfig,
+    use_daemon: bool,
+) -> Result<()> {
+    let defaults = Defaults {
+        repo_root: repo_root.clone(),
+        db_path: db_path.clone(),
+    };
+    let mut state = State::new(defaults, watch_config, use_daemon);
+    state.notifier = Some(Rc::new(|notification: Value| {
+        let mut stdout = io::stdout().lock();
+        if let Ok(line) = serde_json::to_string(&notification) {
+            let _ = writeln!(stdout, "{line}");
+            let _ = stdout.flush();
+        }
+    }));
+
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(value) => value,
+            Err(err) => {
+                eprintln!("stdin error: {err}");
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(value) => handle_message(value, &mut state),
+            Err(err) => Some(jsonrpc_error(
+                Value::Null,
+                -32700,
+                &format!("parse error: {err}"),
+            )),
+        };
+
+        if let Some(payload) = response {
+            writeln!(stdout, "{}", serde_json::to_string(&payload)?)?;
+            stdout.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_message(message: Value, state: &mut State) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let method = message.get("method").and_then(|value| value.as_str());
+
+    let Some(method) = method else {
+        return id.map(|id| jsonrpc_error(id, -32600, "invalid request"));
+    };
+
+    match method {
+        "initialize" => {
+            let id = id?;
+            Some(jsonrpc_result(id, initialize_result(&message)))
+        }
+        "notifications/initialized" => None,
+        "ping" => id.map(|id| jsonrpc_result(id, json!({}))),
+        "tools/list" => {
+            let id = id?;
+            Some(jsonrpc_result(id, json!({ "tools": tool_specs() })))
+        }
+        "tools/call" => {
+            let id = id?;
+            Some(handle_tool_call(id, &message, state))
+        }
+        "resources/list" => id.map(|id| jsonrpc_result(id, resources_list())),
+        "resources/templates/list" => id.map(|id| jsonrpc_result(id, resource_templates())),
+        "resources/read" => {
+            let id = id?;
+            Some(handle_resource_read(id, &message, state))
+        }
+        "prompts/list" => id.map(|id| jsonrpc_result(id, prompts_list())),
+        "prompts/get" => {
+            let id = id?;
+            Some(handle_prompt_get(id, &message, state))
+        }
+        "roots/list" => id.map(|id| jsonrpc_result(id, json!({ "roots": [] }))),
+        _ => id.map(|id| jsonrpc_error(id, -32601, "method not found")),
+    }
+}
+
+/// Methods called out by name in the "START HERE" instructions line.
+/// Everything else in METHOD_LIST surfaces in the "Other methods" line.
+const FEATURED_METHODS: &[&str] = &[
+    "explain_symbol",
+    "analyze_diff",
+    "trace_flow",
+    "orient",
+    "search",
+    "gather_context",
+];
+
+fn other_methods_list() -> String {
+    rpc::METHOD_LIST
+        .iter()
+        .filter(|&&m| !FEATURED_METHODS.contains(&m))
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn initialize_result(message: &Value) -> Value {
+    let protocol = message
+        .get("params")
+        .and_then(|params| params.get("protocolVersion"))
+        .cloned()
+        .unwrap_or_else(|| Value::String("2024-11-05".to_string()));
+    json!({
+        "protocolVersion": protocol,
+        "capabilities": { "tools": {}, "resources": {}, "prompts": {} },
+        "serverInfo": {
+            "name": "lidx",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "instructions": format!(
+            "Each lidx method is its own tool for querying a code index. \
+    Start with onboard or orient for an overview.\n\
+    \n\
+    START HERE: explain_symbol for deep symbol understanding (one call replaces 5+). \
+    analyze_diff for change impact. trace_flow for call chains. \
+    orient for architecture overview. search for regex. \
+    gather_context for LLM-ready context.\n\
+    \n\
+    Other methods: {other_methods}.\n\
+    \n\
+    Edge kinds: CALLS, IMPORTS, CONTAINS, EXTENDS, IMPLEMENTS, INHERITS, RPC_IMPL, RPC_CALL, RPC_ROUTE, \
+    HTTP_ROUTE, HTTP_CALL, GRAPHQL_CALL, GRAPHQL_RESOLVE, CHANNEL_PUBLISH, CHANNEL_SUBSCRIBE, CONFIG_SOURCE, CONFIG_READ, CONFIG_BIND, \
+    ENTRYPOINT, XREF, MODULE_FILE, IMPORTS_FILE, DOC_REF. Scope values: code, docs, tests, examples, all.",
+            other_methods = other_methods_list()
+        ),
+    })
+}
+
+/// Tool-level options accepted by every tool alongside the method params.
+const TOOL_OPTION_KEYS: &[&str] = &[
+    "repo",
+    "db",
+    "set_default",
+    "text_mode",
+    "include_structured",
+];
+
+fn tool_option_properties() -> Value {
+    json!({
+        "repo": {
+            "type": "string",
+            "description": "Optional repo root override for this call."
+        },
+        "db": {
+            "type": "string",
+            "description": "Optional db path override for this call."
+        },
+        "set_default": {
+            "type": "boolean",
+            "description": "If true, update default repo/db for subsequent calls."
+        },
+        "text_mode": {
+            "type": "string",
+            "enum": ["pretty", "compact", "none"],
+            "description": "Controls textual output size in tool responses."
+        },
+        "include_structured": {
+            "type": "boolean",
+            "description": "If false, omit structuredContent from tool responses."
+        }
+    })
+}
+
+/// One MCP tool per RPC method, with its params schema from `rpc::schema`
+/// plus the shared tool options.
+fn tool_specs() -> Vec<Value> {
+    rpc::METHOD_LIST
+        .iter()
+        .map(|&method| {
+            let mut schema = rpc::method_param_schema(method);
+            if let Some(obj) = schema.as_object_mut() {
+                obj.insert("type".to_string(), json!("object"));
+                let properties = obj.entry("properties").or_insert_with(|| json!({}));
+                if let (Some(properties), Value::Object(options)) =
+                    (properties.as_object_mut(), tool_option_properties())
+                {
+                    for (key, value) in options {
+                        properties.entry(key).or_insert(value);
+                    }
+                }
+            }
+            json!({
+                "name": method,
+                "description": rpc::method_description(method),
+                "inputSchema": schema,
+            })
+        })
+        .collect()
+}
+
+fn handle_tool_call(id: Value, message: &Value, state: &mut State) -> Value {
+    let params = match message.get("params") {
+        Some(value) => value,
+        None => return jsonrpc_error(id, -32602, "missing params"),
+    };
+    let tool_name = params
+        .get("name")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+
+    let arguments = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    let (method, call_params) = if tool_name == LEGACY_TOOL_NAME {
+        // Single-tool form from earlier releases: { method, params, ... }.
+        let method = match arguments.get("method").and_then(|value| value.as_str()) {
+            Some(value) => value.to_string(),
+            None => return jsonrpc_error(id, -32602, "missing method"),
+        };
+        let call_params = arguments
+            .get("params")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        (method, call_params)
+    } else if rpc::METHOD_LIST.contains(&tool_name) {
+        let mut call_params = arguments.clone();
+        if let Some(obj) = call_params.as_object_mut() {
+            for key in TOOL_OPTION_KEYS {
+                obj.remove(*key);
+            }
+        }
+        (tool_name.to_string(), call_params)
+    } else {
+        return jsonrpc_error(id, -32601, "unknown tool");
+    };
+    let text_mode = text_mode_from_args(&arguments);
+    let include_structured = include_structured_from_args(&arguments);
+    let (repo_root, db_path) = repo_and_db(&arguments, &state.defaults);
+    let set_default = arguments
+        .get("set_default")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    // Start watcher lazily on first call (or when defaults change); the daemon
+    // owns watching when calls are forwarded to it.
+    if !state.use_daemon
+        && (set_default || state.watcher.is_none())
+        && let Err(err) = state.ensure_watch(&repo_root, &db_path)
+    {
+        eprintln!("watch error: {err}");
+    }
+    if set_default {
+        state.set_defaults(repo_root.clone(), db_path.clone());
+    }
+
+    let progress = progress_callback(message, &method, state);
+    match state.call_with_progress(repo_root, db_path, &method, call_params, progress) {
+        Ok(result) => jsonrpc_result(id, call_result_ok(result, text_mode, include_structured)),
+        Err(err) => jsonrpc_result(
+            id,
+            call_result_error(&err.to_string(), text_mode, include_structured),
+        ),
+    }
+}
+
+/// Progress reporter for a `reindex` call whose request carries
+/// `_meta.progressToken`, emitting `notifications/progress` via the notifier.
+fn progress_callback(message: &Value, method: &str, state: &State) -> Option<ProgressCallback> {
+    if method != "reindex" {
+        return None;
+    }
+    let token = message
+        .get("params")
+        .and_then(|params| params.get("_meta"))
+        .and_then(|meta| meta.get("progressToken"))
+        .filter(|token| token.is_string() || token.is_i64() || token.is_u64())?
+        .clone();
+    let notifier = Rc::clone(state.notifier.as_ref()?);
+    Some(Box::new(move |progress: IndexProgress| {
+        notifier(progress_notification(&token, progress));
+    }))
+}
+
+fn progress_notification(token: &Value, progress: IndexProgress) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/progress",
+        "params": {
+            "progressToken": token,
+            "progress": progress.done,
+            "total": progress.total,
+            "message": format!("reindex: {}", progress.phase),
+        }
+    })
+}
+
+const RESOURCE_SCHEME: &str = "lidx://";
+
+fn resources_list() -> Value {
+    json!({
+        "resources": [
+            {
+                "uri": "lidx://repo_map",
+                "name": "repo_map",
+                "description": "Condensed map of the repository's files and key symbols.",
+                "mimeType": "text/plain"
+            },
+            {
+                "uri": "lidx://onboard",
+                "name": "onboard",
+                "description": "Repository summary with languages, layout and suggested first queries.",
+                "mimeType": "application/json"
+            }
+        ]
+    })
+}
+
+fn resource_templates() -> Value {
+    json!({
+        "resourceTemplates": [
+            {
+                "uriTemplate": "lidx://file/{path}",
+                "name": "file",
+                "description": "Source of a repository file (path relative to the repo root).",
+                "mimeType": "text/plain"
+            },
+            {
+                "uriTemplate": "lidx://symbol/{qualname}",
+                "name": "symbol",
+                "description": "explain_symbol output for a qualified symbol name.",
+                "mimeType": "application/json"
+            }
+        ]
+    })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ResourceRef {
+    RepoMap,
+    Onboard,
+    File(String),
+    Symbol(String),
+}
+
+fn parse_resource_uri(uri: &str) -> Option<ResourceRef> {
+    let rest = uri.strip_prefix(RESOURCE_SCHEME)?;
+    match rest {
+        "repo_map" => Some(ResourceRef::RepoMap),
+        "onboard" => Some(ResourceRef::Onboard),
+        _ => {
+            if let Some(path) = rest.strip_prefix("file/") {
+                let path = percent_decode(path);
+                (!path.is_empty()).then_some(ResourceRef::File(path))
+            } else if let Some(qualname) = rest.strip_prefix("symbol/") {
+                let qualname = percent_decode(qualname);
+                (!qualname.is_empty()).then_some(ResourceRef::Symbol(qualname))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%'
+            && let Some(hex) = raw.get(idx + 1..idx + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            out.push(byte);
+            idx += 3;
+            continue;
+        }
+        out.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn handle_resource_read(id: Value, message: &Value, state: &mut State) -> Value {
+    let Some(uri) = message
+        .get("params")
+        .and_then(|params| params.get("uri"))
+        .and_then(|value| value.as_str())
+    else {
+        return jsonrpc_error(id, -32602, "missing uri");
+    };
+    let Some(resource) = parse_resource_uri(uri) else {
+        return jsonrpc_error(id, -32002, &format!("resource not found: {uri}"));
+    };
+    match read_resource(&resource, state) {
+        Ok((mime_type, text)) => jsonrpc_result(
+            id,
+            json!({
+                "contents": [{ "uri": uri, "mimeType": mime_type, "text": text }]
+            }),
+        ),
+        Err(err) => jsonrpc_error(id, -32002, &err.to_string()),
+    }
+}
+
+fn read_resource(resource: &ResourceRef, state: &mut State) -> Result<(&'static str, String)> {
+    match resource {
+        ResourceRef::RepoMap => {
+            let result = state.call_default("repo_map", json!({}))?;
+            let text = match result.get("text").and_then(|value| value.as_str()) {
+                Some(text) => text.to_string(),
+                None => serde_json::to_string_pretty(&result)?,
+            };
+            Ok(("text/plain", text))
+        }
+        ResourceRef::Onboard => {
+            let result = state.call_default("onboard", json!({}))?;
+            Ok(("application/json", serde_json::to_string_pretty(&result)?))
+        }
+        ResourceRef::File(path) => {
+            let (abs, _) = crate::util::resolve_repo_path_for_op(
+                &state.defaults.repo_root,
+                path,
+                "resources/read",
+            )?;
+            Ok(("text/plain", crate::util::read_to_string(&abs)?))
+        }
+        ResourceRef::Symbol(qualname) => {
+            let result = state.call_default("explain_symbol", json!({ "qualname": qualname }))?;
+            Ok(("application/json", serde_json::to_string_pretty(&result)?))
+        }
+    }
+}
+
+struct PromptSpec {
+    name: &'static str,
+    description: &'static str,
+    /// (name, description, required)
+    arguments: &'static [(&'static str, &'static str, bool)],
+}
+
+const PROMPTS: &[PromptSpec] = &[
+    PromptSpec {
+        name: "review_diff",
+        description: "Review a diff together with its lidx impact analysis (changed symbols, callers, tests, risk).",
+        arguments: &[
+            ("diff", "Unified diff text to review.", false),
+            (
+                "paths",
+                "Comma-separated changed paths, when no diff is given.",
+                false,
+            ),
+        ],
+    },
+    PromptSpec {
+        name: "explain_symbol",
+        description: "Explain a symbol from its definition, callers, callees and tests.",
+        arguments: &[("qualname", "Qualified name of the symbol.", true)],
+    },
+    PromptSpec {
+        name: "onboard",
+        description: "Get oriented in this repository: layout, languages and where to start.",
+        arguments: &[],
+    },
+];
+
+fn prompts_list() -> Value {
+    let prompts: Vec<Value> = PROMPTS
+        .iter()
+        .map(|prompt| {
+            let arguments: Vec<Value> = prompt
+                .arguments
+                .iter()
+                .map(|(name, description, required)| {
+                    json!({ "name": name, "description": description, "required": required })
+                })
+                .collect();
+            json!({
+                "name": prompt.name,
+                "description": prompt.description,
+                "arguments": arguments,
+            })
+        })
+        .collect();
+    json!({ "prompts": prompts })
+}
+
+fn handle_prompt_get(id: Value, message: &Value, state: &mut State) -> Value {
+    let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+    let Some(name) = params.get("name").and_then(|value| value.as_str()) else {
+        return jsonrpc_error(id, -32602, "missing prompt name");
+    };
+    let Some(prompt) = PROMPTS.iter().find(|prompt| prompt.name == name) else {
+        return jsonrpc_error(id, -32602, &format!("unknown prompt: {name}"));
+    };
+    let arguments = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    let arg = |key: &str| {
+        arguments
+            .get(key)
+            .and_then(|value| value.as_str())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    for (key, _, required) in prompt.arguments {
+        if *required && arg(key).is_none() {
+            return jsonrpc_error(id, -32602, &format!("missing argument: {key}"));
+        }
+    }
+    match build_prompt_text(prompt.name, &arg, state) {
+        Ok(text) => jsonrpc_result(
+            id,
+            json!({
+                "description": prompt.description,
+                "messages": [{ "role": "user", "content": { "type": "text", "text": text } }]
+            }),
+        ),
+        Err(err) => jsonrpc_error(id, -32602, &err.to_string()),
+    }
+}
+
+/// Render a prompt with the relevant lidx results embedded, so the model
+/// starts from the index data rather than having to query for it.
+fn build_prompt_text(
+    name: &str,
+    arg: &dyn Fn(&str) -> Option<String>,
+    state: &mut State,
+) -> Result<String> {
+    let mut text = String::new();
+    match name {
+        "review_diff" => {
+            let diff = arg("diff");
+            let params = match (&diff, arg("paths")) {
+                (Some(diff), _) => json!({ "diff": diff }),
+                (None, Some(paths)) => json!({
+                    "paths": paths
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|path| !path.is_empty())
+                        .collect::<Vec<_>>()
+                }),
+                (None, None) => anyhow::bail!("review_diff needs a diff or paths argument"),
+            };
+            let impact = state.call_default("analyze_diff", params)?;
+            text.push_str(
+                "Review this change. Use the impact analysis below to check affected callers, \
+                 cross-service edges and tests; call out risky spots and missing coverage, and \
+                 use the lidx tools where the analysis is inconclusive.\n\n",
+            );
+            if let Some(diff) = diff {
+                text.push_str(&format!("```diff\n{diff}\n```\n\n"));
+            }
+            text.push_str(&format!(
+                "Impact analysis (analyze_diff):\n```json\n{}\n```\n",
+                serde_json::to_string_pretty(&impact)?
+            ));
+        }
+        "explain_symbol" => {
+            let qualname = arg("qualname").unwrap_or_default();
+            let explained =
+                state.call_default("explain_symbol", json!({ "qualname": qualname }))?;
+            text.push_str(&format!(
+                "Explain what `{qualname}` does, how it is used and what would break if it \
+                 changed, based on this lidx data:\n```json\n{}\n```\n",
+                serde_json::to_string_pretty(&explained)?
+            ));
+        }
+        "onboard" => {
+            let overview = state.call_default("onboard", json!({}))?;
+            text.push_str(&format!(
+                "Give me an orientation to this repository: its purpose, main components, \
+                 languages and where to start reading. Base it on this lidx overview:\n\
+                 ```json\n{}\n```\n",
+                serde_json::to_string_pretty(&overview)?
+            ));
+        }
+        _ => anyhow::bail!("unknown prompt: {name}"),
+    }
+    Ok(text)
+}
+
+const MAX_RESPONSE_BYTES: usize = 512_000; // 500KB hard cap
+
+fn call_result_ok(result: Value, text_mode: TextMode, include_structured: bool) -> Value {
+    let content = match format_text(&result, text_mode) {
+        Some(text) if text.len() > MAX_RESPONSE_BYTES => vec![json!({
+            "type": "text",
+            "text": format!(
+                "Response too large ({} bytes, {} est. tokens). Reduce limit or use a more specific query.",
+                text.len(),
+                text.len() / 4
+            )
+        })],
+        Some(text) => vec![json!({ "type": "text", "text": text })],
+        None => Vec::new(),
+    };
+    let mut payload = json!({
+        "content": content,
+        "isError": false
+    });
+    if include_structured {
+        // Ensure structuredContent is always an object
+        payload["structuredContent"] = ensure_object_response(result);
+    }
+    payload
+}
+
+fn ensure_object_response(result: Value) -> Value {
+    if result.is_array() {
+        json!({ "items": result })
+    } else {
+        result
+    }
+}
+
+fn call_result_error(message: &str, text_mode: TextMode, _include_structured: bool) -> Value {
+    let content = match text_mode {
+        TextMode::None => Vec::new(),
+        _ => vec![json!({ "type": "text", "text": message })],
+    };
+    json!({
+        "content": content,
+        "isError": true
+    })
+}
+
+fn format_text(value: &Value, text_mode: TextMode) -> Option<String> {
+    match text_mode {
+        TextMode::None => None,
+        TextMode::Compact => serde_json::to_string(value).ok(),
+        TextMode::Pretty => serde_json::to_string_pretty(value).ok(),
+    }
+}
+
+fn jsonrpc_result(id: Value, result: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": result
+    })
+}
+
+fn jsonrpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": code,
+            "message": message
+        }
+    })
+}
+
+fn repo_and_db(arguments: &Value, defaults: &Defaults) -> (PathBuf, PathBuf) {
+    let repo_override = arguments
+        .get("repo")
+        .and_then(|value| value.as_str())
+        .map(PathBuf::from);
+    let has_repo_override = repo_override.is_some();
+    let db_override = arguments
+        .get("db")
+        .and_then(|value| value.as_str())
+        .map(PathBuf::from);
+
+    let repo_root = repo_override.unwrap_or_else(|| defaults.repo_root.clone());
+    let db_path = match db_override {
+        Some(path) => path,
+        None if has_repo_override => default_db_path(&repo_root),
+        None => defaults.db_path.clone(),
+    };
+    (repo_root, db_path)
+}
+
+fn default_db_path(repo: &Path) -> PathBuf {
+    repo.join(".lidx").join(".lidx.sqlite")
+}
+
+fn text_mode_from_args(arguments: &Value) -> TextMode {
+    match arguments.get("text_mode").and_then(|value| value.as_str()) {
+        Some("none") => TextMode::None,
+        Some("compact") => TextMode::Compact,
+        Some("pretty") => TextMode::Pretty,
+        _ => TextMode::Compact,
+    }
+}
+
+fn include_structured_from_args(arguments: &Value) -> bool {
+    arguments
+        .get("include_structured")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let mut dir = std::env::temp_dir();
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+        dir.push(format!("lidx-mcp-{label}-{nanos}-{counter}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn featured_methods_are_dispatchable() {
+        for method in FEATURED_METHODS {
+            assert!(
+                rpc::METHOD_LIST.contains(method),
+                "FEATURED_METHODS contains '{method}' which is not in METHOD_LIST; \
+                 the START HERE instructions line has drifted from dispatch"
+            );
+        }
+    }
+
+    #[test]
+    fn instructions_mention_every_method_and_no_phantom_help() {
+        let init = initialize_result(&json!({}));
+        let instructions = init["instructions"].as_str().unwrap();
+        for method in rpc::METHOD_LIST {
+            assert!(
+                instructions.contains(method),
+                "instructions do not mention dispatchable method '{method}'"
+            );
+        }
+        assert!(
+            !instructions.contains("help"),
+            "instructions mention a 'help' method, which is not dispatchable"
+        );
+        for spec in tool_specs() {
+            let description = spec["description"].as_str().unwrap();
+            assert!(
+                !description.contains("help"),
+                "tool description mentions a 'help' method, which is not dispatchable"
+            );
+        }
+    }
+
+    #[test]
+    fn tools_are_published_per_method() {
+        let specs = tool_specs();
+        let names: Vec<&str> = specs
+            .iter()
+            .map(|spec| spec["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, rpc::METHOD_LIST);
+        let explain = specs
+            .iter()
+            .find(|spec| spec["name"] == "explain_symbol")
+            .unwrap();
+        let properties = &explain["inputSchema"]["properties"];
+        assert!(properties.get("qualname").is_some());
+        assert!(properties.get("repo").is_some());
+        assert!(properties.get("method").is_none());
+        assert_eq!(explain["inputSchema"]["type"], "object");
+    }
+
+    #[test]
+    fn resource_uris_parse() {
+        assert_eq!(
+            parse_resource_uri("lidx://repo_map"),
+            Some(ResourceRef::RepoMap)
+        );
+        assert_eq!(
+            parse_resource_uri("lidx://file/src/my%20file.rs"),
+            Some(ResourceRef::File("src/my file.rs".to_string()))
+        );
+        assert_eq!(
+            parse_resource_uri("lidx://symbol/pkg.mod.Class.run"),
+            Some(ResourceRef::Symbol("pkg.mod.Class.run".to_string()))
+        );
+        assert_eq!(parse_resource_uri("lidx://file/"), None);
+        assert_eq!(parse_resource_uri("file:///etc/passwd"), None);
+    }
+
+    #[test]
+    fn tool_call_resources_and_prompts_round_trip() {
+        let repo_root = temp_dir("mcp-surface");
+        std::fs::write(
+            repo_root.join("app.py"),
+            "def greet(name):\n    return name\n",
+        )
+        .unwrap();
+        let db_path = repo_root.join(".lidx").join(".lidx.sqlite");
+        let watch_config = watch::WatchConfig {
+            mode: watch::WatchMode::Off,
+            ..watch::WatchConfig::default()
+        };
+        let mut state = State::new(
+            Defaults {
+                repo_root: repo_root.clone(),
+                db_path,
+            },
+            watch_config,
+            false,
+        );
+
+        let reindex = handle_message(
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call",
+                   "params": {"name": "reindex", "arguments": {"text_mode": "none"}}}),
+            &mut state,
+        )
+        .unwrap();
+        assert_eq!(reindex["result"]["isError"], json!(false), "{reindex}");
+
+        let file = handle_message(
+            json!({"jsonrpc": "2.0", "id": 2, "method": "resources/read",
+                   "params": {"uri": "lidx://file/app.py"}}),
+            &mut state,
+        )
+        .unwrap();
+        let text = file["result"]["contents"][0]["text"].as_str().unwrap();
+        assert!(text.contains("def greet"));
+
+        let symbol = handle_message(
+            json!({"jsonrpc": "2.0", "id": 3, "method": "resources/read",
+                   "params": {"uri": "lidx://symbol/app.greet"}}),
+            &mut state,
+        )
+        .unwrap();
+        assert!(
+            symbol["result"]["contents"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("app.greet"),
+            "{symbol}"
+        );
+
+        let escape = handle_message(
+            json!({"jsonrpc": "2.0", "id": 4, "method": "resources/read",
+                   "params": {"uri": "lidx://file/../outside.txt"}}),
+            &mut state,
+        )
+        .unwrap();
+        assert!(escape.get("error").is_some(), "{escape}");
+
+        let prompt = handle_message(
+            json!({"jsonrpc": "2.0", "id": 5, "method": "prompts/get",
+                   "params": {"name": "explain_symbol", "arguments": {"qualname": "app.greet"}}}),
+            &mut state,
+        )
+        .unwrap();
+        let text = prompt["result"]["messages"][0]["content"]["text"]
+            .as_str()
+            .unwrap();
+        assert!(text.contains("app.greet"));
+
+        let missing = handle_message(
+            json!({"jsonrpc": "2.0", "id": 6, "method": "prompts/get",
+                   "params": {"name": "review_diff", "arguments": {}}}),
+            &mut state,
+        )
+        .unwrap();
+        assert!(missing.get("error").is_some());
+
+        let _ = std::fs::remove_dir_all(&repo_root);
+    }
+
+    #[test]
+    fn repo_and_db_defaults() {
+        let defaults = Defaults {
+            repo_root: PathBuf::from("/repo"),
+            db_path: PathBuf::from("/repo/.lidx/.lidx.sqlite"),
+        };
+        let args = json!({});
+        let (repo, db) = repo_and_db(&args, &defaults);
+        assert_eq!(repo, PathBuf::from("/repo"));
+        assert_eq!(db, PathBuf::from("/repo/.lidx/.lidx.sqlite"));
+    }
+
+    #[test]
+    fn repo_and_db_repo_override() {
+        let defaults = Defaults {
+            repo_root: PathBuf::from("/repo"),
+            db_path: PathBuf::from("/repo/.lidx/.lidx.sqlite"),
+        };
+        let args = json!({ "repo": "/other" });
+        let (repo, db) = repo_and_db(&args, &defaults);
+        assert_eq!(repo, PathBuf::from("/other"));
+        assert_eq!(db, PathBuf::from("/other/.lidx/.lidx.sqlite"));
+    }
+
+    #[test]
+    fn repo_and_db_db_override() {
+        let defaults = Defaults {
+            repo_root: PathBuf::from("/repo"),
+            db_path: PathBuf::from("/repo/.lidx/.lidx.sqlite"),
+        };
+        let args = json!({ "repo": "/other", "db": "/tmp/custom.sqlite" });
+        let (repo, db) = repo_and_db(&args, &defaults);
+        assert_eq!(repo, PathBuf::from("/other"));
+        assert_eq!(db, PathBuf::from("/tmp/custom.sqlite"));
+    }
+
+    #[test]
+    fn text_mode_parsing() {
+        assert!(matches!(text_mode_from_args(&json!({})), TextMode::Compact));
+        assert!(matches!(
+            text_mode_from_args(&json!({ "text_mode": "compact" })),
+            TextMode::Compact
+        ));
+        assert!(matches!(
+            text_mode_from_args(&json!({ "text_mode": "none" })),
+            TextMode::None
+        ));
+    }
+
+    #[test]
+    fn include_structured_parsing() {
+        assert!(include_structured_from_args(&json!({})));
+        assert!(!include_structured_from_args(
+            &json!({ "include_structured": false })
+        ));
+        assert!(include_structured_from_args(
+            &json!({ "include_structured": true })
+        ));
+    }
+
+    #[test]
+    fn call_result_ok_modes() {
+        let result = json!({ "a": 1 });
+        let pretty = call_result_ok(result.clone(), TextMode::Pretty, true);
+        let pretty_text = pretty["content"][0]["text"].as_str().unwrap();
+        assert_eq!(pretty_text, serde_json::to_string_pretty(&result).unwrap());
+        assert!(pretty.get("structuredContent").is_some());
+
+        let compact = call_result_ok(result.clone(), TextMode::Compact, true);
+        let compact_text = compact["content"][0]["text"].as_str().unwrap();
+        assert_eq!(compact_text, serde_json::to_string(&result).unwrap());
+
+        let none = call_result_ok(result.clone(), TextMode::None, true);
+        assert!(none["content"].as_array().unwrap().is_empty());
+
+        let no_struct = call_result_ok(result, TextMode::Compact, false);
+        assert!(no_struct.get("structuredContent").is_none());
+    }
+
+    #[test]
+    fn state_caches_indexer() {
+        let repo_root = temp_dir("repo");
+        let db_path = repo_root.join(".lidx").join(".lidx.sqlite");
+        let mut state = State::new(
+            Defaults {
+                repo_root: repo_root.clone(),
+                db_path: db_path.clone(),
+            },
+            watch::WatchConfig::default(),
+            false,
+        );
+        let _ = state
+            .get_indexer(repo_root.clone(), db_path.clone())
+            .unwrap();
+        let _ = state.get_indexer(repo_root, db_path).unwrap();
+        assert_eq!(state.indexers.len(), 1);
+    }
+}
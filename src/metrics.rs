@@ -138,6 +138,18 @@ fn complexity_for(language: &str, snippet: &str) -> i64 {
             &["if", "for", "switch", "case", "select"][..],
             &["&&", "||"][..],
         ),
+        "java" => (
+            &["if", "for", "while", "case", "catch", "switch"][..],
+            &["&&", "||", "?"][..],
+        ),
+        "kotlin" => (
+            &["if", "for", "while", "when", "catch"][..],
+            &["&&", "||", "?:"][..],
+        ),
+        "lua" => (
+            &["if", "elseif", "for", "while", "repeat", "and", "or"][..],
+            &[][..],
+        ),
         _ => (&[][..], &[][..]),
     };
     let keyword_hits = count_keyword_hits(snippet, keywords);
@@ -198,10 +210,12 @@ fn comment_style(
 ) {
     match language {
         "python" => (Some("#"), None, None),
-        "rust" | "javascript" | "typescript" | "tsx" | "csharp" | "go" => {
+        "rust" | "javascript" | "typescript" | "tsx" | "csharp" | "go" | "java" | "kotlin" => {
             (Some("//"), Some("/*"), Some("*/"))
         }
         "postgres" | "sql" | "tsql" => (Some("--"), Some("/*"), Some("*/")),
+        "lua" => (Some("--"), None, None),
+        "markdown" => (None, Some("<!--"), Some("-->")),
         _ => (None, None, None),
     }
 }
@@ -96,6 +96,20 @@ pub struct Edge {
     pub event_ts: Option<i64>,
 }
 
+/// An edge identified by its endpoints' qualnames rather than symbol ids,
+/// which differ between graph versions.
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionEdge {
+    pub kind: String,
+    pub file_path: String,
+    pub source_qualname: Option<String>,
+    /// Resolved target's qualname, else the edge's own `target_qualname`.
+    pub target_qualname: Option<String>,
+    /// File of the resolved target.
+    pub target_path: Option<String>,
+    pub detail: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RepoOverview {
     pub repo_root: String,
@@ -269,6 +283,8 @@ pub struct GraphVersion {
     pub id: i64,
     pub created: i64,
     pub commit_sha: Option<String>,
+    /// Ref a revision version was indexed from; `None` for the working tree.
+    pub revision: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -313,6 +329,23 @@ pub struct IndexStats {
     pub duration_ms: u64,
 }
 
+#[derive(Debug, Serialize)]
+pub struct RevisionStats {
+    pub revision: String,
+    pub commit_sha: String,
+    pub graph_version: i64,
+    /// The commit already had a graph version, which is returned as is.
+    pub already_indexed: bool,
+    pub scanned: usize,
+    pub indexed: usize,
+    /// Files whose blob was unchanged from an indexed version and were copied.
+    pub reused: usize,
+    pub skipped: usize,
+    pub symbols: usize,
+    pub edges: usize,
+    pub duration_ms: u64,
+}
+
 // gather_context types
 
 /// Type of source for a context item
@@ -572,6 +605,9 @@ pub struct ExplainSymbolResult {
     pub tests: Option<Vec<ExplainRef>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub implements: Option<Vec<Symbol>>,
+    /// Effective columns of a table or view after all migrations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<TableColumn>>,
     pub budget: BudgetInfo,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub next_hops: Vec<serde_json::Value>,
@@ -589,6 +625,39 @@ pub struct ExplainRef {
     pub edge_kind: String,
 }
 
+/// A column of a table as of the latest migration.
+#[derive(Debug, Serialize, Clone)]
+pub struct TableColumn {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_id: Option<i64>,
+    /// The statement that added the column.
+    pub introduced_in: SchemaChangeSite,
+    /// Later renames and type changes, oldest first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<ColumnChange>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct SchemaChangeSite {
+    pub path: String,
+    pub line: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ColumnChange {
+    /// `rename_column` or `alter_column`.
+    pub op: String,
+    pub path: String,
+    pub line: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_name: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct BudgetInfo {
     pub budget_bytes: usize,
@@ -726,6 +795,9 @@ pub struct TraceFlowResult {
     pub reached_target: bool,
     pub truncated: bool,
     pub budget: BudgetInfo,
+    /// Hops reached in other workspace repos (only with a `workspace` manifest).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cross_repo: Vec<WorkspaceHit>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub next_hops: Vec<serde_json::Value>,
 }
@@ -746,3 +818,44 @@ pub struct TraceHop {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub protocol_context: Option<serde_json::Value>,
 }
+
+// workspace types
+
+/// A symbol reached in another repository of a workspace.
+#[derive(Debug, Serialize)]
+pub struct WorkspaceHit {
+    pub repo: String,
+    pub symbol: SymbolCompact,
+    pub distance: usize,
+    /// Edge kind (trace) or relationship (impact) that reached the symbol.
+    pub relationship: String,
+    /// Set on the hit that crossed into `repo` over a service boundary.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge: Option<RepoBridge>,
+}
+
+/// The service boundary a cross-repo hop went through.
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoBridge {
+    pub from_repo: String,
+    pub from_qualname: String,
+    pub boundary_type: String,
+    /// Normalized route, RPC method or channel both sides agree on.
+    pub target: String,
+}
+
+/// A producer in one repository matched to a consumer in another.
+#[derive(Debug, Serialize)]
+pub struct CrossRepoLink {
+    pub boundary_type: String,
+    pub target: String,
+    pub from: LinkEnd,
+    pub to: LinkEnd,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LinkEnd {
+    pub repo: String,
+    pub edge_kind: String,
+    pub symbol: SymbolCompact,
+}
@@ -3,7 +3,8 @@
 
 use super::*;
 use crate::search::{
-    RgSearchOptions, annotate_grep_hits, normalize_rg_context, resolve_rg_paths, search_rg,
+    RgSearchOptions, annotate_grep_hits, is_rg_not_found, looks_like_regex, normalize_rg_context,
+    resolve_rg_paths, search_index_grep, search_rg,
 };
 
 // ---------------------------------------------------------------------------
@@ -18,7 +19,14 @@ pub(super) fn handle_explain_symbol(indexer: &mut Indexer, params: Value) -> Res
     let max_refs = params.max_refs.unwrap_or(10);
 
     // Normalize sections: resolve aliases and warn on unknowns
-    let known_sections: &[&str] = &["source", "callers", "callees", "tests", "implements"];
+    let known_sections: &[&str] = &[
+        "source",
+        "callers",
+        "callees",
+        "tests",
+        "implements",
+        "columns",
+    ];
     let aliases: &[(&str, &str)] = &[
         ("dependencies", "callees"),
         ("dependents", "callers"),
@@ -32,6 +40,7 @@ pub(super) fn handle_explain_symbol(indexer: &mut Indexer, params: Value) -> Res
             "callees".into(),
             "tests".into(),
             "implements".into(),
+            "columns".into(),
         ]
     });
     let mut warnings: Vec<String> = Vec::new();
@@ -44,7 +53,7 @@ pub(super) fn handle_explain_symbol(indexer: &mut Indexer, params: Value) -> Res
         }
         if !known_sections.contains(&lower.as_str()) {
             warnings.push(format!(
-                "Unknown section '{}'. Valid: source, callers, callees, tests, implements (aliases: dependencies\u{2192}callees, dependents\u{2192}callers, summary/body\u{2192}source)",
+                "Unknown section '{}'. Valid: source, callers, callees, tests, implements, columns (aliases: dependencies\u{2192}callees, dependents\u{2192}callers, summary/body\u{2192}source)",
                 s
             ));
         }
@@ -415,6 +424,19 @@ pub(super) fn handle_explain_symbol(indexer: &mut Indexer, params: Value) -> Res
         None
     };
 
+    // Effective columns of tables and views, folded over migrations
+    let columns = if sections.contains(&"columns".to_string())
+        && matches!(symbol.kind.as_str(), "table" | "view" | "materialized_view")
+    {
+        Some(
+            indexer
+                .db()
+                .table_columns(&symbol.qualname, ctx.graph_version)?,
+        )
+    } else {
+        None
+    };
+
     // 9. FIX #4: Budget expansion - if >30% budget remaining, fetch source snippets for refs
     let budget_remaining = max_bytes.saturating_sub(used_bytes);
     let budget_utilization = (used_bytes as f64) / (max_bytes as f64);
@@ -518,6 +540,7 @@ pub(super) fn handle_explain_symbol(indexer: &mut Indexer, params: Value) -> Res
         callees,
         tests,
         implements,
+        columns,
         budget: BudgetInfo {
             budget_bytes: max_bytes,
             used_bytes,
@@ -629,6 +652,10 @@ pub(super) fn handle_orient(indexer: &mut Indexer, params: Value) -> Result<Valu
 
     // Include focus symbol metadata when provided
     if let Some(sym) = focus_sym {
+        let runtime = crate::runtime::container_runtime(indexer.db(), &sym, ctx.graph_version)?;
+        if !runtime.is_empty() {
+            result.insert("runtime".to_string(), json!(runtime));
+        }
         result.insert(
             "focus_symbol".to_string(),
             json!({
@@ -762,19 +789,20 @@ pub(super) fn handle_trace_flow(indexer: &mut Indexer, params: Value) -> Result<
         "upstream" => crate::traversal::TraceDirection::Upstream,
         _ => crate::traversal::TraceDirection::Downstream,
     };
-    let allowed_kinds: Vec<String> = params
-        .kinds
-        .clone()
-        .unwrap_or_else(|| crate::traversal::TraceConfig::default().allowed_kinds);
+    let allowed_kinds: Vec<String> = params.kinds.clone().unwrap_or_else(|| {
+        let mut kinds = crate::traversal::TraceConfig::default().allowed_kinds;
+        kinds.extend(indexer.rules().edge_kinds());
+        kinds
+    });
 
-    // Config URI resolution: find all symbols connected to the URI
+    // Config / rule target URI resolution: find all symbols connected to the URI
     let config_uri_seeds: Vec<i64> = if let Some(ref qn) = params.start_qualname {
         if crate::indexer::config::is_config_uri(qn) {
             indexer
                 .db()
                 .source_symbols_for_config_uri(qn, &[], ctx.graph_version)?
         } else {
-            vec![]
+            rule_target_seeds(indexer, qn, ctx.graph_version)?
         }
     } else {
         vec![]
@@ -784,7 +812,7 @@ pub(super) fn handle_trace_flow(indexer: &mut Indexer, params: Value) -> Result<
     let start_ref = if let Some(id) = params.start_id {
         crate::resolve::SymbolRef::Id(id)
     } else if let Some(ref qn) = params.start_qualname {
-        if crate::indexer::config::is_config_uri(qn) {
+        if crate::indexer::config::is_config_uri(qn) || !config_uri_seeds.is_empty() {
             let first_id = config_uri_seeds
                 .first()
                 .ok_or_else(|| anyhow::anyhow!("no symbols found for config URI: {}", qn))?;
@@ -833,13 +861,44 @@ pub(super) fn handle_trace_flow(indexer: &mut Indexer, params: Value) -> Result<
     };
     let trace_result = crate::traversal::trace_flow(
         indexer.db(),
-        seed_ids,
+        seed_ids.clone(),
         end_id,
         ctx.languages.as_deref(),
         ctx.graph_version,
         &config,
     )?;
 
+    // Follow service boundaries into the other repos of a workspace
+    let cross_repo = if let Some(ref manifest) = params.workspace {
+        let workspace = crate::workspace::Workspace::open(&crate::workspace::manifest_path(
+            indexer.repo_root(),
+            manifest,
+        ))?;
+        let home = workspace.member_for_root(indexer.repo_root())?;
+        let reached: Vec<(i64, usize)> = seed_ids
+            .iter()
+            .map(|&id| (id, 0))
+            .chain(trace_result.hops.iter().map(|h| (h.symbol.id, h.distance)))
+            .collect();
+        let bridge_direction = match config.direction {
+            crate::traversal::TraceDirection::Upstream => {
+                crate::workspace::BridgeDirection::Upstream
+            }
+            crate::traversal::TraceDirection::Downstream => {
+                crate::workspace::BridgeDirection::Downstream
+            }
+        };
+        workspace.extend_trace(
+            home,
+            &reached,
+            bridge_direction,
+            ctx.languages.as_deref(),
+            &config,
+        )?
+    } else {
+        Vec::new()
+    };
+
     let trace = &trace_result.hops;
     let truncated = trace_result.truncated;
 
@@ -932,6 +991,7 @@ pub(super) fn handle_trace_flow(indexer: &mut Indexer, params: Value) -> Result<
             used_bytes: trace_result.used_bytes,
             truncated,
         },
+        cross_repo,
         next_hops,
     };
 
@@ -985,6 +1045,19 @@ fn build_impact_config(
     config
 }
 
+/// Symbols with a `.lidx.toml` `[[edges]]` rule edge to the URI `target`,
+/// such as the readers of `flag://new-checkout`.
+fn rule_target_seeds(indexer: &Indexer, target: &str, graph_version: i64) -> Result<Vec<i64>> {
+    let kinds = indexer.rules().edge_kinds();
+    if kinds.is_empty() || !target.contains("://") {
+        return Ok(Vec::new());
+    }
+    let kinds: Vec<&str> = kinds.iter().map(String::as_str).collect();
+    indexer
+        .db()
+        .source_symbols_for_config_uri(target, &kinds, graph_version)
+}
+
 /// Resolve a single qualname (or config URI) to seed IDs and run impact analysis.
 fn resolve_and_analyze_single(
     indexer: &mut Indexer,
@@ -1010,12 +1083,17 @@ fn resolve_and_analyze_single(
             ));
         }
         ids
-    } else {
-        let symbol = indexer
-            .db()
-            .get_symbol_by_qualname(qualname, graph_version)?
-            .ok_or_else(|| anyhow::anyhow!("symbol not found: {}", qualname))?;
+    } else if let Some(symbol) = indexer
+        .db()
+        .get_symbol_by_qualname(qualname, graph_version)?
+    {
         vec![symbol.id]
+    } else {
+        let ids = rule_target_seeds(indexer, qualname, graph_version)?;
+        if ids.is_empty() {
+            return Err(anyhow::anyhow!("symbol not found: {}", qualname));
+        }
+        ids
     };
 
     crate::impact::analyze_impact_multi_layer(
@@ -1137,7 +1215,7 @@ pub(super) fn handle_analyze_impact(indexer: &mut Indexer, params: Value) -> Res
             }
             ids
         } else {
-            vec![]
+            rule_target_seeds(indexer, qualname, ctx.graph_version)?
         }
     } else {
         vec![]
@@ -1224,13 +1302,30 @@ pub(super) fn handle_analyze_impact(indexer: &mut Indexer, params: Value) -> Res
     let result = crate::impact::analyze_impact_multi_layer(
         indexer.db(),
         &seed_ids,
-        config,
+        config.clone(),
         ctx.graph_version,
     )?;
 
+    // Follow service boundaries into the other repos of a workspace
+    let cross_repo = if let Some(ref manifest) = params.workspace {
+        let workspace = crate::workspace::Workspace::open(&crate::workspace::manifest_path(
+            indexer.repo_root(),
+            manifest,
+        ))?;
+        let home = workspace.member_for_root(indexer.repo_root())?;
+        let reached: Vec<(i64, usize)> = seed_ids
+            .iter()
+            .map(|&id| (id, 0))
+            .chain(result.affected.iter().map(|e| (e.symbol.id, e.distance)))
+            .collect();
+        workspace.extend_impact(home, &reached, &config)?
+    } else {
+        Vec::new()
+    };
+
     // When zero symbols were affected, attach recovery next_hops so the LLM has a path
     // forward instead of a dead-end payload.
-    if result.affected.is_empty() {
+    if result.affected.is_empty() && cross_repo.is_empty() {
         let seed_id = seed_ids.first().copied();
         let seed_params = |dir: &str| {
             let mut map = serde_json::Map::new();
@@ -1309,7 +1404,40 @@ pub(super) fn handle_analyze_impact(indexer: &mut Indexer, params: Value) -> Res
         return Ok(value);
     }
 
-    Ok(json!(result))
+    let mut value = serde_json::to_value(&result)?;
+    if !cross_repo.is_empty()
+        && let Some(obj) = value.as_object_mut()
+    {
+        obj.insert("cross_repo".to_string(), json!(cross_repo));
+    }
+    Ok(value)
+}
+
+pub(super) fn handle_workspace_links(indexer: &mut Indexer, params: Value) -> Result<Value> {
+    let params: WorkspaceLinksParams = serde_json::from_value(params)?;
+    let limit = params.limit.unwrap_or(200).min(MAX_RESPONSE_LIMIT);
+    let workspace = crate::workspace::Workspace::open(&crate::workspace::manifest_path(
+        indexer.repo_root(),
+        &params.workspace,
+    ))?;
+    let links = workspace.links(params.boundary_types.as_deref(), limit)?;
+    let repos: Vec<Value> = workspace
+        .members()
+        .iter()
+        .map(|member| {
+            json!({
+                "name": member.name,
+                "root": member.root.display().to_string(),
+                "graph_version": member.graph_version,
+            })
+        })
+        .collect();
+    Ok(json!({
+        "repos": repos,
+        "count": links.len(),
+        "truncated": links.len() >= limit,
+        "links": links,
+    }))
 }
 
 pub(super) fn handle_analyze_diff(indexer: &mut Indexer, params: Value) -> Result<Value> {
@@ -1345,6 +1473,7 @@ pub(super) fn handle_analyze_diff(indexer: &mut Indexer, params: Value) -> Resul
     }
 
     // Step 2: Find symbols in changed files, filtered by hunk ranges
+    let previous_version = indexer.db().previous_graph_version(ctx.graph_version)?;
     let mut changed_symbols = Vec::new();
     for cf in &changed_files {
         let symbols = indexer
@@ -1385,12 +1514,14 @@ pub(super) fn handle_analyze_diff(indexer: &mut Indexer, params: Value) -> Resul
             let new_signature = sym.signature.clone();
             let mut final_change_type = change_type.clone();
 
-            if change_type == "modified" && ctx.graph_version > 1 {
+            if change_type == "modified"
+                && let Some(previous_version) = previous_version
+            {
                 // Try to find the symbol in the previous graph version
                 if let Some(stable_id) = sym.stable_id.as_ref()
                     && let Ok(Some(old_sym)) = indexer
                         .db()
-                        .get_symbol_by_stable_id(stable_id, ctx.graph_version - 1)
+                        .get_symbol_by_stable_id(stable_id, previous_version)
                 {
                     // Compare signatures
                     if old_sym.signature != sym.signature {
@@ -1800,6 +1931,108 @@ pub(super) fn handle_analyze_diff(indexer: &mut Indexer, params: Value) -> Resul
     Ok(serde_json::to_value(&result)?)
 }
 
+pub(super) fn handle_compare_versions(indexer: &mut Indexer, params: Value) -> Result<Value> {
+    let params: CompareVersionsParams = serde_json::from_value(params)?;
+    let from_version = comparable_version(indexer, params.from)?;
+    let to_version = match params.to {
+        Some(to) => comparable_version(indexer, to)?,
+        None => indexer.db().current_graph_version()?,
+    };
+    let ctx = HandlerContext::new(
+        indexer,
+        CommonParams {
+            languages: params.languages,
+            path: params.path,
+            paths: params.paths,
+            graph_version: None,
+        },
+    )?;
+    let config = crate::compare::CompareConfig {
+        from_version,
+        to_version,
+        depth: params.depth.unwrap_or(1).clamp(1, 5),
+        limit: params.limit.unwrap_or(100).min(MAX_RESPONSE_LIMIT),
+        languages: ctx.languages,
+        paths: ctx.paths,
+    };
+    let comparison = crate::compare::compare_versions(indexer.db(), &config)?;
+    Ok(serde_json::to_value(&comparison)?)
+}
+
+pub(super) fn handle_api_changes(indexer: &mut Indexer, params: Value) -> Result<Value> {
+    let params: ApiChangesParams = serde_json::from_value(params)?;
+    let from_version = comparable_version(indexer, params.from)?;
+    let to_version = match params.to {
+        Some(to) => comparable_version(indexer, to)?,
+        None => indexer.db().current_graph_version()?,
+    };
+    let ctx = HandlerContext::new(
+        indexer,
+        CommonParams {
+            languages: params.languages,
+            path: params.path,
+            paths: params.paths,
+            graph_version: None,
+        },
+    )?;
+    let mut approved = indexer.rules().api.approved.clone();
+    approved.extend(params.approve.unwrap_or_default());
+    let config = crate::api_changes::ApiChangesConfig {
+        from_version,
+        to_version,
+        depth: params.depth.unwrap_or(1).clamp(1, 5),
+        limit: params.limit.unwrap_or(100).min(MAX_RESPONSE_LIMIT),
+        max_callers: params.max_callers.unwrap_or(20).min(MAX_RESPONSE_LIMIT),
+        languages: ctx.languages,
+        paths: ctx.paths,
+        approved,
+    };
+    let changes = crate::api_changes::api_changes(indexer.db(), &config)?;
+    Ok(serde_json::to_value(&changes)?)
+}
+
+pub(super) fn handle_affected_tests(indexer: &mut Indexer, params: Value) -> Result<Value> {
+    let params: AffectedTestsParams = serde_json::from_value(params)?;
+    let ctx = HandlerContext::from_version(indexer, params.graph_version)?;
+    let changed_files: Vec<ChangedFile> = if let Some(diff) = params.diff {
+        parse_diff_with_ranges(&diff)
+    } else if let Some(paths) = params.paths {
+        paths
+            .into_iter()
+            .map(|path| ChangedFile {
+                path,
+                changed_ranges: Vec::new(),
+                added_ranges: Vec::new(),
+                deleted_ranges: Vec::new(),
+            })
+            .collect()
+    } else if let Some(base) = params.base {
+        let diff = crate::indexer::revision::diff_since(indexer.repo_root(), &base)?;
+        parse_diff_with_ranges(&diff)
+    } else {
+        anyhow::bail!("affected_tests requires 'diff', 'paths' or 'base' parameter");
+    };
+    let config = crate::affected_tests::AffectedTestsConfig {
+        graph_version: ctx.graph_version,
+        repo_root: indexer.repo_root().to_path_buf(),
+        changed_files,
+        depth: params.depth.unwrap_or(2).clamp(1, 5),
+    };
+    let affected = crate::affected_tests::affected_tests(indexer.db(), &config)?;
+    Ok(serde_json::to_value(&affected)?)
+}
+
+/// Graph version for one side of `compare_versions` or `api_changes`; a git
+/// revision that has not been indexed yet is indexed first.
+fn comparable_version(indexer: &mut Indexer, version: GraphVersionArg) -> Result<i64> {
+    if let GraphVersionArg::Revision(rev) = &version
+        && indexer.revision_graph_version(rev)?.is_none()
+    {
+        return Ok(indexer.index_revision(rev)?.graph_version);
+    }
+    resolve_graph_version(indexer, Some(version))
+}
+
 // ---------------------------------------------------------------------------
 // GROUP 5 -- Search handlers
 // ---------------------------------------------------------------------------
@@ -1815,17 +2048,60 @@ pub(super) fn handle_search_rg(indexer: &mut Indexer, params: Value) -> Result<V
     // resolve_rg_paths handles path/paths with its own normalization for ripgrep
     let paths = resolve_rg_paths(indexer.repo_root(), params.path, params.paths)?;
     let globs = params.globs.unwrap_or_default();
+    let fixed_string = params.fixed_string.unwrap_or(false);
+    let regex = params
+        .regex
+        .unwrap_or_else(|| !fixed_string && looks_like_regex(&params.query));
     let options = RgSearchOptions {
         include_text,
         case_sensitive: params.case_sensitive,
-        fixed_string: params.fixed_string.unwrap_or(false),
+        fixed_string,
         hidden: params.hidden.unwrap_or(false),
         no_ignore: params.no_ignore.unwrap_or(false),
         follow: params.follow.unwrap_or(false),
         globs,
-        paths,
+        paths: paths.clone(),
+    };
+    // The FTS index answers by default: it is scoped to the graph version and
+    // BM25-ranked. It matches case-insensitively and only sees indexed files,
+    // so regex queries and the ripgrep-only options go to ripgrep.
+    let needs_rg = regex
+        || options.case_sensitive == Some(true)
+        || options.hidden
+        || options.no_ignore
+        || options.follow
+        || !options.globs.is_empty();
+    let indexed = if needs_rg {
+        None
+    } else {
+        search_index_grep(
+            indexer.db(),
+            indexer.repo_root(),
+            ctx.graph_version,
+            &params.query,
+            limit,
+            include_text,
+            &paths,
+        )?
+    };
+    let mut results = match indexed {
+        Some(results) => results,
+        None => match search_rg(indexer.repo_root(), &params.query, limit, options) {
+            Ok(results) => results,
+            // Without ripgrep, answer from the FTS index built during indexing.
+            Err(err) if is_rg_not_found(&err) => search_index_grep(
+                indexer.db(),
+                indexer.repo_root(),
+                ctx.graph_version,
+                &params.query,
+                limit,
+                include_text,
+                &paths,
+            )?
+            .ok_or(err)?,
+            Err(err) => return Err(err),
+        },
     };
-    let mut results = search_rg(indexer.repo_root(), &params.query, limit, options)?;
     for hit in &mut results {
         if hit.engine.is_none() {
             hit.engine = Some("search_rg".to_string());
@@ -1958,6 +2234,12 @@ pub(super) fn handle_reindex(indexer: &mut Indexer, params: Value) -> Result<Val
     ))
 }
 
+pub(super) fn handle_index_revision(indexer: &mut Indexer, params: Value) -> Result<Value> {
+    let params: IndexRevisionParams = serde_json::from_value(params)?;
+    let stats = indexer.index_revision(&params.rev)?;
+    Ok(json!(stats))
+}
+
 pub(super) fn handle_gather_context(indexer: &mut Indexer, params: Value) -> Result<Value> {
     use crate::gather_context;
 
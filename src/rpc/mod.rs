@@ -22,7 +22,7 @@ use std::path::PathBuf;
 use std::time::Instant;
 
 pub(crate) use compact::compact_symbol_value;
-pub(crate) use schema::method_param_schema;
+pub(crate) use schema::{method_description, method_param_schema};
 
 #[derive(Deserialize)]
 struct RpcRequest {
@@ -55,6 +55,13 @@ struct ReindexParams {
     mine_git: Option<bool>,
 }
 
+#[derive(Deserialize, schemars::JsonSchema)]
+struct IndexRevisionParams {
+    /// Git revision to index: a branch, tag or commit sha
+    #[serde(alias = "revision", alias = "ref")]
+    rev: String,
+}
+
 #[derive(Deserialize, schemars::JsonSchema)]
 struct TopComplexityParams {
     limit: Option<usize>,
@@ -106,6 +113,8 @@ struct AnalyzeImpactParams {
     /// Global configuration
     limit: Option<usize>,
     min_confidence: Option<f32>,
+    /// Workspace manifest (lidx-workspace.yaml); also follows HTTP/RPC/bus bridges into its other repos (single-seed mode)
+    workspace: Option<String>,
     #[serde(flatten)]
     common: LangVersionParams,
 }
@@ -126,7 +135,66 @@ struct AnalyzeDiffParams {
     max_bytes: Option<usize>,
     languages: Option<Vec<String>>,
     #[serde(alias = "as_of", alias = "version")]
-    graph_version: Option<i64>,
+    graph_version: Option<GraphVersionArg>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+struct CompareVersionsParams {
+    /// Base version: graph version id, or a git revision such as "v1.4" (indexed on first use)
+    #[serde(alias = "base", alias = "old")]
+    from: GraphVersionArg,
+    /// Version to compare against the base (default: current)
+    #[serde(alias = "head", alias = "new")]
+    to: Option<GraphVersionArg>,
+    /// Directory depth that groups files into modules (default: 1, max: 5)
+    depth: Option<usize>,
+    /// Maximum entries per list (default: 100, max: 500)
+    limit: Option<usize>,
+    /// Language filter (e.g. ["rust", "python"])
+    languages: Option<Vec<String>>,
+    /// Path prefix filter: a single path or an array (alternative to `paths`)
+    path: Option<PathArg>,
+    /// Path prefix filters
+    paths: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+struct ApiChangesParams {
+    /// Base version: graph version id, or a git revision such as "v1.4" (indexed on first use)
+    #[serde(alias = "base", alias = "old")]
+    from: GraphVersionArg,
+    /// Version checked against the base (default: current)
+    #[serde(alias = "head", alias = "new")]
+    to: Option<GraphVersionArg>,
+    /// Qualnames whose breaking changes are accepted (a trailing `*` matches a prefix), on top of `[api] approved` in .lidx.toml
+    approve: Option<Vec<String>>,
+    /// Directory depth that groups files into modules; callers in another module are external (default: 1, max: 5)
+    depth: Option<usize>,
+    /// Maximum changes listed (default: 100, max: 500)
+    limit: Option<usize>,
+    /// Maximum external callers listed per breaking change (default: 20)
+    max_callers: Option<usize>,
+    /// Language filter (e.g. ["rust", "python"])
+    languages: Option<Vec<String>>,
+    /// Path prefix filter: a single path or an array (alternative to `paths`)
+    path: Option<PathArg>,
+    /// Path prefix filters
+    paths: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+struct AffectedTestsParams {
+    /// Git diff text (unified diff format)
+    diff: Option<String>,
+    /// Changed file paths, each changed as a whole
+    #[serde(alias = "path")]
+    paths: Option<Vec<String>>,
+    /// Git revision to diff the working tree against (e.g. "origin/main")
+    base: Option<String>,
+    /// Call hops followed from a changed symbol to a test; hops past the first are medium confidence (default: 2, max: 5)
+    depth: Option<usize>,
+    #[serde(alias = "as_of", alias = "version")]
+    graph_version: Option<GraphVersionArg>,
 }
 
 #[derive(Deserialize, schemars::JsonSchema)]
@@ -142,11 +210,12 @@ struct RgParams {
     globs: Option<Vec<String>>,
     case_sensitive: Option<bool>,
     fixed_string: Option<bool>,
+    regex: Option<bool>,
     hidden: Option<bool>,
     no_ignore: Option<bool>,
     follow: Option<bool>,
     #[serde(alias = "as_of", alias = "version")]
-    graph_version: Option<i64>,
+    graph_version: Option<GraphVersionArg>,
 }
 
 #[derive(Deserialize, Default, schemars::JsonSchema)]
@@ -161,7 +230,8 @@ struct OrientParams {
     view: Option<String>,
     depth: Option<usize>,
     max_bytes: Option<usize>,
-    /// Focus on a specific symbol by qualname (filters orient output to symbol's context)
+    /// Focus on a specific symbol by qualname (filters orient output to symbol's context;
+    /// also reports the containers whose entrypoint runs its module, with their env)
     focus_qualname: Option<String>,
     /// Focus on a specific symbol by fuzzy query (alternative to focus_qualname)
     focus_query: Option<String>,
@@ -243,19 +313,31 @@ struct TraceFlowParams {
     format: Option<String>,
     trace_offset: Option<usize>,
     max_bytes: Option<usize>,
+    /// Workspace manifest (lidx-workspace.yaml); also follows HTTP/RPC/bus bridges into its other repos
+    workspace: Option<String>,
     #[serde(flatten)]
     common: LangVersionParams,
 }
 
+#[derive(Deserialize, schemars::JsonSchema)]
+struct WorkspaceLinksParams {
+    /// Workspace manifest path, or a directory containing lidx-workspace.yaml
+    workspace: String,
+    /// Boundary types to include: "http", "grpc", "message_bus" (default: all)
+    boundary_types: Option<Vec<String>>,
+    /// Maximum number of links (default: 200, max: 500)
+    limit: Option<usize>,
+}
+
 #[derive(Deserialize, schemars::JsonSchema)]
 struct ContextParams {
     /// File path (relative to repo root) to retrieve structured context for
     path: String,
     /// Output format: "json" for structured JSON output, or omit for text (default: text)
     format: Option<String>,
-    /// Graph version to query (defaults to current)
+    /// Graph version id, or an indexed git revision such as "main" (defaults to current)
     #[serde(alias = "as_of", alias = "version")]
-    graph_version: Option<i64>,
+    graph_version: Option<GraphVersionArg>,
 }
 
 /// Hard cap on result count to prevent huge responses that blow LLM context windows.
@@ -267,14 +349,19 @@ pub const METHOD_LIST: &[&str] = &[
     "trace_flow",
     "analyze_impact",
     "analyze_diff",
+    "compare_versions",
+    "api_changes",
+    "affected_tests",
     "gather_context",
     "context",
     "orient",
     "onboard",
     "reindex",
+    "index_revision",
     "top_complexity",
     "repo_map",
     "dead_symbols",
+    "workspace_links",
 ];
 
 pub fn serve(repo_root: PathBuf, db_path: PathBuf, watch_config: watch::WatchConfig) -> Result<()> {
@@ -297,28 +384,81 @@ pub fn serve(repo_root: PathBuf, db_path: PathBuf, watch_config: watch::WatchCon
             continue;
         }
 
-        let response = match serde_json::from_str::<RpcRequest>(&line) {
-            Ok(request) => app.handle_request(request),
-            Err(err) => format::error_response(Value::Null, &format!("invalid request: {err}")),
-        };
-
-        writeln!(stdout, "{}", serde_json::to_string(&response)?)?;
+        let response = handle_request_line(&mut app.indexer, &line)?;
+        writeln!(stdout, "{response}")?;
         stdout.flush()?;
     }
 
     Ok(())
 }
 
+/// Handle one JSONL request line and return the serialized response line.
+/// Shared by the stdio server and the daemon socket server.
+pub fn handle_request_line(indexer: &mut Indexer, line: &str) -> Result<String> {
+    let response = match serde_json::from_str::<RpcRequest>(line) {
+        Ok(request) => respond(indexer, request),
+        Err(err) => format::error_response(Value::Null, &format!("invalid request: {err}")),
+    };
+    Ok(serde_json::to_string(&response)?)
+}
+
+/// Build the JSONL request line for `method` as sent by `lidx request`.
+pub fn request_line(method: &str, params_raw: &str, id_raw: &str) -> Result<String> {
+    let params: Value = serde_json::from_str(params_raw).with_context(|| "parse params JSON")?;
+    let id = format::parse_value(id_raw);
+    Ok(serde_json::to_string(&json!({
+        "id": id,
+        "method": method,
+        "params": params,
+    }))?)
+}
+
+/// Unwrap a serialized response line into its `result`, turning an `error`
+/// member into an `Err`.
+pub fn response_result(line: &str) -> Result<Value> {
+    let mut response: Value = serde_json::from_str(line).with_context(|| "parse rpc response")?;
+    if let Some(message) = response
+        .get("error")
+        .and_then(|error| error.get("message"))
+        .and_then(Value::as_str)
+    {
+        anyhow::bail!("{message}");
+    }
+    Ok(response
+        .get_mut("result")
+        .map(Value::take)
+        .unwrap_or(Value::Null))
+}
+
 pub fn call(
     repo_root: PathBuf,
     db_path: PathBuf,
     method: String,
     params_raw: &str,
     id_raw: &str,
+) -> Result<String> {
+    call_with_options(
+        repo_root,
+        db_path,
+        scan::ScanOptions::default(),
+        method,
+        params_raw,
+        id_raw,
+    )
+}
+
+/// `call` with explicit scan options, e.g. to honour `--no-ignore`.
+pub fn call_with_options(
+    repo_root: PathBuf,
+    db_path: PathBuf,
+    scan_options: scan::ScanOptions,
+    method: String,
+    params_raw: &str,
+    id_raw: &str,
 ) -> Result<String> {
     let params: Value = serde_json::from_str(params_raw).with_context(|| "parse params JSON")?;
     let id = format::parse_value(id_raw);
-    let mut app = App::new(repo_root, db_path, scan::ScanOptions::default())?;
+    let mut app = App::new(repo_root, db_path, scan_options)?;
     let request = RpcRequest { id, method, params };
     let response = app.handle_request(request);
     Ok(serde_json::to_string(&response)?)
@@ -335,17 +475,21 @@ impl App {
     }
 
     fn handle_request(&mut self, req: RpcRequest) -> RpcResponse {
-        let id = req.id.clone();
-        let result = handle_method(&mut self.indexer, &req.method, req.params);
-
-        match result {
-            Ok(value) => RpcResponse {
-                id,
-                result: Some(value),
-                error: None,
-            },
-            Err(err) => format::error_response(id, &err.to_string()),
-        }
+        respond(&mut self.indexer, req)
+    }
+}
+
+fn respond(indexer: &mut Indexer, req: RpcRequest) -> RpcResponse {
+    let id = req.id.clone();
+    let result = handle_method(indexer, &req.method, req.params);
+
+    match result {
+        Ok(value) => RpcResponse {
+            id,
+            result: Some(value),
+            error: None,
+        },
+        Err(err) => format::error_response(id, &err.to_string()),
     }
 }
 
@@ -363,14 +507,19 @@ pub fn handle_method(indexer: &mut Indexer, method: &str, params: Value) -> Resu
         "trace_flow" => handlers::handle_trace_flow(indexer, params)?,
         "analyze_impact" => handlers::handle_analyze_impact(indexer, params)?,
         "analyze_diff" => handlers::handle_analyze_diff(indexer, params)?,
+        "compare_versions" => handlers::handle_compare_versions(indexer, params)?,
+        "api_changes" => handlers::handle_api_changes(indexer, params)?,
+        "affected_tests" => handlers::handle_affected_tests(indexer, params)?,
         "gather_context" => handlers::handle_gather_context(indexer, params)?,
         "orient" => handlers::handle_orient(indexer, params)?,
         "onboard" => handlers::handle_onboard(indexer, params)?,
         "reindex" => handlers::handle_reindex(indexer, params)?,
+        "index_revision" => handlers::handle_index_revision(indexer, params)?,
         "top_complexity" => handlers::handle_top_complexity(indexer, params)?,
         "context" => handlers::handle_context(indexer, params)?,
         "repo_map" => handlers::handle_repo_map(indexer, params)?,
         "dead_symbols" => handlers::handle_dead_symbols(indexer, params)?,
+        "workspace_links" => handlers::handle_workspace_links(indexer, params)?,
         other => {
             return Err(anyhow::anyhow!("unknown method: {other}"));
         }
@@ -416,11 +565,28 @@ pub fn handle_method(indexer: &mut Indexer, method: &str, params: Value) -> Resu
     }
 }
 
-pub(super) fn resolve_graph_version(indexer: &Indexer, value: Option<i64>) -> Result<i64> {
-    if let Some(version) = value {
-        return Ok(version);
+/// A graph version id, or a git revision (branch, tag or commit sha) that
+/// was indexed with `index_revision`.
+#[derive(Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+#[serde(untagged)]
+pub(super) enum GraphVersionArg {
+    Id(i64),
+    Revision(String),
+}
+
+pub(super) fn resolve_graph_version(
+    indexer: &Indexer,
+    value: Option<GraphVersionArg>,
+) -> Result<i64> {
+    match value {
+        Some(GraphVersionArg::Id(version)) => Ok(version),
+        Some(GraphVersionArg::Revision(rev)) => {
+            indexer.revision_graph_version(&rev)?.ok_or_else(|| {
+                anyhow::anyhow!("revision {rev} is not indexed; call index_revision first")
+            })
+        }
+        None => indexer.db().current_graph_version(),
     }
-    indexer.db().current_graph_version()
 }
 
 /// A single path or a list of paths. Accepting both shapes keeps `path` forgiving
@@ -442,9 +608,9 @@ pub(super) struct CommonParams {
     pub path: Option<PathArg>,
     /// Path prefix filters
     pub paths: Option<Vec<String>>,
-    /// Graph version to query (defaults to current)
+    /// Graph version id, or an indexed git revision such as "main" (defaults to current)
     #[serde(alias = "as_of", alias = "version")]
-    pub graph_version: Option<i64>,
+    pub graph_version: Option<GraphVersionArg>,
 }
 
 /// Common query parameters for handlers that filter by language but do not
@@ -454,9 +620,9 @@ pub(super) struct CommonParams {
 pub(super) struct LangVersionParams {
     /// Language filter (e.g. ["rust", "python"])
     pub languages: Option<Vec<String>>,
-    /// Graph version to query (defaults to current)
+    /// Graph version id, or an indexed git revision such as "main" (defaults to current)
     #[serde(alias = "as_of", alias = "version")]
-    pub graph_version: Option<i64>,
+    pub graph_version: Option<GraphVersionArg>,
 }
 
 impl From<LangVersionParams> for CommonParams {
@@ -498,7 +664,7 @@ impl HandlerContext {
     }
 
     /// Resolve only graph version (for handlers whose params don't use CommonParams).
-    pub fn from_version(indexer: &Indexer, version: Option<i64>) -> Result<Self> {
+    pub fn from_version(indexer: &Indexer, version: Option<GraphVersionArg>) -> Result<Self> {
         Ok(Self {
             graph_version: resolve_graph_version(indexer, version)?,
             languages: None,
@@ -750,16 +916,16 @@ mod tests {
     fn common_params_honor_graph_version_aliases_through_flatten() {
         let p: super::TopComplexityParams =
             serde_json::from_value(serde_json::json!({"as_of": 3})).unwrap();
-        assert_eq!(p.common.graph_version, Some(3));
+        assert_eq!(p.common.graph_version, Some(super::GraphVersionArg::Id(3)));
         let p: super::TopComplexityParams =
             serde_json::from_value(serde_json::json!({"version": 7})).unwrap();
-        assert_eq!(p.common.graph_version, Some(7));
+        assert_eq!(p.common.graph_version, Some(super::GraphVersionArg::Id(7)));
         let p: super::ExplainSymbolParams =
             serde_json::from_value(serde_json::json!({"qualname": "x", "as_of": 5})).unwrap();
-        assert_eq!(p.common.graph_version, Some(5));
+        assert_eq!(p.common.graph_version, Some(super::GraphVersionArg::Id(5)));
         let p: super::TraceFlowParams =
             serde_json::from_value(serde_json::json!({"query": "x", "version": 2})).unwrap();
-        assert_eq!(p.common.graph_version, Some(2));
+        assert_eq!(p.common.graph_version, Some(super::GraphVersionArg::Id(2)));
     }
 
     #[test]
@@ -10,9 +10,10 @@ pub(super) fn schema_value<T: schemars::JsonSchema>() -> Value {
 /// Return a simplified JSON Schema for the params struct of the given method.
 pub fn method_param_schema(method: &str) -> Value {
     use super::{
-        AnalyzeDiffParams, AnalyzeImpactParams, ContextParams, DeadSymbolsParams,
-        ExplainSymbolParams, GatherContextParams, OnboardParams, OrientParams, ReindexParams,
-        RepoMapParams, RgParams, TopComplexityParams, TraceFlowParams,
+        AffectedTestsParams, AnalyzeDiffParams, AnalyzeImpactParams, ApiChangesParams,
+        CompareVersionsParams, ContextParams, DeadSymbolsParams, ExplainSymbolParams,
+        GatherContextParams, IndexRevisionParams, OnboardParams, OrientParams, ReindexParams,
+        RepoMapParams, RgParams, TopComplexityParams, TraceFlowParams, WorkspaceLinksParams,
     };
     match method {
         "search" => schema_value::<RgParams>(),
@@ -20,18 +21,78 @@ pub fn method_param_schema(method: &str) -> Value {
         "trace_flow" => schema_value::<TraceFlowParams>(),
         "analyze_impact" => schema_value::<AnalyzeImpactParams>(),
         "analyze_diff" => schema_value::<AnalyzeDiffParams>(),
+        "compare_versions" => schema_value::<CompareVersionsParams>(),
+        "api_changes" => schema_value::<ApiChangesParams>(),
+        "affected_tests" => schema_value::<AffectedTestsParams>(),
         "gather_context" => schema_value::<GatherContextParams>(),
         "context" => schema_value::<ContextParams>(),
         "orient" => schema_value::<OrientParams>(),
         "onboard" => schema_value::<OnboardParams>(),
         "reindex" => schema_value::<ReindexParams>(),
+        "index_revision" => schema_value::<IndexRevisionParams>(),
         "top_complexity" => schema_value::<TopComplexityParams>(),
         "repo_map" => schema_value::<RepoMapParams>(),
         "dead_symbols" => schema_value::<DeadSymbolsParams>(),
+        "workspace_links" => schema_value::<WorkspaceLinksParams>(),
         _ => json!({"type": "object"}),
     }
 }
 
+/// One-line description of a method, used when publishing it as an MCP tool.
+pub fn method_description(method: &str) -> &'static str {
+    match method {
+        "search" => {
+            "Text search over indexed files and symbols, BM25-ranked from the FTS index of the graph version; regex queries, globs and case-sensitive matches run through ripgrep. Hits are annotated with enclosing symbols."
+        }
+        "explain_symbol" => {
+            "Deep explanation of one symbol: definition, docs, callers, callees, tests and related edges in a single call."
+        }
+        "trace_flow" => {
+            "Trace call chains from a symbol across files, languages and service boundaries (HTTP, RPC, channels)."
+        }
+        "analyze_impact" => {
+            "Multi-layer impact analysis for a symbol: direct graph dependents, affected tests and historical co-changes."
+        }
+        "analyze_diff" => {
+            "Impact of a git diff or list of changed paths: changed symbols, affected callers, tests and risk."
+        }
+        "compare_versions" => {
+            "Structural diff between two graph versions or git revisions: added, removed, moved and re-signed symbols, edge changes by kind and module, new module dependencies, HTTP routes and RPC implementations."
+        }
+        "api_changes" => {
+            "Public API changes between two graph versions or git revisions, each classified as a major, minor or patch semver bump, with the external callers of every breaking change."
+        }
+        "affected_tests" => {
+            "Tests affected by a git diff, changed paths or a base revision, in high, medium and low confidence tiers with the pytest, cargo, dotnet, go, vitest or jest commands that run them."
+        }
+        "gather_context" => {
+            "Assemble LLM-ready source context for symbols, files or a search query within a byte budget."
+        }
+        "context" => {
+            "Compact cross-file context for one file: its symbols, imports and dependents."
+        }
+        "orient" => {
+            "Architecture overview: modules, entry points and how the main components connect."
+        }
+        "onboard" => {
+            "Start here: repository summary with languages, layout and suggested first queries."
+        }
+        "reindex" => "Rebuild the index for the repository and report statistics.",
+        "index_revision" => {
+            "Index a git branch, tag or commit without checking it out; other methods can then query it via graph_version."
+        }
+        "top_complexity" => "Most complex functions by cyclomatic complexity.",
+        "repo_map" => {
+            "Condensed map of the repository's files and key symbols within a byte budget."
+        }
+        "dead_symbols" => "Symbols with no inbound references, unused imports and orphan tests.",
+        "workspace_links" => {
+            "HTTP, RPC and message-bus calls in one workspace repo matched to their handlers in another."
+        }
+        _ => "lidx RPC method.",
+    }
+}
+
 /// Post-process schemars output into compact, LLM-friendly JSON Schema.
 fn simplify_schema(mut schema: Value) -> Value {
     // 1. Collect definitions for inlining $ref
@@ -0,0 +1,155 @@
+//! Where a module runs: the Dockerfile stages whose entrypoint is the module,
+//! the compose services built from those Dockerfiles, and the environment
+//! each of them sets.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::db::Db;
+use crate::indexer::dockerfile::ENTRYPOINT_KIND;
+use crate::model::{Edge, Symbol};
+
+#[derive(Debug, Serialize)]
+pub struct ContainerRuntime {
+    pub stage: String,
+    pub dockerfile: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_image: Option<String>,
+    pub command: Vec<String>,
+    pub ports: Vec<String>,
+    pub env: Vec<String>,
+    pub services: Vec<ServiceRuntime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServiceRuntime {
+    pub service: String,
+    pub file_path: String,
+    pub ports: Vec<String>,
+    pub env: Vec<String>,
+}
+
+/// Containers that run the module containing `focus`.
+pub fn container_runtime(
+    db: &Db,
+    focus: &Symbol,
+    graph_version: i64,
+) -> Result<Vec<ContainerRuntime>> {
+    let Some(module) = crate::indexer::module_name_for_path(&focus.file_path) else {
+        return Ok(Vec::new());
+    };
+    let mut out = Vec::new();
+    for edge in
+        db.edges_by_target_qualname_and_kinds(&module, &[ENTRYPOINT_KIND], None, graph_version)?
+    {
+        let Some(stage) = edge
+            .source_symbol_id
+            .map(|id| db.get_symbol_by_id(id))
+            .transpose()?
+            .flatten()
+        else {
+            continue;
+        };
+        let detail: serde_json::Value = edge
+            .detail
+            .as_deref()
+            .and_then(|detail| serde_json::from_str(detail).ok())
+            .unwrap_or_default();
+        let command = detail["command"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Dockerfile module names are their paths, which is what compose
+        // `build` entries import.
+        let mut services = Vec::new();
+        for import in db.edges_by_target_qualname_and_kinds(
+            &stage.file_path,
+            &["IMPORTS_FILE"],
+            None,
+            graph_version,
+        )? {
+            let Some(service) = import
+                .source_symbol_id
+                .map(|id| db.get_symbol_by_id(id))
+                .transpose()?
+                .flatten()
+                .filter(|symbol| symbol.kind == "service")
+            else {
+                continue;
+            };
+            services.push(ServiceRuntime {
+                env: env_names(
+                    &db.edges_for_symbol(service.id, None, graph_version)?,
+                    service.id,
+                ),
+                ports: ports(&service),
+                service: service.qualname,
+                file_path: service.file_path,
+            });
+        }
+
+        // Stages inherit the env of the stages they build `FROM`.
+        let mut env = Vec::new();
+        let mut seen = HashSet::new();
+        let mut current = Some(stage.id);
+        while let Some(id) = current.filter(|id| seen.insert(*id)) {
+            let edges = db.edges_for_symbol(id, None, graph_version)?;
+            env.extend(env_names(&edges, id));
+            current = edges
+                .iter()
+                .find(|edge| edge.kind == "EXTENDS" && edge.source_symbol_id == Some(id))
+                .and_then(|edge| edge.target_symbol_id);
+        }
+        env.sort();
+        env.dedup();
+
+        out.push(ContainerRuntime {
+            env,
+            ports: ports(&stage),
+            base_image: stage
+                .signature
+                .as_deref()
+                .and_then(|signature| signature.strip_prefix("FROM "))
+                .map(str::to_string),
+            command,
+            services,
+            stage: stage.qualname,
+            dockerfile: stage.file_path,
+        });
+    }
+    Ok(out)
+}
+
+/// Names of the env vars `owner` sets, leaving out `__` section prefixes.
+fn env_names(edges: &[Edge], owner: i64) -> Vec<String> {
+    let mut names: Vec<String> = edges
+        .iter()
+        .filter(|edge| edge.kind == "CONFIG_SOURCE" && edge.source_symbol_id == Some(owner))
+        .filter_map(|edge| {
+            let detail: serde_json::Value = serde_json::from_str(edge.detail.as_deref()?).ok()?;
+            (detail["source_type"] == "env").then(|| detail["raw"].as_str().map(str::to_string))?
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Ports recorded in a stage's or service's docstring (`ports: 80, 443`).
+fn ports(symbol: &Symbol) -> Vec<String> {
+    symbol
+        .docstring
+        .as_deref()
+        .unwrap_or_default()
+        .lines()
+        .find_map(|line| line.strip_prefix("ports: "))
+        .map(|ports| ports.split(", ").map(str::to_string).collect())
+        .unwrap_or_default()
+}
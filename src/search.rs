@@ -1,4 +1,5 @@
 use crate::config::Config;
+use crate::db::Db;
 use crate::indexer::{Indexer, scan};
 use crate::model::{ContextLine, GrepHit, SearchHit, Symbol};
 use crate::util;
@@ -127,6 +128,142 @@ pub fn search_text(
     Ok(scored.into_iter().map(|entry| entry.hit).collect())
 }
 
+/// Ranked text search over the FTS5 index built during indexing.
+///
+/// Matches query words (split on camel/snake case and stemmed) as prefix terms
+/// against indexed file lines and symbol names/qualnames/docstrings, ranked by
+/// BM25 plus the usual path bonus. Returns `None` when `graph_version` has no
+/// indexed content or the query has no searchable terms, so callers can fall
+/// back to [`search_text`].
+pub fn search_indexed(
+    db: &Db,
+    graph_version: i64,
+    query: &str,
+    limit: usize,
+    options: SearchOptions<'_>,
+) -> Result<Option<Vec<SearchHit>>> {
+    if options.languages.is_some_and(|langs| langs.is_empty()) {
+        return Ok(Some(Vec::new()));
+    }
+    if options.paths.is_some_and(|paths| paths.is_empty()) {
+        return Ok(Some(Vec::new()));
+    }
+    let terms = fts_query_terms(query);
+    if terms.is_empty() || !db.has_content_index(graph_version)? {
+        return Ok(None);
+    }
+    let match_expr = terms
+        .iter()
+        .map(|term| format!("\"{term}\"*"))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let fetch_limit = rank_fetch_limit(limit, options.rank).saturating_mul(FTS_FILTER_HEADROOM);
+    let lang_set = options
+        .languages
+        .map(|langs| langs.iter().map(String::as_str).collect::<HashSet<_>>());
+    let allowed = |path: &str, language: &str| {
+        lang_set.as_ref().is_none_or(|set| set.contains(language))
+            && scope_allows(
+                path,
+                options.scope,
+                options.exclude_generated,
+                options.paths,
+            )
+    };
+    let needle = query.trim().to_ascii_lowercase();
+
+    let mut scored: Vec<ScoredHit> = Vec::new();
+    let mut index_by_line: HashMap<(String, usize), usize> = HashMap::new();
+    for found in db.search_content(&match_expr, graph_version, fetch_limit)? {
+        if !allowed(&found.path, &found.language) {
+            continue;
+        }
+        let line = found.line as usize;
+        let lowered = found.text.to_ascii_lowercase();
+        let mut score = found.score as f32 + path_bonus(&found.path);
+        let mut reasons = vec!["fts".to_string()];
+        if !needle.is_empty() && lowered.contains(&needle) {
+            score += EXACT_BASE_SCORE;
+            reasons.push("exact".to_string());
+        }
+        let column = terms
+            .iter()
+            .filter_map(|term| lowered.find(term.as_str()))
+            .min()
+            .map(|pos| pos + 1)
+            .unwrap_or(1);
+        index_by_line.insert((found.path.clone(), line), scored.len());
+        scored.push(ScoredHit {
+            hit: SearchHit {
+                path: found.path,
+                line,
+                column,
+                line_text: found.text,
+                context: None,
+                enclosing_symbol: None,
+                score: Some(score),
+                reasons: Some(reasons),
+                engine: Some("fts".to_string()),
+                next_hops: None,
+            },
+            score,
+        });
+    }
+    for found in db.search_symbol_text(&match_expr, graph_version, fetch_limit)? {
+        if !allowed(&found.path, &found.language) {
+            continue;
+        }
+        let line = found.line as usize;
+        let symbol_score = found.score as f32;
+        if let Some(&idx) = index_by_line.get(&(found.path.clone(), line)) {
+            let entry = &mut scored[idx];
+            entry.score += symbol_score;
+            entry.hit.score = Some(entry.score);
+            if let Some(reasons) = entry.hit.reasons.as_mut()
+                && !reasons.iter().any(|reason| reason == "symbol")
+            {
+                reasons.push("symbol".to_string());
+            }
+            continue;
+        }
+        let score = symbol_score + path_bonus(&found.path);
+        index_by_line.insert((found.path.clone(), line), scored.len());
+        scored.push(ScoredHit {
+            hit: SearchHit {
+                path: found.path,
+                line,
+                column: found.column.max(1) as usize,
+                line_text: found.text.unwrap_or(found.qualname),
+                context: None,
+                enclosing_symbol: None,
+                score: Some(score),
+                reasons: Some(vec!["symbol".to_string()]),
+                engine: Some("fts".to_string()),
+                next_hops: None,
+            },
+            score,
+        });
+    }
+    if options.rank {
+        scored.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.hit.path.cmp(&b.hit.path))
+                .then_with(|| a.hit.line.cmp(&b.hit.line))
+        });
+    } else {
+        scored.sort_by(|a, b| {
+            a.hit
+                .path
+                .cmp(&b.hit.path)
+                .then_with(|| a.hit.line.cmp(&b.hit.line))
+        });
+    }
+    scored.truncate(limit);
+    Ok(Some(scored.into_iter().map(|entry| entry.hit).collect()))
+}
+
 fn search_with_rg(
     repo_root: &Path,
     query: &str,
@@ -646,6 +783,67 @@ fn split_identifier(word: &str) -> Vec<(String, usize)> {
     parts
 }
 
+/// Extra rows fetched from the index so language/scope filtering applied after
+/// the query still leaves enough hits to fill the limit.
+const FTS_FILTER_HEADROOM: usize = 4;
+/// Identifiers longer than this many parts only index their individual parts.
+const MAX_JOINED_PARTS: usize = 8;
+
+/// Extra index tokens for a content line: the lowercase parts of each compound
+/// identifier and every contiguous run of them, so `scaffoldDataSource` is
+/// found by `scaffold`, `data source` and `datasource` alike. Plain words are
+/// left to the FTS tokenizer.
+pub(crate) fn index_tokens(line: &str) -> String {
+    let mut seen = HashSet::new();
+    let mut out: Vec<String> = Vec::new();
+    for word in line
+        .split(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '-'))
+        .filter(|word| !word.is_empty())
+    {
+        let parts: Vec<String> = split_identifier(word)
+            .into_iter()
+            .map(|(part, _)| part.to_ascii_lowercase())
+            .collect();
+        if parts.len() < 2 {
+            continue;
+        }
+        let max_run = if parts.len() > MAX_JOINED_PARTS {
+            1
+        } else {
+            parts.len()
+        };
+        for start in 0..parts.len() {
+            for end in start + 1..=(start + max_run).min(parts.len()) {
+                let token = parts[start..end].concat();
+                if seen.insert(token.clone()) {
+                    out.push(token);
+                }
+            }
+        }
+    }
+    out.join(" ")
+}
+
+/// Normalized prefix terms for an FTS query. Compound words contribute their
+/// parts; all terms must match.
+fn fts_query_terms(query: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut terms = Vec::new();
+    for word in query
+        .split(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '-'))
+        .filter(|word| !word.is_empty())
+    {
+        for (part, _) in split_identifier(word) {
+            if let Some(term) = normalize_token(&part)
+                && seen.insert(term.clone())
+            {
+                terms.push(term);
+            }
+        }
+    }
+    terms
+}
+
 const CAT_LOWER: u8 = 0;
 const CAT_UPPER: u8 = 1;
 const CAT_DIGIT: u8 = 2;
@@ -965,6 +1163,72 @@ pub fn resolve_rg_paths(
     Ok(resolved)
 }
 
+const RG_NOT_FOUND: &str = "rg not found in PATH";
+
+/// True when `err` came from [`search_rg`] failing to locate the `rg` binary.
+pub fn is_rg_not_found(err: &anyhow::Error) -> bool {
+    err.to_string() == RG_NOT_FOUND
+}
+
+/// True when `query` uses regex syntax (escapes, classes, groups, anchors,
+/// repetition or alternation) and so has to run through [`search_rg`].
+pub fn looks_like_regex(query: &str) -> bool {
+    query.contains([
+        '\\', '[', ']', '(', ')', '{', '}', '*', '+', '?', '|', '^', '$',
+    ])
+}
+
+/// Answers a grep request from the FTS index: the default engine for
+/// `search`, and the fallback when ripgrep is unavailable.
+///
+/// The query is treated as text (no regex semantics) and globs are ignored;
+/// `paths` from [`resolve_rg_paths`] restrict hits to those repo subtrees.
+/// Returns `None` when the index has nothing for `graph_version`.
+pub fn search_index_grep(
+    db: &Db,
+    repo_root: &Path,
+    graph_version: i64,
+    query: &str,
+    limit: usize,
+    include_text: bool,
+    paths: &[PathBuf],
+) -> Result<Option<Vec<GrepHit>>> {
+    let mut rel_paths = Vec::new();
+    for path in paths {
+        match path.strip_prefix(repo_root) {
+            Ok(rel) if rel.as_os_str().is_empty() => {
+                rel_paths.clear();
+                break;
+            }
+            Ok(rel) => rel_paths.push(util::normalize_path(rel)),
+            Err(_) => {}
+        }
+    }
+    let mut options = SearchOptions::new(None);
+    if !rel_paths.is_empty() {
+        options.paths = Some(&rel_paths);
+    }
+    let Some(hits) = search_indexed(db, graph_version, query, limit, options)? else {
+        return Ok(None);
+    };
+    Ok(Some(
+        hits.into_iter()
+            .map(|hit| GrepHit {
+                path: hit.path,
+                line: hit.line,
+                column: hit.column,
+                line_text: include_text.then_some(hit.line_text),
+                context: None,
+                enclosing_symbol: None,
+                score: hit.score,
+                reasons: hit.reasons,
+                engine: hit.engine,
+                next_hops: None,
+            })
+            .collect(),
+    ))
+}
+
 /// Runs ripgrep with `--json` output and returns parsed grep hits.
 pub fn search_rg(
     repo_root: &PathBuf,
@@ -1015,7 +1279,7 @@ pub fn search_rg(
     let mut output = match build_cmd(true).output() {
         Ok(value) => value,
         Err(err) if err.kind() == io::ErrorKind::NotFound => {
-            return Err(anyhow::anyhow!("rg not found in PATH"));
+            return Err(anyhow::anyhow!(RG_NOT_FOUND));
         }
         Err(err) => return Err(err).with_context(|| "run rg"),
     };
@@ -1026,7 +1290,7 @@ pub fn search_rg(
         output = match build_cmd(false).output() {
             Ok(value) => value,
             Err(err) if err.kind() == io::ErrorKind::NotFound => {
-                return Err(anyhow::anyhow!("rg not found in PATH"));
+                return Err(anyhow::anyhow!(RG_NOT_FOUND));
             }
             Err(err) => return Err(err).with_context(|| "run rg"),
         };
@@ -1263,6 +1527,23 @@ mod tests {
         assert!(!rg_symbol_matches_query(&sym, ""));
     }
 
+    #[test]
+    fn index_tokens_expands_compound_identifiers() {
+        let tokens = index_tokens("let pool = scaffoldDataSource(url);");
+        let tokens: Vec<&str> = tokens.split(' ').collect();
+        for expected in ["scaffold", "data", "source", "datasource", "scaffolddata"] {
+            assert!(tokens.contains(&expected), "missing {expected}: {tokens:?}");
+        }
+        assert!(!tokens.contains(&"pool"));
+    }
+
+    #[test]
+    fn fts_query_terms_split_and_stem() {
+        assert_eq!(fts_query_terms("DataSources"), vec!["data", "sourc"]);
+        assert_eq!(fts_query_terms("get_user id"), vec!["get", "user", "id"]);
+        assert!(fts_query_terms("( ) ->").is_empty());
+    }
+
     #[test]
     fn build_rg_context_basic() {
         let lines: Vec<String> = (1..=10).map(|i| format!("line {i}")).collect();
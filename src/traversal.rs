@@ -40,6 +40,8 @@ impl Default for TraceConfig {
                 "CHANNEL_SUBSCRIBE".into(),
                 "HTTP_CALL".into(),
                 "HTTP_ROUTE".into(),
+                "GRAPHQL_CALL".into(),
+                "GRAPHQL_RESOLVE".into(),
                 "CONFIG_SOURCE".into(),
                 "CONFIG_READ".into(),
                 "CONFIG_BIND".into(),
@@ -360,6 +362,7 @@ fn detect_boundary_type(edge_kind: &str, source_lang: &str, target_lang: &str) -
     match edge_kind {
         "RPC_IMPL" | "RPC_CALL" | "RPC_ROUTE" => "grpc".to_string(),
         "HTTP_CALL" | "HTTP_ROUTE" => "http".to_string(),
+        "GRAPHQL_CALL" | "GRAPHQL_RESOLVE" => "graphql".to_string(),
         "CHANNEL_PUBLISH" | "CHANNEL_SUBSCRIBE" => "message_bus".to_string(),
         "CONFIG_SOURCE" | "CONFIG_READ" => "config".to_string(),
         "XREF" if source_lang == "csharp" && target_lang == "sql" => "stored_procedure".to_string(),
@@ -372,6 +375,8 @@ fn detect_boundary_type(edge_kind: &str, source_lang: &str, target_lang: &str) -
 fn display_language(lang: &str) -> String {
     match lang {
         "csharp" => "C#".to_string(),
+        "java" => "Java".to_string(),
+        "kotlin" => "Kotlin".to_string(),
         "javascript" => "JavaScript".to_string(),
         "typescript" | "tsx" => "TypeScript".to_string(),
         other => other.to_string(),
@@ -385,6 +390,7 @@ fn build_boundary_detail(boundary_type: &str, source_lang: &str, target_lang: &s
     match boundary_type {
         "grpc" => format!("{} \u{2192} {} via gRPC", source_display, target_display),
         "http" => format!("{} \u{2192} {} via HTTP", source_display, target_display),
+        "graphql" => format!("{} \u{2192} {} via GraphQL", source_display, target_display),
         "message_bus" => format!(
             "{} \u{2192} {} via message bus",
             source_display, target_display
@@ -470,6 +476,22 @@ fn extract_protocol_context(edge: &Edge) -> Option<serde_json::Value> {
                 "path": path,
             }))
         }
+        "GRAPHQL_CALL" | "GRAPHQL_RESOLVE" => {
+            let operation = detail
+                .get("operation")
+                .or_else(|| detail.get("type"))
+                .and_then(|o| o.as_str());
+            let field = detail.get("field").and_then(|f| f.as_str());
+            let framework = detail
+                .get("framework")
+                .and_then(|f| f.as_str())
+                .unwrap_or("graphql");
+            Some(serde_json::json!({
+                "framework": framework,
+                "operation": operation,
+                "field": field,
+            }))
+        }
         _ => None,
     }
 }
@@ -578,7 +600,10 @@ mod tests {
         assert_eq!(detect_language("test.bicep"), "bicep");
         assert_eq!(detect_language("test.psql"), "postgres");
         assert_eq!(detect_language("test.pgsql"), "postgres");
-        assert_eq!(detect_language("test.md"), "unknown");
+        assert_eq!(detect_language("test.java"), "java");
+        assert_eq!(detect_language("test.kt"), "kotlin");
+        assert_eq!(detect_language("test.lua"), "lua");
+        assert_eq!(detect_language("test.md"), "markdown");
         assert_eq!(detect_language("test.txt"), "unknown");
         // Paths with directories
         assert_eq!(detect_language("src/services/api.py"), "python");
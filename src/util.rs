@@ -36,6 +36,27 @@ pub fn normalize_path(path: &Path) -> String {
     }
 }
 
+/// Resolve `.` and `..` segments of a `/`-separated relative path
+/// (`a/b/../c` → `a/c`). `None` when the path is empty or climbs above its
+/// root.
+pub fn collapse_parent_dirs(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            other => parts.push(other),
+        }
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("/"))
+    }
+}
+
 pub fn slice_lines(content: &str, start_line: i64, end_line: i64) -> String {
     if content.is_empty() {
         return String::new();
@@ -0,0 +1,533 @@
+//! Multi-repository workspaces.
+//!
+//! A workspace manifest lists several repository roots, each indexed into its
+//! own `.lidx` database. Service boundaries (HTTP, RPC, message bus) are
+//! recorded per repository as bridge edges whose `target_qualname` holds the
+//! normalized route, method or channel, so a caller in one database can be
+//! matched to a handler in another by comparing those targets.
+//!
+//! ```yaml
+//! # lidx-workspace.yaml
+//! repos:
+//!   - name: web
+//!     root: ../web
+//!   - name: orders
+//!     root: ../orders
+//!     db: ../orders/.lidx/.lidx.sqlite   # optional
+//! ```
+
+use crate::db::Db;
+use crate::impact::config::MultiLayerConfig;
+use crate::indexer::channel::{boundary_type_for_kind, bridge_complement};
+use crate::model::{CrossRepoLink, Edge, LinkEnd, RepoBridge, SymbolCompact, WorkspaceHit};
+use crate::traversal::TraceConfig;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Conventional manifest file name.
+pub const MANIFEST_FILE: &str = "lidx-workspace.yaml";
+
+/// Caller-side bridge kinds; crossing them follows a request downstream.
+const PRODUCER_KINDS: &[&str] = &["HTTP_CALL", "RPC_CALL", "GRAPHQL_CALL", "CHANNEL_PUBLISH"];
+/// Handler-side bridge kinds; crossing them walks back to the callers.
+const CONSUMER_KINDS: &[&str] = &[
+    "HTTP_ROUTE",
+    "RPC_IMPL",
+    "GRAPHQL_RESOLVE",
+    "CHANNEL_SUBSCRIBE",
+];
+
+/// Upper bound on producer edges scanned per repository by `links`.
+const MAX_LINK_EDGES: usize = 50_000;
+/// Upper bound on symbols collected across repositories by one traversal.
+const MAX_WORKSPACE_HITS: usize = 2_000;
+
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    pub repos: Vec<ManifestRepo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManifestRepo {
+    /// Display name; defaults to the root directory name.
+    pub name: Option<String>,
+    /// Repository root, relative to the manifest directory.
+    pub root: PathBuf,
+    /// Database path, relative to the manifest directory
+    /// (default: `<root>/.lidx/.lidx.sqlite`).
+    pub db: Option<PathBuf>,
+}
+
+/// A manifest entry with its paths resolved.
+#[derive(Debug, Clone)]
+pub struct MemberSpec {
+    pub name: String,
+    pub root: PathBuf,
+    pub db_path: PathBuf,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("read workspace manifest {}", path.display()))?;
+        let manifest: Manifest = serde_yaml_ng::from_str(&text)
+            .with_context(|| format!("parse workspace manifest {}", path.display()))?;
+        if manifest.repos.is_empty() {
+            anyhow::bail!("workspace manifest {} lists no repos", path.display());
+        }
+        Ok(manifest)
+    }
+
+    /// Resolve member paths against `base` (the manifest directory).
+    pub fn members(&self, base: &Path) -> Result<Vec<MemberSpec>> {
+        let mut members: Vec<MemberSpec> = Vec::with_capacity(self.repos.len());
+        for repo in &self.repos {
+            let root = normalize(&base.join(&repo.root));
+            let db_path = match &repo.db {
+                Some(db) => base.join(db),
+                None => root.join(".lidx").join(".lidx.sqlite"),
+            };
+            let name = repo.name.clone().unwrap_or_else(|| {
+                root.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| root.display().to_string())
+            });
+            if members.iter().any(|member| member.name == name) {
+                anyhow::bail!("workspace repo name '{name}' is listed twice");
+            }
+            members.push(MemberSpec {
+                name,
+                root,
+                db_path,
+            });
+        }
+        Ok(members)
+    }
+}
+
+/// Load and resolve the members of the manifest at `path`.
+pub fn load_members(path: &Path) -> Result<Vec<MemberSpec>> {
+    let manifest = Manifest::load(path)?;
+    manifest.members(path.parent().unwrap_or(Path::new(".")))
+}
+
+/// Resolve a manifest argument relative to `repo_root`. A directory resolves
+/// to the `lidx-workspace.yaml` inside it.
+pub fn manifest_path(repo_root: &Path, value: &str) -> PathBuf {
+    let path = repo_root.join(value);
+    if path.is_dir() {
+        path.join(MANIFEST_FILE)
+    } else {
+        path
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Which side of a service boundary a traversal may cross from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeDirection {
+    /// Caller to handler (HTTP_CALL → HTTP_ROUTE, ...).
+    Downstream,
+    /// Handler to caller (HTTP_ROUTE → HTTP_CALL, ...).
+    Upstream,
+    Both,
+}
+
+impl BridgeDirection {
+    /// Parse the direction strings accepted by trace_flow and analyze_impact.
+    pub fn parse(value: &str) -> Self {
+        match value.to_ascii_lowercase().as_str() {
+            "downstream" | "down" | "callees" | "out" => Self::Downstream,
+            "upstream" | "up" | "callers" | "in" => Self::Upstream,
+            _ => Self::Both,
+        }
+    }
+
+    fn crosses(self, kind: &str) -> bool {
+        match self {
+            Self::Downstream => PRODUCER_KINDS.contains(&kind),
+            Self::Upstream => CONSUMER_KINDS.contains(&kind),
+            Self::Both => PRODUCER_KINDS.contains(&kind) || CONSUMER_KINDS.contains(&kind),
+        }
+    }
+}
+
+pub struct Member {
+    pub name: String,
+    pub root: PathBuf,
+    pub db: Db,
+    pub graph_version: i64,
+}
+
+/// The opened databases of every repository in a manifest.
+pub struct Workspace {
+    members: Vec<Member>,
+}
+
+/// One bridge edge in another repository matching an edge in the current one.
+struct Crossing {
+    member: usize,
+    edge: Edge,
+    bridge: RepoBridge,
+}
+
+impl Workspace {
+    /// Open the manifest at `path`. Every member must already be indexed.
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut members = Vec::new();
+        for spec in load_members(path)? {
+            if !spec.db_path.exists() {
+                anyhow::bail!(
+                    "workspace repo '{}' has no index at {}; run `lidx reindex --workspace {}`",
+                    spec.name,
+                    spec.db_path.display(),
+                    path.display()
+                );
+            }
+            let db = Db::new(&spec.db_path)?;
+            let graph_version = db.current_graph_version()?;
+            members.push(Member {
+                name: spec.name,
+                root: spec.root,
+                db,
+                graph_version,
+            });
+        }
+        Ok(Self { members })
+    }
+
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// Index of the member rooted at `repo_root`.
+    pub fn member_for_root(&self, repo_root: &Path) -> Result<usize> {
+        let root = normalize(repo_root);
+        self.members
+            .iter()
+            .position(|member| member.root == root)
+            .ok_or_else(|| {
+                anyhow::anyhow!("{} is not listed in the workspace manifest", root.display())
+            })
+    }
+
+    /// Every producer (HTTP/RPC call, publish) matched to a consumer in a
+    /// different repository. `boundary_types` filters by "http", "grpc" or
+    /// "message_bus".
+    pub fn links(
+        &self,
+        boundary_types: Option<&[String]>,
+        limit: usize,
+    ) -> Result<Vec<CrossRepoLink>> {
+        let kinds: Vec<String> = PRODUCER_KINDS
+            .iter()
+            .filter(|kind| {
+                boundary_types
+                    .is_none_or(|types| types.iter().any(|t| t == boundary_type_for_kind(kind)))
+            })
+            .map(|kind| kind.to_string())
+            .collect();
+        let mut links = Vec::new();
+        if kinds.is_empty() {
+            return Ok(links);
+        }
+        for (idx, member) in self.members.iter().enumerate() {
+            let edges = member.db.list_edges(
+                MAX_LINK_EDGES,
+                0,
+                None,
+                None,
+                Some(&kinds),
+                None,
+                None,
+                None,
+                false,
+                None,
+                member.graph_version,
+                None,
+                None,
+                None,
+            )?;
+            for edge in edges {
+                let Some(from) = self.symbol(idx, edge.source_symbol_id)? else {
+                    continue;
+                };
+                for crossing in self.crossings(idx, &edge, &from.qualname)? {
+                    let Some(to) = self.symbol(crossing.member, crossing.edge.source_symbol_id)?
+                    else {
+                        continue;
+                    };
+                    links.push(CrossRepoLink {
+                        boundary_type: crossing.bridge.boundary_type,
+                        target: crossing.bridge.target,
+                        from: LinkEnd {
+                            repo: member.name.clone(),
+                            edge_kind: edge.kind.clone(),
+                            symbol: from.clone(),
+                        },
+                        to: LinkEnd {
+                            repo: self.members[crossing.member].name.clone(),
+                            edge_kind: crossing.edge.kind,
+                            symbol: to,
+                        },
+                    });
+                    if links.len() >= limit {
+                        return Ok(links);
+                    }
+                }
+            }
+        }
+        Ok(links)
+    }
+
+    /// Continue a trace from `home` into the other repositories. `start`
+    /// holds the symbols already reached in `home` with their distances;
+    /// the result only contains symbols in other repositories.
+    pub fn extend_trace(
+        &self,
+        home: usize,
+        start: &[(i64, usize)],
+        direction: BridgeDirection,
+        languages: Option<&[String]>,
+        config: &TraceConfig,
+    ) -> Result<Vec<WorkspaceHit>> {
+        // trace_flow reports hops up to `max_hops + 1` away from the seed.
+        let max_distance = config.max_hops + 1;
+        self.federate(
+            home,
+            start,
+            direction,
+            &config.allowed_kinds,
+            max_distance,
+            |member, seed, remaining| {
+                let config = TraceConfig {
+                    max_hops: remaining - 1,
+                    trace_offset: 0,
+                    ..config.clone()
+                };
+                let result = crate::traversal::trace_flow(
+                    &member.db,
+                    vec![seed],
+                    None,
+                    languages,
+                    member.graph_version,
+                    &config,
+                )?;
+                Ok(result
+                    .hops
+                    .into_iter()
+                    .map(|hop| (SymbolCompact::from(hop.symbol), hop.edge_kind, hop.distance))
+                    .collect())
+            },
+        )
+    }
+
+    /// Continue an impact analysis from `home` into the other repositories,
+    /// running the direct layer in each repository that is reached.
+    pub fn extend_impact(
+        &self,
+        home: usize,
+        start: &[(i64, usize)],
+        config: &MultiLayerConfig,
+    ) -> Result<Vec<WorkspaceHit>> {
+        let direction = BridgeDirection::parse(&config.direct.direction);
+        let mut remote = config.clone();
+        remote.test.enabled = false;
+        remote.historical.enabled = false;
+        self.federate(
+            home,
+            start,
+            direction,
+            &config.direct.kinds,
+            config.direct.max_depth,
+            |member, seed, remaining| {
+                let mut config = remote.clone();
+                config.direct.max_depth = remaining;
+                let result = crate::impact::analyze_impact_multi_layer(
+                    &member.db,
+                    &[seed],
+                    config,
+                    member.graph_version,
+                )?;
+                Ok(result
+                    .affected
+                    .into_iter()
+                    .map(|entry| (entry.symbol, entry.relationship, entry.distance))
+                    .collect())
+            },
+        )
+    }
+
+    /// Breadth-first walk over repositories. Each symbol reached is checked
+    /// for bridge edges matching consumers/producers in other repositories;
+    /// every symbol crossed into is expanded locally with `expand`, which
+    /// returns `(symbol, relationship, distance)` within `remaining` hops.
+    fn federate(
+        &self,
+        home: usize,
+        start: &[(i64, usize)],
+        direction: BridgeDirection,
+        allowed_kinds: &[String],
+        max_distance: usize,
+        mut expand: impl FnMut(&Member, i64, usize) -> Result<Vec<(SymbolCompact, String, usize)>>,
+    ) -> Result<Vec<WorkspaceHit>> {
+        let mut hits: Vec<WorkspaceHit> = Vec::new();
+        let mut visited: HashSet<(usize, i64)> = HashSet::new();
+        let mut queue: VecDeque<(usize, i64, usize)> = VecDeque::new();
+        for &(id, distance) in start {
+            if visited.insert((home, id)) {
+                queue.push_back((home, id, distance));
+            }
+        }
+
+        while let Some((idx, id, distance)) = queue.pop_front() {
+            if distance >= max_distance || hits.len() >= MAX_WORKSPACE_HITS {
+                continue;
+            }
+            let member = &self.members[idx];
+            let Some(current) = member.db.get_symbol_by_id(id)? else {
+                continue;
+            };
+            let edges = member.db.edges_for_symbol(id, None, member.graph_version)?;
+            for edge in edges {
+                if edge.source_symbol_id != Some(id)
+                    || !direction.crosses(&edge.kind)
+                    || !(allowed_kinds.is_empty() || allowed_kinds.contains(&edge.kind))
+                {
+                    continue;
+                }
+                for crossing in self.crossings(idx, &edge, &current.qualname)? {
+                    let Some(symbol) =
+                        self.symbol(crossing.member, crossing.edge.source_symbol_id)?
+                    else {
+                        continue;
+                    };
+                    if !visited.insert((crossing.member, symbol.id)) {
+                        continue;
+                    }
+                    let target = &self.members[crossing.member];
+                    let reached = distance + 1;
+                    let seed = symbol.id;
+                    hits.push(WorkspaceHit {
+                        repo: target.name.clone(),
+                        symbol,
+                        distance: reached,
+                        relationship: crossing.edge.kind,
+                        bridge: Some(crossing.bridge),
+                    });
+                    queue.push_back((crossing.member, seed, reached));
+                    if reached >= max_distance {
+                        continue;
+                    }
+                    for (symbol, relationship, hops) in
+                        expand(target, seed, max_distance - reached)?
+                    {
+                        if !visited.insert((crossing.member, symbol.id)) {
+                            continue;
+                        }
+                        queue.push_back((crossing.member, symbol.id, reached + hops));
+                        hits.push(WorkspaceHit {
+                            repo: target.name.clone(),
+                            symbol,
+                            distance: reached + hops,
+                            relationship,
+                            bridge: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        hits.sort_by_key(|hit| hit.distance);
+        hits.truncate(MAX_WORKSPACE_HITS);
+        Ok(hits)
+    }
+
+    /// Bridge edges in other repositories whose target matches `edge`.
+    fn crossings(&self, from: usize, edge: &Edge, from_qualname: &str) -> Result<Vec<Crossing>> {
+        let (Some(target), Some(complement)) = (
+            edge.target_qualname.as_deref(),
+            bridge_complement(&edge.kind),
+        ) else {
+            return Ok(Vec::new());
+        };
+        let mut crossings = Vec::new();
+        for (idx, member) in self.members.iter().enumerate() {
+            if idx == from {
+                continue;
+            }
+            let matches = member.db.edges_by_target_qualname_and_kinds(
+                target,
+                complement,
+                None,
+                member.graph_version,
+            )?;
+            for matched in matches {
+                crossings.push(Crossing {
+                    member: idx,
+                    edge: matched,
+                    bridge: RepoBridge {
+                        from_repo: self.members[from].name.clone(),
+                        from_qualname: from_qualname.to_string(),
+                        boundary_type: boundary_type_for_kind(&edge.kind).to_string(),
+                        target: target.to_string(),
+                    },
+                });
+            }
+        }
+        Ok(crossings)
+    }
+
+    fn symbol(&self, member: usize, id: Option<i64>) -> Result<Option<SymbolCompact>> {
+        let Some(id) = id else {
+            return Ok(None);
+        };
+        Ok(self.members[member]
+            .db
+            .get_symbol_by_id(id)?
+            .map(SymbolCompact::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_paths_resolve_against_manifest_dir() {
+        let manifest: Manifest = serde_yaml_ng::from_str(
+            "repos:\n  - root: svc/orders\n  - name: web\n    root: web\n    db: dbs/web.sqlite\n",
+        )
+        .unwrap();
+        let members = manifest.members(Path::new("/ws")).unwrap();
+        assert_eq!(members[0].name, "orders");
+        assert_eq!(
+            members[0].db_path,
+            Path::new("/ws/svc/orders/.lidx/.lidx.sqlite")
+        );
+        assert_eq!(members[1].name, "web");
+        assert_eq!(members[1].db_path, Path::new("/ws/dbs/web.sqlite"));
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let manifest: Manifest =
+            serde_yaml_ng::from_str("repos:\n  - root: a/api\n  - root: b/api\n").unwrap();
+        assert!(manifest.members(Path::new("/ws")).is_err());
+    }
+
+    #[test]
+    fn directions_cross_the_matching_side() {
+        assert!(BridgeDirection::Downstream.crosses("HTTP_CALL"));
+        assert!(!BridgeDirection::Downstream.crosses("HTTP_ROUTE"));
+        assert!(BridgeDirection::Upstream.crosses("CHANNEL_SUBSCRIBE"));
+        assert!(BridgeDirection::Both.crosses("RPC_IMPL"));
+        assert!(!BridgeDirection::Both.crosses("CONFIG_READ"));
+        assert_eq!(BridgeDirection::parse("callers"), BridgeDirection::Upstream);
+    }
+}
@@ -0,0 +1,267 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-affected-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn git(root: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "user.email=test@example.com", "-c", "user.name=test"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+fn write(root: &Path, path: &str, source: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, source).unwrap();
+}
+
+const USERS_TEST: &str = r#"from store.users import load_user
+
+def test_load():
+    assert load_user(1) == 1
+
+class TestUser:
+    def test_again(self):
+        assert load_user(2) == 2
+"#;
+
+const CALC_TEST: &str = r#"package calc
+
+import "testing"
+
+func TestAdd(t *testing.T) {
+	if Add(1, 2) != 3 {
+		t.Fatal("bad")
+	}
+}
+
+func TestTwice(t *testing.T) {
+	if Twice(2) != 4 {
+		t.Fatal("bad")
+	}
+}
+"#;
+
+/// Python, Go and TypeScript projects, committed, then edited so that
+/// `load_user`, `Add` and `sum` change.
+fn repo(label: &str) -> PathBuf {
+    let root = temp_dir(label);
+    git(&root, &["init", "-q"]);
+    write(&root, ".gitignore", ".lidx/\n");
+    write(&root, "pyproject.toml", "[tool.pytest.ini_options]\n");
+    write(
+        &root,
+        "store/users.py",
+        "def load_user(id):\n    return id\n",
+    );
+    write(&root, "tests/test_users.py", USERS_TEST);
+    write(&root, "svc/go.mod", "module example.com/svc\n\ngo 1.21\n");
+    write(
+        &root,
+        "svc/calc/calc.go",
+        "package calc\n\nfunc Add(a, b int) int {\n\treturn a + b\n}\n\nfunc Twice(a int) int {\n\treturn Add(a, a)\n}\n",
+    );
+    write(&root, "svc/calc/calc_test.go", CALC_TEST);
+    write(
+        &root,
+        "web/package.json",
+        "{\"devDependencies\": {\"vitest\": \"1.0.0\"}}\n",
+    );
+    write(
+        &root,
+        "web/src/sum.ts",
+        "export function sum(a: number, b: number): number {\n  return a + b;\n}\n",
+    );
+    write(
+        &root,
+        "web/src/sum.test.ts",
+        "import { sum } from './sum';\n\ntest('adds', () => {\n  expect(sum(1, 2)).toBe(3);\n});\n\ntest('pads', () => {\n  expect(String(1).padStart(2)).toBe(' 1');\n});\n",
+    );
+    git(&root, &["add", "-A"]);
+    git(&root, &["commit", "-q", "-m", "base"]);
+
+    write(
+        &root,
+        "store/users.py",
+        "def load_user(id):\n    return int(id)\n",
+    );
+    write(
+        &root,
+        "svc/calc/calc.go",
+        "package calc\n\nfunc Add(a, b int) int {\n\treturn b + a\n}\n\nfunc Twice(a int) int {\n\treturn Add(a, a)\n}\n",
+    );
+    write(
+        &root,
+        "web/src/sum.ts",
+        "export function sum(a: number, b: number): number {\n  return b + a;\n}\n",
+    );
+    root
+}
+
+fn commands(result: &Value, confidence: &str) -> Vec<(String, String)> {
+    result["tiers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|tier| tier["confidence"] == json!(confidence))
+        .flat_map(|tier| tier["commands"].as_array().unwrap())
+        .map(|command| {
+            (
+                command["cwd"].as_str().unwrap().to_string(),
+                command["command"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn selects_tests_since_base_revision() {
+    let root = repo("base");
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+
+    let result = rpc::handle_method(
+        &mut indexer,
+        "affected_tests",
+        json!({"base": "HEAD", "max_response_bytes": 1_000_000}),
+    )
+    .unwrap();
+    assert_eq!(
+        result["changed_symbols"],
+        json!([
+            "store.users.load_user",
+            "svc/calc/calc.Add",
+            "web/src/sum.sum"
+        ])
+    );
+
+    let high = commands(&result, "high");
+    assert!(
+        high.contains(&(
+            ".".to_string(),
+            "pytest tests/test_users.py::TestUser::test_again tests/test_users.py::test_load"
+                .to_string()
+        ))
+    );
+    assert!(high.contains(&(
+        "svc".to_string(),
+        "go test -run '^TestAdd$' ./calc".to_string()
+    )));
+    assert!(high.contains(&(
+        "web".to_string(),
+        "npx vitest run src/sum.test.ts -t '^adds( |$)'".to_string()
+    )));
+
+    // `TestTwice` reaches `Add` through `Twice`; the rest of the Python
+    // file comes in through its import.
+    let medium = commands(&result, "medium");
+    assert!(medium.contains(&(
+        "svc".to_string(),
+        "go test -run '^TestTwice$' ./calc".to_string()
+    )));
+    assert!(medium.contains(&(".".to_string(), "pytest tests/test_users.py".to_string())));
+    // The vitest file runs whole below the block calling `sum`.
+    assert!(medium.contains(&(
+        "web".to_string(),
+        "npx vitest run src/sum.test.ts".to_string()
+    )));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn block_titles_come_from_the_indexed_source() {
+    let root = repo("indexed-source");
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+
+    // Unsynced edit: the indexed block lines now point elsewhere on disk.
+    write(
+        &root,
+        "web/src/sum.test.ts",
+        "import { sum } from './sum';\n\ntest('pads', () => {\n  expect(String(1).padStart(2)).toBe(' 1');\n});\n\ntest('sums', () => {\n  expect(sum(1, 2)).toBe(3);\n});\n",
+    );
+    let result = rpc::handle_method(
+        &mut indexer,
+        "affected_tests",
+        json!({"base": "HEAD", "max_response_bytes": 1_000_000}),
+    )
+    .unwrap();
+    assert!(commands(&result, "high").contains(&(
+        "web".to_string(),
+        "npx vitest run src/sum.test.ts -t '^adds( |$)'".to_string()
+    )));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn changed_tests_and_paths_select_themselves() {
+    let root = repo("paths");
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+
+    // A hunk inside one Go test selects only that test.
+    let diff = "diff --git a/svc/calc/calc_test.go b/svc/calc/calc_test.go\n\
+                --- a/svc/calc/calc_test.go\n\
+                +++ b/svc/calc/calc_test.go\n\
+                @@ -12,1 +12,1 @@\n\
+                -\t\tt.Fatal(\"bad\")\n\
+                +\t\tt.Fatal(\"twice\")\n";
+    let result = rpc::handle_method(&mut indexer, "affected_tests", json!({"diff": diff})).unwrap();
+    assert_eq!(result["summary"], json!({"high": 1, "medium": 0, "low": 0}));
+    assert_eq!(
+        result["tiers"][0]["tests"][0],
+        json!({
+            "path": "svc/calc/calc_test.go",
+            "qualname": "svc/calc/calc_test.TestTwice",
+            "runner": "go",
+            "strategies": ["changed"],
+        })
+    );
+
+    // A whole test file runs every test in it.
+    let result = rpc::handle_method(
+        &mut indexer,
+        "affected_tests",
+        json!({"paths": ["svc/calc/calc_test.go", "missing.py"]}),
+    )
+    .unwrap();
+    assert_eq!(
+        commands(&result, "high"),
+        vec![(
+            "svc".to_string(),
+            "go test -run '^(TestAdd|TestTwice)$' ./calc".to_string()
+        )]
+    );
+    assert_eq!(
+        result["warnings"],
+        json!(["Path not found in index: missing.py"])
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
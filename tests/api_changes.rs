@@ -0,0 +1,257 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-api-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn git(root: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "user.email=test@example.com", "-c", "user.name=test"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+fn write(root: &Path, path: &str, source: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, source).unwrap();
+}
+
+const USERS_V1: &str = r#"__all__ = ["load_user", "delete_user", "User"]
+
+def load_user(id):
+    return User(id)
+
+def delete_user(id):
+    return None
+
+def _cache():
+    return {}
+
+class User:
+    def __init__(self, id):
+        self.id = id
+"#;
+
+const USERS_V2: &str = r#"__all__ = ["load_user", "find_users", "User"]
+
+def load_user(id, fields):
+    return User(id)
+
+def find_users(query=None):
+    return []
+
+def _cache(size):
+    return {}
+
+class User:
+    def __init__(self, id):
+        self.id = id
+"#;
+
+const ROUTES: &str = r#"from store.users import load_user, delete_user
+
+def get(id):
+    return load_user(id)
+
+def remove(id):
+    return delete_user(id)
+"#;
+
+/// Tag `v1`, then a working tree that re-signs `load_user`, drops
+/// `delete_user`, adds `find_users` and makes the Rust `helper` public.
+fn repo(label: &str) -> PathBuf {
+    let root = temp_dir(label);
+    git(&root, &["init", "-q"]);
+    write(&root, ".gitignore", ".lidx/\n");
+    write(&root, "store/users.py", USERS_V1);
+    write(&root, "api/routes.py", ROUTES);
+    write(
+        &root,
+        "src/lib.rs",
+        "pub fn parse(input: &str) -> u32 {\n    helper();\n    0\n}\n\nfn helper() {}\n",
+    );
+    git(&root, &["add", "-A"]);
+    git(&root, &["commit", "-q", "-m", "v1"]);
+    git(&root, &["tag", "v1"]);
+
+    write(&root, "store/users.py", USERS_V2);
+    write(
+        &root,
+        "src/lib.rs",
+        "pub fn parse(input: &str) -> u32 {\n    helper();\n    0\n}\n\npub fn helper() {}\n",
+    );
+    root
+}
+
+fn change<'a>(result: &'a Value, qualname: &str) -> &'a Value {
+    result["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|change| change["qualname"] == json!(qualname))
+        .unwrap_or_else(|| panic!("no change for {qualname}: {}", result["changes"]))
+}
+
+#[test]
+fn classifies_changes_since_revision() {
+    let root = repo("classify");
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+
+    let result = rpc::handle_method(
+        &mut indexer,
+        "api_changes",
+        json!({"from": "v1", "max_response_bytes": 1_000_000}),
+    )
+    .unwrap();
+    assert_eq!(result["bump"], json!("major"));
+
+    let removed = change(&result, "store.users.delete_user");
+    assert_eq!(removed["change"], json!("removed"));
+    assert_eq!(removed["level"], json!("major"));
+    assert_eq!(removed["approved"], json!(false));
+    // The importing module and the calling function.
+    assert_eq!(
+        removed["callers"],
+        json!([
+            {"source": "api.routes", "path": "api/routes.py"},
+            {"source": "api.routes.remove", "path": "api/routes.py"},
+        ])
+    );
+
+    let load = change(&result, "store.users.load_user");
+    assert_eq!(load["change"], json!("signature_changed"));
+    assert_eq!(load["old_signature"], json!("(id)"));
+    assert_eq!(load["new_signature"], json!("(id, fields)"));
+    assert_eq!(load["external_callers"], json!(2));
+    assert_eq!(load["callers"][1]["source"], json!("api.routes.get"));
+
+    assert_eq!(
+        change(&result, "store.users.find_users")["level"],
+        json!("minor")
+    );
+    let helper = change(&result, "crate::helper");
+    assert_eq!(helper["change"], json!("exposed"));
+    assert_eq!(helper["level"], json!("minor"));
+    // `_cache` is private, so its new signature is only counted.
+    assert!(
+        result["changes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|change| change["qualname"] != json!("store.users._cache"))
+    );
+
+    let summary = &result["summary"];
+    assert_eq!(summary["major"], json!(2));
+    assert_eq!(summary["minor"], json!(2));
+    assert_eq!(summary["internal"], json!(1));
+    assert_eq!(summary["breaking_unapproved"], json!(2));
+
+    // Approving one break leaves the other.
+    let approved = rpc::handle_method(
+        &mut indexer,
+        "api_changes",
+        json!({"from": "v1", "approve": ["store.users.delete_user"]}),
+    )
+    .unwrap();
+    assert_eq!(approved["summary"]["breaking_approved"], json!(1));
+    assert_eq!(approved["summary"]["breaking_unapproved"], json!(1));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn rules_file_approves_breaks_and_filters_apply() {
+    let root = repo("rules");
+    write(
+        &root,
+        ".lidx.toml",
+        "[api]\napproved = [\"store.users.*\"]\n",
+    );
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+
+    let result = rpc::handle_method(&mut indexer, "api_changes", json!({"from": "v1"})).unwrap();
+    assert_eq!(result["summary"]["breaking_approved"], json!(2));
+    assert_eq!(result["summary"]["breaking_unapproved"], json!(0));
+
+    // Only the Rust crate: one newly public function.
+    let rust = rpc::handle_method(
+        &mut indexer,
+        "api_changes",
+        json!({"from": "v1", "languages": ["rust"]}),
+    )
+    .unwrap();
+    assert_eq!(rust["bump"], json!("minor"));
+    assert_eq!(rust["changes"].as_array().unwrap().len(), 1);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn removed_function_lists_callers_in_other_files() {
+    let root = temp_dir("callers");
+    git(&root, &["init", "-q"]);
+    write(&root, ".gitignore", ".lidx/\n");
+    write(
+        &root,
+        "lib/math.ts",
+        "export function oldSum(a: number) {\n  return a;\n}\n\nexport function keep() {}\n",
+    );
+    write(
+        &root,
+        "web/app.ts",
+        "import { oldSum } from '../lib/math';\n\nexport function run() {\n  return oldSum(1);\n}\n",
+    );
+    // Not resolved to the definition: found by name, like find-references.
+    write(
+        &root,
+        "web/legacy.js",
+        "const math = require('../lib/math');\n\nfunction go() {\n  return math.oldSum(2);\n}\n",
+    );
+    git(&root, &["add", "-A"]);
+    git(&root, &["commit", "-q", "-m", "v1"]);
+    git(&root, &["tag", "v1"]);
+    write(&root, "lib/math.ts", "export function keep() {}\n");
+
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    let result = rpc::handle_method(&mut indexer, "api_changes", json!({"from": "v1"})).unwrap();
+
+    let removed = change(&result, "lib/math.oldSum");
+    assert_eq!(removed["change"], json!("removed"));
+    assert_eq!(removed["external_callers"], json!(2));
+    assert_eq!(
+        removed["callers"],
+        json!([
+            {"source": "web/app.run", "path": "web/app.ts"},
+            {"source": "web/legacy.go", "path": "web/legacy.js"},
+        ])
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,148 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-asyncapi-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str, files: &[(&str, &str)]) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+/// Billing only exists as a spec: it receives the events the shop publishes.
+const BILLING_YAML: &str = r##"asyncapi: 2.6.0
+info:
+  title: Billing
+channels:
+  order_created:
+    publish:
+      operationId: chargeOrder
+      message:
+        $ref: "#/components/messages/OrderCreated"
+components:
+  messages:
+    OrderCreated:
+      payload:
+        $ref: "#/components/schemas/Order"
+  schemas:
+    Order:
+      type: object
+      properties:
+        id:
+          type: string
+"##;
+
+const SHOP_PY: &str = r#"def place_order(bus, order):
+    bus.publish("ORDER_CREATED", order)
+"#;
+
+fn files() -> Vec<(&'static str, &'static str)> {
+    vec![("specs/billing.yaml", BILLING_YAML), ("shop.py", SHOP_PY)]
+}
+
+#[test]
+fn spec_channels_bind_code_publishers() {
+    let (root, indexer) = index("bind", &files());
+    let db = indexer.db();
+    let gv = db.current_graph_version().unwrap();
+
+    let channel = db
+        .get_symbol_by_qualname("channel://ordercreated", gv)
+        .unwrap()
+        .expect("channel symbol");
+    assert_eq!(channel.kind, "channel");
+    let operation = db
+        .get_symbol_by_qualname("specs/billing#chargeOrder", gv)
+        .unwrap()
+        .expect("operation symbol");
+    assert_eq!(operation.kind, "operation");
+    assert_eq!(
+        operation.signature.as_deref(),
+        Some("receive order_created")
+    );
+    assert!(
+        db.get_symbol_by_qualname("specs/billing#/components/messages/OrderCreated", gv)
+            .unwrap()
+            .is_some()
+    );
+
+    let publishes = db
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&["CHANNEL_PUBLISH".to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let publish = publishes
+        .iter()
+        .find(|edge| edge.target_qualname.as_deref() == Some("channel://ordercreated"))
+        .expect("code publisher");
+    assert_eq!(publish.target_symbol_id, Some(channel.id));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn trace_flow_reaches_spec_only_subscriber() {
+    let (root, mut indexer) = index("trace", &files());
+
+    let trace = rpc::handle_method(
+        &mut indexer,
+        "trace_flow",
+        json!({
+            "start_qualname": "shop.place_order",
+            "include_snippets": false,
+        }),
+    )
+    .unwrap();
+    let hops: Vec<(&str, &str)> = trace["trace"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hop: &Value| {
+            (
+                hop["symbol"]["qualname"].as_str().unwrap(),
+                hop["edge_kind"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert!(
+        hops.contains(&("specs/billing#chargeOrder", "CHANNEL_SUBSCRIBE")),
+        "{hops:?}"
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
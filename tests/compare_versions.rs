@@ -0,0 +1,217 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-compare-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn git(root: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "user.email=test@example.com", "-c", "user.name=test"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+fn write(root: &Path, path: &str, source: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, source).unwrap();
+}
+
+const ROUTES_V1: &str = r#"from flask import Flask
+from store.users import load_user
+
+app = Flask(__name__)
+
+@app.get("/users/<id>")
+def get_user(id):
+    return load_user(id)
+
+@app.delete("/users/<id>")
+def delete_user(id):
+    return None
+"#;
+
+const ROUTES_V2: &str = r#"from flask import Flask
+from store.users import load_user
+from cache.lru import remember
+
+app = Flask(__name__)
+
+@app.get("/users/<id>")
+def get_user(id):
+    return remember(load_user(id))
+
+@app.post("/orders")
+def create_order(payload):
+    return payload
+"#;
+
+/// Tag `v1`, then a working tree that adds a cache module, a route and a
+/// parameter, and drops a route.
+fn repo(label: &str) -> PathBuf {
+    let root = temp_dir(label);
+    git(&root, &["init", "-q"]);
+    write(&root, ".gitignore", ".lidx/\n");
+    write(&root, "api/routes.py", ROUTES_V1);
+    write(
+        &root,
+        "store/users.py",
+        "def load_user(id):\n    return {'id': id}\n",
+    );
+    git(&root, &["add", "-A"]);
+    git(&root, &["commit", "-q", "-m", "v1"]);
+    git(&root, &["tag", "v1"]);
+
+    write(&root, "api/routes.py", ROUTES_V2);
+    write(
+        &root,
+        "store/users.py",
+        "def load_user(id, fields=None):\n    return {'id': id}\n",
+    );
+    write(
+        &root,
+        "cache/lru.py",
+        "def remember(value):\n    return value\n",
+    );
+    root
+}
+
+fn names(list: &Value, field: &str) -> Vec<String> {
+    list.as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry[field].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn compares_revision_with_working_tree() {
+    let root = repo("worktree");
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    let current = indexer.db().current_graph_version().unwrap();
+
+    // `v1` has not been indexed; compare_versions does it first.
+    let result = rpc::handle_method(
+        &mut indexer,
+        "compare_versions",
+        json!({"from": "v1", "max_response_bytes": 1_000_000}),
+    )
+    .unwrap();
+    assert_eq!(result["to_version"], json!(current));
+    assert!(indexer.revision_graph_version("v1").unwrap().is_some());
+
+    let symbols = &result["symbols"];
+    let added = names(&symbols["added"], "qualname");
+    assert!(
+        added.contains(&"api.routes.create_order".to_string()),
+        "{added:?}"
+    );
+    assert!(
+        added.contains(&"cache.lru.remember".to_string()),
+        "{added:?}"
+    );
+    assert_eq!(
+        names(&symbols["removed"], "qualname"),
+        vec!["api.routes.delete_user"]
+    );
+    assert_eq!(
+        names(&symbols["signature_changed"], "qualname"),
+        vec!["store.users.load_user"]
+    );
+    assert!(symbols["moved"].as_array().unwrap().is_empty());
+
+    let http = &result["http_routes"];
+    assert_eq!(names(&http["added"], "route"), vec!["POST /orders"]);
+    assert_eq!(names(&http["removed"], "route"), vec!["DELETE /users/{}"]);
+    assert_eq!(
+        http["added"][0]["handler"],
+        json!("api.routes.create_order")
+    );
+
+    let deps = &result["module_dependencies"]["added"];
+    assert!(
+        deps.as_array().unwrap().iter().any(|dep| {
+            dep["source_module"] == json!("api/") && dep["target_module"] == json!("cache/")
+        }),
+        "{deps}"
+    );
+    assert!(
+        result["edges"]["added"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|group| group["kind"] == json!("CALLS") && group["module"] == json!("api/")),
+        "{}",
+        result["edges"]
+    );
+    assert_eq!(result["summary"]["http_routes_added"], json!(1));
+    assert_eq!(result["summary"]["signatures_changed"], json!(1));
+
+    // The same versions in reverse swap added and removed.
+    let reverse = rpc::handle_method(
+        &mut indexer,
+        "compare_versions",
+        json!({"from": current, "to": "v1", "max_response_bytes": 1_000_000}),
+    )
+    .unwrap();
+    assert_eq!(
+        names(&reverse["http_routes"]["added"], "route"),
+        vec!["DELETE /users/{}"]
+    );
+    assert_eq!(
+        names(&reverse["symbols"]["added"], "qualname"),
+        vec!["api.routes.delete_user"]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn identical_versions_have_no_changes() {
+    let root = repo("identical");
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    let first = indexer.db().current_graph_version().unwrap();
+    indexer.reindex().unwrap();
+
+    let result =
+        rpc::handle_method(&mut indexer, "compare_versions", json!({"from": first})).unwrap();
+    let summary = &result["summary"];
+    assert!(summary["symbols_unchanged"].as_u64().unwrap() > 0);
+    for field in [
+        "symbols_added",
+        "symbols_removed",
+        "symbols_moved",
+        "signatures_changed",
+        "edges_added",
+        "edges_removed",
+        "module_dependencies_added",
+        "http_routes_added",
+    ] {
+        assert_eq!(summary[field], json!(0), "{field}: {summary}");
+    }
+
+    let _ = std::fs::remove_dir_all(&root);
+}
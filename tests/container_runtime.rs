@@ -0,0 +1,393 @@
+use lidx::indexer::Indexer;
+use lidx::indexer::module_name_for_path;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-runtime-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in FILES {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+/// `(source qualname, bound target qualname)` of every edge of `kind`.
+fn edges(indexer: &Indexer, kind: &str) -> Vec<(String, String)> {
+    let gv = indexer.db().current_graph_version().unwrap();
+    let edges = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&[kind.to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let qualname = |id: Option<i64>| {
+        id.and_then(|id| indexer.db().get_symbol_by_id(id).unwrap())
+            .map(|symbol| symbol.qualname)
+            .unwrap_or_default()
+    };
+    let mut pairs: Vec<(String, String)> = edges
+        .iter()
+        .map(|edge| {
+            (
+                qualname(edge.source_symbol_id),
+                qualname(edge.target_symbol_id),
+            )
+        })
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+fn pair(a: &str, b: &str) -> (String, String) {
+    (a.to_string(), b.to_string())
+}
+
+const DOCKERFILE: &str = r#"# API image
+FROM python:3.12-slim AS base
+WORKDIR /app
+ENV PYTHONUNBUFFERED=1 \
+    LOG_LEVEL=info
+
+FROM base AS runtime
+COPY requirements.txt .
+COPY app/ ./app/
+EXPOSE 8000
+CMD ["python", "-m", "app.main"]
+"#;
+
+const WORKER_DOCKERFILE: &str = r#"FROM node:20
+WORKDIR /srv
+COPY . .
+ENTRYPOINT ["node", "server.js"]
+"#;
+
+const COMPOSE: &str = r#"services:
+  api:
+    build: .
+    ports:
+      - "8000:8000"
+    environment:
+      DATABASE_URL: postgres://db/orders
+      Cache__Host: redis
+    depends_on:
+      - db
+  worker:
+    build:
+      context: ./worker
+    environment:
+      - QUEUE_URL=sqs://jobs
+  db:
+    image: postgres:16
+"#;
+
+const CHART: &str = r#"apiVersion: v2
+name: orders
+description: Orders API chart.
+version: 0.1.0
+"#;
+
+const VALUES: &str = r#"image:
+  repository: orders
+  tag: "1.0"
+database:
+  url: postgres://db/orders
+env:
+  QUEUE_URL: sqs://orders
+"#;
+
+const DEPLOYMENT: &str = r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: {{ include "orders.fullname" . }}
+  labels:
+    {{- include "orders.labels" . | nindent 4 }}
+spec:
+  template:
+    spec:
+      containers:
+        - name: {{ .Chart.Name }}
+          image: "{{ .Values.image.repository }}:{{ .Values.image.tag }}"
+          env:
+            - name: DATABASE_URL
+              value: {{ .Values.database.url | quote }}
+            {{- range $name, $value := .Values.env }}
+            - name: {{ $name }}
+              value: {{ $value | quote }}
+            {{- end }}
+"#;
+
+const MAIN_PY: &str = r#"import os
+
+def main():
+    return os.getenv("DATABASE_URL")
+"#;
+
+const SERVER_JS: &str = r#"function start() {
+  return process.env.QUEUE_URL;
+}
+
+module.exports = { start };
+"#;
+
+const FILES: &[(&str, &str)] = &[
+    ("Dockerfile", DOCKERFILE),
+    ("worker/Dockerfile", WORKER_DOCKERFILE),
+    ("docker-compose.yml", COMPOSE),
+    ("charts/orders/Chart.yaml", CHART),
+    ("charts/orders/values.yaml", VALUES),
+    ("charts/orders/templates/deployment.yaml", DEPLOYMENT),
+    ("app/__init__.py", ""),
+    ("app/main.py", MAIN_PY),
+    ("worker/server.js", SERVER_JS),
+];
+
+#[test]
+fn dockerfile_stages_entrypoints_and_env() {
+    let (root, indexer) = index("dockerfile");
+    let gv = indexer.db().current_graph_version().unwrap();
+
+    let runtime = indexer
+        .db()
+        .get_symbol_by_qualname("Dockerfile#runtime", gv)
+        .unwrap()
+        .unwrap();
+    assert_eq!(runtime.kind, "stage");
+    assert_eq!(runtime.signature.as_deref(), Some("FROM base"));
+    assert_eq!(
+        runtime.docstring.as_deref(),
+        Some("entrypoint: python -m app.main\nports: 8000")
+    );
+
+    assert_eq!(
+        edges(&indexer, "EXTENDS")
+            .into_iter()
+            .filter(|(source, _)| source.starts_with("Dockerfile"))
+            .collect::<Vec<_>>(),
+        vec![pair("Dockerfile#runtime", "Dockerfile#base")]
+    );
+    let worker_module = module_name_for_path("worker/server.js").unwrap();
+    assert_eq!(
+        edges(&indexer, "ENTRYPOINT"),
+        vec![
+            pair("Dockerfile#runtime", "app.main"),
+            pair("worker/Dockerfile#0", &worker_module),
+        ]
+    );
+    let base = indexer
+        .db()
+        .get_symbol_by_qualname("Dockerfile#base", gv)
+        .unwrap()
+        .unwrap();
+    let env: Vec<String> = indexer
+        .db()
+        .edges_for_symbol(base.id, None, gv)
+        .unwrap()
+        .into_iter()
+        .filter(|edge| edge.kind == "CONFIG_SOURCE")
+        .filter_map(|edge| edge.target_qualname)
+        .collect();
+    assert_eq!(env, vec!["env://PYTHONUNBUFFERED", "env://LOG_LEVEL"]);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn compose_services_link_dockerfiles_and_dependencies() {
+    let (root, indexer) = index("compose");
+    let gv = indexer.db().current_graph_version().unwrap();
+
+    let api = indexer
+        .db()
+        .get_symbol_by_qualname("compose://api", gv)
+        .unwrap()
+        .unwrap();
+    assert_eq!(api.kind, "service");
+    assert_eq!(api.signature.as_deref(), Some("build ."));
+    assert_eq!(api.docstring.as_deref(), Some("ports: 8000:8000"));
+    assert_eq!(api.start_line, 2);
+    assert_eq!(api.end_line, 10);
+
+    assert_eq!(
+        edges(&indexer, "DEPENDS_ON"),
+        vec![pair("compose://api", "compose://db")]
+    );
+    assert_eq!(
+        edges(&indexer, "IMPORTS_FILE")
+            .into_iter()
+            .filter(|(source, _)| source.starts_with("compose://"))
+            .collect::<Vec<_>>(),
+        vec![
+            pair("compose://api", "Dockerfile"),
+            pair("compose://worker", "worker/Dockerfile"),
+        ]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn helm_templates_render_against_values() {
+    let (root, indexer) = index("helm");
+    let gv = indexer.db().current_graph_version().unwrap();
+
+    let chart = indexer
+        .db()
+        .get_symbol_by_qualname("helm://charts/orders", gv)
+        .unwrap()
+        .unwrap();
+    assert_eq!(chart.kind, "chart");
+    assert_eq!(chart.signature.as_deref(), Some("version 0.1.0"));
+    let url = indexer
+        .db()
+        .get_symbol_by_qualname("helm://charts/orders/values/database.url", gv)
+        .unwrap()
+        .unwrap();
+    assert_eq!(url.kind, "value");
+    assert_eq!(url.signature.as_deref(), Some("postgres://db/orders"));
+    assert_eq!(url.start_line, 5);
+
+    let deployment = indexer
+        .db()
+        .get_symbol_by_qualname("k8s://default/deployment/orders", gv)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        deployment.file_path,
+        "charts/orders/templates/deployment.yaml"
+    );
+    assert_eq!(deployment.start_line, 1);
+
+    let references: Vec<(String, String)> = edges(&indexer, "REFERENCES")
+        .into_iter()
+        .filter(|(source, _)| source == "k8s://default/deployment/orders")
+        .collect();
+    assert_eq!(
+        references,
+        vec![
+            pair(
+                "k8s://default/deployment/orders",
+                "helm://charts/orders/values/database.url"
+            ),
+            pair(
+                "k8s://default/deployment/orders",
+                "helm://charts/orders/values/env"
+            ),
+            pair(
+                "k8s://default/deployment/orders",
+                "helm://charts/orders/values/env.QUEUE_URL"
+            ),
+            pair(
+                "k8s://default/deployment/orders",
+                "helm://charts/orders/values/image.repository"
+            ),
+            pair(
+                "k8s://default/deployment/orders",
+                "helm://charts/orders/values/image.tag"
+            ),
+        ]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn orient_reports_container_runtime() {
+    let (root, mut indexer) = index("orient");
+
+    let result = rpc::handle_method(
+        &mut indexer,
+        "orient",
+        json!({ "view": "overview", "focus_qualname": "app.main.main" }),
+    )
+    .unwrap();
+    let runtime = result["runtime"].as_array().unwrap();
+    assert_eq!(runtime.len(), 1);
+    let container = &runtime[0];
+    assert_eq!(container["stage"], "Dockerfile#runtime");
+    assert_eq!(container["base_image"], "base");
+    assert_eq!(container["command"], json!(["python", "-m", "app.main"]));
+    assert_eq!(container["ports"], json!(["8000"]));
+    assert_eq!(container["env"], json!(["LOG_LEVEL", "PYTHONUNBUFFERED"]));
+    assert_eq!(
+        container["services"],
+        json!([{
+            "service": "compose://api",
+            "file_path": "docker-compose.yml",
+            "ports": ["8000:8000"],
+            "env": ["Cache__Host", "DATABASE_URL"],
+        }])
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn config_reads_reach_compose_and_helm_sources() {
+    let (root, mut indexer) = index("trace");
+
+    let trace = rpc::handle_method(
+        &mut indexer,
+        "trace_flow",
+        json!({
+            "start_qualname": "app.main.main",
+            "include_snippets": false,
+        }),
+    )
+    .unwrap();
+    let hops: Vec<(&str, &str)> = trace["trace"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hop: &Value| {
+            (
+                hop["symbol"]["qualname"].as_str().unwrap(),
+                hop["edge_kind"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    for expected in [
+        ("compose://api", "CONFIG_SOURCE"),
+        (
+            "k8s://default/deployment/orders/container/orders",
+            "CONFIG_SOURCE",
+        ),
+    ] {
+        assert!(hops.contains(&expected), "missing {expected:?} in {hops:?}");
+    }
+
+    let _ = std::fs::remove_dir_all(&root);
+}
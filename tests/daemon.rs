@@ -0,0 +1,78 @@
+#![cfg(unix)]
+
+use lidx::daemon::{self, Client, DaemonConfig};
+use lidx::watch::{WatchConfig, WatchMode};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_repo_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-daemon-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn wait_for_client(db_path: &std::path::Path) -> Client {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        if let Some(client) = Client::connect(db_path).unwrap() {
+            return client;
+        }
+        assert!(Instant::now() < deadline, "daemon did not start");
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn daemon_serves_multiple_clients_and_exits_when_idle() {
+    let repo_root = temp_repo_dir("serve");
+    std::fs::write(repo_root.join("app.py"), "def greet():\n    return 'hi'\n").unwrap();
+    let db_path = repo_root.join(".lidx").join(".lidx.sqlite");
+
+    let watch = WatchConfig {
+        mode: WatchMode::Off,
+        ..WatchConfig::default()
+    };
+    let config = DaemonConfig::new(watch, 1);
+    let server = {
+        let repo_root = repo_root.clone();
+        let db_path = db_path.clone();
+        std::thread::spawn(move || daemon::serve(repo_root, db_path, config))
+    };
+
+    let mut first = wait_for_client(&db_path);
+    let mut second = Client::connect(&db_path).unwrap().expect("second client");
+
+    let stats = first.call("reindex", json!({})).unwrap();
+    assert!(stats.is_object(), "reindex result: {stats}");
+
+    let response = second
+        .request_line(r#"{"id":7,"method":"explain_symbol","params":{"qualname":"app.greet"}}"#)
+        .unwrap();
+    let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(response["id"], json!(7));
+    assert!(response.to_string().contains("app.greet"), "{response}");
+
+    let err = first.call("no_such_method", json!({})).unwrap_err();
+    assert!(!err.to_string().is_empty());
+
+    // A second daemon for the same database refuses to start.
+    assert!(daemon::serve(repo_root.clone(), db_path.clone(), config).is_err());
+
+    drop(first);
+    drop(second);
+    server.join().unwrap().unwrap();
+    assert!(!daemon::socket_path(&db_path).exists());
+    assert!(Client::connect(&db_path).unwrap().is_none());
+
+    let _ = std::fs::remove_dir_all(&repo_root);
+}
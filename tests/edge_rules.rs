@@ -0,0 +1,235 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-edge-rules-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str, files: &[(&str, &str)]) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in files {
+        std::fs::write(root.join(path), source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+/// `(source qualname, target, rule id)` of every edge of `kind`.
+fn edges(indexer: &Indexer, kind: &str) -> Vec<(String, String, String)> {
+    let gv = indexer.db().current_graph_version().unwrap();
+    let edges = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&[kind.to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let mut rows: Vec<(String, String, String)> = edges
+        .iter()
+        .map(|edge| {
+            let source = edge
+                .source_symbol_id
+                .and_then(|id| indexer.db().get_symbol_by_id(id).unwrap())
+                .map(|symbol| symbol.qualname)
+                .unwrap_or_default();
+            let detail: Value = serde_json::from_str(edge.detail.as_deref().unwrap()).unwrap();
+            (
+                source,
+                edge.target_qualname.clone().unwrap_or_default(),
+                detail["rule"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    rows.sort();
+    rows
+}
+
+fn row(source: &str, target: &str, rule: &str) -> (String, String, String) {
+    (source.to_string(), target.to_string(), rule.to_string())
+}
+
+const RULES: &str = r#"
+[[edges]]
+id = "feature-flag"
+languages = ["csharp"]
+kind = "FEATURE_FLAG_READ"
+target = "flag://{flag}"
+query = '''
+(invocation_expression
+  function: (member_access_expression
+    expression: (identifier) @_class (#eq? @_class "FeatureFlags")
+    name: (identifier) @_method (#eq? @_method "IsEnabled"))
+  arguments: (argument_list (argument (string_literal) @flag)))
+'''
+
+[[edges]]
+id = "auth-policy"
+languages = ["csharp"]
+kind = "AUTH_POLICY"
+target = "policy://{policy}"
+query = '''
+(attribute
+  name: (identifier) @_name (#eq? @_name "Authorize")
+  (attribute_argument_list
+    (attribute_argument
+      (assignment_expression right: (string_literal) @policy))))
+'''
+
+[[edges]]
+id = "bus-handler"
+languages = ["csharp"]
+kind = "REGISTERS_HANDLER"
+target = "{handler}"
+confidence = 0.8
+query = '''
+(invocation_expression
+  function: (member_access_expression
+    name: (generic_name (identifier) @_m (#eq? @_m "Register")
+      (type_argument_list (identifier) @handler))))
+'''
+"#;
+
+const CHECKOUT_CS: &str = r#"namespace Acme
+{
+    public class OrderHandler
+    {
+        public void Handle() {}
+    }
+
+    public class Checkout
+    {
+        [Authorize(Policy = "Admin")]
+        public void Start()
+        {
+            if (FeatureFlags.IsEnabled("new-checkout")) { }
+        }
+
+        public void Wire()
+        {
+            Bus.Register<OrderHandler>();
+        }
+    }
+
+    public class Controller
+    {
+        public void Run(Checkout checkout)
+        {
+            checkout.Start();
+        }
+    }
+}
+"#;
+
+#[test]
+fn query_rules_emit_edges_tagged_with_rule_ids() {
+    let (root, indexer) = index(
+        "emit",
+        &[(".lidx.toml", RULES), ("Checkout.cs", CHECKOUT_CS)],
+    );
+
+    assert_eq!(
+        edges(&indexer, "FEATURE_FLAG_READ"),
+        vec![row(
+            "Acme.Checkout.Start",
+            "flag://new-checkout",
+            "feature-flag"
+        )]
+    );
+    assert_eq!(
+        edges(&indexer, "AUTH_POLICY"),
+        vec![row("Acme.Checkout.Start", "policy://Admin", "auth-policy")]
+    );
+    assert_eq!(
+        edges(&indexer, "REGISTERS_HANDLER"),
+        vec![row("Acme.Checkout.Wire", "OrderHandler", "bus-handler")]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn trace_flow_and_impact_follow_rule_edges() {
+    let (root, mut indexer) = index(
+        "follow",
+        &[(".lidx.toml", RULES), ("Checkout.cs", CHECKOUT_CS)],
+    );
+
+    // Rule kinds are followed by default and their targets resolve to symbols.
+    let trace = rpc::handle_method(
+        &mut indexer,
+        "trace_flow",
+        json!({ "start_qualname": "Acme.Checkout.Wire" }),
+    )
+    .unwrap();
+    let hops: Vec<&str> = trace["trace"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hop| hop["symbol"]["qualname"].as_str().unwrap())
+        .collect();
+    assert!(hops.contains(&"Acme.OrderHandler"), "{trace}");
+
+    // A rule target URI seeds impact analysis with the symbols that use it.
+    let impact = rpc::handle_method(
+        &mut indexer,
+        "analyze_impact",
+        json!({
+            "qualname": "flag://new-checkout",
+            "direction": "upstream",
+            "max_depth": 3,
+            "enable_historical": false,
+        }),
+    )
+    .unwrap();
+    let affected: Vec<&str> = impact["affected"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["symbol"]["qualname"].as_str().unwrap())
+        .collect();
+    assert!(affected.contains(&"Acme.Controller.Run"), "{impact}");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn invalid_query_rules_are_reported() {
+    let root = temp_dir("invalid");
+    std::fs::write(
+        root.join(".lidx.toml"),
+        "[[edges]]\nid = \"broken\"\nlanguages = [\"csharp\"]\nkind = \"X\"\ntarget = \"x\"\nquery = \"(invocation_expression\"\n",
+    )
+    .unwrap();
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let err = Indexer::new(root.clone(), db_path).err().unwrap();
+    assert!(format!("{err:#}").contains("edge rule `broken`"), "{err:#}");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
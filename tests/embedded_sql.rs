@@ -0,0 +1,210 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-embedded-sql-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str, files: &[(&str, &str)]) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in files {
+        std::fs::write(root.join(path), source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+/// `(source qualname, target qualname, bound symbol qualname)` of every edge
+/// of `kind`.
+fn edges(indexer: &Indexer, kind: &str) -> Vec<(String, String, String)> {
+    let gv = indexer.db().current_graph_version().unwrap();
+    let edges = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&[kind.to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let qualname = |id: Option<i64>| {
+        id.and_then(|id| indexer.db().get_symbol_by_id(id).unwrap())
+            .map(|symbol| symbol.qualname)
+            .unwrap_or_default()
+    };
+    let mut rows: Vec<(String, String, String)> = edges
+        .iter()
+        .map(|edge| {
+            (
+                qualname(edge.source_symbol_id),
+                edge.target_qualname.clone().unwrap_or_default(),
+                qualname(edge.target_symbol_id),
+            )
+        })
+        .collect();
+    rows.sort();
+    rows
+}
+
+fn row(source: &str, target: &str, bound: &str) -> (String, String, String) {
+    (source.to_string(), target.to_string(), bound.to_string())
+}
+
+const SCHEMA_SQL: &str = r#"CREATE TABLE orders (id INT PRIMARY KEY, status TEXT);
+CREATE TABLE order_lines (id INT PRIMARY KEY, order_id INT);
+CREATE TABLE audit_log (id INT PRIMARY KEY, message TEXT);
+"#;
+
+const REPO_CS: &str = r#"namespace Shop
+{
+    public class OrderRepository
+    {
+        public async Task<Order> Get(int id)
+        {
+            return await _conn.QueryFirstAsync<Order>(
+                "SELECT o.id, o.status FROM Orders o JOIN order_lines l ON l.order_id = o.id WHERE o.id = @id",
+                new { id });
+        }
+
+        public async Task Cancel(int id)
+        {
+            await _conn.ExecuteAsync(@"UPDATE [dbo].[Orders]
+                SET status = 'cancelled'
+                WHERE id = @id", new { id });
+            _logger.Log("Update failed for order");
+        }
+    }
+
+    [Table("audit_log")]
+    public class AuditEntry
+    {
+        public int Id { get; set; }
+    }
+}
+"#;
+
+const WORKER_PY: &str = r#"from sqlalchemy.orm import declarative_base
+
+Base = declarative_base()
+
+
+class OrderLine(Base):
+    __tablename__ = "order_lines"
+
+
+def purge(cursor):
+    cursor.execute("DELETE FROM audit_log WHERE created < now() - interval '30 days'")
+"#;
+
+const STORE_GO: &str = r#"package store
+
+type Order struct {
+	ID int
+}
+
+func (Order) TableName() string {
+	return "orders"
+}
+
+func Insert(db *sql.DB, id int) error {
+	_, err := db.Exec(`INSERT INTO orders (id, status) VALUES ($1, 'new')`, id)
+	return err
+}
+"#;
+
+fn files() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("schema.sql", SCHEMA_SQL),
+        ("OrderRepository.cs", REPO_CS),
+        ("worker.py", WORKER_PY),
+        ("store.go", STORE_GO),
+    ]
+}
+
+#[test]
+fn sql_strings_and_orm_models_link_to_tables() {
+    let (root, indexer) = index("edges", &files());
+
+    assert_eq!(
+        edges(&indexer, "READS_TABLE"),
+        vec![
+            row("Shop.AuditEntry", "audit_log", "audit_log"),
+            row("Shop.OrderRepository.Get", "Orders", "orders"),
+            row("Shop.OrderRepository.Get", "order_lines", "order_lines"),
+            row("store.Order", "orders", "orders"),
+            row("worker.OrderLine", "order_lines", "order_lines"),
+        ]
+    );
+    assert_eq!(
+        edges(&indexer, "WRITES_TABLE"),
+        vec![
+            row("Shop.AuditEntry", "audit_log", "audit_log"),
+            row("Shop.OrderRepository.Cancel", "dbo.Orders", "orders"),
+            row("store.Insert", "orders", "orders"),
+            row("store.Order", "orders", "orders"),
+            row("worker.OrderLine", "order_lines", "order_lines"),
+            row("worker.purge", "audit_log", "audit_log"),
+        ]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn table_impact_reaches_code_that_reads_or_writes_it() {
+    let (root, mut indexer) = index("impact", &files());
+
+    let impact = rpc::handle_method(
+        &mut indexer,
+        "analyze_impact",
+        json!({
+            "qualname": "orders",
+            "direction": "upstream",
+            "max_depth": 2,
+            "enable_historical": false,
+        }),
+    )
+    .unwrap();
+    let affected: Vec<&str> = impact["affected"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry: &Value| entry["symbol"]["qualname"].as_str().unwrap())
+        .collect();
+    for expected in [
+        "Shop.OrderRepository.Get",
+        "Shop.OrderRepository.Cancel",
+        "store.Insert",
+        "store.Order",
+    ] {
+        assert!(affected.contains(&expected), "{expected}: {impact}");
+    }
+    assert!(!affected.contains(&"worker.purge"), "{impact}");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
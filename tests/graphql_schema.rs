@@ -0,0 +1,239 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-graphql-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str, files: &[(&str, &str)]) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+/// `(source qualname, target qualname)` of every edge of `kind`.
+fn edges(indexer: &Indexer, kind: &str) -> Vec<(String, String)> {
+    let gv = indexer.db().current_graph_version().unwrap();
+    let edges = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&[kind.to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let mut rows: Vec<(String, String)> = edges
+        .iter()
+        .map(|edge| {
+            let source = edge
+                .source_symbol_id
+                .and_then(|id| indexer.db().get_symbol_by_id(id).unwrap())
+                .map(|symbol| symbol.qualname)
+                .unwrap_or_default();
+            (source, edge.target_qualname.clone().unwrap_or_default())
+        })
+        .collect();
+    rows.sort();
+    rows
+}
+
+fn pair(source: &str, target: &str) -> (String, String) {
+    (source.to_string(), target.to_string())
+}
+
+const SCHEMA_GRAPHQL: &str = r#"type User {
+  id: ID!
+  name: String
+}
+
+type Query {
+  user(id: ID!): User
+}
+
+type Mutation {
+  renameUser(id: ID!, name: String!): User
+}
+"#;
+
+const RESOLVERS_TS: &str = r#"import { loadUser } from "./users";
+
+export const resolvers = {
+  Query: {
+    user: (_parent, args) => loadUser(args.id),
+  },
+};
+"#;
+
+const CLIENT_TS: &str = r#"const GET_USER = gql`
+  query GetUser($id: ID!) {
+    user(id: $id) { id name }
+  }
+`;
+
+export async function showUser(client, id) {
+  const result = await client.query({
+    query: gql`
+      mutation Rename($id: ID!) { renameUser(id: $id, name: "x") { id } }
+    `,
+  });
+  return result;
+}
+"#;
+
+const MUTATIONS_CS: &str = r#"namespace Gateway
+{
+    [MutationType]
+    public class UserMutations
+    {
+        public async Task<User> RenameUserAsync(string id, string name)
+        {
+            return null;
+        }
+
+        private void Helper() { }
+    }
+
+    [ExtendObjectType(OperationTypeNames.Query)]
+    public class UserQueries
+    {
+        public User GetUser(string id) => null;
+    }
+}
+"#;
+
+const NEST_TS: &str = r#"@Resolver(() => User)
+export class UsersResolver {
+  @Query(() => User, { name: "user" })
+  findOne(id: string) {
+    return null;
+  }
+}
+"#;
+
+fn files() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("schema/schema.graphql", SCHEMA_GRAPHQL),
+        ("src/resolvers.ts", RESOLVERS_TS),
+        ("src/client.ts", CLIENT_TS),
+        ("src/Mutations.cs", MUTATIONS_CS),
+        ("src/users.resolver.ts", NEST_TS),
+    ]
+}
+
+#[test]
+fn resolvers_and_clients_share_field_keys() {
+    let (root, indexer) = index("edges", &files());
+    let gv = indexer.db().current_graph_version().unwrap();
+    let user = indexer
+        .db()
+        .get_symbol_by_qualname("schema/schema.Query.user", gv)
+        .unwrap()
+        .expect("schema root field");
+    assert_eq!(user.kind, "query");
+    assert_eq!(user.signature.as_deref(), Some("user(id: ID!): User"));
+
+    assert_eq!(
+        edges(&indexer, "GRAPHQL_RESOLVE"),
+        vec![
+            pair(
+                "Gateway.UserMutations.RenameUserAsync",
+                "graphql://mutation/renameuser"
+            ),
+            pair("Gateway.UserQueries.GetUser", "graphql://query/user"),
+            pair(
+                "schema/schema.Mutation.renameUser",
+                "graphql://mutation/renameuser"
+            ),
+            pair("schema/schema.Query.user", "graphql://query/user"),
+            pair("src/resolvers.resolvers", "graphql://query/user"),
+            pair(
+                "src/users.resolver.UsersResolver.findOne",
+                "graphql://query/user"
+            ),
+        ]
+    );
+    assert_eq!(
+        edges(&indexer, "GRAPHQL_CALL"),
+        vec![
+            pair("src/client.GET_USER", "graphql://query/user"),
+            pair("src/client.showUser", "graphql://mutation/renameuser"),
+        ]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn trace_flow_crosses_from_client_to_resolvers() {
+    let (root, mut indexer) = index("trace", &files());
+
+    let trace = rpc::handle_method(
+        &mut indexer,
+        "trace_flow",
+        json!({
+            "start_qualname": "src/client.showUser",
+            "include_snippets": false,
+        }),
+    )
+    .unwrap();
+    let hops: Vec<(&str, &str)> = trace["trace"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hop: &Value| {
+            (
+                hop["symbol"]["qualname"].as_str().unwrap(),
+                hop["edge_kind"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    for expected in [
+        "Gateway.UserMutations.RenameUserAsync",
+        "schema/schema.Mutation.renameUser",
+    ] {
+        assert!(
+            hops.contains(&(expected, "GRAPHQL_RESOLVE")),
+            "{expected}: {hops:?}"
+        );
+    }
+    let boundary = trace["trace"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|hop| hop["edge_kind"] == "GRAPHQL_RESOLVE")
+        .unwrap();
+    assert_eq!(boundary["boundary_type"], "graphql", "{boundary}");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
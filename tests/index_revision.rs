@@ -0,0 +1,286 @@
+use lidx::indexer::Indexer;
+use lidx::indexer::scan::ScanOptions;
+use lidx::rpc;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-revision-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn git(root: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "user.email=test@example.com", "-c", "user.name=test"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+fn write(root: &Path, path: &str, source: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, source).unwrap();
+}
+
+/// `v1` has `legacy.py` and an `app.helper` that later commits drop; the
+/// working tree adds `app.farewell` and keeps `util.py` unchanged.
+fn repo_with_history(label: &str) -> PathBuf {
+    let root = temp_dir(label);
+    git(&root, &["init", "-q"]);
+    write(&root, ".gitignore", ".lidx/\n");
+    write(
+        &root,
+        "app.py",
+        "from util import shout\n\ndef greet():\n    return shout('hi')\n\ndef helper():\n    return greet()\n",
+    );
+    write(
+        &root,
+        "util.py",
+        "def shout(text):\n    return text.upper()\n",
+    );
+    write(&root, "legacy.py", "def old():\n    return 1\n");
+    git(&root, &["add", "-A"]);
+    git(&root, &["commit", "-q", "-m", "v1"]);
+    git(&root, &["tag", "v1"]);
+
+    write(
+        &root,
+        "app.py",
+        "from util import shout\n\ndef greet():\n    return shout('hi')\n\ndef farewell():\n    return shout('bye')\n",
+    );
+    std::fs::remove_file(root.join("legacy.py")).unwrap();
+    git(&root, &["add", "-A"]);
+    git(&root, &["commit", "-q", "-m", "v2"]);
+    git(&root, &["tag", "v2"]);
+    root
+}
+
+fn paths(indexer: &Indexer, graph_version: i64) -> Vec<String> {
+    indexer
+        .db()
+        .list_files(graph_version)
+        .unwrap()
+        .into_iter()
+        .map(|file| file.path)
+        .collect()
+}
+
+#[test]
+fn revision_is_indexed_as_its_own_graph_version() {
+    let root = repo_with_history("own-version");
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    let current = indexer.db().current_graph_version().unwrap();
+
+    let stats = indexer.index_revision("v1").unwrap();
+    assert!(!stats.already_indexed);
+    assert_eq!(stats.revision, "v1");
+    assert_eq!(stats.scanned, 3);
+    // util.py matches the working tree; app.py and legacy.py are parsed.
+    assert_eq!(stats.reused, 1);
+    assert_eq!(stats.indexed, 2);
+    assert_eq!(indexer.db().current_graph_version().unwrap(), current);
+
+    let v1 = indexer.revision_graph_version("v1").unwrap().unwrap();
+    assert_eq!(stats.graph_version, v1);
+    assert_eq!(paths(&indexer, v1), vec!["app.py", "legacy.py", "util.py"]);
+    assert_eq!(paths(&indexer, current), vec!["app.py", "util.py"]);
+    let versions = indexer.db().list_graph_versions(10, 0).unwrap();
+    let version = versions.iter().find(|version| version.id == v1).unwrap();
+    assert_eq!(version.revision.as_deref(), Some("v1"));
+
+    // The revision's symbols answer queries that name it.
+    let helper = rpc::handle_method(
+        &mut indexer,
+        "explain_symbol",
+        json!({"qualname": "app.helper", "graph_version": "v1"}),
+    )
+    .unwrap();
+    assert_eq!(helper["symbol"]["qualname"], json!("app.helper"));
+    let util = indexer.db().get_symbols_for_file("util.py", v1).unwrap();
+    assert!(util.iter().any(|symbol| symbol.qualname == "util.shout"));
+    let app = indexer
+        .db()
+        .get_symbols_for_file("app.py", current)
+        .unwrap();
+    assert!(app.iter().all(|symbol| symbol.qualname != "app.helper"));
+
+    let again = indexer.index_revision("v1").unwrap();
+    assert!(again.already_indexed);
+    assert_eq!(again.graph_version, v1);
+
+    // v2 matches the working tree, so nothing is parsed.
+    let v2 = indexer.index_revision("v2").unwrap();
+    assert_eq!(v2.indexed, 0);
+    assert_eq!(v2.reused, 2);
+    assert_eq!(paths(&indexer, v2.graph_version), vec!["app.py", "util.py"]);
+
+    // A later reindex of the working tree leaves the revisions intact.
+    indexer.reindex().unwrap();
+    let current = indexer.db().current_graph_version().unwrap();
+    assert_eq!(paths(&indexer, current), vec!["app.py", "util.py"]);
+    assert_eq!(paths(&indexer, v1), vec!["app.py", "legacy.py", "util.py"]);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn unindexed_revision_is_rejected() {
+    let root = repo_with_history("unindexed");
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+
+    let err = rpc::handle_method(
+        &mut indexer,
+        "explain_symbol",
+        json!({"qualname": "app.greet", "graph_version": "v1"}),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("index_revision"), "{err}");
+    assert!(indexer.index_revision("no-such-ref").is_err());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn previous_version_skips_revision_versions() {
+    let root = repo_with_history("previous-version");
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    let first = indexer.db().current_graph_version().unwrap();
+    let revision = indexer.index_revision("v1").unwrap().graph_version;
+    indexer.reindex().unwrap();
+    let second = indexer.db().current_graph_version().unwrap();
+    assert!(first < revision && revision < second);
+
+    assert_eq!(
+        indexer.db().previous_graph_version(second).unwrap(),
+        Some(first)
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn dropped_revision_version_is_indexed_again() {
+    let root = repo_with_history("dropped");
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    let first = indexer.index_revision("v1").unwrap();
+
+    // What index_revision does with a version it failed to fill.
+    indexer
+        .db()
+        .delete_revision_version(first.graph_version)
+        .unwrap();
+    assert_eq!(indexer.revision_graph_version("v1").unwrap(), None);
+    assert!(
+        indexer
+            .db()
+            .get_symbols_for_file("legacy.py", first.graph_version)
+            .unwrap()
+            .is_empty()
+    );
+
+    let again = indexer.index_revision("v1").unwrap();
+    assert!(!again.already_indexed);
+    assert_eq!(again.indexed, first.indexed);
+    assert_eq!(
+        paths(&indexer, again.graph_version),
+        vec!["app.py", "legacy.py", "util.py"]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn revision_edge_count_matches_stored_edges() {
+    let root = temp_dir("edge-count");
+    git(&root, &["init", "-q"]);
+    write(&root, ".gitignore", ".lidx/\n");
+    write(
+        &root,
+        "shapes.py",
+        "class Shape:\n    def area(self):\n        return 0\n\nclass Square(Shape):\n    def area(self):\n        return 1\n",
+    );
+    git(&root, &["add", "-A"]);
+    git(&root, &["commit", "-q", "-m", "v1"]);
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    let current = indexer.db().current_graph_version().unwrap();
+
+    // Every file is copied, OVERRIDES edges included, and the override
+    // pass rebuilds them.
+    let stats = indexer.index_revision("HEAD").unwrap();
+    assert_eq!(stats.reused, 1);
+    let stored = indexer.db().count_version_edges(current).unwrap();
+    assert!(stored > 0);
+    assert_eq!(stats.edges, stored);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn revision_scan_applies_ignore_files_and_size_limit() {
+    let root = temp_dir("ignored");
+    git(&root, &["init", "-q"]);
+    write(&root, ".gitignore", ".lidx/\ngenerated/\n");
+    write(&root, "sub/.gitignore", "*_pb2.py\n!keep_pb2.py\n");
+    write(&root, "app.py", "def main():\n    return 1\n");
+    write(&root, "generated/api.py", "def api():\n    return 1\n");
+    write(&root, "sub/model_pb2.py", "def model():\n    return 1\n");
+    write(&root, "sub/keep_pb2.py", "def keep():\n    return 1\n");
+    write(
+        &root,
+        "big.py",
+        &"# padding\n".repeat(11 * 1024 * 1024 / 10 + 1),
+    );
+    // Tracked despite the ignore rules, as checked-in generated code is.
+    git(&root, &["add", "-A", "-f"]);
+    git(&root, &["commit", "-q", "-m", "v1"]);
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+
+    let mut indexer = Indexer::new(root.clone(), db_path.clone()).unwrap();
+    let stats = indexer.index_revision("HEAD").unwrap();
+    assert_eq!(
+        paths(&indexer, stats.graph_version),
+        vec!["app.py", "sub/keep_pb2.py"]
+    );
+    drop(indexer);
+    let _ = std::fs::remove_dir_all(root.join(".lidx"));
+
+    let mut indexer =
+        Indexer::new_with_options(root.clone(), db_path, ScanOptions::new(true)).unwrap();
+    let stats = indexer.index_revision("HEAD").unwrap();
+    assert_eq!(
+        paths(&indexer, stats.graph_version),
+        vec![
+            "app.py",
+            "generated/api.py",
+            "sub/keep_pb2.py",
+            "sub/model_pb2.py"
+        ]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
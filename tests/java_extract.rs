@@ -0,0 +1,316 @@
+use lidx::indexer::Indexer;
+use lidx::indexer::extract::LanguageExtractor;
+use lidx::indexer::java::{JavaExtractor, module_name_from_rel_path};
+
+#[test]
+fn module_name_from_path() {
+    assert_eq!(
+        module_name_from_rel_path("src/main/java/com/acme/App.java"),
+        "src/main/java/com/acme/App"
+    );
+    assert_eq!(module_name_from_rel_path("App.java"), "App");
+}
+
+#[test]
+fn extract_symbols_and_edges() {
+    let source = r#"
+package com.acme.users;
+
+import java.util.List;
+import com.acme.core.BaseService;
+
+/**
+ * Manages users.
+ */
+public class UserService extends BaseService implements Runnable {
+    private final List<String> names;
+
+    public UserService(List<String> names) {
+        this.names = names;
+    }
+
+    public void run() {
+        helper();
+        names.add("x");
+    }
+
+    private static int helper() {
+        return 1;
+    }
+
+    public enum Role { ADMIN, USER }
+
+    interface Listener {
+        void onChange(String name);
+    }
+}
+"#;
+    let mut extractor = JavaExtractor::new().unwrap();
+    let extracted = extractor
+        .extract(source, "src/com/acme/users/UserService")
+        .unwrap();
+
+    let names: Vec<_> = extracted
+        .symbols
+        .iter()
+        .map(|s| (s.kind.as_str(), s.qualname.as_str()))
+        .collect();
+
+    assert!(names.contains(&("module", "src/com/acme/users/UserService")));
+    assert!(names.contains(&("class", "com.acme.users.UserService")));
+    assert!(names.contains(&("field", "com.acme.users.UserService.names")));
+    assert!(names.contains(&("method", "com.acme.users.UserService.UserService")));
+    assert!(names.contains(&("method", "com.acme.users.UserService.run")));
+    assert!(names.contains(&("method", "com.acme.users.UserService.helper")));
+    assert!(names.contains(&("enum", "com.acme.users.UserService.Role")));
+    assert!(names.contains(&("interface", "com.acme.users.UserService.Listener")));
+    assert!(names.contains(&("method", "com.acme.users.UserService.Listener.onChange")));
+
+    let class = extracted
+        .symbols
+        .iter()
+        .find(|s| s.qualname == "com.acme.users.UserService")
+        .unwrap();
+    assert_eq!(class.docstring.as_deref(), Some("Manages users."));
+
+    let has_edge = |kind: &str, target: &str| {
+        extracted
+            .edges
+            .iter()
+            .any(|e| e.kind == kind && e.target_qualname.as_deref() == Some(target))
+    };
+    assert!(has_edge("IMPORTS", "java.util.List"));
+    assert!(has_edge("EXTENDS", "com.acme.core.BaseService"));
+    assert!(has_edge("IMPLEMENTS", "Runnable"));
+    assert!(has_edge("CONTAINS", "com.acme.users.UserService.run"));
+    assert!(has_edge("CALLS", "com.acme.users.UserService.helper"));
+}
+
+#[test]
+fn extract_records() {
+    let source = r#"
+package com.acme.geo;
+
+/** A point. */
+public record Point(int x, int y) implements Comparable<Point> {
+    public Point {
+        validate();
+    }
+
+    static Point origin() {
+        return new Point(0, 0);
+    }
+}
+"#;
+    let mut extractor = JavaExtractor::new().unwrap();
+    let extracted = extractor.extract(source, "geo/Point").unwrap();
+
+    let names: Vec<_> = extracted
+        .symbols
+        .iter()
+        .map(|s| (s.kind.as_str(), s.qualname.as_str()))
+        .collect();
+    assert!(names.contains(&("record", "com.acme.geo.Point")));
+    assert!(names.contains(&("field", "com.acme.geo.Point.x")));
+    assert!(names.contains(&("field", "com.acme.geo.Point.y")));
+    assert!(names.contains(&("method", "com.acme.geo.Point.Point")));
+    assert!(names.contains(&("method", "com.acme.geo.Point.origin")));
+
+    let record = extracted
+        .symbols
+        .iter()
+        .find(|s| s.qualname == "com.acme.geo.Point")
+        .unwrap();
+    assert_eq!(record.docstring.as_deref(), Some("A point."));
+    assert_eq!(
+        record.signature.as_deref(),
+        Some("record Point(int x, int y) implements Comparable<Point>")
+    );
+    assert!(extracted.edges.iter().any(|e| e.kind == "IMPLEMENTS"
+        && e.source_qualname.as_deref() == Some("com.acme.geo.Point")
+        && e.target_qualname.as_deref() == Some("Comparable")));
+    assert!(extracted.edges.iter().any(|e| e.kind == "CALLS"
+        && e.source_qualname.as_deref() == Some("com.acme.geo.Point.Point")
+        && e.target_qualname.as_deref() == Some("com.acme.geo.Point.validate")));
+    assert!(extracted.edges.iter().any(|e| e.kind == "CALLS"
+        && e.source_qualname.as_deref() == Some("com.acme.geo.Point.origin")
+        && e.target_qualname.as_deref() == Some("Point")));
+}
+
+#[test]
+fn extract_spring_routes() {
+    let source = r#"
+package com.acme.web;
+
+@RestController
+@RequestMapping("/api/users")
+public class UserController {
+    @GetMapping("/{id}")
+    public User get(@PathVariable String id) {
+        return null;
+    }
+
+    @RequestMapping(value = "/search", method = RequestMethod.POST)
+    public List<User> search() {
+        return null;
+    }
+}
+"#;
+    let mut extractor = JavaExtractor::new().unwrap();
+    let extracted = extractor.extract(source, "web/UserController").unwrap();
+
+    let routes: Vec<_> = extracted
+        .edges
+        .iter()
+        .filter(|e| e.kind == "HTTP_ROUTE")
+        .collect();
+    assert!(
+        routes.iter().any(|e| {
+            e.source_qualname.as_deref() == Some("com.acme.web.UserController.get")
+                && e.detail.as_deref().is_some_and(|d| {
+                    d.contains("\"GET\"") && d.contains("/api/users/{id}") && d.contains("spring")
+                })
+        }),
+        "routes: {routes:?}"
+    );
+    assert!(routes.iter().any(|e| {
+        e.detail
+            .as_deref()
+            .is_some_and(|d| d.contains("\"POST\"") && d.contains("/api/users/search"))
+    }));
+}
+
+#[test]
+fn extract_grpc_impl_and_stub_calls() {
+    let source = r#"
+package com.acme.greet;
+
+public class GreeterImpl extends GreeterGrpc.GreeterImplBase {
+    @Override
+    public void sayHello(HelloRequest req, StreamObserver<HelloReply> obs) {
+    }
+}
+
+class Client {
+    void call() {
+        GreeterGrpc.GreeterBlockingStub stub = GreeterGrpc.newBlockingStub(channel);
+        stub.sayHello(request);
+    }
+}
+"#;
+    let mut extractor = JavaExtractor::new().unwrap();
+    let extracted = extractor.extract(source, "greet/GreeterImpl").unwrap();
+
+    assert!(extracted.edges.iter().any(|e| {
+        e.kind == "RPC_IMPL"
+            && e.source_qualname.as_deref() == Some("com.acme.greet.GreeterImpl.sayHello")
+            && e.target_qualname.as_deref() == Some("/greeter/sayhello")
+            && e.detail
+                .as_deref()
+                .is_some_and(|d| d.contains("grpc-java") && d.contains("\"rpc\":\"SayHello\""))
+    }));
+    assert!(extracted.edges.iter().any(|e| {
+        e.kind == "RPC_CALL"
+            && e.source_qualname.as_deref() == Some("com.acme.greet.Client.call")
+            && e.target_qualname.as_deref() == Some("/greeter/sayhello")
+    }));
+}
+
+#[test]
+fn grpc_impl_bridges_to_calls_from_other_languages() {
+    let mut root = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    root.push(format!("lidx-java-grpc-{nanos}"));
+    let files = [
+        (
+            "proto/greet.proto",
+            "syntax = \"proto3\";\npackage helloworld;\noption java_package = \"com.acme.greet\";\n\nservice Greeter {\n  rpc SayHello (HelloRequest) returns (HelloReply);\n}\n",
+        ),
+        (
+            "server/src/main/java/com/acme/greet/GreeterImpl.java",
+            "package com.acme.greet;\n\npublic class GreeterImpl extends GreeterGrpc.GreeterImplBase {\n    @Override\n    public void sayHello(HelloRequest req, StreamObserver<HelloReply> obs) {\n    }\n}\n",
+        ),
+        (
+            "client/main.go",
+            "package main\n\nfunc greet(conn *grpc.ClientConn) {\n\tclient := pb.NewGreeterClient(conn)\n\tclient.SayHello(ctx, req)\n}\n",
+        ),
+    ];
+    for (path, source) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+    let mut indexer = Indexer::new(root.clone(), root.join(".lidx").join(".lidx.sqlite")).unwrap();
+    indexer.reindex().unwrap();
+
+    let gv = indexer.db().current_graph_version().unwrap();
+    let impls = indexer
+        .db()
+        .list_edges(
+            10,
+            0,
+            None,
+            None,
+            Some(&["RPC_IMPL".to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(impls.len(), 1, "{impls:?}");
+    assert!(impls[0].file_path.ends_with("GreeterImpl.java"));
+    let target = impls[0].target_qualname.as_deref().unwrap();
+    let calls = indexer
+        .db()
+        .edges_by_target_qualname_and_kinds(target, &["RPC_CALL"], None, gv)
+        .unwrap();
+    assert_eq!(calls.len(), 1, "{calls:?}");
+    assert_eq!(calls[0].file_path, "client/main.go");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn javadoc_attaches_only_to_the_next_member() {
+    let source = r#"
+package com.acme;
+
+class Calc {
+    /** Adds. */
+    int add(int a, int b) { return a + b; }
+
+    /* not documentation */
+    int sub(int a, int b) { return a - b; }
+
+    int mul(int a, int b) { return a * b; }
+
+    /** Divides. */
+    @Deprecated(since = "2")
+    int div(int a, int b) { return a / b; }
+}
+"#;
+    let mut extractor = JavaExtractor::new().unwrap();
+    let extracted = extractor.extract(source, "com/acme/Calc").unwrap();
+    let doc = |qualname: &str| {
+        extracted
+            .symbols
+            .iter()
+            .find(|s| s.qualname == qualname)
+            .unwrap()
+            .docstring
+            .clone()
+    };
+    assert_eq!(doc("com.acme.Calc.add").as_deref(), Some("Adds."));
+    assert_eq!(doc("com.acme.Calc.sub"), None);
+    assert_eq!(doc("com.acme.Calc.mul"), None);
+    assert_eq!(doc("com.acme.Calc.div").as_deref(), Some("Divides."));
+}
@@ -0,0 +1,165 @@
+use lidx::indexer::extract::LanguageExtractor;
+use lidx::indexer::kotlin::{KotlinExtractor, module_name_from_rel_path};
+
+#[test]
+fn module_name_from_path() {
+    assert_eq!(
+        module_name_from_rel_path("src/main/kotlin/com/acme/App.kt"),
+        "src/main/kotlin/com/acme/App"
+    );
+    assert_eq!(
+        module_name_from_rel_path("build.gradle.kts"),
+        "build.gradle"
+    );
+}
+
+#[test]
+fn extract_symbols_and_edges() {
+    let source = r#"
+package com.acme.users
+
+import com.acme.core.BaseService
+import com.acme.core.Auditable as Audit
+
+/** A user record. */
+data class User(val id: Long, var name: String)
+
+class UserService(private val repo: Repo) : BaseService(), Audit {
+    val cache = mutableMapOf<Long, User>()
+
+    fun find(id: Long): User? = repo.load(id)
+
+    fun rename(id: Long, name: String) {
+        val user = find(id)
+        helper()
+    }
+
+    private fun helper() {}
+
+    companion object {
+        const val LIMIT = 10
+    }
+}
+
+object Registry
+
+interface Repo {
+    fun load(id: Long): User?
+}
+
+fun String.shout(): String = uppercase()
+
+val DEFAULT_NAME = "nobody"
+"#;
+    let mut extractor = KotlinExtractor::new().unwrap();
+    let extracted = extractor
+        .extract(source, "src/com/acme/users/Users")
+        .unwrap();
+
+    let names: Vec<_> = extracted
+        .symbols
+        .iter()
+        .map(|s| (s.kind.as_str(), s.qualname.as_str()))
+        .collect();
+
+    assert!(names.contains(&("module", "src/com/acme/users/Users")));
+    assert!(names.contains(&("class", "com.acme.users.User")));
+    assert!(names.contains(&("property", "com.acme.users.User.id")));
+    assert!(names.contains(&("property", "com.acme.users.User.name")));
+    assert!(names.contains(&("class", "com.acme.users.UserService")));
+    assert!(names.contains(&("property", "com.acme.users.UserService.repo")));
+    assert!(names.contains(&("property", "com.acme.users.UserService.cache")));
+    assert!(names.contains(&("method", "com.acme.users.UserService.find")));
+    assert!(names.contains(&("method", "com.acme.users.UserService.rename")));
+    assert!(names.contains(&("class", "com.acme.users.UserService.Companion")));
+    assert!(names.contains(&("class", "com.acme.users.Registry")));
+    assert!(names.contains(&("interface", "com.acme.users.Repo")));
+    assert!(names.contains(&("method", "com.acme.users.Repo.load")));
+    assert!(names.contains(&("function", "com.acme.users.shout")));
+    assert!(names.contains(&("variable", "com.acme.users.DEFAULT_NAME")));
+
+    let user = extracted
+        .symbols
+        .iter()
+        .find(|s| s.qualname == "com.acme.users.User")
+        .unwrap();
+    assert_eq!(user.docstring.as_deref(), Some("A user record."));
+
+    let has_edge = |kind: &str, target: &str| {
+        extracted
+            .edges
+            .iter()
+            .any(|e| e.kind == kind && e.target_qualname.as_deref() == Some(target))
+    };
+    assert!(has_edge("IMPORTS", "com.acme.core.BaseService"));
+    assert!(has_edge("EXTENDS", "com.acme.core.BaseService"));
+    assert!(has_edge("IMPLEMENTS", "com.acme.core.Auditable"));
+    assert!(has_edge("CONTAINS", "com.acme.users.UserService.find"));
+    assert!(has_edge("CALLS", "com.acme.users.UserService.helper"));
+    assert!(has_edge("CALLS", "com.acme.users.UserService.find"));
+}
+
+#[test]
+fn extract_spring_routes() {
+    let source = r#"
+package com.acme.web
+
+@RestController
+@RequestMapping("/api/orders")
+class OrderController(private val service: OrderService) {
+    @PostMapping
+    fun create(@RequestBody order: Order): Order = service.save(order)
+
+    @GetMapping("/{id}")
+    fun get(@PathVariable id: Long): Order? {
+        return service.find(id)
+    }
+}
+"#;
+    let mut extractor = KotlinExtractor::new().unwrap();
+    let extracted = extractor.extract(source, "web/OrderController").unwrap();
+
+    let routes: Vec<_> = extracted
+        .edges
+        .iter()
+        .filter(|e| e.kind == "HTTP_ROUTE")
+        .collect();
+    assert!(
+        routes.iter().any(|e| {
+            e.source_qualname.as_deref() == Some("com.acme.web.OrderController.create")
+                && e.detail
+                    .as_deref()
+                    .is_some_and(|d| d.contains("\"POST\"") && d.contains("/api/orders"))
+        }),
+        "routes: {routes:?}"
+    );
+    assert!(routes.iter().any(|e| {
+        e.source_qualname.as_deref() == Some("com.acme.web.OrderController.get")
+            && e.detail
+                .as_deref()
+                .is_some_and(|d| d.contains("\"GET\"") && d.contains("/api/orders/{id}"))
+    }));
+}
+
+#[test]
+fn extract_grpc_coroutine_impl() {
+    let source = r#"
+package com.acme.greet
+
+class GreeterService : GreeterGrpcKt.GreeterCoroutineImplBase() {
+    override suspend fun sayHello(request: HelloRequest): HelloReply {
+        return HelloReply.getDefaultInstance()
+    }
+}
+"#;
+    let mut extractor = KotlinExtractor::new().unwrap();
+    let extracted = extractor.extract(source, "greet/GreeterService").unwrap();
+
+    assert!(extracted.edges.iter().any(|e| {
+        e.kind == "RPC_IMPL"
+            && e.source_qualname.as_deref() == Some("com.acme.greet.GreeterService.sayHello")
+            && e.detail
+                .as_deref()
+                .is_some_and(|d| d.contains("grpc-kotlin"))
+    }));
+}
@@ -0,0 +1,204 @@
+use lidx::indexer::Indexer;
+use lidx::lsp::{self, Server};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_repo_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-lsp-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+const SERVER_PY: &str = r#"from fastapi import FastAPI
+app = FastAPI()
+
+@app.get("/api/users/{id}")
+def get_user(id):
+    return lookup(id)
+
+def lookup(id):
+    return id
+"#;
+
+const CLIENT_PY: &str = r#"import requests
+from server import lookup
+
+def fetch_user(id):
+    return requests.get("/api/users/7")
+
+def local(id):
+    return lookup(id)
+"#;
+
+fn setup(label: &str) -> (PathBuf, Server) {
+    let repo_root = temp_repo_dir(label);
+    std::fs::write(repo_root.join("server.py"), SERVER_PY).unwrap();
+    std::fs::write(repo_root.join("client.py"), CLIENT_PY).unwrap();
+    let db_path = repo_root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(repo_root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    let mut server = Server::new(indexer);
+    let init = request(&mut server, "initialize", json!({ "capabilities": {} }));
+    assert_eq!(init["capabilities"]["callHierarchyProvider"], true);
+    (repo_root, server)
+}
+
+fn request(server: &mut Server, method: &str, params: Value) -> Value {
+    let response = server
+        .handle_message(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+        .expect("response");
+    assert!(response.get("error").is_none(), "{method}: {response}");
+    response["result"].clone()
+}
+
+fn uri(repo_root: &Path, rel: &str) -> String {
+    lsp::uri_from_path(&repo_root.join(rel))
+}
+
+fn position(repo_root: &Path, rel: &str, line: u32, character: u32) -> Value {
+    json!({
+        "textDocument": { "uri": uri(repo_root, rel) },
+        "position": { "line": line, "character": character },
+    })
+}
+
+#[test]
+fn definition_references_and_symbols() {
+    let (repo_root, mut server) = setup("nav");
+
+    // `lookup(id)` inside `local` in client.py jumps to server.py.
+    let defs = request(
+        &mut server,
+        "textDocument/definition",
+        position(&repo_root, "client.py", 7, 12),
+    );
+    assert_eq!(defs[0]["uri"], uri(&repo_root, "server.py"));
+    assert_eq!(defs[0]["range"]["start"]["line"], 7);
+    assert_eq!(defs[0]["range"]["start"]["character"], 4);
+
+    // The HTTP call jumps across the route to its handler.
+    let defs = request(
+        &mut server,
+        "textDocument/definition",
+        position(&repo_root, "client.py", 4, 21),
+    );
+    let targets: Vec<&Value> = defs.as_array().unwrap().iter().collect();
+    assert!(
+        targets
+            .iter()
+            .any(|loc| loc["uri"] == uri(&repo_root, "server.py")
+                && loc["range"]["start"]["line"] == 4),
+        "{defs}"
+    );
+
+    let mut params = position(&repo_root, "server.py", 7, 5);
+    params["context"] = json!({ "includeDeclaration": true });
+    let refs = request(&mut server, "textDocument/references", params);
+    let lines: Vec<(String, u64)> = refs
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|loc| {
+            (
+                loc["uri"]
+                    .as_str()
+                    .unwrap()
+                    .rsplit('/')
+                    .next()
+                    .unwrap()
+                    .to_string(),
+                loc["range"]["start"]["line"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert!(lines.contains(&("server.py".to_string(), 7)), "{lines:?}");
+    assert!(lines.contains(&("server.py".to_string(), 5)), "{lines:?}");
+    assert!(lines.contains(&("client.py".to_string(), 7)), "{lines:?}");
+
+    let symbols = request(
+        &mut server,
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": uri(&repo_root, "server.py") } }),
+    );
+    let names: Vec<&str> = symbols
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|symbol| symbol["name"].as_str().unwrap())
+        .collect();
+    assert!(
+        names.contains(&"get_user") && names.contains(&"lookup"),
+        "{names:?}"
+    );
+
+    let found = request(
+        &mut server,
+        "workspace/symbol",
+        json!({ "query": "fetch_user" }),
+    );
+    assert_eq!(found[0]["name"], "fetch_user");
+    assert_eq!(found[0]["location"]["uri"], uri(&repo_root, "client.py"));
+
+    let _ = std::fs::remove_dir_all(&repo_root);
+}
+
+#[test]
+fn call_hierarchy_follows_calls_and_http_hops() {
+    let (repo_root, mut server) = setup("calls");
+
+    let items = request(
+        &mut server,
+        "textDocument/prepareCallHierarchy",
+        position(&repo_root, "server.py", 4, 6),
+    );
+    let get_user = items[0].clone();
+    assert_eq!(get_user["name"], "get_user");
+
+    let outgoing = request(
+        &mut server,
+        "callHierarchy/outgoingCalls",
+        json!({ "item": get_user }),
+    );
+    assert!(
+        outgoing
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|call| call["to"]["name"] == "lookup"
+                && call["fromRanges"][0]["start"]["line"] == 5),
+        "{outgoing}"
+    );
+
+    let incoming = request(
+        &mut server,
+        "callHierarchy/incomingCalls",
+        json!({ "item": get_user }),
+    );
+    assert!(
+        incoming
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|call| call["from"]["name"] == "fetch_user"),
+        "{incoming}"
+    );
+
+    assert!(request(&mut server, "shutdown", Value::Null).is_null());
+    assert!(
+        server
+            .handle_message(&json!({ "method": "exit" }))
+            .is_none()
+    );
+    assert!(server.exited());
+
+    let _ = std::fs::remove_dir_all(&repo_root);
+}
@@ -0,0 +1,133 @@
+use lidx::indexer::extract::LanguageExtractor;
+use lidx::indexer::lua::{LuaExtractor, module_name_from_rel_path};
+
+#[test]
+fn module_name_from_path() {
+    assert_eq!(
+        module_name_from_rel_path("lua/plugin/util.lua"),
+        "lua/plugin/util"
+    );
+    assert_eq!(
+        module_name_from_rel_path("lua/plugin/init.lua"),
+        "lua/plugin"
+    );
+    assert_eq!(module_name_from_rel_path("main.lua"), "main");
+}
+
+#[test]
+fn extract_symbols_and_edges() {
+    let source = r#"
+--- Utility helpers.
+local strings = require("plugin.strings")
+local log = require "plugin.log"
+
+local M = {}
+
+--- Trims both ends.
+-- @param s string
+function M.trim(s)
+  return strings.strip(s)
+end
+
+function M:greet(name)
+  local msg = self:format(name)
+  log.info(msg)
+end
+
+M.format = function(self, name)
+  return "hi " .. name
+end
+
+local function helper()
+  for i = 1, 3 do
+    M.trim(" x ")
+  end
+end
+
+local handlers = {
+  on_save = function(buf)
+    helper()
+  end,
+}
+
+return M
+"#;
+    let mut extractor = LuaExtractor::new().unwrap();
+    let extracted = extractor.extract(source, "lua/plugin/util").unwrap();
+
+    let names: Vec<_> = extracted
+        .symbols
+        .iter()
+        .map(|s| (s.kind.as_str(), s.qualname.as_str()))
+        .collect();
+    assert!(names.contains(&("module", "lua/plugin/util")));
+    assert!(names.contains(&("method", "lua/plugin/util.M.trim")));
+    assert!(names.contains(&("method", "lua/plugin/util.M.greet")));
+    assert!(names.contains(&("method", "lua/plugin/util.M.format")));
+    assert!(names.contains(&("function", "lua/plugin/util.helper")));
+    assert!(names.contains(&("method", "lua/plugin/util.handlers.on_save")));
+
+    let trim = extracted
+        .symbols
+        .iter()
+        .find(|s| s.qualname == "lua/plugin/util.M.trim")
+        .unwrap();
+    assert_eq!(trim.docstring.as_deref(), Some("Trims both ends."));
+    assert_eq!(trim.signature.as_deref(), Some("(s)"));
+    assert_eq!(trim.end_line, trim.start_line + 2);
+
+    let imports: Vec<_> = extracted
+        .edges
+        .iter()
+        .filter(|e| e.kind == "IMPORTS")
+        .filter_map(|e| e.target_qualname.as_deref())
+        .collect();
+    assert_eq!(imports, vec!["plugin.strings", "plugin.log"]);
+
+    let calls: Vec<_> = extracted
+        .edges
+        .iter()
+        .filter(|e| e.kind == "CALLS")
+        .map(|e| {
+            (
+                e.source_qualname.as_deref().unwrap_or(""),
+                e.target_qualname.as_deref(),
+            )
+        })
+        .collect();
+    assert!(calls.contains(&("lua/plugin/util.M.greet", Some("lua/plugin/util.M.format"))));
+    assert!(calls.contains(&("lua/plugin/util.helper", Some("lua/plugin/util.M.trim"))));
+    assert!(calls.contains(&(
+        "lua/plugin/util.handlers.on_save",
+        Some("lua/plugin/util.helper")
+    )));
+    assert!(calls.contains(&("lua/plugin/util.M.trim", Some("strings.strip"))));
+}
+
+#[test]
+fn resolve_require_to_file() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("lua/plugin/strings")).unwrap();
+    std::fs::write(dir.path().join("lua/plugin/strings/init.lua"), "return {}").unwrap();
+
+    let source = "local strings = require('plugin.strings')\nlocal json = require('cjson')\n";
+    let mut extractor = LuaExtractor::new().unwrap();
+    let mut extracted = extractor.extract(source, "lua/plugin/util").unwrap();
+    extractor.resolve_imports(
+        dir.path(),
+        "lua/plugin/util.lua",
+        "lua/plugin/util",
+        &mut extracted.edges,
+    );
+
+    let files: Vec<_> = extracted
+        .edges
+        .iter()
+        .filter(|e| e.kind == "IMPORTS_FILE")
+        .collect();
+    assert_eq!(files.len(), 1);
+    assert_eq!(
+        files[0].target_qualname.as_deref(),
+        Some("lua/plugin/strings")
+    );
+}
@@ -0,0 +1,198 @@
+use lidx::indexer::extract::LanguageExtractor;
+use lidx::indexer::markdown::{MarkdownExtractor, heading_slug, module_name_from_rel_path};
+
+#[test]
+fn module_name_from_path() {
+    assert_eq!(module_name_from_rel_path("README.md"), "README");
+    assert_eq!(module_name_from_rel_path("docs/guide.md"), "docs/guide");
+}
+
+#[test]
+fn heading_slugs_match_github_anchors() {
+    assert_eq!(heading_slug("Getting Started"), "getting-started");
+    assert_eq!(heading_slug("What's new in v0.4?"), "whats-new-in-v04");
+    assert_eq!(heading_slug("snake_case API"), "snake_case-api");
+}
+
+#[test]
+fn extract_sections() {
+    let source = r#"---
+title: Guide
+---
+# User Guide
+
+Intro paragraph
+spanning two lines.
+
+## Install
+
+Run `cargo install lidx`.
+
+```sh
+# not a heading
+cargo build
+```
+
+### From source
+
+Clone it.
+
+Usage
+-----
+
+See [install](#install).
+
+## Install
+"#;
+    let mut extractor = MarkdownExtractor::new().unwrap();
+    let extracted = extractor.extract(source, "docs/guide").unwrap();
+
+    let names: Vec<_> = extracted
+        .symbols
+        .iter()
+        .map(|s| (s.kind.as_str(), s.qualname.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("module", "docs/guide"),
+            ("section", "docs/guide#user-guide"),
+            ("section", "docs/guide#install"),
+            ("section", "docs/guide#from-source"),
+            ("section", "docs/guide#usage"),
+            ("section", "docs/guide#install-1"),
+        ]
+    );
+
+    let module = &extracted.symbols[0];
+    assert_eq!(module.docstring.as_deref(), Some("User Guide"));
+
+    let guide = &extracted.symbols[1];
+    assert_eq!(guide.signature.as_deref(), Some("# User Guide"));
+    assert_eq!(
+        guide.docstring.as_deref(),
+        Some("Intro paragraph spanning two lines.")
+    );
+    assert_eq!(guide.start_line, 4);
+    assert_eq!(guide.end_line, 27);
+
+    let install = &extracted.symbols[2];
+    assert_eq!(install.start_line, 9);
+    assert_eq!(install.end_line, 20);
+
+    let contains: Vec<_> = extracted
+        .edges
+        .iter()
+        .filter(|e| e.kind == "CONTAINS")
+        .map(|e| {
+            (
+                e.source_qualname.as_deref().unwrap(),
+                e.target_qualname.as_deref().unwrap(),
+            )
+        })
+        .collect();
+    assert!(contains.contains(&("docs/guide", "docs/guide#user-guide")));
+    assert!(contains.contains(&("docs/guide#install", "docs/guide#from-source")));
+    assert!(contains.contains(&("docs/guide#user-guide", "docs/guide#usage")));
+
+    assert!(extracted.edges.iter().any(|e| {
+        e.kind == "DOC_REF"
+            && e.source_qualname.as_deref() == Some("docs/guide#usage")
+            && e.target_qualname.as_deref() == Some("docs/guide#install")
+    }));
+}
+
+#[test]
+fn resolve_doc_references_to_indexed_files() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("src/indexer")).unwrap();
+    std::fs::create_dir_all(dir.path().join("docs")).unwrap();
+    std::fs::write(dir.path().join("src/indexer/scan.py"), "").unwrap();
+    std::fs::write(dir.path().join("docs/api.md"), "# API\n").unwrap();
+    std::fs::write(dir.path().join("docs/logo.png"), "").unwrap();
+
+    let source = r#"# Overview
+
+Scanning lives in `src/indexer/scan.py:12`, see the [API](api.md#Endpoints).
+![logo](logo.png) and [missing](nope.md) and [site](https://example.com).
+
+```python title="src/indexer/scan.py"
+print("hi")
+```
+"#;
+    let mut extractor = MarkdownExtractor::new().unwrap();
+    let mut extracted = extractor.extract(source, "docs/overview").unwrap();
+    extractor.resolve_imports(
+        dir.path(),
+        "docs/overview.md",
+        "docs/overview",
+        &mut extracted.edges,
+    );
+
+    let refs: Vec<_> = extracted
+        .edges
+        .iter()
+        .filter(|e| e.kind == "DOC_REF")
+        .collect();
+    let targets: Vec<_> = refs
+        .iter()
+        .filter_map(|e| e.target_qualname.as_deref())
+        .collect();
+    assert_eq!(
+        targets,
+        vec!["docs/api#endpoints", "src.indexer.scan", "src.indexer.scan"]
+    );
+    assert!(
+        refs.iter()
+            .all(|e| e.source_qualname.as_deref() == Some("docs/overview#overview"))
+    );
+    let detail = refs[0].detail.as_deref().unwrap();
+    assert!(detail.contains("\"dst_path\":\"docs/api.md\""));
+    assert!(detail.contains("\"via\":\"link\""));
+}
+
+#[test]
+fn headings_and_references_inside_markup() {
+    let source = r#"# The [`lidx`](https://example.com) *guide* ##
+
+> Quoted `docs/setup.md` and [config](config.md).
+
+- [ ] item linking [api](api.md#auth)
+
+[ref]: <notes.md>
+
+    indented [code](skip.md)
+"#;
+    let mut extractor = MarkdownExtractor::new().unwrap();
+    let extracted = extractor.extract(source, "README").unwrap();
+    let section = &extracted.symbols[1];
+    assert_eq!(section.name, "The lidx guide");
+    assert_eq!(section.qualname, "README#the-lidx-guide");
+    assert_eq!(
+        section.docstring.as_deref(),
+        Some("Quoted `docs/setup.md` and [config](config.md).")
+    );
+
+    let refs: Vec<_> = extracted
+        .edges
+        .iter()
+        .filter(|e| e.kind == "DOC_REF")
+        .map(|e| {
+            let detail: serde_json::Value =
+                serde_json::from_str(e.detail.as_deref().unwrap()).unwrap();
+            (
+                detail["raw"].as_str().unwrap().to_string(),
+                e.evidence_start_line.unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        refs,
+        vec![
+            ("config.md".to_string(), 3),
+            ("docs/setup.md".to_string(), 3),
+            ("api.md#auth".to_string(), 5),
+            ("notes.md".to_string(), 7),
+        ]
+    );
+}
@@ -0,0 +1,351 @@
+use lidx::mcp;
+use lidx::watch::{WatchConfig, WatchMode};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_repo_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-mcp-http-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn start_server(repo_root: &Path, use_daemon: bool) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let repo_root = repo_root.to_path_buf();
+    let db_path = repo_root.join(".lidx").join(".lidx.sqlite");
+    let watch = WatchConfig {
+        mode: WatchMode::Off,
+        ..WatchConfig::default()
+    };
+    std::thread::spawn(move || mcp::serve_http(listener, repo_root, db_path, watch, use_daemon));
+    addr
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Response {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap()
+    }
+
+    /// `data:` payloads of the SSE events in the body.
+    fn events(&self) -> Vec<Value> {
+        self.body
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect()
+    }
+}
+
+fn read_head(reader: &mut BufReader<TcpStream>) -> (u16, Vec<(String, String)>) {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    let status = line.split_whitespace().nth(1).unwrap().parse().unwrap();
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':').unwrap();
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+    (status, headers)
+}
+
+fn send(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> Response {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\nContent-Length: {}\r\n",
+        body.len()
+    )
+    .unwrap();
+    for (name, value) in headers {
+        write!(stream, "{name}: {value}\r\n").unwrap();
+    }
+    write!(stream, "\r\n{body}").unwrap();
+    let mut reader = BufReader::new(stream);
+    let (status, headers) = read_head(&mut reader);
+    let mut body = String::new();
+    reader.read_to_string(&mut body).unwrap();
+    Response {
+        status,
+        headers,
+        body,
+    }
+}
+
+fn post_mcp(addr: SocketAddr, session: &str, accept: &str, message: Value) -> Response {
+    send(
+        addr,
+        "POST",
+        "/mcp",
+        &[
+            ("Content-Type", "application/json"),
+            ("Accept", accept),
+            ("Mcp-Session-Id", session),
+        ],
+        &message.to_string(),
+    )
+}
+
+fn initialize(addr: SocketAddr) -> String {
+    let init = send(
+        addr,
+        "POST",
+        "/mcp",
+        &[("Content-Type", "application/json")],
+        &json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"protocolVersion": "2025-03-26"}})
+            .to_string(),
+    );
+    assert_eq!(init.status, 200, "{}", init.body);
+    assert_eq!(init.json()["result"]["serverInfo"]["name"], "lidx");
+    let session = init
+        .header("Mcp-Session-Id")
+        .expect("session id")
+        .to_string();
+
+    let initialized = post_mcp(
+        addr,
+        &session,
+        "application/json",
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+    );
+    assert_eq!(initialized.status, 202);
+    session
+}
+
+/// Streams a `reindex` call asking for progress: its result and the
+/// notifications sent before it.
+fn reindex_with_progress(addr: SocketAddr, session: &str) -> (Value, Vec<Value>) {
+    let reindex = post_mcp(
+        addr,
+        session,
+        "application/json, text/event-stream",
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {"name": "reindex", "arguments": {}, "_meta": {"progressToken": "tok"}}
+        }),
+    );
+    assert_eq!(reindex.status, 200);
+    assert_eq!(reindex.header("Content-Type"), Some("text/event-stream"));
+    let mut events = reindex.events();
+    let result = events.pop().expect("events");
+    (result, events)
+}
+
+fn assert_progress(progress: &[Value]) {
+    assert!(!progress.is_empty(), "no progress notifications");
+    let mut last = 0;
+    for event in progress {
+        assert_eq!(event["method"], "notifications/progress");
+        assert_eq!(event["params"]["progressToken"], "tok");
+        let done = event["params"]["progress"].as_u64().unwrap();
+        assert!(done >= last);
+        last = done;
+    }
+    assert_eq!(last, progress[0]["params"]["total"].as_u64().unwrap());
+}
+
+#[test]
+fn streamable_http_serves_tools_and_streams_reindex_progress() {
+    let repo_root = temp_repo_dir("streamable");
+    std::fs::write(repo_root.join("app.py"), "def greet():\n    return 'hi'\n").unwrap();
+    std::fs::write(repo_root.join("util.py"), "def helper():\n    return 1\n").unwrap();
+    let addr = start_server(&repo_root, false);
+
+    let session = initialize(addr);
+    let (result, progress) = reindex_with_progress(addr, &session);
+    assert_eq!(result["id"], 2);
+    assert!(
+        result["result"]["structuredContent"]["indexed"]
+            .as_u64()
+            .unwrap()
+            >= 2
+    );
+    assert_progress(&progress);
+
+    // Same shared state: the index built above answers a plain JSON call.
+    let batch = post_mcp(
+        addr,
+        &session,
+        "application/json",
+        json!([
+            {"jsonrpc": "2.0", "id": 3, "method": "tools/call",
+             "params": {"name": "explain_symbol", "arguments": {"qualname": "app.greet"}}},
+            {"jsonrpc": "2.0", "id": 4, "method": "ping"}
+        ]),
+    );
+    assert_eq!(batch.status, 200);
+    let responses = batch.json();
+    assert_eq!(responses.as_array().unwrap().len(), 2);
+    assert!(responses[0].to_string().contains("app.greet"));
+    assert_eq!(responses[1]["id"], 4);
+
+    let unknown = post_mcp(
+        addr,
+        "no-such-session",
+        "application/json",
+        json!({"jsonrpc": "2.0", "id": 5, "method": "ping"}),
+    );
+    assert_eq!(unknown.status, 404);
+    let foreign = send(
+        addr,
+        "POST",
+        "/mcp",
+        &[("Origin", "https://evil.example")],
+        &json!({"jsonrpc": "2.0", "id": 6, "method": "ping"}).to_string(),
+    );
+    assert_eq!(foreign.status, 403);
+    assert_eq!(send(addr, "GET", "/mcp", &[], "").status, 405);
+
+    let closed = send(addr, "DELETE", "/mcp", &[("Mcp-Session-Id", &session)], "");
+    assert_eq!(closed.status, 200);
+    let after = post_mcp(
+        addr,
+        &session,
+        "application/json",
+        json!({"jsonrpc": "2.0", "id": 7, "method": "ping"}),
+    );
+    assert_eq!(after.status, 404);
+
+    let _ = std::fs::remove_dir_all(&repo_root);
+}
+
+#[test]
+fn legacy_sse_delivers_responses_on_the_event_stream() {
+    let repo_root = temp_repo_dir("legacy");
+    std::fs::write(repo_root.join("app.py"), "def greet():\n    return 'hi'\n").unwrap();
+    let addr = start_server(&repo_root, false);
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "GET /sse HTTP/1.1\r\nHost: {addr}\r\nAccept: text/event-stream\r\n\r\n"
+    )
+    .unwrap();
+    let mut sse = BufReader::new(stream);
+    let (status, _) = read_head(&mut sse);
+    assert_eq!(status, 200);
+    let mut next_event = || {
+        let mut event = String::new();
+        let mut data = String::new();
+        loop {
+            let mut line = String::new();
+            sse.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if let Some(name) = line.strip_prefix("event: ") {
+                event = name.to_string();
+            } else if let Some(payload) = line.strip_prefix("data: ") {
+                data = payload.to_string();
+            } else if line.is_empty() && !event.is_empty() {
+                return (event, data);
+            }
+        }
+    };
+
+    let (event, endpoint) = next_event();
+    assert_eq!(event, "endpoint");
+    assert!(endpoint.starts_with("/messages?sessionId="), "{endpoint}");
+
+    let accepted = send(
+        addr,
+        "POST",
+        &endpoint,
+        &[("Content-Type", "application/json")],
+        &json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}).to_string(),
+    );
+    assert_eq!(accepted.status, 202);
+    let (event, data) = next_event();
+    assert_eq!(event, "message");
+    let response: Value = serde_json::from_str(&data).unwrap();
+    assert_eq!(response["id"], 1);
+    assert!(!response["result"]["tools"].as_array().unwrap().is_empty());
+
+    let missing = send(
+        addr,
+        "POST",
+        "/messages?sessionId=unknown",
+        &[],
+        &json!({"jsonrpc": "2.0", "id": 2, "method": "ping"}).to_string(),
+    );
+    assert_eq!(missing.status, 404);
+
+    let _ = std::fs::remove_dir_all(&repo_root);
+}
+
+/// The default `mcp-serve --http` setup forwards calls to the repo daemon,
+/// which relays reindex progress back over its socket.
+#[cfg(unix)]
+#[test]
+fn daemon_mode_streams_reindex_progress() {
+    use lidx::daemon::{self, DaemonConfig};
+
+    let repo_root = temp_repo_dir("daemon");
+    std::fs::write(repo_root.join("app.py"), "def greet():\n    return 'hi'\n").unwrap();
+    std::fs::write(repo_root.join("util.py"), "def helper():\n    return 1\n").unwrap();
+    let db_path = repo_root.join(".lidx").join(".lidx.sqlite");
+    let watch = WatchConfig {
+        mode: WatchMode::Off,
+        ..WatchConfig::default()
+    };
+    {
+        let repo_root = repo_root.clone();
+        let db_path = db_path.clone();
+        std::thread::spawn(move || daemon::serve(repo_root, db_path, DaemonConfig::new(watch, 0)));
+    }
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while daemon::Client::connect(&db_path).unwrap().is_none() {
+        assert!(std::time::Instant::now() < deadline, "daemon did not start");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    let addr = start_server(&repo_root, true);
+    let session = initialize(addr);
+    let (result, progress) = reindex_with_progress(addr, &session);
+    assert_eq!(result["id"], 2);
+    assert!(
+        result["result"]["structuredContent"]["indexed"]
+            .as_u64()
+            .unwrap()
+            >= 2
+    );
+    assert_progress(&progress);
+
+    let _ = std::fs::remove_dir_all(&repo_root);
+}
@@ -1,4 +1,5 @@
 use lidx::indexer::Indexer;
+use lidx::indexer::scan::ScanOptions;
 use lidx::{rpc, subgraph};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -46,6 +47,41 @@ fn setup_repo(fixture: &str) -> (PathBuf, PathBuf) {
     (repo_root, db_path)
 }
 
+#[test]
+fn rpc_call_honours_no_ignore() {
+    let (repo_root, db_path) = setup_repo("py_mvp");
+    std::fs::write(repo_root.join(".gitignore"), "vendor/\n").unwrap();
+    std::fs::create_dir_all(repo_root.join("vendor")).unwrap();
+    std::fs::write(
+        repo_root.join("vendor/lib.py"),
+        "def vendored():\n    return 1\n",
+    )
+    .unwrap();
+
+    let indexed = |no_ignore: bool| {
+        let _ = std::fs::remove_dir_all(repo_root.join(".lidx"));
+        rpc::call_with_options(
+            repo_root.clone(),
+            db_path.clone(),
+            ScanOptions::new(no_ignore),
+            "reindex".to_string(),
+            "{}",
+            "1",
+        )
+        .unwrap();
+        let indexer = Indexer::new(repo_root.clone(), db_path.clone()).unwrap();
+        indexer
+            .db()
+            .get_file_by_path("vendor/lib.py")
+            .unwrap()
+            .is_some()
+    };
+    assert!(!indexed(false));
+    assert!(indexed(true));
+
+    let _ = std::fs::remove_dir_all(&repo_root);
+}
+
 #[test]
 fn python_indexing_is_deterministic() {
     let (repo_root_a, db_path_a) = setup_repo("py_mvp");
@@ -243,6 +279,47 @@ fn rpc_search_rg_returns_regex_matches() {
     let _ = std::fs::remove_dir_all(&repo_root);
 }
 
+#[test]
+fn rpc_search_serves_text_queries_from_index() {
+    let (repo_root, db_path) = setup_repo("py_mvp");
+    let mut indexer = Indexer::new(repo_root.clone(), db_path.clone()).unwrap();
+    indexer.reindex().unwrap();
+
+    let first_version = indexer.db().current_graph_version().unwrap();
+    std::fs::write(
+        repo_root.join("pkg/late.py"),
+        "def greet_late():\n    pass\n",
+    )
+    .unwrap();
+    indexer.reindex().unwrap();
+
+    let search = |params: String| {
+        let response = rpc::call(
+            repo_root.clone(),
+            db_path.clone(),
+            "search".to_string(),
+            &params,
+            "1",
+        )
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&response).unwrap();
+        value["result"].as_array().unwrap().clone()
+    };
+    let hits = search(r#"{"query":"greet","limit":50}"#.to_string());
+    assert!(hits.iter().any(|hit| hit["path"] == "pkg/core.py"));
+    assert!(hits.iter().any(|hit| hit["path"] == "pkg/late.py"));
+    // Ripgrep hits are unranked; BM25 scores mean the FTS index answered.
+    assert!(hits.iter().all(|hit| hit["score"].is_number()));
+
+    let hits = search(format!(
+        r#"{{"query":"greet","limit":50,"graph_version":{first_version}}}"#
+    ));
+    assert!(hits.iter().any(|hit| hit["path"] == "pkg/core.py"));
+    assert!(hits.iter().all(|hit| hit["path"] != "pkg/late.py"));
+
+    let _ = std::fs::remove_dir_all(&repo_root);
+}
+
 #[test]
 fn rpc_top_complexity_respects_paths() {
     let (repo_root, db_path) = setup_repo("py_mvp");
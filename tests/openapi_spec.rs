@@ -0,0 +1,268 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-openapi-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str, files: &[(&str, &str)]) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+const OPENAPI_YAML: &str = r##"openapi: 3.0.3
+info:
+  title: Users
+servers:
+  - url: https://users.example.com/api
+paths:
+  /users/{id}:
+    get:
+      operationId: getUserById
+      summary: Fetch one user
+      responses:
+        "200":
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/User"
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        id:
+          type: string
+        orders:
+          type: array
+          items:
+            $ref: "#/components/schemas/Order"
+    Order:
+      type: object
+"##;
+
+const SWAGGER_JSON: &str = r##"{
+  "swagger": "2.0",
+  "basePath": "/legacy",
+  "paths": {
+    "/orders": {
+      "post": {
+        "operationId": "createOrder",
+        "parameters": [
+          { "in": "body", "name": "body", "schema": { "$ref": "#/definitions/Order" } }
+        ]
+      }
+    }
+  },
+  "definitions": {
+    "Order": {
+      "type": "object"
+    }
+  }
+}
+"##;
+
+const CLIENT_TS: &str = r#"export class UsersApi {
+  async getUserById(id: string): Promise<User> {
+    return this.request({ path: "/users/{id}".replace("{id}", id), method: "GET" });
+  }
+}
+"#;
+
+const APP_TS: &str = r#"import { UsersApi } from "./gen/UsersApi";
+
+export async function showUser(api: UsersApi) {
+  return api.getUserById("1");
+}
+"#;
+
+#[test]
+fn spec_operations_and_schemas_are_symbols() {
+    let (_root, indexer) = index(
+        "symbols",
+        &[
+            ("api/openapi.yaml", OPENAPI_YAML),
+            ("api/legacy-swagger.json", SWAGGER_JSON),
+            // Not an API spec by name: JSON is not indexed.
+            ("package.json", r#"{"name": "app"}"#),
+        ],
+    );
+    let db = indexer.db();
+    let gv = db.current_graph_version().unwrap();
+    let symbol = |qualname: &str| {
+        db.get_symbol_by_qualname(qualname, gv)
+            .unwrap()
+            .unwrap_or_else(|| panic!("missing {qualname}"))
+    };
+    let get = symbol("api/openapi#getUserById");
+    assert_eq!(get.kind, "route");
+    assert_eq!(get.signature.as_deref(), Some("GET /api/users/{id}"));
+    assert_eq!(get.docstring.as_deref(), Some("Fetch one user"));
+    assert_eq!(get.start_line, 8);
+    assert_eq!(
+        symbol("api/openapi#/components/schemas/User").kind,
+        "schema"
+    );
+    assert_eq!(
+        symbol("api/openapi#/components/schemas/User.orders")
+            .signature
+            .as_deref(),
+        Some("orders: array of Order")
+    );
+    assert_eq!(
+        symbol("api/legacy-swagger#createOrder")
+            .signature
+            .as_deref(),
+        Some("POST /legacy/orders")
+    );
+    assert!(db.get_file_by_path("package.json").unwrap().is_none());
+
+    let edges = db
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&["TYPE_REF".to_string(), "HTTP_ROUTE".to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let name = |id: Option<i64>| {
+        id.and_then(|id| db.get_symbol_by_id(id).unwrap())
+            .map(|symbol| symbol.qualname)
+            .unwrap_or_default()
+    };
+    let rows: Vec<(String, String, String)> = edges
+        .iter()
+        .map(|edge| {
+            (
+                edge.kind.clone(),
+                name(edge.source_symbol_id),
+                edge.target_qualname.clone().unwrap_or_default(),
+            )
+        })
+        .collect();
+    for (kind, source, target) in [
+        ("HTTP_ROUTE", "api/openapi#getUserById", "/api/users/{}"),
+        (
+            "HTTP_ROUTE",
+            "api/legacy-swagger#createOrder",
+            "/legacy/orders",
+        ),
+        (
+            "TYPE_REF",
+            "api/openapi#getUserById",
+            "api/openapi#/components/schemas/User",
+        ),
+        (
+            "TYPE_REF",
+            "api/openapi#/components/schemas/User.orders",
+            "api/openapi#/components/schemas/Order",
+        ),
+        (
+            "TYPE_REF",
+            "api/legacy-swagger#createOrder",
+            "api/legacy-swagger#/definitions/Order",
+        ),
+    ] {
+        let expected = (kind.to_string(), source.to_string(), target.to_string());
+        assert!(rows.contains(&expected), "missing {expected:?} in {rows:?}");
+    }
+}
+
+#[test]
+fn generated_client_call_traces_to_spec_route() {
+    let (_root, mut indexer) = index(
+        "client",
+        &[
+            ("api/openapi.yaml", OPENAPI_YAML),
+            ("src/gen/UsersApi.ts", CLIENT_TS),
+            ("src/app.ts", APP_TS),
+        ],
+    );
+    let trace = rpc::handle_method(
+        &mut indexer,
+        "trace_flow",
+        json!({
+            "start_qualname": "src/app.showUser",
+            "include_snippets": false,
+        }),
+    )
+    .unwrap();
+    let hops: Vec<(&str, &str)> = trace["trace"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hop: &Value| {
+            (
+                hop["symbol"]["qualname"].as_str().unwrap(),
+                hop["edge_kind"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert!(
+        hops.contains(&("src/gen/UsersApi.UsersApi.getUserById", "CALLS")),
+        "{hops:?}"
+    );
+    assert!(
+        hops.contains(&("api/openapi#getUserById", "HTTP_ROUTE")),
+        "{hops:?}"
+    );
+
+    // Reindexing does not duplicate the derived client edge.
+    indexer.reindex().unwrap();
+    let gv = indexer.db().current_graph_version().unwrap();
+    let calls = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&["HTTP_CALL".to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let client_calls: Vec<_> = calls
+        .iter()
+        .filter(|edge| edge.target_qualname.as_deref() == Some("/api/users/{}"))
+        .collect();
+    assert_eq!(client_calls.len(), 1);
+}
@@ -0,0 +1,302 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-overrides-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str, files: &[(&str, &str)]) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in files {
+        std::fs::write(root.join(path), source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+fn member_links(indexer: &Indexer, kind: &str) -> Vec<(String, String)> {
+    member_link_edges(indexer, kind)
+        .into_iter()
+        .map(|(_, member, base)| (member, base))
+        .collect()
+}
+
+/// Resolved `kind` edges as `(edge id, member, base)`, sorted by member.
+fn member_link_edges(indexer: &Indexer, kind: &str) -> Vec<(i64, String, String)> {
+    let gv = indexer.db().current_graph_version().unwrap();
+    let edges = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&[kind.to_string()]),
+            None,
+            None,
+            None,
+            true,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let qualname = |id: Option<i64>| {
+        indexer
+            .db()
+            .get_symbol_by_id(id.unwrap())
+            .unwrap()
+            .unwrap()
+            .qualname
+    };
+    let mut links: Vec<(i64, String, String)> = edges
+        .iter()
+        .map(|edge| {
+            (
+                edge.id,
+                qualname(edge.source_symbol_id),
+                qualname(edge.target_symbol_id),
+            )
+        })
+        .collect();
+    links.sort_by(|a, b| (&a.1, &a.2).cmp(&(&b.1, &b.2)));
+    links
+}
+
+fn affected(indexer: &mut Indexer, qualname: &str, direction: &str) -> Vec<String> {
+    let result = rpc::handle_method(
+        indexer,
+        "analyze_impact",
+        json!({
+            "qualname": qualname,
+            "direction": direction,
+            "max_depth": 3,
+            "enable_historical": false,
+        }),
+    )
+    .unwrap();
+    result["affected"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["symbol"]["qualname"].as_str().unwrap().to_string())
+        .collect()
+}
+
+fn pair(a: &str, b: &str) -> (String, String) {
+    (a.to_string(), b.to_string())
+}
+
+const REPO_CS: &str = r#"namespace Acme
+{
+    public interface IRepository { void Save(int x); }
+    public abstract class BaseRepo : IRepository { public virtual void Save(int x) {} }
+    public class SqlRepository : BaseRepo { public override void Save(int x) {} }
+    public class MemoryRepository : IRepository { public void Save(int x) {} }
+
+    public class Controller
+    {
+        private readonly IRepository _repo;
+        public Controller(IRepository repo) { _repo = repo; }
+        public void Run() { _repo.Save(1); }
+    }
+}
+"#;
+
+#[test]
+fn csharp_members_link_to_base_and_interface_members() {
+    let (root, indexer) = index("csharp", &[("Repo.cs", REPO_CS)]);
+
+    assert_eq!(
+        member_links(&indexer, "OVERRIDES"),
+        vec![pair("Acme.SqlRepository.Save", "Acme.BaseRepo.Save")]
+    );
+    assert_eq!(
+        member_links(&indexer, "IMPLEMENTS_MEMBER"),
+        vec![
+            pair("Acme.BaseRepo.Save", "Acme.IRepository.Save"),
+            pair("Acme.MemoryRepository.Save", "Acme.IRepository.Save"),
+        ]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn incremental_sync_relinks_only_the_touched_hierarchy() {
+    let (root, mut indexer) = index(
+        "incremental",
+        &[
+            ("Repo.cs", REPO_CS),
+            (
+                "Animal.cs",
+                "namespace Zoo { public class Animal { public virtual void Speak() {} } }\n",
+            ),
+            (
+                "Dog.cs",
+                "namespace Zoo { public class Dog : Animal { public override void Speak() {} } }\n",
+            ),
+        ],
+    );
+    let repo_edges = |indexer: &Indexer| {
+        let mut edges = member_link_edges(indexer, "OVERRIDES");
+        edges.extend(member_link_edges(indexer, "IMPLEMENTS_MEMBER"));
+        edges.retain(|(_, member, _)| member.starts_with("Acme."));
+        edges
+    };
+    let before = repo_edges(&indexer);
+    assert_eq!(before.len(), 3);
+
+    // Dog's OVERRIDES edge lives with Dog.cs but depends on Animal.cs.
+    std::fs::write(
+        root.join("Animal.cs"),
+        "namespace Zoo { public class Animal { public virtual void Speak() {} public virtual void Eat() {} } }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("Dog.cs"),
+        "namespace Zoo { public class Dog : Animal { public override void Speak() {} public override void Eat() {} } }\n",
+    )
+    .unwrap();
+    indexer.sync_rel_paths(&["Animal.cs".to_string()]).unwrap();
+    assert_eq!(
+        member_links(&indexer, "OVERRIDES")
+            .into_iter()
+            .filter(|(member, _)| member.starts_with("Zoo."))
+            .collect::<Vec<_>>(),
+        vec![pair("Zoo.Dog.Speak", "Zoo.Animal.Speak")]
+    );
+    indexer.sync_rel_paths(&["Dog.cs".to_string()]).unwrap();
+    assert_eq!(
+        member_links(&indexer, "OVERRIDES")
+            .into_iter()
+            .filter(|(member, _)| member.starts_with("Zoo."))
+            .collect::<Vec<_>>(),
+        vec![
+            pair("Zoo.Dog.Eat", "Zoo.Animal.Eat"),
+            pair("Zoo.Dog.Speak", "Zoo.Animal.Speak"),
+        ]
+    );
+
+    // The unrelated hierarchy kept its edges untouched.
+    assert_eq!(repo_edges(&indexer), before);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn impact_follows_virtual_dispatch() {
+    let (root, mut indexer) = index("impact", &[("Repo.cs", REPO_CS)]);
+
+    // Callers of the interface member are affected by a change to an implementation.
+    let upstream = affected(&mut indexer, "Acme.SqlRepository.Save", "upstream");
+    assert!(
+        upstream.contains(&"Acme.Controller.Run".to_string()),
+        "{upstream:?}"
+    );
+    // ...but sibling implementations are not.
+    assert!(
+        !upstream.contains(&"Acme.MemoryRepository.Save".to_string()),
+        "{upstream:?}"
+    );
+
+    // A call through the interface reaches every implementation.
+    let downstream = affected(&mut indexer, "Acme.Controller.Run", "downstream");
+    for implementation in [
+        "Acme.IRepository.Save",
+        "Acme.BaseRepo.Save",
+        "Acme.SqlRepository.Save",
+        "Acme.MemoryRepository.Save",
+    ] {
+        assert!(
+            downstream.contains(&implementation.to_string()),
+            "{implementation}: {downstream:?}"
+        );
+    }
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+const MAIN_GO: &str = r#"package main
+
+type Repository interface {
+	Save(x int) error
+	Load() int
+}
+
+type SqlRepo struct{}
+
+func (r *SqlRepo) Save(x int) error { return nil }
+func (r *SqlRepo) Load() int { return 0 }
+
+type Partial struct{}
+
+func (p Partial) Save(x int) error { return nil }
+"#;
+
+const LIB_RS: &str = r#"pub trait Repository {
+    fn save(&self, x: i32);
+}
+
+pub struct SqlRepo;
+
+impl Repository for SqlRepo {
+    fn save(&self, x: i32) {}
+}
+"#;
+
+const SVC_PY: &str = r#"class Base:
+    def __init__(self):
+        pass
+
+    def save(self):
+        pass
+
+
+class Child(Base):
+    def __init__(self):
+        pass
+
+    def save(self):
+        pass
+"#;
+
+#[test]
+fn go_rust_and_python_members_are_linked() {
+    let (root, indexer) = index(
+        "mixed",
+        &[("main.go", MAIN_GO), ("lib.rs", LIB_RS), ("svc.py", SVC_PY)],
+    );
+
+    assert_eq!(
+        member_links(&indexer, "IMPLEMENTS_MEMBER"),
+        vec![
+            pair("crate::SqlRepo::save", "crate::Repository::save"),
+            pair("main.SqlRepo.Load", "main.Repository.Load"),
+            pair("main.SqlRepo.Save", "main.Repository.Save"),
+        ]
+    );
+    assert_eq!(
+        member_links(&indexer, "OVERRIDES"),
+        vec![pair("svc.Child.save", "svc.Base.save")]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,145 @@
+use lidx::indexer::Indexer;
+use lidx::indexer::pipeline::PipelineConfig;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-parallel-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+/// A repo of Python modules that call into each other, plus a few other
+/// languages so several extractors run on every worker.
+fn write_repo(root: &Path) {
+    let mut files: Vec<(String, String)> = Vec::new();
+    for idx in 0..40 {
+        let next = (idx + 1) % 40;
+        files.push((
+            format!("pkg/mod_{idx:02}.py"),
+            format!(
+                "from pkg.mod_{next:02} import work_{next:02}\n\n\
+                 class Worker{idx:02}:\n    def run(self):\n        return work_{idx:02}()\n\n\
+                 def work_{idx:02}():\n    return work_{next:02}()\n"
+            ),
+        ));
+    }
+    files.push((
+        "web/client.ts".to_string(),
+        "export async function load() {\n  return fetch('/api/orders');\n}\n".to_string(),
+    ));
+    files.push((
+        "api/routes.py".to_string(),
+        "from flask import Flask\napp = Flask(__name__)\n\n@app.get('/api/orders')\ndef orders():\n    return []\n".to_string(),
+    ));
+    files.push((
+        "README.md".to_string(),
+        "# Demo\n\nSee `pkg/mod_00.py`.\n".to_string(),
+    ));
+    for (path, source) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+}
+
+type Snapshot = (Vec<(i64, String)>, Vec<String>, Vec<String>);
+
+/// Files, symbols and edges of a fresh index, including their row ids.
+fn snapshot(label: &str, config: PipelineConfig) -> Snapshot {
+    let root = temp_dir(label);
+    write_repo(&root);
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.set_pipeline_config(config);
+    let stats = indexer.reindex().unwrap();
+    assert_eq!(stats.indexed, 43);
+
+    let db = indexer.db();
+    let gv = db.current_graph_version().unwrap();
+    let files: Vec<(i64, String)> = db
+        .list_files(gv)
+        .unwrap()
+        .into_iter()
+        .map(|file| (file.id, file.path))
+        .collect();
+    let mut symbols = Vec::new();
+    for (_, path) in &files {
+        for symbol in db.get_symbols_for_file(path, gv).unwrap() {
+            symbols.push(format!(
+                "{} {} {} {}:{}",
+                symbol.id, symbol.kind, symbol.qualname, symbol.start_line, symbol.end_line
+            ));
+        }
+    }
+    let edges = db
+        .list_edges(
+            100_000, 0, None, None, None, None, None, None, false, None, gv, None, None, None,
+        )
+        .unwrap()
+        .into_iter()
+        .map(|edge| {
+            format!(
+                "{} {} {} {:?} {:?} {:?}",
+                edge.id,
+                edge.file_path,
+                edge.kind,
+                edge.source_symbol_id,
+                edge.target_symbol_id,
+                edge.target_qualname
+            )
+        })
+        .collect();
+    let _ = std::fs::remove_dir_all(&root);
+    (files, symbols, edges)
+}
+
+#[test]
+fn parallel_reindex_matches_single_threaded() {
+    let serial = snapshot(
+        "serial",
+        PipelineConfig {
+            threads: 1,
+            max_memory_bytes: usize::MAX,
+        },
+    );
+    assert!(serial.1.len() > 120, "{}", serial.1.len());
+    assert!(serial.2.len() > 120, "{}", serial.2.len());
+
+    let parallel = snapshot(
+        "parallel",
+        PipelineConfig {
+            threads: 8,
+            max_memory_bytes: usize::MAX,
+        },
+    );
+    assert_eq!(serial, parallel);
+}
+
+#[test]
+fn tiny_memory_budget_still_indexes_everything() {
+    let serial = snapshot(
+        "budget-serial",
+        PipelineConfig {
+            threads: 1,
+            max_memory_bytes: usize::MAX,
+        },
+    );
+    // Every file is larger than the budget, so they go through one at a time.
+    let constrained = snapshot(
+        "budget",
+        PipelineConfig {
+            threads: 4,
+            max_memory_bytes: 1,
+        },
+    );
+    assert_eq!(serial, constrained);
+}
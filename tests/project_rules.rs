@@ -0,0 +1,202 @@
+use lidx::indexer::Indexer;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-project-rules-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str, files: &[(&str, &str)]) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in files {
+        std::fs::write(root.join(path), source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+/// `(source qualname, target)` of every edge of `kind`.
+fn edges(indexer: &Indexer, kind: &str) -> Vec<(String, String)> {
+    let gv = indexer.db().current_graph_version().unwrap();
+    let edges = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&[kind.to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let mut pairs: Vec<(String, String)> = edges
+        .iter()
+        .map(|edge| {
+            let source = edge
+                .source_symbol_id
+                .and_then(|id| indexer.db().get_symbol_by_id(id).unwrap())
+                .map(|symbol| symbol.qualname)
+                .unwrap_or_default();
+            (source, edge.target_qualname.clone().unwrap_or_default())
+        })
+        .collect();
+    pairs.sort();
+    pairs.dedup();
+    pairs
+}
+
+fn pair(a: &str, b: &str) -> (String, String) {
+    (a.to_string(), b.to_string())
+}
+
+const RULES: &str = r#"
+[[channels]]
+languages = ["csharp"]
+receivers = ["_outbox"]
+publish_methods = ["Enqueue"]
+topic_containers = ["Events"]
+
+[topics]
+strip_prefixes = ["prod."]
+
+[http]
+base_paths = ["/api/v1"]
+
+[[http.clients]]
+languages = ["csharp"]
+receivers = ["_gateway"]
+
+[[http.calls]]
+function = "fetch_json"
+method = "GET"
+
+[[http.routes]]
+languages = ["javascript"]
+function = "register"
+method = "POST"
+
+[[rpc.calls]]
+receiver = "_rpc"
+function = "Invoke"
+"#;
+
+const ORDERS_CS: &str = r#"namespace Acme
+{
+    public class Orders
+    {
+        public void Place()
+        {
+            _outbox.Enqueue(Events.OrderPlaced);
+            _gateway.GetAsync("/api/v1/orders");
+            _rpc.Invoke("billing.Invoices/Create");
+        }
+    }
+}
+"#;
+
+const WORKER_PY: &str = r#"def start(bus):
+    bus.subscribe("prod.order_placed", on_order)
+
+
+def load(order_id):
+    return fetch_json("/api/v1/orders/42")
+"#;
+
+const ROUTES_JS: &str = r#"function setup(register) {
+  register("/orders", createOrder);
+}
+"#;
+
+#[test]
+fn rules_extend_bus_http_and_rpc_conventions() {
+    let (root, indexer) = index(
+        "conventions",
+        &[
+            (".lidx.toml", RULES),
+            ("Orders.cs", ORDERS_CS),
+            ("worker.py", WORKER_PY),
+            ("routes.js", ROUTES_JS),
+        ],
+    );
+
+    assert_eq!(
+        edges(&indexer, "CHANNEL_PUBLISH"),
+        vec![pair("Acme.Orders.Place", "channel://orderplaced")]
+    );
+    assert_eq!(
+        edges(&indexer, "CHANNEL_SUBSCRIBE"),
+        vec![pair("worker.start", "channel://orderplaced")]
+    );
+    assert_eq!(
+        edges(&indexer, "HTTP_CALL"),
+        vec![
+            pair("Acme.Orders.Place", "/orders"),
+            pair("worker.load", "/orders/{}"),
+        ]
+    );
+    assert_eq!(
+        edges(&indexer, "HTTP_ROUTE"),
+        vec![pair("routes.setup", "/orders")]
+    );
+    assert_eq!(
+        edges(&indexer, "RPC_CALL"),
+        vec![pair("Acme.Orders.Place", "/billing.invoices/create")]
+    );
+    // String-literal routes found by the xref pass drop the base path too.
+    assert!(
+        edges(&indexer, "ROUTE").contains(&pair("worker.load", "/orders/{}")),
+        "{:?}",
+        edges(&indexer, "ROUTE")
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn conventions_are_inert_without_rules() {
+    let (root, indexer) = index(
+        "defaults",
+        &[("Orders.cs", ORDERS_CS), ("worker.py", WORKER_PY)],
+    );
+
+    assert!(edges(&indexer, "CHANNEL_PUBLISH").is_empty());
+    assert!(edges(&indexer, "HTTP_CALL").is_empty());
+    assert!(edges(&indexer, "RPC_CALL").is_empty());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn invalid_rules_file_is_reported() {
+    let root = temp_dir("invalid");
+    std::fs::write(
+        root.join(".lidx.toml"),
+        "[[channels]]\nrecievers = [\"_outbox\"]\n",
+    )
+    .unwrap();
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let err = Indexer::new(root.clone(), db_path).err().unwrap();
+    assert!(format!("{err:#}").contains(".lidx.toml"), "{err:#}");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
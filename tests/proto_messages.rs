@@ -0,0 +1,236 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-proto-messages-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str, files: &[(&str, &str)]) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+/// `(source qualname, bound target qualname)` of every edge of `kind`.
+fn edges(indexer: &Indexer, kind: &str) -> Vec<(String, String)> {
+    let gv = indexer.db().current_graph_version().unwrap();
+    let edges = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&[kind.to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let qualname = |id: Option<i64>| {
+        id.and_then(|id| indexer.db().get_symbol_by_id(id).unwrap())
+            .map(|symbol| symbol.qualname)
+            .unwrap_or_default()
+    };
+    let mut rows: Vec<(String, String)> = edges
+        .iter()
+        .map(|edge| {
+            (
+                qualname(edge.source_symbol_id),
+                qualname(edge.target_symbol_id),
+            )
+        })
+        .collect();
+    rows.sort();
+    rows
+}
+
+fn pair(source: &str, target: &str) -> (String, String) {
+    (source.to_string(), target.to_string())
+}
+
+const COMMON_PROTO: &str = r#"syntax = "proto3";
+package acme.common;
+
+message Money {
+  string currency = 1;
+  int64 units = 2;
+}
+"#;
+
+const USERS_PROTO: &str = r#"syntax = "proto3";
+package acme.users;
+
+import "proto/common.proto";
+
+message GetUserRequest {
+  string user_id = 1;
+}
+
+message User {
+  string user_id = 1;
+  acme.common.Money balance = 2;
+}
+
+service UserService {
+  rpc GetUser (GetUserRequest) returns (User);
+}
+"#;
+
+const USERS_CS: &str = r#"namespace Acme.Users {
+  public sealed partial class GetUserRequest : pb::IMessage<GetUserRequest> {
+    public const int UserIdFieldNumber = 1;
+    public string UserId { get { return userId_; } set { userId_ = value; } }
+  }
+}
+"#;
+
+const USERS_PB_GO: &str = r#"package users
+
+type GetUserRequest struct {
+	UserId string
+}
+
+func (x *GetUserRequest) GetUserId() string {
+	if x != nil {
+		return x.UserId
+	}
+	return ""
+}
+"#;
+
+const HANDLER_GO: &str = r#"package handler
+
+func Lookup(req *users.GetUserRequest) string {
+	return req.GetUserId()
+}
+"#;
+
+#[test]
+fn proto_messages_fields_and_type_refs_are_indexed() {
+    let (_root, indexer) = index(
+        "type-refs",
+        &[
+            ("proto/common.proto", COMMON_PROTO),
+            ("proto/users.proto", USERS_PROTO),
+        ],
+    );
+    let type_refs = edges(&indexer, "TYPE_REF");
+    for expected in [
+        pair(
+            "acme.users.UserService.GetUser",
+            "acme.users.GetUserRequest",
+        ),
+        pair("acme.users.UserService.GetUser", "acme.users.User"),
+        pair("acme.users.User.balance", "acme.common.Money"),
+    ] {
+        assert!(type_refs.contains(&expected), "missing {expected:?}");
+    }
+    let imports = edges(&indexer, "IMPORTS");
+    assert!(imports.contains(&pair("proto/users", "proto/common")));
+
+    let gv = indexer.db().current_graph_version().unwrap();
+    let field = indexer
+        .db()
+        .get_symbol_by_qualname("acme.users.User.user_id", gv)
+        .unwrap()
+        .unwrap();
+    assert_eq!(field.kind, "field");
+    assert_eq!(field.signature.as_deref(), Some("string user_id = 1"));
+}
+
+#[test]
+fn generated_code_links_back_to_proto_fields() {
+    let (_root, mut indexer) = index(
+        "generated",
+        &[
+            ("proto/users.proto", USERS_PROTO),
+            ("proto/common.proto", COMMON_PROTO),
+            ("gen/cs/Users.cs", USERS_CS),
+            ("gen/go/users.pb.go", USERS_PB_GO),
+            ("handler.go", HANDLER_GO),
+        ],
+    );
+    let generated = edges(&indexer, "GENERATED_FROM");
+    for expected in [
+        pair("Acme.Users.GetUserRequest", "acme.users.GetUserRequest"),
+        pair(
+            "Acme.Users.GetUserRequest.UserId",
+            "acme.users.GetUserRequest.user_id",
+        ),
+        pair(
+            "Acme.Users.GetUserRequest.UserIdFieldNumber",
+            "acme.users.GetUserRequest.user_id",
+        ),
+        pair(
+            "gen/go/users.pb.GetUserRequest",
+            "acme.users.GetUserRequest",
+        ),
+        pair(
+            "gen/go/users.pb.GetUserRequest.GetUserId",
+            "acme.users.GetUserRequest.user_id",
+        ),
+    ] {
+        assert!(generated.contains(&expected), "missing {expected:?}");
+    }
+    // Same-named field of another message stays unlinked.
+    assert!(
+        !generated
+            .iter()
+            .any(|(_, target)| target == "acme.users.User.user_id")
+    );
+
+    let impact = rpc::handle_method(
+        &mut indexer,
+        "analyze_impact",
+        json!({
+            "qualname": "acme.users.GetUserRequest.user_id",
+            "direction": "upstream",
+            "max_depth": 3,
+            "enable_historical": false,
+        }),
+    )
+    .unwrap();
+    let affected: Vec<&str> = impact["affected"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry: &Value| entry["symbol"]["qualname"].as_str().unwrap())
+        .collect();
+    for expected in [
+        "Acme.Users.GetUserRequest.UserId",
+        "gen/go/users.pb.GetUserRequest.GetUserId",
+        "handler.Lookup",
+    ] {
+        assert!(
+            affected.contains(&expected),
+            "missing {expected} in {affected:?}"
+        );
+    }
+}
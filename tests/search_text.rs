@@ -1,3 +1,4 @@
+use lidx::indexer::Indexer;
 use lidx::search;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -74,3 +75,68 @@ fn search_text_respects_gitignore_by_default() {
 
     let _ = std::fs::remove_dir_all(&repo_root);
 }
+
+#[test]
+fn search_indexed_ranks_with_fts_and_tracks_sync() {
+    let repo_root = temp_repo_dir("fts");
+    std::fs::create_dir_all(repo_root.join("src")).unwrap();
+    std::fs::create_dir_all(repo_root.join("docs")).unwrap();
+    std::fs::write(
+        repo_root.join("src/setup.py"),
+        "def scaffoldDataSource(url):\n    \"\"\"Create the primary connection pool.\"\"\"\n    return url\n",
+    )
+    .unwrap();
+    std::fs::write(
+        repo_root.join("docs/guide.md"),
+        "# Guide\n\nCall scaffold_data_source first.\n",
+    )
+    .unwrap();
+    let db_path = repo_root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(repo_root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    let version = indexer.graph_version();
+
+    let options = search::SearchOptions::new(None);
+    let results = search::search_indexed(indexer.db(), version, "scaffold datasource", 10, options)
+        .unwrap()
+        .expect("content index populated by reindex");
+    assert_eq!(results[0].path, "src/setup.py");
+    assert_eq!(results[0].line, 1);
+    assert_eq!(results[0].engine.as_deref(), Some("fts"));
+    assert!(results.iter().any(|hit| hit.path == "docs/guide.md"));
+
+    // Docstrings are searchable through the symbol index.
+    let results = search::search_indexed(indexer.db(), version, "connection pool", 10, options)
+        .unwrap()
+        .unwrap();
+    assert!(results.iter().any(|hit| hit.path == "src/setup.py"));
+
+    let mut code_only = search::SearchOptions::new(None);
+    code_only.scope = Some(search::SearchScope::Code);
+    let results =
+        search::search_indexed(indexer.db(), version, "scaffold datasource", 10, code_only)
+            .unwrap()
+            .unwrap();
+    assert!(results.iter().all(|hit| hit.path == "src/setup.py"));
+
+    // Incremental sync replaces and removes indexed content.
+    std::fs::write(
+        repo_root.join("src/setup.py"),
+        "def teardown():\n    pass\n",
+    )
+    .unwrap();
+    std::fs::remove_file(repo_root.join("docs/guide.md")).unwrap();
+    indexer
+        .sync_rel_paths(&["src/setup.py".to_string(), "docs/guide.md".to_string()])
+        .unwrap();
+    let results = search::search_indexed(indexer.db(), version, "scaffold", 10, options)
+        .unwrap()
+        .unwrap();
+    assert!(results.is_empty(), "stale hits: {results:?}");
+    let results = search::search_indexed(indexer.db(), version, "teardown", 10, options)
+        .unwrap()
+        .unwrap();
+    assert_eq!(results.len(), 1);
+
+    let _ = std::fs::remove_dir_all(&repo_root);
+}
@@ -119,7 +119,7 @@ fn non_ascii_text_does_not_misalign_keyword_scanning() {
     let refs: Vec<_> = extracted_fk
         .edges
         .iter()
-        .filter(|e| e.kind == "REFERENCES")
+        .filter(|e| e.kind == "REFERENCES" && e.source_qualname.as_deref() == Some("orders"))
         .map(|e| e.target_qualname.as_deref())
         .collect();
     assert_eq!(
@@ -0,0 +1,194 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-sql-schema-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    std::fs::create_dir_all(root.join("migrations")).unwrap();
+    for (path, source) in MIGRATIONS {
+        std::fs::write(root.join(path), source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+// `10_` sorts before `2_` as plain text; migrations are replayed numerically.
+const MIGRATIONS: &[(&str, &str)] = &[
+    (
+        "migrations/1_init.sql",
+        r#"CREATE TABLE customers (id INT PRIMARY KEY, name TEXT);
+
+CREATE TABLE orders (
+    id INT PRIMARY KEY,
+    customer_id INT NOT NULL REFERENCES customers(id),
+    status VARCHAR(20),
+    note TEXT
+);
+"#,
+    ),
+    (
+        "migrations/2_totals.sql",
+        r#"ALTER TABLE orders ADD COLUMN total NUMERIC(10,2);
+ALTER TABLE orders RENAME COLUMN note TO notes;
+ALTER TABLE orders DROP COLUMN status;
+"#,
+    ),
+    (
+        "migrations/10_view.sql",
+        r#"ALTER TABLE orders ALTER COLUMN notes TYPE VARCHAR(200);
+
+CREATE VIEW order_totals AS
+SELECT o.id, o.total AS amount, c.name
+FROM orders o
+JOIN customers c ON c.id = o.customer_id;
+"#,
+    ),
+];
+
+/// `(source qualname, bound target qualname)` of every edge of `kind`.
+fn edges(indexer: &Indexer, kind: &str) -> Vec<(String, String)> {
+    let gv = indexer.db().current_graph_version().unwrap();
+    let edges = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&[kind.to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let qualname = |id: Option<i64>| {
+        id.and_then(|id| indexer.db().get_symbol_by_id(id).unwrap())
+            .map(|symbol| symbol.qualname)
+            .unwrap_or_default()
+    };
+    let mut pairs: Vec<(String, String)> = edges
+        .iter()
+        .map(|edge| {
+            (
+                qualname(edge.source_symbol_id),
+                qualname(edge.target_symbol_id),
+            )
+        })
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+fn pair(a: &str, b: &str) -> (String, String) {
+    (a.to_string(), b.to_string())
+}
+
+#[test]
+fn explain_table_folds_migrations_into_columns() {
+    let (root, mut indexer) = index("explain");
+
+    let explained = rpc::handle_method(
+        &mut indexer,
+        "explain_symbol",
+        json!({ "qualname": "orders", "sections": ["columns"] }),
+    )
+    .unwrap();
+    let columns = explained["columns"].as_array().unwrap();
+    let summary: Vec<(&str, &str, &str)> = columns
+        .iter()
+        .map(|column: &Value| {
+            (
+                column["name"].as_str().unwrap(),
+                column["data_type"].as_str().unwrap_or_default(),
+                column["introduced_in"]["path"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("id", "INT", "migrations/1_init.sql"),
+            ("customer_id", "INT", "migrations/1_init.sql"),
+            ("notes", "VARCHAR(200)", "migrations/1_init.sql"),
+            ("total", "NUMERIC(10,2)", "migrations/2_totals.sql"),
+        ],
+        "{explained}"
+    );
+    let notes_changes: Vec<&str> = columns[2]["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| change["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        notes_changes,
+        vec!["migrations/2_totals.sql", "migrations/10_view.sql"]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn columns_carry_lineage_and_foreign_keys() {
+    let (root, mut indexer) = index("lineage");
+
+    assert_eq!(
+        edges(&indexer, "DERIVES_FROM"),
+        vec![
+            pair("order_totals.amount", "orders.total"),
+            pair("order_totals.id", "orders.id"),
+            pair("order_totals.name", "customers.name"),
+        ]
+    );
+    assert!(
+        edges(&indexer, "REFERENCES").contains(&pair("orders.customer_id", "customers.id")),
+        "{:?}",
+        edges(&indexer, "REFERENCES")
+    );
+
+    let impact = rpc::handle_method(
+        &mut indexer,
+        "analyze_impact",
+        json!({
+            "qualname": "orders.total",
+            "direction": "upstream",
+            "max_depth": 2,
+            "enable_historical": false,
+        }),
+    )
+    .unwrap();
+    let affected: Vec<&str> = impact["affected"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["symbol"]["qualname"].as_str().unwrap())
+        .collect();
+    assert!(affected.contains(&"order_totals.amount"), "{impact}");
+    assert!(!affected.contains(&"order_totals.name"), "{impact}");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,413 @@
+use lidx::indexer::Indexer;
+use lidx::indexer::extract::LanguageExtractor;
+use lidx::indexer::terraform::{TerraformExtractor, module_name_from_rel_path};
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-terraform-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str, files: &[(&str, &str)]) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+/// `(source qualname, bound target qualname)` of every edge of `kind`.
+fn edges(indexer: &Indexer, kind: &str) -> Vec<(String, String)> {
+    let gv = indexer.db().current_graph_version().unwrap();
+    let edges = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&[kind.to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let qualname = |id: Option<i64>| {
+        id.and_then(|id| indexer.db().get_symbol_by_id(id).unwrap())
+            .map(|symbol| symbol.qualname)
+            .unwrap_or_default()
+    };
+    let mut pairs: Vec<(String, String)> = edges
+        .iter()
+        .map(|edge| {
+            (
+                qualname(edge.source_symbol_id),
+                qualname(edge.target_symbol_id),
+            )
+        })
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+fn pair(a: &str, b: &str) -> (String, String) {
+    (a.to_string(), b.to_string())
+}
+
+const MAIN_TF: &str = r#"# Orders service
+module "network" {
+  source = "../modules/network"
+  cidr   = var.cidr
+}
+
+data "aws_ami" "base" {
+  most_recent = true
+}
+
+locals {
+  prefix = "orders-${var.env}"
+}
+
+resource "aws_lambda_function" "api" {
+  function_name = "${local.prefix}-api"
+  image_uri     = data.aws_ami.base.id
+  subnet_ids    = module.network.private_subnets
+
+  environment {
+    variables = {
+      DATABASE_URL = aws_db_instance.main.endpoint
+      "LOG_LEVEL"  = "info"
+    }
+  }
+}
+
+resource "azurerm_linux_web_app" "web" {
+  name = "web"
+  app_settings = {
+    "Database__ConnectionString" = "x"
+  }
+}
+
+resource "azurerm_key_vault_secret" "db" {
+  name  = "orders-db"
+  value = aws_db_instance.main.password
+}
+"#;
+
+const VARIABLES_TF: &str = r#"variable "env" {
+  description = "Deployment environment."
+  type        = string
+}
+
+variable "cidr" {
+  type      = list(string)
+  sensitive = true
+}
+
+output "api_arn" {
+  description = "ARN of the API function."
+  value       = aws_lambda_function.api.arn
+}
+"#;
+
+const DB_TF: &str = r#"resource "aws_db_instance" "main" {
+  identifier = "${local.prefix}-db"
+}
+"#;
+
+const NETWORK_TF: &str = r#"variable "cidr" {}
+
+resource "aws_vpc" "main" {
+  cidr_block = var.cidr
+}
+
+output "private_subnets" {
+  value = [for subnet in aws_vpc.main.subnets : subnet.id]
+}
+"#;
+
+const K8S_TF: &str = r#"resource "kubernetes_deployment" "worker" {
+  spec {
+    template {
+      spec {
+        container {
+          env {
+            name  = "QUEUE_URL"
+            value = "sqs://jobs"
+          }
+          env {
+            name = "API_KEY"
+            value_from {
+              secret_key_ref {
+                name = "api"
+                key  = "key"
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+resource "aws_ecs_task_definition" "jobs" {
+  container_definitions = jsonencode([{
+    name        = "jobs"
+    environment = [{ name = "JOBS_TOPIC", value = "jobs" }]
+  }])
+}
+"#;
+
+const APP_PY: &str = r#"import os
+
+def connect():
+    return os.getenv("DATABASE_URL")
+"#;
+
+fn files() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("infra/orders/main.tf", MAIN_TF),
+        ("infra/orders/variables.tf", VARIABLES_TF),
+        ("infra/orders/db.tf", DB_TF),
+        ("infra/modules/network/main.tf", NETWORK_TF),
+        ("infra/modules/network/outputs.tf", "output \"id\" {}\n"),
+        ("app/db.py", APP_PY),
+    ]
+}
+
+#[test]
+fn module_name_from_path() {
+    assert_eq!(
+        module_name_from_rel_path("infra/orders/main.tf"),
+        "infra/orders/main"
+    );
+    assert_eq!(module_name_from_rel_path("main.tf"), "main");
+}
+
+#[test]
+fn extract_blocks_scoped_by_directory() {
+    let module = module_name_from_rel_path("infra/orders/main.tf");
+    let extracted = TerraformExtractor::new()
+        .unwrap()
+        .extract(MAIN_TF, &module)
+        .unwrap();
+    let symbol = |qualname: &str| {
+        extracted
+            .symbols
+            .iter()
+            .find(|symbol| symbol.qualname == qualname)
+            .unwrap_or_else(|| panic!("missing {qualname}"))
+    };
+
+    let lambda = symbol("infra/orders.aws_lambda_function.api");
+    assert_eq!(lambda.kind, "resource");
+    assert_eq!(lambda.name, "aws_lambda_function.api");
+    assert_eq!(lambda.signature.as_deref(), Some("aws_lambda_function"));
+    assert_eq!(lambda.start_line, 15);
+    assert_eq!(lambda.end_line, 26);
+
+    let network = symbol("infra/orders.module.network");
+    assert_eq!(network.kind, "module_ref");
+    assert_eq!(network.signature.as_deref(), Some("../modules/network"));
+    assert_eq!(symbol("infra/orders.data.aws_ami.base").kind, "data_source");
+    assert_eq!(symbol("infra/orders.local.prefix").kind, "local");
+
+    let references: Vec<&str> = extracted
+        .edges
+        .iter()
+        .filter(|edge| {
+            edge.kind == "REFERENCES"
+                && edge.source_qualname.as_deref() == Some("infra/orders.aws_lambda_function.api")
+        })
+        .filter_map(|edge| edge.target_qualname.as_deref())
+        .collect();
+    assert_eq!(
+        references,
+        vec![
+            "infra/orders.local.prefix",
+            "infra/orders.data.aws_ami.base",
+            "infra/orders.module.network",
+            "infra/orders.aws_db_instance.main",
+        ]
+    );
+
+    assert!(extracted.edges.iter().any(|edge| {
+        edge.kind == "IMPORTS_FILE"
+            && edge.source_qualname.as_deref() == Some("infra/orders.module.network")
+            && edge.target_qualname.as_deref() == Some("../modules/network")
+    }));
+}
+
+#[test]
+fn extract_variable_and_output_docs() {
+    let module = module_name_from_rel_path("infra/orders/variables.tf");
+    let extracted = TerraformExtractor::new()
+        .unwrap()
+        .extract(VARIABLES_TF, &module)
+        .unwrap();
+    let env = extracted
+        .symbols
+        .iter()
+        .find(|symbol| symbol.qualname == "infra/orders.var.env")
+        .unwrap();
+    assert_eq!(env.kind, "variable");
+    assert_eq!(env.signature.as_deref(), Some("string"));
+    assert_eq!(env.docstring.as_deref(), Some("Deployment environment."));
+
+    let cidr = extracted
+        .symbols
+        .iter()
+        .find(|symbol| symbol.qualname == "infra/orders.var.cidr")
+        .unwrap();
+    assert_eq!(cidr.signature.as_deref(), Some("list(string) (sensitive)"));
+
+    let output = extracted
+        .symbols
+        .iter()
+        .find(|symbol| symbol.kind == "output")
+        .unwrap();
+    assert_eq!(output.qualname, "infra/orders.output.api_arn");
+    assert_eq!(
+        output.docstring.as_deref(),
+        Some("ARN of the API function.")
+    );
+}
+
+#[test]
+fn extract_config_sources_from_compute_resources() {
+    let mut extractor = TerraformExtractor::new().unwrap();
+    let mut sources: Vec<(String, String)> = Vec::new();
+    for (path, source) in [("infra/orders/main.tf", MAIN_TF), ("infra/k8s.tf", K8S_TF)] {
+        let module = module_name_from_rel_path(path);
+        let extracted = extractor.extract(source, &module).unwrap();
+        sources.extend(
+            extracted
+                .edges
+                .iter()
+                .filter(|edge| edge.kind == "CONFIG_SOURCE")
+                .map(|edge| {
+                    (
+                        edge.source_qualname.clone().unwrap(),
+                        edge.target_qualname.clone().unwrap(),
+                    )
+                }),
+        );
+    }
+    assert_eq!(
+        sources,
+        vec![
+            pair("infra/orders.aws_lambda_function.api", "env://DATABASE_URL"),
+            pair("infra/orders.aws_lambda_function.api", "env://LOG_LEVEL"),
+            pair(
+                "infra/orders.azurerm_linux_web_app.web",
+                "env://DATABASE__CONNECTIONSTRING"
+            ),
+            pair("infra/orders.azurerm_linux_web_app.web", "env://DATABASE"),
+            pair(
+                "infra/orders.azurerm_key_vault_secret.db",
+                "secret://orders-db"
+            ),
+            pair("infra.kubernetes_deployment.worker", "env://QUEUE_URL"),
+            pair("infra.kubernetes_deployment.worker", "env://API_KEY"),
+            pair("infra.aws_ecs_task_definition.jobs", "env://JOBS_TOPIC"),
+        ]
+    );
+}
+
+#[test]
+fn references_and_module_sources_resolve_across_files() {
+    let (root, indexer) = index("resolve", &files());
+
+    let references = edges(&indexer, "REFERENCES");
+    for expected in [
+        pair(
+            "infra/orders.aws_lambda_function.api",
+            "infra/orders.aws_db_instance.main",
+        ),
+        pair(
+            "infra/orders.aws_db_instance.main",
+            "infra/orders.local.prefix",
+        ),
+        pair("infra/orders.local.prefix", "infra/orders.var.env"),
+        pair(
+            "infra/orders.output.api_arn",
+            "infra/orders.aws_lambda_function.api",
+        ),
+        pair(
+            "infra/modules/network.aws_vpc.main",
+            "infra/modules/network.var.cidr",
+        ),
+    ] {
+        assert!(references.contains(&expected), "missing {expected:?}");
+    }
+    assert_eq!(
+        edges(&indexer, "IMPORTS_FILE")
+            .into_iter()
+            .filter(|(source, _)| source == "infra/orders.module.network")
+            .collect::<Vec<_>>(),
+        vec![
+            pair("infra/orders.module.network", "infra/modules/network/main"),
+            pair(
+                "infra/orders.module.network",
+                "infra/modules/network/outputs"
+            ),
+        ]
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn trace_flow_reaches_terraform_env_source() {
+    let (root, mut indexer) = index("trace", &files());
+
+    let trace = rpc::handle_method(
+        &mut indexer,
+        "trace_flow",
+        json!({
+            "start_qualname": "app.db.connect",
+            "include_snippets": false,
+        }),
+    )
+    .unwrap();
+    let hops: Vec<(&str, &str)> = trace["trace"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hop: &Value| {
+            (
+                hop["symbol"]["qualname"].as_str().unwrap(),
+                hop["edge_kind"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert!(
+        hops.contains(&("infra/orders.aws_lambda_function.api", "CONFIG_SOURCE")),
+        "{hops:?}"
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
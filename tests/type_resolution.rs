@@ -0,0 +1,229 @@
+use lidx::indexer::Indexer;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-type-resolution-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+fn index(label: &str, files: &[(&str, &str)]) -> (PathBuf, Indexer) {
+    let root = temp_dir(label);
+    for (path, source) in files {
+        std::fs::write(root.join(path), source).unwrap();
+    }
+    let db_path = root.join(".lidx").join(".lidx.sqlite");
+    let mut indexer = Indexer::new(root.clone(), db_path).unwrap();
+    indexer.reindex().unwrap();
+    (root, indexer)
+}
+
+/// The qualname and confidence the CALLS edge with `snippet` resolved to.
+fn call_target(indexer: &Indexer, snippet: &str) -> (Option<String>, Option<f64>) {
+    let gv = indexer.db().current_graph_version().unwrap();
+    let edges = indexer
+        .db()
+        .list_edges(
+            1000,
+            0,
+            None,
+            None,
+            Some(&["CALLS".to_string()]),
+            None,
+            None,
+            None,
+            false,
+            None,
+            gv,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let edge = edges
+        .iter()
+        .find(|edge| edge.evidence_snippet.as_deref() == Some(snippet))
+        .unwrap_or_else(|| panic!("no CALLS edge for {snippet}: {edges:?}"));
+    let target = edge
+        .target_symbol_id
+        .map(|id| indexer.db().get_symbol_by_id(id).unwrap().unwrap().qualname);
+    (target, edge.confidence)
+}
+
+fn bound(qualname: &str, confidence: f64) -> (Option<String>, Option<f64>) {
+    (Some(qualname.to_string()), Some(confidence))
+}
+
+const DEPLOY_CS: &str = r#"namespace Acme.Deploy
+{
+    public interface IDeployService { Task DeployAsync(string env); }
+    public class DeployService : IDeployService { public Task DeployAsync(string env) => null; }
+    public class Other { public Task DeployAsync(string env) => null; }
+    public class Base { public void Audit() {} }
+    public class Audited : Base {}
+
+    public class Controller
+    {
+        private readonly IDeployService _svc;
+        public Controller(IDeployService svc) { _svc = svc; }
+        public void Run(Audited audited)
+        {
+            _svc.DeployAsync("prod");
+            var local = new DeployService();
+            local.DeployAsync("dev");
+            audited.Audit();
+        }
+    }
+}
+"#;
+
+#[test]
+fn csharp_calls_bind_through_injected_fields() {
+    let (root, indexer) = index("csharp", &[("Deploy.cs", DEPLOY_CS)]);
+
+    assert_eq!(
+        call_target(&indexer, "_svc.DeployAsync(\"prod\")"),
+        bound("Acme.Deploy.IDeployService.DeployAsync", 0.95)
+    );
+    assert_eq!(
+        call_target(&indexer, "local.DeployAsync(\"dev\")"),
+        bound("Acme.Deploy.DeployService.DeployAsync", 0.95)
+    );
+    // Inherited methods bind to the base type with lower confidence.
+    assert_eq!(
+        call_target(&indexer, "audited.Audit()"),
+        bound("Acme.Deploy.Base.Audit", 0.85)
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+const SERVICE_PY: &str = r#"class Repo:
+    def save(self, item):
+        return item
+
+
+class Cache:
+    def save(self, item):
+        return item
+
+
+class Service:
+    def __init__(self, repo: Repo):
+        self.repo = repo
+
+    def handle(self, cache: Cache):
+        self.repo.save(1)
+        cache.save(2)
+"#;
+
+#[test]
+fn python_calls_bind_through_type_hints() {
+    let (root, indexer) = index("python", &[("svc.py", SERVICE_PY)]);
+
+    assert_eq!(
+        call_target(&indexer, "self.repo.save(1)"),
+        bound("svc.Repo.save", 0.95)
+    );
+    assert_eq!(
+        call_target(&indexer, "cache.save(2)"),
+        bound("svc.Cache.save", 0.95)
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+const MAIN_GO: &str = r#"package main
+
+type Repo struct{}
+
+func (r *Repo) Save(x int) {}
+
+type Cache struct{}
+
+func (c *Cache) Save(x int) {}
+
+type Service struct {
+	repo *Repo
+}
+
+func (s *Service) Handle(c *Cache) {
+	s.repo.Save(1)
+	c.Save(2)
+}
+"#;
+
+#[test]
+fn go_calls_bind_through_struct_fields() {
+    let (root, indexer) = index("go", &[("main.go", MAIN_GO)]);
+
+    assert_eq!(
+        call_target(&indexer, "s.repo.Save(1)"),
+        bound("main.Repo.Save", 0.95)
+    );
+    assert_eq!(
+        call_target(&indexer, "c.Save(2)"),
+        bound("main.Cache.Save", 0.95)
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+const LIB_RS: &str = r#"pub struct Repo;
+impl Repo {
+    pub fn save(&self, x: i32) -> i32 { x }
+}
+
+pub struct Cache;
+impl Cache {
+    pub fn save(&self, x: i32) -> i32 { x }
+}
+
+pub struct Service {
+    repo: Repo,
+}
+
+impl Service {
+    pub fn handle(&self, cache: &Cache) {
+        self.repo.save(1);
+        cache.save(2);
+    }
+}
+"#;
+
+#[test]
+fn rust_method_calls_bind_through_impl_blocks() {
+    let (root, mut indexer) = index("rust", &[("lib.rs", LIB_RS)]);
+
+    assert_eq!(
+        call_target(&indexer, "self.repo.save(1)"),
+        bound("crate::Repo::save", 0.95)
+    );
+    assert_eq!(
+        call_target(&indexer, "cache.save(2)"),
+        bound("crate::Cache::save", 0.95)
+    );
+
+    // Incremental syncs re-bind the edges of changed files.
+    std::fs::write(
+        root.join("lib.rs"),
+        LIB_RS.replace("repo: Repo,", "repo: Cache,"),
+    )
+    .unwrap();
+    indexer.sync_rel_paths(&["lib.rs".to_string()]).unwrap();
+    assert_eq!(
+        call_target(&indexer, "self.repo.save(1)"),
+        bound("crate::Cache::save", 0.95)
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
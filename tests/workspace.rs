@@ -0,0 +1,184 @@
+use lidx::indexer::Indexer;
+use lidx::rpc;
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::SeqCst);
+    dir.push(format!("lidx-workspace-{label}-{nanos}-{counter}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::canonicalize(dir).unwrap()
+}
+
+const ORDERS_PY: &str = r#"from fastapi import FastAPI
+app = FastAPI()
+
+@app.get("/api/orders/{id}")
+def get_order(id):
+    return load_order(id)
+
+def load_order(id):
+    return {"id": id}
+"#;
+
+const WEB_PY: &str = r#"import requests
+
+def fetch_order(id):
+    return requests.get("/api/orders/7")
+
+def show_order(id):
+    return fetch_order(id)
+"#;
+
+/// Two repos under one workspace dir: `orders` serves a route that `web` calls.
+fn setup(label: &str) -> (PathBuf, Indexer, Indexer) {
+    let root = temp_dir(label);
+    let mut indexers = Vec::new();
+    for (name, file, source) in [
+        ("orders", "server.py", ORDERS_PY),
+        ("web", "client.py", WEB_PY),
+    ] {
+        let repo = root.join(name);
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::write(repo.join(file), source).unwrap();
+        let db_path = repo.join(".lidx").join(".lidx.sqlite");
+        let mut indexer = Indexer::new(repo, db_path).unwrap();
+        indexer.reindex().unwrap();
+        indexers.push(indexer);
+    }
+    std::fs::write(
+        root.join("lidx-workspace.yaml"),
+        "repos:\n  - root: orders\n  - name: web\n    root: web\n",
+    )
+    .unwrap();
+    let web = indexers.pop().unwrap();
+    let orders = indexers.pop().unwrap();
+    (root, orders, web)
+}
+
+fn manifest(root: &Path) -> String {
+    root.join("lidx-workspace.yaml").display().to_string()
+}
+
+fn cross_repo(value: &Value) -> Vec<(String, String)> {
+    value["cross_repo"]
+        .as_array()
+        .map(|hits| {
+            hits.iter()
+                .map(|hit| {
+                    (
+                        hit["repo"].as_str().unwrap().to_string(),
+                        hit["symbol"]["qualname"].as_str().unwrap().to_string(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn trace_flow_follows_http_call_into_other_repo() {
+    let (root, _orders, mut web) = setup("trace");
+
+    let result = rpc::handle_method(
+        &mut web,
+        "trace_flow",
+        json!({ "start_qualname": "client.show_order", "workspace": manifest(&root) }),
+    )
+    .unwrap();
+    let hits = cross_repo(&result);
+    assert!(
+        hits.contains(&("orders".to_string(), "server.get_order".to_string())),
+        "{hits:?}"
+    );
+    assert!(
+        hits.contains(&("orders".to_string(), "server.load_order".to_string())),
+        "{hits:?}"
+    );
+    let bridged = result["cross_repo"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|hit| hit["symbol"]["qualname"] == "server.get_order")
+        .unwrap();
+    assert_eq!(bridged["bridge"]["from_repo"], "web");
+    assert_eq!(bridged["bridge"]["from_qualname"], "client.fetch_order");
+    assert_eq!(bridged["bridge"]["boundary_type"], "http");
+
+    // Without a manifest the trace stays inside the repo.
+    let local = rpc::handle_method(
+        &mut web,
+        "trace_flow",
+        json!({ "start_qualname": "client.show_order" }),
+    )
+    .unwrap();
+    assert!(local.get("cross_repo").is_none());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn analyze_impact_reaches_callers_in_other_repo() {
+    let (root, mut orders, _web) = setup("impact");
+
+    let result = rpc::handle_method(
+        &mut orders,
+        "analyze_impact",
+        json!({
+            "qualname": "server.load_order",
+            "direction": "upstream",
+            "enable_historical": false,
+            "workspace": manifest(&root),
+        }),
+    )
+    .unwrap();
+    let hits = cross_repo(&result);
+    assert!(
+        hits.contains(&("web".to_string(), "client.fetch_order".to_string())),
+        "{hits:?}"
+    );
+    assert!(
+        hits.contains(&("web".to_string(), "client.show_order".to_string())),
+        "{hits:?}"
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn workspace_links_lists_matched_bridges() {
+    let (root, mut orders, _web) = setup("links");
+
+    let result = rpc::handle_method(
+        &mut orders,
+        "workspace_links",
+        json!({ "workspace": manifest(&root) }),
+    )
+    .unwrap();
+    assert_eq!(result["repos"].as_array().unwrap().len(), 2);
+    let links = result["links"].as_array().unwrap();
+    assert_eq!(links.len(), 1, "{result}");
+    assert_eq!(links[0]["boundary_type"], "http");
+    assert_eq!(links[0]["from"]["repo"], "web");
+    assert_eq!(links[0]["from"]["symbol"]["qualname"], "client.fetch_order");
+    assert_eq!(links[0]["to"]["repo"], "orders");
+    assert_eq!(links[0]["to"]["symbol"]["qualname"], "server.get_order");
+
+    let filtered = rpc::handle_method(
+        &mut orders,
+        "workspace_links",
+        json!({ "workspace": manifest(&root), "boundary_types": ["grpc"] }),
+    )
+    .unwrap();
+    assert_eq!(filtered["count"], 0);
+
+    let _ = std::fs::remove_dir_all(&root);
+}